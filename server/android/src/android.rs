@@ -133,6 +133,8 @@ pub mod android {
                     },
                     // sync settings need to be configured at runtime
                     sync: None,
+                    central_server: None,
+                    changelog: None,
                 };
                 let _ = start_server(settings, off_switch_receiver).await;
             });
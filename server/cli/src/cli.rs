@@ -4,7 +4,9 @@ use clap::StructOpt;
 use cli::RefreshDatesRepository;
 use graphql::schema_builder;
 use log::info;
-use repository::{get_storage_connection_manager, test_db, RemoteSyncBufferRepository};
+use repository::{
+    get_storage_connection_manager, test_db, RemoteSyncBufferRepository, SyncSiteRowRepository,
+};
 use reqwest::{Client, Url};
 use serde::{Deserialize, Serialize};
 use server::configuration;
@@ -22,6 +24,13 @@ use service::{
         central_data_synchroniser::{
             central_sync_batch_records_to_buffer_rows, CentralDataSynchroniser,
         },
+        central_server::{
+            append_central_records, export_site_offline_sync_file, import_site_offline_sync_file,
+            initialise_site, upsert_sync_site,
+        },
+        offline_sync::{
+            confirm_offline_sync_export, export_offline_sync_file, import_offline_sync_file,
+        },
         remote_data_synchroniser::{
             remote_sync_batch_records_to_buffer_rows, RemoteDataSynchroniser,
         },
@@ -39,6 +48,8 @@ use std::{
 use util::{hash, inline_init};
 
 const DATA_EXPORT_FOLDER: &'static str = "data";
+/// Hardware id of sites that have been initialised with an offline sync file
+const OFFLINE_SYNC_HARDWARE_ID: &'static str = "offline";
 
 /// omSupply remote server cli
 #[derive(clap::Parser)]
@@ -88,6 +99,57 @@ enum Action {
     },
     /// Make data current, base on latest date difference to now (takes the latest datetime out of all datetimes, compares to now and adjust all dates and datetimes by the difference), also disabling sync to avoid refreshed data syncing
     RefreshDates,
+    /// Export local changes that haven't been pushed yet to an offline sync file, for sites without connectivity (uses sync settings from the database or configuration/.*yaml)
+    ExportOfflineSync {
+        /// Path of the offline sync file to create
+        #[clap(short, long)]
+        file: PathBuf,
+    },
+    /// Import an offline sync file that has been created for this site by the central server (uses sync settings from the database or configuration/.*yaml)
+    ImportOfflineSync {
+        /// Path of the offline sync file to import
+        #[clap(short, long)]
+        file: PathBuf,
+    },
+    /// Add a remote site to this server or update its credentials, the site can sync with this server if it runs in central server mode (central_server in configuration/.*yaml)
+    AddSyncSite {
+        /// Site id, stores with this site id are synced to the site
+        #[clap(short, long)]
+        site_id: u32,
+        /// Sync username of the site
+        #[clap(short, long)]
+        username: String,
+        /// Plain sync password of the site
+        #[clap(short, long)]
+        password: String,
+    },
+    /// Export the queued and central records of a remote site to an offline sync file, when running in central server mode (central_server in configuration/.*yaml)
+    ExportSiteOfflineSync {
+        /// Site id of the remote site
+        #[clap(short, long)]
+        site_id: u32,
+        /// Path of the offline sync file to create
+        #[clap(short, long)]
+        file: PathBuf,
+        /// Initialise the site, i.e. queue all records of the site again (required for the first file of a site)
+        #[clap(short, long, parse(from_flag))]
+        initialise: bool,
+    },
+    /// Import an offline sync file that has been created by a remote site, when running in central server mode (central_server in configuration/.*yaml)
+    ImportSiteOfflineSync {
+        /// Site id of the remote site
+        #[clap(short, long)]
+        site_id: u32,
+        /// Path of the offline sync file to import
+        #[clap(short, long)]
+        file: PathBuf,
+    },
+    /// Initialise a central server database from exported data (see export-initialisation), drops existing database, creates new database with latest schema and serves the exported central records to remote sites
+    InitialiseCentralServer {
+        /// Name for import of initialisation data (from `data` folder)
+        #[clap(short, long)]
+        name: String,
+    },
 }
 
 #[derive(Serialize, Deserialize)]
@@ -172,6 +234,7 @@ async fn main() {
                 username,
                 password_sha256,
                 url,
+                site_id,
                 ..
             } = settings.sync.unwrap();
 
@@ -212,6 +275,7 @@ async fn main() {
                 credentials.clone(),
                 client.clone(),
                 &hardware_id,
+                site_id,
             );
 
             info!("Requesting initialisation");
@@ -269,16 +333,14 @@ async fn main() {
                 .await
                 .unwrap()
             }
-            CentralDataSynchroniser::integrate_central_records(&ctx.connection)
-                .await
-                .unwrap();
+            CentralDataSynchroniser::integrate_central_records(&ctx.connection).unwrap();
 
             info!("Initialising remote");
             if let Some(data) = data.remote.data {
                 RemoteSyncBufferRepository::new(&ctx.connection)
                     .upsert_many(&remote_sync_batch_records_to_buffer_rows(data).unwrap())
                     .unwrap();
-                RemoteDataSynchroniser::do_integrate_records(&ctx.connection).unwrap()
+                RemoteDataSynchroniser::do_integrate_records(&ctx.connection, None).unwrap();
             }
 
             info!("Initialising users");
@@ -335,6 +397,164 @@ async fn main() {
 
             info!("Refresh data result: {:#?}", result);
         }
+        Action::ExportOfflineSync { file } => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let app_data_folder = settings.server.base_dir.unwrap();
+            let service_provider = ServiceProvider::new(connection_manager, &app_data_folder);
+            let ctx = service_provider.context().unwrap();
+            let sync_settings = service_provider
+                .settings
+                .sync_settings(&ctx)
+                .unwrap()
+                .or(settings.sync)
+                .expect("Sync settings are missing");
+
+            info!("Exporting offline sync file");
+            let export = export_offline_sync_file(&ctx.connection, &sync_settings).unwrap();
+            fs::write(&file, &export.file).unwrap();
+            // Only marked as pushed once the file is written
+            confirm_offline_sync_export(&ctx.connection, &export).unwrap();
+            info!(
+                "Exported {} records and {} acknowledgements to {}",
+                export.pushed_records,
+                export.acknowledged_records,
+                file.to_str().unwrap()
+            );
+        }
+        Action::ImportOfflineSync { file } => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let app_data_folder = settings.server.base_dir.unwrap();
+            let service_provider = ServiceProvider::new(connection_manager, &app_data_folder);
+            let ctx = service_provider.context().unwrap();
+            let sync_settings = service_provider
+                .settings
+                .sync_settings(&ctx)
+                .unwrap()
+                .or(settings.sync)
+                .expect("Sync settings are missing");
+
+            info!("Importing offline sync file {}", file.to_str().unwrap());
+            let result = import_offline_sync_file(
+                &service_provider,
+                &sync_settings,
+                &fs::read(&file).unwrap(),
+            )
+            .unwrap();
+            info!(
+                "Imported {} central records and {} remote records",
+                result.central_records, result.remote_records
+            );
+        }
+        Action::AddSyncSite {
+            site_id,
+            username,
+            password,
+        } => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let connection = connection_manager.connection().unwrap();
+
+            upsert_sync_site(&connection, site_id, &username, &password).unwrap();
+            info!("Sync site {} ({}) saved", site_id, username);
+        }
+        Action::ExportSiteOfflineSync {
+            site_id,
+            file,
+            initialise,
+        } => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let connection = connection_manager.connection().unwrap();
+            let repo = SyncSiteRowRepository::new(&connection);
+            let mut site = repo
+                .find_one_by_id(site_id as i32)
+                .unwrap()
+                .expect("Unknown sync site");
+
+            if initialise {
+                let hardware_id = site
+                    .hardware_id
+                    .clone()
+                    .unwrap_or_else(|| OFFLINE_SYNC_HARDWARE_ID.to_string());
+                initialise_site(&connection, site, &hardware_id).unwrap();
+                site = repo.find_one_by_id(site_id as i32).unwrap().unwrap();
+            }
+            info!("Exporting offline sync file for site {}", site_id);
+            let bytes = export_site_offline_sync_file(&connection, &site).unwrap();
+            fs::write(&file, bytes).unwrap();
+            info!("Exported offline sync file {}", file.to_str().unwrap());
+        }
+        Action::ImportSiteOfflineSync { site_id, file } => {
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let connection = connection_manager.connection().unwrap();
+            let central_server_settings = settings
+                .central_server
+                .expect("Central server settings are missing");
+            let site = SyncSiteRowRepository::new(&connection)
+                .find_one_by_id(site_id as i32)
+                .unwrap()
+                .expect("Unknown sync site");
+
+            info!(
+                "Importing offline sync file {} of site {}",
+                file.to_str().unwrap(),
+                site_id
+            );
+            let result = import_site_offline_sync_file(
+                &connection,
+                &central_server_settings,
+                &site,
+                &fs::read(&file).unwrap(),
+            )
+            .unwrap();
+            info!(
+                "Imported {} pushed records and {} acknowledgements",
+                result.pushed_records, result.acknowledged_records
+            );
+        }
+        Action::InitialiseCentralServer { name } => {
+            test_db::setup(&settings.database).await;
+
+            let connection_manager = get_storage_connection_manager(&settings.database);
+            let app_data_folder = settings.server.base_dir.unwrap();
+            let service_provider = Data::new(ServiceProvider::new(
+                connection_manager.clone(),
+                &app_data_folder,
+            ));
+            let ctx = service_provider.context().unwrap();
+
+            let (_, import_file, users_file) = export_paths(&name);
+
+            info!(
+                "Initialising central server from {}",
+                import_file.to_str().unwrap()
+            );
+
+            let data: InitialisationData =
+                serde_json::from_slice(&fs::read(import_file).unwrap()).unwrap();
+
+            info!("Initialising central records");
+            let central_records =
+                central_sync_batch_records_to_buffer_rows(data.central.data).unwrap();
+            let count = append_central_records(&ctx.connection, central_records).unwrap();
+            info!("Appended {} central records", count);
+
+            info!("Initialising remote records");
+            if let Some(data) = data.remote.data {
+                RemoteSyncBufferRepository::new(&ctx.connection)
+                    .upsert_many(&remote_sync_batch_records_to_buffer_rows(data).unwrap())
+                    .unwrap();
+                RemoteDataSynchroniser::do_integrate_records(&ctx.connection, None).unwrap();
+            }
+
+            info!("Initialising users");
+            for (input, user_info) in data.users {
+                LoginService::update_user(&ctx, &input.password, user_info).unwrap();
+            }
+
+            info!(
+                "Initialisation done, add remote sites with add-sync-site, available users: {}",
+                fs::read_to_string(users_file).unwrap()
+            );
+        }
     }
 }
 
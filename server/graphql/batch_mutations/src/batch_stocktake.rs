@@ -43,8 +43,10 @@ pub struct MutationWithId<T: OutputType> {
 }
 
 type InsertStocktakesResponse = Option<Vec<MutationWithId<stocktake::InsertResponse>>>;
-type InsertStocktakeLinesResponse = Option<Vec<MutationWithId<stocktake_line::InsertResponse>>>;
-type UpdateStocktakeLinesResponse = Option<Vec<MutationWithId<stocktake_line::UpdateResponse>>>;
+pub(crate) type InsertStocktakeLinesResponse =
+    Option<Vec<MutationWithId<stocktake_line::InsertResponse>>>;
+pub(crate) type UpdateStocktakeLinesResponse =
+    Option<Vec<MutationWithId<stocktake_line::UpdateResponse>>>;
 type DeleteStocktakeLinesResponse = Option<Vec<MutationWithId<stocktake_line::DeleteResponse>>>;
 type UpdateStocktakesResponse = Option<Vec<MutationWithId<stocktake::UpdateResponse>>>;
 type DeleteStocktakesResponse = Option<Vec<MutationWithId<stocktake::DeleteResponse>>>;
@@ -199,7 +201,9 @@ fn map_delete_stocktakes(responses: DeleteStocktakesResult) -> Result<DeleteStoc
     Ok(result.vec_or_none())
 }
 
-fn map_insert_lines(responses: InsertStocktakeLinesResult) -> Result<InsertStocktakeLinesResponse> {
+pub(crate) fn map_insert_lines(
+    responses: InsertStocktakeLinesResult,
+) -> Result<InsertStocktakeLinesResponse> {
     let mut result = Vec::new();
     for response in responses {
         let mapped_response = match stocktake_line::insert::map_response(response.result) {
@@ -216,7 +220,9 @@ fn map_insert_lines(responses: InsertStocktakeLinesResult) -> Result<InsertStock
     Ok(result.vec_or_none())
 }
 
-fn map_update_lines(responses: UpdateStocktakeLinesResult) -> Result<UpdateStocktakeLinesResponse> {
+pub(crate) fn map_update_lines(
+    responses: UpdateStocktakeLinesResult,
+) -> Result<UpdateStocktakeLinesResponse> {
     let mut result = Vec::new();
     for response in responses {
         let mapped_response = match stocktake_line::update::map_response(response.result) {
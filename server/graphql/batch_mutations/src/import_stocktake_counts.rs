@@ -0,0 +1,146 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::{
+        ImportStocktakeCounts, ImportStocktakeCountsError as ServiceError,
+        ImportStocktakeCountsResult, ImportStocktakeCountsRowError,
+        ImportStocktakeCountsSkippedRow,
+    },
+};
+
+use crate::batch_stocktake::{
+    map_insert_lines, map_update_lines, InsertStocktakeLinesResponse, UpdateStocktakeLinesResponse,
+};
+
+#[derive(InputObject)]
+#[graphql(name = "ImportStocktakeCountsInput")]
+pub struct ImportInput {
+    pub stocktake_id: String,
+    /// Comma or tab separated file with a header row and the columns:
+    /// item code, batch, expiry date, location code, counted number of packs
+    pub file: String,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ImportStocktakeCountsRowErrorType {
+    InvalidRow,
+    ItemDoesNotExist,
+    LocationDoesNotExist,
+    AmbiguousItemCode,
+    AmbiguousLocationCode,
+    AmbiguousRow,
+    DuplicateRow,
+}
+
+#[derive(SimpleObject)]
+pub struct ImportStocktakeCountsSkippedRowNode {
+    /// Row number in the file, the header is row 1
+    pub row_number: u32,
+    pub error: ImportStocktakeCountsRowErrorType,
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "ImportStocktakeCountsResponse")]
+pub struct ImportResponse {
+    /// Rows that didn't match or create a stocktake line
+    skipped_rows: Vec<ImportStocktakeCountsSkippedRowNode>,
+    /// New stocktake lines for rows that didn't match an existing line
+    insert_stocktake_lines: InsertStocktakeLinesResponse,
+    /// Existing stocktake lines that were counted
+    update_stocktake_lines: UpdateStocktakeLinesResponse,
+}
+
+pub fn import(ctx: &Context<'_>, store_id: &str, input: ImportInput) -> Result<ImportResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider.stocktake_service.import_stocktake_counts(
+        &service_context,
+        store_id,
+        &user.user_id,
+        input.to_domain(),
+    ) {
+        Ok(result) => ImportResponse::from_domain(result),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+impl ImportInput {
+    fn to_domain(self) -> ImportStocktakeCounts {
+        let ImportInput { stocktake_id, file } = self;
+        ImportStocktakeCounts { stocktake_id, file }
+    }
+}
+
+impl ImportResponse {
+    fn from_domain(
+        ImportStocktakeCountsResult {
+            skipped_rows,
+            batch,
+        }: ImportStocktakeCountsResult,
+    ) -> Result<ImportResponse> {
+        Ok(ImportResponse {
+            skipped_rows: skipped_rows
+                .into_iter()
+                .map(ImportStocktakeCountsSkippedRowNode::from_domain)
+                .collect(),
+            insert_stocktake_lines: map_insert_lines(batch.insert_line)?,
+            update_stocktake_lines: map_update_lines(batch.update_line)?,
+        })
+    }
+}
+
+impl ImportStocktakeCountsSkippedRowNode {
+    fn from_domain(
+        ImportStocktakeCountsSkippedRow { row_number, error }: ImportStocktakeCountsSkippedRow,
+    ) -> ImportStocktakeCountsSkippedRowNode {
+        ImportStocktakeCountsSkippedRowNode {
+            row_number,
+            error: ImportStocktakeCountsRowErrorType::from_domain(&error),
+        }
+    }
+}
+
+impl ImportStocktakeCountsRowErrorType {
+    fn from_domain(error: &ImportStocktakeCountsRowError) -> Self {
+        use ImportStocktakeCountsRowError as from;
+        use ImportStocktakeCountsRowErrorType as to;
+        match error {
+            from::InvalidRow => to::InvalidRow,
+            from::ItemDoesNotExist => to::ItemDoesNotExist,
+            from::LocationDoesNotExist => to::LocationDoesNotExist,
+            from::AmbiguousItemCode => to::AmbiguousItemCode,
+            from::AmbiguousLocationCode => to::AmbiguousLocationCode,
+            from::AmbiguousRow => to::AmbiguousRow,
+            from::DuplicateRow => to::DuplicateRow,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::InvalidStore => BadUserInput(formatted_error),
+        ServiceError::StocktakeDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::CannotEditFinalised => BadUserInput(formatted_error),
+        ServiceError::StocktakeIsLocked => BadUserInput(formatted_error),
+        ServiceError::InvalidFile(_) => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -2,6 +2,7 @@ mod batch_inbound_shipment;
 mod batch_outbound_shipment;
 mod batch_request_requisition;
 mod batch_stocktake;
+mod import_stocktake_counts;
 use async_graphql::*;
 
 #[derive(Default, Clone)]
@@ -44,6 +45,17 @@ impl BatchMutations {
     ) -> Result<batch_stocktake::BatchResponse> {
         batch_stocktake::batch(ctx, &store_id, input)
     }
+
+    /// Import stocktake counts from a CSV file, e.g. exported from a handheld or typed up from
+    /// paper. Rows that can't be matched to a single stocktake line are skipped and reported.
+    async fn import_stocktake_counts(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: import_stocktake_counts::ImportInput,
+    ) -> Result<import_stocktake_counts::ImportResponse> {
+        import_stocktake_counts::import(ctx, &store_id, input)
+    }
 }
 
 pub trait VecOrNone<T> {
@@ -18,6 +18,7 @@ use service::service_provider::ServiceProvider;
 
 use loader::LoaderRegistry;
 use service::settings::Settings;
+use service::sync::SyncSenderActor;
 use tokio::sync::mpsc::Sender;
 
 /// Performs a query to ourself, e.g. the report endpoint can query
@@ -36,6 +37,7 @@ pub trait ContextExt {
     fn self_request(&self) -> Option<&Box<dyn SelfRequest>>;
     fn get_settings(&self) -> &Settings;
     fn restart_switch(&self) -> Sender<bool>;
+    fn sync_sender(&self) -> SyncSenderActor;
 }
 
 impl<'a> ContextExt for Context<'a> {
@@ -72,6 +74,12 @@ impl<'a> ContextExt for Context<'a> {
     fn restart_switch(&self) -> Sender<bool> {
         self.data_unchecked::<Data<Sender<bool>>>().as_ref().clone()
     }
+
+    fn sync_sender(&self) -> SyncSenderActor {
+        self.data_unchecked::<Data<SyncSenderActor>>()
+            .as_ref()
+            .clone()
+    }
 }
 
 #[derive(Clone)]
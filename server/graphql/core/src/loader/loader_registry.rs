@@ -98,6 +98,13 @@ pub async fn get_loaders(
         async_std::task::spawn,
     );
 
+    let stock_line_held_packs_loader = DataLoader::new(
+        StockLineHeldPacksLoader {
+            service_provider: service_provider.clone(),
+        },
+        async_std::task::spawn,
+    );
+
     let user_account_loader = DataLoader::new(
         UserLoader {
             connection_manager: connection_manager.clone(),
@@ -133,6 +140,20 @@ pub async fn get_loaders(
         async_std::task::spawn,
     );
 
+    let stocktake_by_id_loader = DataLoader::new(
+        StocktakeByIdLoader {
+            connection_manager: connection_manager.clone(),
+        },
+        async_std::task::spawn,
+    );
+
+    let stocktake_line_count_loader = DataLoader::new(
+        StocktakeLineCountByStocktakeLineIdLoader {
+            connection_manager: connection_manager.clone(),
+        },
+        async_std::task::spawn,
+    );
+
     let requisitions_by_id_loader = DataLoader::new(
         RequisitionsByIdLoader {
             service_provider: service_provider.clone(),
@@ -182,6 +203,13 @@ pub async fn get_loaders(
         async_std::task::spawn,
     );
 
+    let vvm_status_row_loader = DataLoader::new(
+        VvmStatusRowLoader {
+            service_provider: service_provider.clone(),
+        },
+        async_std::task::spawn,
+    );
+
     loaders.insert(item_loader);
     loaders.insert(name_by_id_loader);
     loaders.insert(store_by_id_loader);
@@ -193,6 +221,7 @@ pub async fn get_loaders(
     loaders.insert(stock_line_by_item_id_and_store_id_loader);
     loaders.insert(stock_line_by_location_id_loader);
     loaders.insert(stock_line_by_id_loader);
+    loaders.insert(stock_line_held_packs_loader);
     loaders.insert(user_account_loader);
     loaders.insert(location_by_id_loader);
     loaders.insert(master_list_line_by_master_list_id);
@@ -201,9 +230,12 @@ pub async fn get_loaders(
     loaders.insert(requisition_line_by_linked_requisition_line_id_loader);
     loaders.insert(item_stats_for_item_loader);
     loaders.insert(stocktake_line_loader);
+    loaders.insert(stocktake_by_id_loader);
+    loaders.insert(stocktake_line_count_loader);
     loaders.insert(requisition_line_supply_status_loader);
     loaders.insert(requisition_lines_remaining_to_supply_loader);
     loaders.insert(name_row_loader);
+    loaders.insert(vvm_status_row_loader);
 
     loaders
 }
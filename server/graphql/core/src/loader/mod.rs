@@ -11,9 +11,12 @@ mod requisition;
 mod requisition_line;
 mod requisition_supply_status;
 mod stock_line;
+mod stocktake;
+mod stocktake_line_count;
 mod stocktake_lines;
 mod store;
 mod user;
+mod vvm_status_row;
 
 use std::{collections::HashSet, hash::Hasher};
 
@@ -30,9 +33,12 @@ pub use requisition::*;
 pub use requisition_line::*;
 pub use requisition_supply_status::*;
 pub use stock_line::*;
+pub use stocktake::*;
+pub use stocktake_line_count::*;
 pub use stocktake_lines::*;
 pub use store::*;
 pub use user::*;
+pub use vvm_status_row::*;
 
 #[derive(Debug, Clone)]
 /// Sometimes loaders need to take an extra parameter, like store_id or requisition_id
@@ -1,3 +1,4 @@
+use actix_web::web::Data;
 use repository::EqualFilter;
 use repository::{
     RepositoryError, StockLine, StockLineFilter, StockLineRepository, StorageConnectionManager,
@@ -5,7 +6,9 @@ use repository::{
 
 use async_graphql::dataloader::*;
 use async_graphql::*;
+use service::service_provider::ServiceProvider;
 use std::collections::HashMap;
+use util::date_now;
 
 use super::IdPair;
 
@@ -121,3 +124,27 @@ impl Loader<String> for StockLineByIdLoader {
             .collect())
     }
 }
+
+/// Packs of the stock line that are held by reservations today, loads 0 if nothing is held
+pub struct StockLineHeldPacksLoader {
+    pub service_provider: Data<ServiceProvider>,
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for StockLineHeldPacksLoader {
+    type Value = i32;
+    type Error = RepositoryError;
+
+    async fn load(&self, ids: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let service_context = self.service_provider.context()?;
+        let held_packs = self
+            .service_provider
+            .stock_line_reservation_service
+            .get_held_packs_by_stock_line(&service_context, ids, date_now())?;
+
+        Ok(ids
+            .iter()
+            .map(|id| (id.clone(), held_packs.get(id).copied().unwrap_or_default()))
+            .collect())
+    }
+}
@@ -0,0 +1,33 @@
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use repository::{
+    RepositoryError, StocktakeLineCountRow, StocktakeLineCountRowRepository,
+    StorageConnectionManager,
+};
+use std::collections::HashMap;
+
+pub struct StocktakeLineCountByStocktakeLineIdLoader {
+    pub connection_manager: StorageConnectionManager,
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for StocktakeLineCountByStocktakeLineIdLoader {
+    type Value = Vec<StocktakeLineCountRow>;
+    type Error = RepositoryError;
+
+    async fn load(
+        &self,
+        stocktake_line_ids: &[String],
+    ) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let connection = self.connection_manager.connection()?;
+        let repo = StocktakeLineCountRowRepository::new(&connection);
+
+        let mut map: HashMap<String, Vec<StocktakeLineCountRow>> = HashMap::new();
+        for count in repo.find_many_by_stocktake_line_ids(stocktake_line_ids)? {
+            map.entry(count.stocktake_line_id.clone())
+                .or_insert_with(Vec::new)
+                .push(count);
+        }
+        Ok(map)
+    }
+}
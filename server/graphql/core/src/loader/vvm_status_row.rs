@@ -0,0 +1,28 @@
+use actix_web::web::Data;
+use repository::{RepositoryError, VvmStatusRow, VvmStatusRowRepository};
+
+use async_graphql::dataloader::*;
+use async_graphql::*;
+use service::service_provider::ServiceProvider;
+use std::collections::HashMap;
+
+pub struct VvmStatusRowLoader {
+    pub service_provider: Data<ServiceProvider>,
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for VvmStatusRowLoader {
+    type Value = VvmStatusRow;
+    type Error = RepositoryError;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let service_context = self.service_provider.context()?;
+        let results =
+            VvmStatusRowRepository::new(&service_context.connection).find_many_by_id(keys)?;
+
+        Ok(results
+            .into_iter()
+            .map(|vvm_status_row| (vvm_status_row.id.clone(), vvm_status_row))
+            .collect())
+    }
+}
@@ -0,0 +1,147 @@
+mod mutations;
+use self::mutations::*;
+
+use async_graphql::*;
+use chrono::NaiveDate;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::*;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    SingleRecordError,
+};
+
+#[derive(Default, Clone)]
+pub struct CycleCountQueries;
+
+#[Object]
+impl CycleCountQueries {
+    /// ABC/VEN classification of the store's items, ordered by item id
+    pub async fn item_classifications(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<ItemClassificationNode>> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryCycleCount,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let item_classifications = service_provider
+            .item_classification_service
+            .get_item_classifications(&service_context, &store_id)
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(item_classifications
+            .into_iter()
+            .map(ItemClassificationNode::from_domain)
+            .collect())
+    }
+
+    pub async fn cycle_count_plans(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<CycleCountPlanNode>> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryCycleCount,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let cycle_count_plans = service_provider
+            .cycle_count_service
+            .get_cycle_count_plans(&service_context, &store_id)
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(cycle_count_plans
+            .into_iter()
+            .map(CycleCountPlanNode::from_domain)
+            .collect())
+    }
+
+    /// Items due for counting in the plan's cycle week of the given date
+    pub async fn cycle_count_items(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        plan_id: String,
+        date: NaiveDate,
+    ) -> Result<Vec<ItemClassificationNode>> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryCycleCount,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let items = service_provider
+            .cycle_count_service
+            .get_cycle_count_items(&service_context, &store_id, &plan_id, date)
+            .map_err(|error| match error {
+                SingleRecordError::DatabaseError(error) => {
+                    StandardGraphqlError::from_repository_error(error)
+                }
+                SingleRecordError::NotFound(_) => {
+                    StandardGraphqlError::BadUserInput(format!("{:#?}", error)).extend()
+                }
+            })?;
+
+        Ok(items
+            .into_iter()
+            .map(ItemClassificationNode::from_domain)
+            .collect())
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct CycleCountMutations;
+
+#[Object]
+impl CycleCountMutations {
+    /// Recalculate ABC classes of the store's items from their consumption value, manually set
+    /// classes are kept
+    async fn calculate_item_classifications(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        lookback_months: Option<u32>,
+    ) -> Result<Vec<ItemClassificationNode>> {
+        calculate_item_classifications(ctx, &store_id, lookback_months)
+    }
+
+    async fn update_item_classification(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpdateItemClassificationInput,
+    ) -> Result<ItemClassificationNode> {
+        update_item_classification(ctx, &store_id, input)
+    }
+
+    async fn upsert_cycle_count_plan(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpsertCycleCountPlanInput,
+    ) -> Result<CycleCountPlanNode> {
+        upsert_cycle_count_plan(ctx, &store_id, input)
+    }
+}
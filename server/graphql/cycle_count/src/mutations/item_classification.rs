@@ -0,0 +1,101 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{AbcClassNode, ItemClassificationNode, VenClassNode};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    item_classification::update::{
+        UpdateItemClassification, UpdateItemClassificationError as ServiceError,
+    },
+};
+
+pub fn calculate_item_classifications(
+    ctx: &Context<'_>,
+    store_id: &str,
+    lookback_months: Option<u32>,
+) -> Result<Vec<ItemClassificationNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateCycleCount,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let item_classifications = service_provider
+        .item_classification_service
+        .calculate_item_classifications(&service_context, store_id, lookback_months)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(item_classifications
+        .into_iter()
+        .map(ItemClassificationNode::from_domain)
+        .collect())
+}
+
+pub fn update_item_classification(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateItemClassificationInput,
+) -> Result<ItemClassificationNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateCycleCount,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider
+        .item_classification_service
+        .update_item_classification(&service_context, store_id, input.into())
+    {
+        Ok(item_classification) => Ok(ItemClassificationNode::from_domain(item_classification)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+#[derive(InputObject)]
+pub struct UpdateItemClassificationInput {
+    pub item_id: String,
+    /// Sets the ABC class manually, it is then kept when classes are recalculated
+    pub abc_class: Option<AbcClassNode>,
+    pub ven_class: Option<VenClassNode>,
+}
+
+impl From<UpdateItemClassificationInput> for UpdateItemClassification {
+    fn from(
+        UpdateItemClassificationInput {
+            item_id,
+            abc_class,
+            ven_class,
+        }: UpdateItemClassificationInput,
+    ) -> Self {
+        UpdateItemClassification {
+            item_id,
+            abc_class: abc_class.map(AbcClassNode::to_domain),
+            ven_class: ven_class.map(VenClassNode::to_domain),
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::ItemDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
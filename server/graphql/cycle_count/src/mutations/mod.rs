@@ -0,0 +1,5 @@
+mod item_classification;
+mod plan;
+
+pub use item_classification::*;
+pub use plan::*;
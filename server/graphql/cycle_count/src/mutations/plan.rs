@@ -0,0 +1,90 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::CycleCountPlanNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    cycle_count::upsert::{UpsertCycleCountPlan, UpsertCycleCountPlanError as ServiceError},
+};
+
+pub fn upsert_cycle_count_plan(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpsertCycleCountPlanInput,
+) -> Result<CycleCountPlanNode> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateCycleCount,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider
+        .cycle_count_service
+        .upsert_cycle_count_plan(&service_context, store_id, &user.user_id, input.into())
+    {
+        Ok(cycle_count_plan) => Ok(CycleCountPlanNode::from_domain(cycle_count_plan)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+#[derive(InputObject)]
+pub struct UpsertCycleCountPlanInput {
+    pub id: String,
+    pub name: String,
+    /// First day of cycle week 0
+    pub start_date: NaiveDate,
+    /// Number of weeks in which all A items are counted once
+    pub a_interval_weeks: u32,
+    /// Number of weeks in which all B items are counted once
+    pub b_interval_weeks: u32,
+    /// Number of weeks in which all C items are counted once
+    pub c_interval_weeks: u32,
+    pub is_active: bool,
+}
+
+impl From<UpsertCycleCountPlanInput> for UpsertCycleCountPlan {
+    fn from(
+        UpsertCycleCountPlanInput {
+            id,
+            name,
+            start_date,
+            a_interval_weeks,
+            b_interval_weeks,
+            c_interval_weeks,
+            is_active,
+        }: UpsertCycleCountPlanInput,
+    ) -> Self {
+        UpsertCycleCountPlan {
+            id,
+            name,
+            start_date,
+            a_interval_weeks,
+            b_interval_weeks,
+            c_interval_weeks,
+            is_active,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::InvalidStore => BadUserInput(formatted_error),
+        ServiceError::PlanBelongsToAnotherStore => BadUserInput(formatted_error),
+        ServiceError::IntervalMustBePositive => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
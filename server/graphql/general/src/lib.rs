@@ -4,9 +4,26 @@ mod queries;
 use self::queries::*;
 
 use async_graphql::*;
-use graphql_core::pagination::PaginationInput;
-use mutations::server_settings::{
-    update_server_settings, UpdateServerSettingsInput, UpdateServerSettingsResponse,
+use chrono::{DateTime, Utc};
+use graphql_core::{generic_filters::DateFilterInput, pagination::PaginationInput};
+use graphql_types::types::{
+    ItemCategoryConnector, ItemDepartmentConnector, StorePreferenceNode, SyncConflictNode,
+    SyncIntegrityNode, SyncIntegrityRepairNode, SyncLogNode, SyncQuarantineNode,
+};
+use mutations::{
+    manual_sync::manual_sync,
+    offline_sync::{
+        export_offline_sync_file, import_offline_sync_file, OfflineSyncExportNode,
+        OfflineSyncImportNode,
+    },
+    reinitialise_site::{reinitialise_site, ReinitialiseSiteNode},
+    retry_sync_quarantine::{retry_sync_quarantine, RetrySyncQuarantineResponse},
+    server_settings::{
+        update_server_settings, UpdateServerSettingsInput, UpdateServerSettingsResponse,
+        UpdateSyncSettingsInput,
+    },
+    store_preference::{update_store_preference, UpdateStorePreferenceInput},
+    sync_integrity::repair_sync_integrity,
 };
 use queries::{
     requisition_line_chart::{ConsumptionOptionsInput, StockEvolutionOptionsInput},
@@ -65,6 +82,14 @@ impl GeneralQueries {
         get_store(ctx, &id)
     }
 
+    pub async fn store_preference(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<StorePreferenceNode> {
+        store_preference(ctx, store_id)
+    }
+
     pub async fn stores(
         &self,
         ctx: &Context<'_>,
@@ -102,6 +127,22 @@ impl GeneralQueries {
         items(ctx, store_id, page, filter, sort)
     }
 
+    pub async fn item_categories(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<ItemCategoryConnector> {
+        item_categories(ctx, store_id)
+    }
+
+    pub async fn item_departments(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<ItemDepartmentConnector> {
+        item_departments(ctx, store_id)
+    }
+
     pub async fn invoice_counts(
         &self,
         ctx: &Context<'_>,
@@ -121,6 +162,51 @@ impl GeneralQueries {
         stock_counts(ctx, store_id, timezone_offset, days_till_expired)
     }
 
+    /// Stock quantity and value grouped by time until expiry
+    pub async fn stock_expiry_buckets(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Timezone offset")] timezone_offset: Option<i32>,
+        #[graphql(desc = "Filter option")] filter: Option<StockExpiryBucketFilterInput>,
+    ) -> Result<Vec<StockExpiryBucketNode>> {
+        stock_expiry_buckets(ctx, store_id, timezone_offset, filter)
+    }
+
+    /// Available stock on hand per item and donor
+    pub async fn donor_stock_on_hand(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Filter option")] filter: Option<DonorStatsFilterInput>,
+    ) -> Result<Vec<DonorStockOnHandNode>> {
+        donor_stock_on_hand(ctx, store_id, filter)
+    }
+
+    /// Total quantity issued per item and donor
+    pub async fn donor_consumption(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Filter option")] filter: Option<DonorStatsFilterInput>,
+        #[graphql(desc = "Consumption date range")] date: Option<DateFilterInput>,
+    ) -> Result<Vec<DonorConsumptionNode>> {
+        donor_consumption(ctx, store_id, filter, date)
+    }
+
+    /// Stock on hand per item, and optionally per batch, as it was at the given datetime
+    pub async fn historical_stock_on_hand(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        datetime: DateTime<Utc>,
+        #[graphql(desc = "Group stock on hand by batch (defaults to false)")]
+        group_by_batch: Option<bool>,
+        #[graphql(desc = "Filter option")] filter: Option<HistoricalStockOnHandFilterInput>,
+    ) -> Result<Vec<HistoricalStockOnHandNode>> {
+        historical_stock_on_hand(ctx, store_id, datetime, group_by_batch, filter)
+    }
+
     pub async fn requisition_line_chart(
         &self,
         ctx: &Context<'_>,
@@ -148,6 +234,28 @@ impl GeneralQueries {
     ) -> Result<LogResponse> {
         logs(ctx, page, filter, sort)
     }
+
+    /// Latest sync run, including the progress of a sync that is still running
+    pub async fn latest_sync_status(&self, ctx: &Context<'_>) -> Result<Option<SyncLogNode>> {
+        latest_sync_status(ctx)
+    }
+
+    /// Latest sync run that finished without an error
+    pub async fn latest_successful_sync_status(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<SyncLogNode>> {
+        latest_successful_sync_status(ctx)
+    }
+
+    /// Most recent sync runs first
+    pub async fn sync_history(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Number of sync runs to return (max 100)")] first: Option<u32>,
+    ) -> Result<Vec<SyncLogNode>> {
+        sync_history(ctx, first)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -165,6 +273,26 @@ impl ServerAdminQueries {
     pub async fn server_restart(&self, ctx: &Context<'_>) -> Result<RestartNode> {
         server_restart(ctx, false).await
     }
+
+    /// Sync records that failed to translate or integrate, most recently failed first
+    pub async fn sync_quarantine(&self, ctx: &Context<'_>) -> Result<Vec<SyncQuarantineNode>> {
+        sync_quarantine(ctx)
+    }
+
+    /// Remote records that conflicted with unpushed local changes, most recently detected first
+    pub async fn sync_conflicts(&self, ctx: &Context<'_>) -> Result<Vec<SyncConflictNode>> {
+        sync_conflicts(ctx)
+    }
+
+    /// Compares row counts and content hashes of the remote data of a store with the central
+    /// server
+    pub async fn sync_integrity(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<SyncIntegrityNode> {
+        sync_integrity(ctx, &store_id).await
+    }
 }
 #[derive(Default, Clone)]
 pub struct ServerAdminMutations;
@@ -178,6 +306,84 @@ impl ServerAdminMutations {
     ) -> Result<UpdateServerSettingsResponse> {
         update_server_settings(ctx, input, false)
     }
+
+    pub async fn update_store_preference(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpdateStorePreferenceInput,
+    ) -> Result<StorePreferenceNode> {
+        update_store_preference(ctx, &store_id, input)
+    }
+
+    /// Triggers a sync straight away, the sync schedule restarts once the sync has finished
+    pub async fn manual_sync(&self, ctx: &Context<'_>) -> Result<String> {
+        manual_sync(ctx)
+    }
+
+    /// Retries integration of quarantined sync records, e.g. once missing central data has arrived
+    pub async fn retry_sync_quarantine(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Quarantined records to retry, all records are retried if not set")]
+        ids: Option<Vec<String>>,
+    ) -> Result<RetrySyncQuarantineResponse> {
+        retry_sync_quarantine(ctx, ids)
+    }
+
+    /// Exports unpushed local records and pending acknowledgements to an offline sync file, for
+    /// sites without connectivity to the central server
+    pub async fn export_offline_sync_file(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<OfflineSyncExportNode> {
+        export_offline_sync_file(ctx)
+    }
+
+    /// Imports an offline sync file that has been created by the central server for this site
+    pub async fn import_offline_sync_file(
+        &self,
+        ctx: &Context<'_>,
+        file: Upload,
+    ) -> Result<OfflineSyncImportNode> {
+        import_offline_sync_file(ctx, file)
+    }
+
+    /// Re-pulls the records of a store that don't match the central server, instead of a full
+    /// re-initialisation of the site
+    pub async fn repair_sync_integrity(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<SyncIntegrityRepairNode> {
+        repair_sync_integrity(ctx, &store_id).await
+    }
+
+    /// Re-initialises the site with new sync settings, e.g. to move the server to a different site
+    /// or to recover a broken site. The new settings are validated against the central server, the
+    /// database is backed up, the remote data is deleted and pulled again from the central server.
+    /// The server restarts afterwards.
+    pub async fn reinitialise_site(
+        &self,
+        ctx: &Context<'_>,
+        input: UpdateSyncSettingsInput,
+        #[graphql(
+            desc = "Discard local changes that haven't been pushed to the central server, otherwise the re-initialisation fails if there are any"
+        )]
+        discard_unpushed_changes: Option<bool>,
+        #[graphql(
+            desc = "Skip the database backup, required for postgres databases (back up with pg_dump instead)"
+        )]
+        skip_backup: Option<bool>,
+    ) -> Result<ReinitialiseSiteNode> {
+        reinitialise_site(
+            ctx,
+            input,
+            discard_unpushed_changes.unwrap_or(false),
+            skip_backup.unwrap_or(false),
+        )
+        .await
+    }
 }
 
 /// No access control during init stage
@@ -0,0 +1,18 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::validate_auth, ContextExt};
+use service::auth::{Resource, ResourceAccessRequest};
+
+/// Triggers a sync straight away, the sync schedule restarts once the sync has finished
+pub fn manual_sync(ctx: &Context<'_>) -> Result<String> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ManualSync,
+            store_id: None,
+        },
+    )?;
+
+    ctx.sync_sender().send();
+
+    Ok("Sync triggered".to_string())
+}
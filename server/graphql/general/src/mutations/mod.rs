@@ -1 +1,7 @@
+pub mod manual_sync;
+pub mod offline_sync;
+pub mod reinitialise_site;
+pub mod retry_sync_quarantine;
 pub mod server_settings;
+pub mod store_preference;
+pub mod sync_integrity;
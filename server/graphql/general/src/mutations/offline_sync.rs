@@ -0,0 +1,126 @@
+use std::io::Read;
+
+use async_graphql::*;
+use chrono::Utc;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    static_files::StaticFileService,
+    sync::{
+        offline_sync::{
+            confirm_offline_sync_export, export_offline_sync_file as export_file,
+            import_offline_sync_file as import_file, OfflineSyncError, OfflineSyncImport,
+            OFFLINE_SYNC_FILE_EXTENSION,
+        },
+        settings::SyncSettings,
+    },
+};
+
+#[derive(SimpleObject)]
+pub struct OfflineSyncExportNode {
+    /// Id of the offline sync file, the file can be downloaded through the static files endpoint
+    pub file_id: String,
+    /// Number of local records in the file
+    pub pushed_records: u32,
+    /// Number of acknowledged remote records in the file
+    pub acknowledged_records: u32,
+}
+
+#[derive(SimpleObject)]
+pub struct OfflineSyncImportNode {
+    pub central_records: u32,
+    pub remote_records: u32,
+}
+
+pub fn export_offline_sync_file(ctx: &Context<'_>) -> Result<OfflineSyncExportNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+    let sync_settings = current_sync_settings(ctx)?;
+
+    let export = export_file(&service_context.connection, sync_settings).map_err(map_error)?;
+
+    let file_service = StaticFileService::new(&ctx.get_settings().server.base_dir)
+        .map_err(|err| StandardGraphqlError::InternalError(format!("{}", err)).extend())?;
+    let file_name = format!(
+        "{}_site_{}.{}",
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        sync_settings.site_id,
+        OFFLINE_SYNC_FILE_EXTENSION
+    );
+    let static_file = file_service
+        .store_file(&file_name, &export.file)
+        .map_err(|err| StandardGraphqlError::InternalError(format!("{}", err)).extend())?;
+    // Only marked as pushed once the file is stored
+    confirm_offline_sync_export(&service_context.connection, &export).map_err(map_error)?;
+
+    Ok(OfflineSyncExportNode {
+        file_id: static_file.id,
+        pushed_records: export.pushed_records,
+        acknowledged_records: export.acknowledged_records,
+    })
+}
+
+pub fn import_offline_sync_file(ctx: &Context<'_>, file: Upload) -> Result<OfflineSyncImportNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let sync_settings = current_sync_settings(ctx)?;
+
+    let mut bytes = Vec::new();
+    file.value(ctx)?
+        .into_read()
+        .read_to_end(&mut bytes)
+        .map_err(|err| StandardGraphqlError::BadUserInput(format!("{}", err)).extend())?;
+
+    let OfflineSyncImport {
+        central_records,
+        remote_records,
+    } = import_file(service_provider, sync_settings, &bytes).map_err(map_error)?;
+
+    Ok(OfflineSyncImportNode {
+        central_records,
+        remote_records,
+    })
+}
+
+fn current_sync_settings<'a>(ctx: &'a Context<'_>) -> Result<&'a SyncSettings> {
+    ctx.get_settings().sync.as_ref().ok_or_else(|| {
+        StandardGraphqlError::BadUserInput("Sync settings are not configured".to_string()).extend()
+    })
+}
+
+fn map_error(error: OfflineSyncError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        OfflineSyncError::SyncInProgress
+        | OfflineSyncError::InvalidFile(_)
+        | OfflineSyncError::WrongSite { .. }
+        | OfflineSyncError::InvalidSignature
+        | OfflineSyncError::AlreadyImported { .. } => BadUserInput(formatted_error),
+        OfflineSyncError::FileError(_)
+        | OfflineSyncError::TranslationError(_)
+        | OfflineSyncError::IntegrationError(_)
+        | OfflineSyncError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
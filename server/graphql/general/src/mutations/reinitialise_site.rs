@@ -0,0 +1,80 @@
+use actix_web::web::Data;
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    service_provider::ServiceProvider,
+    sync::site_reinitialisation::{
+        reinitialise_site as reinitialise, ReinitialiseSiteOptions, SiteReinitialisation,
+        SiteReinitialisationError,
+    },
+};
+
+use super::server_settings::UpdateSyncSettingsInput;
+
+#[derive(SimpleObject)]
+pub struct ReinitialiseSiteNode {
+    /// Copy of the database before the re-initialisation, stored next to the database file. Not
+    /// set if the backup has been skipped.
+    pub backup_file: Option<String>,
+}
+
+/// Re-initialises the site with new sync settings and restarts the server once the data of the
+/// site has been pulled. The progress of the initial pull is reported through the latest sync
+/// status.
+pub async fn reinitialise_site(
+    ctx: &Context<'_>,
+    input: UpdateSyncSettingsInput,
+    discard_unpushed_changes: bool,
+    skip_backup: bool,
+) -> Result<ReinitialiseSiteNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    // The synchroniser for the new settings needs a shared service provider
+    let service_provider = ctx.data_unchecked::<Data<ServiceProvider>>().clone();
+    let SiteReinitialisation { backup_file } = reinitialise(
+        service_provider.into_inner(),
+        &ctx.get_settings().database,
+        input.to_domain(),
+        ReinitialiseSiteOptions {
+            discard_unpushed_changes,
+            skip_backup,
+        },
+    )
+    .await
+    .map_err(map_error)?;
+
+    ctx.restart_switch()
+        .send(true)
+        .await
+        .map_err(|err| StandardGraphqlError::InternalError(format!("{:#?}", err)).extend())?;
+
+    Ok(ReinitialiseSiteNode { backup_file })
+}
+
+fn map_error(error: SiteReinitialisationError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        SiteReinitialisationError::InvalidSettings(_)
+        | SiteReinitialisationError::SyncInProgress
+        | SiteReinitialisationError::UnpushedChanges
+        | SiteReinitialisationError::BackupNotSupported
+        | SiteReinitialisationError::ConnectionError(_) => BadUserInput(formatted_error),
+        SiteReinitialisationError::BackupError(_)
+        | SiteReinitialisationError::InitialPullError(_)
+        | SiteReinitialisationError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,71 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::SyncQuarantineNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    sync::sync_quarantine::{RetrySyncQuarantineError, RetrySyncQuarantineResult},
+};
+
+#[derive(SimpleObject)]
+pub struct RetrySyncQuarantineResponse {
+    /// Number of retried records that have been integrated
+    pub integrated_count: u32,
+    /// Retried records that failed again
+    pub still_quarantined: Vec<SyncQuarantineNode>,
+}
+
+pub fn retry_sync_quarantine(
+    ctx: &Context<'_>,
+    ids: Option<Vec<String>>,
+) -> Result<RetrySyncQuarantineResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+
+    match service_provider
+        .sync_quarantine_service
+        .retry_sync_quarantine(service_provider, ids)
+    {
+        Ok(result) => Ok(RetrySyncQuarantineResponse::from_domain(result)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+impl RetrySyncQuarantineResponse {
+    fn from_domain(
+        RetrySyncQuarantineResult {
+            integrated_count,
+            still_quarantined,
+        }: RetrySyncQuarantineResult,
+    ) -> Self {
+        RetrySyncQuarantineResponse {
+            integrated_count,
+            still_quarantined: still_quarantined
+                .into_iter()
+                .map(SyncQuarantineNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+fn map_error(error: RetrySyncQuarantineError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        RetrySyncQuarantineError::SyncInProgress => BadUserInput(formatted_error),
+        RetrySyncQuarantineError::DatabaseError(_) => InternalError(formatted_error),
+        RetrySyncQuarantineError::ImportError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
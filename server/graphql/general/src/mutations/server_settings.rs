@@ -1,4 +1,5 @@
 use async_graphql::*;
+use chrono::NaiveTime;
 
 use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
@@ -6,11 +7,12 @@ use graphql_core::{
 };
 use service::{
     auth::{Resource, ResourceAccessRequest},
-    settings_service::UpdateSettingsError, sync::settings::SyncSettings,
+    settings_service::UpdateSettingsError,
+    sync::settings::{SyncQuietHours, SyncSettings},
 };
 use util::hash::sha256;
 
-use crate::queries::server_settings::ServerSettingsNode;
+use crate::queries::server_settings::{ServerSettingsNode, SyncConflictPolicyNode};
 
 #[derive(Union)]
 pub enum UpdateServerSettingsResponse {
@@ -28,6 +30,25 @@ pub struct UpdateSyncSettingsInput {
     pub interval_sec: u64,
     pub central_server_site_id: u32,
     pub site_id: u32,
+    /// Scheduled sync is paused during quiet hours, manual sync is still possible
+    pub quiet_hours: Option<SyncQuietHoursInput>,
+    /// Conflict resolution policies, tables that aren't listed use REMOTE_WINS
+    pub conflict_policies: Option<Vec<SyncTableConflictPolicyInput>>,
+}
+
+#[derive(InputObject)]
+pub struct SyncTableConflictPolicyInput {
+    /// Table name on the central server, e.g. transact
+    pub table_name: String,
+    pub policy: SyncConflictPolicyNode,
+}
+
+#[derive(InputObject)]
+pub struct SyncQuietHoursInput {
+    /// Local time
+    pub start: NaiveTime,
+    /// Local time, may be before start for quiet hours spanning midnight
+    pub end: NaiveTime,
 }
 
 #[derive(InputObject)]
@@ -92,7 +113,7 @@ pub fn update_server_settings(
 }
 
 impl UpdateSyncSettingsInput {
-    fn to_domain(self) -> SyncSettings {
+    pub(crate) fn to_domain(self) -> SyncSettings {
         SyncSettings {
             url: self.url,
             username: self.username,
@@ -100,6 +121,17 @@ impl UpdateSyncSettingsInput {
             interval_sec: self.interval_sec,
             central_server_site_id: self.central_server_site_id,
             site_id: self.site_id,
+            quiet_hours: self
+                .quiet_hours
+                .map(|SyncQuietHoursInput { start, end }| SyncQuietHours { start, end }),
+            conflict_policies: self
+                .conflict_policies
+                .unwrap_or_default()
+                .into_iter()
+                .map(|SyncTableConflictPolicyInput { table_name, policy }| {
+                    (table_name, policy.to_domain())
+                })
+                .collect(),
         }
     }
 }
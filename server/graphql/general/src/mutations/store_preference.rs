@@ -0,0 +1,71 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{AllocationStrategyNode, StorePreferenceNode};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    store_preference::update::{UpdateStorePreference, UpdateStorePreferenceError as ServiceError},
+};
+
+/// Replaces all preferences of the store
+#[derive(InputObject)]
+pub struct UpdateStorePreferenceInput {
+    pub allocation_strategy: AllocationStrategyNode,
+    /// Location stock is picked from first with the PREFERRED_LOCATION strategy
+    pub pick_face_location_id: Option<String>,
+}
+
+pub fn update_store_preference(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateStorePreferenceInput,
+) -> Result<StorePreferenceNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStorePreference,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider
+        .store_preference_service
+        .update_store_preference(&service_context, store_id, input.to_domain())
+    {
+        Ok(store_preference) => Ok(StorePreferenceNode::from_domain(store_preference)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+impl UpdateStorePreferenceInput {
+    pub fn to_domain(self) -> UpdateStorePreference {
+        let UpdateStorePreferenceInput {
+            allocation_strategy,
+            pick_face_location_id,
+        } = self;
+
+        UpdateStorePreference {
+            allocation_strategy: allocation_strategy.to_domain(),
+            pick_face_location_id,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::InvalidStore => BadUserInput(formatted_error),
+        ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::LocationDoesNotBelongToCurrentStore => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,34 @@
+use async_graphql::*;
+use graphql_core::{standard_graphql_error::validate_auth, ContextExt};
+use graphql_types::types::SyncIntegrityRepairNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    sync::sync_integrity::repair_store_integrity,
+};
+
+use crate::queries::sync_integrity::{current_sync_settings, map_sync_integrity_error};
+
+/// Re-pulls the records of all ranges of a store that don't match the central server. Local
+/// changes need to be pushed first.
+pub async fn repair_sync_integrity(
+    ctx: &Context<'_>,
+    store_id: &str,
+) -> Result<SyncIntegrityRepairNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let repair = repair_store_integrity(
+        ctx.service_provider(),
+        current_sync_settings(ctx)?,
+        store_id,
+    )
+    .await
+    .map_err(map_sync_integrity_error)?;
+
+    Ok(SyncIntegrityRepairNode::from_domain(repair))
+}
@@ -0,0 +1,161 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use graphql_core::{
+    generic_filters::{DateFilterInput, EqualFilterStringInput},
+    loader::{ItemLoader, NameRowLoader},
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::ItemNode;
+use repository::EqualFilter;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    donor_stats::{DonorConsumption, DonorStatsFilter, DonorStockOnHand},
+};
+
+#[derive(InputObject, Clone)]
+pub struct DonorStatsFilterInput {
+    pub item_id: Option<EqualFilterStringInput>,
+    pub donor_id: Option<EqualFilterStringInput>,
+}
+
+pub struct DonorStockOnHandNode {
+    pub donor_stock_on_hand: DonorStockOnHand,
+}
+
+pub struct DonorConsumptionNode {
+    pub donor_consumption: DonorConsumption,
+}
+
+#[Object]
+impl DonorStockOnHandNode {
+    pub async fn item_id(&self) -> &str {
+        &self.donor_stock_on_hand.item_id
+    }
+
+    pub async fn donor_id(&self) -> &str {
+        &self.donor_stock_on_hand.donor_id
+    }
+
+    pub async fn available_stock_on_hand(&self) -> u32 {
+        self.donor_stock_on_hand.available_stock_on_hand
+    }
+
+    pub async fn donor_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        donor_name(ctx, &self.donor_stock_on_hand.donor_id).await
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        item(ctx, &self.donor_stock_on_hand.item_id).await
+    }
+}
+
+#[Object]
+impl DonorConsumptionNode {
+    pub async fn item_id(&self) -> &str {
+        &self.donor_consumption.item_id
+    }
+
+    pub async fn donor_id(&self) -> &str {
+        &self.donor_consumption.donor_id
+    }
+
+    pub async fn total_consumption(&self) -> u32 {
+        self.donor_consumption.total_consumption
+    }
+
+    pub async fn donor_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        donor_name(ctx, &self.donor_consumption.donor_id).await
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        item(ctx, &self.donor_consumption.item_id).await
+    }
+}
+
+async fn donor_name(ctx: &Context<'_>, donor_id: &str) -> Result<Option<String>> {
+    let loader = ctx.get_loader::<DataLoader<NameRowLoader>>();
+    let result = loader.load_one(donor_id.to_string()).await?;
+
+    Ok(result.map(|name_row| name_row.name))
+}
+
+async fn item(ctx: &Context<'_>, item_id: &str) -> Result<ItemNode> {
+    let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+    let item_option = loader.load_one(item_id.to_string()).await?;
+
+    item_option.map(ItemNode::from_domain).ok_or(
+        StandardGraphqlError::InternalError(format!("Cannot find item ({})", item_id)).extend(),
+    )
+}
+
+pub fn donor_stock_on_hand(
+    ctx: &Context<'_>,
+    store_id: String,
+    filter: Option<DonorStatsFilterInput>,
+) -> Result<Vec<DonorStockOnHandNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::Report,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let rows = service_provider
+        .donor_stats_service
+        .get_donor_stock_on_hand(&service_context, &store_id, filter.map(|f| f.to_domain()))
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|donor_stock_on_hand| DonorStockOnHandNode {
+            donor_stock_on_hand,
+        })
+        .collect())
+}
+
+pub fn donor_consumption(
+    ctx: &Context<'_>,
+    store_id: String,
+    filter: Option<DonorStatsFilterInput>,
+    date: Option<DateFilterInput>,
+) -> Result<Vec<DonorConsumptionNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::Report,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let rows = service_provider
+        .donor_stats_service
+        .get_donor_consumption(
+            &service_context,
+            &store_id,
+            filter.map(|f| f.to_domain()),
+            date.map(|d| d.into()),
+        )
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|donor_consumption| DonorConsumptionNode { donor_consumption })
+        .collect())
+}
+
+impl DonorStatsFilterInput {
+    pub fn to_domain(self) -> DonorStatsFilter {
+        DonorStatsFilter {
+            item_id: self.item_id.map(EqualFilter::from),
+            donor_id: self.donor_id.map(EqualFilter::from),
+        }
+    }
+}
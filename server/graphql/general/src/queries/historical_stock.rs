@@ -0,0 +1,95 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    generic_filters::EqualFilterStringInput,
+    loader::ItemLoader,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::ItemNode;
+use repository::EqualFilter;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    historical_stock::{HistoricalStockOnHand, HistoricalStockOnHandFilter},
+};
+
+#[derive(InputObject, Clone)]
+pub struct HistoricalStockOnHandFilterInput {
+    pub item_id: Option<EqualFilterStringInput>,
+}
+
+pub struct HistoricalStockOnHandNode {
+    pub historical_stock_on_hand: HistoricalStockOnHand,
+}
+
+#[Object]
+impl HistoricalStockOnHandNode {
+    pub async fn item_id(&self) -> &str {
+        &self.historical_stock_on_hand.item_id
+    }
+
+    /// Only set when grouped by batch
+    pub async fn batch(&self) -> &Option<String> {
+        &self.historical_stock_on_hand.batch
+    }
+
+    pub async fn total_stock_on_hand(&self) -> u32 {
+        self.historical_stock_on_hand.total_stock_on_hand
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        let item_id = &self.historical_stock_on_hand.item_id;
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let item_option = loader.load_one(item_id.clone()).await?;
+
+        item_option.map(ItemNode::from_domain).ok_or(
+            StandardGraphqlError::InternalError(format!("Cannot find item ({})", item_id)).extend(),
+        )
+    }
+}
+
+pub fn historical_stock_on_hand(
+    ctx: &Context<'_>,
+    store_id: String,
+    datetime: DateTime<Utc>,
+    group_by_batch: Option<bool>,
+    filter: Option<HistoricalStockOnHandFilterInput>,
+) -> Result<Vec<HistoricalStockOnHandNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::Report,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let rows = service_provider
+        .historical_stock_service
+        .get_historical_stock_on_hand(
+            &service_context,
+            &store_id,
+            datetime.naive_utc(),
+            filter.map(|f| f.to_domain()),
+            group_by_batch.unwrap_or(false),
+        )
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|historical_stock_on_hand| HistoricalStockOnHandNode {
+            historical_stock_on_hand,
+        })
+        .collect())
+}
+
+impl HistoricalStockOnHandFilterInput {
+    pub fn to_domain(self) -> HistoricalStockOnHandFilter {
+        HistoricalStockOnHandFilter {
+            item_id: self.item_id.map(EqualFilter::from),
+        }
+    }
+}
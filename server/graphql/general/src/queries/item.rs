@@ -6,12 +6,14 @@ use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
 };
-use graphql_types::types::{ItemConnector, ItemNodeType};
+use graphql_types::types::{
+    ItemCategoryConnector, ItemConnector, ItemDepartmentConnector, ItemNodeType,
+};
 use repository::{EqualFilter, PaginationOption, SimpleStringFilter};
 use repository::{ItemFilter, ItemSort, ItemSortField};
 use service::{
     auth::{Resource, ResourceAccessRequest},
-    item::get_items,
+    item::{get_item_categories, get_item_departments, get_items},
 };
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq)]
@@ -46,6 +48,10 @@ pub struct ItemFilterInput {
     pub r#type: Option<EqualFilterItemTypeInput>,
     pub code: Option<SimpleStringFilterInput>,
     pub is_visible: Option<bool>,
+    pub category_id: Option<EqualFilterStringInput>,
+    pub department_id: Option<EqualFilterStringInput>,
+    pub atc_category: Option<SimpleStringFilterInput>,
+    pub essential_drug_list: Option<bool>,
 }
 
 #[derive(Union)]
@@ -82,6 +88,36 @@ pub fn items(
     Ok(ItemsResponse::Response(ItemConnector::from_domain(items)))
 }
 
+pub fn item_categories(ctx: &Context<'_>, store_id: String) -> Result<ItemCategoryConnector> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryItems,
+            store_id: Some(store_id),
+        },
+    )?;
+
+    let categories = get_item_categories(ctx.get_connection_manager())
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(ItemCategoryConnector::from_vec(categories))
+}
+
+pub fn item_departments(ctx: &Context<'_>, store_id: String) -> Result<ItemDepartmentConnector> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryItems,
+            store_id: Some(store_id),
+        },
+    )?;
+
+    let departments = get_item_departments(ctx.get_connection_manager())
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(ItemDepartmentConnector::from_vec(departments))
+}
+
 impl ItemFilterInput {
     pub fn to_domain(self) -> ItemFilter {
         let ItemFilterInput {
@@ -90,6 +126,10 @@ impl ItemFilterInput {
             r#type,
             code,
             is_visible,
+            category_id,
+            department_id,
+            atc_category,
+            essential_drug_list,
         } = self;
 
         ItemFilter {
@@ -98,6 +138,10 @@ impl ItemFilterInput {
             code: code.map(SimpleStringFilter::from),
             r#type: r#type.map(|t| map_filter!(t, ItemNodeType::to_domain)),
             is_visible,
+            category_id: category_id.map(EqualFilter::from),
+            department_id: department_id.map(EqualFilter::from),
+            atc_category: atc_category.map(SimpleStringFilter::from),
+            essential_drug_list,
         }
     }
 }
@@ -16,10 +16,26 @@ pub mod item;
 pub use self::item::*;
 pub mod stock_counts;
 pub use self::stock_counts::*;
+pub mod stock_expiry_buckets;
+pub use self::stock_expiry_buckets::*;
+pub mod donor_stats;
+pub use self::donor_stats::*;
+pub mod historical_stock;
+pub use self::historical_stock::*;
 pub mod store;
 pub use self::store::*;
+pub mod store_preference;
+pub use self::store_preference::*;
 pub mod log;
 pub use self::log::*;
+pub mod sync_status;
+pub use self::sync_status::*;
+pub mod sync_quarantine;
+pub use self::sync_quarantine::*;
+pub mod sync_conflict;
+pub use self::sync_conflict::*;
+pub mod sync_integrity;
+pub use self::sync_integrity::*;
 pub mod requisition_line_chart;
 pub mod server_settings;
 
@@ -1,4 +1,5 @@
 use async_graphql::*;
+use chrono::NaiveTime;
 use graphql_core::{
     standard_graphql_error::{validate_auth, StandardGraphqlError},
     ContextExt,
@@ -6,7 +7,7 @@ use graphql_core::{
 use serde::Serialize;
 use service::{
     auth::{Resource, ResourceAccessRequest},
-    sync::settings::SyncSettings,
+    sync::settings::{SyncConflictPolicy, SyncSettings},
 };
 
 // TODO find a better place, e.g. merge with getApiVersion?
@@ -47,6 +48,56 @@ impl SyncSettingsNode {
     pub async fn site_id(&self) -> u32 {
         self.settings.site_id
     }
+
+    /// Scheduled sync is paused during quiet hours
+    pub async fn quiet_hours(&self) -> Option<SyncQuietHoursNode> {
+        self.settings
+            .quiet_hours
+            .as_ref()
+            .map(|quiet_hours| SyncQuietHoursNode {
+                start: quiet_hours.start,
+                end: quiet_hours.end,
+            })
+    }
+
+    /// Conflict resolution policies for remote records conflicting with unpushed local changes,
+    /// tables that aren't listed use REMOTE_WINS
+    pub async fn conflict_policies(&self) -> Vec<SyncTableConflictPolicyNode> {
+        let mut policies: Vec<SyncTableConflictPolicyNode> = self
+            .settings
+            .conflict_policies
+            .iter()
+            .map(|(table_name, policy)| SyncTableConflictPolicyNode {
+                table_name: table_name.clone(),
+                policy: SyncConflictPolicyNode::from_domain(policy),
+            })
+            .collect();
+        policies.sort_by(|a, b| a.table_name.cmp(&b.table_name));
+        policies
+    }
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SyncConflictPolicyNode {
+    /// Integrate the remote record, overwriting the local changes
+    RemoteWins,
+    /// Keep the local changes, they are pushed with the next sync
+    LocalWins,
+}
+
+#[derive(SimpleObject)]
+pub struct SyncTableConflictPolicyNode {
+    /// Table name on the central server, e.g. transact
+    pub table_name: String,
+    pub policy: SyncConflictPolicyNode,
+}
+
+#[derive(SimpleObject)]
+pub struct SyncQuietHoursNode {
+    /// Local time
+    pub start: NaiveTime,
+    /// Local time, may be before start for quiet hours spanning midnight
+    pub end: NaiveTime,
 }
 
 #[derive(Debug)]
@@ -157,3 +208,23 @@ pub async fn server_restart(ctx: &Context<'_>, stage0: bool) -> Result<RestartNo
         }
     }
 }
+
+impl SyncConflictPolicyNode {
+    pub fn from_domain(policy: &SyncConflictPolicy) -> Self {
+        use SyncConflictPolicy as from;
+        use SyncConflictPolicyNode as to;
+        match policy {
+            from::RemoteWins => to::RemoteWins,
+            from::LocalWins => to::LocalWins,
+        }
+    }
+
+    pub fn to_domain(self) -> SyncConflictPolicy {
+        use SyncConflictPolicy as to;
+        use SyncConflictPolicyNode as from;
+        match self {
+            from::RemoteWins => to::RemoteWins,
+            from::LocalWins => to::LocalWins,
+        }
+    }
+}
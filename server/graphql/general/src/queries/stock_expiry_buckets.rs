@@ -0,0 +1,125 @@
+use async_graphql::*;
+use chrono::Utc;
+use graphql_core::{
+    generic_filters::EqualFilterStringInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use repository::EqualFilter;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    dashboard::stock_expiry_buckets::{
+        StockExpiryBucket, StockExpiryBucketFilter, StockExpiryBucketType,
+    },
+};
+use util::timezone::offset_to_timezone;
+
+#[derive(InputObject, Clone)]
+pub struct StockExpiryBucketFilterInput {
+    pub location_id: Option<EqualFilterStringInput>,
+    pub master_list_id: Option<EqualFilterStringInput>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum StockExpiryBucketNodeType {
+    Expired,
+    LessThanOneMonth,
+    OneToThreeMonths,
+    ThreeToSixMonths,
+    SixToTwelveMonths,
+    MoreThanTwelveMonths,
+}
+
+pub struct StockExpiryBucketNode {
+    pub stock_expiry_bucket: StockExpiryBucket,
+}
+
+#[Object]
+impl StockExpiryBucketNode {
+    pub async fn bucket(&self) -> StockExpiryBucketNodeType {
+        StockExpiryBucketNodeType::from_domain(&self.stock_expiry_bucket.bucket)
+    }
+
+    pub async fn stock_line_count(&self) -> u32 {
+        self.stock_expiry_bucket.stock_line_count
+    }
+
+    /// Total number of units
+    pub async fn total_quantity(&self) -> u32 {
+        self.stock_expiry_bucket.total_quantity
+    }
+
+    /// Total cost value
+    pub async fn total_value(&self) -> f64 {
+        self.stock_expiry_bucket.total_value
+    }
+}
+
+pub fn stock_expiry_buckets(
+    ctx: &Context<'_>,
+    store_id: String,
+    timezone_offset: Option<i32>,
+    filter: Option<StockExpiryBucketFilterInput>,
+) -> Result<Vec<StockExpiryBucketNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::StockCount,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let timezone_offset = offset_to_timezone(&timezone_offset).ok_or(
+        StandardGraphqlError::BadUserInput("Invalid timezone offset".to_string()),
+    )?;
+    let date = Utc::now()
+        .with_timezone(&timezone_offset)
+        .date()
+        .naive_utc();
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let buckets = service_provider
+        .stock_expiry_bucket_service
+        .get_stock_expiry_buckets(
+            &service_context,
+            &store_id,
+            date,
+            filter.map(|f| f.to_domain()),
+        )
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(buckets
+        .into_iter()
+        .map(|stock_expiry_bucket| StockExpiryBucketNode {
+            stock_expiry_bucket,
+        })
+        .collect())
+}
+
+impl StockExpiryBucketFilterInput {
+    pub fn to_domain(self) -> StockExpiryBucketFilter {
+        StockExpiryBucketFilter {
+            location_id: self.location_id.map(EqualFilter::from),
+            master_list_id: self.master_list_id.map(EqualFilter::from),
+        }
+    }
+}
+
+impl StockExpiryBucketNodeType {
+    pub fn from_domain(from: &StockExpiryBucketType) -> StockExpiryBucketNodeType {
+        match from {
+            StockExpiryBucketType::Expired => StockExpiryBucketNodeType::Expired,
+            StockExpiryBucketType::LessThanOneMonth => StockExpiryBucketNodeType::LessThanOneMonth,
+            StockExpiryBucketType::OneToThreeMonths => StockExpiryBucketNodeType::OneToThreeMonths,
+            StockExpiryBucketType::ThreeToSixMonths => StockExpiryBucketNodeType::ThreeToSixMonths,
+            StockExpiryBucketType::SixToTwelveMonths => {
+                StockExpiryBucketNodeType::SixToTwelveMonths
+            }
+            StockExpiryBucketType::MoreThanTwelveMonths => {
+                StockExpiryBucketNodeType::MoreThanTwelveMonths
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StorePreferenceNode;
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub fn store_preference(ctx: &Context<'_>, store_id: String) -> Result<StorePreferenceNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStorePreference,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let store_preference = service_provider
+        .store_preference_service
+        .get_store_preference(&service_context, &store_id)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(StorePreferenceNode::from_domain(store_preference))
+}
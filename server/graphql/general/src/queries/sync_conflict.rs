@@ -0,0 +1,30 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::SyncConflictNode;
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub fn sync_conflicts(ctx: &Context<'_>) -> Result<Vec<SyncConflictNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let rows = service_provider
+        .sync_conflict_service
+        .get_sync_conflicts(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(rows
+        .into_iter()
+        .map(SyncConflictNode::from_domain)
+        .collect())
+}
@@ -0,0 +1,57 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::SyncIntegrityNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    sync::{
+        settings::SyncSettings,
+        sync_integrity::{verify_store_integrity, SyncIntegrityError},
+    },
+};
+
+/// Compares the remote data of a store with the central server
+pub async fn sync_integrity(ctx: &Context<'_>, store_id: &str) -> Result<SyncIntegrityNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ServerAdmin,
+            store_id: None,
+        },
+    )?;
+
+    let report = verify_store_integrity(
+        ctx.service_provider(),
+        current_sync_settings(ctx)?,
+        store_id,
+    )
+    .await
+    .map_err(map_sync_integrity_error)?;
+
+    Ok(SyncIntegrityNode::from_domain(report))
+}
+
+pub(crate) fn current_sync_settings<'a>(ctx: &'a Context<'_>) -> Result<&'a SyncSettings> {
+    ctx.get_settings().sync.as_ref().ok_or_else(|| {
+        StandardGraphqlError::BadUserInput("Sync settings are not configured".to_string()).extend()
+    })
+}
+
+pub(crate) fn map_sync_integrity_error(error: SyncIntegrityError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        SyncIntegrityError::StoreNotOnSite(_)
+        | SyncIntegrityError::UnpushedChanges
+        | SyncIntegrityError::SyncInProgress => BadUserInput(formatted_error),
+        SyncIntegrityError::ConnectionError(_)
+        | SyncIntegrityError::TranslationError(_)
+        | SyncIntegrityError::IntegrationError(_)
+        | SyncIntegrityError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
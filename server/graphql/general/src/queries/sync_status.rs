@@ -0,0 +1,70 @@
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::SyncLogNode;
+use service::auth::{Resource, ResourceAccessRequest};
+
+pub fn latest_sync_status(ctx: &Context<'_>) -> Result<Option<SyncLogNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QuerySyncStatus,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let sync_log = service_provider
+        .sync_status_service
+        .get_latest_sync_status(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(sync_log.map(SyncLogNode::from_domain))
+}
+
+pub fn latest_successful_sync_status(ctx: &Context<'_>) -> Result<Option<SyncLogNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QuerySyncStatus,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let sync_log = service_provider
+        .sync_status_service
+        .get_latest_successful_sync_status(&service_context)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(sync_log.map(SyncLogNode::from_domain))
+}
+
+pub fn sync_history(ctx: &Context<'_>, first: Option<u32>) -> Result<Vec<SyncLogNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QuerySyncStatus,
+            store_id: None,
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let sync_logs = service_provider
+        .sync_status_service
+        .get_sync_history(&service_context, first)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(sync_logs
+        .into_iter()
+        .map(SyncLogNode::from_domain)
+        .collect())
+}
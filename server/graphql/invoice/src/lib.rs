@@ -6,6 +6,9 @@ use mutations::AddToShipmentFromMasterListInput;
 mod invoice_queries;
 use self::invoice_queries::*;
 
+mod redistribution_queries;
+use self::redistribution_queries::*;
+
 pub mod mutations;
 use self::mutations::{inbound_shipment, outbound_shipment};
 
@@ -47,6 +50,20 @@ impl InvoiceQueries {
     ) -> Result<InvoicesResponse> {
         get_invoices(ctx, store_id, page, filter, sort)
     }
+
+    /// Suggested transfers of short-dated stock to stores that can use it before it expires.
+    /// Only stores whose stock and consumption are held on this server are considered, i.e. the
+    /// site's own stores on a remote site and all stores on the central server.
+    pub async fn redistribution_suggestions(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Timezone offset")] timezone_offset: Option<i32>,
+        #[graphql(desc = "Months of consumption used for the average monthly consumption")]
+        amc_lookback_months: Option<u32>,
+    ) -> Result<Vec<RedistributionSuggestionNode>> {
+        get_redistribution_suggestions(ctx, store_id, timezone_offset, amc_lookback_months)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -126,4 +143,14 @@ impl InvoiceMutations {
     ) -> Result<inbound_shipment::AddFromMasterListResponse> {
         inbound_shipment::add_from_master_list(ctx, &store_id, input)
     }
+
+    /// Creates a new outbound shipment from a redistribution suggestion
+    async fn create_redistribution_shipment(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: outbound_shipment::CreateRedistributionShipmentInput,
+    ) -> Result<outbound_shipment::CreateRedistributionShipmentResponse> {
+        outbound_shipment::create_redistribution_shipment(ctx, &store_id, input)
+    }
 }
@@ -0,0 +1,135 @@
+use async_graphql::*;
+use graphql_core::simple_generic_errors::{OtherPartyNotACustomer, OtherPartyNotVisible};
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::InvoiceNode;
+use repository::Invoice;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::invoice::outbound_shipment::InsertOutboundShipmentError;
+use service::redistribution::create_shipment::{
+    CreateRedistributionShipment as ServiceInput, CreateRedistributionShipmentError as ServiceError,
+};
+
+#[derive(InputObject)]
+pub struct CreateRedistributionShipmentInput {
+    /// The new invoice id provided by the client
+    pub id: String,
+    /// Stock line of the suggestion
+    pub stock_line_id: String,
+    /// Name of the receiving store, must be a customer of the current store
+    pub other_party_id: String,
+    pub number_of_packs: u32,
+}
+
+#[derive(SimpleObject)]
+pub struct CreateRedistributionShipmentError {
+    pub error: CreateRedistributionShipmentErrorInterface,
+}
+
+#[derive(Union)]
+pub enum CreateRedistributionShipmentResponse {
+    Error(CreateRedistributionShipmentError),
+    Response(InvoiceNode),
+}
+
+pub fn create_redistribution_shipment(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: CreateRedistributionShipmentInput,
+) -> Result<CreateRedistributionShipmentResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOutboundShipment,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    map_response(
+        service_provider
+            .redistribution_service
+            .create_redistribution_shipment(
+                &service_context,
+                store_id,
+                &user.user_id,
+                input.to_domain(),
+            ),
+    )
+}
+
+pub fn map_response(
+    from: Result<Invoice, ServiceError>,
+) -> Result<CreateRedistributionShipmentResponse> {
+    let result = match from {
+        Ok(invoice) => {
+            CreateRedistributionShipmentResponse::Response(InvoiceNode::from_domain(invoice))
+        }
+        Err(error) => {
+            CreateRedistributionShipmentResponse::Error(CreateRedistributionShipmentError {
+                error: map_error(error)?,
+            })
+        }
+    };
+
+    Ok(result)
+}
+
+impl CreateRedistributionShipmentInput {
+    pub fn to_domain(self) -> ServiceInput {
+        let CreateRedistributionShipmentInput {
+            id,
+            stock_line_id,
+            other_party_id,
+            number_of_packs,
+        } = self;
+
+        ServiceInput {
+            id,
+            stock_line_id,
+            other_party_id,
+            number_of_packs,
+        }
+    }
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", type = "String"))]
+pub enum CreateRedistributionShipmentErrorInterface {
+    OtherPartyNotACustomer(OtherPartyNotACustomer),
+    OtherPartyNotVisible(OtherPartyNotVisible),
+}
+
+fn map_error(error: ServiceError) -> Result<CreateRedistributionShipmentErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::InsertShipmentError(InsertOutboundShipmentError::OtherPartyNotACustomer) => {
+            return Ok(
+                CreateRedistributionShipmentErrorInterface::OtherPartyNotACustomer(
+                    OtherPartyNotACustomer,
+                ),
+            )
+        }
+        ServiceError::InsertShipmentError(InsertOutboundShipmentError::OtherPartyNotVisible) => {
+            return Ok(
+                CreateRedistributionShipmentErrorInterface::OtherPartyNotVisible(
+                    OtherPartyNotVisible,
+                ),
+            )
+        }
+        // Standard Graphql Errors
+        ServiceError::StockLineDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::NotThisStoreStockLine => BadUserInput(formatted_error),
+        ServiceError::InsertShipmentError(_) => BadUserInput(formatted_error),
+        ServiceError::InsertLineError(_) => BadUserInput(formatted_error),
+        ServiceError::CreatedInvoiceDoesNotExist => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
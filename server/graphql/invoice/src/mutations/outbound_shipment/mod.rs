@@ -10,3 +10,6 @@ pub use update::*;
 
 pub mod add_from_master_list;
 pub use add_from_master_list::*;
+
+pub mod create_redistribution_shipment;
+pub use create_redistribution_shipment::*;
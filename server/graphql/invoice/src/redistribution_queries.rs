@@ -0,0 +1,113 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::{NaiveDate, Utc};
+use graphql_core::{
+    loader::{ItemLoader, NameRowLoader},
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::ItemNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    redistribution::suggestion::RedistributionSuggestion,
+};
+use util::timezone::offset_to_timezone;
+
+pub struct RedistributionSuggestionNode {
+    pub suggestion: RedistributionSuggestion,
+}
+
+#[Object]
+impl RedistributionSuggestionNode {
+    pub async fn stock_line_id(&self) -> &str {
+        &self.suggestion.stock_line_id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.suggestion.item_id
+    }
+
+    pub async fn batch(&self) -> &Option<String> {
+        &self.suggestion.batch
+    }
+
+    pub async fn expiry_date(&self) -> NaiveDate {
+        self.suggestion.expiry_date
+    }
+
+    pub async fn pack_size(&self) -> i32 {
+        self.suggestion.pack_size
+    }
+
+    /// Units of the stock line the store is not expected to use before it expires
+    pub async fn at_risk_quantity(&self) -> u32 {
+        self.suggestion.at_risk_quantity
+    }
+
+    pub async fn to_store_id(&self) -> &str {
+        &self.suggestion.to_store_id
+    }
+
+    /// Name id of the receiving store, to be used as the other party of the outbound shipment
+    pub async fn to_name_id(&self) -> &str {
+        &self.suggestion.to_name_id
+    }
+
+    pub async fn to_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.get_loader::<DataLoader<NameRowLoader>>();
+        let result = loader.load_one(self.suggestion.to_name_id.clone()).await?;
+
+        Ok(result.map(|name_row| name_row.name))
+    }
+
+    /// Suggested number of packs to transfer
+    pub async fn number_of_packs(&self) -> u32 {
+        self.suggestion.number_of_packs
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        let item_id = &self.suggestion.item_id;
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let item_option = loader.load_one(item_id.clone()).await?;
+
+        item_option.map(ItemNode::from_domain).ok_or(
+            StandardGraphqlError::InternalError(format!("Cannot find item ({})", item_id)).extend(),
+        )
+    }
+}
+
+pub fn get_redistribution_suggestions(
+    ctx: &Context<'_>,
+    store_id: String,
+    timezone_offset: Option<i32>,
+    amc_lookback_months: Option<u32>,
+) -> Result<Vec<RedistributionSuggestionNode>> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::StockCount,
+            store_id: Some(store_id.clone()),
+        },
+    )?;
+
+    let timezone_offset = offset_to_timezone(&timezone_offset).ok_or(
+        StandardGraphqlError::BadUserInput("Invalid timezone offset".to_string()),
+    )?;
+    let date = Utc::now()
+        .with_timezone(&timezone_offset)
+        .date()
+        .naive_utc();
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    let suggestions = service_provider
+        .redistribution_service
+        .get_redistribution_suggestions(&service_context, &store_id, date, amc_lookback_months)
+        .map_err(StandardGraphqlError::from_repository_error)?;
+
+    Ok(suggestions
+        .into_iter()
+        .map(|suggestion| RedistributionSuggestionNode { suggestion })
+        .collect())
+}
@@ -29,6 +29,9 @@ pub struct InsertInput {
     pub total_before_tax: f64,
     pub total_after_tax: f64,
     pub tax: Option<TaxUpdate>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 #[derive(SimpleObject)]
@@ -92,6 +95,9 @@ impl InsertInput {
             total_before_tax,
             total_after_tax,
             tax,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         } = self;
 
         ServiceInput {
@@ -108,6 +114,9 @@ impl InsertInput {
             total_before_tax,
             total_after_tax,
             tax: tax.and_then(|tax| tax.percentage),
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         }
     }
 }
@@ -148,6 +157,9 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         ServiceError::NumberOfPacksBelowOne => BadUserInput(formatted_error),
         ServiceError::PackSizeBelowOne => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::DonorDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::ManufacturerDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::VvmStatusDoesNotExist => BadUserInput(formatted_error),
         ServiceError::ItemNotFound => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::NewlyCreatedLineDoesNotExist => InternalError(formatted_error),
@@ -440,7 +452,10 @@ mod test {
                     number_of_packs: 1,
                     total_before_tax: 1.1,
                     total_after_tax: 2.2,
-                    tax: Some(5.0)
+                    tax: Some(5.0),
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 }
             );
             Ok(InvoiceLine {
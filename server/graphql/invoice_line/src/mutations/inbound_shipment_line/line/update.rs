@@ -27,6 +27,9 @@ pub struct UpdateInput {
     pub sell_price_per_pack: Option<f64>,
     pub expiry_date: Option<NaiveDate>,
     pub number_of_packs: Option<u32>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 #[derive(SimpleObject)]
@@ -90,6 +93,9 @@ impl UpdateInput {
             sell_price_per_pack,
             cost_price_per_pack,
             number_of_packs,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         } = self;
 
         ServiceInput {
@@ -102,6 +108,9 @@ impl UpdateInput {
             sell_price_per_pack,
             cost_price_per_pack,
             number_of_packs,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         }
     }
 }
@@ -146,6 +155,9 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         ServiceError::NotThisInvoiceLine(_) => BadUserInput(formatted_error),
         ServiceError::PackSizeBelowOne => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::DonorDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::ManufacturerDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::VvmStatusDoesNotExist => BadUserInput(formatted_error),
         ServiceError::ItemNotFound => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::UpdatedLineDoesNotExist => InternalError(formatted_error),
@@ -460,6 +472,9 @@ mod test {
                     sell_price_per_pack: Some(1.0),
                     expiry_date: Some(NaiveDate::from_ymd(2022, 01, 01)),
                     number_of_packs: Some(1),
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 }
             );
             Ok(InvoiceLine {
@@ -25,9 +25,14 @@ pub struct InsertInput {
     pub item_id: String,
     pub stock_line_id: String,
     pub number_of_packs: u32,
+    /// Quantity in doses, number of packs is calculated from it when provided and needs to be 0
+    pub number_of_doses: Option<u32>,
     pub total_before_tax: f64,
     pub total_after_tax: f64,
     pub tax: Option<TaxUpdate>,
+    /// Reservation to issue the packs from, e.g. stock reserved for a campaign. Stock reserved
+    /// for the customer is used without it.
+    pub stock_line_reservation_id: Option<String>,
 }
 
 #[derive(SimpleObject)]
@@ -94,9 +99,11 @@ impl InsertInput {
             item_id,
             stock_line_id,
             number_of_packs,
+            number_of_doses,
             total_before_tax,
             total_after_tax,
             tax,
+            stock_line_reservation_id,
         } = self;
 
         ServiceInput {
@@ -105,9 +112,11 @@ impl InsertInput {
             item_id,
             stock_line_id,
             number_of_packs,
+            number_of_doses,
             total_before_tax,
             total_after_tax,
             tax: tax.and_then(|tax| tax.percentage),
+            stock_line_reservation_id,
         }
     }
 }
@@ -164,8 +173,11 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         ServiceError::NotAnOutboundShipment => BadUserInput(formatted_error),
         ServiceError::LineAlreadyExists => BadUserInput(formatted_error),
         ServiceError::NumberOfPacksBelowOne => BadUserInput(formatted_error),
+        ServiceError::CannotProvideNumberOfPacksAndDoses => BadUserInput(formatted_error),
+        ServiceError::TooManyDoses => BadUserInput(formatted_error),
         ServiceError::ItemNotFound => BadUserInput(formatted_error),
         ServiceError::ItemDoesNotMatchStockLine => BadUserInput(formatted_error),
+        ServiceError::StockLineReservationNotFound => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::NewlyCreatedLineDoesNotExist => InternalError(formatted_error),
     };
@@ -574,9 +586,11 @@ mod test {
                     item_id: "item input".to_string(),
                     stock_line_id: "stock line input".to_string(),
                     number_of_packs: 1,
+                    number_of_doses: None,
                     total_before_tax: 1.1,
                     total_after_tax: 2.2,
-                    tax: Some(5.0)
+                    tax: Some(5.0),
+                    stock_line_reservation_id: None,
                 }
             );
             Ok(InvoiceLine {
@@ -29,6 +29,8 @@ pub struct UpdateInput {
     item_id: Option<String>,
     stock_line_id: Option<String>,
     number_of_packs: Option<u32>,
+    /// Quantity in doses, number of packs is calculated from it and can't be provided as well
+    number_of_doses: Option<u32>,
     total_before_tax: Option<f64>,
     total_after_tax: Option<f64>,
     tax: Option<TaxUpdate>,
@@ -98,6 +100,7 @@ impl UpdateInput {
             item_id,
             stock_line_id,
             number_of_packs,
+            number_of_doses,
             total_before_tax,
             total_after_tax,
             tax,
@@ -108,6 +111,7 @@ impl UpdateInput {
             item_id,
             stock_line_id,
             number_of_packs,
+            number_of_doses,
             total_before_tax,
             total_after_tax,
             tax: tax.map(|tax| ShipmentTaxUpdate {
@@ -174,6 +178,8 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         ServiceError::NotThisStoreInvoice => BadUserInput(formatted_error),
         ServiceError::NotAnOutboundShipment => BadUserInput(formatted_error),
         ServiceError::NumberOfPacksBelowOne => BadUserInput(formatted_error),
+        ServiceError::CannotProvideNumberOfPacksAndDoses => BadUserInput(formatted_error),
+        ServiceError::TooManyDoses => BadUserInput(formatted_error),
         ServiceError::ItemNotFound => BadUserInput(formatted_error),
         ServiceError::ItemDoesNotMatchStockLine => BadUserInput(formatted_error),
         ServiceError::NotThisInvoiceLine(_) => BadUserInput(formatted_error),
@@ -591,6 +597,7 @@ mod test {
                     item_id: Some("item_id input".to_string()),
                     stock_line_id: Some("stock_line_id input".to_string()),
                     number_of_packs: Some(1),
+                    number_of_doses: None,
                     total_before_tax: Some(1.0),
                     total_after_tax: Some(1.0),
                     tax: Some(ShipmentTaxUpdate {
@@ -39,6 +39,7 @@ pub struct ResponseNode {
     deletes: Vec<DeleteResponse>,
     skipped_expired_stock_lines: StockLineConnector,
     skipped_on_hold_stock_lines: StockLineConnector,
+    skipped_unusable_vvm_stock_lines: StockLineConnector,
     issued_expiring_soon_stock_lines: StockLineConnector,
 }
 
@@ -101,6 +102,7 @@ impl ResponseNode {
             inserts,
             skipped_expired_stock_lines,
             skipped_on_hold_stock_lines,
+            skipped_unusable_vvm_stock_lines,
             issued_expiring_soon_stock_lines,
         } = from;
         ResponseNode {
@@ -109,6 +111,9 @@ impl ResponseNode {
             inserts: InvoiceLineConnector::from_vec(inserts),
             skipped_expired_stock_lines: StockLineConnector::from_vec(skipped_expired_stock_lines),
             skipped_on_hold_stock_lines: StockLineConnector::from_vec(skipped_on_hold_stock_lines),
+            skipped_unusable_vvm_stock_lines: StockLineConnector::from_vec(
+                skipped_unusable_vvm_stock_lines,
+            ),
             issued_expiring_soon_stock_lines: StockLineConnector::from_vec(
                 issued_expiring_soon_stock_lines,
             ),
@@ -284,6 +289,11 @@ mod graphql {
                             id
                         }
                     }
+                    skippedUnusableVvmStockLines {
+                        nodes {
+                            id
+                        }
+                    }
                     issuedExpiringSoonStockLines {
                         nodes {
                             id
@@ -319,6 +329,9 @@ mod graphql {
                 skipped_on_hold_stock_lines: vec![inline_init(|r: &mut StockLine| {
                     r.stock_line_row.id = "skipped_on_hold".to_string();
                 })],
+                skipped_unusable_vvm_stock_lines: vec![inline_init(|r: &mut StockLine| {
+                    r.stock_line_row.id = "skipped_unusable_vvm".to_string();
+                })],
                 issued_expiring_soon_stock_lines: vec![inline_init(|r: &mut StockLine| {
                     r.stock_line_row.id = "expiring_soon".to_string();
                 })],
@@ -352,6 +365,11 @@ mod graphql {
                         "id": "skipped_on_hold"
                     }]
                 },
+                "skippedUnusableVvmStockLines": {
+                    "nodes": [{
+                        "id": "skipped_unusable_vvm"
+                    }]
+                },
                 "issuedExpiringSoonStockLines": {
                     "nodes": [{
                         "id": "expiring_soon"
@@ -16,6 +16,7 @@ use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
 use graphql_batch_mutations::BatchMutations;
 use graphql_core::loader::LoaderRegistry;
 use graphql_core::{auth_data_from_request, RequestUserData, SelfRequest};
+use graphql_cycle_count::{CycleCountMutations, CycleCountQueries};
 use graphql_general::{
     GeneralQueries, ServerAdminMutations, ServerAdminQueries, ServerAdminStage0Mutations,
     ServerAdminStage0Queries,
@@ -23,17 +24,24 @@ use graphql_general::{
 use graphql_invoice::{InvoiceMutations, InvoiceQueries};
 use graphql_invoice_line::InvoiceLineMutations;
 use graphql_location::{LocationMutations, LocationQueries};
+use graphql_open_vial_wastage::{OpenVialWastageMutations, OpenVialWastageQueries};
 use graphql_reports::ReportQueries;
 use graphql_requisition::{RequisitionMutations, RequisitionQueries};
 use graphql_requisition_line::RequisitionLineMutations;
+use graphql_serial_number::{SerialNumberMutations, SerialNumberQueries};
+use graphql_stock_line_reservation::{
+    StockLineReservationMutations, StockLineReservationQueries,
+};
 use graphql_stocktake::{StocktakeMutations, StocktakeQueries};
 use graphql_stocktake_line::StocktakeLineMutations;
+use graphql_vvm_status::{VvmStatusMutations, VvmStatusQueries};
 
 use log::info;
 use repository::StorageConnectionManager;
 use service::auth_data::AuthData;
 use service::service_provider::ServiceProvider;
 use service::settings::Settings;
+use service::sync::SyncSenderActor;
 use tokio::sync::mpsc::Sender;
 
 #[derive(MergedObject, Default, Clone)]
@@ -44,7 +52,12 @@ pub struct FullQuery(
     pub GeneralQueries,
     pub RequisitionQueries,
     pub ReportQueries,
+    pub SerialNumberQueries,
     pub ServerAdminQueries,
+    pub VvmStatusQueries,
+    pub OpenVialWastageQueries,
+    pub CycleCountQueries,
+    pub StockLineReservationQueries,
 );
 
 #[derive(MergedObject, Default, Clone)]
@@ -57,7 +70,12 @@ pub struct FullMutation(
     pub BatchMutations,
     pub RequisitionMutations,
     pub RequisitionLineMutations,
+    pub SerialNumberMutations,
     pub ServerAdminMutations,
+    pub VvmStatusMutations,
+    pub OpenVialWastageMutations,
+    pub CycleCountMutations,
+    pub StockLineReservationMutations,
 );
 
 pub type Schema = async_graphql::Schema<FullQuery, FullMutation, async_graphql::EmptySubscription>;
@@ -71,7 +89,12 @@ pub fn full_query() -> FullQuery {
         GeneralQueries,
         RequisitionQueries,
         ReportQueries,
+        SerialNumberQueries,
         ServerAdminQueries,
+        VvmStatusQueries,
+        OpenVialWastageQueries,
+        CycleCountQueries,
+        StockLineReservationQueries,
     )
 }
 
@@ -85,7 +108,12 @@ pub fn full_mutation() -> FullMutation {
         BatchMutations,
         RequisitionMutations,
         RequisitionLineMutations,
+        SerialNumberMutations,
         ServerAdminMutations,
+        VvmStatusMutations,
+        OpenVialWastageMutations,
+        CycleCountMutations,
+        StockLineReservationMutations,
     )
 }
 
@@ -124,6 +152,7 @@ pub fn build_schema(
     auth_data: Data<AuthData>,
     settings_data: Data<Settings>,
     restart_switch: Data<Sender<bool>>,
+    sync_sender: Data<SyncSenderActor>,
     self_request: Option<Data<Box<dyn SelfRequest>>>,
     include_logger: bool,
 ) -> Schema {
@@ -133,7 +162,8 @@ pub fn build_schema(
         .data(service_provider)
         .data(auth_data)
         .data(settings_data)
-        .data(restart_switch);
+        .data(restart_switch)
+        .data(sync_sender);
 
     match self_request {
         Some(self_request) => builder = builder.data(self_request),
@@ -235,6 +265,7 @@ pub fn config(
     auth_data: Data<AuthData>,
     settings_data: Data<Settings>,
     restart_switch: Data<Sender<bool>>,
+    sync_sender: Data<SyncSenderActor>,
 ) -> impl FnOnce(&mut actix_web::web::ServiceConfig) {
     |cfg| {
         let self_requester: Data<Box<dyn SelfRequest>> = Data::new(Box::new(SelfRequestImpl {
@@ -245,6 +276,7 @@ pub fn config(
                 auth_data.clone(),
                 settings_data.clone(),
                 restart_switch.clone(),
+                sync_sender.clone(),
                 None,
                 false,
             ),
@@ -257,6 +289,7 @@ pub fn config(
             auth_data,
             settings_data,
             restart_switch,
+            sync_sender,
             Some(self_requester),
             true,
         );
@@ -0,0 +1,94 @@
+mod mutations;
+use self::mutations::*;
+
+use async_graphql::*;
+use graphql_core::{
+    generic_filters::{DatetimeFilterInput, EqualFilterStringInput},
+    pagination::PaginationInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::*;
+use repository::{DatetimeFilter, EqualFilter, OpenVialWastageFilter, PaginationOption};
+use service::auth::{Resource, ResourceAccessRequest};
+
+#[derive(InputObject, Clone)]
+pub struct OpenVialWastageFilterInput {
+    pub id: Option<EqualFilterStringInput>,
+    pub item_id: Option<EqualFilterStringInput>,
+    pub stock_line_id: Option<EqualFilterStringInput>,
+    pub opened_datetime: Option<DatetimeFilterInput>,
+}
+
+impl From<OpenVialWastageFilterInput> for OpenVialWastageFilter {
+    fn from(f: OpenVialWastageFilterInput) -> Self {
+        OpenVialWastageFilter {
+            id: f.id.map(EqualFilter::from),
+            store_id: None,
+            item_id: f.item_id.map(EqualFilter::from),
+            stock_line_id: f.stock_line_id.map(EqualFilter::from),
+            opened_datetime: f.opened_datetime.map(DatetimeFilter::from),
+        }
+    }
+}
+
+#[derive(Union)]
+pub enum OpenVialWastageResponse {
+    Response(OpenVialWastageConnector),
+}
+
+#[derive(Default, Clone)]
+pub struct OpenVialWastageQueries;
+
+#[Object]
+impl OpenVialWastageQueries {
+    /// Doses wasted from opened multi-dose vials, most recently opened first
+    pub async fn open_vial_wastage(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<OpenVialWastageFilterInput>,
+    ) -> Result<OpenVialWastageResponse> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryOpenVialWastage,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let open_vial_wastage = service_provider
+            .open_vial_wastage_service
+            .get_open_vial_wastage(
+                &service_context,
+                &store_id,
+                page.map(PaginationOption::from),
+                filter.map(OpenVialWastageFilter::from),
+            )
+            .map_err(StandardGraphqlError::from_list_error)?;
+
+        Ok(OpenVialWastageResponse::Response(
+            OpenVialWastageConnector::from_domain(open_vial_wastage),
+        ))
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct OpenVialWastageMutations;
+
+#[Object]
+impl OpenVialWastageMutations {
+    /// Record doses discarded after a multi-dose vial was opened and partially used
+    async fn insert_open_vial_wastage(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: InsertOpenVialWastageInput,
+    ) -> Result<OpenVialWastageNode> {
+        insert_open_vial_wastage(ctx, &store_id, input)
+    }
+}
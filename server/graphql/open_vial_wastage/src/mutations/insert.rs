@@ -0,0 +1,92 @@
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::OpenVialWastageNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    open_vial_wastage::insert::{
+        InsertOpenVialWastage, InsertOpenVialWastageError as ServiceError,
+    },
+};
+
+pub fn insert_open_vial_wastage(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertOpenVialWastageInput,
+) -> Result<OpenVialWastageNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateOpenVialWastage,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider
+        .open_vial_wastage_service
+        .insert_open_vial_wastage(&service_context, store_id, input.into())
+    {
+        Ok(open_vial_wastage) => Ok(OpenVialWastageNode::from_domain(open_vial_wastage)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+#[derive(InputObject)]
+pub struct InsertOpenVialWastageInput {
+    pub id: String,
+    pub stock_line_id: String,
+    pub invoice_line_id: Option<String>,
+    pub doses_used: u32,
+    pub doses_wasted: u32,
+    pub opened_datetime: Option<DateTime<Utc>>,
+    pub comment: Option<String>,
+}
+
+impl From<InsertOpenVialWastageInput> for InsertOpenVialWastage {
+    fn from(
+        InsertOpenVialWastageInput {
+            id,
+            stock_line_id,
+            invoice_line_id,
+            doses_used,
+            doses_wasted,
+            opened_datetime,
+            comment,
+        }: InsertOpenVialWastageInput,
+    ) -> Self {
+        InsertOpenVialWastage {
+            id,
+            stock_line_id,
+            invoice_line_id,
+            doses_used,
+            doses_wasted,
+            opened_datetime: opened_datetime.map(|datetime| datetime.naive_utc()),
+            comment,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::OpenVialWastageAlreadyExists => BadUserInput(formatted_error),
+        ServiceError::StockLineDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::NotThisStoreStockLine => BadUserInput(formatted_error),
+        ServiceError::ItemIsNotMultiDose => BadUserInput(formatted_error),
+        ServiceError::DosesExceedVial => BadUserInput(formatted_error),
+        ServiceError::InvoiceLineDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InvoiceLineNotForStockLine => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -19,6 +19,8 @@ use service::{
 pub struct UpdateInput {
     pub id: String,
     pub requested_quantity: Option<u32>,
+    /// For items counted in doses, rounded up to whole units
+    pub requested_quantity_in_doses: Option<u32>,
     pub comment: Option<String>,
 }
 
@@ -80,12 +82,14 @@ impl UpdateInput {
         let UpdateInput {
             id,
             requested_quantity,
+            requested_quantity_in_doses,
             comment,
         } = self;
 
         ServiceInput {
             id,
             requested_quantity,
+            requested_quantity_in_doses,
             comment,
         }
     }
@@ -113,6 +117,8 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         // Standard Graphql Errors
         ServiceError::NotThisStoreRequisition => BadUserInput(formatted_error),
         ServiceError::NotARequestRequisition => BadUserInput(formatted_error),
+        ServiceError::CannotProvideQuantityAndDoses => BadUserInput(formatted_error),
+        ServiceError::TooManyDoses => BadUserInput(formatted_error),
         ServiceError::UpdatedRequisitionLineDoesNotExist => InternalError(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
@@ -330,6 +336,7 @@ mod test {
                 ServiceInput {
                     id: "update line id input".to_string(),
                     requested_quantity: Some(1),
+                    requested_quantity_in_doses: None,
                     comment: Some("comment".to_string())
                 }
             );
@@ -0,0 +1,162 @@
+mod mutations;
+use self::mutations::*;
+
+use async_graphql::*;
+use graphql_core::{
+    generic_filters::{EqualFilterStringInput, SimpleStringFilterInput},
+    map_filter,
+    pagination::PaginationInput,
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::*;
+use repository::{EqualFilter, PaginationOption, SerialNumberFilter, SimpleStringFilter};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    SingleRecordError,
+};
+
+#[derive(InputObject, Clone)]
+pub struct EqualFilterSerialNumberStatusInput {
+    pub equal_to: Option<SerialNumberNodeStatus>,
+    pub equal_any: Option<Vec<SerialNumberNodeStatus>>,
+    pub not_equal_to: Option<SerialNumberNodeStatus>,
+}
+
+#[derive(InputObject, Clone)]
+pub struct SerialNumberFilterInput {
+    pub id: Option<EqualFilterStringInput>,
+    pub item_id: Option<EqualFilterStringInput>,
+    pub serial_number: Option<SimpleStringFilterInput>,
+    pub status: Option<EqualFilterSerialNumberStatusInput>,
+    pub holder_name_id: Option<EqualFilterStringInput>,
+    pub invoice_line_id: Option<EqualFilterStringInput>,
+}
+
+impl From<SerialNumberFilterInput> for SerialNumberFilter {
+    fn from(f: SerialNumberFilterInput) -> Self {
+        SerialNumberFilter {
+            id: f.id.map(EqualFilter::from),
+            item_id: f.item_id.map(EqualFilter::from),
+            store_id: None,
+            serial_number: f.serial_number.map(SimpleStringFilter::from),
+            status: f
+                .status
+                .map(|t| map_filter!(t, SerialNumberNodeStatus::to_domain)),
+            holder_name_id: f.holder_name_id.map(EqualFilter::from),
+            invoice_line_id: f.invoice_line_id.map(EqualFilter::from),
+        }
+    }
+}
+
+#[derive(Union)]
+pub enum SerialNumbersResponse {
+    Response(SerialNumberConnector),
+}
+
+#[derive(Default, Clone)]
+pub struct SerialNumberQueries;
+
+#[Object]
+impl SerialNumberQueries {
+    /// Query serial numbers of equipment units held by the store
+    pub async fn serial_numbers(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        #[graphql(desc = "Pagination option (first and offset)")] page: Option<PaginationInput>,
+        #[graphql(desc = "Filter option")] filter: Option<SerialNumberFilterInput>,
+        #[graphql(desc = "Sort options (only first sort input is evaluated for this endpoint)")]
+        sort: Option<Vec<SerialNumberSortInput>>,
+    ) -> Result<SerialNumbersResponse> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QuerySerialNumber,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let serial_numbers = service_provider
+            .serial_number_service
+            .get_serial_numbers(
+                &service_context,
+                &store_id,
+                page.map(PaginationOption::from),
+                filter.map(SerialNumberFilter::from),
+                // Currently only one sort option is supported, use the first from the list.
+                sort.and_then(|mut sort_list| sort_list.pop())
+                    .map(|sort| sort.to_domain()),
+            )
+            .map_err(StandardGraphqlError::from_list_error)?;
+
+        Ok(SerialNumbersResponse::Response(
+            SerialNumberConnector::from_domain(serial_numbers),
+        ))
+    }
+
+    /// Asset history of a serial number, oldest movement first
+    pub async fn serial_number_history(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<Vec<SerialNumberMovementNode>> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QuerySerialNumber,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let movements = service_provider
+            .serial_number_service
+            .get_serial_number_history(&service_context, &store_id, &id)
+            .map_err(|error| match error {
+                SingleRecordError::DatabaseError(error) => {
+                    StandardGraphqlError::from_repository_error(error)
+                }
+                SingleRecordError::NotFound(_) => {
+                    StandardGraphqlError::BadUserInput(format!("{:#?}", error)).extend()
+                }
+            })?;
+
+        Ok(movements
+            .into_iter()
+            .map(|movement| SerialNumberMovementNode { movement })
+            .collect())
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct SerialNumberMutations;
+
+#[Object]
+impl SerialNumberMutations {
+    /// Record the serial numbers of units received or issued by an inbound or outbound shipment
+    /// line
+    async fn add_invoice_line_serial_numbers(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: AddInvoiceLineSerialNumbersInput,
+    ) -> Result<AddInvoiceLineSerialNumbersResponse> {
+        add_invoice_line_serial_numbers(ctx, &store_id, input)
+    }
+
+    async fn update_serial_number(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpdateSerialNumberInput,
+    ) -> Result<SerialNumberNode> {
+        update_serial_number(ctx, &store_id, input)
+    }
+}
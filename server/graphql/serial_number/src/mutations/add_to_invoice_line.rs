@@ -0,0 +1,134 @@
+use async_graphql::*;
+
+use graphql_core::{
+    simple_generic_errors::{CannotEditInvoice, RecordNotFound},
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::InvoiceLineSerialNumberConnector;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    serial_number::add_to_invoice_line::{
+        AddInvoiceLineSerialNumbers, AddInvoiceLineSerialNumbersError as ServiceError,
+    },
+};
+
+pub fn add_invoice_line_serial_numbers(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: AddInvoiceLineSerialNumbersInput,
+) -> Result<AddInvoiceLineSerialNumbersResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateSerialNumber,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider
+        .serial_number_service
+        .add_invoice_line_serial_numbers(&service_context, store_id, input.into())
+    {
+        Ok(serial_numbers) => Ok(AddInvoiceLineSerialNumbersResponse::Response(
+            InvoiceLineSerialNumberConnector::from_vec(serial_numbers),
+        )),
+        Err(error) => Ok(AddInvoiceLineSerialNumbersResponse::Error(
+            AddInvoiceLineSerialNumbersError {
+                error: map_error(error)?,
+            },
+        )),
+    }
+}
+
+#[derive(InputObject)]
+pub struct AddInvoiceLineSerialNumbersInput {
+    pub invoice_line_id: String,
+    pub serial_numbers: Vec<String>,
+}
+
+impl From<AddInvoiceLineSerialNumbersInput> for AddInvoiceLineSerialNumbers {
+    fn from(
+        AddInvoiceLineSerialNumbersInput {
+            invoice_line_id,
+            serial_numbers,
+        }: AddInvoiceLineSerialNumbersInput,
+    ) -> Self {
+        AddInvoiceLineSerialNumbers {
+            invoice_line_id,
+            serial_numbers,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct AddInvoiceLineSerialNumbersError {
+    pub error: AddInvoiceLineSerialNumbersErrorInterface,
+}
+
+#[derive(Union)]
+pub enum AddInvoiceLineSerialNumbersResponse {
+    Error(AddInvoiceLineSerialNumbersError),
+    Response(InvoiceLineSerialNumberConnector),
+}
+
+#[derive(Interface)]
+#[graphql(field(name = "description", type = "&str"))]
+pub enum AddInvoiceLineSerialNumbersErrorInterface {
+    InvoiceLineNotFound(RecordNotFound),
+    CannotEditInvoice(CannotEditInvoice),
+    SerialNumberNotAvailable(SerialNumberNotAvailable),
+}
+
+pub struct SerialNumberNotAvailable(pub String);
+
+#[Object]
+impl SerialNumberNotAvailable {
+    pub async fn description(&self) -> &'static str {
+        "Serial number is a duplicate, decommissioned, not in store or recorded on another shipment"
+    }
+
+    pub async fn serial_number(&self) -> &str {
+        &self.0
+    }
+}
+
+fn map_error(error: ServiceError) -> Result<AddInvoiceLineSerialNumbersErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        // Structured Errors
+        ServiceError::InvoiceLineDoesNotExist => {
+            return Ok(
+                AddInvoiceLineSerialNumbersErrorInterface::InvoiceLineNotFound(RecordNotFound {}),
+            )
+        }
+        ServiceError::CannotEditFinalised => {
+            return Ok(
+                AddInvoiceLineSerialNumbersErrorInterface::CannotEditInvoice(CannotEditInvoice {}),
+            )
+        }
+        ServiceError::DuplicateSerialNumber(serial_number)
+        | ServiceError::SerialNumberDecommissioned(serial_number)
+        | ServiceError::SerialNumberNotAvailable(serial_number) => {
+            return Ok(
+                AddInvoiceLineSerialNumbersErrorInterface::SerialNumberNotAvailable(
+                    SerialNumberNotAvailable(serial_number),
+                ),
+            )
+        }
+        // Standard Graphql Errors
+        ServiceError::NotThisStoreInvoice => BadUserInput(formatted_error),
+        ServiceError::NotAnInboundOrOutboundShipment => BadUserInput(formatted_error),
+        ServiceError::NotAStockLine => BadUserInput(formatted_error),
+        ServiceError::ItemNotSerialTracked => BadUserInput(formatted_error),
+        ServiceError::TooManySerialNumbers => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
@@ -0,0 +1,5 @@
+mod add_to_invoice_line;
+mod update;
+
+pub use add_to_invoice_line::*;
+pub use update::*;
@@ -0,0 +1,78 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::{SerialNumberNode, SerialNumberNodeStatus};
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    serial_number::update::{UpdateSerialNumber, UpdateSerialNumberError as ServiceError},
+};
+
+pub fn update_serial_number(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateSerialNumberInput,
+) -> Result<SerialNumberNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateSerialNumber,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider.serial_number_service.update_serial_number(
+        &service_context,
+        store_id,
+        input.into(),
+    ) {
+        Ok(serial_number) => Ok(SerialNumberNode::from_domain(serial_number)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+#[derive(InputObject)]
+pub struct UpdateSerialNumberInput {
+    pub id: String,
+    pub status: Option<SerialNumberNodeStatus>,
+    pub holder_name_id: Option<String>,
+    pub note: Option<String>,
+}
+
+impl From<UpdateSerialNumberInput> for UpdateSerialNumber {
+    fn from(
+        UpdateSerialNumberInput {
+            id,
+            status,
+            holder_name_id,
+            note,
+        }: UpdateSerialNumberInput,
+    ) -> Self {
+        UpdateSerialNumber {
+            id,
+            status: status.map(SerialNumberNodeStatus::to_domain),
+            holder_name_id,
+            note,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::SerialNumberDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::NotThisStoreSerialNumber => BadUserInput(formatted_error),
+        ServiceError::HolderDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::UpdatedRecordNotFound => InternalError(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
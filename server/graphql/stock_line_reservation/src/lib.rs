@@ -0,0 +1,71 @@
+mod mutations;
+use self::mutations::*;
+
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::*;
+use service::auth::{Resource, ResourceAccessRequest};
+
+#[derive(Default, Clone)]
+pub struct StockLineReservationQueries;
+
+#[Object]
+impl StockLineReservationQueries {
+    /// Stock line reservations of the store that are still held, earliest reserved until first
+    pub async fn stock_line_reservations(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<StockLineReservationNode>> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryStockLineReservation,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let reservations = service_provider
+            .stock_line_reservation_service
+            .get_stock_line_reservations(&service_context, &store_id)
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(reservations
+            .into_iter()
+            .map(StockLineReservationNode::from_domain)
+            .collect())
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct StockLineReservationMutations;
+
+#[Object]
+impl StockLineReservationMutations {
+    /// Hold packs of a stock line for a customer or campaign, the held packs are no longer available
+    /// to anyone else
+    async fn insert_stock_line_reservation(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: InsertStockLineReservationInput,
+    ) -> Result<StockLineReservationNode> {
+        insert_stock_line_reservation(ctx, &store_id, input)
+    }
+
+    /// Return the packs still held by a reservation to the available stock
+    async fn release_stock_line_reservation(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        id: String,
+    ) -> Result<StockLineReservationNode> {
+        release_stock_line_reservation(ctx, &store_id, &id)
+    }
+}
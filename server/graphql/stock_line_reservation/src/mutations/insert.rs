@@ -0,0 +1,92 @@
+use async_graphql::*;
+use chrono::NaiveDate;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StockLineReservationNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stock_line_reservation::insert::{
+        InsertStockLineReservation, InsertStockLineReservationError as ServiceError,
+    },
+};
+
+pub fn insert_stock_line_reservation(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: InsertStockLineReservationInput,
+) -> Result<StockLineReservationNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLineReservation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider
+        .stock_line_reservation_service
+        .insert_stock_line_reservation(&service_context, store_id, input.into())
+    {
+        Ok(reservation) => Ok(StockLineReservationNode::from_domain(reservation)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+#[derive(InputObject)]
+pub struct InsertStockLineReservationInput {
+    pub id: String,
+    pub stock_line_id: String,
+    /// Customer to reserve the stock for, either this or campaign must be set
+    pub name_id: Option<String>,
+    /// Campaign or programme to reserve the stock for
+    pub campaign: Option<String>,
+    pub number_of_packs: u32,
+    /// Last day the stock is held
+    pub reserved_until: NaiveDate,
+}
+
+impl From<InsertStockLineReservationInput> for InsertStockLineReservation {
+    fn from(
+        InsertStockLineReservationInput {
+            id,
+            stock_line_id,
+            name_id,
+            campaign,
+            number_of_packs,
+            reserved_until,
+        }: InsertStockLineReservationInput,
+    ) -> Self {
+        InsertStockLineReservation {
+            id,
+            stock_line_id,
+            name_id,
+            campaign,
+            number_of_packs,
+            reserved_until,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::ReservationAlreadyExists => BadUserInput(formatted_error),
+        ServiceError::StockLineDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::NotThisStoreStockLine => BadUserInput(formatted_error),
+        ServiceError::NameOrCampaignRequired => BadUserInput(formatted_error),
+        ServiceError::NameDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::NumberOfPacksMustBePositive => BadUserInput(formatted_error),
+        ServiceError::ReductionBelowZero => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
@@ -0,0 +1,5 @@
+mod insert;
+mod release;
+
+pub use insert::*;
+pub use release::*;
@@ -0,0 +1,50 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::StockLineReservationNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stock_line_reservation::release::ReleaseStockLineReservationError as ServiceError,
+};
+
+pub fn release_stock_line_reservation(
+    ctx: &Context<'_>,
+    store_id: &str,
+    id: &str,
+) -> Result<StockLineReservationNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateStockLineReservation,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider
+        .stock_line_reservation_service
+        .release_stock_line_reservation(&service_context, store_id, id)
+    {
+        Ok(reservation) => Ok(StockLineReservationNode::from_domain(reservation)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::ReservationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::NotThisStoreReservation => BadUserInput(formatted_error),
+        ServiceError::ReservationAlreadyReleased => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
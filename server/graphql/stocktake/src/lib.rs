@@ -1,6 +1,8 @@
 pub mod mutations;
 mod stocktake_queries;
 use self::stocktake_queries::*;
+mod stocktake_variance;
+use self::stocktake_variance::*;
 use async_graphql::*;
 use graphql_core::pagination::PaginationInput;
 
@@ -37,6 +39,16 @@ impl StocktakeQueries {
     ) -> Result<StocktakesResponse> {
         stocktakes(ctx, &store_id, page, filter, sort)
     }
+
+    /// Per line and total variance of a stocktake, used to review it before finalising
+    pub async fn stocktake_variance(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        stocktake_id: String,
+    ) -> Result<StocktakeVarianceResponse> {
+        stocktake_variance(ctx, &store_id, &stocktake_id)
+    }
 }
 
 #[derive(Default, Clone)]
@@ -70,4 +82,15 @@ impl StocktakeMutations {
     ) -> Result<mutations::DeleteResponse> {
         mutations::delete(ctx, &store_id, input)
     }
+
+    /// Approve the variance of a stocktake in review, required before finalising when the
+    /// variance exceeds the stocktake's approval threshold
+    async fn approve_stocktake(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: mutations::ApproveInput,
+    ) -> Result<mutations::ApproveResponse> {
+        mutations::approve(ctx, &store_id, input)
+    }
 }
@@ -0,0 +1,94 @@
+use async_graphql::*;
+
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::StocktakeNode;
+use repository::Stocktake;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    stocktake::ApproveStocktakeError as ServiceError,
+};
+
+#[derive(InputObject)]
+#[graphql(name = "ApproveStocktakeInput")]
+pub struct ApproveInput {
+    pub id: String,
+}
+
+pub struct StocktakeNotInReview;
+#[Object]
+impl StocktakeNotInReview {
+    pub async fn description(&self) -> &'static str {
+        "Only stocktakes in review can be approved"
+    }
+}
+
+#[derive(Interface)]
+#[graphql(name = "ApproveStocktakeErrorInterface")]
+#[graphql(field(name = "description", type = "String"))]
+pub enum ApproveErrorInterface {
+    StocktakeNotInReview(StocktakeNotInReview),
+}
+
+#[derive(SimpleObject)]
+#[graphql(name = "ApproveStocktakeError")]
+pub struct ApproveError {
+    pub error: ApproveErrorInterface,
+}
+
+#[derive(Union)]
+#[graphql(name = "ApproveStocktakeResponse")]
+pub enum ApproveResponse {
+    Error(ApproveError),
+    Response(StocktakeNode),
+}
+
+pub fn approve(ctx: &Context<'_>, store_id: &str, input: ApproveInput) -> Result<ApproveResponse> {
+    let user = validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::ApproveStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+    map_response(service_provider.stocktake_service.approve_stocktake(
+        &service_context,
+        store_id,
+        &user.user_id,
+        &input.id,
+    ))
+}
+
+pub fn map_response(from: Result<Stocktake, ServiceError>) -> Result<ApproveResponse> {
+    let result = match from {
+        Ok(stocktake) => ApproveResponse::Response(StocktakeNode::from_domain(stocktake)),
+        Err(error) => ApproveResponse::Error(ApproveError {
+            error: map_error(error)?,
+        }),
+    };
+
+    Ok(result)
+}
+
+fn map_error(err: ServiceError) -> Result<ApproveErrorInterface> {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", err);
+    let graphql_error = match err {
+        // Structured Errors
+        ServiceError::StocktakeNotInReview => {
+            return Ok(ApproveErrorInterface::StocktakeNotInReview(
+                StocktakeNotInReview {},
+            ))
+        }
+        // Standard Graphql Errors
+        ServiceError::InvalidStore => BadUserInput(formatted_error),
+        ServiceError::StocktakeDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InternalError(err) => InternalError(err),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
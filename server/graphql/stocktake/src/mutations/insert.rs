@@ -8,7 +8,10 @@ use graphql_types::types::StocktakeNode;
 use repository::Stocktake;
 use service::{
     auth::{Resource, ResourceAccessRequest},
-    stocktake::{InsertStocktake as ServiceInput, InsertStocktakeError as ServiceError},
+    stocktake::{
+        InsertStocktake as ServiceInput, InsertStocktakeError as ServiceError,
+        StocktakeLineCriteria,
+    },
 };
 
 #[derive(InputObject)]
@@ -19,6 +22,22 @@ pub struct InsertInput {
     pub description: Option<String>,
     pub is_locked: Option<bool>,
     pub stocktake_date: Option<NaiveDate>,
+    /// Hide snapshot quantities from counters
+    pub is_blind_count: Option<bool>,
+    /// Number of packs independent counts of a line may differ by before a recount is required
+    pub recount_tolerance: Option<u32>,
+    /// Variance cost value above which the stocktake has to be approved before finalising
+    pub variance_approval_threshold: Option<f64>,
+    /// Add lines for all stock in this location
+    pub location_id: Option<String>,
+    /// Add lines for items on this master list
+    pub master_list_id: Option<String>,
+    /// Add lines for stock expiring before this date
+    pub expires_before: Option<NaiveDate>,
+    /// Add lines for visible items that have no stock
+    pub include_zero_stock_items: Option<bool>,
+    /// Add lines for all stock of this many randomly selected items
+    pub random_item_sample_size: Option<u32>,
 }
 
 #[derive(Union)]
@@ -71,6 +90,8 @@ pub fn map_response(from: Result<Stocktake, ServiceError>) -> Result<InsertRespo
             let graphql_error = match error {
                 ServiceError::InvalidStore => BadUserInput(formatted_error),
                 ServiceError::StocktakeAlreadyExists => BadUserInput(formatted_error),
+                ServiceError::InvalidLocation => BadUserInput(formatted_error),
+                ServiceError::MasterListNotFoundForThisStore => BadUserInput(formatted_error),
                 ServiceError::InternalError(err) => InternalError(err),
                 ServiceError::DatabaseError(_) => InternalError(formatted_error),
             };
@@ -88,6 +109,14 @@ impl InsertInput {
             description,
             stocktake_date,
             is_locked,
+            is_blind_count,
+            recount_tolerance,
+            variance_approval_threshold,
+            location_id,
+            master_list_id,
+            expires_before,
+            include_zero_stock_items,
+            random_item_sample_size,
         } = self;
 
         ServiceInput {
@@ -96,6 +125,18 @@ impl InsertInput {
             description,
             stocktake_date,
             is_locked,
+            is_blind_count,
+            recount_tolerance,
+            variance_approval_threshold,
+            criteria: StocktakeLineCriteria {
+                location_id,
+                master_list_id,
+                expires_before,
+                include_zero_stock_items: include_zero_stock_items.unwrap_or(false),
+                random_item_sample_size,
+                // Only used by cycle count scheduling
+                item_ids: None,
+            },
         }
     }
 }
@@ -110,7 +151,7 @@ mod test {
     use service::{
         service_provider::{ServiceContext, ServiceProvider},
         stocktake::{
-            StocktakeServiceTrait, {InsertStocktake, InsertStocktakeError},
+            StocktakeLineCriteria, StocktakeServiceTrait, {InsertStocktake, InsertStocktakeError},
         },
     };
     use util::inline_init;
@@ -171,7 +212,18 @@ mod test {
                     comment: Some("comment".to_string()),
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd(2022, 01, 03)),
-                    is_locked: Some(true)
+                    is_locked: Some(true),
+                    is_blind_count: Some(true),
+                    recount_tolerance: Some(2),
+                    variance_approval_threshold: Some(100.0),
+                    criteria: StocktakeLineCriteria {
+                        location_id: Some("location id".to_string()),
+                        master_list_id: None,
+                        expires_before: Some(NaiveDate::from_ymd(2022, 06, 01)),
+                        include_zero_stock_items: false,
+                        random_item_sample_size: Some(5),
+                        item_ids: None,
+                    }
                 }
             );
             // StocktakeNode result is checked in queries
@@ -184,7 +236,13 @@ mod test {
               "comment": "comment",
               "description": "description",
               "stocktakeDate": "2022-01-03",
-              "isLocked": true
+              "isLocked": true,
+              "isBlindCount": true,
+              "recountTolerance": 2,
+              "varianceApprovalThreshold": 100.0,
+              "locationId": "location id",
+              "expiresBefore": "2022-06-01",
+              "randomItemSampleSize": 5
             }
         }));
         let expected = json!({
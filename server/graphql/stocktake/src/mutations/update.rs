@@ -20,6 +20,11 @@ pub struct UpdateInput {
     pub status: Option<StocktakeNodeStatus>,
     pub stocktake_date: Option<NaiveDate>,
     pub is_locked: Option<bool>,
+    pub is_blind_count: Option<bool>,
+    pub recount_tolerance: Option<u32>,
+    /// Variance cost value above which the stocktake has to be approved before finalising, requires
+    /// the stocktake approve permission
+    pub variance_approval_threshold: Option<f64>,
 }
 
 pub struct SnapshotCountCurrentCountMismatch(Vec<StocktakeLine>);
@@ -34,11 +39,33 @@ impl SnapshotCountCurrentCountMismatch {
     }
 }
 
+pub struct CountsNotReconciled(Vec<StocktakeLine>);
+#[Object]
+impl CountsNotReconciled {
+    pub async fn description(&self) -> &'static str {
+        "Independent counts of some lines disagree and need a recount"
+    }
+
+    pub async fn lines(&self) -> StocktakeLineConnector {
+        StocktakeLineConnector::from_domain_vec(self.0.clone())
+    }
+}
+
+pub struct VarianceNotApproved;
+#[Object]
+impl VarianceNotApproved {
+    pub async fn description(&self) -> &'static str {
+        "Stocktake variance exceeds the approval threshold and needs to be approved"
+    }
+}
+
 #[derive(Interface)]
 #[graphql(name = "UpdateStocktakeErrorInterface")]
 #[graphql(field(name = "description", type = "String"))]
 pub enum UpdateErrorInterface {
     SnapshotCountCurrentCountMismatch(SnapshotCountCurrentCountMismatch),
+    CountsNotReconciled(CountsNotReconciled),
+    VarianceNotApproved(VarianceNotApproved),
     StocktakeIsLocked(StocktakeIsLocked),
     CannotEditStocktake(CannotEditStocktake),
 }
@@ -64,6 +91,16 @@ pub fn update(ctx: &Context<'_>, store_id: &str, input: UpdateInput) -> Result<U
             store_id: Some(store_id.to_string()),
         },
     )?;
+    // The threshold decides whether the variance needs an approval
+    if input.variance_approval_threshold.is_some() {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::ApproveStocktake,
+                store_id: Some(store_id.to_string()),
+            },
+        )?;
+    }
 
     let service_provider = ctx.service_provider();
     let service_context = service_provider.context()?;
@@ -96,6 +133,16 @@ fn map_error(err: ServiceError) -> Result<UpdateErrorInterface> {
                 SnapshotCountCurrentCountMismatch(lines),
             ))
         }
+        ServiceError::CountsNotReconciled(lines) => {
+            return Ok(UpdateErrorInterface::CountsNotReconciled(
+                CountsNotReconciled(lines),
+            ))
+        }
+        ServiceError::VarianceNotApproved => {
+            return Ok(UpdateErrorInterface::VarianceNotApproved(
+                VarianceNotApproved {},
+            ))
+        }
         ServiceError::StocktakeIsLocked => {
             return Ok(UpdateErrorInterface::StocktakeIsLocked(
                 StocktakeIsLocked {},
@@ -111,6 +158,7 @@ fn map_error(err: ServiceError) -> Result<UpdateErrorInterface> {
         ServiceError::InvalidStore => BadUserInput(formatted_error),
         ServiceError::StocktakeDoesNotExist => BadUserInput(formatted_error),
         ServiceError::NoLines => BadUserInput(formatted_error),
+        ServiceError::CannotChangeApprovalThreshold => BadUserInput(formatted_error),
         ServiceError::InternalError(err) => InternalError(err),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
     };
@@ -127,6 +175,9 @@ impl UpdateInput {
             status,
             is_locked,
             stocktake_date,
+            is_blind_count,
+            recount_tolerance,
+            variance_approval_threshold,
         } = self;
 
         ServiceInput {
@@ -136,6 +187,9 @@ impl UpdateInput {
             status: status.map(|status| status.to_domain()),
             is_locked,
             stocktake_date,
+            is_blind_count,
+            recount_tolerance,
+            variance_approval_threshold,
         }
     }
 }
@@ -258,6 +312,27 @@ mod graphql {
             Some(service_provider(test_service, &connection_manager))
         );
 
+        // VarianceNotApproved
+        let test_service = TestService(Box::new(|_, _, _| {
+            Err(UpdateStocktakeError::VarianceNotApproved)
+        }));
+
+        let expected = json!({
+            "updateStocktake": {
+              "error": {
+                "__typename": "VarianceNotApproved"
+              }
+            }
+          }
+        );
+        assert_graphql_query!(
+            &settings,
+            query,
+            &variables,
+            &expected,
+            Some(service_provider(test_service, &connection_manager))
+        );
+
         // success
         let test_service = TestService(Box::new(|_, _, _| {
             Ok(StocktakeRow {
@@ -273,6 +348,11 @@ mod graphql {
                 finalised_datetime: Some(NaiveDate::from_ymd(2022, 1, 23).and_hms(15, 16, 0)),
                 inventory_adjustment_id: Some("inv id".to_string()),
                 is_locked: false,
+                is_blind_count: false,
+                recount_tolerance: 0,
+                variance_approval_threshold: None,
+                approved_user_id: None,
+                approved_datetime: None,
             })
         }));
 
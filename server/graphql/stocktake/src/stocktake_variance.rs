@@ -0,0 +1,65 @@
+use async_graphql::*;
+use graphql_core::simple_generic_errors::{
+    ErrorWrapper, NodeError, NodeErrorInterface, RecordNotFound,
+};
+use graphql_core::standard_graphql_error::{validate_auth, StandardGraphqlError};
+use graphql_core::ContextExt;
+use graphql_types::types::StocktakeVarianceNode;
+use service::auth::{Resource, ResourceAccessRequest};
+use service::stocktake::{GetStocktakeVarianceError, StocktakeVariance};
+
+#[derive(Union)]
+pub enum StocktakeVarianceResponse {
+    Response(StocktakeVarianceNode),
+    Error(NodeError),
+}
+
+pub fn stocktake_variance(
+    ctx: &Context<'_>,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<StocktakeVarianceResponse> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::QueryStocktake,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_ctx = service_provider.context()?;
+
+    map_response(service_provider.stocktake_service.get_stocktake_variance(
+        &service_ctx,
+        store_id,
+        stocktake_id,
+    ))
+}
+
+fn map_response(
+    from: Result<StocktakeVariance, GetStocktakeVarianceError>,
+) -> Result<StocktakeVarianceResponse> {
+    let error = match from {
+        Ok(variance) => {
+            return Ok(StocktakeVarianceResponse::Response(
+                StocktakeVarianceNode::from_domain(variance),
+            ))
+        }
+        Err(error) => error,
+    };
+
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+    let graphql_error = match error {
+        GetStocktakeVarianceError::StocktakeDoesNotExist => {
+            return Ok(StocktakeVarianceResponse::Error(ErrorWrapper {
+                error: NodeErrorInterface::RecordNotFound(RecordNotFound {}),
+            }))
+        }
+        GetStocktakeVarianceError::InvalidStore => BadUserInput(formatted_error),
+        GetStocktakeVarianceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    Err(graphql_error.extend())
+}
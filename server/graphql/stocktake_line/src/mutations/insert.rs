@@ -29,6 +29,9 @@ pub struct InsertInput {
     pub cost_price_per_pack: Option<f64>,
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 #[derive(Union)]
@@ -99,6 +102,9 @@ fn map_error(error: ServiceError) -> Result<InsertErrorInterface> {
         ServiceError::StockLineDoesNotExist => BadUserInput(formatted_error),
         ServiceError::StockLineAlreadyExistsInStocktake => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::DonorDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::ManufacturerDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::VvmStatusDoesNotExist => BadUserInput(formatted_error),
         ServiceError::StocktakeIsLocked => BadUserInput(formatted_error),
         ServiceError::StockLineXOrItem => BadUserInput(format!(
             "Either a stock line id or item id must be set (not both), {}",
@@ -128,6 +134,9 @@ impl InsertInput {
             cost_price_per_pack,
             sell_price_per_pack,
             note,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         } = self;
 
         ServiceInput {
@@ -144,6 +153,9 @@ impl InsertInput {
             cost_price_per_pack,
             sell_price_per_pack,
             note,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         }
     }
 }
@@ -267,6 +279,10 @@ mod test {
                     cost_price_per_pack: Some(10.0),
                     sell_price_per_pack: Some(12.0),
                     note: Some("note".to_string()),
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
+                    needs_recount: false,
                 },
                 stock_line: Some(mock_stock_line_a()),
                 location: Some(mock_location_1()),
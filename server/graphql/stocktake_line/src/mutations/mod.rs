@@ -6,3 +6,6 @@ pub use delete::*;
 
 pub mod update;
 pub use update::*;
+
+pub mod count;
+pub use count::*;
@@ -27,6 +27,9 @@ pub struct UpdateInput {
     pub cost_price_per_pack: Option<f64>,
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 #[derive(Union)]
@@ -92,6 +95,9 @@ impl UpdateInput {
             cost_price_per_pack,
             sell_price_per_pack,
             note,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         } = self;
 
         ServiceInput {
@@ -106,6 +112,9 @@ impl UpdateInput {
             cost_price_per_pack,
             sell_price_per_pack,
             note,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         }
     }
 }
@@ -126,6 +135,9 @@ fn map_error(error: ServiceError) -> Result<UpdateErrorInterface> {
         ServiceError::InvalidStore => BadUserInput(formatted_error),
         ServiceError::StocktakeLineDoesNotExist => BadUserInput(formatted_error),
         ServiceError::LocationDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::DonorDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::ManufacturerDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::VvmStatusDoesNotExist => BadUserInput(formatted_error),
         ServiceError::StocktakeIsLocked => BadUserInput(formatted_error),
         ServiceError::DatabaseError(_) => InternalError(formatted_error),
         ServiceError::InternalError(err) => InternalError(err),
@@ -251,6 +263,10 @@ mod test {
                     cost_price_per_pack: Some(10.0),
                     sell_price_per_pack: Some(12.0),
                     note: Some("note".to_string()),
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
+                    needs_recount: false,
                 },
                 stock_line: Some(mock_stock_line_a()),
                 location: Some(mock_location_1()),
@@ -15,15 +15,22 @@ mod permission_tests {
     // lib.rs. As a workaround these defs are copied here. Hopefully this should be possible but I
     // gave up on this for now.
     use graphql_batch_mutations::BatchMutations;
+    use graphql_cycle_count::{CycleCountMutations, CycleCountQueries};
     use graphql_general::{GeneralQueries, ServerAdminMutations, ServerAdminQueries};
     use graphql_invoice::{InvoiceMutations, InvoiceQueries};
     use graphql_invoice_line::InvoiceLineMutations;
     use graphql_location::{LocationMutations, LocationQueries};
+    use graphql_open_vial_wastage::{OpenVialWastageMutations, OpenVialWastageQueries};
     use graphql_reports::ReportQueries;
     use graphql_requisition::{RequisitionMutations, RequisitionQueries};
     use graphql_requisition_line::RequisitionLineMutations;
+    use graphql_serial_number::{SerialNumberMutations, SerialNumberQueries};
+    use graphql_stock_line_reservation::{
+        StockLineReservationMutations, StockLineReservationQueries,
+    };
     use graphql_stocktake::{StocktakeMutations, StocktakeQueries};
     use graphql_stocktake_line::StocktakeLineMutations;
+    use graphql_vvm_status::{VvmStatusMutations, VvmStatusQueries};
 
     #[derive(MergedObject, Default, Clone)]
     pub struct FullQuery(
@@ -33,7 +40,12 @@ mod permission_tests {
         pub GeneralQueries,
         pub RequisitionQueries,
         pub ReportQueries,
+        pub SerialNumberQueries,
         pub ServerAdminQueries,
+        pub VvmStatusQueries,
+        pub OpenVialWastageQueries,
+        pub CycleCountQueries,
+        pub StockLineReservationQueries,
     );
 
     #[derive(MergedObject, Default, Clone)]
@@ -46,7 +58,12 @@ mod permission_tests {
         pub BatchMutations,
         pub RequisitionMutations,
         pub RequisitionLineMutations,
+        pub SerialNumberMutations,
         pub ServerAdminMutations,
+        pub VvmStatusMutations,
+        pub OpenVialWastageMutations,
+        pub CycleCountMutations,
+        pub StockLineReservationMutations,
     );
 
     pub fn full_query() -> FullQuery {
@@ -57,7 +74,12 @@ mod permission_tests {
             GeneralQueries,
             RequisitionQueries,
             ReportQueries,
+            SerialNumberQueries,
             ServerAdminQueries,
+            VvmStatusQueries,
+            OpenVialWastageQueries,
+            CycleCountQueries,
+            StockLineReservationQueries,
         )
     }
 
@@ -71,7 +93,12 @@ mod permission_tests {
             BatchMutations,
             RequisitionMutations,
             RequisitionLineMutations,
+            SerialNumberMutations,
             ServerAdminMutations,
+            VvmStatusMutations,
+            OpenVialWastageMutations,
+            CycleCountMutations,
+            StockLineReservationMutations,
         )
     }
 
@@ -89,6 +116,42 @@ mod permission_tests {
 
     fn resource_mapping_query_test_data() -> Vec<TestData> {
         vec![
+            TestData {
+                name: "cycleCountItems",
+                query: r#"query Query {
+                cycleCountItems(storeId: "", planId: "", date: "2022-07-01") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryCycleCount,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "cycleCountPlans",
+                query: r#"query Query {
+                cycleCountPlans(storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryCycleCount,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "historicalStockOnHand",
+                query: r#"query Query {
+                historicalStockOnHand(storeId: "", datetime: "2022-01-01T00:00:00+00:00") {
+                  __typename
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::Report,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "invoice",
                 query: r#"query Query {
@@ -147,6 +210,18 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "itemClassifications",
+                query: r#"query Query {
+                itemClassifications(storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryCycleCount,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "items",
                 query: r#"query Query {
@@ -163,6 +238,34 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "itemCategories",
+                query: r#"query Query {
+                itemCategories(storeId: "") {
+                  nodes {
+                    id
+                  }
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryItems,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "itemDepartments",
+                query: r#"query Query {
+                itemDepartments(storeId: "") {
+                  nodes {
+                    id
+                  }
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryItems,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "locations",
                 query: r#"query Query {
@@ -211,6 +314,22 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "openVialWastage",
+                query: r#"query Query {
+                openVialWastage(storeId: "") {
+                  ... on OpenVialWastageConnector {
+                    nodes {
+                      id
+                    }
+                  }
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryOpenVialWastage,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "printReport",
                 query: r#"query Query {
@@ -257,6 +376,78 @@ mod permission_tests {
                     store_id: None,
                 },
             },
+            TestData {
+                name: "latestSyncStatus",
+                query: r#"query Query {
+                latestSyncStatus {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QuerySyncStatus,
+                    store_id: None,
+                },
+            },
+            TestData {
+                name: "latestSuccessfulSyncStatus",
+                query: r#"query Query {
+                latestSuccessfulSyncStatus {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QuerySyncStatus,
+                    store_id: None,
+                },
+            },
+            TestData {
+                name: "syncHistory",
+                query: r#"query Query {
+                syncHistory {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QuerySyncStatus,
+                    store_id: None,
+                },
+            },
+            TestData {
+                name: "syncConflicts",
+                query: r#"query Query {
+                syncConflicts {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::ServerAdmin,
+                    store_id: None,
+                },
+            },
+            TestData {
+                name: "syncQuarantine",
+                query: r#"query Query {
+                syncQuarantine {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::ServerAdmin,
+                    store_id: None,
+                },
+            },
+            TestData {
+                name: "redistributionSuggestions",
+                query: r#"query Query {
+                redistributionSuggestions(storeId: "") {
+                  stockLineId
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::StockCount,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "requisition",
                 query: r#"query Query {
@@ -318,6 +509,34 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "serialNumberHistory",
+                query: r#"query Query {
+                serialNumberHistory(storeId: "", id: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QuerySerialNumber,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "serialNumbers",
+                query: r#"query Query {
+                serialNumbers(storeId: "") {
+                  ... on SerialNumberConnector {
+                    nodes {
+                      id
+                    }
+                  }
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QuerySerialNumber,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "stockCounts",
                 query: r#"query Query {
@@ -330,6 +549,30 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "stockExpiryBuckets",
+                query: r#"query Query {
+                stockExpiryBuckets(storeId: "") {
+                  bucket
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::StockCount,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "stockLineReservations",
+                query: r#"query Query {
+                stockLineReservations(storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryStockLineReservation,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "stocktake",
                 query: r#"query Query {
@@ -374,6 +617,32 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "stocktakeVariance",
+                query: r#"query Query {
+                stocktakeVariance(stocktakeId: "", storeId: "") {
+                  ... on StocktakeVarianceNode {
+                    stocktakeId
+                  }
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryStocktake,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "storePreference",
+                query: r#"query Query {
+                storePreference(storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryStorePreference,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "stores",
                 query: r#"query Query {
@@ -406,6 +675,30 @@ mod permission_tests {
                     store_id: None,
                 },
             },
+            TestData {
+                name: "vvmStatusLogs",
+                query: r#"query Query {
+                vvmStatusLogs(storeId: "", stockLineId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryVvmStatus,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "vvmStatuses",
+                query: r#"query Query {
+                vvmStatuses(storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::QueryVvmStatus,
+                    store_id: Some("some".to_string()),
+                },
+            },
         ]
     }
 
@@ -427,6 +720,22 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "addInvoiceLineSerialNumbers",
+                query: r#"mutation Mutation {
+                addInvoiceLineSerialNumbers(input: {invoiceLineId: "", serialNumbers: []}, storeId: "") {
+                  ... on InvoiceLineSerialNumberConnector {
+                    nodes {
+                      id
+                    }
+                  }
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateSerialNumber,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "allocateOutboundShipmentUnallocatedLine",
                 query: r#"mutation Mutation {
@@ -444,6 +753,20 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "approveStocktake",
+                query: r#"mutation Mutation {
+                  approveStocktake(input: {id: ""}, storeId: "") {
+                    ... on StocktakeNode {
+                      id
+                    }
+                  }
+                }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::ApproveStocktake,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "batchInboundShipment",
                 query: r#"mutation Mutation {
@@ -500,6 +823,32 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "calculateItemClassifications",
+                query: r#"mutation Mutation {
+                calculateItemClassifications(storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateCycleCount,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "createRedistributionShipment",
+                query: r#"mutation Mutation {
+                createRedistributionShipment(input: {id: "", stockLineId: "", otherPartyId: "", numberOfPacks: 1}, storeId: "") {
+                  ... on InvoiceNode {
+                    id
+                  }
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateOutboundShipment,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "createRequisitionShipment",
                 query: r#"mutation Mutation {
@@ -682,6 +1031,20 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "importStocktakeCounts",
+                query: r#"mutation Mutation {
+                  importStocktakeCounts(input: {stocktakeId: "", file: ""}, storeId: "") {
+                    skippedRows {
+                      rowNumber
+                    }
+                  }
+                }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateStocktake,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "insertInboundShipment",
                 query: r#"mutation Mutation {
@@ -739,6 +1102,18 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "insertOpenVialWastage",
+                query: r#"mutation Mutation {
+                insertOpenVialWastage(input: {id: "", stockLineId: "", dosesUsed: 0, dosesWasted: 0}, storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateOpenVialWastage,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "insertOutboundShipment",
                 query: r#"mutation Mutation {
@@ -823,6 +1198,18 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "insertStockLineReservation",
+                query: r#"mutation Mutation {
+                insertStockLineReservation(input: {id: "", stockLineId: "", numberOfPacks: 0, reservedUntil: "2022-01-01"}, storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateStockLineReservation,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "insertStocktake",
                 query: r#"mutation Mutation {
@@ -851,6 +1238,52 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "manualSync",
+                query: r#"mutation Mutation {
+                manualSync
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::ManualSync,
+                    store_id: None,
+                },
+            },
+            TestData {
+                name: "releaseStockLineReservation",
+                query: r#"mutation Mutation {
+                releaseStockLineReservation(id: "", storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateStockLineReservation,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "exportOfflineSyncFile",
+                query: r#"mutation Mutation {
+                exportOfflineSyncFile {
+                  fileId
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::ServerAdmin,
+                    store_id: None,
+                },
+            },
+            TestData {
+                name: "retrySyncQuarantine",
+                query: r#"mutation Mutation {
+                retrySyncQuarantine {
+                  integratedCount
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::ServerAdmin,
+                    store_id: None,
+                },
+            },
             TestData {
                 name: "supplyRequestedQuantity",
                 query: r#"mutation Mutation {
@@ -909,6 +1342,18 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "updateItemClassification",
+                query: r#"mutation Mutation {
+                updateItemClassification(input: {itemId: ""}, storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateCycleCount,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "updateLocation",
                 query: r#"mutation Mutation {
@@ -1036,6 +1481,30 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "updateSerialNumber",
+                query: r#"mutation Mutation {
+                updateSerialNumber(input: {id: ""}, storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateSerialNumber,
+                    store_id: Some("some".to_string()),
+                },
+            },
+            TestData {
+                name: "updateStockLineVvmStatus",
+                query: r#"mutation Mutation {
+                updateStockLineVvmStatus(input: {stockLineId: "", statusId: ""}, storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateVvmStatus,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "updateServerSettings",
                 query: r#"mutation Mutation {
@@ -1051,6 +1520,18 @@ mod permission_tests {
                     store_id: None,
                 },
             },
+            TestData {
+                name: "updateStorePreference",
+                query: r#"mutation Mutation {
+                updateStorePreference(input: {allocationStrategy: EXPIRY_FIRST}, storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateStorePreference,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "updateStocktake",
                 query: r#"mutation Mutation {
@@ -1079,6 +1560,20 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "insertStocktakeLineCount",
+                query: r#"mutation Mutation {
+                insertStocktakeLineCount(input: {stocktakeLineId: "", countedNumberOfPacks: 0}, storeId: "") {
+                  ... on StocktakeLineNode {
+                    id
+                  }
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateStocktake,
+                    store_id: Some("some".to_string()),
+                },
+            },
             TestData {
                 name: "updateStocktakeLine",
                 query: r#"mutation Mutation {
@@ -1095,6 +1590,18 @@ mod permission_tests {
                     store_id: Some("some".to_string()),
                 },
             },
+            TestData {
+                name: "upsertCycleCountPlan",
+                query: r#"mutation Mutation {
+                upsertCycleCountPlan(input: {id: "", name: "", startDate: "2022-07-01", aIntervalWeeks: 1, bIntervalWeeks: 1, cIntervalWeeks: 1, isActive: true}, storeId: "") {
+                  id
+                }
+              }"#,
+                expected: ResourceAccessRequest {
+                    resource: Resource::MutateCycleCount,
+                    store_id: Some("some".to_string()),
+                },
+            },
         ]
     }
     impl TestService {
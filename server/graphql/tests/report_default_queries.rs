@@ -10,7 +10,7 @@ mod tests {
     use graphql_stocktake::StocktakeQueries;
     use repository::mock::{
         mock_outbound_shipment_a, mock_request_draft_requisition_all_fields, mock_stocktake_a,
-        MockDataInserts,
+        mock_store_a, MockDataInserts,
     };
     use serde_json::json;
     use service::report::{default_queries::get_default_gql_query, definition::DefaultQuery};
@@ -94,5 +94,19 @@ mod tests {
             "dataId": mock_requisition.id,
         }));
         assert_graphql_query!(&settings, &query, &variables, &expected, None);
+
+        // stock on hand
+        let query = get_default_gql_query(DefaultQuery::StockOnHand).query;
+        let mock_store = mock_store_a();
+        let expected = json!({
+          "store": {
+            "id": mock_store.id
+          }
+        });
+        let variables = Some(json!({
+            "storeId": mock_store.id,
+            "dataId": "2022-01-01T00:00:00+00:00",
+        }));
+        assert_graphql_query!(&settings, &query, &variables, &expected, None);
     }
 }
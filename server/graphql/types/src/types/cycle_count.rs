@@ -0,0 +1,171 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{loader::ItemLoader, ContextExt};
+use repository::{AbcClass, CycleCountPlanRow, ItemClassificationRow, VenClass};
+use serde::Serialize;
+
+use super::ItemNode;
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")] // only needed to be comparable in tests
+pub enum AbcClassNode {
+    A,
+    B,
+    C,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")] // only needed to be comparable in tests
+pub enum VenClassNode {
+    V,
+    E,
+    N,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ItemClassificationNode {
+    pub item_classification: ItemClassificationRow,
+}
+
+#[Object]
+impl ItemClassificationNode {
+    pub async fn id(&self) -> &str {
+        &self.item_classification.id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.item_classification.item_id
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<Option<ItemNode>> {
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let result = loader
+            .load_one(self.item_classification.item_id.clone())
+            .await?;
+
+        Ok(result.map(ItemNode::from_domain))
+    }
+
+    pub async fn abc_class(&self) -> AbcClassNode {
+        AbcClassNode::from_domain(&self.item_classification.abc_class)
+    }
+
+    pub async fn ven_class(&self) -> Option<VenClassNode> {
+        self.item_classification
+            .ven_class
+            .as_ref()
+            .map(VenClassNode::from_domain)
+    }
+
+    /// Consumption value over the lookback period of the last calculation
+    pub async fn consumption_value(&self) -> f64 {
+        self.item_classification.consumption_value
+    }
+
+    /// ABC class was set by a user and is kept when recalculating
+    pub async fn is_abc_manual(&self) -> bool {
+        self.item_classification.is_abc_manual
+    }
+
+    pub async fn updated_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.item_classification.updated_datetime, Utc)
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct CycleCountPlanNode {
+    pub cycle_count_plan: CycleCountPlanRow,
+}
+
+#[Object]
+impl CycleCountPlanNode {
+    pub async fn id(&self) -> &str {
+        &self.cycle_count_plan.id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.cycle_count_plan.name
+    }
+
+    /// User that scheduled stocktakes are created for
+    pub async fn user_id(&self) -> &str {
+        &self.cycle_count_plan.user_id
+    }
+
+    /// First day of cycle week 0
+    pub async fn start_date(&self) -> NaiveDate {
+        self.cycle_count_plan.start_date
+    }
+
+    pub async fn a_interval_weeks(&self) -> i32 {
+        self.cycle_count_plan.a_interval_weeks
+    }
+
+    pub async fn b_interval_weeks(&self) -> i32 {
+        self.cycle_count_plan.b_interval_weeks
+    }
+
+    pub async fn c_interval_weeks(&self) -> i32 {
+        self.cycle_count_plan.c_interval_weeks
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.cycle_count_plan.is_active
+    }
+
+    /// Cycle week of the last stocktake created by the scheduler
+    pub async fn last_scheduled_week(&self) -> Option<i32> {
+        self.cycle_count_plan.last_scheduled_week
+    }
+}
+
+impl AbcClassNode {
+    pub fn to_domain(self) -> AbcClass {
+        match self {
+            AbcClassNode::A => AbcClass::A,
+            AbcClassNode::B => AbcClass::B,
+            AbcClassNode::C => AbcClass::C,
+        }
+    }
+
+    pub fn from_domain(class: &AbcClass) -> AbcClassNode {
+        match class {
+            AbcClass::A => AbcClassNode::A,
+            AbcClass::B => AbcClassNode::B,
+            AbcClass::C => AbcClassNode::C,
+        }
+    }
+}
+
+impl VenClassNode {
+    pub fn to_domain(self) -> VenClass {
+        match self {
+            VenClassNode::V => VenClass::V,
+            VenClassNode::E => VenClass::E,
+            VenClassNode::N => VenClass::N,
+        }
+    }
+
+    pub fn from_domain(class: &VenClass) -> VenClassNode {
+        match class {
+            VenClass::V => VenClassNode::V,
+            VenClass::E => VenClassNode::E,
+            VenClass::N => VenClassNode::N,
+        }
+    }
+}
+
+impl ItemClassificationNode {
+    pub fn from_domain(item_classification: ItemClassificationRow) -> ItemClassificationNode {
+        ItemClassificationNode {
+            item_classification,
+        }
+    }
+}
+
+impl CycleCountPlanNode {
+    pub fn from_domain(cycle_count_plan: CycleCountPlanRow) -> CycleCountPlanNode {
+        CycleCountPlanNode { cycle_count_plan }
+    }
+}
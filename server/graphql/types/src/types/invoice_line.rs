@@ -1,16 +1,18 @@
-use super::{ItemNode, LocationNode, PricingNode, StockLineNode};
+use super::{ItemNode, LocationNode, PricingNode, StockLineNode, VvmStatusNode};
 use async_graphql::*;
 use chrono::NaiveDate;
 use dataloader::DataLoader;
 use graphql_core::{
-    loader::{ItemLoader, LocationByIdLoader, StockLineByIdLoader},
+    loader::{
+        ItemLoader, LocationByIdLoader, NameRowLoader, StockLineByIdLoader, VvmStatusRowLoader,
+    },
     simple_generic_errors::NodeError,
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
 };
 use repository::{InvoiceLine, InvoiceLineRow, InvoiceLineRowType};
 use serde::Serialize;
-use service::{usize_to_u32, ListResult};
+use service::{doses::units_to_doses, usize_to_u32, ListResult};
 
 #[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")] // only needed to be comparable in tests
@@ -83,6 +85,13 @@ impl InvoiceLineNode {
     pub async fn number_of_packs(&self) -> i32 {
         self.row().number_of_packs
     }
+
+    /// Number of doses on this line (number_of_packs * pack_size * item doses)
+    pub async fn number_of_doses(&self, ctx: &Context<'_>) -> Result<i32> {
+        let item = self.item(ctx).await?;
+        let units = self.row().number_of_packs * self.row().pack_size;
+        Ok(units_to_doses(units, item.row()))
+    }
     // Batch
     pub async fn batch(&self) -> &Option<String> {
         &self.row().batch
@@ -143,6 +152,57 @@ impl InvoiceLineNode {
 
         Ok(result.map(LocationNode::from_domain))
     }
+    // Donor
+    pub async fn donor_id(&self) -> &Option<String> {
+        &self.row().donor_id
+    }
+    pub async fn donor_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.get_loader::<DataLoader<NameRowLoader>>();
+
+        let donor_id = match &self.row().donor_id {
+            None => return Ok(None),
+            Some(donor_id) => donor_id,
+        };
+
+        let result = loader.load_one(donor_id.clone()).await?;
+
+        Ok(result.map(|name_row| name_row.name))
+    }
+
+    // Manufacturer
+    pub async fn manufacturer_id(&self) -> &Option<String> {
+        &self.row().manufacturer_id
+    }
+    pub async fn manufacturer_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.get_loader::<DataLoader<NameRowLoader>>();
+
+        let manufacturer_id = match &self.row().manufacturer_id {
+            None => return Ok(None),
+            Some(manufacturer_id) => manufacturer_id,
+        };
+
+        let result = loader.load_one(manufacturer_id.clone()).await?;
+
+        Ok(result.map(|name_row| name_row.name))
+    }
+
+    // VVM
+    pub async fn vvm_status_id(&self) -> &Option<String> {
+        &self.row().vvm_status_id
+    }
+
+    pub async fn vvm_status(&self, ctx: &Context<'_>) -> Result<Option<VvmStatusNode>> {
+        let loader = ctx.get_loader::<DataLoader<VvmStatusRowLoader>>();
+
+        let vvm_status_id = match &self.row().vvm_status_id {
+            None => return Ok(None),
+            Some(vvm_status_id) => vvm_status_id,
+        };
+
+        let result = loader.load_one(vvm_status_id.clone()).await?;
+
+        Ok(result.map(VvmStatusNode::from_domain))
+    }
 
     // Other
     pub async fn note(&self) -> &Option<String> {
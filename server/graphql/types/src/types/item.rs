@@ -106,11 +106,11 @@ impl ItemNode {
     }
 
     pub async fn doses(&self) -> i64 {
-        self.legacy_i64("doses")
+        self.row().doses as i64
     }
 
     pub async fn is_vaccine(&self) -> bool {
-        self.legacy_bool("is_vaccine")
+        self.row().is_vaccine
     }
 
     pub async fn default_pack_size(&self) -> i32 {
@@ -118,7 +118,7 @@ impl ItemNode {
     }
 
     pub async fn outer_pack_size(&self) -> i64 {
-        self.legacy_i64("outer_pack_size")
+        self.row().outer_pack_size as i64
     }
 
     pub async fn volume_per_outer_pack(&self) -> f64 {
@@ -126,7 +126,7 @@ impl ItemNode {
     }
 
     pub async fn volume_per_pack(&self) -> f64 {
-        self.legacy_f64("volume_per_pack")
+        self.row().volume_per_pack
     }
 
     pub async fn margin(&self) -> f64 {
@@ -134,15 +134,32 @@ impl ItemNode {
     }
 
     pub async fn weight(&self) -> f64 {
-        self.legacy_f64("weight")
+        self.row().weight
     }
 
-    pub async fn strength(&self) -> String {
-        self.legacy_string("strength")
+    pub async fn strength(&self) -> &str {
+        &self.row().strength
     }
 
-    pub async fn atc_category(&self) -> String {
-        self.legacy_string("atc_category")
+    pub async fn atc_category(&self) -> &str {
+        &self.row().atc_category
+    }
+
+    pub async fn category_id(&self) -> Option<&str> {
+        self.row().category_id.as_deref()
+    }
+
+    pub async fn department_id(&self) -> Option<&str> {
+        self.row().department_id.as_deref()
+    }
+
+    pub async fn essential_drug_list(&self) -> bool {
+        self.row().essential_drug_list
+    }
+
+    /// Serial numbers of the units are recorded on shipments
+    pub async fn is_serial_tracked(&self) -> bool {
+        self.row().is_serial_tracked
     }
 
     pub async fn ddd(&self) -> String {
@@ -284,6 +301,16 @@ mod test {
                 ItemNode {
                     item: inline_init(|r: &mut Item| {
                         r.item_row = inline_init(|r: &mut ItemRow| {
+                            r.doses = 11;
+                            r.is_vaccine = true;
+                            r.outer_pack_size = 10;
+                            r.volume_per_pack = 0.5;
+                            r.weight = 10.5;
+                            r.strength = "1.5mg".to_string();
+                            r.atc_category = "J07BC01".to_string();
+                            r.category_id = Some("category".to_string());
+                            r.essential_drug_list = true;
+                            r.is_serial_tracked = true;
                             r.legacy_record = r#"{
                                 "ID": "AA460A207402434A89B1F6EEAC08DA43",
                                 "item_name": "test_item",
@@ -369,9 +396,13 @@ mod test {
         let expected = json!({
             "testQuery": {
               "__typename": "ItemNode",
-              "atcCategory": "",
+              "atcCategory": "J07BC01",
+              "categoryId": "category",
               "ddd": "0.1",
+              "departmentId": null,
               "doses": 11,
+              "essentialDrugList": true,
+              "isSerialTracked": true,
               "isVaccine": true,
               "margin": 0.3,
               "msupplyUniversalCode": "universal code",
@@ -400,6 +431,10 @@ mod test {
                weight
                strength
                atcCategory
+               categoryId
+               departmentId
+               essentialDrugList
+               isSerialTracked
                ddd
             }
         }
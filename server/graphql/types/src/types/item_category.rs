@@ -0,0 +1,95 @@
+use async_graphql::*;
+use repository::{ItemCategoryRow, ItemDepartmentRow};
+
+#[derive(PartialEq, Debug)]
+pub struct ItemCategoryNode {
+    item_category: ItemCategoryRow,
+}
+
+#[derive(SimpleObject)]
+pub struct ItemCategoryConnector {
+    total_count: u32,
+    nodes: Vec<ItemCategoryNode>,
+}
+
+#[Object]
+impl ItemCategoryNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.row().name
+    }
+
+    /// Null for top level categories
+    pub async fn parent_id(&self) -> Option<&str> {
+        self.row().parent_id.as_deref()
+    }
+}
+
+impl ItemCategoryNode {
+    pub fn from_domain(item_category: ItemCategoryRow) -> Self {
+        ItemCategoryNode { item_category }
+    }
+
+    pub fn row(&self) -> &ItemCategoryRow {
+        &self.item_category
+    }
+}
+
+impl ItemCategoryConnector {
+    pub fn from_vec(categories: Vec<ItemCategoryRow>) -> ItemCategoryConnector {
+        ItemCategoryConnector {
+            total_count: categories.len() as u32,
+            nodes: categories
+                .into_iter()
+                .map(ItemCategoryNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ItemDepartmentNode {
+    item_department: ItemDepartmentRow,
+}
+
+#[derive(SimpleObject)]
+pub struct ItemDepartmentConnector {
+    total_count: u32,
+    nodes: Vec<ItemDepartmentNode>,
+}
+
+#[Object]
+impl ItemDepartmentNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn name(&self) -> &str {
+        &self.row().name
+    }
+}
+
+impl ItemDepartmentNode {
+    pub fn from_domain(item_department: ItemDepartmentRow) -> Self {
+        ItemDepartmentNode { item_department }
+    }
+
+    pub fn row(&self) -> &ItemDepartmentRow {
+        &self.item_department
+    }
+}
+
+impl ItemDepartmentConnector {
+    pub fn from_vec(departments: Vec<ItemDepartmentRow>) -> ItemDepartmentConnector {
+        ItemDepartmentConnector {
+            total_count: departments.len() as u32,
+            nodes: departments
+                .into_iter()
+                .map(ItemDepartmentNode::from_domain)
+                .collect(),
+        }
+    }
+}
@@ -14,6 +14,11 @@ impl ItemStatsNode {
         self.item_stats.available_stock_on_hand
     }
 
+    /// Proportion of doses wasted from opened multi-dose vials
+    pub async fn open_vial_wastage_rate(&self) -> f64 {
+        self.item_stats.open_vial_wastage_rate
+    }
+
     pub async fn available_months_of_stock_on_hand(&self) -> Option<f64> {
         (self.item_stats.average_monthly_consumption != 0.0).then(|| {
             self.item_stats.available_stock_on_hand as f64
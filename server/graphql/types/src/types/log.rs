@@ -37,6 +37,7 @@ pub enum LogNodeType {
     RequisitionDeleted,
     RequisitionStatusSent,
     RequisitionStatusFinalised,
+    StockLineExpiredOnHold,
 }
 
 #[Object]
@@ -119,6 +120,7 @@ impl LogNodeType {
             LogType::RequisitionDeleted => LogNodeType::RequisitionDeleted,
             LogType::RequisitionStatusSent => LogNodeType::RequisitionStatusSent,
             LogType::RequisitionStatusFinalised => LogNodeType::RequisitionStatusFinalised,
+            LogType::StockLineExpiredOnHold => LogNodeType::StockLineExpiredOnHold,
         }
     }
 
@@ -139,6 +141,7 @@ impl LogNodeType {
             LogNodeType::RequisitionDeleted => LogType::RequisitionDeleted,
             LogNodeType::RequisitionStatusSent => LogType::RequisitionStatusSent,
             LogNodeType::RequisitionStatusFinalised => LogType::RequisitionStatusFinalised,
+            LogNodeType::StockLineExpiredOnHold => LogType::StockLineExpiredOnHold,
         }
     }
 }
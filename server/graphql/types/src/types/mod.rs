@@ -6,6 +6,9 @@ pub use self::name::*;
 pub mod item;
 pub use self::item::*;
 
+pub mod item_category;
+pub use self::item_category::*;
+
 pub mod item_stats;
 pub use self::item_stats::*;
 
@@ -36,21 +39,54 @@ pub use self::invoice_line::*;
 pub mod item_chart;
 pub use self::item_chart::*;
 
+pub mod serial_number;
+pub use self::serial_number::*;
+
 pub mod store;
 pub use self::store::*;
 
+pub mod store_preference;
+pub use self::store_preference::*;
+
 pub mod stocktake;
 pub use self::stocktake::*;
 
 pub mod stocktake_line;
 pub use self::stocktake_line::*;
 
+pub mod stocktake_variance;
+pub use self::stocktake_variance::*;
+
 pub mod user;
 pub use self::user::*;
 
 pub mod log;
 pub use self::log::*;
 
+pub mod vvm_status;
+pub use self::vvm_status::*;
+
+pub mod open_vial_wastage;
+pub use self::open_vial_wastage::*;
+
+pub mod cycle_count;
+pub use self::cycle_count::*;
+
+pub mod stock_line_reservation;
+pub use self::stock_line_reservation::*;
+
+pub mod sync_conflict;
+pub use self::sync_conflict::*;
+
+pub mod sync_integrity;
+pub use self::sync_integrity::*;
+
+pub mod sync_log;
+pub use self::sync_log::*;
+
+pub mod sync_quarantine;
+pub use self::sync_quarantine::*;
+
 use async_graphql::*;
 pub struct DeleteResponse(pub String);
 #[Object]
@@ -0,0 +1,97 @@
+use super::{ItemNode, StockLineNode};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    loader::{ItemLoader, StockLineByIdLoader},
+    ContextExt,
+};
+use repository::OpenVialWastageRow;
+use service::ListResult;
+
+#[derive(PartialEq, Debug)]
+pub struct OpenVialWastageNode {
+    pub open_vial_wastage: OpenVialWastageRow,
+}
+
+#[derive(SimpleObject)]
+pub struct OpenVialWastageConnector {
+    total_count: u32,
+    nodes: Vec<OpenVialWastageNode>,
+}
+
+#[Object]
+impl OpenVialWastageNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.row().item_id
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<Option<ItemNode>> {
+        let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+        let result = loader.load_one(self.row().item_id.clone()).await?;
+
+        Ok(result.map(ItemNode::from_domain))
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.row().stock_line_id
+    }
+
+    pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
+        let result = loader.load_one(self.row().stock_line_id.clone()).await?;
+
+        Ok(result.map(StockLineNode::from_domain))
+    }
+
+    /// Shipment line the vial was opened for
+    pub async fn invoice_line_id(&self) -> &Option<String> {
+        &self.row().invoice_line_id
+    }
+
+    pub async fn doses_used(&self) -> i32 {
+        self.row().doses_used
+    }
+
+    /// Doses left in the vial when it was discarded
+    pub async fn doses_wasted(&self) -> i32 {
+        self.row().doses_wasted
+    }
+
+    pub async fn opened_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.row().opened_datetime, Utc)
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.row().comment
+    }
+}
+
+impl OpenVialWastageNode {
+    pub fn from_domain(open_vial_wastage: OpenVialWastageRow) -> OpenVialWastageNode {
+        OpenVialWastageNode { open_vial_wastage }
+    }
+
+    pub fn row(&self) -> &OpenVialWastageRow {
+        &self.open_vial_wastage
+    }
+}
+
+impl OpenVialWastageConnector {
+    pub fn from_domain(
+        open_vial_wastage: ListResult<OpenVialWastageRow>,
+    ) -> OpenVialWastageConnector {
+        OpenVialWastageConnector {
+            total_count: open_vial_wastage.count,
+            nodes: open_vial_wastage
+                .rows
+                .into_iter()
+                .map(OpenVialWastageNode::from_domain)
+                .collect(),
+        }
+    }
+}
@@ -4,7 +4,7 @@ use repository::{
     requisition_row::{RequisitionRow, RequisitionRowType},
     RequisitionLine, RequisitionLineRow,
 };
-use service::{item_stats::ItemStats, usize_to_u32, ListResult};
+use service::{doses::units_to_doses, item_stats::ItemStats, usize_to_u32, ListResult};
 
 use graphql_core::{
     loader::{
@@ -66,6 +66,18 @@ impl RequisitionLineNode {
         &self.row().supply_quantity
     }
 
+    /// Quantity requested, in doses (same as requested_quantity for items not counted in doses)
+    pub async fn requested_quantity_in_doses(&self, ctx: &Context<'_>) -> Result<i32> {
+        let item = self.item(ctx).await?;
+        Ok(units_to_doses(self.row().requested_quantity, item.row()))
+    }
+
+    /// Quantity to be supplied in the next shipment, in doses
+    pub async fn supply_quantity_in_doses(&self, ctx: &Context<'_>) -> Result<i32> {
+        let item = self.item(ctx).await?;
+        Ok(units_to_doses(self.row().supply_quantity, item.row()))
+    }
+
     /// Calculated quantity
     /// When months_of_stock < requisition.min_months_of_stock, calculated = average_monthy_consumption * requisition.max_months_of_stock - months_of_stock
     pub async fn suggested_quantity(&self) -> &i32 {
@@ -0,0 +1,253 @@
+use super::ItemNode;
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{
+    loader::{ItemLoader, NameRowLoader},
+    standard_graphql_error::StandardGraphqlError,
+    ContextExt,
+};
+use repository::{
+    InvoiceLineSerialNumberRow, SerialNumber, SerialNumberMovementRow, SerialNumberRow,
+    SerialNumberSort, SerialNumberSortField, SerialNumberStatus,
+};
+use service::{usize_to_u32, ListResult};
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+#[graphql(rename_items = "camelCase")]
+pub enum SerialNumberSortFieldInput {
+    SerialNumber,
+}
+
+#[derive(InputObject)]
+pub struct SerialNumberSortInput {
+    /// Sort query result by `key`
+    key: SerialNumberSortFieldInput,
+    /// Sort query result is sorted descending or ascending (if not provided the default is
+    /// ascending)
+    desc: Option<bool>,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SerialNumberNodeStatus {
+    InStore,
+    Issued,
+    InRepair,
+    Decommissioned,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct SerialNumberNode {
+    pub serial_number: SerialNumber,
+}
+
+#[derive(SimpleObject)]
+pub struct SerialNumberConnector {
+    total_count: u32,
+    nodes: Vec<SerialNumberNode>,
+}
+
+#[Object]
+impl SerialNumberNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn item_id(&self) -> &str {
+        &self.row().item_id
+    }
+
+    pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
+        item(ctx, &self.row().item_id).await
+    }
+
+    pub async fn serial_number(&self) -> &str {
+        &self.row().serial_number
+    }
+
+    pub async fn status(&self) -> SerialNumberNodeStatus {
+        SerialNumberNodeStatus::from_domain(&self.row().status)
+    }
+
+    /// Name currently holding the unit, e.g. the customer it was issued to
+    pub async fn holder_name_id(&self) -> &Option<String> {
+        &self.row().holder_name_id
+    }
+
+    pub async fn holder_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        name(ctx, &self.row().holder_name_id).await
+    }
+
+    pub async fn note(&self) -> &Option<String> {
+        &self.row().note
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct SerialNumberMovementNode {
+    pub movement: SerialNumberMovementRow,
+}
+
+#[Object]
+impl SerialNumberMovementNode {
+    pub async fn id(&self) -> &str {
+        &self.movement.id
+    }
+
+    pub async fn store_id(&self) -> &str {
+        &self.movement.store_id
+    }
+
+    /// Inbound or outbound shipment line that moved the unit
+    pub async fn invoice_line_id(&self) -> &Option<String> {
+        &self.movement.invoice_line_id
+    }
+
+    pub async fn status(&self) -> SerialNumberNodeStatus {
+        SerialNumberNodeStatus::from_domain(&self.movement.status)
+    }
+
+    pub async fn holder_name_id(&self) -> &Option<String> {
+        &self.movement.holder_name_id
+    }
+
+    pub async fn holder_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        name(ctx, &self.movement.holder_name_id).await
+    }
+
+    pub async fn datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.movement.datetime, Utc)
+    }
+}
+
+/// Serial number recorded on an inbound or outbound shipment line, the unit is moved once the
+/// shipment is delivered or picked
+#[derive(PartialEq, Debug)]
+pub struct InvoiceLineSerialNumberNode {
+    pub row: InvoiceLineSerialNumberRow,
+}
+
+#[derive(SimpleObject)]
+pub struct InvoiceLineSerialNumberConnector {
+    total_count: u32,
+    nodes: Vec<InvoiceLineSerialNumberNode>,
+}
+
+#[Object]
+impl InvoiceLineSerialNumberNode {
+    pub async fn id(&self) -> &str {
+        &self.row.id
+    }
+
+    pub async fn invoice_line_id(&self) -> &str {
+        &self.row.invoice_line_id
+    }
+
+    pub async fn serial_number(&self) -> &str {
+        &self.row.serial_number
+    }
+}
+
+async fn item(ctx: &Context<'_>, item_id: &str) -> Result<ItemNode> {
+    let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
+    let item_option = loader.load_one(item_id.to_string()).await?;
+
+    item_option.map(ItemNode::from_domain).ok_or(
+        StandardGraphqlError::InternalError(format!("Cannot find item ({})", item_id)).extend(),
+    )
+}
+
+async fn name(ctx: &Context<'_>, name_id: &Option<String>) -> Result<Option<String>> {
+    let name_id = match name_id {
+        Some(name_id) => name_id,
+        None => return Ok(None),
+    };
+    let loader = ctx.get_loader::<DataLoader<NameRowLoader>>();
+    let result = loader.load_one(name_id.clone()).await?;
+
+    Ok(result.map(|name_row| name_row.name))
+}
+
+impl SerialNumberNode {
+    pub fn from_domain(serial_number: SerialNumber) -> SerialNumberNode {
+        SerialNumberNode { serial_number }
+    }
+
+    pub fn row(&self) -> &SerialNumberRow {
+        &self.serial_number.serial_number_row
+    }
+}
+
+impl SerialNumberConnector {
+    pub fn from_domain(serial_numbers: ListResult<SerialNumber>) -> SerialNumberConnector {
+        SerialNumberConnector {
+            total_count: serial_numbers.count,
+            nodes: serial_numbers
+                .rows
+                .into_iter()
+                .map(SerialNumberNode::from_domain)
+                .collect(),
+        }
+    }
+
+    pub fn from_vec(serial_numbers: Vec<SerialNumber>) -> SerialNumberConnector {
+        SerialNumberConnector {
+            total_count: usize_to_u32(serial_numbers.len()),
+            nodes: serial_numbers
+                .into_iter()
+                .map(SerialNumberNode::from_domain)
+                .collect(),
+        }
+    }
+}
+
+impl InvoiceLineSerialNumberConnector {
+    pub fn from_vec(rows: Vec<InvoiceLineSerialNumberRow>) -> InvoiceLineSerialNumberConnector {
+        InvoiceLineSerialNumberConnector {
+            total_count: usize_to_u32(rows.len()),
+            nodes: rows
+                .into_iter()
+                .map(|row| InvoiceLineSerialNumberNode { row })
+                .collect(),
+        }
+    }
+}
+
+impl SerialNumberNodeStatus {
+    pub fn to_domain(self) -> SerialNumberStatus {
+        use SerialNumberNodeStatus as from;
+        use SerialNumberStatus as to;
+        match self {
+            from::InStore => to::InStore,
+            from::Issued => to::Issued,
+            from::InRepair => to::InRepair,
+            from::Decommissioned => to::Decommissioned,
+        }
+    }
+
+    pub fn from_domain(status: &SerialNumberStatus) -> SerialNumberNodeStatus {
+        use SerialNumberNodeStatus as to;
+        use SerialNumberStatus as from;
+        match status {
+            from::InStore => to::InStore,
+            from::Issued => to::Issued,
+            from::InRepair => to::InRepair,
+            from::Decommissioned => to::Decommissioned,
+        }
+    }
+}
+
+impl SerialNumberSortInput {
+    pub fn to_domain(self) -> SerialNumberSort {
+        use SerialNumberSortField as to;
+        use SerialNumberSortFieldInput as from;
+        let key = match self.key {
+            from::SerialNumber => to::SerialNumber,
+        };
+
+        SerialNumberSort {
+            key,
+            desc: self.desc,
+        }
+    }
+}
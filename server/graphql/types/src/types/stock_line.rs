@@ -1,9 +1,11 @@
-use super::{ItemNode, LocationNode};
+use super::{ItemNode, LocationNode, VvmStatusNode};
 use async_graphql::dataloader::DataLoader;
 use async_graphql::*;
 use chrono::NaiveDate;
 use graphql_core::{
-    loader::{ItemLoader, LocationByIdLoader},
+    loader::{
+        ItemLoader, LocationByIdLoader, NameRowLoader, StockLineHeldPacksLoader, VvmStatusRowLoader,
+    },
     simple_generic_errors::NodeError,
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
@@ -44,8 +46,17 @@ impl StockLineNode {
     pub async fn sell_price_per_pack(&self) -> f64 {
         self.row().sell_price_per_pack
     }
-    pub async fn available_number_of_packs(&self) -> i32 {
-        self.row().available_number_of_packs
+    /// Packs that can be issued to anyone, i.e. excluding the packs held by reservations
+    pub async fn available_number_of_packs(&self, ctx: &Context<'_>) -> Result<i32> {
+        let held_number_of_packs = self.held_number_of_packs(ctx).await?;
+        Ok((self.row().available_number_of_packs - held_number_of_packs).max(0))
+    }
+    /// Packs held by reservations for a customer or campaign
+    pub async fn held_number_of_packs(&self, ctx: &Context<'_>) -> Result<i32> {
+        let loader = ctx.get_loader::<DataLoader<StockLineHeldPacksLoader>>();
+        let result = loader.load_one(self.row().id.clone()).await?;
+
+        Ok(result.unwrap_or_default())
     }
     pub async fn total_number_of_packs(&self) -> i32 {
         self.row().total_number_of_packs
@@ -77,6 +88,42 @@ impl StockLineNode {
 
         Ok(result.map(LocationNode::from_domain))
     }
+    pub async fn donor_id(&self) -> &Option<String> {
+        &self.row().donor_id
+    }
+    pub async fn donor_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.get_loader::<DataLoader<NameRowLoader>>();
+
+        let donor_id = match &self.row().donor_id {
+            None => return Ok(None),
+            Some(donor_id) => donor_id,
+        };
+
+        let result = loader.load_one(donor_id.clone()).await?;
+
+        Ok(result.map(|name_row| name_row.name))
+    }
+    pub async fn manufacturer_id(&self) -> &Option<String> {
+        &self.row().manufacturer_id
+    }
+    pub async fn manufacturer_name(&self) -> Option<&str> {
+        self.stock_line.manufacturer_name()
+    }
+    pub async fn vvm_status_id(&self) -> &Option<String> {
+        &self.row().vvm_status_id
+    }
+    pub async fn vvm_status(&self, ctx: &Context<'_>) -> Result<Option<VvmStatusNode>> {
+        let loader = ctx.get_loader::<DataLoader<VvmStatusRowLoader>>();
+
+        let vvm_status_id = match &self.row().vvm_status_id {
+            None => return Ok(None),
+            Some(vvm_status_id) => vvm_status_id,
+        };
+
+        let result = loader.load_one(vvm_status_id.clone()).await?;
+
+        Ok(result.map(VvmStatusNode::from_domain))
+    }
     pub async fn item(&self, ctx: &Context<'_>) -> Result<ItemNode> {
         let loader = ctx.get_loader::<DataLoader<ItemLoader>>();
         let item_option = loader.load_one(self.row().item_id.clone()).await?;
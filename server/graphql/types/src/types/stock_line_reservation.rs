@@ -0,0 +1,90 @@
+use super::{NameNode, StockLineNode};
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use graphql_core::{
+    loader::{NameByIdLoader, NameByIdLoaderInput, StockLineByIdLoader},
+    ContextExt,
+};
+use repository::StockLineReservationRow;
+
+#[derive(PartialEq, Debug)]
+pub struct StockLineReservationNode {
+    pub stock_line_reservation: StockLineReservationRow,
+}
+
+#[Object]
+impl StockLineReservationNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.row().stock_line_id
+    }
+
+    pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
+        let result = loader.load_one(self.row().stock_line_id.clone()).await?;
+
+        Ok(result.map(StockLineNode::from_domain))
+    }
+
+    /// Customer the stock is reserved for
+    pub async fn name_id(&self) -> &Option<String> {
+        &self.row().name_id
+    }
+
+    pub async fn name(&self, ctx: &Context<'_>) -> Result<Option<NameNode>> {
+        let name_id = match &self.row().name_id {
+            None => return Ok(None),
+            Some(name_id) => name_id,
+        };
+
+        let loader = ctx.get_loader::<DataLoader<NameByIdLoader>>();
+        let result = loader
+            .load_one(NameByIdLoaderInput::new(&self.row().store_id, name_id))
+            .await?;
+
+        Ok(result.map(NameNode::from_domain))
+    }
+
+    /// Campaign or programme the stock is reserved for
+    pub async fn campaign(&self) -> &Option<String> {
+        &self.row().campaign
+    }
+
+    /// Packs still held
+    pub async fn number_of_packs(&self) -> i32 {
+        self.row().number_of_packs
+    }
+
+    /// Last day the stock is held
+    pub async fn reserved_until(&self) -> NaiveDate {
+        self.row().reserved_until
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.row().created_datetime, Utc)
+    }
+
+    pub async fn released_datetime(&self) -> Option<DateTime<Utc>> {
+        self.row()
+            .released_datetime
+            .map(|released_datetime| DateTime::<Utc>::from_utc(released_datetime, Utc))
+    }
+}
+
+impl StockLineReservationNode {
+    pub fn from_domain(
+        stock_line_reservation: StockLineReservationRow,
+    ) -> StockLineReservationNode {
+        StockLineReservationNode {
+            stock_line_reservation,
+        }
+    }
+
+    pub fn row(&self) -> &StockLineReservationRow {
+        &self.stock_line_reservation
+    }
+}
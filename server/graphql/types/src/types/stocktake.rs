@@ -2,6 +2,7 @@ use async_graphql::{self, dataloader::DataLoader, Context, Enum, ErrorExtensions
 use chrono::{DateTime, NaiveDate, Utc};
 use repository::{unknown_user, StocktakeRow, StocktakeStatus};
 use serde::Serialize;
+use service::i32_to_u32;
 
 use graphql_core::{
     loader::{InvoiceByIdLoader, StocktakeLineByStocktakeIdLoader, UserLoader},
@@ -15,6 +16,7 @@ use super::{InvoiceNode, StocktakeLineConnector, UserNode};
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")] // only needed to be comparable in tests
 pub enum StocktakeNodeStatus {
     New,
+    Review,
     Finalised,
 }
 
@@ -60,6 +62,41 @@ impl StocktakeNode {
         self.stocktake.is_locked
     }
 
+    /// Snapshot quantities of lines are hidden until the stocktake is finalised
+    pub async fn is_blind_count(&self) -> bool {
+        self.stocktake.is_blind_count
+    }
+
+    pub async fn recount_tolerance(&self) -> u32 {
+        i32_to_u32(self.stocktake.recount_tolerance)
+    }
+
+    /// Variance cost value above which the stocktake has to be approved before finalising
+    pub async fn variance_approval_threshold(&self) -> Option<f64> {
+        self.stocktake.variance_approval_threshold
+    }
+
+    /// User that approved the stocktake variance
+    pub async fn approved_user(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        let approved_user_id = match &self.stocktake.approved_user_id {
+            Some(approved_user_id) => approved_user_id,
+            None => return Ok(None),
+        };
+        let loader = ctx.get_loader::<DataLoader<UserLoader>>();
+        let user = loader
+            .load_one(approved_user_id.clone())
+            .await?
+            .unwrap_or(unknown_user());
+
+        Ok(Some(UserNode::from_domain(user)))
+    }
+
+    pub async fn approved_datetime(&self) -> Option<DateTime<Utc>> {
+        self.stocktake
+            .approved_datetime
+            .map(|dt| DateTime::<Utc>::from_utc(dt, Utc))
+    }
+
     pub async fn status(&self) -> StocktakeNodeStatus {
         StocktakeNodeStatus::from_domain(&self.stocktake.status)
     }
@@ -122,6 +159,7 @@ impl StocktakeNodeStatus {
     pub fn to_domain(self) -> StocktakeStatus {
         match self {
             StocktakeNodeStatus::New => StocktakeStatus::New,
+            StocktakeNodeStatus::Review => StocktakeStatus::Review,
             StocktakeNodeStatus::Finalised => StocktakeStatus::Finalised,
         }
     }
@@ -129,6 +167,7 @@ impl StocktakeNodeStatus {
     pub fn from_domain(status: &StocktakeStatus) -> StocktakeNodeStatus {
         match status {
             StocktakeStatus::New => StocktakeNodeStatus::New,
+            StocktakeStatus::Review => StocktakeNodeStatus::Review,
             StocktakeStatus::Finalised => StocktakeNodeStatus::Finalised,
         }
     }
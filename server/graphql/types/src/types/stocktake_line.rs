@@ -1,16 +1,19 @@
 use async_graphql::*;
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use dataloader::DataLoader;
-use repository::{Location, StocktakeLine};
-use service::{i32_to_u32, usize_to_u32};
+use repository::{unknown_user, Location, StocktakeLine, StocktakeLineCountRow};
+use service::{i32_to_u32, stocktake::is_snapshot_hidden, usize_to_u32};
 
 use graphql_core::{
-    loader::{ItemLoader, StockLineByIdLoader},
+    loader::{
+        ItemLoader, NameRowLoader, StockLineByIdLoader, StocktakeByIdLoader,
+        StocktakeLineCountByStocktakeLineIdLoader, UserLoader, VvmStatusRowLoader,
+    },
     standard_graphql_error::StandardGraphqlError,
     ContextExt,
 };
 
-use super::{ItemNode, LocationNode, StockLineNode};
+use super::{ItemNode, LocationNode, StockLineNode, UserNode, VvmStatusNode};
 
 pub struct StocktakeLineNode {
     pub line: StocktakeLine,
@@ -26,7 +29,12 @@ impl StocktakeLineNode {
         &self.line.line.stocktake_id
     }
 
+    /// Null while the stocktake is a blind count that is still being counted, the stock line
+    /// reveals the snapshot
     pub async fn stock_line(&self, ctx: &Context<'_>) -> Result<Option<StockLineNode>> {
+        if self.is_blind_count(ctx).await? {
+            return Ok(None);
+        }
         if let Some(ref stock_line) = self.line.stock_line {
             let loader = ctx.get_loader::<DataLoader<StockLineByIdLoader>>();
             let stock_line = loader.load_one(stock_line.id.clone()).await?.ok_or(
@@ -53,14 +61,37 @@ impl StocktakeLineNode {
         self.line.line.comment.clone()
     }
 
-    pub async fn snapshot_number_of_packs(&self) -> u32 {
-        i32_to_u32(self.line.line.snapshot_number_of_packs)
+    /// Null while the stocktake is a blind count that is still being counted
+    pub async fn snapshot_number_of_packs(&self, ctx: &Context<'_>) -> Result<Option<u32>> {
+        if self.is_blind_count(ctx).await? {
+            return Ok(None);
+        }
+        Ok(Some(i32_to_u32(self.line.line.snapshot_number_of_packs)))
     }
 
     pub async fn counted_number_of_packs(&self) -> Option<u32> {
         self.line.line.counted_number_of_packs.map(i32_to_u32)
     }
 
+    /// Independent counts of this line differ by more than the stocktake's recount tolerance
+    pub async fn needs_recount(&self) -> bool {
+        self.line.line.needs_recount
+    }
+
+    /// Independent counts of this line, one per counting user
+    pub async fn counts(&self, ctx: &Context<'_>) -> Result<Vec<StocktakeLineCountNode>> {
+        let loader = ctx.get_loader::<DataLoader<StocktakeLineCountByStocktakeLineIdLoader>>();
+        let result = loader
+            .load_one(self.line.line.id.clone())
+            .await?
+            .unwrap_or_default();
+
+        Ok(result
+            .into_iter()
+            .map(|count| StocktakeLineCountNode { count })
+            .collect())
+    }
+
     pub async fn item_id(&self) -> &str {
         &self.line.line.item_id
     }
@@ -101,6 +132,92 @@ impl StocktakeLineNode {
     pub async fn note(&self) -> &Option<String> {
         &self.line.line.note
     }
+
+    pub async fn donor_id(&self) -> &Option<String> {
+        &self.line.line.donor_id
+    }
+
+    pub async fn donor_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.get_loader::<DataLoader<NameRowLoader>>();
+
+        let donor_id = match &self.line.line.donor_id {
+            None => return Ok(None),
+            Some(donor_id) => donor_id,
+        };
+
+        let result = loader.load_one(donor_id.clone()).await?;
+
+        Ok(result.map(|name_row| name_row.name))
+    }
+
+    pub async fn manufacturer_id(&self) -> &Option<String> {
+        &self.line.line.manufacturer_id
+    }
+
+    pub async fn manufacturer_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.get_loader::<DataLoader<NameRowLoader>>();
+
+        let manufacturer_id = match &self.line.line.manufacturer_id {
+            None => return Ok(None),
+            Some(manufacturer_id) => manufacturer_id,
+        };
+
+        let result = loader.load_one(manufacturer_id.clone()).await?;
+
+        Ok(result.map(|name_row| name_row.name))
+    }
+
+    pub async fn vvm_status_id(&self) -> &Option<String> {
+        &self.line.line.vvm_status_id
+    }
+
+    pub async fn vvm_status(&self, ctx: &Context<'_>) -> Result<Option<VvmStatusNode>> {
+        let loader = ctx.get_loader::<DataLoader<VvmStatusRowLoader>>();
+
+        let vvm_status_id = match &self.line.line.vvm_status_id {
+            None => return Ok(None),
+            Some(vvm_status_id) => vvm_status_id,
+        };
+
+        let result = loader.load_one(vvm_status_id.clone()).await?;
+
+        Ok(result.map(VvmStatusNode::from_domain))
+    }
+}
+
+pub struct StocktakeLineCountNode {
+    pub count: StocktakeLineCountRow,
+}
+
+#[Object]
+impl StocktakeLineCountNode {
+    pub async fn id(&self) -> &str {
+        &self.count.id
+    }
+
+    pub async fn user_id(&self) -> &str {
+        &self.count.user_id
+    }
+
+    /// User that did the count, if user is not found in system default unknown user is returned
+    pub async fn user(&self, ctx: &Context<'_>) -> Result<UserNode> {
+        let loader = ctx.get_loader::<DataLoader<UserLoader>>();
+
+        let user = loader
+            .load_one(self.count.user_id.clone())
+            .await?
+            .unwrap_or(unknown_user());
+
+        Ok(UserNode::from_domain(user))
+    }
+
+    pub async fn counted_number_of_packs(&self) -> u32 {
+        i32_to_u32(self.count.counted_number_of_packs)
+    }
+
+    pub async fn counted_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.count.counted_datetime, Utc)
+    }
 }
 
 #[derive(SimpleObject)]
@@ -132,4 +249,21 @@ impl StocktakeLineNode {
     pub fn from_domain(line: StocktakeLine) -> StocktakeLineNode {
         StocktakeLineNode { line }
     }
+
+    /// Stocktake is a blind count that is still being counted, i.e. the snapshot is hidden
+    async fn is_blind_count(&self, ctx: &Context<'_>) -> Result<bool> {
+        let loader = ctx.get_loader::<DataLoader<StocktakeByIdLoader>>();
+        let stocktake = loader
+            .load_one(self.line.line.stocktake_id.clone())
+            .await?
+            .ok_or(
+                StandardGraphqlError::InternalError(format!(
+                    "Cannot find stocktake {} for stocktake line id {}",
+                    self.line.line.stocktake_id, self.line.line.id
+                ))
+                .extend(),
+            )?;
+
+        Ok(is_snapshot_hidden(&stocktake))
+    }
 }
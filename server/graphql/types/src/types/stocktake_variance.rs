@@ -0,0 +1,92 @@
+use async_graphql::*;
+use service::stocktake::{StocktakeLineVariance, StocktakeVariance};
+
+use super::StocktakeLineNode;
+
+pub struct StocktakeLineVarianceNode {
+    pub variance: StocktakeLineVariance,
+    pub is_blind_count: bool,
+}
+
+#[Object]
+impl StocktakeLineVarianceNode {
+    pub async fn line(&self) -> StocktakeLineNode {
+        StocktakeLineNode::from_domain(self.variance.line.clone())
+    }
+
+    /// Counted minus snapshot number of packs, null while the stocktake is a blind count that is
+    /// still being counted
+    pub async fn difference_number_of_packs(&self) -> Option<i32> {
+        if self.is_blind_count {
+            return None;
+        }
+        Some(self.variance.difference_number_of_packs)
+    }
+
+    /// Difference in packs multiplied by the cost price per pack, null while the stocktake is a
+    /// blind count that is still being counted
+    pub async fn difference_value(&self) -> Option<f64> {
+        if self.is_blind_count {
+            return None;
+        }
+        Some(self.variance.difference_value)
+    }
+
+    pub async fn exceeds_threshold(&self) -> bool {
+        self.variance.exceeds_threshold
+    }
+}
+
+pub struct StocktakeVarianceNode {
+    pub variance: StocktakeVariance,
+}
+
+#[Object]
+impl StocktakeVarianceNode {
+    pub async fn stocktake_id(&self) -> &str {
+        &self.variance.stocktake_id
+    }
+
+    pub async fn lines(&self) -> Vec<StocktakeLineVarianceNode> {
+        self.variance
+            .lines
+            .iter()
+            .cloned()
+            .map(|variance| StocktakeLineVarianceNode {
+                variance,
+                is_blind_count: self.variance.is_blind_count,
+            })
+            .collect()
+    }
+
+    /// Null while the stocktake is a blind count that is still being counted
+    pub async fn total_difference_value(&self) -> Option<f64> {
+        if self.variance.is_blind_count {
+            return None;
+        }
+        Some(self.variance.total_difference_value)
+    }
+
+    /// Null while the stocktake is a blind count that is still being counted
+    pub async fn total_absolute_difference_value(&self) -> Option<f64> {
+        if self.variance.is_blind_count {
+            return None;
+        }
+        Some(self.variance.total_absolute_difference_value)
+    }
+
+    /// A line or the total absolute difference value exceeds the stocktake's approval threshold
+    pub async fn requires_approval(&self) -> bool {
+        self.variance.requires_approval
+    }
+
+    pub async fn is_approved(&self) -> bool {
+        self.variance.is_approved
+    }
+}
+
+impl StocktakeVarianceNode {
+    pub fn from_domain(variance: StocktakeVariance) -> StocktakeVarianceNode {
+        StocktakeVarianceNode { variance }
+    }
+}
@@ -0,0 +1,82 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use graphql_core::{loader::LocationByIdLoader, ContextExt};
+use repository::{AllocationStrategy, StorePreferenceRow};
+use serde::Serialize;
+
+use super::LocationNode;
+
+/// Order in which available stock lines are used when allocating outbound shipment lines.
+/// Vials closer to their VVM discard point are always used first.
+#[derive(Enum, Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")] // only needed to be comparable in tests
+pub enum AllocationStrategyNode {
+    /// Earliest expiry first (FEFO)
+    ExpiryFirst,
+    /// Earliest received first (FIFO)
+    FirstInFirstOut,
+    /// Stock in the pick face location first, then earliest expiry first
+    PreferredLocation,
+    /// Whole packs first, so that packs are only broken for the remainder
+    WholePackFirst,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct StorePreferenceNode {
+    pub store_preference: StorePreferenceRow,
+}
+
+#[Object]
+impl StorePreferenceNode {
+    /// Id of the store
+    pub async fn id(&self) -> &str {
+        &self.store_preference.id
+    }
+
+    pub async fn allocation_strategy(&self) -> AllocationStrategyNode {
+        AllocationStrategyNode::from_domain(&self.store_preference.allocation_strategy)
+    }
+
+    pub async fn pick_face_location_id(&self) -> &Option<String> {
+        &self.store_preference.pick_face_location_id
+    }
+
+    pub async fn pick_face_location(&self, ctx: &Context<'_>) -> Result<Option<LocationNode>> {
+        let loader = ctx.get_loader::<DataLoader<LocationByIdLoader>>();
+
+        let location_id = match &self.store_preference.pick_face_location_id {
+            None => return Ok(None),
+            Some(location_id) => location_id,
+        };
+
+        let result = loader.load_one(location_id.clone()).await?;
+
+        Ok(result.map(LocationNode::from_domain))
+    }
+}
+
+impl AllocationStrategyNode {
+    pub fn to_domain(self) -> AllocationStrategy {
+        match self {
+            AllocationStrategyNode::ExpiryFirst => AllocationStrategy::ExpiryFirst,
+            AllocationStrategyNode::FirstInFirstOut => AllocationStrategy::FirstInFirstOut,
+            AllocationStrategyNode::PreferredLocation => AllocationStrategy::PreferredLocation,
+            AllocationStrategyNode::WholePackFirst => AllocationStrategy::WholePackFirst,
+        }
+    }
+
+    pub fn from_domain(strategy: &AllocationStrategy) -> AllocationStrategyNode {
+        match strategy {
+            AllocationStrategy::ExpiryFirst => AllocationStrategyNode::ExpiryFirst,
+            AllocationStrategy::FirstInFirstOut => AllocationStrategyNode::FirstInFirstOut,
+            AllocationStrategy::PreferredLocation => AllocationStrategyNode::PreferredLocation,
+            AllocationStrategy::WholePackFirst => AllocationStrategyNode::WholePackFirst,
+        }
+    }
+}
+
+impl StorePreferenceNode {
+    pub fn from_domain(store_preference: StorePreferenceRow) -> StorePreferenceNode {
+        StorePreferenceNode { store_preference }
+    }
+}
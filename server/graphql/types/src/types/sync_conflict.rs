@@ -0,0 +1,71 @@
+use async_graphql::*;
+use chrono::NaiveDateTime;
+use repository::{SyncConflictResolution, SyncConflictRow};
+
+#[derive(PartialEq, Debug)]
+pub struct SyncConflictNode {
+    sync_conflict: SyncConflictRow,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum SyncConflictResolutionNode {
+    /// Remote record has been dropped, the local record is pushed with the next sync
+    LocalWins,
+    /// Remote record has been integrated, overwriting the local changes
+    RemoteWins,
+}
+
+#[Object]
+impl SyncConflictNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    /// Table name on the central server
+    pub async fn table_name(&self) -> &str {
+        &self.row().table_name
+    }
+
+    pub async fn record_id(&self) -> &str {
+        &self.row().record_id
+    }
+
+    pub async fn resolution(&self) -> SyncConflictResolutionNode {
+        SyncConflictResolutionNode::from_domain(&self.row().resolution)
+    }
+
+    /// Raw JSON of the local record as it would have been pushed, null if deleted locally
+    pub async fn local_data(&self) -> &Option<String> {
+        &self.row().local_data
+    }
+
+    /// Raw JSON of the remote record
+    pub async fn remote_data(&self) -> &str {
+        &self.row().remote_data
+    }
+
+    pub async fn detected_datetime(&self) -> &NaiveDateTime {
+        &self.row().detected_datetime
+    }
+}
+
+impl SyncConflictNode {
+    pub fn from_domain(sync_conflict: SyncConflictRow) -> Self {
+        SyncConflictNode { sync_conflict }
+    }
+
+    pub fn row(&self) -> &SyncConflictRow {
+        &self.sync_conflict
+    }
+}
+
+impl SyncConflictResolutionNode {
+    pub fn from_domain(resolution: &SyncConflictResolution) -> Self {
+        use SyncConflictResolution as from;
+        use SyncConflictResolutionNode as to;
+        match resolution {
+            from::LocalWins => to::LocalWins,
+            from::RemoteWins => to::RemoteWins,
+        }
+    }
+}
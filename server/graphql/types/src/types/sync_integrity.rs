@@ -0,0 +1,125 @@
+use async_graphql::*;
+use service::{
+    apis::sync_api_v5::TableIntegrityV5,
+    sync::sync_integrity::{IntegrityMismatch, SyncIntegrityRepair, SyncIntegrityReport},
+};
+
+pub struct SyncIntegrityNode {
+    report: SyncIntegrityReport,
+}
+
+pub struct SyncIntegrityTableNode {
+    local: TableIntegrityV5,
+    central: Option<TableIntegrityV5>,
+}
+
+pub struct SyncIntegrityMismatchNode {
+    mismatch: IntegrityMismatch,
+}
+
+#[derive(SimpleObject)]
+pub struct SyncIntegrityRepairNode {
+    /// Number of records re-pulled from the central server
+    pub repulled_records: u32,
+    /// Integrity after the re-pull
+    pub integrity: SyncIntegrityNode,
+}
+
+#[Object]
+impl SyncIntegrityNode {
+    pub async fn store_id(&self) -> &str {
+        &self.report.local.store_id
+    }
+
+    /// False if the central server doesn't provide integrity summaries, in which case only the
+    /// local row counts are available
+    pub async fn is_central_available(&self) -> bool {
+        self.report.central.is_some()
+    }
+
+    pub async fn tables(&self) -> Vec<SyncIntegrityTableNode> {
+        self.report
+            .local
+            .tables
+            .iter()
+            .map(|local| SyncIntegrityTableNode {
+                local: local.clone(),
+                central: self.report.central.as_ref().and_then(|central| {
+                    central
+                        .tables
+                        .iter()
+                        .find(|table| table.table_name == local.table_name)
+                        .cloned()
+                }),
+            })
+            .collect()
+    }
+
+    /// Ranges of records that differ between this site and the central server
+    pub async fn mismatches(&self) -> Vec<SyncIntegrityMismatchNode> {
+        self.report
+            .mismatches
+            .iter()
+            .cloned()
+            .map(|mismatch| SyncIntegrityMismatchNode { mismatch })
+            .collect()
+    }
+}
+
+#[Object]
+impl SyncIntegrityTableNode {
+    /// Central table name, e.g. `transact`
+    pub async fn table_name(&self) -> &str {
+        &self.local.table_name
+    }
+
+    pub async fn local_count(&self) -> u32 {
+        self.local.count
+    }
+
+    pub async fn central_count(&self) -> Option<u32> {
+        self.central.as_ref().map(|central| central.count)
+    }
+
+    /// Null if the central server doesn't provide integrity summaries
+    pub async fn is_matching(&self) -> Option<bool> {
+        self.central
+            .as_ref()
+            .map(|central| central.hash == self.local.hash)
+    }
+}
+
+#[Object]
+impl SyncIntegrityMismatchNode {
+    pub async fn table_name(&self) -> &str {
+        &self.mismatch.table_name
+    }
+
+    /// Records whose id starts with the range
+    pub async fn range(&self) -> &str {
+        &self.mismatch.range
+    }
+
+    pub async fn local_count(&self) -> u32 {
+        self.mismatch.local_count
+    }
+
+    pub async fn central_count(&self) -> u32 {
+        self.mismatch.central_count
+    }
+}
+
+impl SyncIntegrityNode {
+    pub fn from_domain(report: SyncIntegrityReport) -> Self {
+        SyncIntegrityNode { report }
+    }
+}
+
+impl SyncIntegrityRepairNode {
+    pub fn from_domain(repair: SyncIntegrityRepair) -> Self {
+        SyncIntegrityRepairNode {
+            repulled_records: repair.repulled_records,
+            integrity: SyncIntegrityNode::from_domain(repair.report),
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use async_graphql::*;
+use chrono::NaiveDateTime;
+use repository::{SyncLogRow, SyncLogStage};
+
+#[derive(PartialEq, Debug)]
+pub struct SyncLogNode {
+    sync_log: SyncLogRow,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum SyncLogStageNode {
+    Push,
+    RemotePull,
+    CentralPull,
+    Integrate,
+}
+
+#[Object]
+impl SyncLogNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    /// True when remote data for the site is being pulled for the first time
+    pub async fn is_initial_pull(&self) -> bool {
+        self.row().is_initial_pull
+    }
+
+    pub async fn started_datetime(&self) -> &NaiveDateTime {
+        &self.row().started_datetime
+    }
+
+    /// Null while the sync is running
+    pub async fn finished_datetime(&self) -> &Option<NaiveDateTime> {
+        &self.row().finished_datetime
+    }
+
+    pub async fn is_running(&self) -> bool {
+        self.row().finished_datetime.is_none()
+    }
+
+    /// Current stage of a running sync, or the stage a finished sync ended in
+    pub async fn stage(&self) -> Option<SyncLogStageNode> {
+        self.row().stage.as_ref().map(SyncLogStageNode::from_domain)
+    }
+
+    pub async fn pushed_records(&self) -> i32 {
+        self.row().pushed_records
+    }
+
+    pub async fn pulled_remote_records(&self) -> i32 {
+        self.row().pulled_remote_records
+    }
+
+    pub async fn pulled_central_records(&self) -> i32 {
+        self.row().pulled_central_records
+    }
+
+    pub async fn integrated_records(&self) -> i32 {
+        self.row().integrated_records
+    }
+
+    /// Records still to be pulled in the current pull stage, used to show pull progress
+    pub async fn pull_remaining_records(&self) -> &Option<i32> {
+        &self.row().pull_remaining_records
+    }
+
+    pub async fn error(&self) -> &Option<String> {
+        &self.row().error
+    }
+}
+
+impl SyncLogNode {
+    pub fn from_domain(sync_log: SyncLogRow) -> Self {
+        SyncLogNode { sync_log }
+    }
+
+    pub fn row(&self) -> &SyncLogRow {
+        &self.sync_log
+    }
+}
+
+impl SyncLogStageNode {
+    pub fn from_domain(stage: &SyncLogStage) -> Self {
+        use SyncLogStage as from;
+        use SyncLogStageNode as to;
+        match stage {
+            from::Push => to::Push,
+            from::RemotePull => to::RemotePull,
+            from::CentralPull => to::CentralPull,
+            from::Integrate => to::Integrate,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use async_graphql::*;
+use chrono::NaiveDateTime;
+use repository::{SyncQuarantineRow, SyncQuarantineSource};
+
+#[derive(PartialEq, Debug)]
+pub struct SyncQuarantineNode {
+    sync_quarantine: SyncQuarantineRow,
+}
+
+#[derive(Enum, Copy, Clone, PartialEq, Eq)]
+pub enum SyncQuarantineSourceNode {
+    Central,
+    Remote,
+}
+
+#[Object]
+impl SyncQuarantineNode {
+    pub async fn id(&self) -> &str {
+        &self.row().id
+    }
+
+    pub async fn source(&self) -> SyncQuarantineSourceNode {
+        SyncQuarantineSourceNode::from_domain(&self.row().source)
+    }
+
+    /// Table name on the central server
+    pub async fn table_name(&self) -> &str {
+        &self.row().table_name
+    }
+
+    pub async fn record_id(&self) -> &str {
+        &self.row().record_id
+    }
+
+    /// Raw JSON record as received from the central server
+    pub async fn data(&self) -> &str {
+        &self.row().data
+    }
+
+    pub async fn error(&self) -> &str {
+        &self.row().error
+    }
+
+    pub async fn attempt_count(&self) -> i32 {
+        self.row().attempt_count
+    }
+
+    pub async fn first_failed_datetime(&self) -> &NaiveDateTime {
+        &self.row().first_failed_datetime
+    }
+
+    pub async fn last_failed_datetime(&self) -> &NaiveDateTime {
+        &self.row().last_failed_datetime
+    }
+}
+
+impl SyncQuarantineNode {
+    pub fn from_domain(sync_quarantine: SyncQuarantineRow) -> Self {
+        SyncQuarantineNode { sync_quarantine }
+    }
+
+    pub fn row(&self) -> &SyncQuarantineRow {
+        &self.sync_quarantine
+    }
+}
+
+impl SyncQuarantineSourceNode {
+    pub fn from_domain(source: &SyncQuarantineSource) -> Self {
+        use SyncQuarantineSource as from;
+        use SyncQuarantineSourceNode as to;
+        match source {
+            from::Central => to::Central,
+            from::Remote => to::Remote,
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use async_graphql::dataloader::DataLoader;
+use async_graphql::*;
+use chrono::{DateTime, Utc};
+use graphql_core::{loader::VvmStatusRowLoader, ContextExt};
+use repository::{VvmStatusLogRow, VvmStatusRow};
+
+#[derive(PartialEq, Debug)]
+pub struct VvmStatusNode {
+    pub vvm_status: VvmStatusRow,
+}
+
+#[Object]
+impl VvmStatusNode {
+    pub async fn id(&self) -> &str {
+        &self.vvm_status.id
+    }
+
+    pub async fn code(&self) -> &str {
+        &self.vvm_status.code
+    }
+
+    pub async fn description(&self) -> &str {
+        &self.vvm_status.description
+    }
+
+    /// Higher levels have had more heat exposure
+    pub async fn level(&self) -> i32 {
+        self.vvm_status.level
+    }
+
+    pub async fn is_active(&self) -> bool {
+        self.vvm_status.is_active
+    }
+
+    /// Stock at this stage must not be issued
+    pub async fn unusable(&self) -> bool {
+        self.vvm_status.unusable
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct VvmStatusLogNode {
+    pub vvm_status_log: VvmStatusLogRow,
+}
+
+#[Object]
+impl VvmStatusLogNode {
+    pub async fn id(&self) -> &str {
+        &self.vvm_status_log.id
+    }
+
+    pub async fn status_id(&self) -> &str {
+        &self.vvm_status_log.status_id
+    }
+
+    pub async fn status(&self, ctx: &Context<'_>) -> Result<Option<VvmStatusNode>> {
+        let loader = ctx.get_loader::<DataLoader<VvmStatusRowLoader>>();
+        let result = loader
+            .load_one(self.vvm_status_log.status_id.clone())
+            .await?;
+
+        Ok(result.map(VvmStatusNode::from_domain))
+    }
+
+    pub async fn stock_line_id(&self) -> &str {
+        &self.vvm_status_log.stock_line_id
+    }
+
+    /// Shipment line the status was recorded on
+    pub async fn invoice_line_id(&self) -> &Option<String> {
+        &self.vvm_status_log.invoice_line_id
+    }
+
+    /// Stocktake line the status was counted on
+    pub async fn stocktake_line_id(&self) -> &Option<String> {
+        &self.vvm_status_log.stocktake_line_id
+    }
+
+    pub async fn comment(&self) -> &Option<String> {
+        &self.vvm_status_log.comment
+    }
+
+    pub async fn created_datetime(&self) -> DateTime<Utc> {
+        DateTime::<Utc>::from_utc(self.vvm_status_log.created_datetime, Utc)
+    }
+}
+
+impl VvmStatusNode {
+    pub fn from_domain(vvm_status: VvmStatusRow) -> VvmStatusNode {
+        VvmStatusNode { vvm_status }
+    }
+}
+
+impl VvmStatusLogNode {
+    pub fn from_domain(vvm_status_log: VvmStatusLogRow) -> VvmStatusLogNode {
+        VvmStatusLogNode { vvm_status_log }
+    }
+}
@@ -0,0 +1,99 @@
+mod mutations;
+use self::mutations::*;
+
+use async_graphql::*;
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::*;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    SingleRecordError,
+};
+
+#[derive(Default, Clone)]
+pub struct VvmStatusQueries;
+
+#[Object]
+impl VvmStatusQueries {
+    /// Active vaccine vial monitor stages, lowest level first
+    pub async fn vvm_statuses(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+    ) -> Result<Vec<VvmStatusNode>> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryVvmStatus,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let vvm_statuses = service_provider
+            .vvm_status_service
+            .get_vvm_statuses(&service_context)
+            .map_err(StandardGraphqlError::from_repository_error)?;
+
+        Ok(vvm_statuses
+            .into_iter()
+            .map(VvmStatusNode::from_domain)
+            .collect())
+    }
+
+    /// VVM status history of a stock line, oldest first
+    pub async fn vvm_status_logs(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        stock_line_id: String,
+    ) -> Result<Vec<VvmStatusLogNode>> {
+        validate_auth(
+            ctx,
+            &ResourceAccessRequest {
+                resource: Resource::QueryVvmStatus,
+                store_id: Some(store_id.clone()),
+            },
+        )?;
+
+        let service_provider = ctx.service_provider();
+        let service_context = service_provider.context()?;
+
+        let vvm_status_logs = service_provider
+            .vvm_status_service
+            .get_vvm_status_logs(&service_context, &store_id, &stock_line_id)
+            .map_err(|error| match error {
+                SingleRecordError::DatabaseError(error) => {
+                    StandardGraphqlError::from_repository_error(error)
+                }
+                SingleRecordError::NotFound(_) => {
+                    StandardGraphqlError::BadUserInput(format!("{:#?}", error)).extend()
+                }
+            })?;
+
+        Ok(vvm_status_logs
+            .into_iter()
+            .map(VvmStatusLogNode::from_domain)
+            .collect())
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct VvmStatusMutations;
+
+#[Object]
+impl VvmStatusMutations {
+    /// Record a new VVM stage for a stock line, e.g. when checked on receipt or at issue
+    async fn update_stock_line_vvm_status(
+        &self,
+        ctx: &Context<'_>,
+        store_id: String,
+        input: UpdateStockLineVvmStatusInput,
+    ) -> Result<VvmStatusLogNode> {
+        update_stock_line_vvm_status(ctx, &store_id, input)
+    }
+}
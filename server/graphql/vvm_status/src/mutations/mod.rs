@@ -0,0 +1,3 @@
+mod update;
+
+pub use update::*;
@@ -0,0 +1,78 @@
+use async_graphql::*;
+
+use graphql_core::{
+    standard_graphql_error::{validate_auth, StandardGraphqlError},
+    ContextExt,
+};
+use graphql_types::types::VvmStatusLogNode;
+use service::{
+    auth::{Resource, ResourceAccessRequest},
+    vvm_status::update::{UpdateStockLineVvmStatus, UpdateStockLineVvmStatusError as ServiceError},
+};
+
+pub fn update_stock_line_vvm_status(
+    ctx: &Context<'_>,
+    store_id: &str,
+    input: UpdateStockLineVvmStatusInput,
+) -> Result<VvmStatusLogNode> {
+    validate_auth(
+        ctx,
+        &ResourceAccessRequest {
+            resource: Resource::MutateVvmStatus,
+            store_id: Some(store_id.to_string()),
+        },
+    )?;
+
+    let service_provider = ctx.service_provider();
+    let service_context = service_provider.context()?;
+
+    match service_provider
+        .vvm_status_service
+        .update_stock_line_vvm_status(&service_context, store_id, input.into())
+    {
+        Ok(vvm_status_log) => Ok(VvmStatusLogNode::from_domain(vvm_status_log)),
+        Err(error) => Err(map_error(error)),
+    }
+}
+
+#[derive(InputObject)]
+pub struct UpdateStockLineVvmStatusInput {
+    pub stock_line_id: String,
+    pub status_id: String,
+    pub invoice_line_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+impl From<UpdateStockLineVvmStatusInput> for UpdateStockLineVvmStatus {
+    fn from(
+        UpdateStockLineVvmStatusInput {
+            stock_line_id,
+            status_id,
+            invoice_line_id,
+            comment,
+        }: UpdateStockLineVvmStatusInput,
+    ) -> Self {
+        UpdateStockLineVvmStatus {
+            stock_line_id,
+            status_id,
+            invoice_line_id,
+            comment,
+        }
+    }
+}
+
+fn map_error(error: ServiceError) -> Error {
+    use StandardGraphqlError::*;
+    let formatted_error = format!("{:#?}", error);
+
+    let graphql_error = match error {
+        ServiceError::StockLineDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::NotThisStoreStockLine => BadUserInput(formatted_error),
+        ServiceError::VvmStatusDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InvoiceLineDoesNotExist => BadUserInput(formatted_error),
+        ServiceError::InvoiceLineNotForStockLine => BadUserInput(formatted_error),
+        ServiceError::DatabaseError(_) => InternalError(formatted_error),
+    };
+
+    graphql_error.extend()
+}
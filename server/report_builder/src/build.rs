@@ -34,6 +34,7 @@ fn parse_default_query(input: &str) -> anyhow::Result<DefaultQuery> {
         "invoice" => DefaultQuery::Invoice,
         "stocktake" => DefaultQuery::Stocktake,
         "requisition" => DefaultQuery::Requisition,
+        "stock-on-hand" => DefaultQuery::StockOnHand,
         _ => {
             return Err(anyhow::Error::msg(format!(
                 "Invalid default query: {}",
@@ -35,7 +35,7 @@ pub struct BuildArgs {
     /// Name of the file containing a graphql query
     #[clap(long)]
     pub query_gql: Option<String>,
-    /// Default query type, one of: "invoice" | "stocktake" | "requisition",
+    /// Default query type, one of: "invoice" | "stocktake" | "requisition" | "stock-on-hand",
     #[clap(long)]
     pub query_default: Option<String>,
 }
@@ -0,0 +1,6 @@
+DROP VIEW IF EXISTS donor_consumption CASCADE;
+DROP VIEW IF EXISTS donor_stock_on_hand CASCADE;
+
+ALTER TABLE stocktake_line DROP COLUMN donor_id;
+ALTER TABLE invoice_line DROP COLUMN donor_id;
+ALTER TABLE stock_line DROP COLUMN donor_id;
@@ -0,0 +1,32 @@
+ALTER TABLE stock_line ADD donor_id TEXT REFERENCES name(id);
+ALTER TABLE invoice_line ADD donor_id TEXT REFERENCES name(id);
+ALTER TABLE stocktake_line ADD donor_id TEXT REFERENCES name(id);
+
+CREATE VIEW donor_stock_on_hand AS
+SELECT
+    'n/a' as id,
+    item_id,
+    store_id,
+    donor_id,
+    SUM(pack_size * available_number_of_packs) AS available_stock_on_hand
+FROM stock_line
+WHERE stock_line.donor_id IS NOT NULL
+    AND stock_line.available_number_of_packs > 0
+GROUP BY item_id, store_id, donor_id;
+
+CREATE VIEW donor_consumption AS
+SELECT
+    'n/a' as id,
+    invoice_line.item_id AS item_id,
+    invoice.store_id AS store_id,
+    invoice_line.donor_id AS donor_id,
+    invoice_line.number_of_packs * invoice_line.pack_size AS quantity,
+    invoice.picked_datetime::date AS date
+FROM invoice_line
+JOIN invoice
+    ON invoice_line.invoice_id = invoice.id
+WHERE invoice.type = 'OUTBOUND_SHIPMENT'
+    AND invoice.picked_datetime IS NOT NULL
+    AND invoice_line.number_of_packs > 0
+    AND invoice_line.type = 'STOCK_OUT'
+    AND invoice_line.donor_id IS NOT NULL;
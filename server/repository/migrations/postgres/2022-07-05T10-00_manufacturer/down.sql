@@ -0,0 +1,3 @@
+ALTER TABLE stocktake_line DROP COLUMN manufacturer_id;
+ALTER TABLE invoice_line DROP COLUMN manufacturer_id;
+ALTER TABLE stock_line DROP COLUMN manufacturer_id;
@@ -0,0 +1,3 @@
+ALTER TABLE stock_line ADD manufacturer_id TEXT REFERENCES name(id);
+ALTER TABLE invoice_line ADD manufacturer_id TEXT REFERENCES name(id);
+ALTER TABLE stocktake_line ADD manufacturer_id TEXT REFERENCES name(id);
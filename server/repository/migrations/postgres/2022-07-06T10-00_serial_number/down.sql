@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS serial_number_movement;
+DROP TABLE IF EXISTS serial_number;
+DROP TYPE IF EXISTS serial_number_status;
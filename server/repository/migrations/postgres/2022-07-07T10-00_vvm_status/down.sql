@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS vvm_status_log;
+ALTER TABLE stocktake_line DROP COLUMN vvm_status_id;
+ALTER TABLE invoice_line DROP COLUMN vvm_status_id;
+ALTER TABLE stock_line DROP COLUMN vvm_status_id;
+DROP TABLE IF EXISTS vvm_status;
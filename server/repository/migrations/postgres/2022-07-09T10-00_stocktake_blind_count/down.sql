@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS stocktake_line_count;
+ALTER TABLE stocktake_line DROP COLUMN needs_recount;
+ALTER TABLE stocktake DROP COLUMN recount_tolerance;
+ALTER TABLE stocktake DROP COLUMN is_blind_count;
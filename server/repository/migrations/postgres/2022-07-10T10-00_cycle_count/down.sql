@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS cycle_count_plan;
+DROP TABLE IF EXISTS item_classification;
+DROP TYPE IF EXISTS ven_class;
+DROP TYPE IF EXISTS abc_class;
@@ -0,0 +1,34 @@
+UPDATE stocktake SET status = 'NEW' WHERE status = 'REVIEW';
+DELETE FROM user_permission WHERE permission = 'STOCKTAKE_APPROVE';
+
+ALTER TABLE stocktake DROP COLUMN approved_datetime;
+ALTER TABLE stocktake DROP COLUMN approved_user_id;
+ALTER TABLE stocktake DROP COLUMN variance_approval_threshold;
+
+ALTER TYPE stocktake_status RENAME TO stocktake_status_old;
+CREATE TYPE stocktake_status AS ENUM (
+    'NEW',
+    'FINALISED'
+);
+ALTER TABLE stocktake ALTER COLUMN status TYPE stocktake_status USING status::text::stocktake_status;
+DROP TYPE stocktake_status_old;
+
+ALTER TYPE permission_type RENAME TO permission_type_old;
+CREATE TYPE permission_type AS ENUM (
+    'STORE_ACCESS',
+    'LOCATION_MUTATE',
+    'STOCK_LINE_QUERY',
+    'STOCKTAKE_QUERY',
+    'STOCKTAKE_MUTATE',
+    'REQUISITION_QUERY',
+    'REQUISITION_MUTATE',
+    'OUTBOUND_SHIPMENT_QUERY',
+    'OUTBOUND_SHIPMENT_MUTATE',
+    'INBOUND_SHIPMENT_QUERY',
+    'INBOUND_SHIPMENT_MUTATE',
+    'REPORT',
+    'LOG_QUERY',
+    'SERVER_ADMIN'
+);
+ALTER TABLE user_permission ALTER COLUMN permission TYPE permission_type USING permission::text::permission_type;
+DROP TYPE permission_type_old;
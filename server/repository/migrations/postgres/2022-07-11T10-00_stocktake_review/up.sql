@@ -0,0 +1,36 @@
+-- ALTER TYPE ... ADD VALUE can't run inside the migration transaction on Postgres < 12, the enum
+-- types are recreated with the new values instead
+ALTER TYPE stocktake_status RENAME TO stocktake_status_old;
+CREATE TYPE stocktake_status AS ENUM (
+    'NEW',
+    'REVIEW',
+    'FINALISED'
+);
+ALTER TABLE stocktake ALTER COLUMN status TYPE stocktake_status USING status::text::stocktake_status;
+DROP TYPE stocktake_status_old;
+
+ALTER TYPE permission_type RENAME TO permission_type_old;
+CREATE TYPE permission_type AS ENUM (
+    'STORE_ACCESS',
+    'LOCATION_MUTATE',
+    'STOCK_LINE_QUERY',
+    'STOCKTAKE_QUERY',
+    'STOCKTAKE_MUTATE',
+    'STOCKTAKE_APPROVE',
+    'REQUISITION_QUERY',
+    'REQUISITION_MUTATE',
+    'OUTBOUND_SHIPMENT_QUERY',
+    'OUTBOUND_SHIPMENT_MUTATE',
+    'INBOUND_SHIPMENT_QUERY',
+    'INBOUND_SHIPMENT_MUTATE',
+    'REPORT',
+    'LOG_QUERY',
+    'SERVER_ADMIN'
+);
+ALTER TABLE user_permission ALTER COLUMN permission TYPE permission_type USING permission::text::permission_type;
+DROP TYPE permission_type_old;
+
+-- Variance cost value (per line or in total) above which finalising requires approval
+ALTER TABLE stocktake ADD variance_approval_threshold DOUBLE PRECISION;
+ALTER TABLE stocktake ADD approved_user_id TEXT;
+ALTER TABLE stocktake ADD approved_datetime TIMESTAMP;
@@ -0,0 +1,23 @@
+-- ALTER TYPE ... ADD VALUE can't run inside the migration transaction on Postgres < 12, the enum
+-- type is recreated with the new value instead
+ALTER TYPE log_type RENAME TO log_type_old;
+CREATE TYPE log_type AS ENUM (
+    'USER_LOGGED_IN',
+    'INVOICE_CREATED',
+    'INVOICE_DELETED',
+    'INVOICE_STATUS_ALLOCATED',
+    'INVOICE_STATUS_PICKED',
+    'INVOICE_STATUS_SHIPPED',
+    'INVOICE_STATUS_DELIVERED',
+    'INVOICE_STATUS_VERIFIED',
+    'STOCKTAKE_CREATED',
+    'STOCKTAKE_DELETED',
+    'STOCKTAKE_STATUS_FINALISED',
+    'REQUISITION_CREATED',
+    'REQUISITION_DELETED',
+    'REQUISITION_STATUS_SENT',
+    'REQUISITION_STATUS_FINALISED',
+    'STOCK_LINE_EXPIRED_ON_HOLD'
+);
+ALTER TABLE log ALTER COLUMN type TYPE log_type USING type::text::log_type;
+DROP TYPE log_type_old;
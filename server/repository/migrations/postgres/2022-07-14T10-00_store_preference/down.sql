@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS store_preference;
+DROP TYPE IF EXISTS allocation_strategy;
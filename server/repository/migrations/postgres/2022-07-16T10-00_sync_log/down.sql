@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS sync_log;
+DROP TYPE IF EXISTS sync_log_stage;
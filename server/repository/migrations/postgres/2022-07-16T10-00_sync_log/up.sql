@@ -0,0 +1,25 @@
+CREATE TYPE sync_log_stage AS ENUM (
+    'PUSH',
+    'REMOTE_PULL',
+    'CENTRAL_PULL',
+    'INTEGRATE'
+);
+
+-- One row per sync run
+CREATE TABLE sync_log (
+    id TEXT NOT NULL PRIMARY KEY,
+    -- Initial pull of a newly set up site, otherwise a regular sync
+    is_initial_pull BOOLEAN NOT NULL,
+    started_datetime TIMESTAMP NOT NULL,
+    -- Null while the sync is running
+    finished_datetime TIMESTAMP,
+    -- Stage the sync is in, or was in when it finished or failed
+    stage sync_log_stage,
+    pushed_records INTEGER NOT NULL DEFAULT 0,
+    pulled_remote_records INTEGER NOT NULL DEFAULT 0,
+    pulled_central_records INTEGER NOT NULL DEFAULT 0,
+    integrated_records INTEGER NOT NULL DEFAULT 0,
+    -- Records still to be pulled in the current pull stage, if known
+    pull_remaining_records INTEGER,
+    error TEXT
+);
@@ -0,0 +1,19 @@
+DELETE FROM key_value_store WHERE id IN ('SETTINGS_SYNC_QUIET_HOURS_START', 'SETTINGS_SYNC_QUIET_HOURS_END');
+
+ALTER TYPE key_type RENAME TO key_type_old;
+CREATE TYPE key_type AS ENUM (
+    'CENTRAL_SYNC_PULL_CURSOR',
+    'REMOTE_SYNC_INITILISATION_STARTED',
+    'REMOTE_SYNC_INITILISATION_FINISHED',
+    'REMOTE_SYNC_PUSH_CURSOR',
+    'SETTINGS_SYNC_URL',
+    'SETTINGS_SYNC_USERNAME',
+    'SETTINGS_SYNC_PASSWORD_SHA256',
+    'SETTINGS_SYNC_INTERVAL_SEC',
+    'SETTINGS_SYNC_CENTRAL_SERVER_SITE_ID',
+    'SETTINGS_SYNC_SITE_ID',
+    'SETTINGS_SYNC_IS_DISABLED',
+    'SETTINGS_TOKEN_SECRET'
+);
+ALTER TABLE key_value_store ALTER COLUMN id TYPE key_type USING id::text::key_type;
+DROP TYPE key_type_old;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS sync_quarantine;
+DROP TYPE IF EXISTS sync_quarantine_source;
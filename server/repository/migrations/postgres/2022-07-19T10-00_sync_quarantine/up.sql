@@ -0,0 +1,20 @@
+CREATE TYPE sync_quarantine_source AS ENUM (
+    'CENTRAL',
+    'REMOTE'
+);
+
+-- Sync records that failed to translate or integrate, kept so they can be retried
+CREATE TABLE sync_quarantine (
+    id TEXT NOT NULL PRIMARY KEY,
+    source sync_quarantine_source NOT NULL,
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    -- Only set for remote records
+    action TEXT,
+    -- Raw JSON record data
+    data TEXT NOT NULL,
+    error TEXT NOT NULL,
+    attempt_count INTEGER NOT NULL,
+    first_failed_datetime TIMESTAMP NOT NULL,
+    last_failed_datetime TIMESTAMP NOT NULL
+);
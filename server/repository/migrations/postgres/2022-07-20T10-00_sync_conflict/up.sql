@@ -0,0 +1,41 @@
+-- ALTER TYPE ... ADD VALUE can't run inside the migration transaction on Postgres < 12, the enum
+-- type is recreated with the new value instead
+ALTER TYPE key_type RENAME TO key_type_old;
+CREATE TYPE key_type AS ENUM (
+    'CENTRAL_SYNC_PULL_CURSOR',
+    'REMOTE_SYNC_INITILISATION_STARTED',
+    'REMOTE_SYNC_INITILISATION_FINISHED',
+    'REMOTE_SYNC_PUSH_CURSOR',
+    'SETTINGS_SYNC_URL',
+    'SETTINGS_SYNC_USERNAME',
+    'SETTINGS_SYNC_PASSWORD_SHA256',
+    'SETTINGS_SYNC_INTERVAL_SEC',
+    'SETTINGS_SYNC_CENTRAL_SERVER_SITE_ID',
+    'SETTINGS_SYNC_SITE_ID',
+    'SETTINGS_SYNC_IS_DISABLED',
+    'SETTINGS_SYNC_QUIET_HOURS_START',
+    'SETTINGS_SYNC_QUIET_HOURS_END',
+    'SETTINGS_SYNC_CONFLICT_POLICIES',
+    'SETTINGS_TOKEN_SECRET'
+);
+ALTER TABLE key_value_store ALTER COLUMN id TYPE key_type USING id::text::key_type;
+DROP TYPE key_type_old;
+
+CREATE TYPE sync_conflict_resolution AS ENUM (
+    'LOCAL_WINS',
+    'REMOTE_WINS'
+);
+
+-- Remote records that arrived while the same row had local changes that weren't pushed yet
+CREATE TABLE sync_conflict (
+    id TEXT NOT NULL PRIMARY KEY,
+    -- Table name on the central server
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    resolution sync_conflict_resolution NOT NULL,
+    -- Local record as it would have been pushed, not set if the record has been deleted locally
+    local_data TEXT,
+    remote_action TEXT NOT NULL,
+    remote_data TEXT NOT NULL,
+    detected_datetime TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS sync_site_changelog;
+DROP TABLE IF EXISTS sync_site;
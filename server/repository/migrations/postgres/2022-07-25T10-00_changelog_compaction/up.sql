@@ -0,0 +1,8 @@
+-- One row per changelog compaction run
+CREATE TABLE changelog_compaction_log (
+    id TEXT NOT NULL PRIMARY KEY,
+    datetime TIMESTAMP NOT NULL,
+    -- Superseded changelogs before this changelog id have been removed
+    cursor BIGINT NOT NULL,
+    removed_changelogs BIGINT NOT NULL
+);
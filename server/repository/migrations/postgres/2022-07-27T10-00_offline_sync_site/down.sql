@@ -0,0 +1,3 @@
+ALTER TABLE sync_site DROP COLUMN offline_central_cursor;
+ALTER TABLE sync_site DROP COLUMN offline_import_sequence;
+ALTER TABLE sync_site DROP COLUMN offline_export_sequence;
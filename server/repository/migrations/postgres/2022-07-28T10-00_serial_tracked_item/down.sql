@@ -0,0 +1,3 @@
+DROP TABLE invoice_line_serial_number;
+
+ALTER TABLE item DROP COLUMN is_serial_tracked;
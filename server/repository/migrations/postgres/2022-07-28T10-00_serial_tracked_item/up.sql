@@ -0,0 +1,11 @@
+-- Units of serial tracked items, e.g. fridges, have their serial numbers recorded on shipments
+ALTER TABLE item ADD is_serial_tracked BOOLEAN NOT NULL DEFAULT false;
+
+-- Serial numbers recorded on an invoice line, the serial numbers are moved when the units are
+-- received (inbound shipment delivered) or issued (outbound shipment picked)
+CREATE TABLE invoice_line_serial_number (
+    id TEXT NOT NULL PRIMARY KEY,
+    invoice_line_id TEXT NOT NULL REFERENCES invoice_line(id),
+    serial_number TEXT NOT NULL,
+    UNIQUE (invoice_line_id, serial_number)
+);
@@ -0,0 +1,21 @@
+CREATE TABLE serial_number (
+    id TEXT NOT NULL PRIMARY KEY,
+    item_id TEXT NOT NULL REFERENCES item(id),
+    store_id TEXT NOT NULL REFERENCES store(id),
+    serial_number TEXT NOT NULL,
+    status TEXT CHECK (status IN ('IN_STORE', 'ISSUED', 'IN_REPAIR', 'DECOMMISSIONED')) NOT NULL,
+    -- Name currently holding the unit, e.g. the facility it was issued to
+    holder_name_id TEXT REFERENCES name(id),
+    note TEXT,
+    UNIQUE (item_id, serial_number)
+);
+
+CREATE TABLE serial_number_movement (
+    id TEXT NOT NULL PRIMARY KEY,
+    serial_number_id TEXT NOT NULL REFERENCES serial_number(id),
+    store_id TEXT NOT NULL REFERENCES store(id),
+    invoice_line_id TEXT REFERENCES invoice_line(id),
+    status TEXT CHECK (status IN ('IN_STORE', 'ISSUED', 'IN_REPAIR', 'DECOMMISSIONED')) NOT NULL,
+    holder_name_id TEXT REFERENCES name(id),
+    datetime TIMESTAMP NOT NULL
+);
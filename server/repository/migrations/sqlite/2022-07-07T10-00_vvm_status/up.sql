@@ -0,0 +1,33 @@
+-- Vaccine vial monitor stages, e.g. stage 1 (usable) to stage 4 (discard)
+CREATE TABLE vvm_status (
+    id TEXT NOT NULL PRIMARY KEY,
+    code TEXT NOT NULL,
+    description TEXT NOT NULL,
+    -- Higher levels have had more heat exposure
+    level INTEGER NOT NULL,
+    is_active BOOLEAN NOT NULL,
+    unusable BOOLEAN NOT NULL
+);
+
+-- WHO VVM stages, stage 3 and 4 vials must not be used
+INSERT INTO vvm_status (id, code, description, level, is_active, unusable) VALUES
+    ('VVM_STAGE_1', 'VVM1', 'Stage 1: inner square lighter than outer circle', 1, 1, 0),
+    ('VVM_STAGE_2', 'VVM2', 'Stage 2: inner square lighter than outer circle, use first', 2, 1, 0),
+    ('VVM_STAGE_3', 'VVM3', 'Stage 3: inner square matches outer circle, discard', 3, 1, 1),
+    ('VVM_STAGE_4', 'VVM4', 'Stage 4: inner square darker than outer circle, discard', 4, 1, 1);
+
+ALTER TABLE stock_line ADD vvm_status_id TEXT REFERENCES vvm_status(id);
+ALTER TABLE invoice_line ADD vvm_status_id TEXT REFERENCES vvm_status(id);
+ALTER TABLE stocktake_line ADD vvm_status_id TEXT REFERENCES vvm_status(id);
+
+CREATE TABLE vvm_status_log (
+    id TEXT NOT NULL PRIMARY KEY,
+    status_id TEXT NOT NULL REFERENCES vvm_status(id),
+    stock_line_id TEXT NOT NULL REFERENCES stock_line(id),
+    -- Set when the status was recorded on receipt or at issue
+    invoice_line_id TEXT REFERENCES invoice_line(id),
+    -- Set when the status was recorded during a stocktake
+    stocktake_line_id TEXT REFERENCES stocktake_line(id),
+    comment TEXT,
+    created_datetime TIMESTAMP NOT NULL
+);
@@ -0,0 +1,4 @@
+DROP TABLE open_vial_wastage;
+
+ALTER TABLE item DROP COLUMN is_vaccine;
+ALTER TABLE item DROP COLUMN doses;
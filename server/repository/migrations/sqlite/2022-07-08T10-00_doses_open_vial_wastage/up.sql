@@ -0,0 +1,17 @@
+-- Doses per unit, e.g. 10 for a 10 dose vial, 0 when the item isn't counted in doses
+ALTER TABLE item ADD doses INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE item ADD is_vaccine BOOLEAN NOT NULL DEFAULT 0;
+
+-- Doses discarded after a multi-dose vial was opened and only partially used
+CREATE TABLE open_vial_wastage (
+    id TEXT NOT NULL PRIMARY KEY,
+    store_id TEXT NOT NULL REFERENCES store(id),
+    item_id TEXT NOT NULL REFERENCES item(id),
+    stock_line_id TEXT NOT NULL REFERENCES stock_line(id),
+    -- Set when the vial was opened for an outbound shipment
+    invoice_line_id TEXT REFERENCES invoice_line(id),
+    doses_used INTEGER NOT NULL,
+    doses_wasted INTEGER NOT NULL,
+    opened_datetime TIMESTAMP NOT NULL,
+    comment TEXT
+);
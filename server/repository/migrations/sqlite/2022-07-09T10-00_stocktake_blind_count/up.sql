@@ -0,0 +1,15 @@
+-- Blind counts hide snapshot quantities from counters
+ALTER TABLE stocktake ADD is_blind_count BOOLEAN NOT NULL DEFAULT 0;
+-- Number of packs independent counts of a line may differ by before a recount is required
+ALTER TABLE stocktake ADD recount_tolerance INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE stocktake_line ADD needs_recount BOOLEAN NOT NULL DEFAULT 0;
+
+-- Independent counts of a stocktake line, one per counting user
+CREATE TABLE stocktake_line_count (
+    id TEXT NOT NULL PRIMARY KEY,
+    stocktake_line_id TEXT NOT NULL REFERENCES stocktake_line(id),
+    user_id TEXT NOT NULL REFERENCES user_account(id),
+    counted_number_of_packs INTEGER NOT NULL,
+    counted_datetime TIMESTAMP NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS cycle_count_plan;
+DROP TABLE IF EXISTS item_classification;
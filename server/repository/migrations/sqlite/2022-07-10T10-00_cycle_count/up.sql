@@ -0,0 +1,30 @@
+-- ABC (by consumption value) and VEN (vital, essential, non-essential) class of an item in a store
+CREATE TABLE item_classification (
+    id TEXT NOT NULL PRIMARY KEY,
+    store_id TEXT NOT NULL REFERENCES store(id),
+    item_id TEXT NOT NULL REFERENCES item(id),
+    abc_class TEXT CHECK (abc_class IN ('A', 'B', 'C')) NOT NULL,
+    ven_class TEXT CHECK (ven_class IN ('V', 'E', 'N')),
+    -- Consumption value over the lookback period of the last calculation
+    consumption_value DOUBLE PRECISION NOT NULL,
+    -- Set when a user edited the ABC class, recalculation then keeps the class
+    is_abc_manual BOOLEAN NOT NULL,
+    updated_datetime TIMESTAMP NOT NULL,
+    UNIQUE (store_id, item_id)
+);
+
+-- Weekly cycle count schedule, items of a class are counted every {class}_interval_weeks
+CREATE TABLE cycle_count_plan (
+    id TEXT NOT NULL PRIMARY KEY,
+    store_id TEXT NOT NULL REFERENCES store(id),
+    -- User that scheduled stocktakes are created for
+    user_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    start_date DATE NOT NULL,
+    a_interval_weeks INTEGER NOT NULL,
+    b_interval_weeks INTEGER NOT NULL,
+    c_interval_weeks INTEGER NOT NULL,
+    is_active BOOLEAN NOT NULL,
+    -- Cycle week (weeks since start_date) of the last created stocktake
+    last_scheduled_week INTEGER
+);
@@ -0,0 +1,91 @@
+PRAGMA defer_foreign_keys = ON;
+
+CREATE TABLE stocktake_copy AS SELECT * FROM stocktake;
+DROP TABLE stocktake;
+
+CREATE TABLE stocktake (
+    id TEXT NOT NULL PRIMARY KEY,
+    store_id TEXT NOT NULL REFERENCES store(id),
+    -- Change to reference user_accoun once users are syncing
+    user_id TEXT NOT NULL,
+    stocktake_number INTEGER NOT NULL,
+    comment	TEXT,
+    description TEXT,
+    status TEXT CHECK (status IN ('NEW', 'FINALISED')) NOT NULL,
+    created_datetime TEXT NOT NULL,
+    stocktake_date TEXT,
+    finalised_datetime TEXT,
+    is_locked BOOLEAN,
+    inventory_adjustment_id TEXT REFERENCES invoice(id),
+    is_blind_count BOOLEAN NOT NULL DEFAULT 0,
+    recount_tolerance INTEGER NOT NULL DEFAULT 0
+);
+
+INSERT INTO stocktake
+SELECT
+    id,
+    store_id,
+    user_id,
+    stocktake_number,
+    comment,
+    description,
+    CASE WHEN status = 'REVIEW' THEN 'NEW' ELSE status END,
+    created_datetime,
+    stocktake_date,
+    finalised_datetime,
+    is_locked,
+    inventory_adjustment_id,
+    is_blind_count,
+    recount_tolerance
+FROM stocktake_copy;
+DROP TABLE stocktake_copy;
+
+CREATE TRIGGER stocktake_insert_trigger
+  AFTER INSERT ON stocktake
+  BEGIN
+    INSERT INTO changelog (table_name, row_id, row_action)
+      VALUES ('stocktake', NEW.id, 'UPSERT');
+  END;
+
+CREATE TRIGGER stocktake_update_trigger
+  AFTER UPDATE ON stocktake
+  BEGIN
+    INSERT INTO changelog (table_name, row_id, row_action)
+      VALUES ('stocktake', NEW.id, 'UPSERT');
+  END;
+
+CREATE TRIGGER stocktake_delete_trigger
+  AFTER DELETE ON stocktake
+  BEGIN
+    INSERT INTO changelog (table_name, row_id, row_action)
+      VALUES ('stocktake', OLD.id, 'DELETE');
+  END;
+
+DELETE FROM user_permission WHERE permission = 'STOCKTAKE_APPROVE';
+CREATE TABLE user_permission_copy AS SELECT * FROM user_permission;
+DROP TABLE user_permission;
+
+CREATE TABLE user_permission (
+    id TEXT NOT NULL PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES user_account(id),
+    store_id TEXT NOT NULL REFERENCES store(id),
+    permission TEXT CHECK (permission IN (
+        'STORE_ACCESS',
+        'LOCATION_MUTATE',
+        'STOCK_LINE_QUERY',
+        'STOCKTAKE_QUERY',
+        'STOCKTAKE_MUTATE',
+        'REQUISITION_QUERY',
+        'REQUISITION_MUTATE',
+        'OUTBOUND_SHIPMENT_QUERY',
+        'OUTBOUND_SHIPMENT_MUTATE',
+        'INBOUND_SHIPMENT_QUERY',
+        'INBOUND_SHIPMENT_MUTATE',
+        'REPORT',
+        'LOG_QUERY',
+        'SERVER_ADMIN'
+    )) NOT NULL
+);
+
+INSERT INTO user_permission SELECT id, user_id, store_id, permission FROM user_permission_copy;
+DROP TABLE user_permission_copy;
@@ -0,0 +1,116 @@
+-- SQLite can't alter CHECK constraints, so the stocktake and user_permission tables are rebuilt.
+-- Dropping a parent table deletes its rows, with deferred foreign keys the stocktake line
+-- references are satisfied again once the rows are copied back into the new table.
+PRAGMA defer_foreign_keys = ON;
+
+CREATE TABLE stocktake_copy AS SELECT * FROM stocktake;
+DROP TABLE stocktake;
+
+CREATE TABLE stocktake (
+    id TEXT NOT NULL PRIMARY KEY,
+    store_id TEXT NOT NULL REFERENCES store(id),
+    -- Change to reference user_accoun once users are syncing
+    user_id TEXT NOT NULL,
+    stocktake_number INTEGER NOT NULL,
+    comment	TEXT,
+    description TEXT,
+    status TEXT CHECK (status IN ('NEW', 'REVIEW', 'FINALISED')) NOT NULL,
+    created_datetime TEXT NOT NULL,
+    stocktake_date TEXT,
+    finalised_datetime TEXT,
+    is_locked BOOLEAN,
+    inventory_adjustment_id TEXT REFERENCES invoice(id),
+    -- Blind counts hide snapshot quantities from counters
+    is_blind_count BOOLEAN NOT NULL DEFAULT 0,
+    -- Number of packs independent counts of a line may differ by before a recount is required
+    recount_tolerance INTEGER NOT NULL DEFAULT 0,
+    -- Variance cost value (per line or in total) above which finalising requires approval
+    variance_approval_threshold DOUBLE PRECISION,
+    approved_user_id TEXT,
+    approved_datetime TIMESTAMP
+);
+
+INSERT INTO stocktake (
+    id,
+    store_id,
+    user_id,
+    stocktake_number,
+    comment,
+    description,
+    status,
+    created_datetime,
+    stocktake_date,
+    finalised_datetime,
+    is_locked,
+    inventory_adjustment_id,
+    is_blind_count,
+    recount_tolerance
+)
+SELECT
+    id,
+    store_id,
+    user_id,
+    stocktake_number,
+    comment,
+    description,
+    status,
+    created_datetime,
+    stocktake_date,
+    finalised_datetime,
+    is_locked,
+    inventory_adjustment_id,
+    is_blind_count,
+    recount_tolerance
+FROM stocktake_copy;
+DROP TABLE stocktake_copy;
+
+-- Triggers are dropped with the table, recreate them after copying the rows back
+CREATE TRIGGER stocktake_insert_trigger
+  AFTER INSERT ON stocktake
+  BEGIN
+    INSERT INTO changelog (table_name, row_id, row_action)
+      VALUES ('stocktake', NEW.id, 'UPSERT');
+  END;
+
+CREATE TRIGGER stocktake_update_trigger
+  AFTER UPDATE ON stocktake
+  BEGIN
+    INSERT INTO changelog (table_name, row_id, row_action)
+      VALUES ('stocktake', NEW.id, 'UPSERT');
+  END;
+
+CREATE TRIGGER stocktake_delete_trigger
+  AFTER DELETE ON stocktake
+  BEGIN
+    INSERT INTO changelog (table_name, row_id, row_action)
+      VALUES ('stocktake', OLD.id, 'DELETE');
+  END;
+
+CREATE TABLE user_permission_copy AS SELECT * FROM user_permission;
+DROP TABLE user_permission;
+
+CREATE TABLE user_permission (
+    id TEXT NOT NULL PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES user_account(id),
+    store_id TEXT NOT NULL REFERENCES store(id),
+    permission TEXT CHECK (permission IN (
+        'STORE_ACCESS',
+        'LOCATION_MUTATE',
+        'STOCK_LINE_QUERY',
+        'STOCKTAKE_QUERY',
+        'STOCKTAKE_MUTATE',
+        'STOCKTAKE_APPROVE',
+        'REQUISITION_QUERY',
+        'REQUISITION_MUTATE',
+        'OUTBOUND_SHIPMENT_QUERY',
+        'OUTBOUND_SHIPMENT_MUTATE',
+        'INBOUND_SHIPMENT_QUERY',
+        'INBOUND_SHIPMENT_MUTATE',
+        'REPORT',
+        'LOG_QUERY',
+        'SERVER_ADMIN'
+    )) NOT NULL
+);
+
+INSERT INTO user_permission SELECT id, user_id, store_id, permission FROM user_permission_copy;
+DROP TABLE user_permission_copy;
@@ -0,0 +1,33 @@
+-- Stock movements per invoice line, used to work out the stock on hand at a point in time.
+-- Same movements as in the stock_movement view but keeping the stock line and batch.
+CREATE VIEW stock_line_movement AS
+SELECT
+    invoice_line.id AS id,
+    invoice_line.stock_line_id AS stock_line_id,
+    invoice_line.item_id AS item_id,
+    invoice.store_id AS store_id,
+    invoice_line.batch AS batch,
+    CASE
+        WHEN invoice_line.type = 'STOCK_IN' THEN invoice_line.number_of_packs * invoice_line.pack_size
+        ELSE invoice_line.number_of_packs * invoice_line.pack_size * -1
+    END AS quantity,
+    CASE
+        WHEN invoice.type = 'OUTBOUND_SHIPMENT' THEN invoice.picked_datetime
+        WHEN invoice.type = 'INBOUND_SHIPMENT' THEN invoice.delivered_datetime
+        ELSE invoice.verified_datetime
+    END AS datetime
+FROM invoice_line
+JOIN invoice
+    ON invoice_line.invoice_id = invoice.id
+WHERE invoice_line.number_of_packs > 0
+    AND (
+        (invoice.type = 'OUTBOUND_SHIPMENT'
+            AND invoice.picked_datetime IS NOT NULL
+            AND invoice_line.type = 'STOCK_OUT')
+        OR (invoice.type = 'INBOUND_SHIPMENT'
+            AND invoice.delivered_datetime IS NOT NULL
+            AND invoice_line.type = 'STOCK_IN')
+        OR (invoice.type = 'INVENTORY_ADJUSTMENT'
+            AND invoice.verified_datetime IS NOT NULL
+            AND invoice_line.type IN ('STOCK_IN', 'STOCK_OUT'))
+    );
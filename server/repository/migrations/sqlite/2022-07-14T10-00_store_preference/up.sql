@@ -0,0 +1,14 @@
+-- Store level settings, stores without a row use the defaults
+CREATE TABLE store_preference (
+    -- Id of the store
+    id TEXT NOT NULL PRIMARY KEY REFERENCES store(id),
+    -- Order in which stock lines are used when allocating outbound shipment lines
+    allocation_strategy TEXT CHECK (allocation_strategy IN (
+        'EXPIRY_FIRST',
+        'FIRST_IN_FIRST_OUT',
+        'PREFERRED_LOCATION',
+        'WHOLE_PACK_FIRST'
+    )) NOT NULL DEFAULT 'EXPIRY_FIRST',
+    -- Location stock is picked from first with the PREFERRED_LOCATION strategy
+    pick_face_location_id TEXT REFERENCES location(id)
+);
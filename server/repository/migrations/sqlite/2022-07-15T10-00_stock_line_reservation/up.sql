@@ -0,0 +1,19 @@
+-- Packs of a stock line held for a customer or a named campaign until a date. Reserved packs stay
+-- in the stock line's available_number_of_packs (which is synced), they are subtracted when
+-- checking the packs available to other customers.
+CREATE TABLE stock_line_reservation (
+    id TEXT NOT NULL PRIMARY KEY,
+    store_id TEXT NOT NULL REFERENCES store(id),
+    stock_line_id TEXT NOT NULL REFERENCES stock_line(id),
+    -- Customer the stock is reserved for, not set for campaign reservations
+    name_id TEXT REFERENCES name(id),
+    -- Campaign or programme the stock is reserved for, not set for customer reservations
+    campaign TEXT,
+    -- Packs still held, reduced when issued to the reserved customer or campaign
+    number_of_packs INTEGER NOT NULL,
+    -- Last day the stock is held
+    reserved_until DATE NOT NULL,
+    created_datetime TIMESTAMP NOT NULL,
+    -- Set when the packs still held were returned to the available stock
+    released_datetime TIMESTAMP
+);
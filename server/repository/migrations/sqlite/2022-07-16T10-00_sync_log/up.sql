@@ -0,0 +1,23 @@
+-- One row per sync run
+CREATE TABLE sync_log (
+    id TEXT NOT NULL PRIMARY KEY,
+    -- Initial pull of a newly set up site, otherwise a regular sync
+    is_initial_pull BOOLEAN NOT NULL,
+    started_datetime TIMESTAMP NOT NULL,
+    -- Null while the sync is running
+    finished_datetime TIMESTAMP,
+    -- Stage the sync is in, or was in when it finished or failed
+    stage TEXT CHECK (stage IN (
+        'PUSH',
+        'REMOTE_PULL',
+        'CENTRAL_PULL',
+        'INTEGRATE'
+    )),
+    pushed_records INTEGER NOT NULL DEFAULT 0,
+    pulled_remote_records INTEGER NOT NULL DEFAULT 0,
+    pulled_central_records INTEGER NOT NULL DEFAULT 0,
+    integrated_records INTEGER NOT NULL DEFAULT 0,
+    -- Records still to be pulled in the current pull stage, if known
+    pull_remaining_records INTEGER,
+    error TEXT
+);
@@ -0,0 +1,15 @@
+-- Sync records that failed to translate or integrate, kept so they can be retried
+CREATE TABLE sync_quarantine (
+    id TEXT NOT NULL PRIMARY KEY,
+    source TEXT NOT NULL CHECK (source IN ('CENTRAL', 'REMOTE')),
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    -- Only set for remote records
+    action TEXT,
+    -- Raw JSON record data
+    data TEXT NOT NULL,
+    error TEXT NOT NULL,
+    attempt_count INTEGER NOT NULL,
+    first_failed_datetime TIMESTAMP NOT NULL,
+    last_failed_datetime TIMESTAMP NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Remote records that arrived while the same row had local changes that weren't pushed yet
+CREATE TABLE sync_conflict (
+    id TEXT NOT NULL PRIMARY KEY,
+    -- Table name on the central server
+    table_name TEXT NOT NULL,
+    record_id TEXT NOT NULL,
+    resolution TEXT NOT NULL CHECK (resolution IN ('LOCAL_WINS', 'REMOTE_WINS')),
+    -- Local record as it would have been pushed, not set if the record has been deleted locally
+    local_data TEXT,
+    remote_action TEXT NOT NULL,
+    remote_data TEXT NOT NULL,
+    detected_datetime TIMESTAMP NOT NULL
+);
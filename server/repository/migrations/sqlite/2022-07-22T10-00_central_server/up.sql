@@ -0,0 +1,19 @@
+-- Remote sites that sync with this server, when running as central server
+CREATE TABLE sync_site (
+    -- Site id as configured in the sync settings of the site
+    id INTEGER NOT NULL PRIMARY KEY,
+    -- Sync username of the site
+    name TEXT NOT NULL UNIQUE,
+    password_sha256 TEXT NOT NULL,
+    -- Set when the site initialises, the site can't sync from other hardware afterwards
+    hardware_id TEXT,
+    -- First changelog id that hasn't been acknowledged by the site
+    queue_cursor BIGINT NOT NULL,
+    initialised_datetime TIMESTAMP
+);
+
+-- Changelogs from integrating records pushed by a site, these aren't queued for the same site
+CREATE TABLE sync_site_changelog (
+    changelog_id BIGINT NOT NULL PRIMARY KEY,
+    site_id INTEGER NOT NULL REFERENCES sync_site(id)
+);
@@ -0,0 +1,11 @@
+ALTER TABLE item DROP COLUMN essential_drug_list;
+ALTER TABLE item DROP COLUMN weight;
+ALTER TABLE item DROP COLUMN volume_per_pack;
+ALTER TABLE item DROP COLUMN outer_pack_size;
+ALTER TABLE item DROP COLUMN strength;
+ALTER TABLE item DROP COLUMN atc_category;
+ALTER TABLE item DROP COLUMN department_id;
+ALTER TABLE item DROP COLUMN category_id;
+
+DROP TABLE item_department;
+DROP TABLE item_category;
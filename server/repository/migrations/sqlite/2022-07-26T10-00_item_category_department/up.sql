@@ -0,0 +1,23 @@
+-- Item categories from the central server, categories can have a parent category
+CREATE TABLE item_category (
+    id TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL,
+    parent_id TEXT
+);
+
+CREATE TABLE item_department (
+    id TEXT NOT NULL PRIMARY KEY,
+    name TEXT NOT NULL
+);
+
+-- No foreign keys, the central server might send items referencing categories or departments
+-- that haven't been synced
+ALTER TABLE item ADD category_id TEXT;
+ALTER TABLE item ADD department_id TEXT;
+-- ATC code, e.g. "J07BC01"
+ALTER TABLE item ADD atc_category TEXT NOT NULL DEFAULT '';
+ALTER TABLE item ADD strength TEXT NOT NULL DEFAULT '';
+ALTER TABLE item ADD outer_pack_size INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE item ADD volume_per_pack REAL NOT NULL DEFAULT 0;
+ALTER TABLE item ADD weight REAL NOT NULL DEFAULT 0;
+ALTER TABLE item ADD essential_drug_list BOOLEAN NOT NULL DEFAULT 0;
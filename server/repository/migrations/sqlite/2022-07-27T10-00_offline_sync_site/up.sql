@@ -0,0 +1,6 @@
+-- Offline sync files exchanged with the site, files with a sequence at or below the last imported
+-- one are rejected
+ALTER TABLE sync_site ADD offline_export_sequence BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE sync_site ADD offline_import_sequence BIGINT NOT NULL DEFAULT 0;
+-- Last central record integrated by the site, as reported in the last imported offline sync file
+ALTER TABLE sync_site ADD offline_central_cursor BIGINT NOT NULL DEFAULT 0;
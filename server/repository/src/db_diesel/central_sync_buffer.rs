@@ -54,13 +54,13 @@ impl<'a> CentralSyncBufferRepository<'a> {
         Ok(())
     }
 
-    pub async fn remove_all(&self) -> Result<(), RepositoryError> {
+    pub fn remove_all(&self) -> Result<(), RepositoryError> {
         diesel::delete(central_sync_buffer).execute(&self.connection.connection)?;
         Ok(())
     }
 
     // Retrieves all sync entries for a given table and returns them in asc order.
-    pub async fn get_sync_entries(
+    pub fn get_sync_entries(
         &self,
         table: &str,
     ) -> Result<Vec<CentralSyncBufferRow>, RepositoryError> {
@@ -70,4 +70,26 @@ impl<'a> CentralSyncBufferRepository<'a> {
             .load(&self.connection.connection)?;
         Ok(result)
     }
+
+    /// Records after the `cursor` in asc order, used as central sync log when running as central
+    /// server
+    pub fn find_after(
+        &self,
+        cursor: i32,
+        limit: u32,
+    ) -> Result<Vec<CentralSyncBufferRow>, RepositoryError> {
+        let result = central_sync_buffer
+            .filter(id.gt(cursor))
+            .order(id.asc())
+            .limit(limit.into())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn max_id(&self) -> Result<Option<i32>, RepositoryError> {
+        let result = central_sync_buffer
+            .select(diesel::dsl::max(id))
+            .first(&self.connection.connection)?;
+        Ok(result)
+    }
 }
@@ -0,0 +1,58 @@
+use super::{
+    changelog_compaction_log_row::changelog_compaction_log::dsl as changelog_compaction_log_dsl,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    changelog_compaction_log (id) {
+        id -> Text,
+        datetime -> Timestamp,
+        cursor -> BigInt,
+        removed_changelogs -> BigInt,
+    }
+}
+
+/// A changelog compaction run
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[table_name = "changelog_compaction_log"]
+pub struct ChangelogCompactionLogRow {
+    pub id: String,
+    pub datetime: NaiveDateTime,
+    /// Superseded changelogs before this changelog id have been removed
+    pub cursor: i64,
+    pub removed_changelogs: i64,
+}
+
+pub struct ChangelogCompactionLogRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ChangelogCompactionLogRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ChangelogCompactionLogRowRepository { connection }
+    }
+
+    pub fn insert_one(&self, row: &ChangelogCompactionLogRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(changelog_compaction_log_dsl::changelog_compaction_log)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Most recent runs first
+    pub fn find_latest(
+        &self,
+        limit: u32,
+    ) -> Result<Vec<ChangelogCompactionLogRow>, RepositoryError> {
+        let result = changelog_compaction_log_dsl::changelog_compaction_log
+            .order(changelog_compaction_log_dsl::datetime.desc())
+            .limit(limit.into())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
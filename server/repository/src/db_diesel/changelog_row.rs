@@ -1,6 +1,11 @@
 use diesel::prelude::*;
 
-use super::{changelog_row::changelog_deduped::dsl as changelog_deduped_dsl, StorageConnection};
+use super::{
+    changelog_row::{
+        changelog::dsl as changelog_dsl, changelog_deduped::dsl as changelog_deduped_dsl,
+    },
+    StorageConnection,
+};
 
 use crate::RepositoryError;
 use std::convert::TryInto;
@@ -80,6 +85,56 @@ impl<'a> ChangelogRowRepository<'a> {
         Ok(result)
     }
 
+    /// Latest changelog entry of every row changed since `earliest` (inclusive), i.e. the latest
+    /// version of the rows that haven't been pushed yet if `earliest` is the push cursor
+    pub fn outstanding_changelogs(
+        &self,
+        earliest: u64,
+    ) -> Result<Vec<ChangelogRow>, RepositoryError> {
+        let result = changelog_deduped_dsl::changelog_deduped
+            .filter(changelog_deduped_dsl::id.ge(earliest.try_into().unwrap_or(0)))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Number of rows changed since `earliest` (inclusive)
+    pub fn count(&self, earliest: u64) -> Result<i64, RepositoryError> {
+        let result = changelog_deduped_dsl::changelog_deduped
+            .filter(changelog_deduped_dsl::id.ge(earliest.try_into().unwrap_or(0)))
+            .count()
+            .get_result(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// All changelog entries (not deduped) of the given rows since `earliest` (inclusive)
+    pub fn changelogs_for_rows(
+        &self,
+        earliest: u64,
+        row_ids: &[String],
+    ) -> Result<Vec<ChangelogRow>, RepositoryError> {
+        let result = changelog_dsl::changelog
+            .filter(changelog_dsl::id.ge(earliest.try_into().unwrap_or(0)))
+            .filter(changelog_dsl::row_id.eq_any(row_ids))
+            .order(changelog_dsl::id.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Removes changelog entries before `cursor` (exclusive) that have been superseded by a newer
+    /// entry for the same row, i.e. entries that don't show up in the deduped changelog anymore.
+    /// Returns the number of removed entries.
+    pub fn delete_superseded(&self, cursor: u64) -> Result<usize, RepositoryError> {
+        let result = diesel::delete(
+            changelog_dsl::changelog
+                .filter(changelog_dsl::id.lt(cursor.try_into().unwrap_or(0)))
+                .filter(changelog_dsl::id.ne_all(
+                    changelog_deduped_dsl::changelog_deduped.select(changelog_deduped_dsl::id),
+                )),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(result)
+    }
+
     pub fn latest_changelog(&self) -> Result<Option<ChangelogRow>, RepositoryError> {
         let result = changelog_deduped_dsl::changelog_deduped
             .order(changelog_deduped_dsl::id.desc())
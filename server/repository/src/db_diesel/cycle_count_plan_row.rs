@@ -0,0 +1,121 @@
+use super::{
+    cycle_count_plan_row::cycle_count_plan::dsl as cycle_count_plan_dsl, store_row::store,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use util::Defaults;
+
+table! {
+    cycle_count_plan (id) {
+        id -> Text,
+        store_id -> Text,
+        user_id -> Text,
+        name -> Text,
+        start_date -> Date,
+        a_interval_weeks -> Integer,
+        b_interval_weeks -> Integer,
+        c_interval_weeks -> Integer,
+        is_active -> Bool,
+        last_scheduled_week -> Nullable<Integer>,
+    }
+}
+
+joinable!(cycle_count_plan -> store (store_id));
+
+/// Weekly cycle count schedule of a store, items of each ABC class are split into as many groups
+/// as the class interval and one group is counted each week
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "cycle_count_plan"]
+pub struct CycleCountPlanRow {
+    pub id: String,
+    pub store_id: String,
+    /// User that scheduled stocktakes are created for
+    pub user_id: String,
+    pub name: String,
+    /// First day of cycle week 0
+    pub start_date: NaiveDate,
+    pub a_interval_weeks: i32,
+    pub b_interval_weeks: i32,
+    pub c_interval_weeks: i32,
+    pub is_active: bool,
+    /// Cycle week of the last stocktake created by the scheduler
+    pub last_scheduled_week: Option<i32>,
+}
+
+impl Default for CycleCountPlanRow {
+    fn default() -> Self {
+        Self {
+            start_date: Defaults::naive_date(),
+            // Defaults
+            id: Default::default(),
+            store_id: Default::default(),
+            user_id: Default::default(),
+            name: Default::default(),
+            a_interval_weeks: Default::default(),
+            b_interval_weeks: Default::default(),
+            c_interval_weeks: Default::default(),
+            is_active: Default::default(),
+            last_scheduled_week: Default::default(),
+        }
+    }
+}
+
+pub struct CycleCountPlanRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> CycleCountPlanRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        CycleCountPlanRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &CycleCountPlanRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(cycle_count_plan_dsl::cycle_count_plan)
+            .values(row)
+            .on_conflict(cycle_count_plan_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &CycleCountPlanRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(cycle_count_plan_dsl::cycle_count_plan)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<CycleCountPlanRow>, RepositoryError> {
+        let result = cycle_count_plan_dsl::cycle_count_plan
+            .filter(cycle_count_plan_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+        let result = cycle_count_plan_dsl::cycle_count_plan
+            .filter(cycle_count_plan_dsl::store_id.eq(store_id))
+            .order(cycle_count_plan_dsl::name.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn find_active(&self) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+        let result = cycle_count_plan_dsl::cycle_count_plan
+            .filter(cycle_count_plan_dsl::is_active.eq(true))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,98 @@
+use super::{donor_consumption::donor_consumption::dsl as donor_consumption_dsl, StorageConnection};
+
+use crate::{
+    diesel_macros::{apply_date_time_filter, apply_equal_filter},
+    DateFilter, EqualFilter, RepositoryError,
+};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::{QueryDsl, RunQueryDsl};
+
+table! {
+    donor_consumption (id) {
+        id -> Text,
+        item_id -> Text,
+        store_id -> Text,
+        donor_id -> Text,
+        quantity -> Integer,
+        date -> Date,
+    }
+}
+
+/// Quantity issued in outbound shipments from stock lines with a donor
+#[derive(Clone, Queryable, Debug, PartialEq)]
+pub struct DonorConsumptionRow {
+    pub id: String,
+    pub item_id: String,
+    pub store_id: String,
+    pub donor_id: String,
+    pub quantity: i32,
+    pub date: NaiveDate,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DonorConsumptionFilter {
+    pub item_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub donor_id: Option<EqualFilter<String>>,
+    pub date: Option<DateFilter>,
+}
+
+pub struct DonorConsumptionRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> DonorConsumptionRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        DonorConsumptionRepository { connection }
+    }
+
+    pub fn query(
+        &self,
+        filter: Option<DonorConsumptionFilter>,
+    ) -> Result<Vec<DonorConsumptionRow>, RepositoryError> {
+        let mut query = donor_consumption_dsl::donor_consumption.into_boxed();
+
+        if let Some(f) = filter {
+            let DonorConsumptionFilter {
+                item_id,
+                store_id,
+                donor_id,
+                date,
+            } = f;
+
+            apply_equal_filter!(query, item_id, donor_consumption_dsl::item_id);
+            apply_equal_filter!(query, store_id, donor_consumption_dsl::store_id);
+            apply_equal_filter!(query, donor_id, donor_consumption_dsl::donor_id);
+            apply_date_time_filter!(query, date, donor_consumption_dsl::date);
+        }
+
+        Ok(query.load::<DonorConsumptionRow>(&self.connection.connection)?)
+    }
+}
+
+impl DonorConsumptionFilter {
+    pub fn new() -> DonorConsumptionFilter {
+        DonorConsumptionFilter::default()
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn donor_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.donor_id = Some(filter);
+        self
+    }
+
+    pub fn date(mut self, filter: DateFilter) -> Self {
+        self.date = Some(filter);
+        self
+    }
+}
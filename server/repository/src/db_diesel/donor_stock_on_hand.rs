@@ -0,0 +1,87 @@
+use super::{
+    donor_stock_on_hand::donor_stock_on_hand::dsl as donor_stock_on_hand_dsl, StorageConnection,
+};
+
+use crate::{diesel_macros::apply_equal_filter, EqualFilter, RepositoryError};
+use diesel::prelude::*;
+use diesel::{QueryDsl, RunQueryDsl};
+
+table! {
+    donor_stock_on_hand (id) {
+        id -> Text,
+        item_id -> Text,
+        store_id -> Text,
+        donor_id -> Text,
+        available_stock_on_hand -> BigInt,
+    }
+}
+
+/// Available stock on hand per item and store, grouped by the donor of the stock lines.
+/// Only stock lines with a donor are included.
+#[derive(Clone, Queryable, Debug, PartialEq)]
+pub struct DonorStockOnHandRow {
+    pub id: String,
+    pub item_id: String,
+    pub store_id: String,
+    pub donor_id: String,
+    pub available_stock_on_hand: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DonorStockOnHandFilter {
+    pub item_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub donor_id: Option<EqualFilter<String>>,
+}
+
+pub struct DonorStockOnHandRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> DonorStockOnHandRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        DonorStockOnHandRepository { connection }
+    }
+
+    pub fn query(
+        &self,
+        filter: Option<DonorStockOnHandFilter>,
+    ) -> Result<Vec<DonorStockOnHandRow>, RepositoryError> {
+        let mut query = donor_stock_on_hand_dsl::donor_stock_on_hand.into_boxed();
+
+        if let Some(f) = filter {
+            let DonorStockOnHandFilter {
+                item_id,
+                store_id,
+                donor_id,
+            } = f;
+
+            apply_equal_filter!(query, item_id, donor_stock_on_hand_dsl::item_id);
+            apply_equal_filter!(query, store_id, donor_stock_on_hand_dsl::store_id);
+            apply_equal_filter!(query, donor_id, donor_stock_on_hand_dsl::donor_id);
+        }
+
+        Ok(query.load::<DonorStockOnHandRow>(&self.connection.connection)?)
+    }
+}
+
+impl DonorStockOnHandFilter {
+    pub fn new() -> DonorStockOnHandFilter {
+        DonorStockOnHandFilter::default()
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn donor_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.donor_id = Some(filter);
+        self
+    }
+}
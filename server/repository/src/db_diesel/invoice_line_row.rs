@@ -30,6 +30,9 @@ table! {
         #[sql_name = "type"] type_ -> crate::db_diesel::invoice_line_row::InvoiceLineRowTypeMapping,
         number_of_packs -> Integer,
         note -> Nullable<Text>,
+        donor_id -> Nullable<Text>,
+        manufacturer_id -> Nullable<Text>,
+        vvm_status_id -> Nullable<Text>,
     }
 }
 
@@ -77,6 +80,9 @@ pub struct InvoiceLineRow {
     pub r#type: InvoiceLineRowType,
     pub number_of_packs: i32,
     pub note: Option<String>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 pub struct InvoiceLineRowRepository<'a> {
@@ -0,0 +1,75 @@
+use super::{
+    invoice_line_row::invoice_line,
+    invoice_line_serial_number_row::invoice_line_serial_number::dsl as invoice_line_serial_number_dsl,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+table! {
+    invoice_line_serial_number (id) {
+        id -> Text,
+        invoice_line_id -> Text,
+        serial_number -> Text,
+    }
+}
+
+joinable!(invoice_line_serial_number -> invoice_line (invoice_line_id));
+
+/// Serial number of a unit received or issued by an inbound or outbound shipment line, the serial
+/// number row is only moved once the units are received or issued
+#[derive(Clone, Queryable, Insertable, Debug, PartialEq, Default)]
+#[table_name = "invoice_line_serial_number"]
+pub struct InvoiceLineSerialNumberRow {
+    pub id: String,
+    pub invoice_line_id: String,
+    pub serial_number: String,
+}
+
+pub struct InvoiceLineSerialNumberRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> InvoiceLineSerialNumberRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        InvoiceLineSerialNumberRowRepository { connection }
+    }
+
+    pub fn insert_one(&self, row: &InvoiceLineSerialNumberRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(invoice_line_serial_number_dsl::invoice_line_serial_number)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_many_by_invoice_line_ids(
+        &self,
+        invoice_line_ids: &[String],
+    ) -> Result<Vec<InvoiceLineSerialNumberRow>, RepositoryError> {
+        Ok(invoice_line_serial_number_dsl::invoice_line_serial_number
+            .filter(invoice_line_serial_number_dsl::invoice_line_id.eq_any(invoice_line_ids))
+            .order(invoice_line_serial_number_dsl::serial_number.asc())
+            .load(&self.connection.connection)?)
+    }
+
+    /// Lines of any item the serial number is recorded on
+    pub fn find_many_by_serial_number(
+        &self,
+        serial_number: &str,
+    ) -> Result<Vec<InvoiceLineSerialNumberRow>, RepositoryError> {
+        Ok(invoice_line_serial_number_dsl::invoice_line_serial_number
+            .filter(invoice_line_serial_number_dsl::serial_number.eq(serial_number))
+            .load(&self.connection.connection)?)
+    }
+
+    pub fn delete_by_invoice_line_id(&self, invoice_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            invoice_line_serial_number_dsl::invoice_line_serial_number
+                .filter(invoice_line_serial_number_dsl::invoice_line_id.eq(invoice_line_id)),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+}
@@ -37,6 +37,10 @@ pub struct ItemFilter {
     pub r#type: Option<EqualFilter<ItemRowType>>,
     /// If true it only returns ItemAndMasterList that have a name join row
     pub is_visible: Option<bool>,
+    pub category_id: Option<EqualFilter<String>>,
+    pub department_id: Option<EqualFilter<String>>,
+    pub atc_category: Option<SimpleStringFilter>,
+    pub essential_drug_list: Option<bool>,
 }
 
 impl ItemFilter {
@@ -47,6 +51,10 @@ impl ItemFilter {
             code: None,
             r#type: None,
             is_visible: None,
+            category_id: None,
+            department_id: None,
+            atc_category: None,
+            essential_drug_list: None,
         }
     }
 
@@ -74,6 +82,26 @@ impl ItemFilter {
         self.is_visible = Some(value);
         self
     }
+
+    pub fn category_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.category_id = Some(filter);
+        self
+    }
+
+    pub fn department_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.department_id = Some(filter);
+        self
+    }
+
+    pub fn atc_category(mut self, filter: SimpleStringFilter) -> Self {
+        self.atc_category = Some(filter);
+        self
+    }
+
+    pub fn essential_drug_list(mut self, value: bool) -> Self {
+        self.essential_drug_list = Some(value);
+        self
+    }
 }
 
 use diesel::{
@@ -168,12 +196,23 @@ fn create_filtered_query(filter: Option<ItemFilter>) -> BoxedItemQuery {
             code,
             r#type,
             is_visible,
+            category_id,
+            department_id,
+            atc_category,
+            essential_drug_list,
         } = f;
 
         apply_equal_filter!(query, id, item_dsl::id);
         apply_simple_string_filter!(query, code, item_dsl::code);
         apply_simple_string_filter!(query, name, item_dsl::name);
         apply_equal_filter!(query, r#type, item_dsl::type_);
+        apply_equal_filter!(query, category_id, item_dsl::category_id);
+        apply_equal_filter!(query, department_id, item_dsl::department_id);
+        apply_simple_string_filter!(query, atc_category, item_dsl::atc_category);
+
+        if let Some(essential_drug_list) = essential_drug_list {
+            query = query.filter(item_dsl::essential_drug_list.eq(essential_drug_list));
+        }
 
         if let Some(is_visible) = is_visible {
             query = query.filter(item_is_visible::is_visible.eq(is_visible));
@@ -205,7 +244,8 @@ mod tests {
         mock::MockDataInserts, test_db, EqualFilter, ItemFilter, ItemRepository, ItemRow,
         ItemRowRepository, ItemRowType, MasterListLineRow, MasterListLineRowRepository,
         MasterListNameJoinRepository, MasterListNameJoinRow, MasterListRow,
-        MasterListRowRepository, NameRow, NameRowRepository, Pagination, DEFAULT_PAGINATION_LIMIT,
+        MasterListRowRepository, NameRow, NameRowRepository, Pagination, SimpleStringFilter,
+        DEFAULT_PAGINATION_LIMIT,
     };
 
     use super::{Item, ItemSort, ItemSortField};
@@ -338,6 +378,10 @@ mod tests {
                     // query invisible rows
                     is_visible: Some(false),
                     r#type: None,
+                    category_id: None,
+                    department_id: None,
+                    atc_category: None,
+                    essential_drug_list: None,
                 }),
                 None,
             )
@@ -345,6 +389,61 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[actix_rt::test]
+    async fn test_item_query_category_filter() {
+        let (_, storage_connection, _, _) =
+            test_db::setup_all("test_item_query_category_filter", MockDataInserts::none()).await;
+        let item_query_repository = ItemRepository::new(&storage_connection);
+
+        let item_rows = vec![
+            inline_init(|r: &mut ItemRow| {
+                r.id = "vaccine".to_owned();
+                r.category_id = Some("category_a".to_owned());
+                r.atc_category = "J07BC01".to_owned();
+                r.essential_drug_list = true;
+            }),
+            inline_init(|r: &mut ItemRow| {
+                r.id = "antibiotic".to_owned();
+                r.category_id = Some("category_b".to_owned());
+                r.department_id = Some("department_a".to_owned());
+                r.atc_category = "J01CA04".to_owned();
+            }),
+            inline_init(|r: &mut ItemRow| {
+                r.id = "no_category".to_owned();
+            }),
+        ];
+        for row in item_rows.iter() {
+            ItemRowRepository::new(&storage_connection)
+                .upsert_one(row)
+                .unwrap();
+        }
+        let query = |filter: ItemFilter| -> Vec<String> {
+            item_query_repository
+                .query_by_filter(filter)
+                .unwrap()
+                .into_iter()
+                .map(|item| item.item_row.id)
+                .collect()
+        };
+
+        assert_eq!(
+            query(ItemFilter::new().category_id(EqualFilter::equal_to("category_a"))),
+            vec!["vaccine"]
+        );
+        assert_eq!(
+            query(ItemFilter::new().department_id(EqualFilter::equal_to("department_a"))),
+            vec!["antibiotic"]
+        );
+        assert_eq!(
+            query(ItemFilter::new().atc_category(SimpleStringFilter::like("J07"))),
+            vec!["vaccine"]
+        );
+        assert_eq!(
+            query(ItemFilter::new().essential_drug_list(false)),
+            vec!["antibiotic", "no_category"]
+        );
+    }
+
     // TODO not sure where this fits, seems like this unit test has a lot of dependencies
     // I think test snapshot-like functionality is need ?
 
@@ -512,6 +611,10 @@ mod tests {
                     // query invisible rows
                     is_visible: Some(false),
                     r#type: None,
+                    category_id: None,
+                    department_id: None,
+                    atc_category: None,
+                    essential_drug_list: None,
                 }),
                 None,
             )
@@ -528,6 +631,10 @@ mod tests {
                     // query invisible rows
                     is_visible: Some(true),
                     r#type: None,
+                    category_id: None,
+                    department_id: None,
+                    atc_category: None,
+                    essential_drug_list: None,
                 }),
                 None,
             )
@@ -0,0 +1,68 @@
+use super::{item_category_row::item_category::dsl::*, StorageConnection};
+use crate::repository_error::RepositoryError;
+use diesel::prelude::*;
+
+table! {
+    item_category (id) {
+        id -> Text,
+        name -> Text,
+        parent_id -> Nullable<Text>,
+    }
+}
+
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, Eq, AsChangeset, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "item_category"]
+pub struct ItemCategoryRow {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+}
+
+pub struct ItemCategoryRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ItemCategoryRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ItemCategoryRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &ItemCategoryRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(item_category)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &ItemCategoryRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(item_category)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        category_id: &str,
+    ) -> Result<Option<ItemCategoryRow>, RepositoryError> {
+        let result = item_category
+            .filter(id.eq(category_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// All categories ordered by name
+    pub fn find_all(&self) -> Result<Vec<ItemCategoryRow>, RepositoryError> {
+        let result = item_category
+            .order(name.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
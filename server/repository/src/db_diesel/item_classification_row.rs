@@ -0,0 +1,148 @@
+use super::{
+    item_classification_row::item_classification::dsl as item_classification_dsl, item_row::item,
+    store_row::store, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use util::Defaults;
+
+table! {
+    item_classification (id) {
+        id -> Text,
+        store_id -> Text,
+        item_id -> Text,
+        abc_class -> crate::db_diesel::item_classification_row::AbcClassMapping,
+        ven_class -> Nullable<crate::db_diesel::item_classification_row::VenClassMapping>,
+        consumption_value -> Double,
+        is_abc_manual -> Bool,
+        updated_datetime -> Timestamp,
+    }
+}
+
+joinable!(item_classification -> item (item_id));
+joinable!(item_classification -> store (store_id));
+
+/// Class by share of the store's consumption value, A items make up most of it
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AbcClass {
+    A,
+    B,
+    C,
+}
+
+/// Vital, essential or non-essential item
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum VenClass {
+    V,
+    E,
+    N,
+}
+
+impl Default for AbcClass {
+    fn default() -> Self {
+        Self::C
+    }
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "item_classification"]
+pub struct ItemClassificationRow {
+    pub id: String,
+    pub store_id: String,
+    pub item_id: String,
+    pub abc_class: AbcClass,
+    pub ven_class: Option<VenClass>,
+    /// Consumption value over the lookback period of the last calculation
+    pub consumption_value: f64,
+    /// ABC class was set by a user and is kept when recalculating
+    pub is_abc_manual: bool,
+    pub updated_datetime: NaiveDateTime,
+}
+
+impl Default for ItemClassificationRow {
+    fn default() -> Self {
+        Self {
+            updated_datetime: Defaults::naive_date_time(),
+            // Defaults
+            id: Default::default(),
+            store_id: Default::default(),
+            item_id: Default::default(),
+            abc_class: Default::default(),
+            ven_class: Default::default(),
+            consumption_value: Default::default(),
+            is_abc_manual: Default::default(),
+        }
+    }
+}
+
+pub struct ItemClassificationRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ItemClassificationRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ItemClassificationRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &ItemClassificationRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(item_classification_dsl::item_classification)
+            .values(row)
+            .on_conflict(item_classification_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &ItemClassificationRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(item_classification_dsl::item_classification)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<ItemClassificationRow>, RepositoryError> {
+        let result = item_classification_dsl::item_classification
+            .filter(item_classification_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_store_and_item_id(
+        &self,
+        store_id: &str,
+        item_id: &str,
+    ) -> Result<Option<ItemClassificationRow>, RepositoryError> {
+        let result = item_classification_dsl::item_classification
+            .filter(item_classification_dsl::store_id.eq(store_id))
+            .filter(item_classification_dsl::item_id.eq(item_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Classifications of a store, ordered by item id
+    pub fn find_many_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<ItemClassificationRow>, RepositoryError> {
+        let result = item_classification_dsl::item_classification
+            .filter(item_classification_dsl::store_id.eq(store_id))
+            .order(item_classification_dsl::item_id.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,65 @@
+use super::{item_department_row::item_department::dsl::*, StorageConnection};
+use crate::repository_error::RepositoryError;
+use diesel::prelude::*;
+
+table! {
+    item_department (id) {
+        id -> Text,
+        name -> Text,
+    }
+}
+
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, Eq, AsChangeset, Default)]
+#[table_name = "item_department"]
+pub struct ItemDepartmentRow {
+    pub id: String,
+    pub name: String,
+}
+
+pub struct ItemDepartmentRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> ItemDepartmentRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        ItemDepartmentRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &ItemDepartmentRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(item_department)
+            .values(row)
+            .on_conflict(id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &ItemDepartmentRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(item_department)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        department_id: &str,
+    ) -> Result<Option<ItemDepartmentRow>, RepositoryError> {
+        let result = item_department
+            .filter(id.eq(department_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// All departments ordered by name
+    pub fn find_all(&self) -> Result<Vec<ItemDepartmentRow>, RepositoryError> {
+        let result = item_department
+            .order(name.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -13,6 +13,17 @@ table! {
         unit_id -> Nullable<Text>,
         default_pack_size -> Integer,
         #[sql_name = "type"] type_ -> crate::db_diesel::item_row::ItemRowTypeMapping,
+        doses -> Integer,
+        is_vaccine -> Bool,
+        category_id -> Nullable<Text>,
+        department_id -> Nullable<Text>,
+        atc_category -> Text,
+        strength -> Text,
+        outer_pack_size -> Integer,
+        volume_per_pack -> Double,
+        weight -> Double,
+        essential_drug_list -> Bool,
+        is_serial_tracked -> Bool,
         // TODO, this is temporary, remove
         legacy_record -> Text,
     }
@@ -36,7 +47,8 @@ pub enum ItemRowType {
     NonStock,
 }
 
-#[derive(Clone, Insertable, Queryable, Debug, PartialEq, Eq, AsChangeset)]
+#[derive(Clone, Insertable, Queryable, Debug, PartialEq, AsChangeset)]
+#[changeset_options(treat_none_as_null = "true")]
 #[table_name = "item"]
 pub struct ItemRow {
     pub id: String,
@@ -46,6 +58,21 @@ pub struct ItemRow {
     pub default_pack_size: i32,
     #[column_name = "type_"]
     pub r#type: ItemRowType,
+    /// Doses per unit, e.g. 10 for a 10 dose vial
+    pub doses: i32,
+    pub is_vaccine: bool,
+    pub category_id: Option<String>,
+    pub department_id: Option<String>,
+    /// ATC code, e.g. "J07BC01", empty if not set
+    pub atc_category: String,
+    pub strength: String,
+    pub outer_pack_size: i32,
+    pub volume_per_pack: f64,
+    pub weight: f64,
+    /// Item is on the essential drug list
+    pub essential_drug_list: bool,
+    /// Serial numbers of the units are recorded on shipments, e.g. for equipment
+    pub is_serial_tracked: bool,
     // TODO, this is temporary, remove
     pub legacy_record: String,
 }
@@ -59,6 +86,17 @@ impl Default for ItemRow {
             unit_id: Default::default(),
             default_pack_size: Default::default(),
             r#type: ItemRowType::Stock,
+            doses: Default::default(),
+            is_vaccine: Default::default(),
+            category_id: Default::default(),
+            department_id: Default::default(),
+            atc_category: Default::default(),
+            strength: Default::default(),
+            outer_pack_size: Default::default(),
+            volume_per_pack: Default::default(),
+            weight: Default::default(),
+            essential_drug_list: Default::default(),
+            is_serial_tracked: Default::default(),
             legacy_record: Default::default(),
         }
     }
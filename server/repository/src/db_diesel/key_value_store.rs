@@ -27,6 +27,13 @@ pub enum KeyValueType {
     /// Possible value: "true"
     RemoteSyncInitilisationFinished,
     RemoteSyncPushCursor,
+    /// JSON array of remote sync ids that have been imported from offline sync files but haven't
+    /// been acknowledged to the central server yet
+    RemoteSyncPendingAcknowledgements,
+    /// Sequence of the last offline sync file imported from the central server
+    RemoteSyncOfflineImportSequence,
+    /// Sequence of the last offline sync file exported to the central server
+    RemoteSyncOfflineExportSequence,
 
     SettingsSyncUrl,
     SettingsSyncUsername,
@@ -35,6 +42,12 @@ pub enum KeyValueType {
     SettingsSyncCentralServerSiteId,
     SettingsSyncSiteId,
     SettingsSyncIsDisabled,
+    /// Local time, formatted as HH:MM:SS
+    SettingsSyncQuietHoursStart,
+    /// Local time, formatted as HH:MM:SS
+    SettingsSyncQuietHoursEnd,
+    /// JSON object mapping central table names to conflict resolution policies
+    SettingsSyncConflictPolicies,
     SettingsTokenSecret,
 }
 
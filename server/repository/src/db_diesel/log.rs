@@ -145,3 +145,14 @@ impl LogFilter {
         self
     }
 }
+
+impl LogType {
+    pub fn equal_to(&self) -> EqualFilter<LogType> {
+        EqualFilter {
+            equal_to: Some(self.clone()),
+            not_equal_to: None,
+            equal_any: None,
+            not_equal_all: None,
+        }
+    }
+}
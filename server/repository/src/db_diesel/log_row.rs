@@ -38,6 +38,8 @@ pub enum LogType {
     RequisitionDeleted,
     RequisitionStatusSent,
     RequisitionStatusFinalised,
+    /// Stock line was automatically put on hold because it expired
+    StockLineExpiredOnHold,
 }
 
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
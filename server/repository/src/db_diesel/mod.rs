@@ -1,15 +1,23 @@
 use crate::repository_error::RepositoryError;
 
 mod central_sync_buffer;
+mod changelog_compaction_log_row;
 mod changelog_row;
 mod consumption;
+mod cycle_count_plan_row;
 pub mod diesel_schema;
+mod donor_consumption;
+mod donor_stock_on_hand;
 mod filter_sort_pagination;
 mod invoice;
 mod invoice_line;
 mod invoice_line_row;
+mod invoice_line_serial_number_row;
 mod invoice_row;
 mod item;
+mod item_category_row;
+mod item_classification_row;
+mod item_department_row;
 mod item_row;
 mod key_value_store;
 mod location;
@@ -25,39 +33,65 @@ mod name;
 mod name_row;
 mod name_store_join;
 mod number_row;
+mod open_vial_wastage;
+mod open_vial_wastage_row;
+mod remote_data;
 mod remote_sync_buffer;
 mod report;
 mod report_row;
 mod requisition;
 mod requisition_line;
+mod serial_number;
+mod serial_number_movement_row;
+mod serial_number_row;
 mod stock_line;
 mod stock_line_row;
+mod stock_line_movement;
+mod stock_line_reservation_row;
 mod stock_movement;
 mod stock_on_hand;
 mod stocktake;
 mod stocktake_line;
+mod stocktake_line_count_row;
 mod stocktake_line_row;
 mod stocktake_row;
 mod storage_connection;
 mod store;
+mod store_preference_row;
+mod store_remote_data;
 mod store_row;
+mod sync_conflict_row;
+mod sync_log_row;
+mod sync_quarantine_row;
+mod sync_site_changelog_row;
+mod sync_site_row;
 mod unit_row;
 mod user;
 mod user_permission;
 mod user_permission_row;
 mod user_row;
 mod user_store_join_row;
+mod vvm_status_log_row;
+mod vvm_status_row;
 
 pub use self::log::*;
 pub use central_sync_buffer::*;
+pub use changelog_compaction_log_row::*;
 pub use changelog_row::*;
 pub use consumption::*;
+pub use cycle_count_plan_row::*;
+pub use donor_consumption::*;
+pub use donor_stock_on_hand::*;
 pub use filter_sort_pagination::*;
 pub use invoice::*;
 pub use invoice_line::*;
 pub use invoice_line_row::*;
+pub use invoice_line_serial_number_row::*;
 pub use invoice_row::*;
 pub use item::*;
+pub use item_category_row::*;
+pub use item_classification_row::*;
+pub use item_department_row::*;
 pub use item_row::*;
 pub use key_value_store::*;
 pub use location::*;
@@ -72,28 +106,46 @@ pub use name::*;
 pub use name_row::*;
 pub use name_store_join::*;
 pub use number_row::*;
+pub use open_vial_wastage::*;
+pub use open_vial_wastage_row::*;
+pub use remote_data::*;
 pub use remote_sync_buffer::*;
 pub use report::*;
 pub use report_row::*;
 pub use requisition::*;
 pub use requisition_line::*;
+pub use serial_number::*;
+pub use serial_number_movement_row::*;
+pub use serial_number_row::*;
 pub use stock_line::*;
 pub use stock_line_row::*;
+pub use stock_line_movement::*;
+pub use stock_line_reservation_row::*;
 pub use stock_movement::*;
 pub use stock_on_hand::*;
 pub use stocktake::*;
 pub use stocktake_line::*;
+pub use stocktake_line_count_row::*;
 pub use stocktake_line_row::*;
 pub use stocktake_row::*;
 pub use storage_connection::*;
 pub use store::*;
+pub use store_preference_row::*;
+pub use store_remote_data::*;
 pub use store_row::*;
+pub use sync_conflict_row::*;
+pub use sync_log_row::*;
+pub use sync_quarantine_row::*;
+pub use sync_site_changelog_row::*;
+pub use sync_site_row::*;
 pub use unit_row::*;
 pub use user::*;
 pub use user_permission::*;
 pub use user_permission_row::*;
 pub use user_row::*;
 pub use user_store_join_row::*;
+pub use vvm_status_log_row::*;
+pub use vvm_status_row::*;
 
 use diesel::{
     prelude::*,
@@ -0,0 +1,118 @@
+use super::{
+    open_vial_wastage_row::{open_vial_wastage, open_vial_wastage::dsl as open_vial_wastage_dsl},
+    OpenVialWastageRow, StorageConnection,
+};
+
+use crate::{
+    diesel_macros::{apply_date_time_filter, apply_equal_filter},
+    repository_error::RepositoryError,
+    DBType, DatetimeFilter, EqualFilter, Pagination,
+};
+
+use diesel::prelude::*;
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct OpenVialWastageFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub item_id: Option<EqualFilter<String>>,
+    pub stock_line_id: Option<EqualFilter<String>>,
+    pub opened_datetime: Option<DatetimeFilter>,
+}
+
+pub struct OpenVialWastageRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> OpenVialWastageRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        OpenVialWastageRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<OpenVialWastageFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: OpenVialWastageFilter,
+    ) -> Result<Vec<OpenVialWastageRow>, RepositoryError> {
+        self.query(Pagination::new(), Some(filter))
+    }
+
+    /// Most recently opened vials first
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<OpenVialWastageFilter>,
+    ) -> Result<Vec<OpenVialWastageRow>, RepositoryError> {
+        let result = create_filtered_query(filter)
+            .order(open_vial_wastage_dsl::opened_datetime.desc())
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<OpenVialWastageRow>(&self.connection.connection)?;
+
+        Ok(result)
+    }
+}
+
+type BoxedOpenVialWastageQuery = open_vial_wastage::BoxedQuery<'static, DBType>;
+
+fn create_filtered_query(filter: Option<OpenVialWastageFilter>) -> BoxedOpenVialWastageQuery {
+    let mut query = open_vial_wastage::table.into_boxed();
+
+    if let Some(f) = filter {
+        let OpenVialWastageFilter {
+            id,
+            store_id,
+            item_id,
+            stock_line_id,
+            opened_datetime,
+        } = f;
+
+        apply_equal_filter!(query, id, open_vial_wastage_dsl::id);
+        apply_equal_filter!(query, store_id, open_vial_wastage_dsl::store_id);
+        apply_equal_filter!(query, item_id, open_vial_wastage_dsl::item_id);
+        apply_equal_filter!(query, stock_line_id, open_vial_wastage_dsl::stock_line_id);
+        apply_date_time_filter!(
+            query,
+            opened_datetime,
+            open_vial_wastage_dsl::opened_datetime
+        );
+    }
+
+    query
+}
+
+impl OpenVialWastageFilter {
+    pub fn new() -> OpenVialWastageFilter {
+        OpenVialWastageFilter::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn stock_line_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.stock_line_id = Some(filter);
+        self
+    }
+
+    pub fn opened_datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.opened_datetime = Some(filter);
+        self
+    }
+}
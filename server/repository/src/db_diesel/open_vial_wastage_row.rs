@@ -0,0 +1,111 @@
+use super::{
+    invoice_line_row::invoice_line, item_row::item,
+    open_vial_wastage_row::open_vial_wastage::dsl as open_vial_wastage_dsl,
+    stock_line_row::stock_line, store_row::store, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    open_vial_wastage (id) {
+        id -> Text,
+        store_id -> Text,
+        item_id -> Text,
+        stock_line_id -> Text,
+        invoice_line_id -> Nullable<Text>,
+        doses_used -> Integer,
+        doses_wasted -> Integer,
+        opened_datetime -> Timestamp,
+        comment -> Nullable<Text>,
+    }
+}
+
+joinable!(open_vial_wastage -> store (store_id));
+joinable!(open_vial_wastage -> item (item_id));
+joinable!(open_vial_wastage -> stock_line (stock_line_id));
+joinable!(open_vial_wastage -> invoice_line (invoice_line_id));
+
+/// Doses discarded from a multi-dose vial that was opened and only partially used
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[table_name = "open_vial_wastage"]
+pub struct OpenVialWastageRow {
+    pub id: String,
+    pub store_id: String,
+    pub item_id: String,
+    pub stock_line_id: String,
+    pub invoice_line_id: Option<String>,
+    pub doses_used: i32,
+    pub doses_wasted: i32,
+    pub opened_datetime: NaiveDateTime,
+    pub comment: Option<String>,
+}
+
+pub struct OpenVialWastageRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> OpenVialWastageRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        OpenVialWastageRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &OpenVialWastageRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(open_vial_wastage_dsl::open_vial_wastage)
+            .values(row)
+            .on_conflict(open_vial_wastage_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &OpenVialWastageRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(open_vial_wastage_dsl::open_vial_wastage)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<OpenVialWastageRow>, RepositoryError> {
+        let result = open_vial_wastage_dsl::open_vial_wastage
+            .filter(open_vial_wastage_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_stock_line_id(
+        &self,
+        stock_line_id: &str,
+    ) -> Result<Vec<OpenVialWastageRow>, RepositoryError> {
+        Ok(open_vial_wastage_dsl::open_vial_wastage
+            .filter(open_vial_wastage_dsl::stock_line_id.eq(stock_line_id))
+            .load(&self.connection.connection)?)
+    }
+
+    pub fn delete_by_stock_line_id(&self, stock_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            open_vial_wastage_dsl::open_vial_wastage
+                .filter(open_vial_wastage_dsl::stock_line_id.eq(stock_line_id)),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Keeps the wastage of the stock line when the invoice line the vial was opened for is
+    /// deleted
+    pub fn unlink_invoice_line(&self, invoice_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::update(
+            open_vial_wastage_dsl::open_vial_wastage
+                .filter(open_vial_wastage_dsl::invoice_line_id.eq(invoice_line_id)),
+        )
+        .set(open_vial_wastage_dsl::invoice_line_id.eq(None::<String>))
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use super::{
+    invoice_line_row::invoice_line::dsl as invoice_line_dsl,
+    invoice_line_serial_number_row::invoice_line_serial_number::dsl as invoice_line_serial_number_dsl,
+    invoice_row::invoice::dsl as invoice_dsl, location_row::location::dsl as location_dsl,
+    number_row::number::dsl as number_dsl,
+    open_vial_wastage_row::open_vial_wastage::dsl as open_vial_wastage_dsl,
+    remote_sync_buffer::remote_sync_buffer::dsl as remote_sync_buffer_dsl,
+    requisition::requisition_row::requisition::dsl as requisition_dsl,
+    requisition_line::requisition_line_row::requisition_line::dsl as requisition_line_dsl,
+    serial_number_movement_row::serial_number_movement::dsl as serial_number_movement_dsl,
+    stock_line_reservation_row::stock_line_reservation::dsl as stock_line_reservation_dsl,
+    stock_line_row::stock_line::dsl as stock_line_dsl,
+    stocktake_line_count_row::stocktake_line_count::dsl as stocktake_line_count_dsl,
+    stocktake_line_row::stocktake_line::dsl as stocktake_line_dsl,
+    stocktake_row::stocktake::dsl as stocktake_dsl,
+    store_preference_row::store_preference::dsl as store_preference_dsl,
+    sync_conflict_row::sync_conflict::dsl as sync_conflict_dsl,
+    sync_quarantine_row::sync_quarantine::dsl as sync_quarantine_dsl,
+    vvm_status_log_row::vvm_status_log::dsl as vvm_status_log_dsl, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+pub struct RemoteDataRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> RemoteDataRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        RemoteDataRepository { connection }
+    }
+
+    /// Deletes the remote data of all stores, i.e. the data that is re-pulled from the central
+    /// server in an initial pull, together with the local rows referring to it and pending sync
+    /// records. Names and name store joins are kept since central data refers to them, they are
+    /// updated by the initial pull.
+    ///
+    /// Should be called in a transaction.
+    pub fn delete_all(&self) -> Result<(), RepositoryError> {
+        let connection = &self.connection.connection;
+        // Rows referring to stock lines, invoice lines or stocktake lines
+        diesel::delete(vvm_status_log_dsl::vvm_status_log).execute(connection)?;
+        diesel::delete(open_vial_wastage_dsl::open_vial_wastage).execute(connection)?;
+        diesel::delete(serial_number_movement_dsl::serial_number_movement).execute(connection)?;
+        diesel::delete(invoice_line_serial_number_dsl::invoice_line_serial_number)
+            .execute(connection)?;
+        diesel::delete(stock_line_reservation_dsl::stock_line_reservation).execute(connection)?;
+        diesel::delete(stocktake_line_count_dsl::stocktake_line_count).execute(connection)?;
+        diesel::update(store_preference_dsl::store_preference)
+            .set(store_preference_dsl::pick_face_location_id.eq(None::<String>))
+            .execute(connection)?;
+
+        diesel::delete(requisition_line_dsl::requisition_line).execute(connection)?;
+        diesel::delete(requisition_dsl::requisition).execute(connection)?;
+        diesel::delete(stocktake_line_dsl::stocktake_line).execute(connection)?;
+        diesel::delete(stocktake_dsl::stocktake).execute(connection)?;
+        diesel::delete(invoice_line_dsl::invoice_line).execute(connection)?;
+        diesel::delete(invoice_dsl::invoice).execute(connection)?;
+        diesel::delete(stock_line_dsl::stock_line).execute(connection)?;
+        diesel::delete(location_dsl::location).execute(connection)?;
+        diesel::delete(number_dsl::number).execute(connection)?;
+
+        diesel::delete(remote_sync_buffer_dsl::remote_sync_buffer).execute(connection)?;
+        diesel::delete(sync_conflict_dsl::sync_conflict).execute(connection)?;
+        diesel::delete(sync_quarantine_dsl::sync_quarantine).execute(connection)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        mock::{mock_outbound_shipment_a, mock_stock_line_a, MockDataInserts},
+        test_db, InvoiceRowRepository, NameRowRepository, RemoteDataRepository, RepositoryError,
+        StockLineRowRepository,
+    };
+
+    #[actix_rt::test]
+    async fn test_remote_data_delete_all() {
+        let (_, connection, _, _) =
+            test_db::setup_all("test_remote_data_delete_all", MockDataInserts::all()).await;
+
+        RemoteDataRepository::new(&connection).delete_all().unwrap();
+
+        assert_eq!(
+            InvoiceRowRepository::new(&connection)
+                .find_one_by_id_option(&mock_outbound_shipment_a().id)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            StockLineRowRepository::new(&connection).find_one_by_id(&mock_stock_line_a().id),
+            Err(RepositoryError::NotFound)
+        );
+        // Names are central data as well and are kept
+        assert!(NameRowRepository::new(&connection)
+            .find_one_by_id("name_store_a")
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,181 @@
+use super::{
+    serial_number_movement_row::serial_number_movement::dsl as serial_number_movement_dsl,
+    serial_number_row::{serial_number, serial_number::dsl as serial_number_dsl},
+    SerialNumberRow, SerialNumberStatus, StorageConnection,
+};
+
+use crate::{
+    diesel_macros::{apply_equal_filter, apply_simple_string_filter, apply_sort_no_case},
+    repository_error::RepositoryError,
+    DBType, EqualFilter, Pagination, SimpleStringFilter, Sort,
+};
+
+use diesel::prelude::*;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct SerialNumber {
+    pub serial_number_row: SerialNumberRow,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct SerialNumberFilter {
+    pub id: Option<EqualFilter<String>>,
+    pub item_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub serial_number: Option<SimpleStringFilter>,
+    pub status: Option<EqualFilter<SerialNumberStatus>>,
+    pub holder_name_id: Option<EqualFilter<String>>,
+    /// Serial numbers moved by the invoice lines
+    pub invoice_line_id: Option<EqualFilter<String>>,
+}
+
+#[derive(PartialEq, Debug)]
+pub enum SerialNumberSortField {
+    SerialNumber,
+}
+
+pub type SerialNumberSort = Sort<SerialNumberSortField>;
+
+pub struct SerialNumberRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SerialNumberRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SerialNumberRepository { connection }
+    }
+
+    pub fn count(&self, filter: Option<SerialNumberFilter>) -> Result<i64, RepositoryError> {
+        let query = create_filtered_query(filter);
+
+        Ok(query.count().get_result(&self.connection.connection)?)
+    }
+
+    pub fn query_by_filter(
+        &self,
+        filter: SerialNumberFilter,
+    ) -> Result<Vec<SerialNumber>, RepositoryError> {
+        self.query(Pagination::new(), Some(filter), None)
+    }
+
+    pub fn query(
+        &self,
+        pagination: Pagination,
+        filter: Option<SerialNumberFilter>,
+        sort: Option<SerialNumberSort>,
+    ) -> Result<Vec<SerialNumber>, RepositoryError> {
+        let mut query = create_filtered_query(filter);
+
+        if let Some(sort) = sort {
+            match sort.key {
+                SerialNumberSortField::SerialNumber => {
+                    apply_sort_no_case!(query, sort, serial_number_dsl::serial_number_)
+                }
+            }
+        } else {
+            query = query.order(serial_number_dsl::id.asc())
+        }
+
+        let result = query
+            .offset(pagination.offset as i64)
+            .limit(pagination.limit as i64)
+            .load::<SerialNumberRow>(&self.connection.connection)?;
+
+        Ok(result.into_iter().map(to_domain).collect())
+    }
+}
+
+type BoxedSerialNumberQuery = serial_number::BoxedQuery<'static, DBType>;
+
+fn create_filtered_query(filter: Option<SerialNumberFilter>) -> BoxedSerialNumberQuery {
+    let mut query = serial_number::table.into_boxed();
+
+    if let Some(f) = filter {
+        let SerialNumberFilter {
+            id,
+            item_id,
+            store_id,
+            serial_number,
+            status,
+            holder_name_id,
+            invoice_line_id,
+        } = f;
+
+        apply_equal_filter!(query, id, serial_number_dsl::id);
+        apply_equal_filter!(query, item_id, serial_number_dsl::item_id);
+        apply_equal_filter!(query, store_id, serial_number_dsl::store_id);
+        apply_simple_string_filter!(query, serial_number, serial_number_dsl::serial_number_);
+        apply_equal_filter!(query, status, serial_number_dsl::status);
+        apply_equal_filter!(query, holder_name_id, serial_number_dsl::holder_name_id);
+
+        if invoice_line_id.is_some() {
+            let mut sub_query = serial_number_movement_dsl::serial_number_movement
+                .select(serial_number_movement_dsl::serial_number_id)
+                .into_boxed();
+            apply_equal_filter!(
+                sub_query,
+                invoice_line_id,
+                serial_number_movement_dsl::invoice_line_id
+            );
+            query = query.filter(serial_number_dsl::id.eq_any(sub_query));
+        }
+    }
+
+    query
+}
+
+pub fn to_domain(serial_number_row: SerialNumberRow) -> SerialNumber {
+    SerialNumber { serial_number_row }
+}
+
+impl SerialNumberFilter {
+    pub fn new() -> SerialNumberFilter {
+        SerialNumberFilter::default()
+    }
+
+    pub fn id(mut self, filter: EqualFilter<String>) -> Self {
+        self.id = Some(filter);
+        self
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn serial_number(mut self, filter: SimpleStringFilter) -> Self {
+        self.serial_number = Some(filter);
+        self
+    }
+
+    pub fn status(mut self, filter: EqualFilter<SerialNumberStatus>) -> Self {
+        self.status = Some(filter);
+        self
+    }
+
+    pub fn holder_name_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.holder_name_id = Some(filter);
+        self
+    }
+
+    pub fn invoice_line_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.invoice_line_id = Some(filter);
+        self
+    }
+}
+
+impl SerialNumberStatus {
+    pub fn equal_to(&self) -> EqualFilter<SerialNumberStatus> {
+        EqualFilter {
+            equal_to: Some(self.clone()),
+            not_equal_to: None,
+            equal_any: None,
+            not_equal_all: None,
+        }
+    }
+}
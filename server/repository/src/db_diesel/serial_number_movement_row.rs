@@ -0,0 +1,86 @@
+use super::{
+    invoice_line_row::invoice_line,
+    serial_number_movement_row::serial_number_movement::dsl as serial_number_movement_dsl,
+    serial_number_row::serial_number, store_row::store, SerialNumberStatus, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    serial_number_movement (id) {
+        id -> Text,
+        serial_number_id -> Text,
+        store_id -> Text,
+        invoice_line_id -> Nullable<Text>,
+        status -> crate::db_diesel::serial_number_row::SerialNumberStatusMapping,
+        holder_name_id -> Nullable<Text>,
+        datetime -> Timestamp,
+    }
+}
+
+joinable!(serial_number_movement -> serial_number (serial_number_id));
+joinable!(serial_number_movement -> store (store_id));
+joinable!(serial_number_movement -> invoice_line (invoice_line_id));
+
+/// Records a change of status or holder of a serial number, e.g. receipt in an inbound shipment,
+/// issue in an outbound shipment or being sent for repair
+#[derive(Clone, Queryable, Insertable, Debug, PartialEq)]
+#[table_name = "serial_number_movement"]
+pub struct SerialNumberMovementRow {
+    pub id: String,
+    pub serial_number_id: String,
+    pub store_id: String,
+    pub invoice_line_id: Option<String>,
+    pub status: SerialNumberStatus,
+    pub holder_name_id: Option<String>,
+    pub datetime: NaiveDateTime,
+}
+
+pub struct SerialNumberMovementRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SerialNumberMovementRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SerialNumberMovementRowRepository { connection }
+    }
+
+    pub fn insert_one(&self, row: &SerialNumberMovementRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(serial_number_movement_dsl::serial_number_movement)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Movements of a serial number, oldest first
+    pub fn find_many_by_serial_number_id(
+        &self,
+        serial_number_id: &str,
+    ) -> Result<Vec<SerialNumberMovementRow>, RepositoryError> {
+        Ok(serial_number_movement_dsl::serial_number_movement
+            .filter(serial_number_movement_dsl::serial_number_id.eq(serial_number_id))
+            .order(serial_number_movement_dsl::datetime.asc())
+            .load(&self.connection.connection)?)
+    }
+
+    pub fn find_many_by_invoice_line_id(
+        &self,
+        invoice_line_id: &str,
+    ) -> Result<Vec<SerialNumberMovementRow>, RepositoryError> {
+        Ok(serial_number_movement_dsl::serial_number_movement
+            .filter(serial_number_movement_dsl::invoice_line_id.eq(invoice_line_id))
+            .load(&self.connection.connection)?)
+    }
+
+    pub fn delete_by_invoice_line_id(&self, invoice_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            serial_number_movement_dsl::serial_number_movement
+                .filter(serial_number_movement_dsl::invoice_line_id.eq(invoice_line_id)),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+}
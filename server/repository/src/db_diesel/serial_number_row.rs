@@ -0,0 +1,106 @@
+use super::{
+    item_row::item, name_row::name, serial_number_row::serial_number::dsl as serial_number_dsl,
+    store_row::store, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    serial_number (id) {
+        id -> Text,
+        item_id -> Text,
+        store_id -> Text,
+        #[sql_name = "serial_number"] serial_number_ -> Text,
+        status -> crate::db_diesel::serial_number_row::SerialNumberStatusMapping,
+        holder_name_id -> Nullable<Text>,
+        note -> Nullable<Text>,
+    }
+}
+
+joinable!(serial_number -> item (item_id));
+joinable!(serial_number -> store (store_id));
+joinable!(serial_number -> name (holder_name_id));
+
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Default)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum SerialNumberStatus {
+    #[default]
+    InStore,
+    Issued,
+    InRepair,
+    Decommissioned,
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "serial_number"]
+pub struct SerialNumberRow {
+    pub id: String,
+    pub item_id: String,
+    /// Store the unit is currently managed by
+    pub store_id: String,
+    #[column_name = "serial_number_"]
+    pub serial_number: String,
+    pub status: SerialNumberStatus,
+    pub holder_name_id: Option<String>,
+    pub note: Option<String>,
+}
+
+pub struct SerialNumberRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SerialNumberRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SerialNumberRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SerialNumberRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(serial_number_dsl::serial_number)
+            .values(row)
+            .on_conflict(serial_number_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SerialNumberRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(serial_number_dsl::serial_number)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<SerialNumberRow>, RepositoryError> {
+        let result = serial_number_dsl::serial_number
+            .filter(serial_number_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_one_by_serial_number(
+        &self,
+        item_id: &str,
+        serial_number: &str,
+    ) -> Result<Option<SerialNumberRow>, RepositoryError> {
+        let result = serial_number_dsl::serial_number
+            .filter(serial_number_dsl::item_id.eq(item_id))
+            .filter(serial_number_dsl::serial_number_.eq(serial_number))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(serial_number_dsl::serial_number.filter(serial_number_dsl::id.eq(id)))
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,8 @@
 use super::{
     location_row::{location, location::dsl as location_dsl},
+    name_row::{name, name::dsl as name_dsl},
     stock_line_row::{stock_line, stock_line::dsl as stock_line_dsl},
-    DBType, LocationRow, StockLineRow, StorageConnection,
+    DBType, LocationRow, NameRow, StockLineRow, StorageConnection,
 };
 
 use crate::{
@@ -19,10 +20,12 @@ use diesel::{
 pub struct StockLine {
     pub stock_line_row: StockLineRow,
     pub location_row: Option<LocationRow>,
+    pub manufacturer_row: Option<NameRow>,
 }
 
 pub enum StockLineSortField {
     ExpiryDate,
+    ManufacturerName,
 }
 
 #[derive(Debug)]
@@ -33,11 +36,12 @@ pub struct StockLineFilter {
     pub is_available: Option<bool>,
     pub expiry_date: Option<DateFilter>,
     pub store_id: Option<EqualFilter<String>>,
+    pub manufacturer_id: Option<EqualFilter<String>>,
 }
 
 pub type StockLineSort = Sort<StockLineSortField>;
 
-type StockLineJoin = (StockLineRow, Option<LocationRow>);
+type StockLineJoin = (StockLineRow, Option<LocationRow>, Option<NameRow>);
 pub struct StockLineRepository<'a> {
     connection: &'a StorageConnection,
 }
@@ -73,6 +77,9 @@ impl<'a> StockLineRepository<'a> {
                     // TODO: would prefer to have extra parameter on Sort.nulls_last
                     apply_sort_asc_nulls_last!(query, sort, stock_line_dsl::expiry_date);
                 }
+                StockLineSortField::ManufacturerName => {
+                    apply_sort_asc_nulls_last!(query, sort, name_dsl::name_);
+                }
             }
         } else {
             query = query.order(stock_line_dsl::id.asc())
@@ -94,11 +101,13 @@ impl<'a> StockLineRepository<'a> {
     }
 }
 
-type BoxedStockLineQuery = IntoBoxed<'static, LeftJoin<stock_line::table, location::table>, DBType>;
+type BoxedStockLineQuery =
+    IntoBoxed<'static, LeftJoin<LeftJoin<stock_line::table, location::table>, name::table>, DBType>;
 
 fn create_filtered_query(filter: Option<StockLineFilter>) -> BoxedStockLineQuery {
     let mut query = stock_line_dsl::stock_line
         .left_join(location_dsl::location)
+        .left_join(name_dsl::name)
         .into_boxed();
 
     if let Some(f) = filter {
@@ -109,6 +118,7 @@ fn create_filtered_query(filter: Option<StockLineFilter>) -> BoxedStockLineQuery
             is_available,
             expiry_date,
             store_id,
+            manufacturer_id,
         } = f;
 
         apply_equal_filter!(query, id, stock_line_dsl::id);
@@ -116,6 +126,7 @@ fn create_filtered_query(filter: Option<StockLineFilter>) -> BoxedStockLineQuery
         apply_equal_filter!(query, location_id, stock_line_dsl::location_id);
         apply_date_time_filter!(query, expiry_date, stock_line_dsl::expiry_date);
         apply_equal_filter!(query, store_id, stock_line_dsl::store_id);
+        apply_equal_filter!(query, manufacturer_id, stock_line_dsl::manufacturer_id);
 
         query = match is_available {
             Some(true) => query.filter(stock_line_dsl::available_number_of_packs.gt(0)),
@@ -127,10 +138,11 @@ fn create_filtered_query(filter: Option<StockLineFilter>) -> BoxedStockLineQuery
     query
 }
 
-pub fn to_domain((stock_line_row, location_row): StockLineJoin) -> StockLine {
+pub fn to_domain((stock_line_row, location_row, manufacturer_row): StockLineJoin) -> StockLine {
     StockLine {
         stock_line_row,
         location_row,
+        manufacturer_row,
     }
 }
 
@@ -143,6 +155,7 @@ impl StockLineFilter {
             expiry_date: None,
             store_id: None,
             is_available: None,
+            manufacturer_id: None,
         }
     }
 
@@ -175,6 +188,11 @@ impl StockLineFilter {
         self.is_available = Some(filter);
         self
     }
+
+    pub fn manufacturer_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.manufacturer_id = Some(filter);
+        self
+    }
 }
 
 impl StockLine {
@@ -184,6 +202,12 @@ impl StockLine {
             .map(|location_row| location_row.name.as_str())
     }
 
+    pub fn manufacturer_name(&self) -> Option<&str> {
+        self.manufacturer_row
+            .as_ref()
+            .map(|manufacturer_row| manufacturer_row.name.as_str())
+    }
+
     pub fn available_quantity(&self) -> i32 {
         self.stock_line_row.available_number_of_packs * self.stock_line_row.pack_size
     }
@@ -196,9 +220,9 @@ mod test {
 
     use crate::{
         mock::MockDataInserts,
-        mock::{mock_item_a, mock_store_a, MockData},
-        test_db, Pagination, StockLine, StockLineRepository, StockLineRow, StockLineSort,
-        StockLineSortField,
+        mock::{mock_item_a, mock_name_a, mock_name_b, mock_store_a, MockData},
+        test_db, EqualFilter, Pagination, StockLine, StockLineFilter, StockLineRepository,
+        StockLineRow, StockLineSort, StockLineSortField,
     };
 
     fn from_row(stock_line_row: StockLineRow) -> StockLine {
@@ -269,4 +293,70 @@ mod test {
             repo.query(Pagination::new(), None, Some(sort)).unwrap()
         );
     }
+
+    #[actix_rt::test]
+    async fn test_stock_line_manufacturer() {
+        fn line1() -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = "line1".to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_a().id;
+                r.manufacturer_id = Some(mock_name_b().id);
+            })
+        }
+        fn line2() -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = "line2".to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_a().id;
+                r.manufacturer_id = Some(mock_name_a().id);
+            })
+        }
+        fn line3() -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = "line3".to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_a().id;
+                r.manufacturer_id = None;
+            })
+        }
+
+        let (_, connection, _, _) = test_db::setup_all_with_data(
+            "test_stock_line_manufacturer",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![line1(), line2(), line3()];
+            }),
+        )
+        .await;
+
+        let repo = StockLineRepository::new(&connection);
+
+        // Filter by manufacturer
+        let result = repo
+            .query_by_filter(
+                StockLineFilter::new().manufacturer_id(EqualFilter::equal_to(&mock_name_a().id)),
+            )
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].stock_line_row.id, line2().id);
+        assert_eq!(
+            result[0].manufacturer_name(),
+            Some(mock_name_a().name.as_str())
+        );
+
+        // Asc by manufacturer name, NULLS last
+        let sort = StockLineSort {
+            key: StockLineSortField::ManufacturerName,
+            desc: Some(false),
+        };
+        let result = repo.query(Pagination::new(), None, Some(sort)).unwrap();
+        assert_eq!(
+            result
+                .iter()
+                .map(|line| line.stock_line_row.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["line2", "line1", "line3"]
+        );
+    }
 }
@@ -0,0 +1,111 @@
+use super::{
+    stock_line_movement::stock_line_movement::dsl as stock_line_movement_dsl, StorageConnection,
+};
+
+use crate::{
+    diesel_macros::{apply_date_time_filter, apply_equal_filter},
+    DatetimeFilter, EqualFilter, RepositoryError,
+};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::{QueryDsl, RunQueryDsl};
+use util::Defaults;
+
+table! {
+    stock_line_movement (id) {
+        id -> Text,
+        stock_line_id -> Nullable<Text>,
+        item_id -> Text,
+        store_id -> Text,
+        batch -> Nullable<Text>,
+        quantity -> Integer,
+        datetime -> Timestamp,
+    }
+}
+
+/// Stock movement of a single invoice line (in units), positive for stock coming in
+#[derive(Clone, Queryable, Debug, PartialEq)]
+pub struct StockLineMovementRow {
+    /// Id of the invoice line
+    pub id: String,
+    pub stock_line_id: Option<String>,
+    pub item_id: String,
+    pub store_id: String,
+    pub batch: Option<String>,
+    pub quantity: i32,
+    pub datetime: NaiveDateTime,
+}
+
+impl Default for StockLineMovementRow {
+    fn default() -> Self {
+        Self {
+            datetime: Defaults::naive_date_time(),
+            // Default
+            id: Default::default(),
+            stock_line_id: Default::default(),
+            item_id: Default::default(),
+            store_id: Default::default(),
+            batch: Default::default(),
+            quantity: Default::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct StockLineMovementFilter {
+    pub item_id: Option<EqualFilter<String>>,
+    pub store_id: Option<EqualFilter<String>>,
+    pub datetime: Option<DatetimeFilter>,
+}
+
+pub struct StockLineMovementRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StockLineMovementRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StockLineMovementRepository { connection }
+    }
+
+    pub fn query(
+        &self,
+        filter: Option<StockLineMovementFilter>,
+    ) -> Result<Vec<StockLineMovementRow>, RepositoryError> {
+        let mut query = stock_line_movement_dsl::stock_line_movement.into_boxed();
+
+        if let Some(f) = filter {
+            let StockLineMovementFilter {
+                item_id,
+                store_id,
+                datetime,
+            } = f;
+
+            apply_equal_filter!(query, item_id, stock_line_movement_dsl::item_id);
+            apply_equal_filter!(query, store_id, stock_line_movement_dsl::store_id);
+            apply_date_time_filter!(query, datetime, stock_line_movement_dsl::datetime);
+        }
+
+        Ok(query.load::<StockLineMovementRow>(&self.connection.connection)?)
+    }
+}
+
+impl StockLineMovementFilter {
+    pub fn new() -> StockLineMovementFilter {
+        StockLineMovementFilter::default()
+    }
+
+    pub fn item_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.item_id = Some(filter);
+        self
+    }
+
+    pub fn store_id(mut self, filter: EqualFilter<String>) -> Self {
+        self.store_id = Some(filter);
+        self
+    }
+
+    pub fn datetime(mut self, filter: DatetimeFilter) -> Self {
+        self.datetime = Some(filter);
+        self
+    }
+}
@@ -0,0 +1,160 @@
+use super::{
+    name_row::name, stock_line_reservation_row::stock_line_reservation::dsl as reservation_dsl,
+    stock_line_row::stock_line, store_row::store, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use util::Defaults;
+
+table! {
+    stock_line_reservation (id) {
+        id -> Text,
+        store_id -> Text,
+        stock_line_id -> Text,
+        name_id -> Nullable<Text>,
+        campaign -> Nullable<Text>,
+        number_of_packs -> Integer,
+        reserved_until -> Date,
+        created_datetime -> Timestamp,
+        released_datetime -> Nullable<Timestamp>,
+    }
+}
+
+joinable!(stock_line_reservation -> store (store_id));
+joinable!(stock_line_reservation -> stock_line (stock_line_id));
+joinable!(stock_line_reservation -> name (name_id));
+
+/// Packs of a stock line held for a customer or a named campaign, the held packs can't be issued
+/// to anyone else (see `service::stock_line_reservation::consume`)
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "stock_line_reservation"]
+pub struct StockLineReservationRow {
+    pub id: String,
+    pub store_id: String,
+    pub stock_line_id: String,
+    /// Customer the stock is reserved for
+    pub name_id: Option<String>,
+    /// Campaign or programme the stock is reserved for
+    pub campaign: Option<String>,
+    /// Packs still held, reduced when issued to the reserved customer or campaign
+    pub number_of_packs: i32,
+    /// Last day the stock is held
+    pub reserved_until: NaiveDate,
+    pub created_datetime: NaiveDateTime,
+    /// Set when the packs still held were returned to the available stock
+    pub released_datetime: Option<NaiveDateTime>,
+}
+
+impl Default for StockLineReservationRow {
+    fn default() -> Self {
+        Self {
+            reserved_until: Defaults::naive_date(),
+            created_datetime: Defaults::naive_date_time(),
+            // Defaults
+            id: Default::default(),
+            store_id: Default::default(),
+            stock_line_id: Default::default(),
+            name_id: Default::default(),
+            campaign: Default::default(),
+            number_of_packs: Default::default(),
+            released_datetime: Default::default(),
+        }
+    }
+}
+
+pub struct StockLineReservationRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StockLineReservationRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StockLineReservationRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &StockLineReservationRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(reservation_dsl::stock_line_reservation)
+            .values(row)
+            .on_conflict(reservation_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &StockLineReservationRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(reservation_dsl::stock_line_reservation)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<StockLineReservationRow>, RepositoryError> {
+        let result = reservation_dsl::stock_line_reservation
+            .filter(reservation_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Reservations of the store that haven't been released, earliest reserved_until first
+    pub fn find_many_held_by_store_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Vec<StockLineReservationRow>, RepositoryError> {
+        let result = reservation_dsl::stock_line_reservation
+            .filter(reservation_dsl::store_id.eq(store_id))
+            .filter(reservation_dsl::released_datetime.is_null())
+            .order((
+                reservation_dsl::reserved_until.asc(),
+                reservation_dsl::created_datetime.asc(),
+            ))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Reservations of the stock lines that haven't been released, earliest reserved_until first
+    pub fn find_many_held_by_stock_line_ids(
+        &self,
+        stock_line_ids: &[String],
+    ) -> Result<Vec<StockLineReservationRow>, RepositoryError> {
+        let result = reservation_dsl::stock_line_reservation
+            .filter(reservation_dsl::stock_line_id.eq_any(stock_line_ids))
+            .filter(reservation_dsl::released_datetime.is_null())
+            .order((
+                reservation_dsl::reserved_until.asc(),
+                reservation_dsl::created_datetime.asc(),
+            ))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn delete_by_stock_line_id(&self, stock_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            reservation_dsl::stock_line_reservation
+                .filter(reservation_dsl::stock_line_id.eq(stock_line_id)),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Reservations that haven't been released and were held until before the given date
+    pub fn find_many_held_until_before(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Vec<StockLineReservationRow>, RepositoryError> {
+        let result = reservation_dsl::stock_line_reservation
+            .filter(reservation_dsl::released_datetime.is_null())
+            .filter(reservation_dsl::reserved_until.lt(date))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
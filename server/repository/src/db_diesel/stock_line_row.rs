@@ -1,6 +1,6 @@
 use super::{
-    item_row::item, location_row::location, stock_line_row::stock_line::dsl as stock_line_dsl,
-    store_row::store, StorageConnection,
+    item_row::item, location_row::location, name_row::name,
+    stock_line_row::stock_line::dsl as stock_line_dsl, store_row::store, StorageConnection,
 };
 
 use crate::repository_error::RepositoryError;
@@ -24,12 +24,16 @@ table! {
         expiry_date -> Nullable<Date>,
         on_hold -> Bool,
         note -> Nullable<Text>,
+        donor_id -> Nullable<Text>,
+        manufacturer_id -> Nullable<Text>,
+        vvm_status_id -> Nullable<Text>,
     }
 }
 
 joinable!(stock_line -> item (item_id));
 joinable!(stock_line -> store (store_id));
 joinable!(stock_line -> location (location_id));
+joinable!(stock_line -> name (manufacturer_id));
 
 #[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
 #[table_name = "stock_line"]
@@ -47,6 +51,9 @@ pub struct StockLineRow {
     pub expiry_date: Option<NaiveDate>,
     pub on_hold: bool,
     pub note: Option<String>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 pub struct StockLineRowRepository<'a> {
@@ -0,0 +1,96 @@
+use super::{
+    stocktake_line_count_row::stocktake_line_count::dsl as stocktake_line_count_dsl,
+    stocktake_line_row::stocktake_line, user_row::user_account, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    stocktake_line_count (id) {
+        id -> Text,
+        stocktake_line_id -> Text,
+        user_id -> Text,
+        counted_number_of_packs -> Integer,
+        counted_datetime -> Timestamp,
+    }
+}
+
+joinable!(stocktake_line_count -> stocktake_line (stocktake_line_id));
+joinable!(stocktake_line_count -> user_account (user_id));
+
+/// Independent count of a stocktake line, each user has at most one count per line
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[table_name = "stocktake_line_count"]
+pub struct StocktakeLineCountRow {
+    pub id: String,
+    pub stocktake_line_id: String,
+    pub user_id: String,
+    pub counted_number_of_packs: i32,
+    pub counted_datetime: NaiveDateTime,
+}
+
+pub struct StocktakeLineCountRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StocktakeLineCountRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StocktakeLineCountRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &StocktakeLineCountRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(stocktake_line_count_dsl::stocktake_line_count)
+            .values(row)
+            .on_conflict(stocktake_line_count_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &StocktakeLineCountRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(stocktake_line_count_dsl::stocktake_line_count)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn delete_by_stocktake_line_id(
+        &self,
+        stocktake_line_id: &str,
+    ) -> Result<(), RepositoryError> {
+        diesel::delete(
+            stocktake_line_count_dsl::stocktake_line_count
+                .filter(stocktake_line_count_dsl::stocktake_line_id.eq(stocktake_line_id)),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        id: &str,
+    ) -> Result<Option<StocktakeLineCountRow>, RepositoryError> {
+        let result = stocktake_line_count_dsl::stocktake_line_count
+            .filter(stocktake_line_count_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_stocktake_line_ids(
+        &self,
+        stocktake_line_ids: &[String],
+    ) -> Result<Vec<StocktakeLineCountRow>, RepositoryError> {
+        let result = stocktake_line_count_dsl::stocktake_line_count
+            .filter(stocktake_line_count_dsl::stocktake_line_id.eq_any(stocktake_line_ids))
+            .order(stocktake_line_count_dsl::counted_datetime.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -28,6 +28,10 @@ table! {
         cost_price_per_pack -> Nullable<Double>,
         sell_price_per_pack -> Nullable<Double>,
         note -> Nullable<Text>,
+        donor_id -> Nullable<Text>,
+        manufacturer_id -> Nullable<Text>,
+        vvm_status_id -> Nullable<Text>,
+        needs_recount -> Bool,
     }
 }
 
@@ -57,6 +61,11 @@ pub struct StocktakeLineRow {
     pub cost_price_per_pack: Option<f64>,
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
+    /// Independent counts of this line differ by more than the stocktake's recount tolerance
+    pub needs_recount: bool,
 }
 
 pub struct StocktakeLineRowRepository<'a> {
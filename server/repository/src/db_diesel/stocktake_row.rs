@@ -8,6 +8,7 @@ use diesel::prelude::*;
 
 use chrono::{NaiveDate, NaiveDateTime};
 use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
 use util::Defaults;
 
 table! {
@@ -24,19 +25,28 @@ table! {
         finalised_datetime -> Nullable<Timestamp>,
         inventory_adjustment_id -> Nullable<Text>,
         is_locked -> Bool,
+        is_blind_count -> Bool,
+        recount_tolerance -> Integer,
+        variance_approval_threshold -> Nullable<Double>,
+        approved_user_id -> Nullable<Text>,
+        approved_datetime -> Nullable<Timestamp>,
     }
 }
 
 joinable!(stocktake -> user_account (user_id));
 
-#[derive(DbEnum, Debug, Clone, PartialEq, Eq)]
+#[derive(DbEnum, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[DbValueStyle = "SCREAMING_SNAKE_CASE"]
 pub enum StocktakeStatus {
     New,
+    /// Counting is complete and the variance is being reviewed, lines can't be edited
+    Review,
     Finalised,
 }
 
-#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Eq)]
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[changeset_options(treat_none_as_null = "true")]
 #[table_name = "stocktake"]
 pub struct StocktakeRow {
     pub id: String,
@@ -52,6 +62,16 @@ pub struct StocktakeRow {
     /// reference to the inventory adjustment shipment
     pub inventory_adjustment_id: Option<String>,
     pub is_locked: bool,
+    /// Snapshot quantities are hidden from counters
+    pub is_blind_count: bool,
+    /// Number of packs that independent counts of a line may differ by before a recount is
+    /// required
+    pub recount_tolerance: i32,
+    /// Variance cost value, of a single line or of the whole stocktake, above which the
+    /// stocktake has to be approved before it can be finalised (no approval needed if None)
+    pub variance_approval_threshold: Option<f64>,
+    pub approved_user_id: Option<String>,
+    pub approved_datetime: Option<NaiveDateTime>,
 }
 
 impl Default for StocktakeStatus {
@@ -76,6 +96,11 @@ impl Default for StocktakeRow {
             finalised_datetime: Default::default(),
             inventory_adjustment_id: Default::default(),
             is_locked: Default::default(),
+            is_blind_count: Default::default(),
+            recount_tolerance: Default::default(),
+            variance_approval_threshold: Default::default(),
+            approved_user_id: Default::default(),
+            approved_datetime: Default::default(),
         }
     }
 }
@@ -207,6 +207,39 @@ impl StorageConnection {
             }
         }
     }
+
+    /// Writes a consistent copy of the database into a new file at `file_path`, e.g. before
+    /// deleting data. Must not be called in a transaction.
+    // feature sqlite
+    #[cfg(not(feature = "postgres"))]
+    pub fn backup_to_file(&self, file_path: &str) -> Result<(), RepositoryError> {
+        use diesel::connection::SimpleConnection;
+        self.connection
+            .batch_execute(&format!("VACUUM INTO '{}';", file_path.replace('\'', "''")))?;
+        Ok(())
+    }
+
+    // feature postgres
+    #[cfg(feature = "postgres")]
+    pub fn backup_to_file(&self, file_path: &str) -> Result<(), RepositoryError> {
+        Err(RepositoryError::as_db_error(
+            "Database backups are not supported for postgres, use pg_dump instead",
+            file_path,
+        ))
+    }
+
+    /// Returns false if [StorageConnection::backup_to_file] isn't supported by the database
+    // feature sqlite
+    #[cfg(not(feature = "postgres"))]
+    pub fn is_backup_supported(&self) -> bool {
+        true
+    }
+
+    // feature postgres
+    #[cfg(feature = "postgres")]
+    pub fn is_backup_supported(&self) -> bool {
+        false
+    }
 }
 
 fn map_begin_transaction_error<T>(
@@ -289,4 +322,20 @@ mod connection_manager_tests {
             );
         assert_eq!(connection.transaction_level.get(), 0);
     }
+
+    // feature sqlite
+    #[cfg(not(feature = "postgres"))]
+    #[actix_rt::test]
+    async fn test_backup_to_file() {
+        let settings = test_db::get_test_db_settings("omsupply-backup-to-file");
+        let connection_manager = test_db::setup(&settings).await;
+        let connection = connection_manager.connection().unwrap();
+
+        let backup_file = format!("{}.backup", settings.database_name);
+        let _ = std::fs::remove_file(&backup_file);
+        connection.backup_to_file(&backup_file).unwrap();
+        assert!(std::path::Path::new(&backup_file).exists());
+        // Existing files are never overwritten
+        assert!(connection.backup_to_file(&backup_file).is_err());
+    }
 }
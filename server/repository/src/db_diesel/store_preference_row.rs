@@ -0,0 +1,86 @@
+use super::{
+    store_preference_row::store_preference::dsl as store_preference_dsl, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+
+table! {
+    store_preference (id) {
+        id -> Text,
+        allocation_strategy -> crate::db_diesel::store_preference_row::AllocationStrategyMapping,
+        pick_face_location_id -> Nullable<Text>,
+    }
+}
+
+/// Order in which available stock lines are used when allocating outbound shipment lines
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum AllocationStrategy {
+    /// Earliest expiry first (FEFO)
+    ExpiryFirst,
+    /// Earliest received first (FIFO)
+    FirstInFirstOut,
+    /// Stock in the pick face location first, then earliest expiry first
+    PreferredLocation,
+    /// Whole packs first, so that packs are only broken for the remainder
+    WholePackFirst,
+}
+
+impl Default for AllocationStrategy {
+    fn default() -> Self {
+        Self::ExpiryFirst
+    }
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "store_preference"]
+pub struct StorePreferenceRow {
+    /// Id of the store
+    pub id: String,
+    pub allocation_strategy: AllocationStrategy,
+    pub pick_face_location_id: Option<String>,
+}
+
+pub struct StorePreferenceRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StorePreferenceRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StorePreferenceRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &StorePreferenceRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(store_preference_dsl::store_preference)
+            .values(row)
+            .on_conflict(store_preference_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &StorePreferenceRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(store_preference_dsl::store_preference)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(
+        &self,
+        store_id: &str,
+    ) -> Result<Option<StorePreferenceRow>, RepositoryError> {
+        let result = store_preference_dsl::store_preference
+            .filter(store_preference_dsl::id.eq(store_id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+}
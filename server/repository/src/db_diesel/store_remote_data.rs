@@ -0,0 +1,116 @@
+use super::{
+    invoice_line_row::invoice_line::dsl as invoice_line_dsl,
+    invoice_row::invoice::dsl as invoice_dsl, location_row::location::dsl as location_dsl,
+    number_row::number::dsl as number_dsl,
+    requisition::requisition_row::requisition::dsl as requisition_dsl,
+    requisition_line::requisition_line_row::requisition_line::dsl as requisition_line_dsl,
+    stock_line_row::stock_line::dsl as stock_line_dsl,
+    stocktake_line_row::stocktake_line::dsl as stocktake_line_dsl,
+    stocktake_row::stocktake::dsl as stocktake_dsl, ChangelogTableName, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+/// Remote data tables that belong to a store, see [StoreRemoteDataRepository::row_ids]
+pub const STORE_REMOTE_DATA_TABLES: &[ChangelogTableName] = &[
+    ChangelogTableName::Number,
+    ChangelogTableName::Location,
+    ChangelogTableName::StockLine,
+    ChangelogTableName::Invoice,
+    ChangelogTableName::InvoiceLine,
+    ChangelogTableName::Stocktake,
+    ChangelogTableName::StocktakeLine,
+    ChangelogTableName::Requisition,
+    ChangelogTableName::RequisitionLine,
+];
+
+pub struct StoreRemoteDataRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> StoreRemoteDataRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        StoreRemoteDataRepository { connection }
+    }
+
+    /// Ids of the rows of a store in the given table, ordered by id. Lines belong to the store of
+    /// their invoice, stocktake or requisition.
+    ///
+    /// Returns None for tables that don't belong to a store, e.g. names.
+    pub fn row_ids(
+        &self,
+        table: &ChangelogTableName,
+        store_id: &str,
+    ) -> Result<Option<Vec<String>>, RepositoryError> {
+        let connection = &self.connection.connection;
+        let result = match table {
+            ChangelogTableName::Number => number_dsl::number
+                .select(number_dsl::id)
+                .filter(number_dsl::store_id.eq(store_id))
+                .order(number_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::Location => location_dsl::location
+                .select(location_dsl::id)
+                .filter(location_dsl::store_id.eq(store_id))
+                .order(location_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::StockLine => stock_line_dsl::stock_line
+                .select(stock_line_dsl::id)
+                .filter(stock_line_dsl::store_id.eq(store_id))
+                .order(stock_line_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::Invoice => invoice_dsl::invoice
+                .select(invoice_dsl::id)
+                .filter(invoice_dsl::store_id.eq(store_id))
+                .order(invoice_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::InvoiceLine => invoice_line_dsl::invoice_line
+                .select(invoice_line_dsl::id)
+                .filter(
+                    invoice_line_dsl::invoice_id.eq_any(
+                        invoice_dsl::invoice
+                            .select(invoice_dsl::id)
+                            .filter(invoice_dsl::store_id.eq(store_id)),
+                    ),
+                )
+                .order(invoice_line_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::Stocktake => stocktake_dsl::stocktake
+                .select(stocktake_dsl::id)
+                .filter(stocktake_dsl::store_id.eq(store_id))
+                .order(stocktake_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::StocktakeLine => stocktake_line_dsl::stocktake_line
+                .select(stocktake_line_dsl::id)
+                .filter(
+                    stocktake_line_dsl::stocktake_id.eq_any(
+                        stocktake_dsl::stocktake
+                            .select(stocktake_dsl::id)
+                            .filter(stocktake_dsl::store_id.eq(store_id)),
+                    ),
+                )
+                .order(stocktake_line_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::Requisition => requisition_dsl::requisition
+                .select(requisition_dsl::id)
+                .filter(requisition_dsl::store_id.eq(store_id))
+                .order(requisition_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::RequisitionLine => requisition_line_dsl::requisition_line
+                .select(requisition_line_dsl::id)
+                .filter(
+                    requisition_line_dsl::requisition_id.eq_any(
+                        requisition_dsl::requisition
+                            .select(requisition_dsl::id)
+                            .filter(requisition_dsl::store_id.eq(store_id)),
+                    ),
+                )
+                .order(requisition_line_dsl::id.asc())
+                .load(connection)?,
+            ChangelogTableName::Name | ChangelogTableName::NameStoreJoin => return Ok(None),
+        };
+        Ok(Some(result))
+    }
+}
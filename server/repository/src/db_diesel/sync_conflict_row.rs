@@ -0,0 +1,112 @@
+use super::{
+    remote_sync_buffer::RemoteSyncBufferAction,
+    sync_conflict_row::sync_conflict::dsl as sync_conflict_dsl, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use util::Defaults;
+
+table! {
+    sync_conflict (id) {
+        id -> Text,
+        table_name -> Text,
+        record_id -> Text,
+        resolution -> crate::db_diesel::sync_conflict_row::SyncConflictResolutionMapping,
+        local_data -> Nullable<Text>,
+        remote_action -> crate::db_diesel::remote_sync_buffer::RemoteSyncBufferActionMapping,
+        remote_data -> Text,
+        detected_datetime -> Timestamp,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum SyncConflictResolution {
+    /// Remote record has been dropped, the local record is pushed with the next sync
+    LocalWins,
+    /// Remote record has been integrated, overwriting the local changes
+    RemoteWins,
+}
+
+/// Remote record that conflicted with local changes that hadn't been pushed yet
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "sync_conflict"]
+pub struct SyncConflictRow {
+    pub id: String,
+    /// Table name on the central server
+    pub table_name: String,
+    pub record_id: String,
+    pub resolution: SyncConflictResolution,
+    /// Raw JSON of the local record as it would have been pushed, None if deleted locally
+    pub local_data: Option<String>,
+    pub remote_action: RemoteSyncBufferAction,
+    /// Raw JSON of the remote record
+    pub remote_data: String,
+    pub detected_datetime: NaiveDateTime,
+}
+
+impl Default for SyncConflictRow {
+    fn default() -> Self {
+        Self {
+            resolution: SyncConflictResolution::RemoteWins,
+            remote_action: RemoteSyncBufferAction::Update,
+            detected_datetime: Defaults::naive_date_time(),
+            // Defaults
+            id: Default::default(),
+            table_name: Default::default(),
+            record_id: Default::default(),
+            local_data: Default::default(),
+            remote_data: Default::default(),
+        }
+    }
+}
+
+pub struct SyncConflictRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncConflictRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncConflictRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncConflictRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_conflict_dsl::sync_conflict)
+            .values(row)
+            .on_conflict(sync_conflict_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncConflictRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_conflict_dsl::sync_conflict)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<SyncConflictRow>, RepositoryError> {
+        let result = sync_conflict_dsl::sync_conflict
+            .filter(sync_conflict_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Most recently detected conflicts first
+    pub fn find_all(&self) -> Result<Vec<SyncConflictRow>, RepositoryError> {
+        let result = sync_conflict_dsl::sync_conflict
+            .order(sync_conflict_dsl::detected_datetime.desc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,165 @@
+use super::{sync_log_row::sync_log::dsl as sync_log_dsl, StorageConnection};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use util::Defaults;
+
+table! {
+    sync_log (id) {
+        id -> Text,
+        is_initial_pull -> Bool,
+        started_datetime -> Timestamp,
+        finished_datetime -> Nullable<Timestamp>,
+        stage -> Nullable<crate::db_diesel::sync_log_row::SyncLogStageMapping>,
+        pushed_records -> Integer,
+        pulled_remote_records -> Integer,
+        pulled_central_records -> Integer,
+        integrated_records -> Integer,
+        pull_remaining_records -> Nullable<Integer>,
+        error -> Nullable<Text>,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum SyncLogStage {
+    /// Pushing local changes to the central server
+    Push,
+    /// Pulling remote data and messages from the central server
+    RemotePull,
+    /// Pulling central data from the central server
+    CentralPull,
+    /// Integrating the pulled records into the local database
+    Integrate,
+}
+
+/// A sync run, updated as the sync progresses
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "sync_log"]
+pub struct SyncLogRow {
+    pub id: String,
+    pub is_initial_pull: bool,
+    pub started_datetime: NaiveDateTime,
+    /// None while the sync is running
+    pub finished_datetime: Option<NaiveDateTime>,
+    /// Stage the sync is in, or was in when it finished or failed
+    pub stage: Option<SyncLogStage>,
+    pub pushed_records: i32,
+    pub pulled_remote_records: i32,
+    pub pulled_central_records: i32,
+    pub integrated_records: i32,
+    /// Records still to be pulled in the current pull stage, if known
+    pub pull_remaining_records: Option<i32>,
+    pub error: Option<String>,
+}
+
+impl Default for SyncLogRow {
+    fn default() -> Self {
+        Self {
+            started_datetime: Defaults::naive_date_time(),
+            // Defaults
+            id: Default::default(),
+            is_initial_pull: Default::default(),
+            finished_datetime: Default::default(),
+            stage: Default::default(),
+            pushed_records: Default::default(),
+            pulled_remote_records: Default::default(),
+            pulled_central_records: Default::default(),
+            integrated_records: Default::default(),
+            pull_remaining_records: Default::default(),
+            error: Default::default(),
+        }
+    }
+}
+
+pub struct SyncLogRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncLogRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncLogRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncLogRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_log_dsl::sync_log)
+            .values(row)
+            .on_conflict(sync_log_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncLogRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_log_dsl::sync_log)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<SyncLogRow>, RepositoryError> {
+        let result = sync_log_dsl::sync_log
+            .filter(sync_log_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Most recent sync runs first
+    pub fn find_many_latest_first(&self, limit: u32) -> Result<Vec<SyncLogRow>, RepositoryError> {
+        let result = sync_log_dsl::sync_log
+            .order(sync_log_dsl::started_datetime.desc())
+            .limit(limit as i64)
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn find_latest(&self) -> Result<Option<SyncLogRow>, RepositoryError> {
+        Ok(self.find_many_latest_first(1)?.pop())
+    }
+
+    /// Most recent sync run that finished without an error
+    pub fn find_latest_successful(&self) -> Result<Option<SyncLogRow>, RepositoryError> {
+        let result = sync_log_dsl::sync_log
+            .filter(sync_log_dsl::finished_datetime.is_not_null())
+            .filter(sync_log_dsl::error.is_null())
+            .order(sync_log_dsl::started_datetime.desc())
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    /// Removes all but the `retained` most recent sync runs. The most recent successful sync run
+    /// is always kept since the sync status is based on it.
+    pub fn delete_all_but_latest(&self, retained: u64) -> Result<usize, RepositoryError> {
+        let first_removed: Option<NaiveDateTime> = sync_log_dsl::sync_log
+            .select(sync_log_dsl::started_datetime)
+            .order(sync_log_dsl::started_datetime.desc())
+            .offset(retained as i64)
+            .first(&self.connection.connection)
+            .optional()?;
+        let first_removed = match first_removed {
+            Some(first_removed) => first_removed,
+            None => return Ok(0),
+        };
+        let latest_successful_id = self
+            .find_latest_successful()?
+            .map(|row| row.id)
+            .unwrap_or_default();
+
+        let result = diesel::delete(
+            sync_log_dsl::sync_log
+                .filter(sync_log_dsl::started_datetime.le(first_removed))
+                .filter(sync_log_dsl::id.ne(latest_successful_id)),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(result)
+    }
+}
@@ -0,0 +1,141 @@
+use super::{
+    remote_sync_buffer::RemoteSyncBufferAction,
+    sync_quarantine_row::sync_quarantine::dsl as sync_quarantine_dsl, StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_derive_enum::DbEnum;
+use util::Defaults;
+
+table! {
+    sync_quarantine (id) {
+        id -> Text,
+        source -> crate::db_diesel::sync_quarantine_row::SyncQuarantineSourceMapping,
+        table_name -> Text,
+        record_id -> Text,
+        action -> Nullable<crate::db_diesel::remote_sync_buffer::RemoteSyncBufferActionMapping>,
+        data -> Text,
+        error -> Text,
+        attempt_count -> Integer,
+        first_failed_datetime -> Timestamp,
+        last_failed_datetime -> Timestamp,
+    }
+}
+
+#[derive(DbEnum, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[DbValueStyle = "SCREAMING_SNAKE_CASE"]
+pub enum SyncQuarantineSource {
+    Central,
+    Remote,
+}
+
+/// Sync record that failed to translate or integrate
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "sync_quarantine"]
+pub struct SyncQuarantineRow {
+    pub id: String,
+    pub source: SyncQuarantineSource,
+    pub table_name: String,
+    pub record_id: String,
+    /// Only set for remote records
+    pub action: Option<RemoteSyncBufferAction>,
+    /// Raw JSON record data
+    pub data: String,
+    pub error: String,
+    pub attempt_count: i32,
+    pub first_failed_datetime: NaiveDateTime,
+    pub last_failed_datetime: NaiveDateTime,
+}
+
+impl Default for SyncQuarantineRow {
+    fn default() -> Self {
+        Self {
+            source: SyncQuarantineSource::Central,
+            first_failed_datetime: Defaults::naive_date_time(),
+            last_failed_datetime: Defaults::naive_date_time(),
+            // Defaults
+            id: Default::default(),
+            table_name: Default::default(),
+            record_id: Default::default(),
+            action: Default::default(),
+            data: Default::default(),
+            error: Default::default(),
+            attempt_count: Default::default(),
+        }
+    }
+}
+
+pub struct SyncQuarantineRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncQuarantineRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncQuarantineRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncQuarantineRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_quarantine_dsl::sync_quarantine)
+            .values(row)
+            .on_conflict(sync_quarantine_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncQuarantineRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_quarantine_dsl::sync_quarantine)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<SyncQuarantineRow>, RepositoryError> {
+        let result = sync_quarantine_dsl::sync_quarantine
+            .filter(sync_quarantine_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_id(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<SyncQuarantineRow>, RepositoryError> {
+        let result = sync_quarantine_dsl::sync_quarantine
+            .filter(sync_quarantine_dsl::id.eq_any(ids))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_source(
+        &self,
+        source: SyncQuarantineSource,
+    ) -> Result<Vec<SyncQuarantineRow>, RepositoryError> {
+        let result = sync_quarantine_dsl::sync_quarantine
+            .filter(sync_quarantine_dsl::source.eq(source))
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Most recently failed records first
+    pub fn find_all(&self) -> Result<Vec<SyncQuarantineRow>, RepositoryError> {
+        let result = sync_quarantine_dsl::sync_quarantine
+            .order(sync_quarantine_dsl::last_failed_datetime.desc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(sync_quarantine_dsl::sync_quarantine.filter(sync_quarantine_dsl::id.eq(id)))
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+}
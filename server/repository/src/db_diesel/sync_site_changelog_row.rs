@@ -0,0 +1,79 @@
+use super::{
+    changelog_row::changelog::dsl as changelog_dsl,
+    sync_site_changelog_row::sync_site_changelog::dsl as sync_site_changelog_dsl,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+table! {
+    sync_site_changelog (changelog_id) {
+        changelog_id -> BigInt,
+        site_id -> Integer,
+    }
+}
+
+/// Changelog from integrating a record that has been pushed by a remote site
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[table_name = "sync_site_changelog"]
+pub struct SyncSiteChangelogRow {
+    pub changelog_id: i64,
+    pub site_id: i32,
+}
+
+pub struct SyncSiteChangelogRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncSiteChangelogRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncSiteChangelogRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncSiteChangelogRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_site_changelog_dsl::sync_site_changelog)
+            .values(row)
+            .on_conflict(sync_site_changelog_dsl::changelog_id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncSiteChangelogRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_site_changelog_dsl::sync_site_changelog)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Returns the ids of the given changelogs that originate from the site
+    pub fn find_site_changelog_ids(
+        &self,
+        site_id: i32,
+        changelog_ids: &[i64],
+    ) -> Result<Vec<i64>, RepositoryError> {
+        let result = sync_site_changelog_dsl::sync_site_changelog
+            .filter(sync_site_changelog_dsl::site_id.eq(site_id))
+            .filter(sync_site_changelog_dsl::changelog_id.eq_any(changelog_ids))
+            .select(sync_site_changelog_dsl::changelog_id)
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+
+    /// Removes rows of changelogs that don't exist anymore, e.g. after changelog compaction
+    pub fn delete_removed_changelogs(&self) -> Result<usize, RepositoryError> {
+        let result = diesel::delete(
+            sync_site_changelog_dsl::sync_site_changelog.filter(
+                sync_site_changelog_dsl::changelog_id
+                    .ne_all(changelog_dsl::changelog.select(changelog_dsl::id)),
+            ),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(result)
+    }
+}
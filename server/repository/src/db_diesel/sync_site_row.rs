@@ -0,0 +1,88 @@
+use super::{sync_site_row::sync_site::dsl as sync_site_dsl, StorageConnection};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    sync_site (id) {
+        id -> Integer,
+        name -> Text,
+        password_sha256 -> Text,
+        hardware_id -> Nullable<Text>,
+        queue_cursor -> BigInt,
+        initialised_datetime -> Nullable<Timestamp>,
+        offline_export_sequence -> BigInt,
+        offline_import_sequence -> BigInt,
+        offline_central_cursor -> BigInt,
+    }
+}
+
+/// Remote site that syncs with this server, when running as central server
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[changeset_options(treat_none_as_null = "true")]
+#[table_name = "sync_site"]
+pub struct SyncSiteRow {
+    /// Site id as configured in the sync settings of the site
+    pub id: i32,
+    /// Sync username of the site
+    pub name: String,
+    pub password_sha256: String,
+    /// Set when the site initialises
+    pub hardware_id: Option<String>,
+    /// First changelog id that hasn't been acknowledged by the site
+    pub queue_cursor: i64,
+    pub initialised_datetime: Option<NaiveDateTime>,
+    /// Sequence of the last offline sync file created for the site
+    pub offline_export_sequence: i64,
+    /// Sequence of the last offline sync file imported from the site
+    pub offline_import_sequence: i64,
+    /// Last central record integrated by the site, as reported in its last imported offline sync
+    /// file
+    pub offline_central_cursor: i64,
+}
+
+pub struct SyncSiteRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> SyncSiteRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        SyncSiteRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &SyncSiteRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(sync_site_dsl::sync_site)
+            .values(row)
+            .on_conflict(sync_site_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &SyncSiteRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(sync_site_dsl::sync_site)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: i32) -> Result<Option<SyncSiteRow>, RepositoryError> {
+        let result = sync_site_dsl::sync_site
+            .filter(sync_site_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_all(&self) -> Result<Vec<SyncSiteRow>, RepositoryError> {
+        let result = sync_site_dsl::sync_site
+            .order(sync_site_dsl::id.asc())
+            .load(&self.connection.connection)?;
+        Ok(result)
+    }
+}
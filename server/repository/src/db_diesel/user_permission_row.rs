@@ -33,6 +33,8 @@ pub enum Permission {
     // stocktake
     StocktakeQuery,
     StocktakeMutate,
+    /// Approve stocktake variances above the stocktake's approval threshold
+    StocktakeApprove,
     // requisition
     RequisitionQuery,
     RequisitionMutate,
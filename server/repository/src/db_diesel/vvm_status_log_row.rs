@@ -0,0 +1,101 @@
+use super::{
+    invoice_line_row::invoice_line, stock_line_row::stock_line, stocktake_line_row::stocktake_line,
+    vvm_status_log_row::vvm_status_log::dsl as vvm_status_log_dsl, vvm_status_row::vvm_status,
+    StorageConnection,
+};
+
+use crate::repository_error::RepositoryError;
+
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+
+table! {
+    vvm_status_log (id) {
+        id -> Text,
+        status_id -> Text,
+        stock_line_id -> Text,
+        invoice_line_id -> Nullable<Text>,
+        stocktake_line_id -> Nullable<Text>,
+        comment -> Nullable<Text>,
+        created_datetime -> Timestamp,
+    }
+}
+
+joinable!(vvm_status_log -> vvm_status (status_id));
+joinable!(vvm_status_log -> stock_line (stock_line_id));
+joinable!(vvm_status_log -> invoice_line (invoice_line_id));
+joinable!(vvm_status_log -> stocktake_line (stocktake_line_id));
+
+/// Records a VVM status observed for a stock line, e.g. on receipt, during a stocktake or at issue
+#[derive(Clone, Queryable, Insertable, Debug, PartialEq)]
+#[table_name = "vvm_status_log"]
+pub struct VvmStatusLogRow {
+    pub id: String,
+    pub status_id: String,
+    pub stock_line_id: String,
+    pub invoice_line_id: Option<String>,
+    pub stocktake_line_id: Option<String>,
+    pub comment: Option<String>,
+    pub created_datetime: NaiveDateTime,
+}
+
+pub struct VvmStatusLogRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> VvmStatusLogRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        VvmStatusLogRowRepository { connection }
+    }
+
+    pub fn insert_one(&self, row: &VvmStatusLogRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(vvm_status_log_dsl::vvm_status_log)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Status changes of a stock line, oldest first
+    pub fn find_many_by_stock_line_id(
+        &self,
+        stock_line_id: &str,
+    ) -> Result<Vec<VvmStatusLogRow>, RepositoryError> {
+        Ok(vvm_status_log_dsl::vvm_status_log
+            .filter(vvm_status_log_dsl::stock_line_id.eq(stock_line_id))
+            .order(vvm_status_log_dsl::created_datetime.asc())
+            .load(&self.connection.connection)?)
+    }
+
+    pub fn delete_by_stock_line_id(&self, stock_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::delete(
+            vvm_status_log_dsl::vvm_status_log
+                .filter(vvm_status_log_dsl::stock_line_id.eq(stock_line_id)),
+        )
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Keeps the status changes of the stock line when the invoice line they were observed on is
+    /// deleted
+    pub fn unlink_invoice_line(&self, invoice_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::update(
+            vvm_status_log_dsl::vvm_status_log
+                .filter(vvm_status_log_dsl::invoice_line_id.eq(invoice_line_id)),
+        )
+        .set(vvm_status_log_dsl::invoice_line_id.eq(None::<String>))
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    /// Keeps the status changes of the stock line when the stocktake line they were observed on
+    /// is deleted
+    pub fn unlink_stocktake_line(&self, stocktake_line_id: &str) -> Result<(), RepositoryError> {
+        diesel::update(
+            vvm_status_log_dsl::vvm_status_log
+                .filter(vvm_status_log_dsl::stocktake_line_id.eq(stocktake_line_id)),
+        )
+        .set(vvm_status_log_dsl::stocktake_line_id.eq(None::<String>))
+        .execute(&self.connection.connection)?;
+        Ok(())
+    }
+}
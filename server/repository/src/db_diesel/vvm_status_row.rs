@@ -0,0 +1,79 @@
+use super::{vvm_status_row::vvm_status::dsl as vvm_status_dsl, StorageConnection};
+
+use crate::repository_error::RepositoryError;
+
+use diesel::prelude::*;
+
+table! {
+    vvm_status (id) {
+        id -> Text,
+        code -> Text,
+        description -> Text,
+        level -> Integer,
+        is_active -> Bool,
+        unusable -> Bool,
+    }
+}
+
+#[derive(Clone, Queryable, Insertable, AsChangeset, Debug, PartialEq, Default)]
+#[table_name = "vvm_status"]
+pub struct VvmStatusRow {
+    pub id: String,
+    pub code: String,
+    pub description: String,
+    /// Higher levels have had more heat exposure
+    pub level: i32,
+    pub is_active: bool,
+    /// Stock at this stage must not be issued
+    pub unusable: bool,
+}
+
+pub struct VvmStatusRowRepository<'a> {
+    connection: &'a StorageConnection,
+}
+
+impl<'a> VvmStatusRowRepository<'a> {
+    pub fn new(connection: &'a StorageConnection) -> Self {
+        VvmStatusRowRepository { connection }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub fn upsert_one(&self, row: &VvmStatusRow) -> Result<(), RepositoryError> {
+        diesel::insert_into(vvm_status_dsl::vvm_status)
+            .values(row)
+            .on_conflict(vvm_status_dsl::id)
+            .do_update()
+            .set(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "postgres"))]
+    pub fn upsert_one(&self, row: &VvmStatusRow) -> Result<(), RepositoryError> {
+        diesel::replace_into(vvm_status_dsl::vvm_status)
+            .values(row)
+            .execute(&self.connection.connection)?;
+        Ok(())
+    }
+
+    pub fn find_one_by_id(&self, id: &str) -> Result<Option<VvmStatusRow>, RepositoryError> {
+        let result = vvm_status_dsl::vvm_status
+            .filter(vvm_status_dsl::id.eq(id))
+            .first(&self.connection.connection)
+            .optional()?;
+        Ok(result)
+    }
+
+    pub fn find_many_by_id(&self, ids: &[String]) -> Result<Vec<VvmStatusRow>, RepositoryError> {
+        Ok(vvm_status_dsl::vvm_status
+            .filter(vvm_status_dsl::id.eq_any(ids))
+            .load(&self.connection.connection)?)
+    }
+
+    /// All statuses, ordered by level
+    pub fn find_all(&self) -> Result<Vec<VvmStatusRow>, RepositoryError> {
+        Ok(vvm_status_dsl::vvm_status
+            .order(vvm_status_dsl::level.asc())
+            .load(&self.connection.connection)?)
+    }
+}
@@ -22,6 +22,9 @@ pub fn mock_outbound_shipment_a_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 10,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_outbound_shipment_a_invoice_line_b: InvoiceLineRow = InvoiceLineRow {
@@ -43,6 +46,9 @@ pub fn mock_outbound_shipment_a_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 4,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![
@@ -71,6 +77,9 @@ pub fn mock_outbound_shipment_b_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 3,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_outbound_shipment_b_invoice_line_b: InvoiceLineRow = InvoiceLineRow {
@@ -92,6 +101,9 @@ pub fn mock_outbound_shipment_b_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 5,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![
@@ -120,6 +132,9 @@ pub fn mock_outbound_shipment_c_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 3,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_outbound_shipment_c_invoice_line_b: InvoiceLineRow = InvoiceLineRow {
@@ -141,6 +156,9 @@ pub fn mock_outbound_shipment_c_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 1,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![
@@ -169,6 +187,9 @@ pub fn mock_outbound_shipment_d_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 2,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_outbound_shipment_d_invoice_line_a]
@@ -194,6 +215,9 @@ pub fn mock_outbound_shipment_no_stock_line() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 2,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_outbound_shipment_no_stock_line]
@@ -219,6 +243,9 @@ pub fn mock_inbound_shipment_a_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockIn,
         number_of_packs: 1,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_inbound_shipment_a_invoice_line_b: InvoiceLineRow = InvoiceLineRow {
@@ -240,6 +267,9 @@ pub fn mock_inbound_shipment_a_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockIn,
         number_of_packs: 1,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![
@@ -268,6 +298,9 @@ pub fn mock_inbound_shipment_b_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockIn,
         number_of_packs: 1,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_inbound_shipment_b_invoice_line_b: InvoiceLineRow = InvoiceLineRow {
@@ -289,6 +322,9 @@ pub fn mock_inbound_shipment_b_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockIn,
         number_of_packs: 1,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![
@@ -317,6 +353,9 @@ pub fn mock_inbound_shipment_c_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockIn,
         number_of_packs: 3,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_inbound_shipment_c_invoice_line_b: InvoiceLineRow = InvoiceLineRow {
@@ -338,6 +377,9 @@ pub fn mock_inbound_shipment_c_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockIn,
         number_of_packs: 2,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![
@@ -366,6 +408,9 @@ pub fn mock_inbound_shipment_d_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockIn,
         number_of_packs: 7,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_inbound_shipment_d_invoice_line_b: InvoiceLineRow = InvoiceLineRow {
@@ -387,6 +432,9 @@ pub fn mock_inbound_shipment_d_invoice_lines() -> Vec<InvoiceLineRow> {
         r#type: InvoiceLineRowType::StockIn,
         number_of_packs: 2,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![
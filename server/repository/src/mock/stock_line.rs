@@ -17,6 +17,9 @@ pub fn mock_stock_line_a() -> StockLineRow {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     }
 }
 
@@ -35,6 +38,9 @@ pub fn mock_stock_line_b() -> StockLineRow {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     }
 }
 
@@ -61,6 +67,9 @@ pub fn mock_item_b_lines() -> Vec<StockLineRow> {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_item_b_line_b: StockLineRow = StockLineRow {
@@ -77,6 +86,9 @@ pub fn mock_item_b_lines() -> Vec<StockLineRow> {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_item_b_line_a, mock_item_b_line_b]
@@ -97,6 +109,9 @@ pub fn mock_item_c_lines() -> Vec<StockLineRow> {
         expiry_date: None,
         on_hold: false,
         note: Some("stock line note".to_owned()),
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_item_c_line_b: StockLineRow = StockLineRow {
@@ -113,6 +128,9 @@ pub fn mock_item_c_lines() -> Vec<StockLineRow> {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_item_c_line_a, mock_item_c_line_b]
@@ -133,6 +151,9 @@ pub fn mock_stock_line_si_d() -> Vec<StockLineRow> {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_stock_line_si_d_siline_b: StockLineRow = StockLineRow {
@@ -149,6 +170,9 @@ pub fn mock_stock_line_si_d() -> Vec<StockLineRow> {
         expiry_date: Some(NaiveDate::from_ymd(2020, 8, 11)),
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_stock_line_si_d_siline_a, mock_stock_line_si_d_siline_b]
@@ -169,6 +193,9 @@ pub fn mock_stock_line_ci_c() -> Vec<StockLineRow> {
         expiry_date: Some(NaiveDate::from_ymd(2020, 1, 4)),
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     let mock_stock_line_ci_c_siline_b: StockLineRow = StockLineRow {
@@ -185,6 +212,9 @@ pub fn mock_stock_line_ci_c() -> Vec<StockLineRow> {
         expiry_date: Some(NaiveDate::from_ymd(2020, 3, 23)),
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_stock_line_ci_c_siline_a, mock_stock_line_ci_c_siline_b]
@@ -205,6 +235,9 @@ pub fn mock_stock_line_ci_d() -> Vec<StockLineRow> {
         expiry_date: Some(NaiveDate::from_ymd(2020, 1, 4)),
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_stock_line_ci_d_siline_a]
@@ -225,6 +258,9 @@ pub fn mock_item_query_test1() -> Vec<StockLineRow> {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_item_query_test1]
@@ -245,6 +281,9 @@ pub fn mock_stock_line_on_hold() -> Vec<StockLineRow> {
         expiry_date: None,
         on_hold: true,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_stock_line_on_hold]
@@ -265,6 +304,9 @@ pub fn mock_stock_line_location_is_on_hold() -> Vec<StockLineRow> {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     vec![mock_stock_line_location_is_on_hold]
@@ -16,6 +16,10 @@ pub fn mock_stocktake_line_a() -> StocktakeLineRow {
         cost_price_per_pack: None,
         sell_price_per_pack: None,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
+        needs_recount: false,
     }
 }
 
@@ -35,6 +39,10 @@ pub fn mock_stocktake_line_b() -> StocktakeLineRow {
         cost_price_per_pack: None,
         sell_price_per_pack: None,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
+        needs_recount: false,
     }
 }
 
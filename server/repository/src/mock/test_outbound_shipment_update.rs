@@ -28,6 +28,9 @@ fn mock_outbound_shipment_line_no_stock_line() -> InvoiceLineRow {
         r#type: InvoiceLineRowType::StockOut,
         number_of_packs: 1,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     }
 }
 
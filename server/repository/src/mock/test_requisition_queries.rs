@@ -167,6 +167,9 @@ pub fn mock_invoice1_linked_to_requisition() -> FullMockInvoice {
                     r#type: InvoiceLineRowType::StockOut,
                     number_of_packs: 10,
                     note: None,
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 },
                 stock_line: StockLineRow {
                     id: line1_id.clone(),
@@ -182,6 +185,9 @@ pub fn mock_invoice1_linked_to_requisition() -> FullMockInvoice {
                     expiry_date: None,
                     on_hold: false,
                     note: None,
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 },
             },
             FullMockInvoiceLine {
@@ -204,6 +210,9 @@ pub fn mock_invoice1_linked_to_requisition() -> FullMockInvoice {
                     r#type: InvoiceLineRowType::StockOut,
                     number_of_packs: 10,
                     note: None,
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 },
                 stock_line: StockLineRow {
                     id: line2_id.clone(),
@@ -219,6 +228,9 @@ pub fn mock_invoice1_linked_to_requisition() -> FullMockInvoice {
                     expiry_date: None,
                     on_hold: false,
                     note: None,
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 },
             },
         ],
@@ -260,6 +272,9 @@ pub fn mock_invoice2_linked_to_requisition() -> FullMockInvoice {
                 r#type: InvoiceLineRowType::StockOut,
                 number_of_packs: 10,
                 note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             },
             stock_line: StockLineRow {
                 id: line1_id.clone(),
@@ -275,6 +290,9 @@ pub fn mock_invoice2_linked_to_requisition() -> FullMockInvoice {
                 expiry_date: None,
                 on_hold: false,
                 note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             },
         }],
     }
@@ -315,6 +333,9 @@ pub fn mock_invoice3_linked_to_requisition() -> FullMockInvoice {
                 r#type: InvoiceLineRowType::StockOut,
                 number_of_packs: 10,
                 note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             },
             stock_line: StockLineRow {
                 id: line1_id.clone(),
@@ -330,6 +351,9 @@ pub fn mock_invoice3_linked_to_requisition() -> FullMockInvoice {
                 expiry_date: None,
                 on_hold: false,
                 note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             },
         }],
     }
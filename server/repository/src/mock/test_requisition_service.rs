@@ -326,6 +326,9 @@ pub fn mock_new_response_requisition_test_invoice() -> FullMockInvoice {
                     note: None,
                     location_id: None,
                     stock_line_id: None,
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 },
                 stock_line: mock_stock_line_a(),
             },
@@ -349,6 +352,9 @@ pub fn mock_new_response_requisition_test_invoice() -> FullMockInvoice {
                     note: None,
                     location_id: None,
                     stock_line_id: None,
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 },
                 stock_line: mock_stock_line_a(),
             },
@@ -95,6 +95,9 @@ pub fn mock_stock_line_stocktake_surplus() -> StockLineRow {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     }
 }
 
@@ -136,6 +139,9 @@ pub fn mock_stock_line_stocktake_deficit() -> StockLineRow {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     }
 }
 
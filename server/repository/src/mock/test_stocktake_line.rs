@@ -19,6 +19,9 @@ pub fn mock_new_stock_line_for_stocktake_a() -> StockLineRow {
         expiry_date: None,
         on_hold: false,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     }
 }
 
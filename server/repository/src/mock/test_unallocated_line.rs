@@ -51,6 +51,9 @@ pub fn mock_unallocated_line() -> InvoiceLineRow {
         r#type: InvoiceLineRowType::UnallocatedStock,
         number_of_packs: 1,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     }
 }
 
@@ -88,6 +91,9 @@ pub fn mock_unallocated_line2() -> InvoiceLineRow {
         r#type: InvoiceLineRowType::UnallocatedStock,
         number_of_packs: 1,
         note: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     }
 }
 
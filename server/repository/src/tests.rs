@@ -64,6 +64,9 @@ mod repository_test {
                 on_hold: false,
                 note: None,
                 location_id: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }
         }
 
@@ -158,6 +161,9 @@ mod repository_test {
                 number_of_packs: 1,
                 note: None,
                 location_id: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }
         }
         pub fn invoice_line_2() -> InvoiceLineRow {
@@ -180,6 +186,9 @@ mod repository_test {
                 number_of_packs: 1,
                 note: None,
                 location_id: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }
         }
 
@@ -203,6 +212,9 @@ mod repository_test {
                 number_of_packs: 1,
                 note: None,
                 location_id: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }
         }
 
@@ -226,6 +238,9 @@ mod repository_test {
                 number_of_packs: 1,
                 note: None,
                 location_id: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }
         }
 
@@ -737,10 +752,9 @@ mod repository_test {
         repo.insert_one(&central_sync_buffer_row_b).await.unwrap();
 
         // `remove_all` removes all buffered records.
-        repo.remove_all().await.unwrap();
+        repo.remove_all().unwrap();
         let result = repo
             .get_sync_entries(&central_sync_buffer_row_a.table_name)
-            .await
             .unwrap();
         assert!(result.is_empty());
     }
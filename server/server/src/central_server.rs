@@ -0,0 +1,275 @@
+use actix_web::{
+    guard,
+    http::StatusCode,
+    web::{self, Data},
+    HttpRequest, HttpResponse, ResponseError,
+};
+use log::error;
+use repository::{RepositoryError, StorageConnection, SyncSiteRow};
+use serde::Deserialize;
+use service::{
+    apis::{sync_api_v3::RemotePostRecordV3, sync_api_v5::RemoteSyncAckV5},
+    service_provider::ServiceProvider,
+    settings::Settings,
+    sync::central_server::{
+        acknowledge_records, authenticate_site, central_records, initialise_site, queued_records,
+        receive_pushed_records, site_integrity_records, site_store_integrity, CentralServerError,
+    },
+};
+use thiserror::Error;
+
+/// Serves the sync api for remote sites, see `service::sync::central_server`
+pub fn config_central_server(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/sync/v5/initialise")
+            .guard(guard::Post())
+            .to(initialise),
+    )
+    .service(
+        web::resource("/sync/v5/queued_records")
+            .guard(guard::Get())
+            .to(get_queued_records),
+    )
+    .service(
+        web::resource("/sync/v5/acknowledged_records")
+            .guard(guard::Post())
+            .to(post_acknowledged_records),
+    )
+    .service(
+        web::resource("/sync/v5/central_records")
+            .guard(guard::Get())
+            .to(get_central_records),
+    )
+    .service(
+        web::resource("/sync/v5/integrity")
+            .guard(guard::Get())
+            .to(get_integrity),
+    )
+    .service(
+        web::resource("/sync/v5/integrity_records")
+            .guard(guard::Get())
+            .to(get_integrity_records),
+    )
+    .service(
+        web::resource("/sync/v3/queued_records")
+            .guard(guard::Post())
+            .to(post_queued_records),
+    );
+}
+
+#[derive(Debug, Error)]
+enum SyncEndpointError {
+    #[error("Missing or invalid basic auth header")]
+    MissingCredentials,
+    #[error("Missing msupply-site-uuid header")]
+    MissingHardwareId,
+    #[error("Missing or invalid msupply-site-id header")]
+    MissingSiteId,
+    #[error("Invalid request body: {0}")]
+    InvalidBody(#[from] serde_json::Error),
+    #[error("Central server mode is not configured")]
+    NotConfigured,
+    #[error(transparent)]
+    CentralServerError(#[from] CentralServerError),
+}
+
+impl From<RepositoryError> for SyncEndpointError {
+    fn from(error: RepositoryError) -> Self {
+        SyncEndpointError::CentralServerError(error.into())
+    }
+}
+
+impl ResponseError for SyncEndpointError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SyncEndpointError::MissingCredentials
+            | SyncEndpointError::MissingHardwareId
+            | SyncEndpointError::MissingSiteId
+            | SyncEndpointError::CentralServerError(CentralServerError::Unauthorised)
+            | SyncEndpointError::CentralServerError(CentralServerError::HardwareIdMismatch) => {
+                StatusCode::UNAUTHORIZED
+            }
+            SyncEndpointError::InvalidBody(_)
+            | SyncEndpointError::CentralServerError(CentralServerError::SiteNotInitialised)
+            | SyncEndpointError::CentralServerError(CentralServerError::InvalidPushSites {
+                ..
+            })
+            | SyncEndpointError::CentralServerError(CentralServerError::InvalidRequest(_)) => {
+                StatusCode::BAD_REQUEST
+            }
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if self.status_code() == StatusCode::INTERNAL_SERVER_ERROR {
+            error!("Central server: {:?}", self);
+        }
+        HttpResponse::build(self.status_code()).body(self.to_string())
+    }
+}
+
+/// Returns the hardware id, the site id and the password_sha256 of the basic auth credentials
+fn request_credentials(request: &HttpRequest) -> Result<(String, u32, String), SyncEndpointError> {
+    let hardware_id = request
+        .headers()
+        .get("msupply-site-uuid")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(SyncEndpointError::MissingHardwareId)?
+        .to_string();
+    let site_id = request
+        .headers()
+        .get("msupply-site-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .ok_or(SyncEndpointError::MissingSiteId)?;
+
+    let encoded = request
+        .headers()
+        .get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .ok_or(SyncEndpointError::MissingCredentials)?;
+    let decoded = base64::decode(encoded.trim())
+        .ok()
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .ok_or(SyncEndpointError::MissingCredentials)?;
+    // The username is only informative, sites are identified by their site id
+    let (_, password_sha256) = decoded
+        .split_once(':')
+        .ok_or(SyncEndpointError::MissingCredentials)?;
+
+    Ok((hardware_id, site_id, password_sha256.to_string()))
+}
+
+/// Authenticates the site of the request
+fn site_connection(
+    request: &HttpRequest,
+    service_provider: &ServiceProvider,
+) -> Result<(StorageConnection, SyncSiteRow, String), SyncEndpointError> {
+    let (hardware_id, site_id, password_sha256) = request_credentials(request)?;
+    let connection = service_provider.connection()?;
+    let site = authenticate_site(&connection, site_id, &password_sha256, &hardware_id)?;
+    Ok((connection, site, hardware_id))
+}
+
+async fn initialise(
+    request: HttpRequest,
+    service_provider: Data<ServiceProvider>,
+) -> Result<HttpResponse, SyncEndpointError> {
+    let (connection, site, hardware_id) = site_connection(&request, &service_provider)?;
+    let batch = initialise_site(&connection, site, &hardware_id)?;
+    Ok(HttpResponse::Ok().json(batch))
+}
+
+#[derive(Debug, Deserialize)]
+struct QueuedRecordsQuery {
+    limit: u32,
+}
+
+async fn get_queued_records(
+    request: HttpRequest,
+    query: web::Query<QueuedRecordsQuery>,
+    service_provider: Data<ServiceProvider>,
+) -> Result<HttpResponse, SyncEndpointError> {
+    let (connection, site, _) = site_connection(&request, &service_provider)?;
+    let batch = queued_records(&connection, &site, query.limit)?;
+    Ok(HttpResponse::Ok().json(batch))
+}
+
+async fn post_acknowledged_records(
+    request: HttpRequest,
+    // The sync client doesn't set a content type, i.e. the body is parsed manually
+    body: web::Bytes,
+    service_provider: Data<ServiceProvider>,
+) -> Result<HttpResponse, SyncEndpointError> {
+    let (connection, site, _) = site_connection(&request, &service_provider)?;
+    let ack: RemoteSyncAckV5 = serde_json::from_slice(&body)?;
+    acknowledge_records(&connection, &site, &ack.sync_ids)?;
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[derive(Debug, Deserialize)]
+struct CentralRecordsQuery {
+    cursor: u32,
+    limit: u32,
+}
+
+async fn get_central_records(
+    request: HttpRequest,
+    query: web::Query<CentralRecordsQuery>,
+    service_provider: Data<ServiceProvider>,
+) -> Result<HttpResponse, SyncEndpointError> {
+    let (connection, _, _) = site_connection(&request, &service_provider)?;
+    let batch = central_records(&connection, query.cursor, query.limit)?;
+    Ok(HttpResponse::Ok().json(batch))
+}
+
+#[derive(Debug, Deserialize)]
+struct IntegrityQuery {
+    store_id: String,
+}
+
+async fn get_integrity(
+    request: HttpRequest,
+    query: web::Query<IntegrityQuery>,
+    service_provider: Data<ServiceProvider>,
+) -> Result<HttpResponse, SyncEndpointError> {
+    let (connection, site, _) = site_connection(&request, &service_provider)?;
+    let integrity = site_store_integrity(&connection, &site, &query.store_id)?;
+    Ok(HttpResponse::Ok().json(integrity))
+}
+
+#[derive(Debug, Deserialize)]
+struct IntegrityRecordsQuery {
+    store_id: String,
+    table_name: String,
+    range: String,
+}
+
+async fn get_integrity_records(
+    request: HttpRequest,
+    query: web::Query<IntegrityRecordsQuery>,
+    service_provider: Data<ServiceProvider>,
+) -> Result<HttpResponse, SyncEndpointError> {
+    let (connection, site, _) = site_connection(&request, &service_provider)?;
+    let batch = site_integrity_records(
+        &connection,
+        &site,
+        &query.store_id,
+        &query.table_name,
+        &query.range,
+    )?;
+    Ok(HttpResponse::Ok().json(batch))
+}
+
+#[derive(Debug, Deserialize)]
+struct PushQuery {
+    from_site: u32,
+    to_site: u32,
+}
+
+async fn post_queued_records(
+    request: HttpRequest,
+    query: web::Query<PushQuery>,
+    body: web::Bytes,
+    service_provider: Data<ServiceProvider>,
+    settings: Data<Settings>,
+) -> Result<HttpResponse, SyncEndpointError> {
+    let central_server_settings = settings
+        .central_server
+        .as_ref()
+        .ok_or(SyncEndpointError::NotConfigured)?;
+    let (connection, site, _) = site_connection(&request, &service_provider)?;
+    let records: Vec<RemotePostRecordV3> = serde_json::from_slice(&body)?;
+    receive_pushed_records(
+        &connection,
+        central_server_settings,
+        &site,
+        query.from_site,
+        query.to_site,
+        records,
+    )?;
+    // The v3 sync client expects a json response without an "error" field
+    Ok(HttpResponse::Ok().json(serde_json::json!({})))
+}
@@ -2,7 +2,8 @@
 extern crate machine_uid;
 
 use crate::{
-    certs::Certificates, configuration::get_or_create_token_secret, cors::cors_policy,
+    central_server::config_central_server, certs::Certificates,
+    configuration::get_or_create_token_secret, cors::cors_policy,
     serve_frontend::config_server_frontend, static_files::config_static_files,
 };
 
@@ -15,9 +16,14 @@ use repository::{get_storage_connection_manager, run_db_migrations, StorageConne
 
 use service::{
     auth_data::AuthData,
+    cycle_count::schedule::run_cycle_count_scheduler,
     service_provider::ServiceProvider,
     settings::{is_develop, ServerSettings, Settings},
-    sync::Synchroniser,
+    stock_line_reservation::release::run_stock_line_reservation_release_scheduler,
+    stock_quarantine::run_expired_stock_quarantine_scheduler,
+    sync::{
+        changelog_compaction::run_changelog_compaction_scheduler, get_sync_actors, Synchroniser,
+    },
     token_bucket::TokenBucket,
 };
 
@@ -25,9 +31,11 @@ use actix_web::{web::Data, App, HttpServer};
 use std::{
     ops::{Deref, DerefMut},
     sync::{Arc, RwLock},
+    time::Duration,
 };
 use tokio::sync::{oneshot, Mutex};
 
+mod central_server;
 pub mod certs;
 pub mod configuration;
 pub mod cors;
@@ -40,6 +48,13 @@ pub mod static_files;
 #[cfg(not(target_os = "android"))]
 mod discovery;
 
+/// How often due cycle count stocktakes are checked for
+const CYCLE_COUNT_SCHEDULER_INTERVAL_SEC: u64 = 60 * 60;
+/// How often newly expired stock is put on hold
+const EXPIRED_STOCK_QUARANTINE_INTERVAL_SEC: u64 = 60 * 60;
+/// How often stock line reservations that are no longer held are released
+const STOCK_LINE_RESERVATION_RELEASE_INTERVAL_SEC: u64 = 60 * 60;
+
 fn auth_data(
     server_settings: &ServerSettings,
     token_bucket: Arc<RwLock<TokenBucket>>,
@@ -167,8 +182,11 @@ async fn run_server(
 
     let db_settings = service.sync_settings(&service_context).unwrap();
     let sync_settings = db_settings.or(config_settings.sync.clone());
+    let is_central_server = config_settings.central_server.is_some();
     let sync_settings = match sync_settings {
-        Some(sync_settings) => sync_settings,
+        Some(sync_settings) => Some(sync_settings),
+        // A central server doesn't need to sync with another server
+        None if is_central_server => None,
         // No sync settings found, start in stage0 mode
         None => {
             return run_stage0(
@@ -184,7 +202,7 @@ async fn run_server(
     };
     // Final settings:
     let mut settings = config_settings;
-    settings.sync = Some(sync_settings.clone());
+    settings.sync = sync_settings.clone();
 
     let auth_data = auth_data(
         &settings.server,
@@ -209,10 +227,23 @@ async fn run_server(
 
     let restart_switch = Data::new(restart_switch);
 
-    let mut synchroniser =
-        Synchroniser::new(sync_settings, service_provider_data.deref().clone()).unwrap();
+    let synchroniser = sync_settings.map(|sync_settings| {
+        Synchroniser::new(sync_settings, service_provider_data.deref().clone()).unwrap()
+    });
+    let (sync_sender, sync_receiver) = get_sync_actors();
+    let sync_sender = Data::new(sync_sender);
+    let cycle_count_service_provider = service_provider_data.deref().clone();
+    let quarantine_service_provider = service_provider_data.deref().clone();
+    let reservation_service_provider = service_provider_data.deref().clone();
+    let compaction_service_provider = service_provider_data.deref().clone();
+    // Changelogs are only pushed if this server syncs with a central server
+    let is_push_cursor_used = synchroniser.is_some();
     // Do the initial pull before doing anything else
-    match synchroniser.initial_pull().await {
+    let initial_pull = match &synchroniser {
+        Some(synchroniser) => synchroniser.initial_pull().await,
+        None => Ok(()),
+    };
+    match initial_pull {
         Ok(_) => {}
         Err(err) => {
             error!("Failed to perform the initial sync: {}", err);
@@ -235,21 +266,27 @@ async fn run_server(
 
     let mut http_server = HttpServer::new(move || {
         let cors = cors_policy(&closure_settings);
-        App::new()
+        let mut app = App::new()
             .wrap(logger_middleware())
             .wrap(cors)
-            .wrap(compress_middleware())
-            .configure(graphql_config(
-                connection_manager_data_app.clone(),
-                loader_registry_data.clone(),
-                service_provider_data.clone(),
-                auth_data.clone(),
-                settings_data.clone(),
-                restart_switch.clone(),
-            ))
-            .app_data(Data::new(closure_settings.clone()))
-            .configure(config_static_files)
-            .configure(config_server_frontend)
+            .wrap(compress_middleware());
+        if is_central_server {
+            app = app
+                .app_data(service_provider_data.clone())
+                .configure(config_central_server);
+        }
+        app.configure(graphql_config(
+            connection_manager_data_app.clone(),
+            loader_registry_data.clone(),
+            service_provider_data.clone(),
+            auth_data.clone(),
+            settings_data.clone(),
+            restart_switch.clone(),
+            sync_sender.clone(),
+        ))
+        .app_data(Data::new(closure_settings.clone()))
+        .configure(config_static_files)
+        .configure(config_server_frontend)
     })
     .disable_signals();
 
@@ -271,8 +308,28 @@ async fn run_server(
         _ = off_switch => false,
         _ = restart_switch_receiver.recv() => true,
         () = async {
-            synchroniser.run().await;
+            match synchroniser {
+                Some(synchroniser) => synchroniser.run(sync_receiver).await,
+                None => std::future::pending().await,
+            }
         } => unreachable!("Synchroniser unexpectedly died!?"),
+        () = run_cycle_count_scheduler(
+            cycle_count_service_provider,
+            Duration::from_secs(CYCLE_COUNT_SCHEDULER_INTERVAL_SEC),
+        ) => unreachable!("Cycle count scheduler unexpectedly died!?"),
+        () = run_expired_stock_quarantine_scheduler(
+            quarantine_service_provider,
+            Duration::from_secs(EXPIRED_STOCK_QUARANTINE_INTERVAL_SEC),
+        ) => unreachable!("Expired stock quarantine scheduler unexpectedly died!?"),
+        () = run_stock_line_reservation_release_scheduler(
+            reservation_service_provider,
+            Duration::from_secs(STOCK_LINE_RESERVATION_RELEASE_INTERVAL_SEC),
+        ) => unreachable!("Stock line reservation release scheduler unexpectedly died!?"),
+        () = run_changelog_compaction_scheduler(
+            compaction_service_provider,
+            settings.changelog.clone().unwrap_or_default(),
+            is_push_cursor_used,
+        ) => unreachable!("Changelog compaction scheduler unexpectedly died!?"),
     };
 
     server_handle.stop(true).await;
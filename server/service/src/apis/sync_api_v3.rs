@@ -42,9 +42,10 @@ fn validate_response(response: serde_json::Value) -> Result<serde_json::Value, a
     Ok(response)
 }
 
-fn extra_headers(hardware_id: &str) -> anyhow::Result<HeaderMap> {
+fn extra_headers(hardware_id: &str, site_id: u32) -> anyhow::Result<HeaderMap> {
     let mut headers = HeaderMap::new();
     headers.insert("msupply-site-uuid", format!("{}", hardware_id).parse()?);
+    headers.insert("msupply-site-id", format!("{}", site_id).parse()?);
     headers.insert(CONTENT_LENGTH, "application/json".parse()?);
     headers.insert(ACCEPT, "application/json".parse()?);
     Ok(headers)
@@ -113,10 +114,11 @@ impl SyncApiV3 {
         credentials: SyncCredentials,
         client: Client,
         hardware_id: &str,
+        site_id: u32,
     ) -> anyhow::Result<Self> {
         Ok(SyncApiV3 {
             server_url,
-            extra_headers: extra_headers(hardware_id)?,
+            extra_headers: extra_headers(hardware_id, site_id)?,
             client,
             credentials,
         })
@@ -1,7 +1,7 @@
 use log::info;
 use reqwest::{
     header::{HeaderMap, HeaderName, CONTENT_LENGTH},
-    Client, Response, Url,
+    Client, Response, StatusCode, Url,
 };
 use serde::{Deserialize, Serialize};
 
@@ -67,6 +67,32 @@ pub struct CentralSyncBatchV5 {
     pub data: Option<Vec<CentralSyncRecordV5>>,
 }
 
+/// Row count and content hash of the records of a table whose id starts with `range`
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct IntegrityRangeV5 {
+    pub range: String,
+    pub count: u32,
+    pub hash: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TableIntegrityV5 {
+    #[serde(rename = "tableName")]
+    pub table_name: String,
+    pub count: u32,
+    pub hash: String,
+    pub ranges: Vec<IntegrityRangeV5>,
+}
+
+/// Summary of the remote data of a store, used to verify that a site is in sync with the central
+/// server
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct StoreIntegrityV5 {
+    #[serde(rename = "storeId")]
+    pub store_id: String,
+    pub tables: Vec<TableIntegrityV5>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SyncApiV5 {
     server_url: Url,
@@ -75,12 +101,16 @@ pub struct SyncApiV5 {
     headers: HeaderMap,
 }
 
-fn generate_headers(hardware_id: &str) -> HeaderMap {
+fn generate_headers(hardware_id: &str, site_id: u32) -> HeaderMap {
     let mut headers = HeaderMap::new();
     headers.insert(
         HeaderName::from_static("msupply-site-uuid"),
         format!("{}", hardware_id).parse().unwrap(),
     );
+    headers.insert(
+        HeaderName::from_static("msupply-site-id"),
+        format!("{}", site_id).parse().unwrap(),
+    );
     headers.insert(
         HeaderName::from_static("app-version"),
         "1.0".parse().unwrap(),
@@ -106,12 +136,13 @@ impl SyncApiV5 {
         credentials: SyncCredentials,
         client: Client,
         hardware_id: &str,
+        site_id: u32,
     ) -> SyncApiV5 {
         SyncApiV5 {
             server_url,
             credentials,
             client,
-            headers: generate_headers(&hardware_id),
+            headers: generate_headers(&hardware_id, site_id),
         }
     }
 
@@ -221,6 +252,70 @@ impl SyncApiV5 {
 
         Ok(sync_batch)
     }
+
+    // Get the integrity summary of the remote data of a store.
+    //
+    // Returns None if the central server doesn't provide integrity summaries.
+    pub async fn get_store_integrity(
+        &self,
+        store_id: &str,
+    ) -> Result<Option<StoreIntegrityV5>, SyncConnectionError> {
+        let url = self.server_url.join("/sync/v5/integrity")?;
+
+        let query = [("store_id", store_id)];
+        let response = self
+            .client
+            .get(url)
+            .basic_auth(
+                &self.credentials.username,
+                Some(&self.credentials.password_sha256),
+            )
+            .query(&query)
+            .headers(self.headers.clone())
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = check_status(response).await?;
+
+        let integrity = response.json::<StoreIntegrityV5>().await?;
+
+        Ok(Some(integrity))
+    }
+
+    // Get all remote records of a store in an integrity range, e.g. to re-pull a range that
+    // doesn't match the central server.
+    pub async fn get_integrity_records(
+        &self,
+        store_id: &str,
+        table_name: &str,
+        range: &str,
+    ) -> Result<RemoteSyncBatchV5, SyncConnectionError> {
+        let url = self.server_url.join("/sync/v5/integrity_records")?;
+
+        let query = [
+            ("store_id", store_id),
+            ("table_name", table_name),
+            ("range", range),
+        ];
+        let response = self
+            .client
+            .get(url)
+            .basic_auth(
+                &self.credentials.username,
+                Some(&self.credentials.password_sha256),
+            )
+            .query(&query)
+            .headers(self.headers.clone())
+            .send()
+            .await?;
+        let response = check_status(response).await?;
+
+        let sync_batch = response.json::<RemoteSyncBatchV5>().await?;
+
+        Ok(sync_batch)
+    }
 }
 
 #[cfg(test)]
@@ -235,8 +330,9 @@ mod tests {
     use crate::apis::{
         sync_api_credentials::SyncCredentials,
         sync_api_v5::{
-            CentralSyncBatchV5, CentralSyncRecordV5, RemoteSyncAckV5, RemoteSyncActionV5,
-            RemoteSyncBatchV5, RemoteSyncRecordV5,
+            CentralSyncBatchV5, CentralSyncRecordV5, IntegrityRangeV5, RemoteSyncAckV5,
+            RemoteSyncActionV5, RemoteSyncBatchV5, RemoteSyncRecordV5, StoreIntegrityV5,
+            TableIntegrityV5,
         },
     };
 
@@ -246,7 +342,7 @@ mod tests {
         let url = Url::parse(url).unwrap();
         let credentials = SyncCredentials::from_plain(username, password);
         let client = Client::new();
-        SyncApiV5::new(url, credentials, client, "hardware_id")
+        SyncApiV5::new(url, credentials, client, "hardware_id", 1)
     }
 
     #[actix_rt::test]
@@ -458,4 +554,54 @@ mod tests {
 
         assert!(pull_central_records_result_without_auth.is_err());
     }
+
+    #[actix_rt::test]
+    async fn test_get_store_integrity() {
+        let mock_server = MockServer::start();
+        let url = mock_server.base_url();
+
+        let mock_integrity_body = StoreIntegrityV5 {
+            store_id: "store_a".to_string(),
+            tables: vec![TableIntegrityV5 {
+                table_name: "Location".to_string(),
+                count: 1,
+                hash: "table_hash".to_string(),
+                ranges: vec![IntegrityRangeV5 {
+                    range: "a".to_string(),
+                    count: 1,
+                    hash: "range_hash".to_string(),
+                }],
+            }],
+        };
+
+        mock_server.mock(|when, then| {
+            when.method(GET)
+                .path("/sync/v5/integrity")
+                .query_param("store_id", "store_a");
+            then.status(200)
+                .body(serde_json::to_string(&mock_integrity_body).unwrap());
+        });
+
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/sync/v5/integrity");
+            then.status(404);
+        });
+
+        let sync_connection = create_api(&url, "username", "password");
+        assert_eq!(
+            sync_connection
+                .get_store_integrity("store_a")
+                .await
+                .unwrap(),
+            Some(mock_integrity_body)
+        );
+        // Central server without integrity endpoint
+        assert_eq!(
+            sync_connection
+                .get_store_integrity("store_b")
+                .await
+                .unwrap(),
+            None
+        );
+    }
 }
@@ -30,8 +30,22 @@ pub enum Resource {
     // location
     QueryLocation,
     MutateLocation,
+    // serial number
+    QuerySerialNumber,
+    MutateSerialNumber,
+    // vaccine vial monitor
+    QueryVvmStatus,
+    MutateVvmStatus,
+    // open vial wastage
+    QueryOpenVialWastage,
+    MutateOpenVialWastage,
+    // stock line reservation
+    QueryStockLineReservation,
+    MutateStockLineReservation,
     // store
     QueryStore,
+    QueryStorePreference,
+    MutateStorePreference,
     // master list
     QueryMasterList,
     // items
@@ -41,6 +55,10 @@ pub enum Resource {
     // stocktake
     QueryStocktake,
     MutateStocktake,
+    ApproveStocktake,
+    // item classification and cycle count plans
+    QueryCycleCount,
+    MutateCycleCount,
     // requisition
     QueryRequisition,
     MutateRequisition,
@@ -60,6 +78,8 @@ pub enum Resource {
     Report,
     // view/edit server setting
     QueryLog,
+    QuerySyncStatus,
+    ManualSync,
     ServerAdmin,
 }
 
@@ -85,8 +105,84 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
         ]),
     );
 
+    // serial number
+    map.insert(
+        Resource::QuerySerialNumber,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::StockLineQuery),
+        ]),
+    );
+    map.insert(
+        Resource::MutateSerialNumber,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::Any(vec![
+                PermissionDSL::HasPermission(Permission::InboundShipmentMutate),
+                PermissionDSL::HasPermission(Permission::OutboundShipmentMutate),
+            ]),
+        ]),
+    );
+    // vaccine vial monitor
+    map.insert(
+        Resource::QueryVvmStatus,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::StockLineQuery),
+        ]),
+    );
+    map.insert(
+        Resource::MutateVvmStatus,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::Any(vec![
+                PermissionDSL::HasPermission(Permission::InboundShipmentMutate),
+                PermissionDSL::HasPermission(Permission::OutboundShipmentMutate),
+                PermissionDSL::HasPermission(Permission::StocktakeMutate),
+            ]),
+        ]),
+    );
+    // open vial wastage
+    map.insert(
+        Resource::QueryOpenVialWastage,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::StockLineQuery),
+        ]),
+    );
+    map.insert(
+        Resource::MutateOpenVialWastage,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::OutboundShipmentMutate),
+        ]),
+    );
+    // stock line reservation
+    map.insert(
+        Resource::QueryStockLineReservation,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::StockLineQuery),
+        ]),
+    );
+    map.insert(
+        Resource::MutateStockLineReservation,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::OutboundShipmentMutate),
+        ]),
+    );
+
     // store: No permission needed
     map.insert(Resource::QueryStore, PermissionDSL::NoPermissionRequired);
+    map.insert(Resource::QueryStorePreference, PermissionDSL::HasStoreAccess);
+    map.insert(
+        Resource::MutateStorePreference,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::ServerAdmin),
+        ]),
+    );
     // master list
     map.insert(Resource::QueryMasterList, PermissionDSL::HasStoreAccess);
 
@@ -117,6 +213,28 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
             PermissionDSL::HasPermission(Permission::StocktakeMutate),
         ]),
     );
+    map.insert(
+        Resource::ApproveStocktake,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::StocktakeApprove),
+        ]),
+    );
+    // item classification and cycle count plans
+    map.insert(
+        Resource::QueryCycleCount,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::StocktakeQuery),
+        ]),
+    );
+    map.insert(
+        Resource::MutateCycleCount,
+        PermissionDSL::And(vec![
+            PermissionDSL::HasStoreAccess,
+            PermissionDSL::HasPermission(Permission::StocktakeMutate),
+        ]),
+    );
     // stock take line
     map.insert(
         Resource::InsertStocktakeLine,
@@ -209,6 +327,12 @@ fn all_permissions() -> HashMap<Resource, PermissionDSL> {
         Resource::QueryLog,
         PermissionDSL::HasPermission(Permission::LogQuery),
     );
+    // sync status is shown to all users and any user can sync the site
+    map.insert(
+        Resource::QuerySyncStatus,
+        PermissionDSL::NoPermissionRequired,
+    );
+    map.insert(Resource::ManualSync, PermissionDSL::NoPermissionRequired);
     map
 }
 
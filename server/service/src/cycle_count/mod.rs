@@ -0,0 +1,58 @@
+use self::{
+    query::{get_cycle_count_items, get_cycle_count_plans},
+    schedule::{create_due_cycle_count_stocktakes, CreateCycleCountStocktakesError},
+    upsert::{upsert_cycle_count_plan, UpsertCycleCountPlan, UpsertCycleCountPlanError},
+};
+
+use crate::{service_provider::ServiceContext, SingleRecordError};
+use chrono::NaiveDate;
+use repository::{CycleCountPlanRow, ItemClassificationRow, RepositoryError, Stocktake};
+
+pub mod query;
+pub mod schedule;
+pub mod upsert;
+
+pub trait CycleCountServiceTrait: Sync + Send {
+    fn get_cycle_count_plans(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+        get_cycle_count_plans(ctx, store_id)
+    }
+
+    /// Classified items of the store that are due for counting in the plan's cycle week of the
+    /// given date
+    fn get_cycle_count_items(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        plan_id: &str,
+        date: NaiveDate,
+    ) -> Result<Vec<ItemClassificationRow>, SingleRecordError> {
+        get_cycle_count_items(ctx, store_id, plan_id, date)
+    }
+
+    fn upsert_cycle_count_plan(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        user_id: &str,
+        input: UpsertCycleCountPlan,
+    ) -> Result<CycleCountPlanRow, UpsertCycleCountPlanError> {
+        upsert_cycle_count_plan(ctx, store_id, user_id, input)
+    }
+
+    /// Creates the stocktakes of all active plans whose cycle week of the given date has not been
+    /// scheduled yet
+    fn create_due_cycle_count_stocktakes(
+        &self,
+        ctx: &ServiceContext,
+        date: NaiveDate,
+    ) -> Result<Vec<Stocktake>, CreateCycleCountStocktakesError> {
+        create_due_cycle_count_stocktakes(ctx, date)
+    }
+}
+
+pub struct CycleCountService {}
+impl CycleCountServiceTrait for CycleCountService {}
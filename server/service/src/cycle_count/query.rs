@@ -0,0 +1,36 @@
+use chrono::NaiveDate;
+use repository::{
+    CycleCountPlanRow, CycleCountPlanRowRepository, ItemClassificationRow,
+    ItemClassificationRowRepository, RepositoryError,
+};
+
+use crate::{service_provider::ServiceContext, SingleRecordError};
+
+use super::schedule::{cycle_week, due_items};
+
+pub fn get_cycle_count_plans(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<Vec<CycleCountPlanRow>, RepositoryError> {
+    CycleCountPlanRowRepository::new(&ctx.connection).find_many_by_store_id(store_id)
+}
+
+pub fn get_cycle_count_items(
+    ctx: &ServiceContext,
+    store_id: &str,
+    plan_id: &str,
+    date: NaiveDate,
+) -> Result<Vec<ItemClassificationRow>, SingleRecordError> {
+    let plan = match CycleCountPlanRowRepository::new(&ctx.connection).find_one_by_id(plan_id)? {
+        Some(plan) if plan.store_id == store_id => plan,
+        _ => return Err(SingleRecordError::NotFound(plan_id.to_string())),
+    };
+    let week = match cycle_week(&plan, date) {
+        Some(week) => week,
+        None => return Ok(vec![]),
+    };
+
+    let classifications =
+        ItemClassificationRowRepository::new(&ctx.connection).find_many_by_store_id(store_id)?;
+    Ok(due_items(&plan, week, classifications))
+}
@@ -0,0 +1,294 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{NaiveDate, Utc};
+use log::error;
+use repository::{
+    AbcClass, CycleCountPlanRow, CycleCountPlanRowRepository, ItemClassificationRow,
+    ItemClassificationRowRepository, RepositoryError, Stocktake,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::{ServiceContext, ServiceProvider},
+    stocktake::insert::{
+        insert_stocktake, InsertStocktake, InsertStocktakeError, StocktakeLineCriteria,
+    },
+};
+
+#[derive(Debug, PartialEq)]
+pub enum CreateCycleCountStocktakesError {
+    DatabaseError(RepositoryError),
+    /// Plan id and the error of inserting its stocktake
+    InsertStocktakeError(String, InsertStocktakeError),
+}
+
+/// Week of the plan's cycle the date is in, `None` if the plan hasn't started yet
+pub fn cycle_week(plan: &CycleCountPlanRow, date: NaiveDate) -> Option<i32> {
+    let days = (date - plan.start_date).num_days();
+    if days < 0 {
+        return None;
+    }
+    Some((days / 7) as i32)
+}
+
+/// Items of each class are split into as many groups as the class interval (in item id order) and
+/// the group matching the cycle week is due, i.e. each item is counted once per interval
+pub fn due_items(
+    plan: &CycleCountPlanRow,
+    week: i32,
+    mut classifications: Vec<ItemClassificationRow>,
+) -> Vec<ItemClassificationRow> {
+    classifications.sort_by(|a, b| a.item_id.cmp(&b.item_id));
+
+    let mut due = Vec::new();
+    for (class, interval) in [
+        (AbcClass::A, plan.a_interval_weeks),
+        (AbcClass::B, plan.b_interval_weeks),
+        (AbcClass::C, plan.c_interval_weeks),
+    ] {
+        let interval = interval.max(1);
+        due.extend(
+            classifications
+                .iter()
+                .filter(|classification| classification.abc_class == class)
+                .enumerate()
+                .filter(|(index, _)| *index as i32 % interval == week % interval)
+                .map(|(_, classification)| classification.clone()),
+        );
+    }
+    due.sort_by(|a, b| a.item_id.cmp(&b.item_id));
+    due
+}
+
+/// Creates the stocktakes of all plans due on `date`. A plan that fails is logged and retried on
+/// the next run, the other plans are still scheduled.
+pub fn create_due_cycle_count_stocktakes(
+    ctx: &ServiceContext,
+    date: NaiveDate,
+) -> Result<Vec<Stocktake>, CreateCycleCountStocktakesError> {
+    let mut result = Vec::new();
+    for plan in CycleCountPlanRowRepository::new(&ctx.connection).find_active()? {
+        let plan_id = plan.id.clone();
+        match create_plan_stocktake(ctx, plan, date) {
+            Ok(Some(stocktake)) => result.push(stocktake),
+            Ok(None) => {}
+            Err(error) => error!(
+                "Failed to create cycle count stocktake for plan {}: {:?}",
+                plan_id, error
+            ),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Creates the stocktake of the plan's current week, if not already scheduled, and records the
+/// week in the plan in the same transaction
+fn create_plan_stocktake(
+    ctx: &ServiceContext,
+    plan: CycleCountPlanRow,
+    date: NaiveDate,
+) -> Result<Option<Stocktake>, CreateCycleCountStocktakesError> {
+    let week = match cycle_week(&plan, date) {
+        Some(week) => week,
+        None => return Ok(None),
+    };
+    if plan.last_scheduled_week.map_or(false, |last| last >= week) {
+        return Ok(None);
+    }
+
+    ctx.connection
+        .transaction_sync(|connection| {
+            let item_ids: Vec<String> = due_items(
+                &plan,
+                week,
+                ItemClassificationRowRepository::new(connection)
+                    .find_many_by_store_id(&plan.store_id)?,
+            )
+            .into_iter()
+            .map(|classification| classification.item_id)
+            .collect();
+
+            let stocktake = if item_ids.is_empty() {
+                None
+            } else {
+                // Uses the connection of the context, i.e. runs in this transaction
+                let stocktake = insert_stocktake(
+                    ctx,
+                    &plan.store_id,
+                    &plan.user_id,
+                    InsertStocktake {
+                        id: uuid(),
+                        description: Some(format!("{} - week {}", plan.name, week + 1)),
+                        stocktake_date: Some(date),
+                        criteria: StocktakeLineCriteria {
+                            item_ids: Some(item_ids),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                )
+                .map_err(|error| {
+                    CreateCycleCountStocktakesError::InsertStocktakeError(plan.id.clone(), error)
+                })?;
+                Some(stocktake)
+            };
+
+            CycleCountPlanRowRepository::new(connection).upsert_one(&CycleCountPlanRow {
+                last_scheduled_week: Some(week),
+                ..plan
+            })?;
+            Ok(stocktake)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Creates due cycle count stocktakes at the given interval
+pub async fn run_cycle_count_scheduler(service_provider: Arc<ServiceProvider>, interval: Duration) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        let ctx = match service_provider.context() {
+            Ok(ctx) => ctx,
+            Err(error) => {
+                error!("Cycle count scheduler failed to connect: {:?}", error);
+                continue;
+            }
+        };
+        if let Err(error) = service_provider
+            .cycle_count_service
+            .create_due_cycle_count_stocktakes(&ctx, Utc::now().naive_utc().date())
+        {
+            error!("Failed to create cycle count stocktakes: {:?}", error);
+        }
+    }
+}
+
+impl From<RepositoryError> for CreateCycleCountStocktakesError {
+    fn from(error: RepositoryError) -> Self {
+        CreateCycleCountStocktakesError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a, mock_item_b, mock_store_a, mock_user_account_a, MockDataInserts},
+        test_db::setup_all,
+        AbcClass, CycleCountPlanRow, CycleCountPlanRowRepository, EqualFilter,
+        ItemClassificationRow, ItemClassificationRowRepository, StocktakeLineFilter,
+        StocktakeLineRepository,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::{cycle_week, due_items};
+
+    fn classification(item_id: &str, abc_class: AbcClass) -> ItemClassificationRow {
+        inline_init(|r: &mut ItemClassificationRow| {
+            r.id = format!("{}_classification", item_id);
+            r.store_id = mock_store_a().id;
+            r.item_id = item_id.to_string();
+            r.abc_class = abc_class;
+        })
+    }
+
+    fn plan() -> CycleCountPlanRow {
+        inline_init(|r: &mut CycleCountPlanRow| {
+            r.id = "plan".to_string();
+            r.store_id = mock_store_a().id;
+            r.user_id = mock_user_account_a().id;
+            r.name = "Weekly".to_string();
+            r.start_date = NaiveDate::from_ymd(2022, 7, 4);
+            r.a_interval_weeks = 1;
+            r.b_interval_weeks = 2;
+            r.c_interval_weeks = 4;
+            r.is_active = true;
+        })
+    }
+
+    #[test]
+    fn cycle_count_due_items() {
+        let plan = plan();
+        assert_eq!(cycle_week(&plan, NaiveDate::from_ymd(2022, 7, 3)), None);
+        assert_eq!(cycle_week(&plan, NaiveDate::from_ymd(2022, 7, 10)), Some(0));
+        assert_eq!(cycle_week(&plan, NaiveDate::from_ymd(2022, 7, 11)), Some(1));
+
+        let classifications = vec![
+            classification("a", AbcClass::A),
+            classification("b1", AbcClass::B),
+            classification("b2", AbcClass::B),
+            classification("c1", AbcClass::C),
+            classification("c2", AbcClass::C),
+        ];
+        let due_ids = |week| -> Vec<String> {
+            due_items(&plan, week, classifications.clone())
+                .into_iter()
+                .map(|r| r.item_id)
+                .collect()
+        };
+        assert_eq!(due_ids(0), vec!["a", "b1", "c1"]);
+        assert_eq!(due_ids(1), vec!["a", "b2", "c2"]);
+        assert_eq!(due_ids(2), vec!["a", "b1"]);
+        assert_eq!(due_ids(3), vec!["a", "b2"]);
+        assert_eq!(due_ids(4), vec!["a", "b1", "c1"]);
+    }
+
+    #[actix_rt::test]
+    async fn create_due_cycle_count_stocktakes() {
+        let (_, connection, connection_manager, _) =
+            setup_all("create_due_cycle_count_stocktakes", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.cycle_count_service;
+
+        let classification_repo = ItemClassificationRowRepository::new(&connection);
+        classification_repo
+            .upsert_one(&classification(&mock_item_a().id, AbcClass::A))
+            .unwrap();
+        classification_repo
+            .upsert_one(&classification(&mock_item_b().id, AbcClass::C))
+            .unwrap();
+        CycleCountPlanRowRepository::new(&connection)
+            .upsert_one(&plan())
+            .unwrap();
+
+        // before the plan starts
+        let result = service
+            .create_due_cycle_count_stocktakes(&context, NaiveDate::from_ymd(2022, 7, 1))
+            .unwrap();
+        assert_eq!(result.len(), 0);
+
+        // week 1: only the A item is due
+        let date = NaiveDate::from_ymd(2022, 7, 12);
+        let result = service
+            .create_due_cycle_count_stocktakes(&context, date)
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].stocktake_date, Some(date));
+        assert_eq!(result[0].user_id, mock_user_account_a().id);
+        let lines = StocktakeLineRepository::new(&connection)
+            .query_by_filter(
+                StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&result[0].id)),
+            )
+            .unwrap();
+        assert!(lines.len() > 0);
+        assert!(lines
+            .iter()
+            .all(|line| line.line.item_id == mock_item_a().id));
+
+        // same week is only scheduled once
+        let result = service
+            .create_due_cycle_count_stocktakes(&context, NaiveDate::from_ymd(2022, 7, 14))
+            .unwrap();
+        assert_eq!(result.len(), 0);
+        let plan = CycleCountPlanRowRepository::new(&connection)
+            .find_one_by_id("plan")
+            .unwrap()
+            .unwrap();
+        assert_eq!(plan.last_scheduled_week, Some(1));
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use repository::{
+    CycleCountPlanRow, CycleCountPlanRowRepository, RepositoryError, StorageConnection,
+};
+
+use crate::{service_provider::ServiceContext, u32_to_i32, validate::check_store_exists};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct UpsertCycleCountPlan {
+    pub id: String,
+    pub name: String,
+    /// First day of cycle week 0
+    pub start_date: NaiveDate,
+    /// Number of weeks in which all A items are counted once
+    pub a_interval_weeks: u32,
+    /// Number of weeks in which all B items are counted once
+    pub b_interval_weeks: u32,
+    /// Number of weeks in which all C items are counted once
+    pub c_interval_weeks: u32,
+    pub is_active: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpsertCycleCountPlanError {
+    DatabaseError(RepositoryError),
+    InvalidStore,
+    PlanBelongsToAnotherStore,
+    IntervalMustBePositive,
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpsertCycleCountPlan,
+) -> Result<Option<CycleCountPlanRow>, UpsertCycleCountPlanError> {
+    if !check_store_exists(connection, store_id)? {
+        return Err(UpsertCycleCountPlanError::InvalidStore);
+    }
+    if input.a_interval_weeks == 0 || input.b_interval_weeks == 0 || input.c_interval_weeks == 0 {
+        return Err(UpsertCycleCountPlanError::IntervalMustBePositive);
+    }
+
+    let existing = CycleCountPlanRowRepository::new(connection).find_one_by_id(&input.id)?;
+    if let Some(existing) = &existing {
+        if existing.store_id != store_id {
+            return Err(UpsertCycleCountPlanError::PlanBelongsToAnotherStore);
+        }
+    }
+    Ok(existing)
+}
+
+fn generate(
+    store_id: &str,
+    user_id: &str,
+    existing: Option<CycleCountPlanRow>,
+    UpsertCycleCountPlan {
+        id,
+        name,
+        start_date,
+        a_interval_weeks,
+        b_interval_weeks,
+        c_interval_weeks,
+        is_active,
+    }: UpsertCycleCountPlan,
+) -> CycleCountPlanRow {
+    // Cycle weeks are counted from the start date, weeks scheduled before it moved don't apply
+    let last_scheduled_week = existing
+        .filter(|existing| existing.start_date == start_date)
+        .and_then(|existing| existing.last_scheduled_week);
+
+    CycleCountPlanRow {
+        id,
+        store_id: store_id.to_string(),
+        user_id: user_id.to_string(),
+        name,
+        start_date,
+        a_interval_weeks: u32_to_i32(a_interval_weeks),
+        b_interval_weeks: u32_to_i32(b_interval_weeks),
+        c_interval_weeks: u32_to_i32(c_interval_weeks),
+        is_active,
+        last_scheduled_week,
+    }
+}
+
+/// Scheduled stocktakes are created for the user that last saved the plan
+pub fn upsert_cycle_count_plan(
+    ctx: &ServiceContext,
+    store_id: &str,
+    user_id: &str,
+    input: UpsertCycleCountPlan,
+) -> Result<CycleCountPlanRow, UpsertCycleCountPlanError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let existing = validate(connection, store_id, &input)?;
+            let new_plan = generate(store_id, user_id, existing, input);
+            CycleCountPlanRowRepository::new(connection).upsert_one(&new_plan)?;
+            Ok(new_plan)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for UpsertCycleCountPlanError {
+    fn from(error: RepositoryError) -> Self {
+        UpsertCycleCountPlanError::DatabaseError(error)
+    }
+}
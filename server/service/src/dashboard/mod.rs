@@ -1,2 +1,3 @@
 pub mod invoice_count;
+pub mod stock_expiry_buckets;
 pub mod stock_expiry_count;
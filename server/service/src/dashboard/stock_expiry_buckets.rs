@@ -0,0 +1,306 @@
+use chrono::{Datelike, NaiveDate};
+use repository::{
+    EqualFilter, MasterListLineFilter, MasterListLineRepository, RepositoryError, StockLineFilter,
+    StockLineRepository,
+};
+use util::{date_with_months_offset, last_day_of_the_month};
+
+use crate::{i64_to_u32, service_provider::ServiceContext};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StockExpiryBucketType {
+    Expired,
+    LessThanOneMonth,
+    OneToThreeMonths,
+    ThreeToSixMonths,
+    SixToTwelveMonths,
+    MoreThanTwelveMonths,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StockExpiryBucket {
+    pub bucket: StockExpiryBucketType,
+    pub stock_line_count: u32,
+    /// Total number of units
+    pub total_quantity: u32,
+    /// Total cost value
+    pub total_value: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct StockExpiryBucketFilter {
+    pub location_id: Option<EqualFilter<String>>,
+    pub master_list_id: Option<EqualFilter<String>>,
+}
+
+pub trait StockExpiryBucketServiceTrait: Send + Sync {
+    /// # Arguments
+    ///
+    /// * date date from which the time until expiry is calculated
+    fn get_stock_expiry_buckets(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        date: NaiveDate,
+        filter: Option<StockExpiryBucketFilter>,
+    ) -> Result<Vec<StockExpiryBucket>, RepositoryError> {
+        get_stock_expiry_buckets(ctx, store_id, date, filter)
+    }
+}
+
+pub struct StockExpiryBucketService {}
+impl StockExpiryBucketServiceTrait for StockExpiryBucketService {}
+
+/// Same day of the month, months later (or the last day of the month if it's too short)
+fn add_months(date: &NaiveDate, months: i32) -> NaiveDate {
+    let first_day = date_with_months_offset(date, months);
+    let last_day = last_day_of_the_month(&first_day).day();
+    first_day.with_day(date.day().min(last_day)).unwrap()
+}
+
+pub fn expiry_bucket(date: &NaiveDate, expiry_date: &NaiveDate) -> StockExpiryBucketType {
+    if expiry_date <= date {
+        StockExpiryBucketType::Expired
+    } else if *expiry_date <= add_months(date, 1) {
+        StockExpiryBucketType::LessThanOneMonth
+    } else if *expiry_date <= add_months(date, 3) {
+        StockExpiryBucketType::OneToThreeMonths
+    } else if *expiry_date <= add_months(date, 6) {
+        StockExpiryBucketType::ThreeToSixMonths
+    } else if *expiry_date <= add_months(date, 12) {
+        StockExpiryBucketType::SixToTwelveMonths
+    } else {
+        StockExpiryBucketType::MoreThanTwelveMonths
+    }
+}
+
+/// Quantity and value of the store's stock grouped by time until expiry, stock without an expiry
+/// date is not included. All buckets are returned, in order of expiry.
+pub fn get_stock_expiry_buckets(
+    ctx: &ServiceContext,
+    store_id: &str,
+    date: NaiveDate,
+    filter: Option<StockExpiryBucketFilter>,
+) -> Result<Vec<StockExpiryBucket>, RepositoryError> {
+    let StockExpiryBucketFilter {
+        location_id,
+        master_list_id,
+    } = filter.unwrap_or_default();
+
+    let item_id = match master_list_id {
+        Some(master_list_id) => {
+            let item_ids = MasterListLineRepository::new(&ctx.connection)
+                .query_by_filter(MasterListLineFilter::new().master_list_id(master_list_id))?
+                .into_iter()
+                .map(|line| line.item_id)
+                .collect();
+            Some(EqualFilter::equal_any(item_ids))
+        }
+        None => None,
+    };
+    let stock_lines =
+        StockLineRepository::new(&ctx.connection).query_by_filter(StockLineFilter {
+            item_id,
+            location_id,
+            store_id: Some(EqualFilter::equal_to(store_id)),
+            ..Default::default()
+        })?;
+
+    let mut buckets: Vec<(StockExpiryBucket, i64)> = vec![
+        StockExpiryBucketType::Expired,
+        StockExpiryBucketType::LessThanOneMonth,
+        StockExpiryBucketType::OneToThreeMonths,
+        StockExpiryBucketType::ThreeToSixMonths,
+        StockExpiryBucketType::SixToTwelveMonths,
+        StockExpiryBucketType::MoreThanTwelveMonths,
+    ]
+    .into_iter()
+    .map(|bucket| {
+        (
+            StockExpiryBucket {
+                bucket,
+                stock_line_count: 0,
+                total_quantity: 0,
+                total_value: 0.0,
+            },
+            0,
+        )
+    })
+    .collect();
+    for stock_line in stock_lines {
+        let row = stock_line.stock_line_row;
+        let expiry_date = match row.expiry_date {
+            Some(expiry_date) if row.total_number_of_packs > 0 => expiry_date,
+            _ => continue,
+        };
+        let bucket_type = expiry_bucket(&date, &expiry_date);
+        let (bucket, total_quantity) = buckets
+            .iter_mut()
+            .find(|(bucket, _)| bucket.bucket == bucket_type)
+            .unwrap();
+        bucket.stock_line_count += 1;
+        bucket.total_value += row.total_number_of_packs as f64 * row.cost_price_per_pack;
+        *total_quantity += row.total_number_of_packs as i64 * row.pack_size as i64;
+    }
+
+    Ok(buckets
+        .into_iter()
+        .map(|(bucket, total_quantity)| StockExpiryBucket {
+            total_quantity: i64_to_u32(total_quantity),
+            ..bucket
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_b, mock_master_list_item_query_test1, mock_store_a, MockData,
+            MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        EqualFilter, StockLineRow,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::{expiry_bucket, StockExpiryBucketFilter, StockExpiryBucketType};
+
+    #[test]
+    fn stock_expiry_bucket() {
+        let date = NaiveDate::from_ymd(2022, 1, 31);
+        let bucket = |y, m, d| expiry_bucket(&date, &NaiveDate::from_ymd(y, m, d));
+
+        assert_eq!(bucket(2022, 1, 31), StockExpiryBucketType::Expired);
+        assert_eq!(bucket(2022, 2, 1), StockExpiryBucketType::LessThanOneMonth);
+        // month is shorter than the date's day of the month
+        assert_eq!(bucket(2022, 2, 28), StockExpiryBucketType::LessThanOneMonth);
+        assert_eq!(bucket(2022, 3, 1), StockExpiryBucketType::OneToThreeMonths);
+        assert_eq!(bucket(2022, 4, 30), StockExpiryBucketType::OneToThreeMonths);
+        assert_eq!(bucket(2022, 5, 1), StockExpiryBucketType::ThreeToSixMonths);
+        assert_eq!(bucket(2022, 7, 31), StockExpiryBucketType::ThreeToSixMonths);
+        assert_eq!(
+            bucket(2023, 1, 31),
+            StockExpiryBucketType::SixToTwelveMonths
+        );
+        assert_eq!(
+            bucket(2023, 2, 1),
+            StockExpiryBucketType::MoreThanTwelveMonths
+        );
+    }
+
+    fn stock_line(
+        id: &str,
+        item_id: String,
+        expiry_date: Option<NaiveDate>,
+        total_number_of_packs: i32,
+    ) -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = id.to_string();
+            r.item_id = item_id;
+            r.store_id = mock_store_a().id;
+            r.pack_size = 10;
+            r.cost_price_per_pack = 2.0;
+            r.expiry_date = expiry_date;
+            r.total_number_of_packs = total_number_of_packs;
+            r.available_number_of_packs = total_number_of_packs;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn get_stock_expiry_buckets() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "get_stock_expiry_buckets",
+            MockDataInserts::none()
+                .units()
+                .items()
+                .names()
+                .stores()
+                .full_master_list(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![
+                    stock_line(
+                        "expired",
+                        mock_item_a().id,
+                        Some(NaiveDate::from_ymd(2022, 1, 1)),
+                        1,
+                    ),
+                    stock_line(
+                        "expiring",
+                        mock_item_a().id,
+                        Some(NaiveDate::from_ymd(2022, 1, 15)),
+                        2,
+                    ),
+                    stock_line(
+                        "expiring_item_b",
+                        mock_item_b().id,
+                        Some(NaiveDate::from_ymd(2022, 1, 20)),
+                        3,
+                    ),
+                    stock_line(
+                        "empty",
+                        mock_item_a().id,
+                        Some(NaiveDate::from_ymd(2022, 1, 15)),
+                        0,
+                    ),
+                    stock_line("no_expiry", mock_item_a().id, None, 4),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stock_expiry_bucket_service;
+
+        let buckets = service
+            .get_stock_expiry_buckets(
+                &context,
+                &mock_store_a().id,
+                NaiveDate::from_ymd(2022, 1, 10),
+                None,
+            )
+            .unwrap();
+        let summary: Vec<(StockExpiryBucketType, u32, u32, f64)> = buckets
+            .into_iter()
+            .map(|b| {
+                (
+                    b.bucket,
+                    b.stock_line_count,
+                    b.total_quantity,
+                    b.total_value,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (StockExpiryBucketType::Expired, 1, 10, 2.0),
+                (StockExpiryBucketType::LessThanOneMonth, 2, 50, 10.0),
+                (StockExpiryBucketType::OneToThreeMonths, 0, 0, 0.0),
+                (StockExpiryBucketType::ThreeToSixMonths, 0, 0, 0.0),
+                (StockExpiryBucketType::SixToTwelveMonths, 0, 0, 0.0),
+                (StockExpiryBucketType::MoreThanTwelveMonths, 0, 0, 0.0),
+            ]
+        );
+
+        // master list without any of the items
+        let buckets = service
+            .get_stock_expiry_buckets(
+                &context,
+                &mock_store_a().id,
+                NaiveDate::from_ymd(2022, 1, 10),
+                Some(StockExpiryBucketFilter {
+                    master_list_id: Some(EqualFilter::equal_to(
+                        &mock_master_list_item_query_test1().master_list.id,
+                    )),
+                    ..Default::default()
+                }),
+            )
+            .unwrap();
+        assert_eq!(buckets.iter().map(|b| b.stock_line_count).sum::<u32>(), 0);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use crate::{
+    i64_to_u32, service_provider::ServiceContext,
+    stock_line_reservation::consume::get_held_stock_lines,
+};
+use repository::{
+    DateFilter, DonorConsumptionFilter, DonorConsumptionRepository, DonorStockOnHandFilter,
+    DonorStockOnHandRepository, EqualFilter, RepositoryError,
+};
+use util::date_now;
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DonorStatsFilter {
+    pub item_id: Option<EqualFilter<String>>,
+    pub donor_id: Option<EqualFilter<String>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DonorStockOnHand {
+    pub item_id: String,
+    pub donor_id: String,
+    pub available_stock_on_hand: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DonorConsumption {
+    pub item_id: String,
+    pub donor_id: String,
+    pub total_consumption: u32,
+}
+
+pub trait DonorStatsServiceTrait: Sync + Send {
+    fn get_donor_stock_on_hand(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        filter: Option<DonorStatsFilter>,
+    ) -> Result<Vec<DonorStockOnHand>, RepositoryError> {
+        get_donor_stock_on_hand(ctx, store_id, filter)
+    }
+
+    fn get_donor_consumption(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        filter: Option<DonorStatsFilter>,
+        date: Option<DateFilter>,
+    ) -> Result<Vec<DonorConsumption>, RepositoryError> {
+        get_donor_consumption(ctx, store_id, filter, date)
+    }
+}
+
+pub struct DonorStatsService {}
+impl DonorStatsServiceTrait for DonorStatsService {}
+
+pub fn get_donor_stock_on_hand(
+    ctx: &ServiceContext,
+    store_id: &str,
+    filter: Option<DonorStatsFilter>,
+) -> Result<Vec<DonorStockOnHand>, RepositoryError> {
+    let DonorStatsFilter { item_id, donor_id } = filter.unwrap_or_default();
+
+    let filter = DonorStockOnHandFilter {
+        item_id,
+        store_id: Some(EqualFilter::equal_to(store_id)),
+        donor_id,
+    };
+
+    let rows = DonorStockOnHandRepository::new(&ctx.connection).query(Some(filter))?;
+
+    // Stock held by reservations isn't available to everyone
+    let mut held_units: HashMap<(String, String), i64> = HashMap::new();
+    for (stock_line, held_packs) in get_held_stock_lines(&ctx.connection, store_id, date_now())? {
+        if let Some(donor_id) = stock_line.donor_id {
+            *held_units
+                .entry((stock_line.item_id, donor_id))
+                .or_default() += held_packs as i64 * stock_line.pack_size as i64;
+        }
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let held = held_units
+                .get(&(row.item_id.clone(), row.donor_id.clone()))
+                .copied()
+                .unwrap_or_default();
+            DonorStockOnHand {
+                available_stock_on_hand: i64_to_u32(row.available_stock_on_hand - held),
+                item_id: row.item_id,
+                donor_id: row.donor_id,
+            }
+        })
+        .collect())
+}
+
+/// Total quantity issued per item and donor, within the optional date range
+pub fn get_donor_consumption(
+    ctx: &ServiceContext,
+    store_id: &str,
+    filter: Option<DonorStatsFilter>,
+    date: Option<DateFilter>,
+) -> Result<Vec<DonorConsumption>, RepositoryError> {
+    let DonorStatsFilter { item_id, donor_id } = filter.unwrap_or_default();
+
+    let filter = DonorConsumptionFilter {
+        item_id,
+        store_id: Some(EqualFilter::equal_to(store_id)),
+        donor_id,
+        date,
+    };
+
+    let rows = DonorConsumptionRepository::new(&ctx.connection).query(Some(filter))?;
+
+    let mut consumption_map: HashMap<(String, String), i64> = HashMap::new();
+    for row in rows.into_iter() {
+        let total = consumption_map
+            .entry((row.item_id, row.donor_id))
+            .or_insert(0);
+        *total += row.quantity as i64;
+    }
+
+    let mut result: Vec<DonorConsumption> = consumption_map
+        .into_iter()
+        .map(|((item_id, donor_id), total)| DonorConsumption {
+            item_id,
+            donor_id,
+            total_consumption: i64_to_u32(total),
+        })
+        .collect();
+    result.sort_by(|a, b| (&a.item_id, &a.donor_id).cmp(&(&b.item_id, &b.donor_id)));
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_item_a, mock_name_a, mock_stock_line_a, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        EqualFilter, StockLineRowRepository,
+    };
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::DonorStatsFilter;
+
+    #[actix_rt::test]
+    async fn donor_stock_on_hand() {
+        let (_, connection, connection_manager, _) =
+            setup_all("donor_stock_on_hand", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.donor_stats_service;
+
+        let filter = DonorStatsFilter {
+            item_id: Some(EqualFilter::equal_to(&mock_item_a().id)),
+            donor_id: None,
+        };
+
+        // No donor stock
+        let result = service
+            .get_donor_stock_on_hand(&context, &mock_store_a().id, Some(filter.clone()))
+            .unwrap();
+        assert_eq!(result, vec![]);
+
+        // Assign donor to stock line of item a
+        let repo = StockLineRowRepository::new(&connection);
+        let mut stock_line = mock_stock_line_a();
+        stock_line.donor_id = Some(mock_name_a().id);
+        repo.upsert_one(&stock_line).unwrap();
+
+        let result = service
+            .get_donor_stock_on_hand(&context, &mock_store_a().id, Some(filter))
+            .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].donor_id, mock_name_a().id);
+        assert_eq!(
+            result[0].available_stock_on_hand,
+            (stock_line.available_number_of_packs * stock_line.pack_size) as u32
+        );
+    }
+}
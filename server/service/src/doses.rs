@@ -0,0 +1,81 @@
+use repository::{
+    ItemRow, ItemRowRepository, RepositoryError, StockLineRowRepository, StorageConnection,
+};
+
+/// Only vaccines are counted in doses, other items are treated as one dose per unit
+pub fn doses_per_unit(item: &ItemRow) -> u32 {
+    if item.is_vaccine && item.doses > 0 {
+        item.doses as u32
+    } else {
+        1
+    }
+}
+
+pub fn units_to_doses(units: i32, item: &ItemRow) -> i32 {
+    units * doses_per_unit(item) as i32
+}
+
+/// Rounds up, a part of a unit can't be requested or issued. None if `doses` is too large to be
+/// rounded up
+pub fn doses_to_units(doses: u32, item: &ItemRow) -> Option<u32> {
+    let doses_per_unit = doses_per_unit(item);
+    Some(doses.checked_add(doses_per_unit - 1)? / doses_per_unit)
+}
+
+/// Rounds up to whole packs, None if `doses` is too large to be rounded up
+pub fn doses_to_number_of_packs(doses: u32, pack_size: i32, item: &ItemRow) -> Option<u32> {
+    let units = doses_to_units(doses, item)?;
+    let pack_size = pack_size.max(1) as u32;
+    Some(units.checked_add(pack_size - 1)? / pack_size)
+}
+
+/// Packs of a stock line needed to cover `doses`, None if `doses` is too large to be rounded up.
+/// Returns RepositoryError::NotFound if the stock line or its item can't be found.
+pub fn stock_line_doses_to_number_of_packs(
+    connection: &StorageConnection,
+    stock_line_id: &str,
+    doses: u32,
+) -> Result<Option<u32>, RepositoryError> {
+    let stock_line = StockLineRowRepository::new(connection).find_one_by_id(stock_line_id)?;
+    let item = ItemRowRepository::new(connection)
+        .find_one_by_id(&stock_line.item_id)?
+        .ok_or(RepositoryError::NotFound)?;
+
+    Ok(doses_to_number_of_packs(doses, stock_line.pack_size, &item))
+}
+
+#[cfg(test)]
+mod test {
+    use repository::ItemRow;
+    use util::inline_init;
+
+    use super::{doses_to_number_of_packs, doses_to_units, units_to_doses};
+
+    #[test]
+    fn test_dose_conversions() {
+        let vaccine = inline_init(|r: &mut ItemRow| {
+            r.doses = 10;
+            r.is_vaccine = true;
+        });
+        let not_dose_counted = ItemRow::default();
+        let not_a_vaccine = inline_init(|r: &mut ItemRow| r.doses = 10);
+
+        assert_eq!(units_to_doses(3, &vaccine), 30);
+        assert_eq!(units_to_doses(3, &not_dose_counted), 3);
+        assert_eq!(units_to_doses(3, &not_a_vaccine), 3);
+
+        assert_eq!(doses_to_units(20, &vaccine), Some(2));
+        assert_eq!(doses_to_units(21, &vaccine), Some(3));
+        assert_eq!(doses_to_units(21, &not_dose_counted), Some(21));
+        assert_eq!(doses_to_units(u32::MAX, &vaccine), None);
+        assert_eq!(doses_to_units(u32::MAX, &not_dose_counted), Some(u32::MAX));
+
+        // 5 vials per pack
+        assert_eq!(doses_to_number_of_packs(50, 5, &vaccine), Some(1));
+        assert_eq!(doses_to_number_of_packs(51, 5, &vaccine), Some(2));
+        assert_eq!(
+            doses_to_number_of_packs(u32::MAX, 5, &not_dose_counted),
+            None
+        );
+    }
+}
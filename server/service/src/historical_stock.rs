@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+
+use crate::{i64_to_u32, service_provider::ServiceContext};
+use repository::{
+    DatetimeFilter, EqualFilter, RepositoryError, StockLineFilter, StockLineMovementFilter,
+    StockLineMovementRepository, StockLineRepository,
+};
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct HistoricalStockOnHandFilter {
+    pub item_id: Option<EqualFilter<String>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HistoricalStockOnHand {
+    pub item_id: String,
+    /// Only set when stock on hand is grouped by batch
+    pub batch: Option<String>,
+    pub total_stock_on_hand: u32,
+}
+
+pub trait HistoricalStockServiceTrait: Sync + Send {
+    fn get_historical_stock_on_hand(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        datetime: NaiveDateTime,
+        filter: Option<HistoricalStockOnHandFilter>,
+        group_by_batch: bool,
+    ) -> Result<Vec<HistoricalStockOnHand>, RepositoryError> {
+        get_historical_stock_on_hand(ctx, store_id, datetime, filter, group_by_batch)
+    }
+}
+
+pub struct HistoricalStockService {}
+impl HistoricalStockServiceTrait for HistoricalStockService {}
+
+/// Total stock on hand (in units) per item, and optionally per batch, as it was at `datetime`.
+///
+/// Worked out backwards from the current stock on hand by reverting the stock movements (picked
+/// outbound shipments, delivered inbound shipments and verified inventory adjustments) that
+/// happened after `datetime`. Items without stock at that time are not returned.
+pub fn get_historical_stock_on_hand(
+    ctx: &ServiceContext,
+    store_id: &str,
+    datetime: NaiveDateTime,
+    filter: Option<HistoricalStockOnHandFilter>,
+    group_by_batch: bool,
+) -> Result<Vec<HistoricalStockOnHand>, RepositoryError> {
+    let HistoricalStockOnHandFilter { item_id } = filter.unwrap_or_default();
+
+    let stock_lines =
+        StockLineRepository::new(&ctx.connection).query_by_filter(StockLineFilter {
+            item_id: item_id.clone(),
+            store_id: Some(EqualFilter::equal_to(store_id)),
+            ..Default::default()
+        })?;
+    let movements =
+        StockLineMovementRepository::new(&ctx.connection).query(Some(StockLineMovementFilter {
+            item_id,
+            store_id: Some(EqualFilter::equal_to(store_id)),
+            datetime: Some(DatetimeFilter::after_or_equal_to(datetime)),
+        }))?;
+
+    let batch = |batch: Option<String>| if group_by_batch { batch } else { None };
+    let mut stock_map: HashMap<(String, Option<String>), i64> = HashMap::new();
+    for stock_line in stock_lines.into_iter() {
+        let row = stock_line.stock_line_row;
+        let total = stock_map
+            .entry((row.item_id, batch(row.batch)))
+            .or_insert(0);
+        *total += row.total_number_of_packs as i64 * row.pack_size as i64;
+    }
+    for movement in movements.into_iter() {
+        // movements at exactly `datetime` are already part of the stock on hand at that time
+        if movement.datetime <= datetime {
+            continue;
+        }
+        let total = stock_map
+            .entry((movement.item_id, batch(movement.batch)))
+            .or_insert(0);
+        *total -= movement.quantity as i64;
+    }
+
+    let mut result: Vec<HistoricalStockOnHand> = stock_map
+        .into_iter()
+        .filter(|(_, total)| *total != 0)
+        .map(|((item_id, batch), total)| HistoricalStockOnHand {
+            item_id,
+            batch,
+            total_stock_on_hand: i64_to_u32(total),
+        })
+        .collect();
+    result.sort_by(|a, b| (&a.item_id, &a.batch).cmp(&(&b.item_id, &b.batch)));
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a, mock_name_a, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        InvoiceLineRow, InvoiceLineRowType, InvoiceRow, InvoiceRowType, StockLineRow,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::HistoricalStockOnHand;
+
+    fn stock_line(id: &str, batch: &str, total_number_of_packs: i32) -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = id.to_string();
+            r.item_id = mock_item_a().id;
+            r.store_id = mock_store_a().id;
+            r.batch = Some(batch.to_string());
+            r.pack_size = 1;
+            r.total_number_of_packs = total_number_of_packs;
+            r.available_number_of_packs = total_number_of_packs;
+        })
+    }
+
+    fn invoice(id: &str, r#type: InvoiceRowType, datetime: NaiveDate) -> InvoiceRow {
+        inline_init(|r: &mut InvoiceRow| {
+            r.id = id.to_string();
+            r.name_id = mock_name_a().id;
+            r.store_id = mock_store_a().id;
+            match r#type {
+                InvoiceRowType::InboundShipment => {
+                    r.delivered_datetime = Some(datetime.and_hms(0, 0, 0))
+                }
+                InvoiceRowType::OutboundShipment => {
+                    r.picked_datetime = Some(datetime.and_hms(0, 0, 0))
+                }
+                _ => r.verified_datetime = Some(datetime.and_hms(0, 0, 0)),
+            }
+            r.r#type = r#type;
+        })
+    }
+
+    fn invoice_line(
+        invoice_id: &str,
+        r#type: InvoiceLineRowType,
+        number_of_packs: i32,
+    ) -> InvoiceLineRow {
+        inline_init(|r: &mut InvoiceLineRow| {
+            r.id = format!("{}_line", invoice_id);
+            r.invoice_id = invoice_id.to_string();
+            r.item_id = mock_item_a().id;
+            r.stock_line_id = Some("stock_line_1".to_string());
+            r.batch = Some("batch_1".to_string());
+            r.pack_size = 1;
+            r.number_of_packs = number_of_packs;
+            r.r#type = r#type;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn historical_stock_on_hand() {
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "historical_stock_on_hand",
+            MockDataInserts::none().units().items().names().stores(),
+            inline_init(|r: &mut MockData| {
+                // 10 packs received, 4 issued and 1 added by a stocktake
+                r.stock_lines = vec![
+                    stock_line("stock_line_1", "batch_1", 7),
+                    stock_line("stock_line_2", "batch_2", 5),
+                ];
+                r.invoices = vec![
+                    invoice(
+                        "inbound",
+                        InvoiceRowType::InboundShipment,
+                        NaiveDate::from_ymd(2022, 1, 10),
+                    ),
+                    invoice(
+                        "outbound",
+                        InvoiceRowType::OutboundShipment,
+                        NaiveDate::from_ymd(2022, 2, 10),
+                    ),
+                    invoice(
+                        "adjustment",
+                        InvoiceRowType::InventoryAdjustment,
+                        NaiveDate::from_ymd(2022, 3, 10),
+                    ),
+                ];
+                r.invoice_lines = vec![
+                    invoice_line("inbound", InvoiceLineRowType::StockIn, 10),
+                    invoice_line("outbound", InvoiceLineRowType::StockOut, 4),
+                    invoice_line("adjustment", InvoiceLineRowType::StockIn, 1),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.historical_stock_service;
+        let store_id = mock_store_a().id;
+        let item_id = mock_item_a().id;
+
+        let stock_on_hand = |date: NaiveDate, group_by_batch: bool| {
+            service
+                .get_historical_stock_on_hand(
+                    &context,
+                    &store_id,
+                    date.and_hms(0, 0, 0),
+                    None,
+                    group_by_batch,
+                )
+                .unwrap()
+        };
+        let row = |batch: Option<&str>, total_stock_on_hand: u32| HistoricalStockOnHand {
+            item_id: item_id.clone(),
+            batch: batch.map(str::to_string),
+            total_stock_on_hand,
+        };
+
+        // before the first movement only the batch without movements is in stock
+        assert_eq!(
+            stock_on_hand(NaiveDate::from_ymd(2022, 1, 1), true),
+            vec![row(Some("batch_2"), 5)]
+        );
+        // movements at the queried datetime are included
+        assert_eq!(
+            stock_on_hand(NaiveDate::from_ymd(2022, 1, 10), true),
+            vec![row(Some("batch_1"), 10), row(Some("batch_2"), 5)]
+        );
+        assert_eq!(
+            stock_on_hand(NaiveDate::from_ymd(2022, 2, 28), false),
+            vec![row(None, 11)]
+        );
+        // current stock on hand
+        assert_eq!(
+            stock_on_hand(NaiveDate::from_ymd(2022, 4, 1), false),
+            vec![row(None, 12)]
+        );
+    }
+}
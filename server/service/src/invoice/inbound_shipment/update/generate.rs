@@ -170,6 +170,9 @@ pub fn generate_lines_and_stock_lines(
             r#type: _,
             number_of_packs,
             note,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         }: InvoiceLineRow = invoice_lines;
         if number_of_packs > 0 {
             let stock_line = StockLineRow {
@@ -186,6 +189,9 @@ pub fn generate_lines_and_stock_lines(
                 expiry_date,
                 on_hold: false,
                 note,
+                donor_id,
+                manufacturer_id,
+                vvm_status_id,
             };
             result.push(LineAndStockLine { line, stock_line });
         }
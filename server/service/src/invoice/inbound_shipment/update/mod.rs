@@ -1,6 +1,9 @@
 use crate::{
     invoice::query::get_invoice,
     log::log_entry,
+    serial_number::move_with_invoice::{
+        check_serial_numbers_moved, move_invoice_serial_numbers, MoveSerialNumbersError,
+    },
     service_provider::ServiceContext,
     sync_processor::{process_records, Record},
     WithDBError,
@@ -51,6 +54,7 @@ pub fn update_inbound_shipment(
         .connection
         .transaction_sync(|connection| {
             let (invoice, other_party) = validate(connection, store_id, &patch)?;
+            let serial_numbers_moved = check_serial_numbers_moved(&invoice);
             let GenerateResult {
                 batches_to_update,
                 update_invoice,
@@ -58,6 +62,10 @@ pub fn update_inbound_shipment(
             } = generate(connection, user_id, invoice, other_party, patch.clone())?;
 
             InvoiceRowRepository::new(connection).upsert_one(&update_invoice)?;
+            // Units with recorded serial numbers are received when delivered
+            if !serial_numbers_moved && check_serial_numbers_moved(&update_invoice) {
+                move_invoice_serial_numbers(connection, &update_invoice)?;
+            }
 
             if let Some(lines_and_invoice_lines) = batches_to_update {
                 let stock_line_repository = StockLineRowRepository::new(connection);
@@ -123,6 +131,8 @@ pub enum UpdateInboundShipmentError {
     OtherPartyDoesNotExist,
     OtherPartyNotVisible,
     OtherPartyNotASupplier,
+    /// Serial number recorded on a line is decommissioned
+    SerialNumberNotAvailable(String),
     // Internal
     DatabaseError(RepositoryError),
     UpdatedInvoiceDoesNotExist,
@@ -134,6 +144,19 @@ impl From<RepositoryError> for UpdateInboundShipmentError {
     }
 }
 
+impl From<MoveSerialNumbersError> for UpdateInboundShipmentError {
+    fn from(error: MoveSerialNumbersError) -> Self {
+        match error {
+            MoveSerialNumbersError::SerialNumberNotAvailable(serial_number) => {
+                UpdateInboundShipmentError::SerialNumberNotAvailable(serial_number)
+            }
+            MoveSerialNumbersError::DatabaseError(error) => {
+                UpdateInboundShipmentError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 impl<ERR> From<WithDBError<ERR>> for UpdateInboundShipmentError
 where
     ERR: Into<UpdateInboundShipmentError>,
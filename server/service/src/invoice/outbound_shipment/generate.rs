@@ -33,6 +33,9 @@ pub fn generate_unallocated_invoice_lines(
                     r#type: InvoiceLineRowType::UnallocatedStock,
                     number_of_packs: 0,
                     note: None,
+                    donor_id: None,
+                    manufacturer_id: None,
+                    vvm_status_id: None,
                 });
             }
             Ok(None) => {}
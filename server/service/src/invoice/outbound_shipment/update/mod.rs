@@ -14,6 +14,9 @@ use validate::validate;
 use crate::invoice::outbound_shipment::update::generate::GenerateResult;
 use crate::invoice::query::get_invoice;
 use crate::log::log_entry;
+use crate::serial_number::move_with_invoice::{
+    check_serial_numbers_moved, move_invoice_serial_numbers, MoveSerialNumbersError,
+};
 use crate::service_provider::ServiceContext;
 use crate::sync_processor::{process_records, Record};
 #[derive(Clone, Debug, PartialEq)]
@@ -53,6 +56,8 @@ pub enum UpdateOutboundShipmentError {
     DatabaseError(RepositoryError),
     /// Holds the id of the invalid invoice line
     InvoiceLineHasNoStockLine(String),
+    /// Serial number recorded on a line is no longer in store
+    SerialNumberNotAvailable(String),
 }
 
 type OutError = UpdateOutboundShipmentError;
@@ -66,6 +71,7 @@ pub fn update_outbound_shipment(
         .connection
         .transaction_sync(|connection| {
             let (invoice, other_party_option) = validate(connection, store_id, &patch)?;
+            let serial_numbers_moved = check_serial_numbers_moved(&invoice);
             let GenerateResult {
                 batches_to_update,
                 update_invoice,
@@ -73,6 +79,10 @@ pub fn update_outbound_shipment(
             } = generate(invoice, other_party_option, patch.clone(), connection)?;
 
             InvoiceRowRepository::new(connection).upsert_one(&update_invoice)?;
+            // Units with recorded serial numbers are issued when picked
+            if !serial_numbers_moved && check_serial_numbers_moved(&update_invoice) {
+                move_invoice_serial_numbers(connection, &update_invoice)?;
+            }
             if let Some(stock_lines) = batches_to_update {
                 let repository = StockLineRowRepository::new(connection);
                 for stock_line in stock_lines {
@@ -129,6 +139,19 @@ impl From<RepositoryError> for UpdateOutboundShipmentError {
     }
 }
 
+impl From<MoveSerialNumbersError> for UpdateOutboundShipmentError {
+    fn from(error: MoveSerialNumbersError) -> Self {
+        match error {
+            MoveSerialNumbersError::SerialNumberNotAvailable(serial_number) => {
+                UpdateOutboundShipmentError::SerialNumberNotAvailable(serial_number)
+            }
+            MoveSerialNumbersError::DatabaseError(error) => {
+                UpdateOutboundShipmentError::DatabaseError(error)
+            }
+        }
+    }
+}
+
 impl From<TransactionError<UpdateOutboundShipmentError>> for UpdateOutboundShipmentError {
     fn from(error: TransactionError<UpdateOutboundShipmentError>) -> Self {
         match error {
@@ -1,8 +1,11 @@
 use crate::{
-    invoice::common::generate_invoice_user_id_update, service_provider::ServiceContext, WithDBError,
+    invoice::common::generate_invoice_user_id_update,
+    serial_number::remove_from_invoice_line::remove_invoice_line_serial_numbers,
+    service_provider::ServiceContext, WithDBError,
 };
 use repository::{
-    InvoiceLineRowRepository, InvoiceRowRepository, RepositoryError, StockLineRowRepository,
+    InvoiceLineRowRepository, InvoiceRowRepository, OpenVialWastageRowRepository, RepositoryError,
+    StockLineReservationRowRepository, StockLineRowRepository, VvmStatusLogRowRepository,
 };
 
 mod validate;
@@ -29,9 +32,18 @@ pub fn delete_inbound_shipment_line(
 
             let delete_batch_id_option = line.stock_line_id.clone();
 
+            remove_invoice_line_serial_numbers(&connection, &line.id)?;
+            let vvm_status_log_repo = VvmStatusLogRowRepository::new(&connection);
+            vvm_status_log_repo.unlink_invoice_line(&line.id)?;
+            let open_vial_wastage_repo = OpenVialWastageRowRepository::new(&connection);
+            open_vial_wastage_repo.unlink_invoice_line(&line.id)?;
             InvoiceLineRowRepository::new(&connection).delete(&line.id)?;
 
             if let Some(id) = delete_batch_id_option {
+                vvm_status_log_repo.delete_by_stock_line_id(&id)?;
+                open_vial_wastage_repo.delete_by_stock_line_id(&id)?;
+                // Only released, used up or expired reservations are left (checked in validate)
+                StockLineReservationRowRepository::new(&connection).delete_by_stock_line_id(&id)?;
                 StockLineRowRepository::new(&connection).delete(&id)?;
             }
 
@@ -80,18 +92,26 @@ mod test {
     use repository::{
         mock::{
             mock_inbound_shipment_a_invoice_lines, mock_inbound_shipment_b_invoice_lines,
-            mock_inbound_shipment_c_invoice_lines, mock_store_a, mock_store_b, mock_user_account_a,
-            MockData, MockDataInserts,
+            mock_inbound_shipment_c_invoice_lines, mock_inbound_shipment_d_invoice_lines,
+            mock_item_a, mock_store_a, mock_store_b, mock_user_account_a, MockData,
+            MockDataInserts,
         },
         test_db::{setup_all, setup_all_with_data},
         InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType,
+        InvoiceLineSerialNumberRowRepository, ItemRowRepository, OpenVialWastageRow,
+        OpenVialWastageRowRepository, SerialNumberMovementRow, SerialNumberMovementRowRepository,
+        SerialNumberRow, SerialNumberRowRepository, SerialNumberStatus, StockLineRowRepository,
+        VvmStatusLogRowRepository,
     };
-    use util::inline_init;
+    use util::{inline_edit, inline_init};
 
     use crate::{
+        invoice::inbound_shipment::{UpdateInboundShipment, UpdateInboundShipmentStatus},
         invoice_line::inbound_shipment_line::delete::DeleteInboundShipmentLine,
         invoice_line::inbound_shipment_line::DeleteInboundShipmentLineError as ServiceError,
+        serial_number::add_to_invoice_line::AddInvoiceLineSerialNumbers,
         service_provider::ServiceProvider,
+        vvm_status::update::UpdateStockLineVvmStatus,
     };
 
     #[actix_rt::test]
@@ -227,4 +247,186 @@ mod test {
             None
         );
     }
+
+    #[actix_rt::test]
+    async fn delete_inbound_shipment_line_serial_numbers() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "delete_inbound_shipment_line_serial_numbers",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let line = mock_inbound_shipment_c_invoice_lines()[0].clone();
+        ItemRowRepository::new(&connection)
+            .upsert_one(&inline_edit(&mock_item_a(), |mut u| {
+                u.is_serial_tracked = true;
+                u
+            }))
+            .unwrap();
+
+        // SN2 was issued to store_a's name by store_b before it was received
+        let returned = inline_init(|r: &mut SerialNumberRow| {
+            r.id = "returned_serial_number".to_string();
+            r.item_id = line.item_id.clone();
+            r.store_id = "store_b".to_string();
+            r.serial_number = "SN2".to_string();
+            r.status = SerialNumberStatus::Issued;
+            r.holder_name_id = Some("name_store_a".to_string());
+        });
+        SerialNumberRowRepository::new(&connection)
+            .upsert_one(&returned)
+            .unwrap();
+        SerialNumberMovementRowRepository::new(&connection)
+            .insert_one(&SerialNumberMovementRow {
+                id: "returned_serial_number_issue".to_string(),
+                serial_number_id: returned.id.clone(),
+                store_id: "store_b".to_string(),
+                invoice_line_id: None,
+                status: SerialNumberStatus::Issued,
+                holder_name_id: Some("name_store_a".to_string()),
+                datetime: NaiveDate::from_ymd(2022, 1, 1).and_hms(0, 0, 0),
+            })
+            .unwrap();
+
+        // Units are received when the shipment is delivered
+        service_provider
+            .serial_number_service
+            .add_invoice_line_serial_numbers(
+                &context,
+                &mock_store_a().id,
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: line.id.clone(),
+                    serial_numbers: vec!["SN1".to_string(), "SN2".to_string()],
+                },
+            )
+            .unwrap();
+        service_provider
+            .invoice_service
+            .update_inbound_shipment(
+                &context,
+                &mock_store_a().id,
+                &mock_user_account_a().id,
+                UpdateInboundShipment {
+                    id: line.invoice_id.clone(),
+                    status: Some(UpdateInboundShipmentStatus::Delivered),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let created = SerialNumberRowRepository::new(&connection)
+            .find_one_by_serial_number(&line.item_id, "SN1")
+            .unwrap()
+            .unwrap();
+
+        service_provider
+            .invoice_line_service
+            .delete_inbound_shipment_line(
+                &context,
+                &mock_store_a().id,
+                &mock_user_account_a().id,
+                DeleteInboundShipmentLine {
+                    id: line.id.clone(),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            SerialNumberMovementRowRepository::new(&connection)
+                .find_many_by_invoice_line_id(&line.id)
+                .unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            InvoiceLineSerialNumberRowRepository::new(&connection)
+                .find_many_by_invoice_line_ids(&[line.id.clone()])
+                .unwrap(),
+            vec![]
+        );
+        // Serial number created by the line is removed
+        assert_eq!(
+            SerialNumberRowRepository::new(&connection)
+                .find_one_by_id(&created.id)
+                .unwrap(),
+            None
+        );
+        // Received serial number is back with its previous store and holder
+        assert_eq!(
+            SerialNumberRowRepository::new(&connection)
+                .find_one_by_id(&returned.id)
+                .unwrap(),
+            Some(returned)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn delete_inbound_shipment_line_vvm_status_and_wastage() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "delete_inbound_shipment_line_vvm_status_and_wastage",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let line = mock_inbound_shipment_d_invoice_lines()[0].clone();
+        let stock_line_id = line.stock_line_id.clone().unwrap();
+
+        service_provider
+            .vvm_status_service
+            .update_stock_line_vvm_status(
+                &context,
+                &mock_store_a().id,
+                UpdateStockLineVvmStatus {
+                    stock_line_id: stock_line_id.clone(),
+                    status_id: "VVM_STAGE_1".to_string(),
+                    invoice_line_id: Some(line.id.clone()),
+                    comment: None,
+                },
+            )
+            .unwrap();
+        OpenVialWastageRowRepository::new(&connection)
+            .upsert_one(&OpenVialWastageRow {
+                id: "inbound_line_wastage".to_string(),
+                store_id: mock_store_a().id,
+                item_id: line.item_id.clone(),
+                stock_line_id: stock_line_id.clone(),
+                invoice_line_id: Some(line.id.clone()),
+                doses_used: 8,
+                doses_wasted: 2,
+                opened_datetime: NaiveDate::from_ymd(2022, 1, 1).and_hms(0, 0, 0),
+                comment: None,
+            })
+            .unwrap();
+
+        service_provider
+            .invoice_line_service
+            .delete_inbound_shipment_line(
+                &context,
+                &mock_store_a().id,
+                &mock_user_account_a().id,
+                DeleteInboundShipmentLine {
+                    id: line.id.clone(),
+                },
+            )
+            .unwrap();
+
+        // VVM status history and wastage are deleted together with the stock line
+        assert!(StockLineRowRepository::new(&connection)
+            .find_one_by_id(&stock_line_id)
+            .is_err());
+        assert_eq!(
+            VvmStatusLogRowRepository::new(&connection)
+                .find_many_by_stock_line_id(&stock_line_id)
+                .unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            OpenVialWastageRowRepository::new(&connection)
+                .find_many_by_stock_line_id(&stock_line_id)
+                .unwrap(),
+            vec![]
+        );
+    }
 }
@@ -14,6 +14,9 @@ pub fn generate_batch(
         number_of_packs,
         location_id,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
         ..
     }: InvoiceLineRow,
     keep_existing_batch: bool,
@@ -38,5 +41,8 @@ pub fn generate_batch(
         expiry_date,
         on_hold: false,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
     }
 }
@@ -45,6 +45,9 @@ fn generate_line(
         total_before_tax,
         total_after_tax,
         tax,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
     }: InsertInboundShipmentLine,
     ItemRow {
         name: item_name,
@@ -71,5 +74,8 @@ fn generate_line(
         total_after_tax,
         tax,
         note: None,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
     }
 }
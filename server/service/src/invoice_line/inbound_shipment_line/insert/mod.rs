@@ -26,6 +26,9 @@ pub struct InsertInboundShipmentLine {
     pub total_before_tax: f64,
     pub total_after_tax: f64,
     pub tax: Option<f64>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 type OutError = InsertInboundShipmentLineError;
@@ -69,6 +72,9 @@ pub enum InsertInboundShipmentLineError {
     NotThisStoreInvoice,
     CannotEditFinalised,
     LocationDoesNotExist,
+    DonorDoesNotExist,
+    ManufacturerDoesNotExist,
+    VvmStatusDoesNotExist,
     ItemNotFound,
     PackSizeBelowOne,
     NumberOfPacksBelowOne,
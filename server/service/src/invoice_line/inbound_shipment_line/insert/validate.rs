@@ -6,7 +6,10 @@ use crate::{
     },
     invoice_line::{
         check_location_exists,
-        inbound_shipment_line::check_pack_size,
+        inbound_shipment_line::{
+            check_donor, check_manufacturer, check_pack_size, check_vvm_status, DonorDoesNotExist,
+            ManufacturerDoesNotExist, VvmStatusDoesNotExist,
+        },
         validate::{
             check_item, check_line_does_not_exists, check_number_of_packs, ItemNotFound,
             LineAlreadyExists, NumberOfPacksBelowOne,
@@ -29,6 +32,9 @@ pub fn validate(
     let item = check_item(&input.item_id, connection)?;
 
     check_location_exists(&input.location_id, connection)?;
+    check_donor(&input.donor_id, connection)?;
+    check_manufacturer(&input.manufacturer_id, connection)?;
+    check_vvm_status(&input.vvm_status_id, connection)?;
 
     let invoice = check_invoice_exists(&input.invoice_id, connection)?;
     check_store(&invoice, store_id)?;
@@ -53,6 +59,24 @@ impl From<LocationDoesNotExist> for InsertInboundShipmentLineError {
     }
 }
 
+impl From<DonorDoesNotExist> for InsertInboundShipmentLineError {
+    fn from(_: DonorDoesNotExist) -> Self {
+        InsertInboundShipmentLineError::DonorDoesNotExist
+    }
+}
+
+impl From<ManufacturerDoesNotExist> for InsertInboundShipmentLineError {
+    fn from(_: ManufacturerDoesNotExist) -> Self {
+        InsertInboundShipmentLineError::ManufacturerDoesNotExist
+    }
+}
+
+impl From<VvmStatusDoesNotExist> for InsertInboundShipmentLineError {
+    fn from(_: VvmStatusDoesNotExist) -> Self {
+        InsertInboundShipmentLineError::VvmStatusDoesNotExist
+    }
+}
+
 impl From<NumberOfPacksBelowOne> for InsertInboundShipmentLineError {
     fn from(_: NumberOfPacksBelowOne) -> Self {
         InsertInboundShipmentLineError::NumberOfPacksBelowOne
@@ -63,6 +63,9 @@ fn generate_line(
         expiry_date,
         number_of_packs,
         location_id,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
         id: _,
         item_id: _,
     }: UpdateInboundShipmentLine,
@@ -74,6 +77,9 @@ fn generate_line(
     update_line.pack_size = pack_size.map(u32_to_i32).unwrap_or(update_line.pack_size);
     update_line.batch = batch.or(update_line.batch);
     update_line.location_id = location_id.or(update_line.location_id);
+    update_line.donor_id = donor_id.or(update_line.donor_id);
+    update_line.manufacturer_id = manufacturer_id.or(update_line.manufacturer_id);
+    update_line.vvm_status_id = vvm_status_id.or(update_line.vvm_status_id);
     update_line.expiry_date = expiry_date.or(update_line.expiry_date);
     update_line.sell_price_per_pack =
         sell_price_per_pack.unwrap_or(update_line.sell_price_per_pack);
@@ -1,4 +1,8 @@
-use crate::{invoice_line::query::get_invoice_line, service_provider::ServiceContext, WithDBError};
+use crate::{
+    invoice_line::query::get_invoice_line,
+    serial_number::remove_from_invoice_line::reconcile_invoice_line_serial_numbers,
+    service_provider::ServiceContext, WithDBError,
+};
 use chrono::NaiveDate;
 use repository::{
     InvoiceLine, InvoiceLineRowRepository, InvoiceRowRepository, RepositoryError,
@@ -22,6 +26,9 @@ pub struct UpdateInboundShipmentLine {
     pub sell_price_per_pack: Option<f64>,
     pub expiry_date: Option<NaiveDate>,
     pub number_of_packs: Option<u32>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 type OutError = UpdateInboundShipmentLineError;
@@ -36,6 +43,7 @@ pub fn update_inbound_shipment_line(
         .connection
         .transaction_sync(|connection| {
             let (line, item, invoice) = validate(&input, store_id, &connection)?;
+            let existing_line = line.clone();
 
             let (invoice_row_option, updated_line, upsert_batch_option, delete_batch_id_option) =
                 generate(user_id, input, line, item, invoice);
@@ -47,6 +55,7 @@ pub fn update_inbound_shipment_line(
             }
 
             InvoiceLineRowRepository::new(&connection).upsert_one(&updated_line)?;
+            reconcile_invoice_line_serial_numbers(&connection, &existing_line, &updated_line)?;
 
             if let Some(id) = delete_batch_id_option {
                 stock_line_respository.delete(&id)?;
@@ -74,6 +83,9 @@ pub enum UpdateInboundShipmentLineError {
     NotThisStoreInvoice,
     CannotEditFinalised,
     LocationDoesNotExist,
+    DonorDoesNotExist,
+    ManufacturerDoesNotExist,
+    VvmStatusDoesNotExist,
     ItemNotFound,
     PackSizeBelowOne,
     NumberOfPacksBelowOne,
@@ -109,7 +121,7 @@ mod test {
             mock_user_account_a, MockDataInserts,
         },
         test_db::setup_all,
-        InvoiceLineRowRepository,
+        InvoiceLineRowRepository, InvoiceLineSerialNumberRowRepository, ItemRowRepository,
     };
     use util::{inline_edit, inline_init};
 
@@ -117,6 +129,7 @@ mod test {
         invoice_line::inbound_shipment_line::{
             update::UpdateInboundShipmentLine, UpdateInboundShipmentLineError as ServiceError,
         },
+        serial_number::add_to_invoice_line::AddInvoiceLineSerialNumbers,
         service_provider::ServiceProvider,
     };
 
@@ -297,4 +310,77 @@ mod test {
             })
         );
     }
+
+    #[actix_rt::test]
+    async fn update_inbound_shipment_line_serial_numbers() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "update_inbound_shipment_line_serial_numbers",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let line = mock_inbound_shipment_c_invoice_lines()[0].clone();
+        ItemRowRepository::new(&connection)
+            .upsert_one(&inline_edit(&mock_item_a(), |mut u| {
+                u.is_serial_tracked = true;
+                u
+            }))
+            .unwrap();
+
+        service_provider
+            .serial_number_service
+            .add_invoice_line_serial_numbers(
+                &context,
+                &mock_store_a().id,
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: line.id.clone(),
+                    serial_numbers: vec!["SN1".to_string(), "SN2".to_string()],
+                },
+            )
+            .unwrap();
+        let serial_number_repo = InvoiceLineSerialNumberRowRepository::new(&connection);
+
+        // Serial numbers still fit the units on the line
+        service_provider
+            .invoice_line_service
+            .update_inbound_shipment_line(
+                &context,
+                &mock_store_a().id,
+                &mock_user_account_a().id,
+                inline_init(|r: &mut UpdateInboundShipmentLine| {
+                    r.id = line.id.clone();
+                    r.number_of_packs = Some(2);
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            serial_number_repo
+                .find_many_by_invoice_line_ids(&[line.id.clone()])
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // Fewer units than serial numbers, they have to be recorded again
+        service_provider
+            .invoice_line_service
+            .update_inbound_shipment_line(
+                &context,
+                &mock_store_a().id,
+                &mock_user_account_a().id,
+                inline_init(|r: &mut UpdateInboundShipmentLine| {
+                    r.id = line.id.clone();
+                    r.number_of_packs = Some(1);
+                }),
+            )
+            .unwrap();
+        assert_eq!(
+            serial_number_repo
+                .find_many_by_invoice_line_ids(&[line.id.clone()])
+                .unwrap(),
+            vec![]
+        );
+    }
 }
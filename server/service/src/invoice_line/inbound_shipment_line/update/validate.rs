@@ -6,6 +6,10 @@ use crate::{
     },
     invoice_line::{
         check_batch, check_location_exists, check_pack_size,
+        inbound_shipment_line::{
+            check_donor, check_manufacturer, check_vvm_status, DonorDoesNotExist,
+            ManufacturerDoesNotExist, VvmStatusDoesNotExist,
+        },
         validate::{
             check_item, check_line_exists, check_number_of_packs, ItemNotFound, LineDoesNotExist,
             NotInvoiceLine, NumberOfPacksBelowOne,
@@ -36,6 +40,9 @@ pub fn validate(
     check_batch(&line, connection)?;
 
     check_location_exists(&input.location_id, connection)?;
+    check_donor(&input.donor_id, connection)?;
+    check_manufacturer(&input.manufacturer_id, connection)?;
+    check_vvm_status(&input.vvm_status_id, connection)?;
 
     // TODO: StockLineDoesNotBelongToCurrentStore
     // TODO: LocationDoesNotBelongToCurrentStore
@@ -66,6 +73,24 @@ impl From<LocationDoesNotExist> for UpdateInboundShipmentLineError {
     }
 }
 
+impl From<DonorDoesNotExist> for UpdateInboundShipmentLineError {
+    fn from(_: DonorDoesNotExist) -> Self {
+        UpdateInboundShipmentLineError::DonorDoesNotExist
+    }
+}
+
+impl From<ManufacturerDoesNotExist> for UpdateInboundShipmentLineError {
+    fn from(_: ManufacturerDoesNotExist) -> Self {
+        UpdateInboundShipmentLineError::ManufacturerDoesNotExist
+    }
+}
+
+impl From<VvmStatusDoesNotExist> for UpdateInboundShipmentLineError {
+    fn from(_: VvmStatusDoesNotExist) -> Self {
+        UpdateInboundShipmentLineError::VvmStatusDoesNotExist
+    }
+}
+
 impl From<NumberOfPacksBelowOne> for UpdateInboundShipmentLineError {
     fn from(_: NumberOfPacksBelowOne) -> Self {
         UpdateInboundShipmentLineError::NumberOfPacksBelowOne
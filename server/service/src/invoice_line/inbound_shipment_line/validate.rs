@@ -2,7 +2,12 @@ use repository::{
     InvoiceLineRow, LocationRowRepository, StockLineRow, StockLineRowRepository, StorageConnection,
 };
 
-use crate::WithDBError;
+use crate::{
+    stock_line_reservation::consume::get_held_packs,
+    validate::{check_donor_exists, check_manufacturer_exists, check_vvm_status_exists},
+    WithDBError,
+};
+use util::date_now;
 
 pub struct PackSizeBelowOne;
 
@@ -23,9 +28,13 @@ pub fn check_batch(
 ) -> Result<(), WithDBError<BatchIsReserved>> {
     if let Some(batch_id) = &line.stock_line_id {
         match StockLineRowRepository::new(connection).find_one_by_id(batch_id) {
-            Ok(batch) => return check_batch_stock_reserved(line, batch),
+            Ok(batch) => check_batch_stock_reserved(line, batch)?,
             Err(error) => return Err(WithDBError::db(error)),
         };
+        // Stock held for a customer or campaign can't be changed or removed
+        if get_held_packs(connection, batch_id, date_now()).map_err(WithDBError::db)? > 0 {
+            return Err(WithDBError::err(BatchIsReserved));
+        }
     }
 
     return Ok(());
@@ -62,3 +71,51 @@ pub fn check_location_exists(
         None => Ok(()),
     }
 }
+
+pub struct DonorDoesNotExist;
+
+pub fn check_donor(
+    donor_id: &Option<String>,
+    connection: &StorageConnection,
+) -> Result<(), WithDBError<DonorDoesNotExist>> {
+    match donor_id {
+        Some(donor_id) => match check_donor_exists(connection, donor_id) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(WithDBError::err(DonorDoesNotExist)),
+            Err(error) => Err(WithDBError::db(error)),
+        },
+        None => Ok(()),
+    }
+}
+
+pub struct ManufacturerDoesNotExist;
+
+pub fn check_manufacturer(
+    manufacturer_id: &Option<String>,
+    connection: &StorageConnection,
+) -> Result<(), WithDBError<ManufacturerDoesNotExist>> {
+    match manufacturer_id {
+        Some(manufacturer_id) => match check_manufacturer_exists(connection, manufacturer_id) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(WithDBError::err(ManufacturerDoesNotExist)),
+            Err(error) => Err(WithDBError::db(error)),
+        },
+        None => Ok(()),
+    }
+}
+
+pub struct VvmStatusDoesNotExist;
+
+pub fn check_vvm_status(
+    vvm_status_id: &Option<String>,
+    connection: &StorageConnection,
+) -> Result<(), WithDBError<VvmStatusDoesNotExist>> {
+    match vvm_status_id {
+        Some(vvm_status_id) => match check_vvm_status_exists(connection, vvm_status_id) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(WithDBError::err(VvmStatusDoesNotExist)),
+            Err(error) => Err(WithDBError::db(error)),
+        },
+        None => Ok(()),
+    }
+}
@@ -1,7 +1,10 @@
-use crate::{service_provider::ServiceContext, WithDBError};
+use crate::{
+    serial_number::remove_from_invoice_line::remove_invoice_line_serial_numbers,
+    service_provider::ServiceContext, WithDBError,
+};
 use repository::{
-    InvoiceLineRowRepository, InvoiceRowRepository, InvoiceRowStatus, RepositoryError,
-    StockLineRowRepository,
+    InvoiceLineRowRepository, InvoiceRowRepository, InvoiceRowStatus, OpenVialWastageRowRepository,
+    RepositoryError, StockLineRowRepository, VvmStatusLogRowRepository,
 };
 
 mod validate;
@@ -25,6 +28,9 @@ pub fn delete_outbound_shipment_line(
             let line = validate(&input, store_id, &connection)?;
             let stock_line_id_option = line.stock_line_id.clone();
 
+            remove_invoice_line_serial_numbers(&connection, &line.id)?;
+            VvmStatusLogRowRepository::new(&connection).unlink_invoice_line(&line.id)?;
+            OpenVialWastageRowRepository::new(&connection).unlink_invoice_line(&line.id)?;
             InvoiceLineRowRepository::new(&connection).delete(&line.id)?;
 
             if let Some(stock_line_id) = stock_line_id_option {
@@ -82,21 +88,26 @@ mod test {
     use chrono::NaiveDate;
     use repository::{
         mock::{
-            mock_inbound_shipment_a_invoice_lines, mock_outbound_shipment_a_invoice_lines,
-            mock_outbound_shipment_b_invoice_lines, mock_outbound_shipment_c_invoice_lines,
-            mock_outbound_shipment_no_lines, mock_store_a, mock_store_b, mock_store_c, MockData,
-            MockDataInserts,
+            mock_inbound_shipment_a_invoice_lines, mock_item_a,
+            mock_outbound_shipment_a_invoice_lines, mock_outbound_shipment_b_invoice_lines,
+            mock_outbound_shipment_c_invoice_lines, mock_outbound_shipment_no_lines, mock_store_a,
+            mock_store_b, mock_store_c, MockData, MockDataInserts,
         },
         test_db::{setup_all, setup_all_with_data},
-        InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType, InvoiceRow, InvoiceRowStatus,
-        StockLineRowRepository,
+        InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType,
+        InvoiceLineSerialNumberRowRepository, InvoiceRow, InvoiceRowStatus, ItemRowRepository,
+        SerialNumberMovementRowRepository, SerialNumberRow, SerialNumberRowRepository,
+        SerialNumberStatus, StockLineRowRepository, VvmStatusLogRowRepository,
     };
     use util::{inline_edit, inline_init};
 
     use crate::{
+        invoice::outbound_shipment::{UpdateOutboundShipment, UpdateOutboundShipmentStatus},
         invoice_line::outbound_shipment_line::delete::DeleteOutboundShipmentLine,
         invoice_line::outbound_shipment_line::DeleteOutboundShipmentLineError as ServiceError,
+        serial_number::add_to_invoice_line::AddInvoiceLineSerialNumbers,
         service_provider::ServiceProvider,
+        vvm_status::update::UpdateStockLineVvmStatus,
     };
 
     #[actix_rt::test]
@@ -301,4 +312,145 @@ mod test {
             stock_line.available_number_of_packs
         );
     }
+
+    #[actix_rt::test]
+    async fn delete_outbound_shipment_line_serial_numbers() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "delete_outbound_shipment_line_serial_numbers",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let line = mock_outbound_shipment_c_invoice_lines()[0].clone();
+        ItemRowRepository::new(&connection)
+            .upsert_one(&inline_edit(&mock_item_a(), |mut u| {
+                u.is_serial_tracked = true;
+                u
+            }))
+            .unwrap();
+
+        let in_store = inline_init(|r: &mut SerialNumberRow| {
+            r.id = "in_store_serial_number".to_string();
+            r.item_id = line.item_id.clone();
+            r.store_id = mock_store_c().id;
+            r.serial_number = "SN1".to_string();
+            r.status = SerialNumberStatus::InStore;
+        });
+        SerialNumberRowRepository::new(&connection)
+            .upsert_one(&in_store)
+            .unwrap();
+
+        service_provider
+            .serial_number_service
+            .add_invoice_line_serial_numbers(
+                &context,
+                &mock_store_c().id,
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: line.id.clone(),
+                    serial_numbers: vec!["SN1".to_string()],
+                },
+            )
+            .unwrap();
+        // Unit is issued when the shipment is picked
+        service_provider
+            .invoice_service
+            .update_outbound_shipment(
+                &context,
+                &mock_store_c().id,
+                UpdateOutboundShipment {
+                    id: line.invoice_id.clone(),
+                    status: Some(UpdateOutboundShipmentStatus::Picked),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            SerialNumberRowRepository::new(&connection)
+                .find_one_by_id(&in_store.id)
+                .unwrap()
+                .unwrap()
+                .status,
+            SerialNumberStatus::Issued
+        );
+
+        service_provider
+            .invoice_line_service
+            .delete_outbound_shipment_line(
+                &context,
+                &mock_store_c().id,
+                DeleteOutboundShipmentLine {
+                    id: line.id.clone(),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            SerialNumberMovementRowRepository::new(&connection)
+                .find_many_by_invoice_line_id(&line.id)
+                .unwrap(),
+            vec![]
+        );
+        assert_eq!(
+            InvoiceLineSerialNumberRowRepository::new(&connection)
+                .find_many_by_invoice_line_ids(&[line.id.clone()])
+                .unwrap(),
+            vec![]
+        );
+        // Issued serial number is back in store without a holder
+        assert_eq!(
+            SerialNumberRowRepository::new(&connection)
+                .find_one_by_id(&in_store.id)
+                .unwrap(),
+            Some(in_store)
+        );
+    }
+
+    #[actix_rt::test]
+    async fn delete_outbound_shipment_line_vvm_status() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "delete_outbound_shipment_line_vvm_status",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let line = mock_outbound_shipment_c_invoice_lines()[0].clone();
+        let stock_line_id = line.stock_line_id.clone().unwrap();
+
+        service_provider
+            .vvm_status_service
+            .update_stock_line_vvm_status(
+                &context,
+                &mock_store_a().id,
+                UpdateStockLineVvmStatus {
+                    stock_line_id: stock_line_id.clone(),
+                    status_id: "VVM_STAGE_2".to_string(),
+                    invoice_line_id: Some(line.id.clone()),
+                    comment: None,
+                },
+            )
+            .unwrap();
+
+        service_provider
+            .invoice_line_service
+            .delete_outbound_shipment_line(
+                &context,
+                &mock_store_c().id,
+                DeleteOutboundShipmentLine {
+                    id: line.id.clone(),
+                },
+            )
+            .unwrap();
+
+        // Status observed at issue is kept in the stock line history
+        let logs = VvmStatusLogRowRepository::new(&connection)
+            .find_many_by_stock_line_id(&stock_line_id)
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].status_id, "VVM_STAGE_2");
+        assert_eq!(logs[0].invoice_line_id, None);
+    }
 }
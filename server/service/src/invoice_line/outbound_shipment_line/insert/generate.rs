@@ -43,9 +43,11 @@ fn generate_line(
         item_id,
         stock_line_id,
         number_of_packs,
+        number_of_doses: _,
         total_before_tax,
         total_after_tax,
         tax,
+        stock_line_reservation_id: _,
     }: InsertOutboundShipmentLine,
     ItemRow {
         name: item_name,
@@ -60,6 +62,9 @@ fn generate_line(
         expiry_date,
         location_id,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
         ..
     }: StockLineRow,
 ) -> InvoiceLineRow {
@@ -82,5 +87,8 @@ fn generate_line(
         total_after_tax,
         tax,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
     }
 }
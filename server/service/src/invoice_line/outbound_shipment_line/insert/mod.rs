@@ -1,5 +1,12 @@
-use crate::{invoice_line::query::get_invoice_line, service_provider::ServiceContext, WithDBError};
+use crate::{
+    doses::stock_line_doses_to_number_of_packs,
+    invoice_line::query::get_invoice_line,
+    service_provider::ServiceContext,
+    stock_line_reservation::consume::{consume_stock_line_reservations, ReservationHolder},
+    u32_to_i32, WithDBError,
+};
 use repository::{InvoiceLine, InvoiceLineRowRepository, RepositoryError, StockLineRowRepository};
+use util::date_now;
 
 mod generate;
 mod validate;
@@ -13,9 +20,15 @@ pub struct InsertOutboundShipmentLine {
     pub item_id: String,
     pub stock_line_id: String,
     pub number_of_packs: u32,
+    /// Quantity entered in doses, when provided number_of_packs is calculated from it and needs
+    /// to be 0
+    pub number_of_doses: Option<u32>,
     pub total_before_tax: f64,
     pub total_after_tax: f64,
     pub tax: Option<f64>,
+    /// Reservation of the stock line to issue the packs from, e.g. for a campaign. Reservations
+    /// held for the customer are used without it.
+    pub stock_line_reservation_id: Option<String>,
 }
 
 type OutError = InsertOutboundShipmentLineError;
@@ -28,7 +41,34 @@ pub fn insert_outbound_shipment_line(
     let new_line = ctx
         .connection
         .transaction_sync(|connection| {
+            let mut input = input;
+            if let Some(number_of_doses) = input.number_of_doses {
+                if input.number_of_packs != 0 {
+                    return Err(OutError::CannotProvideNumberOfPacksAndDoses);
+                }
+                input.number_of_packs = match stock_line_doses_to_number_of_packs(
+                    connection,
+                    &input.stock_line_id,
+                    number_of_doses,
+                ) {
+                    Ok(Some(number_of_packs)) => number_of_packs,
+                    Ok(None) => return Err(OutError::TooManyDoses),
+                    Err(RepositoryError::NotFound) => return Err(OutError::StockLineNotFound),
+                    Err(error) => return Err(error.into()),
+                };
+            }
             let (item, invoice, batch) = validate(&input, store_id, &connection)?;
+            let holder = ReservationHolder {
+                name_id: &invoice.name_id,
+                reservation_id: input.stock_line_reservation_id.as_deref(),
+            };
+            consume_stock_line_reservations(
+                connection,
+                &batch.id,
+                &holder,
+                u32_to_i32(input.number_of_packs),
+                date_now(),
+            )?;
             let (new_line, update_batch) = generate(input, item, batch, invoice)?;
             InvoiceLineRowRepository::new(&connection).upsert_one(&new_line)?;
             StockLineRowRepository::new(&connection).upsert_one(&update_batch)?;
@@ -51,6 +91,8 @@ pub enum InsertOutboundShipmentLineError {
     ItemNotFound,
     StockLineNotFound,
     NumberOfPacksBelowOne,
+    CannotProvideNumberOfPacksAndDoses,
+    TooManyDoses,
     LocationIsOnHold,
     LocationNotFound,
     StockLineAlreadyExistsInInvoice(String),
@@ -58,6 +100,7 @@ pub enum InsertOutboundShipmentLineError {
     NewlyCreatedLineDoesNotExist,
     BatchIsOnHold,
     ReductionBelowZero { stock_line_id: String },
+    StockLineReservationNotFound,
 }
 
 impl From<RepositoryError> for InsertOutboundShipmentLineError {
@@ -80,17 +123,20 @@ where
 
 #[cfg(test)]
 mod test {
+    use chrono::Duration;
     use repository::{
         mock::{
-            mock_item_a, mock_item_b, mock_item_b_lines, mock_outbound_shipment_a_invoice_lines,
-            mock_outbound_shipment_c, mock_outbound_shipment_c_invoice_lines, mock_stock_line_a,
+            mock_item_a, mock_item_b, mock_item_b_lines, mock_name_a, mock_name_b,
+            mock_outbound_shipment_a_invoice_lines, mock_outbound_shipment_c,
+            mock_outbound_shipment_c_invoice_lines, mock_stock_line_a,
             mock_stock_line_location_is_on_hold, mock_stock_line_on_hold, mock_stock_line_si_d,
-            mock_store_a, mock_store_b, mock_store_c, MockDataInserts,
+            mock_store_a, mock_store_b, mock_store_c, MockData, MockDataInserts,
         },
-        test_db::setup_all,
-        InvoiceLineRow, InvoiceLineRowRepository, StockLineRowRepository,
+        test_db::{setup_all, setup_all_with_data},
+        InvoiceLineRow, InvoiceLineRowRepository, InvoiceRow, InvoiceRowType, ItemRowRepository,
+        StockLineReservationRowRepository, StockLineRow, StockLineRowRepository,
     };
-    use util::{inline_edit, inline_init};
+    use util::{date_now_with_offset, inline_edit, inline_init};
 
     use crate::{
         invoice::outbound_shipment::{UpdateOutboundShipment, UpdateOutboundShipmentStatus},
@@ -98,11 +144,12 @@ mod test {
             insert::InsertOutboundShipmentLine, InsertOutboundShipmentLineError as ServiceError,
         },
         service_provider::ServiceProvider,
+        stock_line_reservation::insert::InsertStockLineReservation,
     };
 
     #[actix_rt::test]
     async fn insert_outbound_shipment_line_errors() {
-        let (_, _, connection_manager, _) = setup_all(
+        let (_, connection, connection_manager, _) = setup_all(
             "insert_outbound_shipment_line_errors",
             MockDataInserts::all(),
         )
@@ -302,6 +349,47 @@ mod test {
             Err(ServiceError::NotThisStoreInvoice)
         );
 
+        // CannotProvideNumberOfPacksAndDoses
+        assert_eq!(
+            service.insert_outbound_shipment_line(
+                &context,
+                &mock_store_c().id,
+                inline_init(|r: &mut InsertOutboundShipmentLine| {
+                    r.id = "new outbound line id".to_string();
+                    r.invoice_id = mock_outbound_shipment_c().id;
+                    r.number_of_packs = 1;
+                    r.number_of_doses = Some(10);
+                    r.stock_line_id = mock_stock_line_a().id.clone();
+                    r.item_id = mock_stock_line_a().item_id.clone();
+                }),
+            ),
+            Err(ServiceError::CannotProvideNumberOfPacksAndDoses)
+        );
+
+        // TooManyDoses
+        let item_repo = ItemRowRepository::new(&connection);
+        item_repo
+            .upsert_one(&inline_edit(&mock_item_a(), |mut u| {
+                u.doses = 10;
+                u.is_vaccine = true;
+                u
+            }))
+            .unwrap();
+        assert_eq!(
+            service.insert_outbound_shipment_line(
+                &context,
+                &mock_store_c().id,
+                inline_init(|r: &mut InsertOutboundShipmentLine| {
+                    r.id = "new outbound line id".to_string();
+                    r.invoice_id = mock_outbound_shipment_c().id;
+                    r.number_of_doses = Some(u32::MAX);
+                    r.stock_line_id = mock_stock_line_a().id.clone();
+                    r.item_id = mock_stock_line_a().item_id.clone();
+                }),
+            ),
+            Err(ServiceError::TooManyDoses)
+        );
+
         //TODO: NewlyCreatedLineDoesNotExist
     }
 
@@ -463,4 +551,146 @@ mod test {
             stock_line_for_invoice_line(&picked_outbound_line).total_number_of_packs
         )
     }
+
+    #[actix_rt::test]
+    async fn insert_outbound_shipment_line_reservations() {
+        fn invoice(id: &str, name_id: String) -> InvoiceRow {
+            inline_init(|r: &mut InvoiceRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.name_id = name_id;
+                r.r#type = InvoiceRowType::OutboundShipment;
+            })
+        }
+
+        fn line(invoice_id: &str, number_of_packs: u32) -> InsertOutboundShipmentLine {
+            inline_init(|r: &mut InsertOutboundShipmentLine| {
+                r.id = format!("{}_line", invoice_id);
+                r.invoice_id = invoice_id.to_string();
+                r.item_id = mock_item_a().id;
+                r.stock_line_id = "stock_line".to_string();
+                r.number_of_packs = number_of_packs;
+            })
+        }
+
+        fn reservation(id: &str, number_of_packs: u32) -> InsertStockLineReservation {
+            InsertStockLineReservation {
+                id: id.to_string(),
+                stock_line_id: "stock_line".to_string(),
+                name_id: None,
+                campaign: None,
+                number_of_packs,
+                reserved_until: date_now_with_offset(Duration::days(30)),
+            }
+        }
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "insert_outbound_shipment_line_reservations",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![
+                    invoice("customer", mock_name_a().id),
+                    invoice("other_customer", mock_name_b().id),
+                    invoice("campaign", mock_name_b().id),
+                ];
+                r.stock_lines = vec![inline_init(|r: &mut StockLineRow| {
+                    r.id = "stock_line".to_string();
+                    r.store_id = mock_store_a().id;
+                    r.item_id = mock_item_a().id;
+                    r.pack_size = 1;
+                    r.available_number_of_packs = 10;
+                    r.total_number_of_packs = 10;
+                })];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.invoice_line_service;
+        let store_id = mock_store_a().id;
+        let reservation_service = service_provider.stock_line_reservation_service;
+        reservation_service
+            .insert_stock_line_reservation(
+                &context,
+                &store_id,
+                InsertStockLineReservation {
+                    name_id: Some(mock_name_a().id),
+                    ..reservation("customer_reservation", 4)
+                },
+            )
+            .unwrap();
+        reservation_service
+            .insert_stock_line_reservation(
+                &context,
+                &store_id,
+                InsertStockLineReservation {
+                    campaign: Some("Measles campaign".to_string()),
+                    ..reservation("campaign_reservation", 3)
+                },
+            )
+            .unwrap();
+        let reserved_packs = |id: &str| {
+            StockLineReservationRowRepository::new(&connection)
+                .find_one_by_id(id)
+                .unwrap()
+                .unwrap()
+                .number_of_packs
+        };
+
+        // Reserved packs can't be issued to other customers
+        assert_eq!(
+            service.insert_outbound_shipment_line(&context, &store_id, line("other_customer", 4)),
+            Err(ServiceError::ReductionBelowZero {
+                stock_line_id: "stock_line".to_string()
+            })
+        );
+        service
+            .insert_outbound_shipment_line(&context, &store_id, line("other_customer", 3))
+            .unwrap();
+
+        // Customer's reservation is used, but not the campaign's
+        assert_eq!(
+            service.insert_outbound_shipment_line(&context, &store_id, line("customer", 5)),
+            Err(ServiceError::ReductionBelowZero {
+                stock_line_id: "stock_line".to_string()
+            })
+        );
+        service
+            .insert_outbound_shipment_line(&context, &store_id, line("customer", 4))
+            .unwrap();
+        assert_eq!(reserved_packs("customer_reservation"), 0);
+        assert_eq!(reserved_packs("campaign_reservation"), 3);
+
+        // Campaign stock is issued by picking the reservation
+        assert_eq!(
+            service.insert_outbound_shipment_line(
+                &context,
+                &store_id,
+                InsertOutboundShipmentLine {
+                    stock_line_reservation_id: Some("customer_reservation".to_string()),
+                    ..line("campaign", 3)
+                }
+            ),
+            Err(ServiceError::StockLineReservationNotFound)
+        );
+        service
+            .insert_outbound_shipment_line(
+                &context,
+                &store_id,
+                InsertOutboundShipmentLine {
+                    stock_line_reservation_id: Some("campaign_reservation".to_string()),
+                    ..line("campaign", 3)
+                },
+            )
+            .unwrap();
+        assert_eq!(reserved_packs("campaign_reservation"), 0);
+        assert_eq!(
+            StockLineRowRepository::new(&connection)
+                .find_one_by_id("stock_line")
+                .unwrap()
+                .available_number_of_packs,
+            0
+        );
+    }
 }
@@ -13,9 +13,14 @@ use crate::{
         BatchIsOnHold, ItemDoesNotMatchStockLine, LocationIsOnHoldError,
         StockLineAlreadyExistsInInvoice, StockLineNotFound,
     },
+    stock_line_reservation::consume::{get_packs_reserved_for_others, ReservationHolder},
     u32_to_i32,
 };
-use repository::{InvoiceRow, InvoiceRowType, ItemRow, StockLineRow, StorageConnection};
+use repository::{
+    InvoiceRow, InvoiceRowType, ItemRow, StockLineReservationRowRepository, StockLineRow,
+    StorageConnection,
+};
+use util::date_now;
 
 use super::{InsertOutboundShipmentLine, InsertOutboundShipmentLineError};
 
@@ -42,16 +47,53 @@ pub fn validate(
 
     check_batch_on_hold(&batch)?;
     check_location_on_hold(&batch, connection)?;
-    check_reduction_below_zero(&input, &batch)?;
+    check_stock_line_reservation(&input, &invoice, connection)?;
+    check_reduction_below_zero(&input, &invoice, &batch, connection)?;
 
     Ok((item, invoice, batch))
 }
 
+/// The picked reservation must be held for the stock line and, if it's held for a customer, for
+/// the customer of the shipment
+fn check_stock_line_reservation(
+    input: &InsertOutboundShipmentLine,
+    invoice: &InvoiceRow,
+    connection: &StorageConnection,
+) -> Result<(), InsertOutboundShipmentLineError> {
+    let reservation_id = match &input.stock_line_reservation_id {
+        Some(reservation_id) => reservation_id,
+        None => return Ok(()),
+    };
+    match StockLineReservationRowRepository::new(connection).find_one_by_id(reservation_id)? {
+        Some(reservation)
+            if reservation.released_datetime.is_none()
+                && reservation.reserved_until >= date_now()
+                && reservation.stock_line_id == input.stock_line_id
+                && reservation
+                    .name_id
+                    .as_ref()
+                    .map_or(true, |name_id| name_id == &invoice.name_id) =>
+        {
+            Ok(())
+        }
+        _ => Err(InsertOutboundShipmentLineError::StockLineReservationNotFound),
+    }
+}
+
+/// Packs reserved for someone else than the customer (or the picked reservation) can't be issued
 fn check_reduction_below_zero(
     input: &InsertOutboundShipmentLine,
+    invoice: &InvoiceRow,
     batch: &StockLineRow,
+    connection: &StorageConnection,
 ) -> Result<(), InsertOutboundShipmentLineError> {
-    if batch.available_number_of_packs < u32_to_i32(input.number_of_packs) {
+    let holder = ReservationHolder {
+        name_id: &invoice.name_id,
+        reservation_id: input.stock_line_reservation_id.as_deref(),
+    };
+    let reserved_for_others =
+        get_packs_reserved_for_others(connection, &batch.id, &holder, date_now())?;
+    if batch.available_number_of_packs - reserved_for_others < u32_to_i32(input.number_of_packs) {
         Err(InsertOutboundShipmentLineError::ReductionBelowZero {
             stock_line_id: batch.id.clone(),
         })
@@ -97,6 +97,9 @@ fn generate_line(
         expiry_date,
         location_id,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
         ..
     }: StockLineRow,
 ) -> InvoiceLineRow {
@@ -119,6 +122,9 @@ fn generate_line(
         tax,
         r#type,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
     };
 
     if let Some(number_of_packs) = input.number_of_packs {
@@ -1,12 +1,16 @@
 use crate::{
+    doses::stock_line_doses_to_number_of_packs,
     invoice_line::{query::get_invoice_line, ShipmentTaxUpdate},
+    serial_number::remove_from_invoice_line::reconcile_invoice_line_serial_numbers,
     service_provider::ServiceContext,
+    stock_line_reservation::consume::{consume_stock_line_reservations, ReservationHolder},
     u32_to_i32, WithDBError,
 };
 use repository::{
     InvoiceLine, InvoiceLineRow, InvoiceLineRowRepository, RepositoryError, StockLineRow,
     StockLineRowRepository,
 };
+use util::date_now;
 
 mod generate;
 mod validate;
@@ -19,6 +23,9 @@ pub struct UpdateOutboundShipmentLine {
     pub item_id: Option<String>,
     pub stock_line_id: Option<String>,
     pub number_of_packs: Option<u32>,
+    /// Quantity entered in doses, when provided number_of_packs is calculated from it and can't
+    /// be provided as well
+    pub number_of_doses: Option<u32>,
     pub total_before_tax: Option<f64>,
     pub total_after_tax: Option<f64>,
     pub tax: Option<ShipmentTaxUpdate>,
@@ -34,10 +41,47 @@ pub fn update_outbound_shipment_line(
     let updated_line = ctx
         .connection
         .transaction_sync(|connection| {
+            let mut input = input;
+            if let Some(number_of_doses) = input.number_of_doses {
+                if input.number_of_packs.is_some() {
+                    return Err(OutError::CannotProvideNumberOfPacksAndDoses);
+                }
+                let stock_line_id = match &input.stock_line_id {
+                    Some(stock_line_id) => Some(stock_line_id.clone()),
+                    None => InvoiceLineRowRepository::new(connection)
+                        .find_one_by_id_option(&input.id)?
+                        .and_then(|line| line.stock_line_id),
+                };
+                // Missing line or stock line are reported by the validation
+                if let Some(stock_line_id) = stock_line_id {
+                    input.number_of_packs = match stock_line_doses_to_number_of_packs(
+                        connection,
+                        &stock_line_id,
+                        number_of_doses,
+                    ) {
+                        Ok(Some(number_of_packs)) => Some(number_of_packs),
+                        Ok(None) => return Err(OutError::TooManyDoses),
+                        Err(RepositoryError::NotFound) => return Err(OutError::StockLineNotFound),
+                        Err(error) => return Err(error.into()),
+                    };
+                }
+            }
             let (line, item, batch_pair, invoice) = validate(&input, store_id, &connection)?;
+            let reduction = batch_pair.get_main_batch_reduction(&input, &line);
+            if reduction > 0 {
+                consume_stock_line_reservations(
+                    connection,
+                    &batch_pair.main_batch.id,
+                    &ReservationHolder::customer(&invoice.name_id),
+                    reduction,
+                    date_now(),
+                )?;
+            }
 
+            let existing_line = line.clone();
             let (update_line, batch_pair) = generate(input, line, item, batch_pair, invoice)?;
             InvoiceLineRowRepository::new(&connection).upsert_one(&update_line)?;
+            reconcile_invoice_line_serial_numbers(&connection, &existing_line, &update_line)?;
 
             let stock_line_repo = StockLineRowRepository::new(&connection);
             stock_line_repo.upsert_one(&batch_pair.main_batch)?;
@@ -99,6 +143,8 @@ pub enum UpdateOutboundShipmentLineError {
     ItemNotFound,
     StockLineNotFound,
     NumberOfPacksBelowOne,
+    CannotProvideNumberOfPacksAndDoses,
+    TooManyDoses,
     ItemDoesNotMatchStockLine,
     LocationIsOnHold,
     LocationNotFound,
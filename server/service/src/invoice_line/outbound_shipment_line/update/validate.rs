@@ -13,8 +13,10 @@ use crate::{
         BatchIsOnHold, ItemDoesNotMatchStockLine, LocationIsOnHoldError,
         StockLineAlreadyExistsInInvoice, StockLineNotFound,
     },
+    stock_line_reservation::consume::{get_packs_reserved_for_others, ReservationHolder},
 };
 use repository::{InvoiceLineRow, InvoiceRow, InvoiceRowType, ItemRow, StorageConnection};
+use util::date_now;
 
 use super::{BatchPair, UpdateOutboundShipmentLine, UpdateOutboundShipmentLineError};
 
@@ -45,21 +47,33 @@ pub fn validate(
 
     check_batch_on_hold(&batch_pair.main_batch)?;
     check_location_on_hold(&batch_pair.main_batch, connection)?;
-    check_reduction_below_zero(&input, &line, &batch_pair)?;
+    check_reduction_below_zero(&input, &line, &invoice, &batch_pair, connection)?;
 
     Ok((line, item, batch_pair, invoice))
 }
 
+/// Packs reserved for someone else than the customer can't be issued
 fn check_reduction_below_zero(
     input: &UpdateOutboundShipmentLine,
     line: &InvoiceLineRow,
+    invoice: &InvoiceRow,
     batch_pair: &BatchPair,
+    connection: &StorageConnection,
 ) -> Result<(), UpdateOutboundShipmentLineError> {
     // If previous batch is present, this means we are adjust new batch thus:
     // - check full number of pack in invoice
     let reduction = batch_pair.get_main_batch_reduction(input, line);
+    if reduction <= 0 {
+        return Ok(());
+    }
 
-    if batch_pair.main_batch.available_number_of_packs < reduction {
+    let reserved_for_others = get_packs_reserved_for_others(
+        connection,
+        &batch_pair.main_batch.id,
+        &ReservationHolder::customer(&invoice.name_id),
+        date_now(),
+    )?;
+    if batch_pair.main_batch.available_number_of_packs - reserved_for_others < reduction {
         Err(UpdateOutboundShipmentLineError::ReductionBelowZero {
             stock_line_id: batch_pair.main_batch.id.clone(),
             line_id: line.id.clone(),
@@ -35,5 +35,8 @@ pub fn generate(
         cost_price_per_pack: 0.0,
         sell_price_per_pack: 0.0,
         number_of_packs: 0,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     })
 }
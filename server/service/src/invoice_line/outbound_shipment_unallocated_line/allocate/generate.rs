@@ -1,20 +1,27 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::HashMap};
 
+use chrono::NaiveDateTime;
 use repository::{
-    EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository, InvoiceLineRow,
-    InvoiceLineRowType, Pagination, RepositoryError, StockLine, StockLineFilter,
-    StockLineRepository, StockLineSort, StockLineSortField, StorageConnection,
+    AllocationStrategy, EqualFilter, InvoiceLine, InvoiceLineFilter, InvoiceLineRepository,
+    InvoiceLineRow, InvoiceLineRowType, InvoiceRowRepository, Pagination, RepositoryError,
+    StockLine, StockLineFilter, StockLineMovementFilter, StockLineMovementRepository,
+    StockLineRepository, StockLineSort, StockLineSortField, StorageConnection, StorePreferenceRow,
+    VvmStatusRow, VvmStatusRowRepository,
 };
 use util::{
     constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset,
     fraction_is_integer, uuid,
 };
 
-use crate::invoice_line::{
-    outbound_shipment_line::{InsertOutboundShipmentLine, UpdateOutboundShipmentLine},
-    outbound_shipment_unallocated_line::{
-        DeleteOutboundShipmentUnallocatedLine, UpdateOutboundShipmentUnallocatedLine,
+use crate::{
+    invoice_line::{
+        outbound_shipment_line::{InsertOutboundShipmentLine, UpdateOutboundShipmentLine},
+        outbound_shipment_unallocated_line::{
+            DeleteOutboundShipmentUnallocatedLine, UpdateOutboundShipmentUnallocatedLine,
+        },
     },
+    stock_line_reservation::consume::{get_reserved_packs, ReservationHolder},
+    store_preference::query::get_store_preference,
 };
 
 #[derive(Default)]
@@ -25,6 +32,7 @@ pub struct GenerateOutput {
     pub delete_unallocated_line: Option<DeleteOutboundShipmentUnallocatedLine>,
     pub skipped_expired_stock_lines: Vec<StockLine>,
     pub skipped_on_hold_stock_lines: Vec<StockLine>,
+    pub skipped_unusable_vvm_stock_lines: Vec<StockLine>,
     pub issued_expiring_soon_stock_lines: Vec<StockLine>,
 }
 
@@ -44,36 +52,71 @@ pub fn generate(
         });
         return Ok(result);
     }
-    // Asc, by expiry date, nulls last
-    let sorted_available_stock_lines =
-        get_sorted_available_stock_lines(connection, store_id, &unallocated_line)?;
-    // Use FEFO to allocate
-    for stock_line in sorted_available_stock_lines {
-        let can_use = get_stock_line_eligibility(&stock_line)
-            .map(|eligibility| match eligibility {
-                StockLineAlert::OnHold => {
-                    result.skipped_on_hold_stock_lines.push(stock_line.clone());
-                    false
-                }
-                StockLineAlert::Expired => {
-                    result.skipped_expired_stock_lines.push(stock_line.clone());
-                    false
-                }
-                StockLineAlert::ExpiringSoon => {
-                    result
-                        .issued_expiring_soon_stock_lines
-                        .push(stock_line.clone());
-                    true
-                }
-            })
-            .unwrap_or(true);
-
-        if !can_use {
-            continue;
+    let vvm_statuses = get_vvm_statuses(connection)?;
+    let preference = get_store_preference(connection, store_id)?;
+    let invoice =
+        InvoiceRowRepository::new(connection).find_one_by_id(&unallocated_line.invoice_id)?;
+    let sorted_available_stock_lines = get_sorted_available_stock_lines(
+        connection,
+        store_id,
+        &unallocated_line,
+        &vvm_statuses,
+        &preference,
+        &ReservationHolder::customer(&invoice.name_id),
+    )?;
+    let stock_lines: Vec<(StockLine, Option<StockLineAlert>)> = sorted_available_stock_lines
+        .into_iter()
+        .map(|stock_line| {
+            let eligibility = get_stock_line_eligibility(&stock_line, &vvm_statuses);
+            (stock_line, eligibility)
+        })
+        .collect();
+
+    let packs_to_allocate = match preference.allocation_strategy {
+        AllocationStrategy::WholePackFirst => {
+            whole_packs_first_to_allocate(remaining_to_allocated, &stock_lines)
         }
+        AllocationStrategy::ExpiryFirst
+        | AllocationStrategy::FirstInFirstOut
+        | AllocationStrategy::PreferredLocation => {
+            packs_to_allocate_in_order(remaining_to_allocated, &stock_lines)
+        }
+    };
+    remaining_to_allocated -= stock_lines
+        .iter()
+        .zip(packs_to_allocate.iter())
+        .map(|((stock_line, _), packs)| packs * stock_line.stock_line_row.pack_size)
+        .sum::<i32>();
+    // Unusable stock lines are reported if they would have been used otherwise, i.e. they come
+    // before the last allocated stock line or there wasn't enough usable stock
+    let last_allocated_index = packs_to_allocate.iter().rposition(|packs| *packs > 0);
 
-        let packs_to_allocate =
-            packs_to_allocate_from_stock_line(remaining_to_allocated, &stock_line);
+    for (index, ((stock_line, eligibility), packs_to_allocate)) in stock_lines
+        .into_iter()
+        .zip(packs_to_allocate.into_iter())
+        .enumerate()
+    {
+        let passed_over = remaining_to_allocated > 0
+            || last_allocated_index.map_or(false, |last_index| index < last_index);
+        match eligibility {
+            Some(StockLineAlert::OnHold) if passed_over => {
+                result.skipped_on_hold_stock_lines.push(stock_line.clone())
+            }
+            Some(StockLineAlert::UnusableVvm) if passed_over => result
+                .skipped_unusable_vvm_stock_lines
+                .push(stock_line.clone()),
+            Some(StockLineAlert::Expired) if passed_over => {
+                result.skipped_expired_stock_lines.push(stock_line.clone())
+            }
+            Some(StockLineAlert::ExpiringSoon) if packs_to_allocate > 0 => result
+                .issued_expiring_soon_stock_lines
+                .push(stock_line.clone()),
+            _ => {}
+        }
+
+        if packs_to_allocate <= 0 {
+            continue;
+        }
 
         // Add to existing allocated line or create new
         match try_allocate_existing_line(
@@ -88,13 +131,6 @@ pub fn generate(
                 &stock_line,
             )),
         }
-
-        remaining_to_allocated =
-            remaining_to_allocated - packs_to_allocate * stock_line.stock_line_row.pack_size;
-
-        if remaining_to_allocated <= 0 {
-            break;
-        }
     }
 
     // If nothing remaing to alloacted just remove the line, otherwise update
@@ -112,13 +148,85 @@ pub fn generate(
     Ok(result)
 }
 
+/// Packs to allocate from each of the stock lines, using them in order
+fn packs_to_allocate_in_order(
+    mut remaining_to_allocated: i32,
+    stock_lines: &[(StockLine, Option<StockLineAlert>)],
+) -> Vec<i32> {
+    stock_lines
+        .iter()
+        .map(|(stock_line, eligibility)| {
+            if remaining_to_allocated <= 0 || !is_usable(eligibility) {
+                return 0;
+            }
+            let packs = packs_to_allocate_from_stock_line(remaining_to_allocated, stock_line);
+            remaining_to_allocated -= packs * stock_line.stock_line_row.pack_size;
+            packs
+        })
+        .collect()
+}
+
+/// Packs to allocate from each of the stock lines, whole packs of the largest pack size are used
+/// first (in stock line order within the same pack size). The remainder is then allocated in
+/// stock line order, only this part may need a pack to be broken.
+fn whole_packs_first_to_allocate(
+    mut remaining_to_allocated: i32,
+    stock_lines: &[(StockLine, Option<StockLineAlert>)],
+) -> Vec<i32> {
+    let mut result = vec![0; stock_lines.len()];
+
+    let mut by_pack_size: Vec<usize> = (0..stock_lines.len())
+        .filter(|index| is_usable(&stock_lines[*index].1))
+        .collect();
+    // Stable sort keeps stock line order within the same pack size
+    by_pack_size.sort_by(|a, b| {
+        let a_pack_size = stock_lines[*a].0.stock_line_row.pack_size;
+        let b_pack_size = stock_lines[*b].0.stock_line_row.pack_size;
+        b_pack_size.cmp(&a_pack_size)
+    });
+    for index in by_pack_size {
+        let line_row = &stock_lines[index].0.stock_line_row;
+        let whole_packs = (remaining_to_allocated / line_row.pack_size.max(1))
+            .min(line_row.available_number_of_packs);
+        result[index] = whole_packs;
+        remaining_to_allocated -= whole_packs * line_row.pack_size;
+    }
+
+    for (index, (stock_line, eligibility)) in stock_lines.iter().enumerate() {
+        if remaining_to_allocated <= 0 {
+            break;
+        }
+        let line_row = &stock_line.stock_line_row;
+        let available_packs = line_row.available_number_of_packs - result[index];
+        if !is_usable(eligibility) || available_packs <= 0 {
+            continue;
+        }
+        let packs = ((remaining_to_allocated as f64 / line_row.pack_size.max(1) as f64).ceil()
+            as i32)
+            .min(available_packs);
+        result[index] += packs;
+        remaining_to_allocated -= packs * line_row.pack_size;
+    }
+
+    result
+}
+
 enum StockLineAlert {
     OnHold,
+    UnusableVvm,
     Expired,
     ExpiringSoon,
 }
 
-fn get_stock_line_eligibility(stock_line: &StockLine) -> Option<StockLineAlert> {
+/// Stock lines that are expiring soon are still issued
+fn is_usable(eligibility: &Option<StockLineAlert>) -> bool {
+    matches!(eligibility, None | Some(StockLineAlert::ExpiringSoon))
+}
+
+fn get_stock_line_eligibility(
+    stock_line: &StockLine,
+    vvm_statuses: &HashMap<String, VvmStatusRow>,
+) -> Option<StockLineAlert> {
     use StockLineAlert::*;
     let stock_line_row = &stock_line.stock_line_row;
     // Expired
@@ -126,6 +234,12 @@ fn get_stock_line_eligibility(stock_line: &StockLine) -> Option<StockLineAlert>
         return Some(OnHold);
     }
 
+    if let Some(vvm_status) = get_vvm_status(stock_line, vvm_statuses) {
+        if vvm_status.unusable {
+            return Some(UnusableVvm);
+        }
+    }
+
     let expiry_date = match &stock_line_row.expiry_date {
         Some(expiry_date) => expiry_date,
         None => return None,
@@ -156,9 +270,11 @@ fn generate_new_line(
         item_id: stock_line_row.item_id.clone(),
         stock_line_id: stock_line_row.id.clone(),
         number_of_packs: packs_to_allocate as u32,
+        number_of_doses: None,
         total_before_tax: 0.0,
         total_after_tax: 0.0,
         tax: None,
+        stock_line_reservation_id: None,
     }
 }
 
@@ -175,6 +291,7 @@ fn try_allocate_existing_line(
             UpdateOutboundShipmentLine {
                 id: line_row.id,
                 number_of_packs: Some((line_row.number_of_packs + number_of_packs_to_add) as u32),
+                number_of_doses: None,
                 item_id: None,
                 stock_line_id: None,
                 total_before_tax: None,
@@ -200,10 +317,39 @@ fn packs_to_allocate_from_stock_line(remaining_to_allocated: i32, line: &StockLi
     fractional_number_of_packs.floor() as i32 + 1
 }
 
+fn get_vvm_statuses(
+    connection: &StorageConnection,
+) -> Result<HashMap<String, VvmStatusRow>, RepositoryError> {
+    Ok(VvmStatusRowRepository::new(connection)
+        .find_all()?
+        .into_iter()
+        .map(|status| (status.id.clone(), status))
+        .collect())
+}
+
+fn get_vvm_status<'a>(
+    stock_line: &StockLine,
+    vvm_statuses: &'a HashMap<String, VvmStatusRow>,
+) -> Option<&'a VvmStatusRow> {
+    stock_line
+        .stock_line_row
+        .vvm_status_id
+        .as_ref()
+        .and_then(|vvm_status_id| vvm_statuses.get(vvm_status_id))
+}
+
+/// Stock lines in the order they should be used in for the store's allocation strategy.
+///
+/// Packs reserved for someone else than the customer are not available. Stock lines reserved for
+/// the customer are used first, then vials closer to their VVM discard point. Otherwise stock
+/// lines are ordered by the strategy and then by expiry date (nulls last).
 fn get_sorted_available_stock_lines(
     connection: &StorageConnection,
     store_id: &str,
     unallocated_line: &InvoiceLineRow,
+    vvm_statuses: &HashMap<String, VvmStatusRow>,
+    preference: &StorePreferenceRow,
+    holder: &ReservationHolder,
 ) -> Result<Vec<StockLine>, RepositoryError> {
     let filter = StockLineFilter::new()
         .item_id(EqualFilter::equal_to(&unallocated_line.item_id))
@@ -216,7 +362,88 @@ fn get_sorted_available_stock_lines(
         desc: Some(false),
     };
 
-    StockLineRepository::new(connection).query(Pagination::new(), Some(filter), Some(sort))
+    let stock_lines =
+        StockLineRepository::new(connection).query(Pagination::all(), Some(filter), Some(sort))?;
+    let stock_line_ids: Vec<String> = stock_lines
+        .iter()
+        .map(|stock_line| stock_line.stock_line_row.id.clone())
+        .collect();
+    let reserved_packs = get_reserved_packs(connection, &stock_line_ids, holder, date_now())?;
+    let mut stock_lines: Vec<StockLine> = stock_lines
+        .into_iter()
+        .filter_map(|mut stock_line| {
+            let line_row = &mut stock_line.stock_line_row;
+            if let Some(reserved) = reserved_packs.get(&line_row.id) {
+                line_row.available_number_of_packs -= reserved.for_others;
+            }
+            if line_row.available_number_of_packs > 0 {
+                Some(stock_line)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Stable sorts below keep the previous order for equal keys
+    match preference.allocation_strategy {
+        AllocationStrategy::FirstInFirstOut => {
+            let received_datetimes =
+                get_received_datetimes(connection, store_id, &unallocated_line.item_id)?;
+            // Stock lines without a recorded receipt predate the recorded ones and go first
+            stock_lines.sort_by_key(|stock_line| {
+                received_datetimes
+                    .get(&stock_line.stock_line_row.id)
+                    .cloned()
+            });
+        }
+        AllocationStrategy::PreferredLocation => {
+            let pick_face_location_id = &preference.pick_face_location_id;
+            stock_lines.sort_by_key(|stock_line| {
+                pick_face_location_id.is_none()
+                    || stock_line.stock_line_row.location_id != *pick_face_location_id
+            });
+        }
+        AllocationStrategy::ExpiryFirst | AllocationStrategy::WholePackFirst => {}
+    }
+
+    stock_lines.sort_by(|a, b| {
+        let a_level = get_vvm_status(a, vvm_statuses).map(|status| status.level);
+        let b_level = get_vvm_status(b, vvm_statuses).map(|status| status.level);
+        b_level.cmp(&a_level)
+    });
+    stock_lines.sort_by_key(|stock_line| {
+        reserved_packs
+            .get(&stock_line.stock_line_row.id)
+            .map_or(true, |reserved| reserved.for_holder <= 0)
+    });
+
+    Ok(stock_lines)
+}
+
+/// Datetime of the first stock movement into each stock line (by id) of the item
+fn get_received_datetimes(
+    connection: &StorageConnection,
+    store_id: &str,
+    item_id: &str,
+) -> Result<HashMap<String, NaiveDateTime>, RepositoryError> {
+    let movements = StockLineMovementRepository::new(connection).query(Some(
+        StockLineMovementFilter::new()
+            .item_id(EqualFilter::equal_to(item_id))
+            .store_id(EqualFilter::equal_to(store_id)),
+    ))?;
+
+    let mut result: HashMap<String, NaiveDateTime> = HashMap::new();
+    for movement in movements {
+        let stock_line_id = match movement.stock_line_id {
+            Some(stock_line_id) if movement.quantity > 0 => stock_line_id,
+            _ => continue,
+        };
+        let received_datetime = result.entry(stock_line_id).or_insert(movement.datetime);
+        if movement.datetime < *received_datetime {
+            *received_datetime = movement.datetime;
+        }
+    }
+    Ok(result)
 }
 
 fn get_allocated_lines(
@@ -69,6 +69,7 @@ pub struct AllocateLineResult {
     pub updates: Vec<InvoiceLine>,
     pub skipped_expired_stock_lines: Vec<StockLine>,
     pub skipped_on_hold_stock_lines: Vec<StockLine>,
+    pub skipped_unusable_vvm_stock_lines: Vec<StockLine>,
     pub issued_expiring_soon_stock_lines: Vec<StockLine>,
 }
 
@@ -90,6 +91,7 @@ pub fn allocate_outbound_shipment_unallocated_line(
                 delete_unallocated_line,
                 skipped_expired_stock_lines,
                 skipped_on_hold_stock_lines,
+                skipped_unusable_vvm_stock_lines,
                 issued_expiring_soon_stock_lines,
             } = generate(&connection, &store_id, unallocated_line)?;
 
@@ -99,6 +101,7 @@ pub fn allocate_outbound_shipment_unallocated_line(
                 updates: vec![],
                 skipped_expired_stock_lines,
                 skipped_on_hold_stock_lines,
+                skipped_unusable_vvm_stock_lines,
                 issued_expiring_soon_stock_lines,
             };
 
@@ -4,12 +4,14 @@ mod test {
     use chrono::{Duration, NaiveDate};
     use repository::{
         mock::{
-            mock_item_a, mock_item_b, mock_name_a, mock_outbound_shipment_a_invoice_lines,
-            mock_store_a, mock_store_b, MockData, MockDataInserts,
+            mock_item_a, mock_item_b, mock_location_1, mock_name_a, mock_name_b,
+            mock_outbound_shipment_a_invoice_lines, mock_store_a, mock_store_b, MockData,
+            MockDataInserts,
         },
         test_db::{setup_all, setup_all_with_data},
-        InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType, InvoiceRow, InvoiceRowType,
-        RepositoryError, StockLine, StockLineRow,
+        AllocationStrategy, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType,
+        InvoiceRow, InvoiceRowType, RepositoryError, StockLine, StockLineReservationRowRepository,
+        StockLineRow, StockLineRowRepository, StorePreferenceRow, StorePreferenceRowRepository,
     };
     use util::{
         constants::stock_line_expiring_soon_offset, date_now, date_now_with_offset, inline_edit,
@@ -19,8 +21,11 @@ mod test {
     use crate::{
         invoice_line::AllocateOutboundShipmentUnallocatedLineError as ServiceError,
         service_provider::ServiceProvider,
+        stock_line_reservation::insert::InsertStockLineReservation,
     };
 
+    use super::super::generate;
+
     #[actix_rt::test]
     async fn allocate_unallocated_line_errors() {
         let (_, _, connection_manager, _) =
@@ -437,6 +442,117 @@ mod test {
         );
     }
 
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_vvm_status() {
+        fn invoice() -> InvoiceRow {
+            inline_init(|r: &mut InvoiceRow| {
+                r.id = "invoice".to_string();
+                r.store_id = mock_store_a().id;
+                r.name_id = mock_name_a().id;
+                r.r#type = InvoiceRowType::OutboundShipment;
+            })
+        }
+
+        fn line() -> InvoiceLineRow {
+            inline_init(|r: &mut InvoiceLineRow| {
+                r.id = "line".to_string();
+                r.invoice_id = invoice().id;
+                r.item_id = mock_item_a().id;
+                r.r#type = InvoiceLineRowType::UnallocatedStock;
+                r.number_of_packs = 3;
+                r.pack_size = 1;
+            })
+        }
+
+        fn base_stock_line(id: &str, expiry_days: i64) -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_a().id;
+                r.pack_size = 1;
+                r.available_number_of_packs = 1;
+                r.expiry_date = Some(date_now_with_offset(
+                    stock_line_expiring_soon_offset() + Duration::days(expiry_days),
+                ));
+            })
+        }
+
+        fn stock_line_no_vvm() -> StockLineRow {
+            base_stock_line("stock_line_no_vvm", 1)
+        }
+
+        fn stock_line_vvm_stage_1() -> StockLineRow {
+            inline_edit(&base_stock_line("stock_line_vvm_stage_1", 2), |mut u| {
+                u.vvm_status_id = Some("VVM_STAGE_1".to_string());
+                u
+            })
+        }
+
+        fn stock_line_vvm_stage_2() -> StockLineRow {
+            inline_edit(&base_stock_line("stock_line_vvm_stage_2", 3), |mut u| {
+                u.vvm_status_id = Some("VVM_STAGE_2".to_string());
+                u
+            })
+        }
+
+        fn stock_line_vvm_stage_3() -> StockLineRow {
+            inline_edit(&base_stock_line("stock_line_vvm_stage_3", 1), |mut u| {
+                u.vvm_status_id = Some("VVM_STAGE_3".to_string());
+                u
+            })
+        }
+
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "allocate_unallocated_line_vvm_status",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![invoice()];
+                r.invoice_lines = vec![line()];
+                r.stock_lines = vec![
+                    stock_line_no_vvm(),
+                    stock_line_vvm_stage_1(),
+                    stock_line_vvm_stage_3(),
+                    stock_line_vvm_stage_2(),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.invoice_line_service;
+
+        let result = service
+            .allocate_outbound_shipment_unallocated_line(
+                &context,
+                &mock_store_a().id,
+                line().id.clone(),
+            )
+            .unwrap();
+
+        // Worst usable stage first, even though it expires last
+        assert_eq!(result.inserts.len(), 3);
+        assert_eq!(
+            result.inserts[0].invoice_line_row.stock_line_id,
+            Some(stock_line_vvm_stage_2().id)
+        );
+        assert_eq!(
+            result.inserts[1].invoice_line_row.stock_line_id,
+            Some(stock_line_vvm_stage_1().id)
+        );
+        assert_eq!(
+            result.inserts[2].invoice_line_row.stock_line_id,
+            Some(stock_line_no_vvm().id)
+        );
+
+        assert_eq!(
+            result.skipped_unusable_vvm_stock_lines,
+            vec![inline_init(|r: &mut StockLine| {
+                r.stock_line_row = stock_line_vvm_stage_3();
+            })]
+        );
+    }
+
     #[actix_rt::test]
     async fn allocate_unallocated_line_add_to_existing_lines() {
         fn invoice() -> InvoiceRow {
@@ -650,4 +766,250 @@ mod test {
             })
         );
     }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_strategies() {
+        fn invoice() -> InvoiceRow {
+            inline_init(|r: &mut InvoiceRow| {
+                r.id = "invoice".to_string();
+                r.store_id = mock_store_a().id;
+                r.name_id = mock_name_a().id;
+                r.r#type = InvoiceRowType::OutboundShipment;
+            })
+        }
+
+        fn line(number_of_packs: i32) -> InvoiceLineRow {
+            inline_init(|r: &mut InvoiceLineRow| {
+                r.id = "line".to_string();
+                r.invoice_id = invoice().id;
+                r.item_id = mock_item_a().id;
+                r.r#type = InvoiceLineRowType::UnallocatedStock;
+                r.number_of_packs = number_of_packs;
+                r.pack_size = 1;
+            })
+        }
+
+        // Expires first, received last
+        fn single_units() -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = "single_units".to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_a().id;
+                r.expiry_date = Some(date_now() + Duration::days(400));
+                r.pack_size = 1;
+                r.available_number_of_packs = 10;
+                r.total_number_of_packs = 10;
+            })
+        }
+
+        // Expires last, received first, in the pick face
+        fn packs_of_five() -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = "packs_of_five".to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_a().id;
+                r.location_id = Some(mock_location_1().id);
+                r.expiry_date = Some(date_now() + Duration::days(800));
+                r.pack_size = 5;
+                r.available_number_of_packs = 10;
+                r.total_number_of_packs = 10;
+            })
+        }
+
+        fn receipt(stock_line: StockLineRow, delivered_datetime: NaiveDate) -> InvoiceRow {
+            inline_init(|r: &mut InvoiceRow| {
+                r.id = format!("{}_receipt", stock_line.id);
+                r.store_id = mock_store_a().id;
+                r.name_id = mock_name_a().id;
+                r.r#type = InvoiceRowType::InboundShipment;
+                r.delivered_datetime = Some(delivered_datetime.and_hms(0, 0, 0));
+            })
+        }
+
+        fn receipt_line(stock_line: StockLineRow) -> InvoiceLineRow {
+            inline_init(|r: &mut InvoiceLineRow| {
+                r.id = format!("{}_receipt_line", stock_line.id);
+                r.invoice_id = format!("{}_receipt", stock_line.id);
+                r.item_id = stock_line.item_id.clone();
+                r.r#type = InvoiceLineRowType::StockIn;
+                r.number_of_packs = stock_line.total_number_of_packs;
+                r.pack_size = stock_line.pack_size;
+                r.stock_line_id = Some(stock_line.id);
+            })
+        }
+
+        let (_, connection, _, _) = setup_all_with_data(
+            "allocate_unallocated_line_strategies",
+            MockDataInserts::none()
+                .stores()
+                .items()
+                .names()
+                .units()
+                .locations(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![
+                    invoice(),
+                    receipt(single_units(), NaiveDate::from_ymd(2022, 2, 1)),
+                    receipt(packs_of_five(), NaiveDate::from_ymd(2022, 1, 1)),
+                ];
+                r.invoice_lines = vec![
+                    line(10),
+                    receipt_line(single_units()),
+                    receipt_line(packs_of_five()),
+                ];
+                r.stock_lines = vec![single_units(), packs_of_five()];
+            }),
+        )
+        .await;
+
+        let allocate = |allocation_strategy: AllocationStrategy, number_of_packs: i32| {
+            StorePreferenceRowRepository::new(&connection)
+                .upsert_one(&StorePreferenceRow {
+                    id: mock_store_a().id,
+                    allocation_strategy,
+                    pick_face_location_id: Some(mock_location_1().id),
+                })
+                .unwrap();
+            generate(&connection, &mock_store_a().id, line(number_of_packs))
+                .unwrap()
+                .insert_lines
+                .into_iter()
+                .map(|line| (line.stock_line_id, line.number_of_packs))
+                .collect::<Vec<(String, u32)>>()
+        };
+
+        assert_eq!(
+            allocate(AllocationStrategy::ExpiryFirst, 10),
+            vec![(single_units().id, 10)]
+        );
+        assert_eq!(
+            allocate(AllocationStrategy::FirstInFirstOut, 10),
+            vec![(packs_of_five().id, 2)]
+        );
+        assert_eq!(
+            allocate(AllocationStrategy::PreferredLocation, 10),
+            vec![(packs_of_five().id, 2)]
+        );
+        // FEFO breaks a pack of five for the last 2 units
+        assert_eq!(
+            allocate(AllocationStrategy::ExpiryFirst, 12),
+            vec![(single_units().id, 10), (packs_of_five().id, 1)]
+        );
+        assert_eq!(
+            allocate(AllocationStrategy::WholePackFirst, 12),
+            vec![(single_units().id, 2), (packs_of_five().id, 2)]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn allocate_unallocated_line_reservations() {
+        fn invoice(id: &str, name_id: String) -> InvoiceRow {
+            inline_init(|r: &mut InvoiceRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.name_id = name_id;
+                r.r#type = InvoiceRowType::OutboundShipment;
+            })
+        }
+
+        fn line(invoice_id: &str) -> InvoiceLineRow {
+            inline_init(|r: &mut InvoiceLineRow| {
+                r.id = format!("{}_line", invoice_id);
+                r.invoice_id = invoice_id.to_string();
+                r.item_id = mock_item_a().id;
+                r.r#type = InvoiceLineRowType::UnallocatedStock;
+                r.number_of_packs = 12;
+                r.pack_size = 1;
+            })
+        }
+
+        fn stock_line(id: &str, expiry_date: NaiveDate) -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = id.to_string();
+                r.store_id = mock_store_a().id;
+                r.item_id = mock_item_a().id;
+                r.expiry_date = Some(expiry_date);
+                r.pack_size = 1;
+                r.available_number_of_packs = 10;
+                r.total_number_of_packs = 10;
+            })
+        }
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "allocate_unallocated_line_reservations",
+            MockDataInserts::none().stores().items().names().units(),
+            inline_init(|r: &mut MockData| {
+                r.invoices = vec![
+                    invoice("other_customer", mock_name_b().id),
+                    invoice("reserved_customer", mock_name_a().id),
+                ];
+                r.invoice_lines = vec![line("other_customer"), line("reserved_customer")];
+                r.stock_lines = vec![
+                    stock_line("unreserved", date_now() + Duration::days(400)),
+                    stock_line("reserved", date_now() + Duration::days(800)),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager.clone(), "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.invoice_line_service;
+
+        service_provider
+            .stock_line_reservation_service
+            .insert_stock_line_reservation(
+                &context,
+                &mock_store_a().id,
+                InsertStockLineReservation {
+                    id: "reservation".to_string(),
+                    stock_line_id: "reserved".to_string(),
+                    name_id: Some(mock_name_a().id),
+                    campaign: None,
+                    number_of_packs: 8,
+                    reserved_until: date_now(),
+                },
+            )
+            .unwrap();
+
+        let allocated = |invoice_id: &str| {
+            service
+                .allocate_outbound_shipment_unallocated_line(
+                    &context,
+                    &mock_store_a().id,
+                    format!("{}_line", invoice_id),
+                )
+                .unwrap()
+                .inserts
+                .into_iter()
+                .map(|line| {
+                    (
+                        line.invoice_line_row.stock_line_id.unwrap(),
+                        line.invoice_line_row.number_of_packs,
+                    )
+                })
+                .collect::<Vec<(String, i32)>>()
+        };
+
+        // Reserved packs are not available to other customers
+        assert_eq!(
+            allocated("other_customer"),
+            vec![("unreserved".to_string(), 10), ("reserved".to_string(), 2)]
+        );
+
+        // Reservation is consumed first, even though the stock line expires later
+        assert_eq!(
+            allocated("reserved_customer"),
+            vec![("reserved".to_string(), 8)]
+        );
+        let reservation = StockLineReservationRowRepository::new(&connection)
+            .find_one_by_id("reservation")
+            .unwrap()
+            .unwrap();
+        assert_eq!(reservation.number_of_packs, 0);
+        let stock_line = StockLineRowRepository::new(&connection)
+            .find_one_by_id("reserved")
+            .unwrap();
+        assert_eq!(stock_line.available_number_of_packs, 0);
+    }
 }
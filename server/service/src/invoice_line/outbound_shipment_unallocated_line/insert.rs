@@ -122,6 +122,9 @@ fn generate(
         sell_price_per_pack: 0.0,
         cost_price_per_pack: 0.0,
         stock_line_id: None,
+        donor_id: None,
+        manufacturer_id: None,
+        vvm_status_id: None,
     };
 
     Ok(new_line)
@@ -365,6 +368,9 @@ mod test_insert {
                 sell_price_per_pack: 0.0,
                 cost_price_per_pack: 0.0,
                 stock_line_id: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }
         )
     }
@@ -1,5 +1,9 @@
 use repository::PaginationOption;
-use repository::{Item, ItemFilter, ItemRepository, ItemSort, StorageConnectionManager};
+use repository::{
+    Item, ItemCategoryRow, ItemCategoryRowRepository, ItemDepartmentRow,
+    ItemDepartmentRowRepository, ItemFilter, ItemRepository, ItemSort, RepositoryError,
+    StorageConnectionManager,
+};
 
 use super::{get_default_pagination, i64_to_u32, ListError, ListResult};
 
@@ -21,3 +25,17 @@ pub fn get_items(
         count: i64_to_u32(repository.count(filter)?),
     })
 }
+
+pub fn get_item_categories(
+    connection_manager: &StorageConnectionManager,
+) -> Result<Vec<ItemCategoryRow>, RepositoryError> {
+    let connection = connection_manager.connection()?;
+    ItemCategoryRowRepository::new(&connection).find_all()
+}
+
+pub fn get_item_departments(
+    connection_manager: &StorageConnectionManager,
+) -> Result<Vec<ItemDepartmentRow>, RepositoryError> {
+    let connection = connection_manager.connection()?;
+    ItemDepartmentRowRepository::new(&connection).find_all()
+}
@@ -0,0 +1,222 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::Utc;
+use repository::{
+    AbcClass, EqualFilter, ItemClassificationRow, ItemClassificationRowRepository, RepositoryError,
+    StockLineFilter, StockLineRepository, StockLineRow,
+};
+use util::{constants::DEFAULT_AMC_LOOKBACK_MONTHS, uuid::uuid};
+
+use crate::{item_stats::get_consumption_rows, service_provider::ServiceContext};
+
+/// Share of the total consumption value made up by A items
+const A_CLASS_VALUE_SHARE: f64 = 0.8;
+/// Share of the total consumption value made up by A and B items
+const B_CLASS_VALUE_SHARE: f64 = 0.95;
+
+/// Classifies the items of a store by consumption value (consumed units over the lookback period
+/// times the average unit cost of the item's stock in the store). Items making up the first 80% of
+/// the store's consumption value are A items, the next 15% B items and the rest C items.
+/// Manually set ABC classes and VEN classes are kept.
+pub fn calculate_item_classifications(
+    ctx: &ServiceContext,
+    store_id: &str,
+    lookback_months: Option<u32>,
+) -> Result<Vec<ItemClassificationRow>, RepositoryError> {
+    let lookback_months = lookback_months.unwrap_or(DEFAULT_AMC_LOOKBACK_MONTHS);
+
+    ctx.connection
+        .transaction_sync(|connection| {
+            let repo = ItemClassificationRowRepository::new(connection);
+            let existing: HashMap<String, ItemClassificationRow> = repo
+                .find_many_by_store_id(store_id)?
+                .into_iter()
+                .map(|row| (row.item_id.clone(), row))
+                .collect();
+
+            let stock_lines: Vec<StockLineRow> = StockLineRepository::new(connection)
+                .query_by_filter(StockLineFilter::new().store_id(EqualFilter::equal_to(store_id)))?
+                .into_iter()
+                .map(|stock_line| stock_line.stock_line_row)
+                .collect();
+            let unit_costs = average_unit_costs(&stock_lines);
+
+            // BTreeMap for a predictable order when values are equal
+            let mut consumption: BTreeMap<String, i64> = BTreeMap::new();
+            for item_id in existing.keys().chain(unit_costs.keys()) {
+                consumption.entry(item_id.clone()).or_insert(0);
+            }
+            for row in get_consumption_rows(connection, store_id, None, lookback_months)? {
+                *consumption.entry(row.item_id).or_insert(0) += row.quantity as i64;
+            }
+
+            let values: Vec<(String, f64)> = consumption
+                .into_iter()
+                .map(|(item_id, quantity)| {
+                    let unit_cost = unit_costs.get(&item_id).cloned().unwrap_or_default();
+                    (item_id, quantity as f64 * unit_cost)
+                })
+                .collect();
+            let classes = abc_classes(&values);
+
+            let updated_datetime = Utc::now().naive_utc();
+            let mut result = Vec::new();
+            for (item_id, consumption_value) in values {
+                let abc_class = classes.get(&item_id).cloned().unwrap_or_default();
+                let row = match existing.get(&item_id) {
+                    Some(existing) => ItemClassificationRow {
+                        abc_class: if existing.is_abc_manual {
+                            existing.abc_class
+                        } else {
+                            abc_class
+                        },
+                        consumption_value,
+                        updated_datetime,
+                        ..existing.clone()
+                    },
+                    None => ItemClassificationRow {
+                        id: uuid(),
+                        store_id: store_id.to_string(),
+                        item_id,
+                        abc_class,
+                        ven_class: None,
+                        consumption_value,
+                        is_abc_manual: false,
+                        updated_datetime,
+                    },
+                };
+                repo.upsert_one(&row)?;
+                result.push(row);
+            }
+
+            Ok(result)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Average cost per unit of each item over the given stock lines
+fn average_unit_costs(stock_lines: &[StockLineRow]) -> HashMap<String, f64> {
+    let mut totals: HashMap<String, (f64, u32)> = HashMap::new();
+    for stock_line in stock_lines {
+        let (total, count) = totals.entry(stock_line.item_id.clone()).or_insert((0.0, 0));
+        if stock_line.pack_size > 0 {
+            *total += stock_line.cost_price_per_pack / stock_line.pack_size as f64;
+            *count += 1;
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(item_id, (total, count))| {
+            let average = if count > 0 { total / count as f64 } else { 0.0 };
+            (item_id, average)
+        })
+        .collect()
+}
+
+/// An item is in a class while the items with a higher value make up less than the class share
+fn abc_classes(values: &[(String, f64)]) -> HashMap<String, AbcClass> {
+    let total: f64 = values.iter().map(|(_, value)| value).sum();
+    let mut sorted: Vec<&(String, f64)> = values.iter().collect();
+    sorted.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut cumulative = 0.0;
+    sorted
+        .into_iter()
+        .map(|(item_id, value)| {
+            let share_before = if total > 0.0 { cumulative / total } else { 1.0 };
+            cumulative += value;
+            let class = if *value <= 0.0 {
+                AbcClass::C
+            } else if share_before < A_CLASS_VALUE_SHARE {
+                AbcClass::A
+            } else if share_before < B_CLASS_VALUE_SHARE {
+                AbcClass::B
+            } else {
+                AbcClass::C
+            };
+            (item_id.clone(), class)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_store_a, test_item_stats, MockDataInserts},
+        test_db::setup_all_with_data,
+        AbcClass, ItemClassificationRow, ItemClassificationRowRepository, VenClass,
+    };
+    use util::inline_edit;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::abc_classes;
+
+    #[test]
+    fn abc_classes_by_value_share() {
+        let values = vec![
+            ("a1".to_string(), 50.0),
+            ("a2".to_string(), 30.0),
+            ("b".to_string(), 10.0),
+            ("c1".to_string(), 6.0),
+            ("c2".to_string(), 4.0),
+            ("zero".to_string(), 0.0),
+        ];
+        let classes = abc_classes(&values);
+        assert_eq!(classes["a1"], AbcClass::A);
+        assert_eq!(classes["a2"], AbcClass::A);
+        assert_eq!(classes["b"], AbcClass::B);
+        assert_eq!(classes["c1"], AbcClass::B);
+        assert_eq!(classes["c2"], AbcClass::C);
+        assert_eq!(classes["zero"], AbcClass::C);
+    }
+
+    #[actix_rt::test]
+    async fn calculate_item_classifications() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "calculate_item_classifications",
+            MockDataInserts::none().units().items().names().stores(),
+            inline_edit(&test_item_stats::mock_item_stats(), |mut u| {
+                for stock_line in u.stock_lines.iter_mut() {
+                    stock_line.cost_price_per_pack = stock_line.pack_size as f64;
+                }
+                u
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.item_classification_service;
+        let store_a = mock_store_a();
+        let item1 = test_item_stats::item();
+        let item2 = test_item_stats::item2();
+
+        // item1 makes up most of the consumption value, unit cost of both items is 1
+        let result = service
+            .calculate_item_classifications(&context, &store_a.id, None)
+            .unwrap();
+        let item1_row = result.iter().find(|r| r.item_id == item1.id).unwrap();
+        assert_eq!(item1_row.abc_class, AbcClass::A);
+        assert_eq!(item1_row.consumption_value, (3 * 5 + 1000) as f64);
+        let item2_row = result.iter().find(|r| r.item_id == item2.id).unwrap();
+        assert_eq!(item2_row.abc_class, AbcClass::C);
+
+        // manual ABC and VEN classes are kept
+        let repo = ItemClassificationRowRepository::new(&connection);
+        repo.upsert_one(&ItemClassificationRow {
+            abc_class: AbcClass::A,
+            is_abc_manual: true,
+            ven_class: Some(VenClass::V),
+            ..item2_row.clone()
+        })
+        .unwrap();
+        service
+            .calculate_item_classifications(&context, &store_a.id, None)
+            .unwrap();
+        let item2_row = repo.find_one_by_id(&item2_row.id).unwrap().unwrap();
+        assert_eq!(item2_row.abc_class, AbcClass::A);
+        assert_eq!(item2_row.ven_class, Some(VenClass::V));
+    }
+}
@@ -0,0 +1,45 @@
+use self::{
+    calculate::calculate_item_classifications,
+    query::get_item_classifications,
+    update::{update_item_classification, UpdateItemClassification, UpdateItemClassificationError},
+};
+
+use crate::service_provider::ServiceContext;
+use repository::{ItemClassificationRow, RepositoryError};
+
+pub mod calculate;
+pub mod query;
+pub mod update;
+
+pub trait ItemClassificationServiceTrait: Sync + Send {
+    /// Classifications of a store, ordered by item id
+    fn get_item_classifications(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Vec<ItemClassificationRow>, RepositoryError> {
+        get_item_classifications(ctx, store_id)
+    }
+
+    /// Recalculates ABC classes of a store's items from their consumption value
+    fn calculate_item_classifications(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        lookback_months: Option<u32>,
+    ) -> Result<Vec<ItemClassificationRow>, RepositoryError> {
+        calculate_item_classifications(ctx, store_id, lookback_months)
+    }
+
+    fn update_item_classification(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        input: UpdateItemClassification,
+    ) -> Result<ItemClassificationRow, UpdateItemClassificationError> {
+        update_item_classification(ctx, store_id, input)
+    }
+}
+
+pub struct ItemClassificationService {}
+impl ItemClassificationServiceTrait for ItemClassificationService {}
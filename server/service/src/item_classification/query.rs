@@ -0,0 +1,10 @@
+use repository::{ItemClassificationRow, ItemClassificationRowRepository, RepositoryError};
+
+use crate::service_provider::ServiceContext;
+
+pub fn get_item_classifications(
+    ctx: &ServiceContext,
+    store_id: &str,
+) -> Result<Vec<ItemClassificationRow>, RepositoryError> {
+    ItemClassificationRowRepository::new(&ctx.connection).find_many_by_store_id(store_id)
+}
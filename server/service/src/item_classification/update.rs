@@ -0,0 +1,158 @@
+use chrono::Utc;
+use repository::{
+    AbcClass, ItemClassificationRow, ItemClassificationRowRepository, ItemRowRepository,
+    RepositoryError, StorageConnection, VenClass,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct UpdateItemClassification {
+    pub item_id: String,
+    /// Manually set ABC class, kept when classes are recalculated
+    pub abc_class: Option<AbcClass>,
+    pub ven_class: Option<VenClass>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateItemClassificationError {
+    DatabaseError(RepositoryError),
+    ItemDoesNotExist,
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpdateItemClassification,
+) -> Result<Option<ItemClassificationRow>, UpdateItemClassificationError> {
+    if ItemRowRepository::new(connection)
+        .find_one_by_id(&input.item_id)?
+        .is_none()
+    {
+        return Err(UpdateItemClassificationError::ItemDoesNotExist);
+    }
+
+    Ok(ItemClassificationRowRepository::new(connection)
+        .find_one_by_store_and_item_id(store_id, &input.item_id)?)
+}
+
+fn generate(
+    store_id: &str,
+    existing: Option<ItemClassificationRow>,
+    UpdateItemClassification {
+        item_id,
+        abc_class,
+        ven_class,
+    }: UpdateItemClassification,
+) -> ItemClassificationRow {
+    let existing = existing.unwrap_or_else(|| ItemClassificationRow {
+        id: uuid(),
+        store_id: store_id.to_string(),
+        item_id,
+        ..Default::default()
+    });
+
+    ItemClassificationRow {
+        is_abc_manual: abc_class.is_some() || existing.is_abc_manual,
+        abc_class: abc_class.unwrap_or(existing.abc_class),
+        ven_class: ven_class.or(existing.ven_class),
+        updated_datetime: Utc::now().naive_utc(),
+        ..existing
+    }
+}
+
+/// Sets the ABC and/or VEN class of an item in a store, creating the classification if the item
+/// has not been classified yet
+pub fn update_item_classification(
+    ctx: &ServiceContext,
+    store_id: &str,
+    input: UpdateItemClassification,
+) -> Result<ItemClassificationRow, UpdateItemClassificationError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let existing = validate(connection, store_id, &input)?;
+            let new_row = generate(store_id, existing, input);
+            ItemClassificationRowRepository::new(connection).upsert_one(&new_row)?;
+            Ok(new_row)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for UpdateItemClassificationError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateItemClassificationError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_item_a, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        AbcClass, VenClass,
+    };
+
+    use crate::{
+        item_classification::update::{UpdateItemClassification, UpdateItemClassificationError},
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn update_item_classification() {
+        let (_, _, connection_manager, _) =
+            setup_all("update_item_classification", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.item_classification_service;
+        let store_a = mock_store_a();
+
+        // error: ItemDoesNotExist
+        assert_eq!(
+            service.update_item_classification(
+                &context,
+                &store_a.id,
+                UpdateItemClassification {
+                    item_id: "invalid".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(UpdateItemClassificationError::ItemDoesNotExist)
+        );
+
+        // success: VEN class only, ABC class stays calculated
+        let result = service
+            .update_item_classification(
+                &context,
+                &store_a.id,
+                UpdateItemClassification {
+                    item_id: mock_item_a().id,
+                    abc_class: None,
+                    ven_class: Some(VenClass::E),
+                },
+            )
+            .unwrap();
+        assert_eq!(result.ven_class, Some(VenClass::E));
+        assert_eq!(result.is_abc_manual, false);
+
+        // success: manual ABC class on the existing classification
+        let updated = service
+            .update_item_classification(
+                &context,
+                &store_a.id,
+                UpdateItemClassification {
+                    item_id: mock_item_a().id,
+                    abc_class: Some(AbcClass::A),
+                    ven_class: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(updated.id, result.id);
+        assert_eq!(updated.abc_class, AbcClass::A);
+        assert_eq!(updated.is_abc_manual, true);
+        assert_eq!(updated.ven_class, Some(VenClass::E));
+    }
+}
@@ -1,15 +1,19 @@
 use std::{collections::HashMap, ops::Neg};
 
-use crate::{i64_to_u32, service_provider::ServiceContext};
+use crate::{
+    i64_to_u32, service_provider::ServiceContext,
+    stock_line_reservation::consume::get_held_stock_lines,
+};
 use chrono::Duration;
 use repository::{
-    ConsumptionFilter, ConsumptionRepository, ConsumptionRow, DateFilter, EqualFilter,
+    ConsumptionFilter, ConsumptionRepository, ConsumptionRow, DateFilter, DatetimeFilter,
+    EqualFilter, OpenVialWastageFilter, OpenVialWastageRepository, OpenVialWastageRow,
     RepositoryError, RequisitionLineRow, StockOnHandFilter, StockOnHandRepository, StockOnHandRow,
     StorageConnection,
 };
 use util::{
     constants::{DEFAULT_AMC_LOOKBACK_MONTHS, NUMBER_OF_DAYS_IN_A_MONTH},
-    date_now_with_offset,
+    date_now, date_now_with_offset,
 };
 
 #[derive(Clone, Debug, PartialEq, Default)]
@@ -22,6 +26,8 @@ pub struct ItemStats {
     pub average_monthly_consumption: f64,
     pub available_stock_on_hand: u32,
     pub item_id: String,
+    /// Doses wasted from opened multi-dose vials / doses opened, over the lookback period
+    pub open_vial_wastage_rate: f64,
 }
 
 pub trait ItemStatsServiceTrait: Sync + Send {
@@ -58,7 +64,13 @@ pub fn get_item_stats(
             item_id_filter.clone(),
             amc_lookback_months,
         )?,
-        get_stock_on_hand_rows(&ctx.connection, store_id, item_id_filter)?,
+        get_available_stock_on_hand_rows(&ctx.connection, store_id, item_id_filter.clone())?,
+        get_open_vial_wastage_rows(
+            &ctx.connection,
+            store_id,
+            item_id_filter,
+            amc_lookback_months,
+        )?,
         amc_lookback_months,
     ))
 }
@@ -95,10 +107,54 @@ pub fn get_stock_on_hand_rows(
     StockOnHandRepository::new(&connection).query(Some(filter))
 }
 
+/// Stock on hand that can be issued to anyone, i.e. without stock held by reservations
+fn get_available_stock_on_hand_rows(
+    connection: &StorageConnection,
+    store_id: &str,
+    item_id_filter: Option<EqualFilter<String>>,
+) -> Result<Vec<StockOnHandRow>, RepositoryError> {
+    let mut held_units: HashMap<String, i64> = HashMap::new();
+    for (stock_line, held_packs) in get_held_stock_lines(connection, store_id, date_now())? {
+        *held_units.entry(stock_line.item_id).or_default() +=
+            held_packs as i64 * stock_line.pack_size as i64;
+    }
+
+    let mut rows = get_stock_on_hand_rows(connection, store_id, item_id_filter)?;
+    for row in rows.iter_mut() {
+        if let Some(units) = held_units.get(&row.item_id) {
+            row.available_stock_on_hand -= units;
+        }
+    }
+    Ok(rows)
+}
+
+pub fn get_open_vial_wastage_rows(
+    connection: &StorageConnection,
+    store_id: &str,
+    item_id_filter: Option<EqualFilter<String>>,
+    lookback_months: u32,
+) -> Result<Vec<OpenVialWastageRow>, RepositoryError> {
+    let start_date = date_now_with_offset(Duration::days(
+        (lookback_months as f64 * NUMBER_OF_DAYS_IN_A_MONTH).neg() as i64,
+    ));
+
+    let filter = OpenVialWastageFilter {
+        item_id: item_id_filter,
+        store_id: Some(EqualFilter::equal_to(store_id)),
+        opened_datetime: Some(DatetimeFilter::after_or_equal_to(
+            start_date.and_hms(0, 0, 0),
+        )),
+        ..Default::default()
+    };
+
+    OpenVialWastageRepository::new(connection).query_by_filter(filter)
+}
+
 impl ItemStats {
     fn new_vec(
         consumption_rows: Vec<ConsumptionRow>,
         stock_on_hand_rows: Vec<StockOnHandRow>,
+        open_vial_wastage_rows: Vec<OpenVialWastageRow>,
         amc_lookback_months: u32,
     ) -> Vec<Self> {
         let mut consumption_map = HashMap::new();
@@ -109,6 +165,14 @@ impl ItemStats {
             *item_total_consumption += consumption_row.quantity;
         }
 
+        // (doses used, doses wasted) per item
+        let mut open_vial_map = HashMap::new();
+        for wastage_row in open_vial_wastage_rows.into_iter() {
+            let (used, wasted) = open_vial_map.entry(wastage_row.item_id).or_insert((0, 0));
+            *used += wastage_row.doses_used as i64;
+            *wasted += wastage_row.doses_wasted as i64;
+        }
+
         stock_on_hand_rows
             .into_iter()
             .map(|stock_on_hand| ItemStats {
@@ -118,6 +182,11 @@ impl ItemStats {
                     .get(&stock_on_hand.item_id)
                     .map(|consumption| *consumption as f64 / amc_lookback_months as f64)
                     .unwrap_or_default(),
+                open_vial_wastage_rate: open_vial_map
+                    .get(&stock_on_hand.item_id)
+                    .filter(|(used, wasted)| used + wasted > 0)
+                    .map(|(used, wasted)| *wasted as f64 / (used + wasted) as f64)
+                    .unwrap_or_default(),
             })
             .collect()
     }
@@ -127,6 +196,7 @@ impl ItemStats {
             average_monthly_consumption: requistion_line.average_monthly_consumption as f64,
             available_stock_on_hand: requistion_line.available_stock_on_hand as u32,
             item_id: requistion_line.item_id.clone(),
+            open_vial_wastage_rate: 0.0,
         }
     }
 }
@@ -143,16 +213,17 @@ impl ItemStatsFilter {
 }
 #[cfg(test)]
 mod test {
+    use chrono::Utc;
     use repository::{
         mock::{mock_store_a, mock_store_b, test_item_stats, MockDataInserts},
-        test_db, EqualFilter,
+        test_db, EqualFilter, OpenVialWastageRow, OpenVialWastageRowRepository,
     };
 
     use crate::{item_stats::ItemStatsFilter, service_provider::ServiceProvider};
 
     #[actix_rt::test]
     async fn test_item_stats_service() {
-        let (_, _, connection_manager, _) = test_db::setup_all_with_data(
+        let (_, connection, connection_manager, _) = test_db::setup_all_with_data(
             "test_item_stats_service",
             MockDataInserts::all(),
             test_item_stats::mock_item_stats(),
@@ -163,6 +234,24 @@ mod test {
         let context = service_provider.context().unwrap();
         let service = service_provider.item_stats_service;
 
+        let wastage_repo = OpenVialWastageRowRepository::new(&connection);
+        let opened_datetime = Utc::now().naive_utc();
+        for (id, doses_used, doses_wasted) in [("wastage1", 6, 4), ("wastage2", 9, 1)] {
+            wastage_repo
+                .upsert_one(&OpenVialWastageRow {
+                    id: id.to_string(),
+                    store_id: mock_store_a().id,
+                    item_id: test_item_stats::item().id,
+                    stock_line_id: test_item_stats::stock_line1().id,
+                    invoice_line_id: None,
+                    doses_used,
+                    doses_wasted,
+                    opened_datetime,
+                    comment: None,
+                })
+                .unwrap();
+        }
+
         let item_ids = vec![test_item_stats::item().id, test_item_stats::item2().id];
         let filter = Some(ItemStatsFilter::new().item_id(EqualFilter::equal_any(item_ids)));
 
@@ -190,6 +279,10 @@ mod test {
             test_item_stats::item2_amc_3_months()
         );
 
+        // 5 of 20 opened doses wasted
+        assert_eq!(item_stats[0].open_vial_wastage_rate, 0.25);
+        assert_eq!(item_stats[1].open_vial_wastage_rate, 0.0);
+
         // Reduce to looking back 10 days
         let mut item_stats = service
             .get_item_stats(&context, &mock_store_a().id, Some(1), filter.clone())
@@ -230,5 +323,7 @@ mod test {
             item_stats[0].average_monthly_consumption,
             test_item_stats::item1_amc_3_months_store_b()
         );
+        // Wastage is per store
+        assert_eq!(item_stats[0].open_vial_wastage_rate, 0.0);
     }
 }
@@ -7,10 +7,15 @@ pub mod apis;
 pub mod app_data;
 pub mod auth;
 pub mod auth_data;
+pub mod cycle_count;
 pub mod dashboard;
+pub mod donor_stats;
+pub mod doses;
+pub mod historical_stock;
 pub mod invoice;
 pub mod invoice_line;
 pub mod item;
+pub mod item_classification;
 pub mod item_stats;
 pub mod location;
 pub mod log;
@@ -18,23 +23,30 @@ pub mod login;
 pub mod master_list;
 pub mod name;
 pub mod number;
+pub mod open_vial_wastage;
+pub mod redistribution;
 pub mod report;
 pub mod requisition;
 pub mod requisition_line;
+pub mod serial_number;
 pub mod service_provider;
 pub mod settings;
 pub mod settings_service;
 pub mod static_files;
 pub mod stock_line;
+pub mod stock_line_reservation;
+pub mod stock_quarantine;
 pub mod stocktake;
 pub mod stocktake_line;
 pub mod store;
+pub mod store_preference;
 pub mod sync;
 pub mod sync_processor;
 pub mod token;
 pub mod token_bucket;
 pub mod user_account;
 pub mod validate;
+pub mod vvm_status;
 
 #[cfg(test)]
 mod login_mock_data;
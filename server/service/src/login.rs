@@ -308,6 +308,9 @@ fn permissions_to_domain(permissions: Vec<Permissions>) -> HashSet<Permission> {
             Permissions::DeleteStocktake => {
                 output.insert(Permission::StocktakeMutate);
             }
+            Permissions::FinaliseInventoryAdjustments => {
+                output.insert(Permission::StocktakeApprove);
+            }
             // stocktake lines
             Permissions::ViewStocktakeLines => {
                 output.insert(Permission::StocktakeQuery);
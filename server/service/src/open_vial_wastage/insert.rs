@@ -0,0 +1,304 @@
+use chrono::{NaiveDateTime, Utc};
+use repository::{
+    InvoiceLineRowRepository, ItemRowRepository, OpenVialWastageRow, OpenVialWastageRowRepository,
+    RepositoryError, StockLineRow, StockLineRowRepository, StorageConnection,
+};
+
+use crate::{doses::doses_per_unit, service_provider::ServiceContext, u32_to_i32};
+
+#[derive(Default, Debug, Clone)]
+pub struct InsertOpenVialWastage {
+    pub id: String,
+    pub stock_line_id: String,
+    /// Shipment line the vial was opened for, if any
+    pub invoice_line_id: Option<String>,
+    pub doses_used: u32,
+    pub doses_wasted: u32,
+    /// Defaults to now
+    pub opened_datetime: Option<NaiveDateTime>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertOpenVialWastageError {
+    OpenVialWastageAlreadyExists,
+    StockLineDoesNotExist,
+    NotThisStoreStockLine,
+    ItemIsNotMultiDose,
+    /// doses_used + doses_wasted is more than the doses in a vial of the item
+    DosesExceedVial,
+    InvoiceLineDoesNotExist,
+    InvoiceLineNotForStockLine,
+    DatabaseError(RepositoryError),
+}
+
+type OutError = InsertOpenVialWastageError;
+
+pub fn insert_open_vial_wastage(
+    ctx: &ServiceContext,
+    store_id: &str,
+    input: InsertOpenVialWastage,
+) -> Result<OpenVialWastageRow, OutError> {
+    let row = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stock_line = validate(connection, store_id, &input)?;
+            let row = generate(stock_line, input);
+
+            OpenVialWastageRowRepository::new(connection).upsert_one(&row)?;
+
+            Ok(row) as Result<OpenVialWastageRow, OutError>
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(row)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &InsertOpenVialWastage,
+) -> Result<StockLineRow, OutError> {
+    if OpenVialWastageRowRepository::new(connection)
+        .find_one_by_id(&input.id)?
+        .is_some()
+    {
+        return Err(OutError::OpenVialWastageAlreadyExists);
+    }
+
+    let stock_line =
+        match StockLineRowRepository::new(connection).find_one_by_id(&input.stock_line_id) {
+            Ok(stock_line) => stock_line,
+            Err(RepositoryError::NotFound) => return Err(OutError::StockLineDoesNotExist),
+            Err(error) => return Err(error.into()),
+        };
+    if stock_line.store_id != store_id {
+        return Err(OutError::NotThisStoreStockLine);
+    }
+
+    let item = ItemRowRepository::new(connection)
+        .find_one_by_id(&stock_line.item_id)?
+        .ok_or(OutError::DatabaseError(RepositoryError::NotFound))?;
+    let doses_per_vial = doses_per_unit(&item);
+    if doses_per_vial <= 1 {
+        return Err(OutError::ItemIsNotMultiDose);
+    }
+    match input.doses_used.checked_add(input.doses_wasted) {
+        Some(doses) if doses <= doses_per_vial => {}
+        _ => return Err(OutError::DosesExceedVial),
+    }
+
+    if let Some(invoice_line_id) = &input.invoice_line_id {
+        let invoice_line =
+            match InvoiceLineRowRepository::new(connection).find_one_by_id(invoice_line_id) {
+                Ok(invoice_line) => invoice_line,
+                Err(RepositoryError::NotFound) => return Err(OutError::InvoiceLineDoesNotExist),
+                Err(error) => return Err(error.into()),
+            };
+        if invoice_line.stock_line_id.as_ref() != Some(&stock_line.id) {
+            return Err(OutError::InvoiceLineNotForStockLine);
+        }
+    }
+
+    Ok(stock_line)
+}
+
+fn generate(
+    StockLineRow {
+        store_id,
+        item_id,
+        id: stock_line_id,
+        ..
+    }: StockLineRow,
+    InsertOpenVialWastage {
+        id,
+        stock_line_id: _,
+        invoice_line_id,
+        doses_used,
+        doses_wasted,
+        opened_datetime,
+        comment,
+    }: InsertOpenVialWastage,
+) -> OpenVialWastageRow {
+    OpenVialWastageRow {
+        id,
+        store_id,
+        item_id,
+        stock_line_id,
+        invoice_line_id,
+        doses_used: u32_to_i32(doses_used),
+        doses_wasted: u32_to_i32(doses_wasted),
+        opened_datetime: opened_datetime.unwrap_or_else(|| Utc::now().naive_utc()),
+        comment,
+    }
+}
+
+impl From<RepositoryError> for InsertOpenVialWastageError {
+    fn from(error: RepositoryError) -> Self {
+        InsertOpenVialWastageError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_stock_line_a, MockDataInserts},
+        test_db::setup_all,
+        ItemRowRepository,
+    };
+
+    use crate::{
+        open_vial_wastage::insert::{
+            InsertOpenVialWastage, InsertOpenVialWastageError as ServiceError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn insert_open_vial_wastage() {
+        let (_, connection, connection_manager, _) =
+            setup_all("insert_open_vial_wastage", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.open_vial_wastage_service;
+
+        // StockLineDoesNotExist
+        assert_eq!(
+            service.insert_open_vial_wastage(
+                &context,
+                "store_a",
+                InsertOpenVialWastage {
+                    id: "new_id".to_string(),
+                    stock_line_id: "invalid".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::StockLineDoesNotExist)
+        );
+
+        // NotThisStoreStockLine
+        assert_eq!(
+            service.insert_open_vial_wastage(
+                &context,
+                "store_b",
+                InsertOpenVialWastage {
+                    id: "new_id".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::NotThisStoreStockLine)
+        );
+
+        // ItemIsNotMultiDose
+        assert_eq!(
+            service.insert_open_vial_wastage(
+                &context,
+                "store_a",
+                InsertOpenVialWastage {
+                    id: "new_id".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    doses_used: 1,
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::ItemIsNotMultiDose)
+        );
+
+        let item_repo = ItemRowRepository::new(&connection);
+        let mut item = item_repo
+            .find_one_by_id(&mock_stock_line_a().item_id)
+            .unwrap()
+            .unwrap();
+        item.doses = 10;
+        item.is_vaccine = true;
+        item_repo.upsert_one(&item).unwrap();
+
+        // DosesExceedVial
+        assert_eq!(
+            service.insert_open_vial_wastage(
+                &context,
+                "store_a",
+                InsertOpenVialWastage {
+                    id: "new_id".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    doses_used: 6,
+                    doses_wasted: 5,
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::DosesExceedVial)
+        );
+        assert_eq!(
+            service.insert_open_vial_wastage(
+                &context,
+                "store_a",
+                InsertOpenVialWastage {
+                    id: "new_id".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    doses_used: u32::MAX,
+                    doses_wasted: 1,
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::DosesExceedVial)
+        );
+
+        // InvoiceLineDoesNotExist
+        assert_eq!(
+            service.insert_open_vial_wastage(
+                &context,
+                "store_a",
+                InsertOpenVialWastage {
+                    id: "new_id".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    invoice_line_id: Some("invalid".to_string()),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::InvoiceLineDoesNotExist)
+        );
+
+        // Success
+        let row = service
+            .insert_open_vial_wastage(
+                &context,
+                "store_a",
+                InsertOpenVialWastage {
+                    id: "new_id".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    doses_used: 6,
+                    doses_wasted: 4,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(row.item_id, mock_stock_line_a().item_id);
+        assert_eq!(row.store_id, "store_a");
+        assert_eq!(row.doses_wasted, 4);
+
+        // OpenVialWastageAlreadyExists
+        assert_eq!(
+            service.insert_open_vial_wastage(
+                &context,
+                "store_a",
+                InsertOpenVialWastage {
+                    id: "new_id".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::OpenVialWastageAlreadyExists)
+        );
+
+        let result = service
+            .get_open_vial_wastage(&context, "store_a", None, None)
+            .unwrap();
+        assert_eq!(result.count, 1);
+        assert_eq!(result.rows, vec![row]);
+        let result = service
+            .get_open_vial_wastage(&context, "store_b", None, None)
+            .unwrap();
+        assert_eq!(result.count, 0);
+    }
+}
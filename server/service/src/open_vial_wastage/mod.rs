@@ -0,0 +1,35 @@
+use self::{
+    insert::{insert_open_vial_wastage, InsertOpenVialWastage, InsertOpenVialWastageError},
+    query::get_open_vial_wastage,
+};
+
+use crate::{service_provider::ServiceContext, ListError, ListResult};
+use repository::{OpenVialWastageFilter, OpenVialWastageRow, PaginationOption};
+
+pub mod insert;
+pub mod query;
+
+pub trait OpenVialWastageServiceTrait: Sync + Send {
+    /// Open vial wastage records of a store, most recently opened first
+    fn get_open_vial_wastage(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        pagination: Option<PaginationOption>,
+        filter: Option<OpenVialWastageFilter>,
+    ) -> Result<ListResult<OpenVialWastageRow>, ListError> {
+        get_open_vial_wastage(ctx, store_id, pagination, filter)
+    }
+
+    fn insert_open_vial_wastage(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        input: InsertOpenVialWastage,
+    ) -> Result<OpenVialWastageRow, InsertOpenVialWastageError> {
+        insert_open_vial_wastage(ctx, store_id, input)
+    }
+}
+
+pub struct OpenVialWastageService {}
+impl OpenVialWastageServiceTrait for OpenVialWastageService {}
@@ -0,0 +1,29 @@
+use repository::{
+    EqualFilter, OpenVialWastageFilter, OpenVialWastageRepository, OpenVialWastageRow,
+    PaginationOption,
+};
+
+use crate::{
+    get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+pub fn get_open_vial_wastage(
+    ctx: &ServiceContext,
+    store_id: &str,
+    pagination: Option<PaginationOption>,
+    filter: Option<OpenVialWastageFilter>,
+) -> Result<ListResult<OpenVialWastageRow>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let filter = filter
+        .unwrap_or_default()
+        .store_id(EqualFilter::equal_to(store_id));
+    let repository = OpenVialWastageRepository::new(&ctx.connection);
+
+    Ok(ListResult {
+        rows: repository.query(pagination, Some(filter.clone()))?,
+        count: i64_to_u32(repository.count(Some(filter))?),
+    })
+}
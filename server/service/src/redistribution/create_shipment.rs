@@ -0,0 +1,185 @@
+use repository::{Invoice, RepositoryError, StockLineRowRepository};
+use util::uuid::uuid;
+
+use crate::{
+    invoice::{
+        outbound_shipment::{
+            insert_outbound_shipment, InsertOutboundShipment, InsertOutboundShipmentError,
+        },
+        query::get_invoice,
+    },
+    invoice_line::outbound_shipment_line::{
+        insert_outbound_shipment_line, InsertOutboundShipmentLine, InsertOutboundShipmentLineError,
+    },
+    service_provider::ServiceContext,
+};
+
+/// Redistribution suggestion to turn into a draft outbound shipment
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct CreateRedistributionShipment {
+    pub id: String,
+    pub stock_line_id: String,
+    /// Name of the receiving store
+    pub other_party_id: String,
+    pub number_of_packs: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CreateRedistributionShipmentError {
+    StockLineDoesNotExist,
+    NotThisStoreStockLine,
+    InsertShipmentError(InsertOutboundShipmentError),
+    InsertLineError(InsertOutboundShipmentLineError),
+    CreatedInvoiceDoesNotExist,
+    DatabaseError(RepositoryError),
+}
+
+type OutError = CreateRedistributionShipmentError;
+
+/// Creates a new outbound shipment to the receiving store with a line for the stock line
+pub fn create_redistribution_shipment(
+    ctx: &ServiceContext,
+    store_id: &str,
+    user_id: &str,
+    input: CreateRedistributionShipment,
+) -> Result<Invoice, OutError> {
+    let invoice = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stock_line = StockLineRowRepository::new(connection)
+                .find_many_by_ids(&[input.stock_line_id.clone()])?
+                .pop()
+                .ok_or(OutError::StockLineDoesNotExist)?;
+            if stock_line.store_id != store_id {
+                return Err(OutError::NotThisStoreStockLine);
+            }
+
+            insert_outbound_shipment(
+                ctx,
+                store_id,
+                user_id,
+                InsertOutboundShipment {
+                    id: input.id.clone(),
+                    other_party_id: input.other_party_id.clone(),
+                    comment: Some("Redistribution of short-dated stock".to_string()),
+                    ..Default::default()
+                },
+            )
+            .map_err(OutError::InsertShipmentError)?;
+
+            insert_outbound_shipment_line(
+                ctx,
+                store_id,
+                InsertOutboundShipmentLine {
+                    id: uuid(),
+                    invoice_id: input.id.clone(),
+                    item_id: stock_line.item_id,
+                    stock_line_id: stock_line.id,
+                    number_of_packs: input.number_of_packs,
+                    number_of_doses: None,
+                    total_before_tax: 0.0,
+                    total_after_tax: 0.0,
+                    tax: None,
+                    stock_line_reservation_id: None,
+                },
+            )
+            .map_err(OutError::InsertLineError)?;
+
+            get_invoice(ctx, Some(store_id), &input.id)?.ok_or(OutError::CreatedInvoiceDoesNotExist)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(invoice)
+}
+
+impl From<RepositoryError> for CreateRedistributionShipmentError {
+    fn from(error: RepositoryError) -> Self {
+        CreateRedistributionShipmentError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_name_store_b, mock_stock_line_a, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        InvoiceLineRowRepository, StockLineRowRepository,
+    };
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::{CreateRedistributionShipment, CreateRedistributionShipmentError as ServiceError};
+
+    #[actix_rt::test]
+    async fn create_redistribution_shipment() {
+        let (_, connection, connection_manager, _) =
+            setup_all("create_redistribution_shipment", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.redistribution_service;
+
+        // StockLineDoesNotExist
+        assert_eq!(
+            service.create_redistribution_shipment(
+                &context,
+                &mock_store_a().id,
+                "n/a",
+                CreateRedistributionShipment {
+                    id: "redistribution".to_string(),
+                    stock_line_id: "invalid".to_string(),
+                    other_party_id: mock_name_store_b().id,
+                    number_of_packs: 1,
+                },
+            ),
+            Err(ServiceError::StockLineDoesNotExist)
+        );
+
+        // NotThisStoreStockLine
+        assert_eq!(
+            service.create_redistribution_shipment(
+                &context,
+                "store_b",
+                "n/a",
+                CreateRedistributionShipment {
+                    id: "redistribution".to_string(),
+                    stock_line_id: mock_stock_line_a().id,
+                    other_party_id: mock_name_store_b().id,
+                    number_of_packs: 1,
+                },
+            ),
+            Err(ServiceError::NotThisStoreStockLine)
+        );
+
+        // Success
+        let stock_line = mock_stock_line_a();
+        let invoice = service
+            .create_redistribution_shipment(
+                &context,
+                &mock_store_a().id,
+                "n/a",
+                CreateRedistributionShipment {
+                    id: "redistribution".to_string(),
+                    stock_line_id: stock_line.id.clone(),
+                    other_party_id: mock_name_store_b().id,
+                    number_of_packs: 2,
+                },
+            )
+            .unwrap();
+        assert_eq!(invoice.invoice_row.id, "redistribution");
+        assert_eq!(invoice.invoice_row.name_id, mock_name_store_b().id);
+
+        let lines = InvoiceLineRowRepository::new(&connection)
+            .find_many_by_invoice_id("redistribution")
+            .unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].stock_line_id, Some(stock_line.id.clone()));
+        assert_eq!(lines[0].number_of_packs, 2);
+        assert_eq!(
+            StockLineRowRepository::new(&connection)
+                .find_one_by_id(&stock_line.id)
+                .unwrap()
+                .available_number_of_packs,
+            stock_line.available_number_of_packs - 2
+        );
+    }
+}
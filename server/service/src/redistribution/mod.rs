@@ -0,0 +1,41 @@
+use self::{
+    create_shipment::{
+        create_redistribution_shipment, CreateRedistributionShipment,
+        CreateRedistributionShipmentError,
+    },
+    suggestion::{get_redistribution_suggestions, RedistributionSuggestion},
+};
+
+use crate::service_provider::ServiceContext;
+use chrono::NaiveDate;
+use repository::{Invoice, RepositoryError};
+
+pub mod create_shipment;
+pub mod suggestion;
+
+pub trait RedistributionServiceTrait: Sync + Send {
+    /// Suggested transfers of the store's stock that will expire before the store can use it, as
+    /// of the given date
+    fn get_redistribution_suggestions(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        date: NaiveDate,
+        amc_lookback_months: Option<u32>,
+    ) -> Result<Vec<RedistributionSuggestion>, RepositoryError> {
+        get_redistribution_suggestions(ctx, store_id, date, amc_lookback_months)
+    }
+
+    fn create_redistribution_shipment(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        user_id: &str,
+        input: CreateRedistributionShipment,
+    ) -> Result<Invoice, CreateRedistributionShipmentError> {
+        create_redistribution_shipment(ctx, store_id, user_id, input)
+    }
+}
+
+pub struct RedistributionService {}
+impl RedistributionServiceTrait for RedistributionService {}
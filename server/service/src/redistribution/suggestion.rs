@@ -0,0 +1,362 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use repository::{
+    EqualFilter, KeyValueStoreRepository, KeyValueType, NameFilter, NameRepository,
+    RepositoryError, StockLineFilter, StockLineRepository, StockLineRow, StorageConnection,
+    SyncSiteRowRepository,
+};
+use util::constants::NUMBER_OF_DAYS_IN_A_MONTH;
+
+use crate::{
+    i64_to_u32,
+    item_stats::{get_item_stats, ItemStats, ItemStatsFilter},
+    service_provider::ServiceContext,
+    stock_line_reservation::consume::get_held_packs_by_stock_line,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedistributionSuggestion {
+    pub stock_line_id: String,
+    pub item_id: String,
+    pub batch: Option<String>,
+    pub expiry_date: NaiveDate,
+    pub pack_size: i32,
+    /// Units of the stock line the store is not expected to use before it expires
+    pub at_risk_quantity: u32,
+    pub to_store_id: String,
+    /// Name of the receiving store, i.e. the other party of the outbound shipment
+    pub to_name_id: String,
+    /// Packs the receiving store is expected to use before the stock line expires
+    pub number_of_packs: u32,
+}
+
+/// Stock of another store that can receive redistributed stock
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedistributionDestination {
+    pub store_id: String,
+    pub name_id: String,
+    pub item_stats: Vec<ItemStats>,
+}
+
+fn daily_consumption(item_stats: Option<&ItemStats>) -> f64 {
+    item_stats
+        .map(|stats| stats.average_monthly_consumption / NUMBER_OF_DAYS_IN_A_MONTH)
+        .unwrap_or(0.0)
+}
+
+/// Units of each stock line (by id) that are not expected to be used before they expire.
+///
+/// Stock lines of an item are used first expiry first out at the store's average daily
+/// consumption.
+pub fn at_risk_quantities(
+    date: &NaiveDate,
+    stock_lines: &[StockLineRow],
+    item_stats: &[ItemStats],
+) -> HashMap<String, i64> {
+    let mut stock_lines: Vec<&StockLineRow> = stock_lines.iter().collect();
+    stock_lines.sort_by(|a, b| a.expiry_date.cmp(&b.expiry_date));
+
+    // units expected to be used by earlier expiring stock lines, per item
+    let mut used_map: HashMap<&str, f64> = HashMap::new();
+    let mut result = HashMap::new();
+    for stock_line in stock_lines {
+        let expiry_date = match &stock_line.expiry_date {
+            Some(expiry_date) => expiry_date,
+            None => continue,
+        };
+        let daily_consumption = daily_consumption(
+            item_stats
+                .iter()
+                .find(|stats| stats.item_id == stock_line.item_id),
+        );
+        let used = used_map.entry(stock_line.item_id.as_str()).or_insert(0.0);
+
+        let quantity = stock_line.total_number_of_packs as f64 * stock_line.pack_size as f64;
+        let usable = daily_consumption * (*expiry_date - *date).num_days() as f64 - *used;
+        let usable = usable.max(0.0).min(quantity);
+        *used += usable;
+
+        result.insert(stock_line.id.clone(), (quantity - usable).floor() as i64);
+    }
+    result
+}
+
+/// Suggests transfers of the at risk stock lines to the destinations that are expected to use
+/// them before they expire, on top of their current stock.
+///
+/// Earlier expiring stock lines are assigned first, each to the destinations with the most
+/// remaining demand.
+pub fn generate_redistribution_suggestions(
+    date: &NaiveDate,
+    stock_lines: Vec<StockLineRow>,
+    item_stats: &[ItemStats],
+    destinations: &[RedistributionDestination],
+) -> Vec<RedistributionSuggestion> {
+    let at_risk = at_risk_quantities(date, &stock_lines, item_stats);
+
+    let mut stock_lines = stock_lines;
+    stock_lines.sort_by(|a, b| a.expiry_date.cmp(&b.expiry_date));
+
+    // units already suggested per (destination store, item)
+    let mut assigned: HashMap<(String, String), f64> = HashMap::new();
+    let mut result = Vec::new();
+    for stock_line in stock_lines {
+        let (expiry_date, at_risk_quantity) =
+            match (&stock_line.expiry_date, at_risk.get(&stock_line.id)) {
+                (Some(expiry_date), Some(at_risk_quantity)) if *at_risk_quantity > 0 => {
+                    (*expiry_date, *at_risk_quantity)
+                }
+                _ => continue,
+            };
+        let pack_size = stock_line.pack_size.max(1) as f64;
+        let days = (expiry_date - *date).num_days() as f64;
+        let mut remaining_packs = ((at_risk_quantity as f64 / pack_size).floor() as i64)
+            .min(stock_line.available_number_of_packs as i64);
+
+        let mut demands: Vec<(&RedistributionDestination, f64)> = destinations
+            .iter()
+            .map(|destination| {
+                let stats = destination
+                    .item_stats
+                    .iter()
+                    .find(|stats| stats.item_id == stock_line.item_id);
+                let demand = daily_consumption(stats) * days
+                    - stats.map_or(0.0, |stats| stats.available_stock_on_hand as f64)
+                    - assigned
+                        .get(&(destination.store_id.clone(), stock_line.item_id.clone()))
+                        .unwrap_or(&0.0);
+                (destination, demand)
+            })
+            .filter(|(_, demand)| *demand >= pack_size)
+            .collect();
+        demands.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap());
+
+        for (destination, demand) in demands {
+            if remaining_packs <= 0 {
+                break;
+            }
+            let packs = ((demand / pack_size).floor() as i64).min(remaining_packs);
+            remaining_packs -= packs;
+            *assigned
+                .entry((destination.store_id.clone(), stock_line.item_id.clone()))
+                .or_insert(0.0) += packs as f64 * pack_size;
+
+            result.push(RedistributionSuggestion {
+                stock_line_id: stock_line.id.clone(),
+                item_id: stock_line.item_id.clone(),
+                batch: stock_line.batch.clone(),
+                expiry_date,
+                pack_size: stock_line.pack_size,
+                at_risk_quantity: i64_to_u32(at_risk_quantity),
+                to_store_id: destination.store_id.clone(),
+                to_name_id: destination.name_id.clone(),
+                number_of_packs: i64_to_u32(packs),
+            });
+        }
+    }
+    result
+}
+
+/// Sites whose store data (stock and consumption) is held on this site, None if the data of all
+/// sites is held.
+///
+/// A remote site only holds the data of its own stores. A central server holds the data of the
+/// remote sites that push to it, or of all sites if it doesn't sync with another server itself.
+fn sites_with_store_data(
+    connection: &StorageConnection,
+) -> Result<Option<HashSet<i32>>, RepositoryError> {
+    let site_id =
+        match KeyValueStoreRepository::new(connection).get_i32(KeyValueType::SettingsSyncSiteId)? {
+            Some(site_id) => site_id,
+            None => return Ok(None),
+        };
+    let mut site_ids: HashSet<i32> = SyncSiteRowRepository::new(connection)
+        .find_all()?
+        .into_iter()
+        .filter(|site| site.initialised_datetime.is_some())
+        .map(|site| site.id)
+        .collect();
+    site_ids.insert(site_id);
+    Ok(Some(site_ids))
+}
+
+/// Suggested transfers of the store's stock that is expected to expire before it's used, to
+/// stores (visible customers of the store) that are expected to use it in time.
+///
+/// Average monthly consumption and stock on hand of the other stores are taken from the data
+/// available on this site, stores of sites whose data isn't held here are skipped (see
+/// [sites_with_store_data]). I.e. a remote site only gets suggestions for its own stores, the
+/// central server gets suggestions for all stores.
+pub fn get_redistribution_suggestions(
+    ctx: &ServiceContext,
+    store_id: &str,
+    date: NaiveDate,
+    amc_lookback_months: Option<u32>,
+) -> Result<Vec<RedistributionSuggestion>, RepositoryError> {
+    let stock_lines: Vec<StockLineRow> = StockLineRepository::new(&ctx.connection)
+        .query_by_filter(StockLineFilter::new().store_id(EqualFilter::equal_to(store_id)))?
+        .into_iter()
+        .map(|stock_line| stock_line.stock_line_row)
+        .filter(|row| {
+            !row.on_hold
+                && row.total_number_of_packs > 0
+                && row
+                    .expiry_date
+                    .map_or(false, |expiry_date| expiry_date > date)
+        })
+        .collect();
+    if stock_lines.is_empty() {
+        return Ok(Vec::new());
+    }
+    // Held packs can only be issued to the reserved customer or campaign
+    let stock_line_ids: Vec<String> = stock_lines.iter().map(|row| row.id.clone()).collect();
+    let held_packs = get_held_packs_by_stock_line(&ctx.connection, &stock_line_ids, date)?;
+    let stock_lines: Vec<StockLineRow> = stock_lines
+        .into_iter()
+        .map(|row| StockLineRow {
+            available_number_of_packs: row.available_number_of_packs
+                - held_packs.get(&row.id).copied().unwrap_or_default(),
+            ..row
+        })
+        .collect();
+
+    let mut item_ids: Vec<String> = stock_lines.iter().map(|row| row.item_id.clone()).collect();
+    item_ids.sort();
+    item_ids.dedup();
+    let item_stats_filter =
+        || Some(ItemStatsFilter::new().item_id(EqualFilter::equal_any(item_ids.clone())));
+
+    let item_stats = get_item_stats(ctx, store_id, amc_lookback_months, item_stats_filter())?;
+
+    let names = NameRepository::new(&ctx.connection).query_by_filter(
+        store_id,
+        NameFilter::new()
+            .is_customer(true)
+            .is_store(true)
+            .is_visible(true),
+    )?;
+    let site_ids = sites_with_store_data(&ctx.connection)?;
+    let mut destinations = Vec::new();
+    for name in names {
+        let store_row = match &name.store_row {
+            Some(store_row) if store_row.id != store_id => store_row,
+            _ => continue,
+        };
+        // Without the store's data it would look like the store has no consumption
+        if let Some(site_ids) = &site_ids {
+            if !site_ids.contains(&store_row.site_id) {
+                continue;
+            }
+        }
+        let destination_store_id = store_row.id.clone();
+        destinations.push(RedistributionDestination {
+            item_stats: get_item_stats(
+                ctx,
+                &destination_store_id,
+                amc_lookback_months,
+                item_stats_filter(),
+            )?,
+            store_id: destination_store_id,
+            name_id: name.name_row.id,
+        });
+    }
+
+    Ok(generate_redistribution_suggestions(
+        &date,
+        stock_lines,
+        &item_stats,
+        &destinations,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::StockLineRow;
+    use util::inline_init;
+
+    use crate::item_stats::ItemStats;
+
+    use super::{
+        at_risk_quantities, generate_redistribution_suggestions, RedistributionDestination,
+    };
+
+    fn stock_line(id: &str, expiry_date: NaiveDate, total_number_of_packs: i32) -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = id.to_string();
+            r.item_id = "item".to_string();
+            r.pack_size = 10;
+            r.expiry_date = Some(expiry_date);
+            r.total_number_of_packs = total_number_of_packs;
+            r.available_number_of_packs = total_number_of_packs;
+        })
+    }
+
+    fn item_stats(average_monthly_consumption: f64, available_stock_on_hand: u32) -> ItemStats {
+        ItemStats {
+            item_id: "item".to_string(),
+            average_monthly_consumption,
+            available_stock_on_hand,
+            open_vial_wastage_rate: 0.0,
+        }
+    }
+
+    fn destination(id: &str, item_stats: ItemStats) -> RedistributionDestination {
+        RedistributionDestination {
+            store_id: id.to_string(),
+            name_id: format!("{}_name", id),
+            item_stats: vec![item_stats],
+        }
+    }
+
+    #[test]
+    fn redistribution_suggestions() {
+        let date = NaiveDate::from_ymd(2022, 1, 1);
+        // 30 units used per month, i.e. one per day
+        let stats = vec![item_stats(30.0, 0)];
+        let stock_lines = vec![
+            // 40 units expiring in 30 days: 10 at risk
+            stock_line("first", NaiveDate::from_ymd(2022, 1, 31), 4),
+            // 100 units expiring in 90 days: 60 used, 40 at risk
+            stock_line("second", NaiveDate::from_ymd(2022, 4, 1), 10),
+            // 100 units expiring in a year: all used
+            stock_line("third", NaiveDate::from_ymd(2023, 1, 1), 10),
+        ];
+
+        let at_risk = at_risk_quantities(&date, &stock_lines, &stats);
+        assert_eq!(at_risk.get("first"), Some(&10));
+        assert_eq!(at_risk.get("second"), Some(&40));
+        assert_eq!(at_risk.get("third"), Some(&0));
+
+        let destinations = vec![
+            // uses 60 units in 30 days, has 45 in stock: 15 units demand for the first line
+            destination("store_b", item_stats(60.0, 45)),
+            // no consumption
+            destination("store_c", item_stats(0.0, 0)),
+            // uses 90 units in 90 days, has 70 in stock: 20 units demand for the second line
+            destination("store_d", item_stats(30.0, 70)),
+        ];
+        let suggestions =
+            generate_redistribution_suggestions(&date, stock_lines, &stats, &destinations);
+        let summary: Vec<(&str, &str, u32, u32)> = suggestions
+            .iter()
+            .map(|s| {
+                (
+                    s.stock_line_id.as_str(),
+                    s.to_store_id.as_str(),
+                    s.at_risk_quantity,
+                    s.number_of_packs,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("first", "store_b", 10, 1),
+                // store_b: 180 - 45 - 10 = 125 units, store_d: 90 - 70 = 20 units
+                ("second", "store_b", 40, 4),
+            ]
+        );
+    }
+}
@@ -14,6 +14,10 @@ pub fn get_default_gql_query(query: DefaultQuery) -> GraphQlQuery {
             query: REQUISITION_QUERY.to_string(),
             variables: None,
         },
+        DefaultQuery::StockOnHand => GraphQlQuery {
+            query: STOCK_ON_HAND_QUERY.to_string(),
+            variables: None,
+        },
     }
 }
 
@@ -331,3 +335,41 @@ const REQUISITION_QUERY: &str = r#"query RequisitionQuery($storeId: String, $dat
     }
   }
 }"#;
+
+const STOCK_ON_HAND_QUERY: &str = r#"query StockOnHandQuery($storeId: String, $dataId: DateTime) {
+  historicalStockOnHand(storeId: $storeId, datetime: $dataId, groupByBatch: true) {
+    itemId
+    batch
+    totalStockOnHand
+    item {
+      code
+      name
+      unitName
+    }
+  }
+  store(id: $storeId) {
+    ... on StoreNode {
+      id
+      name(storeId: $storeId) {
+        address1
+        address2
+        chargeCode
+        code
+        comment
+        country
+        email
+        name
+        phone
+        website
+      }
+      code
+      storeName
+    }
+    ... on NodeError {
+      __typename
+      error {
+        description
+      }
+    }
+  }
+}"#;
@@ -35,6 +35,8 @@ pub enum DefaultQuery {
     Invoice,
     Stocktake,
     Requisition,
+    /// Historical stock on hand, the data id is the datetime of the stock on hand
+    StockOnHand,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -83,6 +83,9 @@ pub fn generate_invoice_lines(
             sell_price_per_pack: 0.0,
             cost_price_per_pack: 0.0,
             stock_line_id: None,
+            donor_id: None,
+            manufacturer_id: None,
+            vvm_status_id: None,
         });
     }
 
@@ -1,4 +1,5 @@
 use crate::{
+    doses::doses_to_units,
     requisition::common::check_requisition_exists,
     requisition_line::{common::check_requisition_line_exists, query::get_requisition_line},
     service_provider::ServiceContext,
@@ -6,8 +7,8 @@ use crate::{
 
 use repository::{
     requisition_row::{RequisitionRowStatus, RequisitionRowType},
-    RepositoryError, RequisitionLine, RequisitionLineRow, RequisitionLineRowRepository,
-    StorageConnection,
+    ItemRow, ItemRowRepository, RepositoryError, RequisitionLine, RequisitionLineRow,
+    RequisitionLineRowRepository, StorageConnection,
 };
 use util::inline_edit;
 
@@ -15,6 +16,8 @@ use util::inline_edit;
 pub struct UpdateRequestRequisitionLine {
     pub id: String,
     pub requested_quantity: Option<u32>,
+    /// Alternative to `requested_quantity` for items counted in doses, rounded up to whole units
+    pub requested_quantity_in_doses: Option<u32>,
     pub comment: Option<String>,
 }
 
@@ -27,6 +30,8 @@ pub enum UpdateRequestRequisitionLineError {
     NotARequestRequisition,
     UpdatedRequisitionLineDoesNotExist,
     RequisitionDoesNotExist,
+    CannotProvideQuantityAndDoses,
+    TooManyDoses,
     DatabaseError(RepositoryError),
 }
 
@@ -40,8 +45,8 @@ pub fn update_request_requisition_line(
     let requisition_line = ctx
         .connection
         .transaction_sync(|connection| {
-            let requisition_row = validate(connection, store_id, &input)?;
-            let updated_requisition_line_row = generate(requisition_row, input);
+            let (requisition_row, item_row) = validate(connection, store_id, &input)?;
+            let updated_requisition_line_row = generate(requisition_row, item_row, input);
 
             RequisitionLineRowRepository::new(&connection)
                 .upsert_one(&updated_requisition_line_row)?;
@@ -58,7 +63,11 @@ fn validate(
     connection: &StorageConnection,
     store_id: &str,
     input: &UpdateRequestRequisitionLine,
-) -> Result<RequisitionLineRow, OutError> {
+) -> Result<(RequisitionLineRow, ItemRow), OutError> {
+    if input.requested_quantity.is_some() && input.requested_quantity_in_doses.is_some() {
+        return Err(OutError::CannotProvideQuantityAndDoses);
+    }
+
     let requisition_line_row = check_requisition_line_exists(connection, &input.id)?
         .ok_or(OutError::RequisitionLineDoesNotExist)?
         .requisition_line_row;
@@ -79,17 +88,32 @@ fn validate(
         return Err(OutError::NotARequestRequisition);
     }
 
-    Ok(requisition_line_row)
+    let item_row = ItemRowRepository::new(connection)
+        .find_one_by_id(&requisition_line_row.item_id)?
+        .ok_or(RepositoryError::NotFound)?;
+
+    if let Some(doses) = input.requested_quantity_in_doses {
+        if doses_to_units(doses, &item_row).is_none() {
+            return Err(OutError::TooManyDoses);
+        }
+    }
+
+    Ok((requisition_line_row, item_row))
 }
 
 fn generate(
     existing: RequisitionLineRow,
+    item_row: ItemRow,
     UpdateRequestRequisitionLine {
         id: _,
         requested_quantity: updated_requested_quantity,
+        requested_quantity_in_doses: updated_requested_quantity_in_doses,
         comment: updated_comment,
     }: UpdateRequestRequisitionLine,
 ) -> RequisitionLineRow {
+    let updated_requested_quantity = updated_requested_quantity
+        .or(updated_requested_quantity_in_doses.and_then(|doses| doses_to_units(doses, &item_row)));
+
     inline_edit(&existing, |mut u| {
         u.requested_quantity =
             updated_requested_quantity.unwrap_or(u.requested_quantity as u32) as i32;
@@ -113,7 +137,7 @@ mod test {
             MockDataInserts,
         },
         test_db::setup_all,
-        RequisitionLineRowRepository,
+        ItemRowRepository, RequisitionLineRowRepository,
     };
     use util::{inline_edit, inline_init};
 
@@ -185,6 +209,22 @@ mod test {
             ),
             Err(ServiceError::NotARequestRequisition)
         );
+
+        // CannotProvideQuantityAndDoses
+        assert_eq!(
+            service.update_request_requisition_line(
+                &context,
+                "store_a",
+                inline_init(|r: &mut UpdateRequestRequisitionLine| {
+                    r.id = mock_request_draft_requisition_calculation_test().lines[0]
+                        .id
+                        .clone();
+                    r.requested_quantity = Some(1);
+                    r.requested_quantity_in_doses = Some(1);
+                }),
+            ),
+            Err(ServiceError::CannotProvideQuantityAndDoses)
+        );
     }
 
     #[actix_rt::test]
@@ -208,6 +248,7 @@ mod test {
                 UpdateRequestRequisitionLine {
                     id: test_line.id.clone(),
                     requested_quantity: Some(99),
+                    requested_quantity_in_doses: None,
                     comment: Some("comment".to_string()),
                 },
             )
@@ -226,5 +267,36 @@ mod test {
                 u
             })
         );
+
+        // Requested in doses, rounded up to whole vials
+        let item_repository = ItemRowRepository::new(&connection);
+        let item = item_repository
+            .find_one_by_id(&test_line.item_id)
+            .unwrap()
+            .unwrap();
+        item_repository
+            .upsert_one(&inline_edit(&item, |mut u| {
+                u.doses = 10;
+                u.is_vaccine = true;
+                u
+            }))
+            .unwrap();
+
+        service
+            .update_request_requisition_line(
+                &context,
+                "store_a",
+                inline_init(|r: &mut UpdateRequestRequisitionLine| {
+                    r.id = test_line.id.clone();
+                    r.requested_quantity_in_doses = Some(25);
+                }),
+            )
+            .unwrap();
+
+        let line = RequisitionLineRowRepository::new(&connection)
+            .find_one_by_id(&test_line.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(line.requested_quantity, 3);
     }
 }
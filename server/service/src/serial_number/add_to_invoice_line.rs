@@ -0,0 +1,470 @@
+use std::collections::HashSet;
+
+use repository::{
+    InvoiceLineRow, InvoiceLineRowType, InvoiceLineSerialNumberRow,
+    InvoiceLineSerialNumberRowRepository, InvoiceRow, InvoiceRowType, ItemRowRepository,
+    RepositoryError, SerialNumberRowRepository, SerialNumberStatus, StorageConnection,
+};
+use util::uuid::uuid;
+
+use super::move_with_invoice::{
+    check_serial_numbers_moved, move_invoice_line_serial_numbers, MoveSerialNumbersError,
+};
+use crate::{
+    invoice::{check_invoice_exists_option, check_invoice_is_editable, check_store},
+    invoice_line::validate::check_line_exists_option,
+    service_provider::ServiceContext,
+};
+
+/// Records the serial numbers of the units received or issued by an inbound or outbound shipment
+/// line. The serial numbers are moved when the inbound shipment is delivered or the outbound
+/// shipment is picked, or straight away if the shipment already is.
+#[derive(Default, Debug, Clone)]
+pub struct AddInvoiceLineSerialNumbers {
+    pub invoice_line_id: String,
+    pub serial_numbers: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AddInvoiceLineSerialNumbersError {
+    InvoiceLineDoesNotExist,
+    NotThisStoreInvoice,
+    NotAnInboundOrOutboundShipment,
+    /// Unallocated or service line
+    NotAStockLine,
+    CannotEditFinalised,
+    ItemNotSerialTracked,
+    /// Serial number is repeated or already recorded on the line
+    DuplicateSerialNumber(String),
+    /// More serial numbers than units on the line
+    TooManySerialNumbers,
+    SerialNumberDecommissioned(String),
+    /// Serial number is not in store or already recorded on another outbound shipment that hasn't
+    /// been picked
+    SerialNumberNotAvailable(String),
+    DatabaseError(RepositoryError),
+}
+
+type OutError = AddInvoiceLineSerialNumbersError;
+
+/// Returns all serial numbers recorded on the line
+pub fn add_invoice_line_serial_numbers(
+    ctx: &ServiceContext,
+    store_id: &str,
+    input: AddInvoiceLineSerialNumbers,
+) -> Result<Vec<InvoiceLineSerialNumberRow>, OutError> {
+    let serial_numbers = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (line, invoice) = validate(connection, store_id, &input)?;
+            let line_serial_number_rows = generate(&line, &input.serial_numbers);
+
+            let repo = InvoiceLineSerialNumberRowRepository::new(connection);
+            for row in line_serial_number_rows.iter() {
+                repo.insert_one(row)?;
+            }
+            if check_serial_numbers_moved(&invoice) {
+                move_invoice_line_serial_numbers(
+                    connection,
+                    &invoice,
+                    &line,
+                    &input.serial_numbers,
+                )?;
+            }
+
+            let serial_numbers = repo.find_many_by_invoice_line_ids(&[line.id])?;
+            Ok(serial_numbers) as Result<Vec<InvoiceLineSerialNumberRow>, OutError>
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(serial_numbers)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &AddInvoiceLineSerialNumbers,
+) -> Result<(InvoiceLineRow, InvoiceRow), OutError> {
+    let line = check_line_exists_option(connection, &input.invoice_line_id)?
+        .ok_or(OutError::InvoiceLineDoesNotExist)?;
+    let invoice = check_invoice_exists_option(&line.invoice_id, connection)?
+        .ok_or(OutError::InvoiceLineDoesNotExist)?;
+
+    check_store(&invoice, store_id).map_err(|_| OutError::NotThisStoreInvoice)?;
+    let is_inbound = match invoice.r#type {
+        InvoiceRowType::InboundShipment => true,
+        InvoiceRowType::OutboundShipment => false,
+        _ => return Err(OutError::NotAnInboundOrOutboundShipment),
+    };
+    match line.r#type {
+        InvoiceLineRowType::StockIn | InvoiceLineRowType::StockOut => {}
+        _ => return Err(OutError::NotAStockLine),
+    }
+    check_invoice_is_editable(&invoice).map_err(|_| OutError::CannotEditFinalised)?;
+
+    let is_serial_tracked = ItemRowRepository::new(connection)
+        .find_one_by_id(&line.item_id)?
+        .map(|item| item.is_serial_tracked)
+        .unwrap_or(false);
+    if !is_serial_tracked {
+        return Err(OutError::ItemNotSerialTracked);
+    }
+
+    let recorded: HashSet<String> = InvoiceLineSerialNumberRowRepository::new(connection)
+        .find_many_by_invoice_line_ids(&[line.id.clone()])?
+        .into_iter()
+        .map(|row| row.serial_number)
+        .collect();
+
+    let units = (line.number_of_packs * line.pack_size).max(0) as usize;
+    if recorded.len() + input.serial_numbers.len() > units {
+        return Err(OutError::TooManySerialNumbers);
+    }
+
+    let repo = SerialNumberRowRepository::new(connection);
+    let mut seen = HashSet::new();
+    for serial_number in input.serial_numbers.iter() {
+        if !seen.insert(serial_number) || recorded.contains(serial_number) {
+            return Err(OutError::DuplicateSerialNumber(serial_number.clone()));
+        }
+
+        match repo.find_one_by_serial_number(&line.item_id, serial_number)? {
+            Some(row) if row.status == SerialNumberStatus::Decommissioned => {
+                return Err(OutError::SerialNumberDecommissioned(serial_number.clone()))
+            }
+            Some(row)
+                if !is_inbound
+                    && (row.store_id != store_id || row.status != SerialNumberStatus::InStore) =>
+            {
+                return Err(OutError::SerialNumberNotAvailable(serial_number.clone()))
+            }
+            None if !is_inbound => {
+                return Err(OutError::SerialNumberNotAvailable(serial_number.clone()))
+            }
+            _ => {}
+        }
+
+        if !is_inbound && check_recorded_on_unpicked_outbound(connection, &line, serial_number)? {
+            return Err(OutError::SerialNumberNotAvailable(serial_number.clone()));
+        }
+    }
+
+    Ok((line, invoice))
+}
+
+/// Serial number is already recorded on another outbound shipment line of the item, that will
+/// issue the unit once picked
+fn check_recorded_on_unpicked_outbound(
+    connection: &StorageConnection,
+    line: &InvoiceLineRow,
+    serial_number: &str,
+) -> Result<bool, RepositoryError> {
+    let rows = InvoiceLineSerialNumberRowRepository::new(connection)
+        .find_many_by_serial_number(serial_number)?;
+    for row in rows {
+        let other_line = match check_line_exists_option(connection, &row.invoice_line_id)? {
+            Some(other_line) if other_line.item_id == line.item_id => other_line,
+            _ => continue,
+        };
+        let other_invoice = match check_invoice_exists_option(&other_line.invoice_id, connection)? {
+            Some(other_invoice) => other_invoice,
+            None => continue,
+        };
+        if other_invoice.r#type == InvoiceRowType::OutboundShipment
+            && !check_serial_numbers_moved(&other_invoice)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn generate(line: &InvoiceLineRow, serial_numbers: &[String]) -> Vec<InvoiceLineSerialNumberRow> {
+    serial_numbers
+        .iter()
+        .map(|serial_number| InvoiceLineSerialNumberRow {
+            id: uuid(),
+            invoice_line_id: line.id.clone(),
+            serial_number: serial_number.clone(),
+        })
+        .collect()
+}
+
+impl From<RepositoryError> for AddInvoiceLineSerialNumbersError {
+    fn from(error: RepositoryError) -> Self {
+        AddInvoiceLineSerialNumbersError::DatabaseError(error)
+    }
+}
+
+impl From<MoveSerialNumbersError> for AddInvoiceLineSerialNumbersError {
+    fn from(error: MoveSerialNumbersError) -> Self {
+        match error {
+            MoveSerialNumbersError::SerialNumberNotAvailable(serial_number) => {
+                AddInvoiceLineSerialNumbersError::SerialNumberNotAvailable(serial_number)
+            }
+            MoveSerialNumbersError::DatabaseError(error) => {
+                AddInvoiceLineSerialNumbersError::DatabaseError(error)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{
+            mock_inbound_shipment_c_invoice_lines, mock_item_a,
+            mock_outbound_shipment_c_invoice_lines, MockDataInserts,
+        },
+        test_db::setup_all,
+        ItemRowRepository, SerialNumberRow, SerialNumberRowRepository, SerialNumberStatus,
+    };
+    use util::inline_edit;
+
+    use crate::{
+        invoice::{
+            inbound_shipment::{UpdateInboundShipment, UpdateInboundShipmentStatus},
+            outbound_shipment::{UpdateOutboundShipment, UpdateOutboundShipmentStatus},
+        },
+        serial_number::add_to_invoice_line::{
+            AddInvoiceLineSerialNumbers, AddInvoiceLineSerialNumbersError as ServiceError,
+        },
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn add_invoice_line_serial_numbers() {
+        let (_, connection, connection_manager, _) =
+            setup_all("add_invoice_line_serial_numbers", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = &service_provider.serial_number_service;
+        let serial_number_repo = SerialNumberRowRepository::new(&connection);
+
+        // inbound_shipment_c_line_a has 3 units of item_a in store_a
+        let inbound_line = mock_inbound_shipment_c_invoice_lines()[0].clone();
+
+        // InvoiceLineDoesNotExist
+        assert_eq!(
+            service.add_invoice_line_serial_numbers(
+                &context,
+                "store_a",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: "invalid".to_string(),
+                    serial_numbers: vec!["SN1".to_string()],
+                },
+            ),
+            Err(ServiceError::InvoiceLineDoesNotExist)
+        );
+
+        // NotThisStoreInvoice
+        assert_eq!(
+            service.add_invoice_line_serial_numbers(
+                &context,
+                "store_b",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: inbound_line.id.clone(),
+                    serial_numbers: vec!["SN1".to_string()],
+                },
+            ),
+            Err(ServiceError::NotThisStoreInvoice)
+        );
+
+        // ItemNotSerialTracked
+        assert_eq!(
+            service.add_invoice_line_serial_numbers(
+                &context,
+                "store_a",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: inbound_line.id.clone(),
+                    serial_numbers: vec!["SN1".to_string()],
+                },
+            ),
+            Err(ServiceError::ItemNotSerialTracked)
+        );
+        ItemRowRepository::new(&connection)
+            .upsert_one(&inline_edit(&mock_item_a(), |mut u| {
+                u.is_serial_tracked = true;
+                u
+            }))
+            .unwrap();
+
+        // DuplicateSerialNumber
+        assert_eq!(
+            service.add_invoice_line_serial_numbers(
+                &context,
+                "store_a",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: inbound_line.id.clone(),
+                    serial_numbers: vec!["SN1".to_string(), "SN1".to_string()],
+                },
+            ),
+            Err(ServiceError::DuplicateSerialNumber("SN1".to_string()))
+        );
+
+        // TooManySerialNumbers
+        assert_eq!(
+            service.add_invoice_line_serial_numbers(
+                &context,
+                "store_a",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: inbound_line.id.clone(),
+                    serial_numbers: vec!["SN1", "SN2", "SN3", "SN4"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect(),
+                },
+            ),
+            Err(ServiceError::TooManySerialNumbers)
+        );
+
+        // Success: inbound, the units haven't been received yet
+        let result = service
+            .add_invoice_line_serial_numbers(
+                &context,
+                "store_a",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: inbound_line.id.clone(),
+                    serial_numbers: vec!["SN1".to_string(), "SN2".to_string()],
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            result
+                .iter()
+                .map(|row| row.serial_number.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["SN1", "SN2"]
+        );
+        assert_eq!(
+            serial_number_repo
+                .find_one_by_serial_number("item_a", "SN1")
+                .unwrap(),
+            None
+        );
+
+        // DuplicateSerialNumber: already recorded on the line
+        assert_eq!(
+            service.add_invoice_line_serial_numbers(
+                &context,
+                "store_a",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: inbound_line.id.clone(),
+                    serial_numbers: vec!["SN2".to_string()],
+                },
+            ),
+            Err(ServiceError::DuplicateSerialNumber("SN2".to_string()))
+        );
+
+        // Success: delivering the inbound shipment receives the units
+        service_provider
+            .invoice_service
+            .update_inbound_shipment(
+                &context,
+                "store_a",
+                "n/a",
+                UpdateInboundShipment {
+                    id: inbound_line.invoice_id.clone(),
+                    status: Some(UpdateInboundShipmentStatus::Delivered),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        for serial_number in ["SN1", "SN2"] {
+            let row = serial_number_repo
+                .find_one_by_serial_number("item_a", serial_number)
+                .unwrap()
+                .unwrap();
+            assert_eq!(row.store_id, "store_a");
+            assert_eq!(row.status, SerialNumberStatus::InStore);
+
+            let history = service
+                .get_serial_number_history(&context, "store_a", &row.id)
+                .unwrap();
+            assert_eq!(history.len(), 1);
+            assert_eq!(history[0].invoice_line_id, Some(inbound_line.id.clone()));
+        }
+
+        // Success: the last unit is received straight away once delivered
+        service
+            .add_invoice_line_serial_numbers(
+                &context,
+                "store_a",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: inbound_line.id.clone(),
+                    serial_numbers: vec!["SN4".to_string()],
+                },
+            )
+            .unwrap();
+        let row = serial_number_repo
+            .find_one_by_serial_number("item_a", "SN4")
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.status, SerialNumberStatus::InStore);
+
+        // outbound_shipment_c_line_a issues item_a from store_c to name_store_a
+        let outbound_line = mock_outbound_shipment_c_invoice_lines()[0].clone();
+        serial_number_repo
+            .upsert_one(&SerialNumberRow {
+                id: "serial_number_store_c".to_string(),
+                item_id: "item_a".to_string(),
+                store_id: "store_c".to_string(),
+                serial_number: "SN3".to_string(),
+                status: SerialNumberStatus::InStore,
+                holder_name_id: None,
+                note: None,
+            })
+            .unwrap();
+
+        // SerialNumberNotAvailable: held by another store
+        assert_eq!(
+            service.add_invoice_line_serial_numbers(
+                &context,
+                "store_c",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: outbound_line.id.clone(),
+                    serial_numbers: vec!["SN1".to_string()],
+                },
+            ),
+            Err(ServiceError::SerialNumberNotAvailable("SN1".to_string()))
+        );
+
+        // Success: outbound, the unit stays in store until picked
+        service
+            .add_invoice_line_serial_numbers(
+                &context,
+                "store_c",
+                AddInvoiceLineSerialNumbers {
+                    invoice_line_id: outbound_line.id.clone(),
+                    serial_numbers: vec!["SN3".to_string()],
+                },
+            )
+            .unwrap();
+        let row = serial_number_repo
+            .find_one_by_id("serial_number_store_c")
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.status, SerialNumberStatus::InStore);
+        assert_eq!(row.holder_name_id, None);
+
+        // Success: picking the outbound shipment issues the unit
+        service_provider
+            .invoice_service
+            .update_outbound_shipment(
+                &context,
+                "store_c",
+                UpdateOutboundShipment {
+                    id: outbound_line.invoice_id.clone(),
+                    status: Some(UpdateOutboundShipmentStatus::Picked),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let row = serial_number_repo
+            .find_one_by_id("serial_number_store_c")
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.status, SerialNumberStatus::Issued);
+        assert_eq!(row.holder_name_id, Some("name_store_a".to_string()));
+    }
+}
@@ -0,0 +1,65 @@
+use self::{
+    add_to_invoice_line::{
+        add_invoice_line_serial_numbers, AddInvoiceLineSerialNumbers,
+        AddInvoiceLineSerialNumbersError,
+    },
+    query::{get_serial_number_history, get_serial_numbers},
+    update::{update_serial_number, UpdateSerialNumber, UpdateSerialNumberError},
+};
+
+use super::{ListError, ListResult};
+use crate::{service_provider::ServiceContext, SingleRecordError};
+use repository::{
+    InvoiceLineSerialNumberRow, PaginationOption, SerialNumber, SerialNumberFilter,
+    SerialNumberMovementRow, SerialNumberSort,
+};
+
+pub mod add_to_invoice_line;
+pub mod move_with_invoice;
+pub mod query;
+pub mod remove_from_invoice_line;
+pub mod update;
+
+pub trait SerialNumberServiceTrait: Sync + Send {
+    fn get_serial_numbers(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        pagination: Option<PaginationOption>,
+        filter: Option<SerialNumberFilter>,
+        sort: Option<SerialNumberSort>,
+    ) -> Result<ListResult<SerialNumber>, ListError> {
+        get_serial_numbers(ctx, store_id, pagination, filter, sort)
+    }
+
+    /// Asset history of a serial number, oldest movement first
+    fn get_serial_number_history(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        id: &str,
+    ) -> Result<Vec<SerialNumberMovementRow>, SingleRecordError> {
+        get_serial_number_history(ctx, store_id, id)
+    }
+
+    fn add_invoice_line_serial_numbers(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        input: AddInvoiceLineSerialNumbers,
+    ) -> Result<Vec<InvoiceLineSerialNumberRow>, AddInvoiceLineSerialNumbersError> {
+        add_invoice_line_serial_numbers(ctx, store_id, input)
+    }
+
+    fn update_serial_number(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        input: UpdateSerialNumber,
+    ) -> Result<SerialNumber, UpdateSerialNumberError> {
+        update_serial_number(ctx, store_id, input)
+    }
+}
+
+pub struct SerialNumberService {}
+impl SerialNumberServiceTrait for SerialNumberService {}
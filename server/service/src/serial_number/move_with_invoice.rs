@@ -0,0 +1,130 @@
+use chrono::Utc;
+use repository::{
+    InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineSerialNumberRowRepository, InvoiceRow,
+    InvoiceRowStatus, InvoiceRowType, RepositoryError, SerialNumberMovementRow,
+    SerialNumberMovementRowRepository, SerialNumberRow, SerialNumberRowRepository,
+    SerialNumberStatus, StorageConnection,
+};
+use util::uuid::uuid;
+
+#[derive(Debug, PartialEq)]
+pub enum MoveSerialNumbersError {
+    /// Serial number is decommissioned, or not in store for an outbound shipment
+    SerialNumberNotAvailable(String),
+    DatabaseError(RepositoryError),
+}
+
+/// Serial numbers recorded on the lines of a shipment are moved once the units are received, i.e.
+/// the inbound shipment is delivered, or issued, i.e. the outbound shipment is picked
+pub(crate) fn check_serial_numbers_moved(invoice: &InvoiceRow) -> bool {
+    let moved_status = match invoice.r#type {
+        InvoiceRowType::InboundShipment => InvoiceRowStatus::Delivered,
+        InvoiceRowType::OutboundShipment => InvoiceRowStatus::Picked,
+        _ => return false,
+    };
+    invoice.status.index() >= moved_status.index()
+}
+
+/// Moves the serial numbers recorded on the lines of the shipment, called when the status changes
+/// to the status the units are received or issued in.
+///
+/// Should be called in a transaction.
+pub(crate) fn move_invoice_serial_numbers(
+    connection: &StorageConnection,
+    invoice: &InvoiceRow,
+) -> Result<(), MoveSerialNumbersError> {
+    let lines = InvoiceLineRowRepository::new(connection).find_many_by_invoice_id(&invoice.id)?;
+    let line_ids: Vec<String> = lines.iter().map(|line| line.id.clone()).collect();
+    let line_serial_numbers = InvoiceLineSerialNumberRowRepository::new(connection)
+        .find_many_by_invoice_line_ids(&line_ids)?;
+
+    for line in lines.iter() {
+        let serial_numbers: Vec<String> = line_serial_numbers
+            .iter()
+            .filter(|row| row.invoice_line_id == line.id)
+            .map(|row| row.serial_number.clone())
+            .collect();
+        move_invoice_line_serial_numbers(connection, invoice, line, &serial_numbers)?;
+    }
+    Ok(())
+}
+
+/// Moves the serial numbers of a line to the store (inbound shipment) or the customer (outbound
+/// shipment) and records the movements in the asset history.
+///
+/// Should be called in a transaction.
+pub(crate) fn move_invoice_line_serial_numbers(
+    connection: &StorageConnection,
+    invoice: &InvoiceRow,
+    line: &InvoiceLineRow,
+    serial_numbers: &[String],
+) -> Result<(), MoveSerialNumbersError> {
+    // Received units are held by the store, issued units by the customer
+    let (status, holder_name_id) = match invoice.r#type {
+        InvoiceRowType::OutboundShipment => {
+            (SerialNumberStatus::Issued, Some(invoice.name_id.clone()))
+        }
+        _ => (SerialNumberStatus::InStore, None),
+    };
+    let is_issue = status == SerialNumberStatus::Issued;
+    let datetime = Utc::now().naive_utc();
+    let serial_number_repo = SerialNumberRowRepository::new(connection);
+    let movement_repo = SerialNumberMovementRowRepository::new(connection);
+
+    for serial_number in serial_numbers {
+        let serial_number_row =
+            match serial_number_repo.find_one_by_serial_number(&line.item_id, serial_number)? {
+                Some(row) if row.status == SerialNumberStatus::Decommissioned => {
+                    return Err(MoveSerialNumbersError::SerialNumberNotAvailable(
+                        serial_number.clone(),
+                    ))
+                }
+                // Units might have been issued or sent for repair since they were recorded
+                Some(row)
+                    if is_issue
+                        && (row.store_id != invoice.store_id
+                            || row.status != SerialNumberStatus::InStore) =>
+                {
+                    return Err(MoveSerialNumbersError::SerialNumberNotAvailable(
+                        serial_number.clone(),
+                    ))
+                }
+                Some(row) => row,
+                None if is_issue => {
+                    return Err(MoveSerialNumbersError::SerialNumberNotAvailable(
+                        serial_number.clone(),
+                    ))
+                }
+                None => SerialNumberRow {
+                    id: uuid(),
+                    item_id: line.item_id.clone(),
+                    serial_number: serial_number.clone(),
+                    ..Default::default()
+                },
+            };
+
+        let serial_number_row = SerialNumberRow {
+            store_id: invoice.store_id.clone(),
+            status: status.clone(),
+            holder_name_id: holder_name_id.clone(),
+            ..serial_number_row
+        };
+        serial_number_repo.upsert_one(&serial_number_row)?;
+        movement_repo.insert_one(&SerialNumberMovementRow {
+            id: uuid(),
+            serial_number_id: serial_number_row.id,
+            store_id: invoice.store_id.clone(),
+            invoice_line_id: Some(line.id.clone()),
+            status: status.clone(),
+            holder_name_id: holder_name_id.clone(),
+            datetime,
+        })?;
+    }
+    Ok(())
+}
+
+impl From<RepositoryError> for MoveSerialNumbersError {
+    fn from(error: RepositoryError) -> Self {
+        MoveSerialNumbersError::DatabaseError(error)
+    }
+}
@@ -0,0 +1,63 @@
+use repository::{
+    EqualFilter, PaginationOption, SerialNumber, SerialNumberFilter, SerialNumberMovementRow,
+    SerialNumberMovementRowRepository, SerialNumberRepository, SerialNumberSort, StorageConnection,
+};
+
+use crate::{
+    get_default_pagination, i64_to_u32, service_provider::ServiceContext, ListError, ListResult,
+    SingleRecordError,
+};
+
+pub const MAX_LIMIT: u32 = 1000;
+pub const MIN_LIMIT: u32 = 1;
+
+pub fn get_serial_numbers(
+    ctx: &ServiceContext,
+    store_id: &str,
+    pagination: Option<PaginationOption>,
+    filter: Option<SerialNumberFilter>,
+    sort: Option<SerialNumberSort>,
+) -> Result<ListResult<SerialNumber>, ListError> {
+    let pagination = get_default_pagination(pagination, MAX_LIMIT, MIN_LIMIT)?;
+    let repository = SerialNumberRepository::new(&ctx.connection);
+    let filter = filter
+        .unwrap_or_default()
+        .store_id(EqualFilter::equal_to(store_id));
+
+    Ok(ListResult {
+        rows: repository.query(pagination, Some(filter.clone()), sort)?,
+        count: i64_to_u32(repository.count(Some(filter))?),
+    })
+}
+
+pub fn get_serial_number(
+    connection: &StorageConnection,
+    store_id: &str,
+    id: &str,
+) -> Result<SerialNumber, SingleRecordError> {
+    let mut result = SerialNumberRepository::new(connection).query_by_filter(
+        SerialNumberFilter::new()
+            .id(EqualFilter::equal_to(id))
+            .store_id(EqualFilter::equal_to(store_id)),
+    )?;
+
+    if let Some(record) = result.pop() {
+        Ok(record)
+    } else {
+        Err(SingleRecordError::NotFound(id.to_string()))
+    }
+}
+
+pub fn get_serial_number_history(
+    ctx: &ServiceContext,
+    store_id: &str,
+    id: &str,
+) -> Result<Vec<SerialNumberMovementRow>, SingleRecordError> {
+    // Serial numbers move between stores, only the current holding store can see the history
+    get_serial_number(&ctx.connection, store_id, id)?;
+
+    Ok(
+        SerialNumberMovementRowRepository::new(&ctx.connection)
+            .find_many_by_serial_number_id(id)?,
+    )
+}
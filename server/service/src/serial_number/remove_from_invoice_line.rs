@@ -0,0 +1,90 @@
+use repository::{
+    InvoiceLineRow, InvoiceLineSerialNumberRowRepository, RepositoryError,
+    SerialNumberMovementRowRepository, SerialNumberRowRepository, SerialNumberStatus,
+    StorageConnection,
+};
+
+/// Removes the serial numbers recorded on an updated invoice line when they no longer match the
+/// line, i.e. the item changed or the line has fewer units than recorded serial numbers. The
+/// serial numbers have to be recorded again.
+///
+/// Should be called in a transaction.
+pub(crate) fn reconcile_invoice_line_serial_numbers(
+    connection: &StorageConnection,
+    existing_line: &InvoiceLineRow,
+    updated_line: &InvoiceLineRow,
+) -> Result<(), RepositoryError> {
+    let recorded = InvoiceLineSerialNumberRowRepository::new(connection)
+        .find_many_by_invoice_line_ids(&[updated_line.id.clone()])?;
+    let units = (updated_line.number_of_packs * updated_line.pack_size).max(0) as usize;
+
+    if existing_line.item_id != updated_line.item_id || recorded.len() > units {
+        remove_invoice_line_serial_numbers(connection, &updated_line.id)?;
+    }
+    Ok(())
+}
+
+/// Removes the serial numbers recorded on an invoice line, used when the line is deleted or its
+/// serial numbers no longer match the line.
+///
+/// The movements of the line are deleted and the serial numbers are put back to the status and
+/// holder of their previous movement, i.e. units issued by an outbound line are back in store.
+/// Serial numbers created by a deleted inbound line are removed.
+///
+/// Should be called in a transaction.
+pub(crate) fn remove_invoice_line_serial_numbers(
+    connection: &StorageConnection,
+    invoice_line_id: &str,
+) -> Result<(), RepositoryError> {
+    InvoiceLineSerialNumberRowRepository::new(connection)
+        .delete_by_invoice_line_id(invoice_line_id)?;
+
+    let movement_repo = SerialNumberMovementRowRepository::new(connection);
+    let serial_number_repo = SerialNumberRowRepository::new(connection);
+
+    let line_movements = movement_repo.find_many_by_invoice_line_id(invoice_line_id)?;
+    if line_movements.is_empty() {
+        return Ok(());
+    }
+    movement_repo.delete_by_invoice_line_id(invoice_line_id)?;
+
+    for line_movement in line_movements {
+        let mut serial_number_row =
+            match serial_number_repo.find_one_by_id(&line_movement.serial_number_id)? {
+                Some(serial_number_row) => serial_number_row,
+                None => continue,
+            };
+        let remaining = movement_repo.find_many_by_serial_number_id(&serial_number_row.id)?;
+
+        // Later movements, e.g. a repair, already changed the state of the serial number
+        if remaining
+            .last()
+            .map(|latest| latest.datetime > line_movement.datetime)
+            .unwrap_or(false)
+        {
+            continue;
+        }
+
+        match (remaining.last(), line_movement.status) {
+            (Some(previous), _) => {
+                serial_number_row.store_id = previous.store_id.clone();
+                serial_number_row.status = previous.status.clone();
+                serial_number_row.holder_name_id = previous.holder_name_id.clone();
+            }
+            // Only units in store can be issued
+            (None, SerialNumberStatus::Issued) => {
+                serial_number_row.store_id = line_movement.store_id;
+                serial_number_row.status = SerialNumberStatus::InStore;
+                serial_number_row.holder_name_id = None;
+            }
+            // Created when received by the deleted inbound line
+            (None, _) => {
+                serial_number_repo.delete(&serial_number_row.id)?;
+                continue;
+            }
+        }
+        serial_number_repo.upsert_one(&serial_number_row)?;
+    }
+
+    Ok(())
+}
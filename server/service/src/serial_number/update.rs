@@ -0,0 +1,258 @@
+use chrono::Utc;
+use repository::{
+    RepositoryError, SerialNumber, SerialNumberMovementRow, SerialNumberMovementRowRepository,
+    SerialNumberRow, SerialNumberRowRepository, SerialNumberStatus, StorageConnection,
+};
+use util::uuid::uuid;
+
+use super::query::get_serial_number;
+use crate::{service_provider::ServiceContext, validate::check_name_exists, SingleRecordError};
+
+#[derive(Default, Debug, Clone)]
+pub struct UpdateSerialNumber {
+    pub id: String,
+    pub status: Option<SerialNumberStatus>,
+    pub holder_name_id: Option<String>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateSerialNumberError {
+    SerialNumberDoesNotExist,
+    NotThisStoreSerialNumber,
+    HolderDoesNotExist,
+    UpdatedRecordNotFound,
+    DatabaseError(RepositoryError),
+}
+
+pub fn update_serial_number(
+    ctx: &ServiceContext,
+    store_id: &str,
+    input: UpdateSerialNumber,
+) -> Result<SerialNumber, UpdateSerialNumberError> {
+    let serial_number = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let serial_number_row = validate(connection, store_id, &input)?;
+            let (updated_row, movement_row) = generate(store_id, serial_number_row, input);
+
+            SerialNumberRowRepository::new(connection).upsert_one(&updated_row)?;
+            if let Some(movement_row) = movement_row {
+                SerialNumberMovementRowRepository::new(connection).insert_one(&movement_row)?;
+            }
+
+            get_serial_number(connection, store_id, &updated_row.id)
+                .map_err(UpdateSerialNumberError::from)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(serial_number)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpdateSerialNumber,
+) -> Result<SerialNumberRow, UpdateSerialNumberError> {
+    let serial_number_row =
+        match SerialNumberRowRepository::new(connection).find_one_by_id(&input.id)? {
+            Some(serial_number_row) => serial_number_row,
+            None => return Err(UpdateSerialNumberError::SerialNumberDoesNotExist),
+        };
+
+    if serial_number_row.store_id != store_id {
+        return Err(UpdateSerialNumberError::NotThisStoreSerialNumber);
+    }
+
+    if let Some(holder_name_id) = &input.holder_name_id {
+        if !check_name_exists(connection, holder_name_id)? {
+            return Err(UpdateSerialNumberError::HolderDoesNotExist);
+        }
+    }
+
+    Ok(serial_number_row)
+}
+
+/// Returns the updated row and, if the status or holder changed, the movement to record in the
+/// asset history
+fn generate(
+    store_id: &str,
+    mut serial_number_row: SerialNumberRow,
+    UpdateSerialNumber {
+        id: _,
+        status,
+        holder_name_id,
+        note,
+    }: UpdateSerialNumber,
+) -> (SerialNumberRow, Option<SerialNumberMovementRow>) {
+    let previous = serial_number_row.clone();
+
+    if let Some(status) = status {
+        // Back in the store means nobody else is holding it
+        if status == SerialNumberStatus::InStore && holder_name_id.is_none() {
+            serial_number_row.holder_name_id = None;
+        }
+        serial_number_row.status = status;
+    }
+    if holder_name_id.is_some() {
+        serial_number_row.holder_name_id = holder_name_id;
+    }
+    if note.is_some() {
+        serial_number_row.note = note;
+    }
+
+    let movement_row = if previous.status != serial_number_row.status
+        || previous.holder_name_id != serial_number_row.holder_name_id
+    {
+        Some(SerialNumberMovementRow {
+            id: uuid(),
+            serial_number_id: serial_number_row.id.clone(),
+            store_id: store_id.to_string(),
+            invoice_line_id: None,
+            status: serial_number_row.status.clone(),
+            holder_name_id: serial_number_row.holder_name_id.clone(),
+            datetime: Utc::now().naive_utc(),
+        })
+    } else {
+        None
+    };
+
+    (serial_number_row, movement_row)
+}
+
+impl From<RepositoryError> for UpdateSerialNumberError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateSerialNumberError::DatabaseError(error)
+    }
+}
+
+impl From<SingleRecordError> for UpdateSerialNumberError {
+    fn from(error: SingleRecordError) -> Self {
+        use UpdateSerialNumberError::*;
+        match error {
+            SingleRecordError::DatabaseError(error) => DatabaseError(error),
+            SingleRecordError::NotFound(_) => UpdatedRecordNotFound,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, SerialNumberRow, SerialNumberRowRepository,
+        SerialNumberStatus,
+    };
+
+    use crate::{
+        serial_number::update::{UpdateSerialNumber, UpdateSerialNumberError as ServiceError},
+        service_provider::ServiceProvider,
+    };
+
+    #[actix_rt::test]
+    async fn update_serial_number() {
+        let (_, connection, connection_manager, _) =
+            setup_all("update_serial_number", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.serial_number_service;
+
+        SerialNumberRowRepository::new(&connection)
+            .upsert_one(&SerialNumberRow {
+                id: "serial_number_a".to_string(),
+                item_id: "item_a".to_string(),
+                store_id: "store_a".to_string(),
+                serial_number: "SN1".to_string(),
+                status: SerialNumberStatus::InStore,
+                holder_name_id: None,
+                note: None,
+            })
+            .unwrap();
+
+        // SerialNumberDoesNotExist
+        assert_eq!(
+            service.update_serial_number(
+                &context,
+                "store_a",
+                UpdateSerialNumber {
+                    id: "invalid".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::SerialNumberDoesNotExist)
+        );
+
+        // NotThisStoreSerialNumber
+        assert_eq!(
+            service.update_serial_number(
+                &context,
+                "store_b",
+                UpdateSerialNumber {
+                    id: "serial_number_a".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::NotThisStoreSerialNumber)
+        );
+
+        // HolderDoesNotExist
+        assert_eq!(
+            service.update_serial_number(
+                &context,
+                "store_a",
+                UpdateSerialNumber {
+                    id: "serial_number_a".to_string(),
+                    holder_name_id: Some("invalid".to_string()),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::HolderDoesNotExist)
+        );
+
+        // Success: note only, no history
+        service
+            .update_serial_number(
+                &context,
+                "store_a",
+                UpdateSerialNumber {
+                    id: "serial_number_a".to_string(),
+                    note: Some("Fridge".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let history = service
+            .get_serial_number_history(&context, "store_a", "serial_number_a")
+            .unwrap();
+        assert_eq!(history.len(), 0);
+
+        // Success: sent for repair
+        let result = service
+            .update_serial_number(
+                &context,
+                "store_a",
+                UpdateSerialNumber {
+                    id: "serial_number_a".to_string(),
+                    status: Some(SerialNumberStatus::InRepair),
+                    holder_name_id: Some("name_a".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            result.serial_number_row.status,
+            SerialNumberStatus::InRepair
+        );
+        assert_eq!(
+            result.serial_number_row.holder_name_id,
+            Some("name_a".to_string())
+        );
+        assert_eq!(result.serial_number_row.note, Some("Fridge".to_string()));
+
+        let history = service
+            .get_serial_number_history(&context, "store_a", "serial_number_a")
+            .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].status, SerialNumberStatus::InRepair);
+        assert_eq!(history[0].invoice_line_id, None);
+    }
+}
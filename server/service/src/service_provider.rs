@@ -2,27 +2,46 @@ use repository::{
     Name, NameFilter, NameSort, PaginationOption, RepositoryError, StorageConnection,
     StorageConnectionManager, Store, StoreFilter, StoreSort,
 };
+use tokio::sync::Mutex;
 
 use crate::{
     app_data::{AppDataService, AppDataServiceTrait},
     auth::{AuthService, AuthServiceTrait},
+    cycle_count::{CycleCountService, CycleCountServiceTrait},
     dashboard::{
         invoice_count::{InvoiceCountService, InvoiceCountServiceTrait},
+        stock_expiry_buckets::{StockExpiryBucketService, StockExpiryBucketServiceTrait},
         stock_expiry_count::{StockExpiryCountServiceTrait, StockExpiryServiceCount},
     },
+    donor_stats::{DonorStatsService, DonorStatsServiceTrait},
+    historical_stock::{HistoricalStockService, HistoricalStockServiceTrait},
     invoice::{InvoiceService, InvoiceServiceTrait},
     invoice_line::{InvoiceLineService, InvoiceLineServiceTrait},
+    item_classification::{ItemClassificationService, ItemClassificationServiceTrait},
     item_stats::{ItemStatsService, ItemStatsServiceTrait},
     location::{LocationService, LocationServiceTrait},
     master_list::{MasterListService, MasterListServiceTrait},
     name::get_names,
+    open_vial_wastage::{OpenVialWastageService, OpenVialWastageServiceTrait},
+    redistribution::{RedistributionService, RedistributionServiceTrait},
     report::report_service::{ReportService, ReportServiceTrait},
     requisition::{RequisitionService, RequisitionServiceTrait},
     requisition_line::{RequisitionLineService, RequisitionLineServiceTrait},
+    serial_number::{SerialNumberService, SerialNumberServiceTrait},
     settings_service::{SettingsService, SettingsServiceTrait},
+    stock_line_reservation::{StockLineReservationService, StockLineReservationServiceTrait},
+    stock_quarantine::{StockQuarantineService, StockQuarantineServiceTrait},
     stocktake::{StocktakeService, StocktakeServiceTrait},
     stocktake_line::{StocktakeLineService, StocktakeLineServiceTrait},
     store::{get_store, get_stores},
+    store_preference::{StorePreferenceService, StorePreferenceServiceTrait},
+    sync::{
+        changelog_compaction::{ChangelogCompactionService, ChangelogCompactionServiceTrait},
+        sync_conflict::{SyncConflictService, SyncConflictServiceTrait},
+        sync_quarantine::{SyncQuarantineService, SyncQuarantineServiceTrait},
+        sync_status::{SyncStatusService, SyncStatusServiceTrait},
+    },
+    vvm_status::{VvmStatusService, VvmStatusServiceTrait},
     ListError, ListResult,
 };
 
@@ -38,18 +57,38 @@ pub struct ServiceProvider {
     pub invoice_line_service: Box<dyn InvoiceLineServiceTrait>,
     pub requisition_service: Box<dyn RequisitionServiceTrait>,
     pub requisition_line_service: Box<dyn RequisitionLineServiceTrait>,
+    pub serial_number_service: Box<dyn SerialNumberServiceTrait>,
+    pub vvm_status_service: Box<dyn VvmStatusServiceTrait>,
+    pub open_vial_wastage_service: Box<dyn OpenVialWastageServiceTrait>,
+    pub item_classification_service: Box<dyn ItemClassificationServiceTrait>,
+    pub cycle_count_service: Box<dyn CycleCountServiceTrait>,
+    pub store_preference_service: Box<dyn StorePreferenceServiceTrait>,
     pub general_service: Box<dyn GeneralServiceTrait>,
     // Dashboard:
     pub invoice_count_service: Box<dyn InvoiceCountServiceTrait>,
     pub stock_expiry_count_service: Box<dyn StockExpiryCountServiceTrait>,
+    pub stock_expiry_bucket_service: Box<dyn StockExpiryBucketServiceTrait>,
     // Stock stats
     pub item_stats_service: Box<dyn ItemStatsServiceTrait>,
+    pub donor_stats_service: Box<dyn DonorStatsServiceTrait>,
+    pub redistribution_service: Box<dyn RedistributionServiceTrait>,
+    pub stock_quarantine_service: Box<dyn StockQuarantineServiceTrait>,
+    pub stock_line_reservation_service: Box<dyn StockLineReservationServiceTrait>,
+    pub historical_stock_service: Box<dyn HistoricalStockServiceTrait>,
     // Reports
     pub report_service: Box<dyn ReportServiceTrait>,
     // Settings
     pub settings: Box<dyn SettingsServiceTrait>,
     // App Data Service
     pub app_data_service: Box<dyn AppDataServiceTrait>,
+    // Sync
+    pub sync_status_service: Box<dyn SyncStatusServiceTrait>,
+    pub sync_quarantine_service: Box<dyn SyncQuarantineServiceTrait>,
+    pub sync_conflict_service: Box<dyn SyncConflictServiceTrait>,
+    pub changelog_compaction_service: Box<dyn ChangelogCompactionServiceTrait>,
+    /// Held while a sync runs, e.g. a scheduled sync and a site re-initialisation must not run
+    /// concurrently (see [crate::sync::Synchroniser::sync])
+    pub(crate) sync_lock: Mutex<()>,
 }
 
 pub struct ServiceContext {
@@ -67,15 +106,32 @@ impl ServiceProvider {
             invoice_count_service: Box::new(InvoiceCountService {}),
             invoice_service: Box::new(InvoiceService {}),
             stock_expiry_count_service: Box::new(StockExpiryServiceCount {}),
+            stock_expiry_bucket_service: Box::new(StockExpiryBucketService {}),
             stocktake_service: Box::new(StocktakeService {}),
             stocktake_line_service: Box::new(StocktakeLineService {}),
             requisition_service: Box::new(RequisitionService {}),
             requisition_line_service: Box::new(RequisitionLineService {}),
+            serial_number_service: Box::new(SerialNumberService {}),
+            vvm_status_service: Box::new(VvmStatusService {}),
+            open_vial_wastage_service: Box::new(OpenVialWastageService {}),
+            item_classification_service: Box::new(ItemClassificationService {}),
+            cycle_count_service: Box::new(CycleCountService {}),
+            store_preference_service: Box::new(StorePreferenceService {}),
             item_stats_service: Box::new(ItemStatsService {}),
+            donor_stats_service: Box::new(DonorStatsService {}),
+            redistribution_service: Box::new(RedistributionService {}),
+            stock_quarantine_service: Box::new(StockQuarantineService {}),
+            stock_line_reservation_service: Box::new(StockLineReservationService {}),
+            historical_stock_service: Box::new(HistoricalStockService {}),
             general_service: Box::new(GeneralService {}),
             report_service: Box::new(ReportService {}),
             settings: Box::new(SettingsService {}),
             app_data_service: Box::new(AppDataService::new(app_data_folder)),
+            sync_status_service: Box::new(SyncStatusService {}),
+            sync_quarantine_service: Box::new(SyncQuarantineService {}),
+            sync_conflict_service: Box::new(SyncConflictService {}),
+            changelog_compaction_service: Box::new(ChangelogCompactionService {}),
+            sync_lock: Mutex::new(()),
         }
     }
 
@@ -1,12 +1,16 @@
 use repository::database_settings::DatabaseSettings;
 
-use crate::sync::settings::SyncSettings;
+use crate::sync::settings::{CentralServerSettings, SyncSettings};
 
 #[derive(serde::Deserialize, Clone)]
 pub struct Settings {
     pub server: ServerSettings,
     pub database: DatabaseSettings,
     pub sync: Option<SyncSettings>,
+    /// Serves the sync api for remote sites if set, sync settings are optional in this case
+    pub central_server: Option<CentralServerSettings>,
+    /// Changelog compaction, defaults are used if not set
+    pub changelog: Option<ChangelogSettings>,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -26,6 +30,41 @@ pub struct ServerSettings {
     pub machine_uid: Option<String>,
 }
 
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct ChangelogSettings {
+    /// How often superseded changelogs are removed, values below 1 are treated as 1
+    #[serde(default = "default_changelog_compaction_interval_sec")]
+    pub compaction_interval_sec: u64,
+    /// Number of most recent changelogs that are never removed, e.g. to inspect recent changes
+    #[serde(default = "default_changelog_retained_changelogs")]
+    pub retained_changelogs: u64,
+    /// Number of most recent sync runs kept in the sync log, older ones are removed on compaction
+    #[serde(default = "default_changelog_retained_sync_logs")]
+    pub retained_sync_logs: u64,
+}
+
+fn default_changelog_compaction_interval_sec() -> u64 {
+    60 * 60
+}
+
+fn default_changelog_retained_changelogs() -> u64 {
+    10000
+}
+
+fn default_changelog_retained_sync_logs() -> u64 {
+    1000
+}
+
+impl Default for ChangelogSettings {
+    fn default() -> Self {
+        ChangelogSettings {
+            compaction_interval_sec: default_changelog_compaction_interval_sec(),
+            retained_changelogs: default_changelog_retained_changelogs(),
+            retained_sync_logs: default_changelog_retained_sync_logs(),
+        }
+    }
+}
+
 impl ServerSettings {
     pub fn address(&self) -> String {
         format!("0.0.0.0:{}", self.port)
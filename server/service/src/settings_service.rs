@@ -1,7 +1,13 @@
+use chrono::NaiveTime;
 use repository::{KeyValueStoreRepository, KeyValueType, RepositoryError};
 use reqwest::Url;
 
-use crate::{service_provider::ServiceContext, sync::settings::SyncSettings};
+use crate::{
+    service_provider::ServiceContext,
+    sync::settings::{SyncQuietHours, SyncSettings},
+};
+
+const QUIET_HOURS_TIME_FORMAT: &str = "%H:%M:%S";
 
 #[derive(Debug)]
 pub enum UpdateSettingsError {
@@ -9,7 +15,7 @@ pub enum UpdateSettingsError {
     InvalidSettings(String),
 }
 
-fn validate(settings: &SyncSettings) -> Result<(), UpdateSettingsError> {
+pub(crate) fn validate(settings: &SyncSettings) -> Result<(), UpdateSettingsError> {
     if let Err(err) = Url::parse(&settings.url) {
         return Err(UpdateSettingsError::InvalidSettings(format!(
             "Invalid url: {:?}",
@@ -23,6 +29,22 @@ fn validate(settings: &SyncSettings) -> Result<(), UpdateSettingsError> {
         ));
     }
 
+    if let Some(quiet_hours) = &settings.quiet_hours {
+        if quiet_hours.start == quiet_hours.end {
+            return Err(UpdateSettingsError::InvalidSettings(
+                "Quiet hours start and end must differ".to_string(),
+            ));
+        }
+    }
+
+    let invalid_tables = settings.invalid_conflict_policy_tables();
+    if !invalid_tables.is_empty() {
+        return Err(UpdateSettingsError::InvalidSettings(format!(
+            "Conflict policies for tables that aren't synced as remote data: {}",
+            invalid_tables.join(", ")
+        )));
+    }
+
     Ok(())
 }
 
@@ -39,6 +61,21 @@ pub trait SettingsServiceTrait: Sync + Send {
         let central_server_site_id =
             key_value_store.get_i32(KeyValueType::SettingsSyncCentralServerSiteId)?;
         let site_id = key_value_store.get_i32(KeyValueType::SettingsSyncSiteId)?;
+        let get_time = |key| -> Result<Option<NaiveTime>, RepositoryError> {
+            Ok(key_value_store
+                .get_string(key)?
+                .and_then(|time| NaiveTime::parse_from_str(&time, QUIET_HOURS_TIME_FORMAT).ok()))
+        };
+        let quiet_hours_start = get_time(KeyValueType::SettingsSyncQuietHoursStart)?;
+        let quiet_hours_end = get_time(KeyValueType::SettingsSyncQuietHoursEnd)?;
+        let quiet_hours = match (quiet_hours_start, quiet_hours_end) {
+            (Some(start), Some(end)) => Some(SyncQuietHours { start, end }),
+            _ => None,
+        };
+        let conflict_policies = key_value_store
+            .get_string(KeyValueType::SettingsSyncConflictPolicies)?
+            .and_then(|policies| serde_json::from_str(&policies).ok())
+            .unwrap_or_default();
 
         let make_settings = || {
             Some(SyncSettings {
@@ -48,6 +85,8 @@ pub trait SettingsServiceTrait: Sync + Send {
                 interval_sec: interval_sec? as u64,
                 central_server_site_id: central_server_site_id? as u32,
                 site_id: site_id? as u32,
+                quiet_hours,
+                conflict_policies,
             })
         };
 
@@ -87,6 +126,26 @@ pub trait SettingsServiceTrait: Sync + Send {
                     KeyValueType::SettingsSyncSiteId,
                     Some(settings.site_id as i32),
                 )?;
+                let format_time =
+                    |time: &NaiveTime| time.format(QUIET_HOURS_TIME_FORMAT).to_string();
+                key_value_store.set_string(
+                    KeyValueType::SettingsSyncQuietHoursStart,
+                    settings
+                        .quiet_hours
+                        .as_ref()
+                        .map(|hours| format_time(&hours.start)),
+                )?;
+                key_value_store.set_string(
+                    KeyValueType::SettingsSyncQuietHoursEnd,
+                    settings
+                        .quiet_hours
+                        .as_ref()
+                        .map(|hours| format_time(&hours.end)),
+                )?;
+                key_value_store.set_string(
+                    KeyValueType::SettingsSyncConflictPolicies,
+                    Some(serde_json::to_string(&settings.conflict_policies).unwrap()),
+                )?;
                 Ok(())
             })
             .map_err(|err| UpdateSettingsError::RepositoryError(err.to_inner_error()))?;
@@ -103,6 +162,11 @@ pub trait SettingsServiceTrait: Sync + Send {
         KeyValueStoreRepository::new(&ctx.connection)
             .set_bool(KeyValueType::SettingsSyncIsDisabled, Some(true))
     }
+
+    fn enable_sync(&self, ctx: &ServiceContext) -> Result<(), RepositoryError> {
+        KeyValueStoreRepository::new(&ctx.connection)
+            .set_bool(KeyValueType::SettingsSyncIsDisabled, Some(false))
+    }
 }
 
 pub struct SettingsService {}
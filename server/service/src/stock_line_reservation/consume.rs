@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use repository::{
+    RepositoryError, StockLineReservationRow, StockLineReservationRowRepository, StockLineRow,
+    StockLineRowRepository, StorageConnection,
+};
+
+/// Who stock is issued to, reservations held for them can be used
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReservationHolder<'a> {
+    /// Customer of the outbound shipment
+    pub name_id: &'a str,
+    /// Reservation picked explicitly, e.g. to issue stock reserved for a campaign
+    pub reservation_id: Option<&'a str>,
+}
+
+impl<'a> ReservationHolder<'a> {
+    pub fn customer(name_id: &'a str) -> Self {
+        ReservationHolder {
+            name_id,
+            reservation_id: None,
+        }
+    }
+
+    fn can_use(&self, reservation: &StockLineReservationRow) -> bool {
+        reservation.name_id.as_deref() == Some(self.name_id)
+            || self.reservation_id == Some(reservation.id.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConsumeStockLineReservation {
+    pub reservation_id: String,
+    pub number_of_packs: i32,
+}
+
+/// Expired reservations that haven't been released yet don't hold any packs
+fn is_held(reservation: &StockLineReservationRow, date: NaiveDate) -> bool {
+    reservation.reserved_until >= date && reservation.number_of_packs > 0
+}
+
+/// Held reservations of the stock lines on the date, by stock line id (earliest reserved_until
+/// first)
+fn get_held_reservations(
+    connection: &StorageConnection,
+    stock_line_ids: &[String],
+    date: NaiveDate,
+) -> Result<HashMap<String, Vec<StockLineReservationRow>>, RepositoryError> {
+    let mut result: HashMap<String, Vec<StockLineReservationRow>> = HashMap::new();
+    for reservation in StockLineReservationRowRepository::new(connection)
+        .find_many_held_by_stock_line_ids(stock_line_ids)?
+    {
+        if !is_held(&reservation, date) {
+            continue;
+        }
+        result
+            .entry(reservation.stock_line_id.clone())
+            .or_default()
+            .push(reservation);
+    }
+    Ok(result)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReservedPacks {
+    /// Packs reserved for the holder, they are issued first
+    pub for_holder: i32,
+    /// Packs reserved for someone else, they are part of the stock line's available number of
+    /// packs but can't be issued to the holder
+    pub for_others: i32,
+}
+
+/// Packs of the stock lines held by reservations, by stock line id
+pub fn get_reserved_packs(
+    connection: &StorageConnection,
+    stock_line_ids: &[String],
+    holder: &ReservationHolder,
+    date: NaiveDate,
+) -> Result<HashMap<String, ReservedPacks>, RepositoryError> {
+    let mut result: HashMap<String, ReservedPacks> = HashMap::new();
+    for (stock_line_id, reservations) in get_held_reservations(connection, stock_line_ids, date)? {
+        let reserved = result.entry(stock_line_id).or_default();
+        for reservation in reservations {
+            if holder.can_use(&reservation) {
+                reserved.for_holder += reservation.number_of_packs;
+            } else {
+                reserved.for_others += reservation.number_of_packs;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Packs of the stock lines held by any reservation, by stock line id. Held packs can't be issued
+/// to anyone but the reserved customer or campaign, i.e. they aren't available to everyone else.
+pub fn get_held_packs_by_stock_line(
+    connection: &StorageConnection,
+    stock_line_ids: &[String],
+    date: NaiveDate,
+) -> Result<HashMap<String, i32>, RepositoryError> {
+    Ok(get_held_reservations(connection, stock_line_ids, date)?
+        .into_iter()
+        .map(|(stock_line_id, reservations)| {
+            let packs = reservations
+                .iter()
+                .map(|reservation| reservation.number_of_packs)
+                .sum();
+            (stock_line_id, packs)
+        })
+        .collect())
+}
+
+/// Packs of the stock line held by any reservation
+pub fn get_held_packs(
+    connection: &StorageConnection,
+    stock_line_id: &str,
+    date: NaiveDate,
+) -> Result<i32, RepositoryError> {
+    Ok(
+        get_held_packs_by_stock_line(connection, &[stock_line_id.to_string()], date)?
+            .remove(stock_line_id)
+            .unwrap_or_default(),
+    )
+}
+
+/// Stock lines of the store with packs held by reservations and the held packs, at most the
+/// available packs of the stock line. Used to remove held stock from stock totals, e.g. item
+/// stats.
+pub fn get_held_stock_lines(
+    connection: &StorageConnection,
+    store_id: &str,
+    date: NaiveDate,
+) -> Result<Vec<(StockLineRow, i32)>, RepositoryError> {
+    let mut held_packs: HashMap<String, i32> = HashMap::new();
+    for reservation in
+        StockLineReservationRowRepository::new(connection).find_many_held_by_store_id(store_id)?
+    {
+        if is_held(&reservation, date) {
+            *held_packs.entry(reservation.stock_line_id).or_default() +=
+                reservation.number_of_packs;
+        }
+    }
+    if held_packs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let stock_line_ids: Vec<String> = held_packs.keys().cloned().collect();
+    Ok(StockLineRowRepository::new(connection)
+        .find_many_by_ids(&stock_line_ids)?
+        .into_iter()
+        .map(|stock_line| {
+            let packs = held_packs
+                .get(&stock_line.id)
+                .copied()
+                .unwrap_or_default()
+                .min(stock_line.available_number_of_packs.max(0));
+            (stock_line, packs)
+        })
+        .collect())
+}
+
+/// Packs of the stock line that can't be issued to the holder since they're reserved for someone
+/// else
+pub fn get_packs_reserved_for_others(
+    connection: &StorageConnection,
+    stock_line_id: &str,
+    holder: &ReservationHolder,
+    date: NaiveDate,
+) -> Result<i32, RepositoryError> {
+    Ok(
+        get_reserved_packs(connection, &[stock_line_id.to_string()], holder, date)?
+            .remove(stock_line_id)
+            .unwrap_or_default()
+            .for_others,
+    )
+}
+
+/// Packs to take from each of the reservations (in order) to cover the number of packs
+pub fn generate_reservation_consumption(
+    reservations: &[StockLineReservationRow],
+    mut number_of_packs: i32,
+) -> Vec<ConsumeStockLineReservation> {
+    let mut result = Vec::new();
+    for reservation in reservations {
+        if number_of_packs <= 0 {
+            break;
+        }
+        let packs = number_of_packs.min(reservation.number_of_packs);
+        if packs <= 0 {
+            continue;
+        }
+        number_of_packs -= packs;
+        result.push(ConsumeStockLineReservation {
+            reservation_id: reservation.id.clone(),
+            number_of_packs: packs,
+        });
+    }
+    result
+}
+
+/// Reduces the reservations of the stock line the holder can use by the packs issued to the
+/// holder. The explicitly picked reservation is used first, then the customer's reservations.
+pub fn consume_stock_line_reservations(
+    connection: &StorageConnection,
+    stock_line_id: &str,
+    holder: &ReservationHolder,
+    number_of_packs: i32,
+    date: NaiveDate,
+) -> Result<(), RepositoryError> {
+    let mut reservations: Vec<StockLineReservationRow> =
+        get_held_reservations(connection, &[stock_line_id.to_string()], date)?
+            .remove(stock_line_id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|reservation| holder.can_use(reservation))
+            .collect();
+    // Stable sort keeps the earliest reserved_until first otherwise
+    reservations.sort_by_key(|reservation| holder.reservation_id != Some(reservation.id.as_str()));
+
+    let repo = StockLineReservationRowRepository::new(connection);
+    for ConsumeStockLineReservation {
+        reservation_id,
+        number_of_packs,
+    } in generate_reservation_consumption(&reservations, number_of_packs)
+    {
+        if let Some(reservation) = reservations
+            .iter()
+            .find(|reservation| reservation.id == reservation_id)
+        {
+            repo.upsert_one(&StockLineReservationRow {
+                number_of_packs: reservation.number_of_packs - number_of_packs,
+                ..reservation.clone()
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Reduces the reservations of the stock line so they hold at most its available packs, e.g.
+/// after a stocktake found less stock than reserved. Reservations held the longest are reduced
+/// first.
+pub fn trim_stock_line_reservations(
+    connection: &StorageConnection,
+    stock_line: &StockLineRow,
+    date: NaiveDate,
+) -> Result<(), RepositoryError> {
+    let mut reservations = get_held_reservations(connection, &[stock_line.id.clone()], date)?
+        .remove(&stock_line.id)
+        .unwrap_or_default();
+    let held_packs: i32 = reservations
+        .iter()
+        .map(|reservation| reservation.number_of_packs)
+        .sum();
+    let excess_packs = held_packs - stock_line.available_number_of_packs.max(0);
+    if excess_packs <= 0 {
+        return Ok(());
+    }
+    reservations.reverse();
+
+    let repo = StockLineReservationRowRepository::new(connection);
+    for ConsumeStockLineReservation {
+        reservation_id,
+        number_of_packs,
+    } in generate_reservation_consumption(&reservations, excess_packs)
+    {
+        if let Some(reservation) = reservations
+            .iter()
+            .find(|reservation| reservation.id == reservation_id)
+        {
+            repo.upsert_one(&StockLineReservationRow {
+                number_of_packs: reservation.number_of_packs - number_of_packs,
+                ..reservation.clone()
+            })?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use chrono::{NaiveDate, Utc};
+use repository::{
+    RepositoryError, StockLineReservationRow, StockLineReservationRowRepository,
+    StockLineRowRepository, StorageConnection,
+};
+use util::date_now;
+
+use crate::{
+    service_provider::ServiceContext, stock_line_reservation::consume::get_held_packs, u32_to_i32,
+    validate::check_name_exists,
+};
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct InsertStockLineReservation {
+    pub id: String,
+    pub stock_line_id: String,
+    /// Customer the stock is reserved for, either this or the campaign must be set
+    pub name_id: Option<String>,
+    /// Campaign or programme the stock is reserved for
+    pub campaign: Option<String>,
+    pub number_of_packs: u32,
+    /// Last day the stock is held
+    pub reserved_until: NaiveDate,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertStockLineReservationError {
+    ReservationAlreadyExists,
+    StockLineDoesNotExist,
+    NotThisStoreStockLine,
+    /// Exactly one of name_id and campaign must be set
+    NameOrCampaignRequired,
+    NameDoesNotExist,
+    NumberOfPacksMustBePositive,
+    /// Stock line doesn't have enough available packs that aren't reserved yet
+    ReductionBelowZero,
+    DatabaseError(RepositoryError),
+}
+
+type OutError = InsertStockLineReservationError;
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &InsertStockLineReservation,
+) -> Result<(), OutError> {
+    if StockLineReservationRowRepository::new(connection)
+        .find_one_by_id(&input.id)?
+        .is_some()
+    {
+        return Err(OutError::ReservationAlreadyExists);
+    }
+
+    let stock_line = StockLineRowRepository::new(connection)
+        .find_many_by_ids(&[input.stock_line_id.clone()])?
+        .pop()
+        .ok_or(OutError::StockLineDoesNotExist)?;
+    if stock_line.store_id != store_id {
+        return Err(OutError::NotThisStoreStockLine);
+    }
+
+    match (&input.name_id, &input.campaign) {
+        (Some(name_id), None) => {
+            if !check_name_exists(connection, name_id)? {
+                return Err(OutError::NameDoesNotExist);
+            }
+        }
+        (None, Some(campaign)) if !campaign.trim().is_empty() => {}
+        _ => return Err(OutError::NameOrCampaignRequired),
+    }
+
+    if input.number_of_packs == 0 {
+        return Err(OutError::NumberOfPacksMustBePositive);
+    }
+    let held_packs = get_held_packs(connection, &stock_line.id, date_now())?;
+    if stock_line.available_number_of_packs - held_packs < u32_to_i32(input.number_of_packs) {
+        return Err(OutError::ReductionBelowZero);
+    }
+
+    Ok(())
+}
+
+fn generate(
+    store_id: &str,
+    InsertStockLineReservation {
+        id,
+        stock_line_id,
+        name_id,
+        campaign,
+        number_of_packs,
+        reserved_until,
+    }: InsertStockLineReservation,
+) -> StockLineReservationRow {
+    StockLineReservationRow {
+        id,
+        store_id: store_id.to_string(),
+        stock_line_id,
+        name_id,
+        campaign,
+        number_of_packs: u32_to_i32(number_of_packs),
+        reserved_until,
+        created_datetime: Utc::now().naive_utc(),
+        released_datetime: None,
+    }
+}
+
+/// Holds packs of a stock line. The stock line's available number of packs (which is synced)
+/// isn't changed, the held packs are subtracted when stock is issued to anyone else.
+pub fn insert_stock_line_reservation(
+    ctx: &ServiceContext,
+    store_id: &str,
+    input: InsertStockLineReservation,
+) -> Result<StockLineReservationRow, OutError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, store_id, &input)?;
+            let reservation = generate(store_id, input);
+            StockLineReservationRowRepository::new(connection).upsert_one(&reservation)?;
+            Ok(reservation)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for InsertStockLineReservationError {
+    fn from(error: RepositoryError) -> Self {
+        InsertStockLineReservationError::DatabaseError(error)
+    }
+}
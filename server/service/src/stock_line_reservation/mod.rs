@@ -0,0 +1,72 @@
+use self::{
+    consume::get_held_packs_by_stock_line,
+    insert::{
+        insert_stock_line_reservation, InsertStockLineReservation, InsertStockLineReservationError,
+    },
+    release::{
+        release_expired_stock_line_reservations, release_stock_line_reservation,
+        ReleaseStockLineReservationError,
+    },
+};
+
+use crate::service_provider::ServiceContext;
+use chrono::NaiveDate;
+use repository::{RepositoryError, StockLineReservationRow, StockLineReservationRowRepository};
+use std::collections::HashMap;
+
+pub mod consume;
+pub mod insert;
+pub mod release;
+
+pub trait StockLineReservationServiceTrait: Sync + Send {
+    /// Reservations of the store that are still held, earliest reserved_until first
+    fn get_stock_line_reservations(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<Vec<StockLineReservationRow>, RepositoryError> {
+        StockLineReservationRowRepository::new(&ctx.connection).find_many_held_by_store_id(store_id)
+    }
+
+    /// Packs of each stock line held by reservations on the given date, stock lines without
+    /// held packs are omitted
+    fn get_held_packs_by_stock_line(
+        &self,
+        ctx: &ServiceContext,
+        stock_line_ids: &[String],
+        date: NaiveDate,
+    ) -> Result<HashMap<String, i32>, RepositoryError> {
+        get_held_packs_by_stock_line(&ctx.connection, stock_line_ids, date)
+    }
+
+    fn insert_stock_line_reservation(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        input: InsertStockLineReservation,
+    ) -> Result<StockLineReservationRow, InsertStockLineReservationError> {
+        insert_stock_line_reservation(ctx, store_id, input)
+    }
+
+    /// Returns the packs still held to the available stock
+    fn release_stock_line_reservation(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        id: &str,
+    ) -> Result<StockLineReservationRow, ReleaseStockLineReservationError> {
+        release_stock_line_reservation(ctx, store_id, id)
+    }
+
+    /// Releases all reservations that were held until before the given date
+    fn release_expired_stock_line_reservations(
+        &self,
+        ctx: &ServiceContext,
+        date: NaiveDate,
+    ) -> Result<Vec<StockLineReservationRow>, RepositoryError> {
+        release_expired_stock_line_reservations(ctx, date)
+    }
+}
+
+pub struct StockLineReservationService {}
+impl StockLineReservationServiceTrait for StockLineReservationService {}
@@ -0,0 +1,356 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{NaiveDate, Utc};
+use log::error;
+use repository::{
+    RepositoryError, StockLineReservationRow, StockLineReservationRowRepository, StorageConnection,
+};
+
+use crate::service_provider::{ServiceContext, ServiceProvider};
+
+#[derive(Debug, PartialEq)]
+pub enum ReleaseStockLineReservationError {
+    ReservationDoesNotExist,
+    NotThisStoreReservation,
+    ReservationAlreadyReleased,
+    DatabaseError(RepositoryError),
+}
+
+type OutError = ReleaseStockLineReservationError;
+
+/// The packs still held by the reservation can be issued to anyone again
+fn release(
+    connection: &StorageConnection,
+    reservation: StockLineReservationRow,
+) -> Result<StockLineReservationRow, RepositoryError> {
+    let reservation = StockLineReservationRow {
+        released_datetime: Some(Utc::now().naive_utc()),
+        ..reservation
+    };
+    StockLineReservationRowRepository::new(connection).upsert_one(&reservation)?;
+    Ok(reservation)
+}
+
+pub fn release_stock_line_reservation(
+    ctx: &ServiceContext,
+    store_id: &str,
+    id: &str,
+) -> Result<StockLineReservationRow, OutError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let reservation = StockLineReservationRowRepository::new(connection)
+                .find_one_by_id(id)?
+                .ok_or(OutError::ReservationDoesNotExist)?;
+            if reservation.store_id != store_id {
+                return Err(OutError::NotThisStoreReservation);
+            }
+            if reservation.released_datetime.is_some() {
+                return Err(OutError::ReservationAlreadyReleased);
+            }
+            Ok(release(connection, reservation)?)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+pub fn release_expired_stock_line_reservations(
+    ctx: &ServiceContext,
+    date: NaiveDate,
+) -> Result<Vec<StockLineReservationRow>, RepositoryError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            StockLineReservationRowRepository::new(connection)
+                .find_many_held_until_before(date)?
+                .into_iter()
+                .map(|reservation| release(connection, reservation))
+                .collect()
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Releases expired reservations at the given interval
+pub async fn run_stock_line_reservation_release_scheduler(
+    service_provider: Arc<ServiceProvider>,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        let ctx = match service_provider.context() {
+            Ok(ctx) => ctx,
+            Err(error) => {
+                error!(
+                    "Stock line reservation release failed to connect: {:?}",
+                    error
+                );
+                continue;
+            }
+        };
+        if let Err(error) = service_provider
+            .stock_line_reservation_service
+            .release_expired_stock_line_reservations(&ctx, Utc::now().naive_utc().date())
+        {
+            error!(
+                "Failed to release expired stock line reservations: {:?}",
+                error
+            );
+        }
+    }
+}
+
+impl From<RepositoryError> for ReleaseStockLineReservationError {
+    fn from(error: RepositoryError) -> Self {
+        ReleaseStockLineReservationError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+    use repository::{
+        mock::{mock_item_a, mock_name_a, mock_store_a, mock_store_b, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        EqualFilter, StockLineRow, StockLineRowRepository,
+    };
+    use util::{date_now, date_now_with_offset, inline_init};
+
+    use crate::{
+        item_stats::ItemStatsFilter,
+        service_provider::ServiceProvider,
+        stock_line_reservation::{
+            consume::{get_held_packs, trim_stock_line_reservations},
+            insert::{InsertStockLineReservation, InsertStockLineReservationError},
+            release::ReleaseStockLineReservationError,
+        },
+    };
+
+    fn stock_line() -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = "stock_line".to_string();
+            r.item_id = mock_item_a().id;
+            r.store_id = mock_store_a().id;
+            r.pack_size = 1;
+            r.total_number_of_packs = 10;
+            r.available_number_of_packs = 10;
+        })
+    }
+
+    fn reservation(id: &str, number_of_packs: u32) -> InsertStockLineReservation {
+        InsertStockLineReservation {
+            id: id.to_string(),
+            stock_line_id: stock_line().id,
+            name_id: None,
+            campaign: Some("Measles campaign".to_string()),
+            number_of_packs,
+            reserved_until: date_now_with_offset(Duration::days(30)),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn stock_line_reservations() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "stock_line_reservations",
+            MockDataInserts::none().units().items().names().stores(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![stock_line()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stock_line_reservation_service;
+        let store_id = mock_store_a().id;
+        let held = || get_held_packs(&connection, &stock_line().id, date_now()).unwrap();
+        let available = || {
+            StockLineRowRepository::new(&connection)
+                .find_one_by_id(&stock_line().id)
+                .unwrap()
+                .available_number_of_packs
+        };
+
+        // NotThisStoreStockLine
+        assert_eq!(
+            service.insert_stock_line_reservation(
+                &context,
+                &mock_store_b().id,
+                reservation("reservation", 5)
+            ),
+            Err(InsertStockLineReservationError::NotThisStoreStockLine)
+        );
+        // NameOrCampaignRequired
+        assert_eq!(
+            service.insert_stock_line_reservation(
+                &context,
+                &store_id,
+                InsertStockLineReservation {
+                    name_id: Some(mock_name_a().id),
+                    ..reservation("reservation", 5)
+                }
+            ),
+            Err(InsertStockLineReservationError::NameOrCampaignRequired)
+        );
+        // ReductionBelowZero
+        assert_eq!(
+            service.insert_stock_line_reservation(
+                &context,
+                &store_id,
+                reservation("reservation", 11)
+            ),
+            Err(InsertStockLineReservationError::ReductionBelowZero)
+        );
+
+        // Reserved packs are held without changing the (synced) stock line
+        service
+            .insert_stock_line_reservation(&context, &store_id, reservation("reservation", 4))
+            .unwrap();
+        service
+            .insert_stock_line_reservation(
+                &context,
+                &store_id,
+                InsertStockLineReservation {
+                    reserved_until: date_now_with_offset(Duration::days(60)),
+                    ..reservation("reservation2", 3)
+                },
+            )
+            .unwrap();
+        assert_eq!(held(), 7);
+        assert_eq!(available(), 10);
+        // Packs held by other reservations can't be reserved again
+        assert_eq!(
+            service.insert_stock_line_reservation(
+                &context,
+                &store_id,
+                reservation("reservation3", 4)
+            ),
+            Err(InsertStockLineReservationError::ReductionBelowZero)
+        );
+        assert_eq!(
+            service
+                .get_stock_line_reservations(&context, &store_id)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // Released after reserved_until
+        let released = service
+            .release_expired_stock_line_reservations(
+                &context,
+                date_now_with_offset(Duration::days(30)),
+            )
+            .unwrap();
+        assert_eq!(released.len(), 0);
+        let released = service
+            .release_expired_stock_line_reservations(
+                &context,
+                date_now_with_offset(Duration::days(31)),
+            )
+            .unwrap();
+        assert_eq!(
+            released
+                .iter()
+                .map(|r| r.id.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["reservation"]
+        );
+        assert_eq!(held(), 3);
+
+        // Manual release
+        assert_eq!(
+            service.release_stock_line_reservation(&context, &store_id, "reservation"),
+            Err(ReleaseStockLineReservationError::ReservationAlreadyReleased)
+        );
+        let released = service
+            .release_stock_line_reservation(&context, &store_id, "reservation2")
+            .unwrap();
+        assert!(released.released_datetime.is_some());
+        assert_eq!(held(), 0);
+        assert_eq!(available(), 10);
+        assert_eq!(
+            service
+                .get_stock_line_reservations(&context, &store_id)
+                .unwrap()
+                .len(),
+            0
+        );
+    }
+
+    #[actix_rt::test]
+    async fn held_stock_is_not_available() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "held_stock_is_not_available",
+            MockDataInserts::none().units().items().names().stores(),
+            inline_init(|r: &mut MockData| {
+                r.stock_lines = vec![stock_line()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = &service_provider.stock_line_reservation_service;
+        let store_id = mock_store_a().id;
+        let available_stock_on_hand = || {
+            service_provider
+                .item_stats_service
+                .get_item_stats(
+                    &context,
+                    &store_id,
+                    None,
+                    Some(ItemStatsFilter::new().item_id(EqualFilter::equal_to(&mock_item_a().id))),
+                )
+                .unwrap()
+                .pop()
+                .unwrap()
+                .available_stock_on_hand
+        };
+
+        service
+            .insert_stock_line_reservation(&context, &store_id, reservation("reservation", 4))
+            .unwrap();
+        service
+            .insert_stock_line_reservation(
+                &context,
+                &store_id,
+                InsertStockLineReservation {
+                    reserved_until: date_now_with_offset(Duration::days(60)),
+                    ..reservation("reservation2", 3)
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            service
+                .get_held_packs_by_stock_line(&context, &[stock_line().id], date_now())
+                .unwrap()
+                .get(&stock_line().id),
+            Some(&7)
+        );
+        assert_eq!(available_stock_on_hand(), 3);
+
+        // A stocktake finds only 5 packs, the reservation held the longest is reduced
+        let counted_stock_line = StockLineRow {
+            total_number_of_packs: 5,
+            available_number_of_packs: 5,
+            ..stock_line()
+        };
+        StockLineRowRepository::new(&connection)
+            .upsert_one(&counted_stock_line)
+            .unwrap();
+        trim_stock_line_reservations(&connection, &counted_stock_line, date_now()).unwrap();
+
+        let mut reservations = service
+            .get_stock_line_reservations(&context, &store_id)
+            .unwrap();
+        reservations.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(
+            reservations
+                .iter()
+                .map(|r| (r.id.as_str(), r.number_of_packs))
+                .collect::<Vec<(&str, i32)>>(),
+            vec![("reservation", 4), ("reservation2", 1)]
+        );
+        assert_eq!(available_stock_on_hand(), 0);
+    }
+}
@@ -0,0 +1,249 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use chrono::{NaiveDate, Utc};
+use log::error;
+use repository::{
+    DateFilter, EqualFilter, KeyValueStoreRepository, KeyValueType, LogFilter, LogRepository,
+    LogRow, LogType, RepositoryError, StockLineFilter, StockLineRepository, StockLineRow,
+    StockLineRowRepository, StoreFilter, StoreRepository,
+};
+use util::uuid::uuid;
+
+use crate::{
+    log::log_entry,
+    service_provider::{ServiceContext, ServiceProvider},
+};
+
+pub trait StockQuarantineServiceTrait: Sync + Send {
+    /// Puts stock lines that expired on or before the given date on hold
+    fn quarantine_expired_stock(
+        &self,
+        ctx: &ServiceContext,
+        date: NaiveDate,
+    ) -> Result<Vec<StockLineRow>, RepositoryError> {
+        quarantine_expired_stock(ctx, date)
+    }
+}
+
+pub struct StockQuarantineService {}
+impl StockQuarantineServiceTrait for StockQuarantineService {}
+
+/// Expired stock lines that still have stock and are not on hold yet are put on hold and a
+/// `StockLineExpiredOnHold` log entry is added for each of them.
+///
+/// Stock lines are only quarantined once, i.e. a stock line that has been taken off hold again
+/// after it was quarantined is left alone.
+///
+/// Only stock of the stores of this site is quarantined, stock of other stores is synced from
+/// their own sites and changes to it would be overwritten (or conflict) on the next pull.
+pub fn quarantine_expired_stock(
+    ctx: &ServiceContext,
+    date: NaiveDate,
+) -> Result<Vec<StockLineRow>, RepositoryError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let site_id = match KeyValueStoreRepository::new(connection)
+                .get_i32(KeyValueType::SettingsSyncSiteId)?
+            {
+                Some(site_id) => site_id,
+                // Site hasn't been initialised yet
+                None => return Ok(Vec::new()),
+            };
+            let store_ids: Vec<String> = StoreRepository::new(connection)
+                .query_by_filter(StoreFilter::new().site_id(EqualFilter::equal_to_i32(site_id)))?
+                .into_iter()
+                .map(|store| store.store_row.id)
+                .collect();
+
+            let expired: Vec<StockLineRow> = StockLineRepository::new(connection)
+                .query_by_filter(
+                    StockLineFilter::new()
+                        .store_id(EqualFilter::equal_any(store_ids))
+                        .expiry_date(DateFilter::before_or_equal_to(date)),
+                )?
+                .into_iter()
+                .map(|stock_line| stock_line.stock_line_row)
+                .filter(|row| !row.on_hold && row.total_number_of_packs > 0)
+                .collect();
+            if expired.is_empty() {
+                return Ok(expired);
+            }
+
+            let already_quarantined: HashSet<String> = LogRepository::new(connection)
+                .query_by_filter(
+                    LogFilter::new()
+                        .r#type(LogType::StockLineExpiredOnHold.equal_to())
+                        .record_id(EqualFilter::equal_any(
+                            expired.iter().map(|row| row.id.clone()).collect(),
+                        )),
+                )?
+                .into_iter()
+                .filter_map(|log| log.log_row.record_id)
+                .collect();
+
+            let stock_line_repo = StockLineRowRepository::new(connection);
+            let mut result = Vec::new();
+            for row in expired {
+                if already_quarantined.contains(&row.id) {
+                    continue;
+                }
+                let row = StockLineRow {
+                    on_hold: true,
+                    ..row
+                };
+                stock_line_repo.upsert_one(&row)?;
+                log_entry(
+                    connection,
+                    &LogRow {
+                        id: uuid(),
+                        r#type: LogType::StockLineExpiredOnHold,
+                        user_id: None,
+                        store_id: Some(row.store_id.clone()),
+                        record_id: Some(row.id.clone()),
+                        datetime: Utc::now().naive_utc(),
+                    },
+                )?;
+                result.push(row);
+            }
+            Ok(result)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Quarantines expired stock at the given interval
+pub async fn run_expired_stock_quarantine_scheduler(
+    service_provider: Arc<ServiceProvider>,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        let ctx = match service_provider.context() {
+            Ok(ctx) => ctx,
+            Err(error) => {
+                error!("Expired stock quarantine failed to connect: {:?}", error);
+                continue;
+            }
+        };
+        if let Err(error) = service_provider
+            .stock_quarantine_service
+            .quarantine_expired_stock(&ctx, Utc::now().naive_utc().date())
+        {
+            error!("Failed to quarantine expired stock: {:?}", error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_item_a, mock_name_a, mock_store_a, MockData, MockDataInserts},
+        test_db::setup_all_with_data,
+        EqualFilter, KeyValueStoreRepository, KeyValueType, LogFilter, LogRepository, LogType,
+        StockLineRow, StockLineRowRepository, StoreRow,
+    };
+    use util::{inline_edit, inline_init};
+
+    use crate::service_provider::ServiceProvider;
+
+    fn stock_line(id: &str, expiry_date: NaiveDate, total_number_of_packs: i32) -> StockLineRow {
+        inline_init(|r: &mut StockLineRow| {
+            r.id = id.to_string();
+            r.item_id = mock_item_a().id;
+            r.store_id = mock_store_a().id;
+            r.pack_size = 1;
+            r.expiry_date = Some(expiry_date);
+            r.total_number_of_packs = total_number_of_packs;
+            r.available_number_of_packs = total_number_of_packs;
+        })
+    }
+
+    #[actix_rt::test]
+    async fn quarantine_expired_stock() {
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "quarantine_expired_stock",
+            MockDataInserts::none().units().items().names().stores(),
+            inline_init(|r: &mut MockData| {
+                r.stores = vec![inline_init(|s: &mut StoreRow| {
+                    s.id = "other_site_store".to_string();
+                    s.name_id = mock_name_a().id;
+                    s.site_id = 2;
+                })];
+                r.stock_lines = vec![
+                    stock_line("expired", NaiveDate::from_ymd(2022, 1, 1), 10),
+                    stock_line("expired_empty", NaiveDate::from_ymd(2022, 1, 1), 0),
+                    stock_line("not_expired", NaiveDate::from_ymd(2022, 1, 2), 10),
+                    inline_edit(
+                        &stock_line("other_site", NaiveDate::from_ymd(2022, 1, 1), 10),
+                        |mut r| {
+                            r.store_id = "other_site_store".to_string();
+                            r
+                        },
+                    ),
+                ];
+            }),
+        )
+        .await;
+        // mock stores are on site 0
+        KeyValueStoreRepository::new(&connection)
+            .set_i32(KeyValueType::SettingsSyncSiteId, Some(0))
+            .unwrap();
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stock_quarantine_service;
+        let stock_line_repo = StockLineRowRepository::new(&connection);
+
+        let result = service
+            .quarantine_expired_stock(&context, NaiveDate::from_ymd(2022, 1, 1))
+            .unwrap();
+        assert_eq!(
+            result.iter().map(|r| r.id.as_str()).collect::<Vec<&str>>(),
+            vec!["expired"]
+        );
+        assert_eq!(
+            stock_line_repo.find_one_by_id("expired").unwrap().on_hold,
+            true
+        );
+        assert_eq!(
+            stock_line_repo
+                .find_one_by_id("not_expired")
+                .unwrap()
+                .on_hold,
+            false
+        );
+        assert_eq!(
+            stock_line_repo
+                .find_one_by_id("other_site")
+                .unwrap()
+                .on_hold,
+            false
+        );
+        let logs = LogRepository::new(&connection)
+            .query_by_filter(
+                LogFilter::new()
+                    .r#type(LogType::StockLineExpiredOnHold.equal_to())
+                    .record_id(EqualFilter::equal_to("expired")),
+            )
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].log_row.store_id, Some(mock_store_a().id));
+
+        // released stock lines are not quarantined again
+        let mut released = stock_line_repo.find_one_by_id("expired").unwrap();
+        released.on_hold = false;
+        stock_line_repo.upsert_one(&released).unwrap();
+        let result = service
+            .quarantine_expired_stock(&context, NaiveDate::from_ymd(2022, 1, 2))
+            .unwrap();
+        assert_eq!(
+            result.iter().map(|r| r.id.as_str()).collect::<Vec<&str>>(),
+            vec!["not_expired"]
+        );
+        assert_eq!(
+            stock_line_repo.find_one_by_id("expired").unwrap().on_hold,
+            false
+        );
+    }
+}
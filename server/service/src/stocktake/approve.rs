@@ -0,0 +1,74 @@
+use chrono::Utc;
+use repository::{
+    RepositoryError, Stocktake, StocktakeRow, StocktakeRowRepository, StocktakeStatus,
+    StorageConnection,
+};
+
+use crate::{
+    service_provider::ServiceContext, stocktake::query::get_stocktake,
+    validate::check_store_id_matches,
+};
+
+use super::validate::check_stocktake_exist;
+
+#[derive(Debug, PartialEq)]
+pub enum ApproveStocktakeError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+    InvalidStore,
+    StocktakeDoesNotExist,
+    /// Only stocktakes in review can be approved
+    StocktakeNotInReview,
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<StocktakeRow, ApproveStocktakeError> {
+    let existing = check_stocktake_exist(connection, stocktake_id)?
+        .ok_or(ApproveStocktakeError::StocktakeDoesNotExist)?;
+    if !check_store_id_matches(store_id, &existing.store_id) {
+        return Err(ApproveStocktakeError::InvalidStore);
+    }
+    if existing.status != StocktakeStatus::Review {
+        return Err(ApproveStocktakeError::StocktakeNotInReview);
+    }
+    Ok(existing)
+}
+
+fn generate(user_id: &str, existing: StocktakeRow) -> StocktakeRow {
+    StocktakeRow {
+        approved_user_id: Some(user_id.to_string()),
+        approved_datetime: Some(Utc::now().naive_utc()),
+        ..existing
+    }
+}
+
+/// Approves the variance of a stocktake in review, allowing it to be finalised
+pub fn approve_stocktake(
+    ctx: &ServiceContext,
+    store_id: &str,
+    user_id: &str,
+    stocktake_id: &str,
+) -> Result<Stocktake, ApproveStocktakeError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let existing = validate(connection, store_id, stocktake_id)?;
+            let stocktake = generate(user_id, existing);
+            StocktakeRowRepository::new(connection).upsert_one(&stocktake)?;
+
+            get_stocktake(ctx, stocktake.id)?.ok_or(ApproveStocktakeError::InternalError(
+                "Failed to read the just approved stocktake!".to_string(),
+            ))
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for ApproveStocktakeError {
+    fn from(error: RepositoryError) -> Self {
+        ApproveStocktakeError::DatabaseError(error)
+    }
+}
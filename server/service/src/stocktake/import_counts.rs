@@ -0,0 +1,625 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use repository::{
+    EqualFilter, ItemFilter, ItemRepository, LocationFilter, LocationRepository, RepositoryError,
+    SimpleStringFilter, StocktakeLine, StocktakeLineFilter, StocktakeLineRepository, StocktakeRow,
+    StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::ServiceContext,
+    stocktake_line::{InsertStocktakeLine, UpdateStocktakeLine},
+    validate::check_store_id_matches,
+};
+
+use super::{
+    batch_stocktake,
+    validate::{
+        check_stocktake_exist, check_stocktake_not_finalised, check_stocktake_not_in_review,
+    },
+    BatchStocktake, BatchStocktakeResult,
+};
+
+#[derive(Default, Debug, Clone)]
+pub struct ImportStocktakeCounts {
+    pub stocktake_id: String,
+    /// Comma or tab separated file with a header row and the columns:
+    /// item code, batch, expiry date, location code, counted number of packs
+    pub file: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ImportStocktakeCountsRowError {
+    /// Missing item code or counted packs, or an invalid number or date
+    InvalidRow,
+    ItemDoesNotExist,
+    LocationDoesNotExist,
+    /// Item code is shared by more than one item
+    AmbiguousItemCode,
+    /// Location code is shared by more than one location of the store
+    AmbiguousLocationCode,
+    /// Row matches more than one stocktake line, e.g. the same batch in different locations
+    AmbiguousRow,
+    /// An earlier row of the file already counted the same stocktake line
+    DuplicateRow,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportStocktakeCountsSkippedRow {
+    /// Row number in the file, the header is row 1
+    pub row_number: u32,
+    pub error: ImportStocktakeCountsRowError,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportStocktakeCountsResult {
+    /// Rows that didn't match or create a stocktake line
+    pub skipped_rows: Vec<ImportStocktakeCountsSkippedRow>,
+    /// Results for the matched (updated) and new (inserted) stocktake lines
+    pub batch: BatchStocktakeResult,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ImportStocktakeCountsError {
+    DatabaseError(RepositoryError),
+    InvalidStore,
+    StocktakeDoesNotExist,
+    CannotEditFinalised,
+    StocktakeIsLocked,
+    /// File can't be read as CSV, e.g. it has no header row
+    InvalidFile(String),
+}
+
+type OutError = ImportStocktakeCountsError;
+type RowError = ImportStocktakeCountsRowError;
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%d/%m/%Y"];
+
+struct ImportRow {
+    item_code: String,
+    batch: Option<String>,
+    expiry_date: Option<NaiveDate>,
+    location_code: Option<String>,
+    counted_number_of_packs: u32,
+}
+
+/// Matched row with the item and location codes resolved to ids
+struct ResolvedRow {
+    item_id: String,
+    batch: Option<String>,
+    expiry_date: Option<NaiveDate>,
+    location_id: Option<String>,
+    counted_number_of_packs: u32,
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &ImportStocktakeCounts,
+) -> Result<StocktakeRow, OutError> {
+    let stocktake = check_stocktake_exist(connection, &input.stocktake_id)?
+        .ok_or(OutError::StocktakeDoesNotExist)?;
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(OutError::InvalidStore);
+    }
+    if !check_stocktake_not_finalised(&stocktake.status) {
+        return Err(OutError::CannotEditFinalised);
+    }
+    if stocktake.is_locked || !check_stocktake_not_in_review(&stocktake.status) {
+        return Err(OutError::StocktakeIsLocked);
+    }
+    Ok(stocktake)
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+fn parse_row(record: &csv::StringRecord) -> Result<ImportRow, RowError> {
+    let item_code = non_empty(record.get(0)).ok_or(RowError::InvalidRow)?;
+    let expiry_date = match non_empty(record.get(2)) {
+        Some(expiry_date) => Some(parse_date(&expiry_date).ok_or(RowError::InvalidRow)?),
+        None => None,
+    };
+    let counted_number_of_packs = non_empty(record.get(4))
+        .and_then(|packs| packs.parse::<u32>().ok())
+        .ok_or(RowError::InvalidRow)?;
+
+    Ok(ImportRow {
+        item_code,
+        batch: non_empty(record.get(1)),
+        expiry_date,
+        location_code: non_empty(record.get(3)),
+        counted_number_of_packs,
+    })
+}
+
+fn parse_file(file: &str) -> Result<Vec<(u32, Result<ImportRow, RowError>)>, OutError> {
+    let header = file.lines().next().unwrap_or_default();
+    let delimiter = if header.contains('\t') && !header.contains(',') {
+        b'\t'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(file.as_bytes());
+    reader
+        .headers()
+        .map_err(|error| OutError::InvalidFile(format!("{}", error)))?;
+
+    let mut rows = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        // Header is row 1
+        let row_number = index as u32 + 2;
+        let row = match record {
+            Ok(record) if record.iter().all(|field| field.is_empty()) => continue,
+            Ok(record) => parse_row(&record),
+            Err(_) => Err(RowError::InvalidRow),
+        };
+        rows.push((row_number, row));
+    }
+    Ok(rows)
+}
+
+/// Codes are resolved once per file, the same item is usually counted in several rows
+struct CodeLookup<'a> {
+    connection: &'a StorageConnection,
+    store_id: &'a str,
+    /// Ids of all items with the code, codes aren't unique
+    items: HashMap<String, Vec<String>>,
+    locations: HashMap<String, Vec<String>>,
+}
+
+impl<'a> CodeLookup<'a> {
+    fn item_ids(&mut self, code: &str) -> Result<Vec<String>, RepositoryError> {
+        if let Some(item_ids) = self.items.get(code) {
+            return Ok(item_ids.clone());
+        }
+        let item_ids: Vec<String> = ItemRepository::new(self.connection)
+            .query_by_filter(ItemFilter::new().code(SimpleStringFilter::equal_to(code)))?
+            .into_iter()
+            .map(|item| item.item_row.id)
+            .collect();
+        self.items.insert(code.to_string(), item_ids.clone());
+        Ok(item_ids)
+    }
+
+    fn location_ids(&mut self, code: &str) -> Result<Vec<String>, RepositoryError> {
+        if let Some(location_ids) = self.locations.get(code) {
+            return Ok(location_ids.clone());
+        }
+        let location_ids: Vec<String> = LocationRepository::new(self.connection)
+            .query_by_filter(
+                LocationFilter::new()
+                    .code(EqualFilter::equal_to(code))
+                    .store_id(EqualFilter::equal_to(self.store_id)),
+            )?
+            .into_iter()
+            .map(|location| location.location_row.id)
+            .collect();
+        self.locations
+            .insert(code.to_string(), location_ids.clone());
+        Ok(location_ids)
+    }
+
+    fn resolve(
+        &mut self,
+        row: ImportRow,
+    ) -> Result<Result<ResolvedRow, RowError>, RepositoryError> {
+        let item_id = match self.item_ids(&row.item_code)?.as_slice() {
+            [item_id] => item_id.clone(),
+            [] => return Ok(Err(RowError::ItemDoesNotExist)),
+            _ => return Ok(Err(RowError::AmbiguousItemCode)),
+        };
+        let location_id = match &row.location_code {
+            Some(code) => match self.location_ids(code)?.as_slice() {
+                [location_id] => Some(location_id.clone()),
+                [] => return Ok(Err(RowError::LocationDoesNotExist)),
+                _ => return Ok(Err(RowError::AmbiguousLocationCode)),
+            },
+            None => None,
+        };
+        Ok(Ok(ResolvedRow {
+            item_id,
+            batch: row.batch,
+            expiry_date: row.expiry_date,
+            location_id,
+            counted_number_of_packs: row.counted_number_of_packs,
+        }))
+    }
+}
+
+/// Stocktake lines of existing stock only have the batch, expiry and location on the stock line
+fn line_matches(line: &StocktakeLine, row: &ResolvedRow) -> bool {
+    let stock_line = line.stock_line.as_ref();
+    let batch = line
+        .line
+        .batch
+        .clone()
+        .or_else(|| stock_line.and_then(|stock_line| stock_line.batch.clone()));
+    let expiry_date = line
+        .line
+        .expiry_date
+        .or_else(|| stock_line.and_then(|stock_line| stock_line.expiry_date));
+    let location_id = line
+        .line
+        .location_id
+        .clone()
+        .or_else(|| stock_line.and_then(|stock_line| stock_line.location_id.clone()));
+
+    line.line.item_id == row.item_id
+        && batch == row.batch
+        && (row.expiry_date.is_none() || expiry_date == row.expiry_date)
+        && (row.location_id.is_none() || location_id == row.location_id)
+}
+
+fn generate(
+    stocktake_id: &str,
+    lines: &[StocktakeLine],
+    rows: Vec<(u32, Result<ResolvedRow, RowError>)>,
+) -> (
+    Vec<InsertStocktakeLine>,
+    Vec<UpdateStocktakeLine>,
+    Vec<ImportStocktakeCountsSkippedRow>,
+) {
+    let mut insert_lines = Vec::new();
+    let mut update_lines = Vec::new();
+    let mut skipped_rows = Vec::new();
+    let mut counted_line_ids = HashSet::new();
+    let mut new_rows: Vec<ResolvedRow> = Vec::new();
+
+    for (row_number, row) in rows {
+        let row = match row {
+            Ok(row) => row,
+            Err(error) => {
+                skipped_rows.push(ImportStocktakeCountsSkippedRow { row_number, error });
+                continue;
+            }
+        };
+
+        let matches: Vec<&StocktakeLine> = lines
+            .iter()
+            .filter(|line| line_matches(line, &row))
+            .collect();
+        let error = match matches.as_slice() {
+            [line] if counted_line_ids.insert(line.line.id.clone()) => {
+                update_lines.push(UpdateStocktakeLine {
+                    id: line.line.id.clone(),
+                    counted_number_of_packs: Some(row.counted_number_of_packs),
+                    ..Default::default()
+                });
+                continue;
+            }
+            [_] => RowError::DuplicateRow,
+            [] if new_rows.iter().any(|new_row| {
+                new_row.item_id == row.item_id
+                    && new_row.batch == row.batch
+                    && new_row.expiry_date == row.expiry_date
+                    && new_row.location_id == row.location_id
+            }) =>
+            {
+                RowError::DuplicateRow
+            }
+            [] => {
+                insert_lines.push(InsertStocktakeLine {
+                    id: uuid(),
+                    stocktake_id: stocktake_id.to_string(),
+                    item_id: Some(row.item_id.clone()),
+                    batch: row.batch.clone(),
+                    expiry_date: row.expiry_date,
+                    location_id: row.location_id.clone(),
+                    counted_number_of_packs: Some(row.counted_number_of_packs),
+                    ..Default::default()
+                });
+                new_rows.push(row);
+                continue;
+            }
+            _ => RowError::AmbiguousRow,
+        };
+        skipped_rows.push(ImportStocktakeCountsSkippedRow { row_number, error });
+    }
+
+    (insert_lines, update_lines, skipped_rows)
+}
+
+/// Imports counts, e.g. from a handheld or typed up from paper, into a stocktake.
+///
+/// Rows are matched to existing stocktake lines by item code and batch, and by expiry date and
+/// location code when given. Rows without a matching line create a new stocktake line. Lines are
+/// updated and inserted with [batch_stocktake] continuing on error, so rows that can be imported
+/// are kept when others fail.
+pub fn import_stocktake_counts(
+    ctx: &ServiceContext,
+    store_id: &str,
+    user_id: &str,
+    input: ImportStocktakeCounts,
+) -> Result<ImportStocktakeCountsResult, OutError> {
+    let connection = &ctx.connection;
+    let stocktake = validate(connection, store_id, &input)?;
+    let rows = parse_file(&input.file)?;
+
+    let mut lookup = CodeLookup {
+        connection,
+        store_id,
+        items: HashMap::new(),
+        locations: HashMap::new(),
+    };
+    let mut resolved_rows = Vec::new();
+    for (row_number, row) in rows {
+        let row = match row {
+            Ok(row) => lookup.resolve(row)?,
+            Err(error) => Err(error),
+        };
+        resolved_rows.push((row_number, row));
+    }
+
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&stocktake.id)),
+    )?;
+    let (insert_lines, update_lines, skipped_rows) = generate(&stocktake.id, &lines, resolved_rows);
+
+    let batch = batch_stocktake(
+        ctx,
+        store_id,
+        user_id,
+        BatchStocktake {
+            insert_stocktake: None,
+            insert_line: Some(insert_lines),
+            update_line: Some(update_lines),
+            delete_line: None,
+            update_stocktake: None,
+            delete_stocktake: None,
+            continue_on_error: Some(true),
+        },
+    )?;
+
+    Ok(ImportStocktakeCountsResult {
+        skipped_rows,
+        batch,
+    })
+}
+
+impl From<RepositoryError> for ImportStocktakeCountsError {
+    fn from(error: RepositoryError) -> Self {
+        ImportStocktakeCountsError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{
+            mock_item_a, mock_item_b, mock_location_1, mock_store_a, MockData, MockDataInserts,
+        },
+        test_db::setup_all_with_data,
+        ItemRow, LocationRow, StocktakeLineRow, StocktakeLineRowRepository, StocktakeRow,
+    };
+    use util::inline_init;
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake::{
+            ImportStocktakeCounts, ImportStocktakeCountsError, ImportStocktakeCountsRowError,
+            ImportStocktakeCountsSkippedRow,
+        },
+    };
+
+    #[actix_rt::test]
+    async fn import_stocktake_counts() {
+        fn line(id: &str, batch: &str, location_id: Option<String>) -> StocktakeLineRow {
+            inline_init(|r: &mut StocktakeLineRow| {
+                r.id = id.to_string();
+                r.stocktake_id = "import_stocktake".to_string();
+                r.item_id = mock_item_a().id;
+                r.batch = Some(batch.to_string());
+                r.expiry_date = Some(NaiveDate::from_ymd(2025, 1, 31));
+                r.location_id = location_id;
+                r.snapshot_number_of_packs = 10;
+            })
+        }
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "import_stocktake_counts",
+            MockDataInserts::none().stores().items().units().locations(),
+            inline_init(|r: &mut MockData| {
+                r.stocktakes = vec![inline_init(|r: &mut StocktakeRow| {
+                    r.id = "import_stocktake".to_string();
+                    r.store_id = mock_store_a().id;
+                })];
+                r.stocktake_lines = vec![
+                    line("b1", "B1", None),
+                    line("b2_location", "B2", Some(mock_location_1().id)),
+                    line("b2", "B2", None),
+                ];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stocktake_service;
+        let import = |file: &str| {
+            service.import_stocktake_counts(
+                &context,
+                &mock_store_a().id,
+                "n/a",
+                ImportStocktakeCounts {
+                    stocktake_id: "import_stocktake".to_string(),
+                    file: file.to_string(),
+                },
+            )
+        };
+
+        // error: StocktakeDoesNotExist
+        assert_eq!(
+            service
+                .import_stocktake_counts(
+                    &context,
+                    &mock_store_a().id,
+                    "n/a",
+                    ImportStocktakeCounts {
+                        stocktake_id: "invalid".to_string(),
+                        file: String::new(),
+                    },
+                )
+                .unwrap_err(),
+            ImportStocktakeCountsError::StocktakeDoesNotExist
+        );
+
+        let file = format!(
+            "item code,batch,expiry,location code,counted packs
+            {item_a},B1,31/01/2025,,7
+            {item_a},B2,,{location},3
+            {item_a},B2,,,4
+            {item_a},B1,,,8
+            unknown,B1,,,1
+            {item_a},B3,,unknown,1
+            {item_a},B1,,,not a number
+            {item_b},B9,2026-06-30,{location},5",
+            item_a = mock_item_a().code,
+            item_b = mock_item_b().code,
+            location = mock_location_1().code,
+        );
+        let result = import(&file).unwrap();
+
+        assert_eq!(
+            result.skipped_rows,
+            vec![
+                ImportStocktakeCountsSkippedRow {
+                    row_number: 4,
+                    error: ImportStocktakeCountsRowError::AmbiguousRow
+                },
+                ImportStocktakeCountsSkippedRow {
+                    row_number: 5,
+                    error: ImportStocktakeCountsRowError::DuplicateRow
+                },
+                ImportStocktakeCountsSkippedRow {
+                    row_number: 6,
+                    error: ImportStocktakeCountsRowError::ItemDoesNotExist
+                },
+                ImportStocktakeCountsSkippedRow {
+                    row_number: 7,
+                    error: ImportStocktakeCountsRowError::LocationDoesNotExist
+                },
+                ImportStocktakeCountsSkippedRow {
+                    row_number: 8,
+                    error: ImportStocktakeCountsRowError::InvalidRow
+                },
+            ]
+        );
+
+        let repo = StocktakeLineRowRepository::new(&connection);
+        let counted = |id: &str| {
+            repo.find_one_by_id(id)
+                .unwrap()
+                .unwrap()
+                .counted_number_of_packs
+        };
+        assert_eq!(counted("b1"), Some(7));
+        assert_eq!(counted("b2_location"), Some(3));
+        assert_eq!(counted("b2"), None);
+
+        // Unmatched row creates a new line
+        assert_eq!(result.batch.update_line.len(), 2);
+        assert_eq!(result.batch.insert_line.len(), 1);
+        let new_line = result.batch.insert_line[0].result.as_ref().unwrap();
+        assert_eq!(new_line.line.item_id, mock_item_b().id);
+        assert_eq!(new_line.line.batch, Some("B9".to_string()));
+        assert_eq!(
+            new_line.line.expiry_date,
+            Some(NaiveDate::from_ymd(2026, 6, 30))
+        );
+        assert_eq!(new_line.line.location_id, Some(mock_location_1().id));
+        assert_eq!(new_line.line.counted_number_of_packs, Some(5));
+
+        // Tab separated handheld export
+        let file = format!(
+            "item\tbatch\texpiry\tlocation\tpacks\n{}\tB1\t\t\t9",
+            mock_item_a().code
+        );
+        let result = import(&file).unwrap();
+        assert_eq!(result.skipped_rows, vec![]);
+        assert_eq!(counted("b1"), Some(9));
+    }
+
+    #[actix_rt::test]
+    async fn import_stocktake_counts_ambiguous_codes() {
+        let shared_item = |id: &str| {
+            inline_init(|r: &mut ItemRow| {
+                r.id = id.to_string();
+                r.name = id.to_string();
+                r.code = "shared_code".to_string();
+            })
+        };
+        let (_, _, connection_manager, _) = setup_all_with_data(
+            "import_stocktake_counts_ambiguous_codes",
+            MockDataInserts::none().stores().items().units().locations(),
+            inline_init(|r: &mut MockData| {
+                r.items = vec![shared_item("shared_item_1"), shared_item("shared_item_2")];
+                r.locations = vec![LocationRow {
+                    id: "shared_location".to_string(),
+                    ..mock_location_1()
+                }];
+                r.stocktakes = vec![inline_init(|r: &mut StocktakeRow| {
+                    r.id = "import_stocktake".to_string();
+                    r.store_id = mock_store_a().id;
+                })];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stocktake_service;
+
+        let file = format!(
+            "item code,batch,expiry,location code,counted packs
+            shared_code,B1,,,1
+            {item_a},B1,,{location},2",
+            item_a = mock_item_a().code,
+            location = mock_location_1().code,
+        );
+        let result = service
+            .import_stocktake_counts(
+                &context,
+                &mock_store_a().id,
+                "n/a",
+                ImportStocktakeCounts {
+                    stocktake_id: "import_stocktake".to_string(),
+                    file,
+                },
+            )
+            .unwrap();
+
+        // Rows aren't counted against an arbitrary item or location
+        assert_eq!(
+            result.skipped_rows,
+            vec![
+                ImportStocktakeCountsSkippedRow {
+                    row_number: 2,
+                    error: ImportStocktakeCountsRowError::AmbiguousItemCode
+                },
+                ImportStocktakeCountsSkippedRow {
+                    row_number: 3,
+                    error: ImportStocktakeCountsRowError::AmbiguousLocationCode
+                },
+            ]
+        );
+        assert_eq!(result.batch.insert_line.len(), 0);
+        assert_eq!(result.batch.update_line.len(), 0);
+    }
+}
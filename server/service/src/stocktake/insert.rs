@@ -1,13 +1,19 @@
+use std::collections::HashSet;
+
 use chrono::{NaiveDate, Utc};
+use rand::{seq::SliceRandom, thread_rng};
 use repository::{
-    EqualFilter, LogRow, LogType, NumberRowType, RepositoryError, Stocktake, StocktakeFilter,
-    StocktakeRepository, StocktakeRow, StocktakeRowRepository, StocktakeStatus, StorageConnection,
+    EqualFilter, LocationRowRepository, LogRow, LogType, MasterListFilter, MasterListLineFilter,
+    MasterListLineRepository, MasterListRepository, NumberRowType, RepositoryError,
+    StockLineFilter, StockLineRepository, StockLineRow, Stocktake, StocktakeFilter,
+    StocktakeLineRow, StocktakeLineRowRepository, StocktakeRepository, StocktakeRow,
+    StocktakeRowRepository, StocktakeStatus, StorageConnection,
 };
 use util::uuid::uuid;
 
 use crate::{
-    log::log_entry, number::next_number, service_provider::ServiceContext,
-    validate::check_store_exists,
+    invoice::common::check_master_list_for_store, log::log_entry, number::next_number,
+    service_provider::ServiceContext, u32_to_i32, validate::check_store_exists,
 };
 
 use super::query::get_stocktake;
@@ -19,6 +25,31 @@ pub struct InsertStocktake {
     pub description: Option<String>,
     pub stocktake_date: Option<NaiveDate>,
     pub is_locked: Option<bool>,
+    /// Hide snapshot quantities from counters
+    pub is_blind_count: Option<bool>,
+    /// Number of packs independent counts of a line may differ by before a recount is required
+    pub recount_tolerance: Option<u32>,
+    /// Variance cost value above which the stocktake has to be approved before finalising
+    pub variance_approval_threshold: Option<f64>,
+    /// Criteria for pre-populating lines, lines are added for each of the provided criteria
+    /// (a stock line or item is only added once)
+    pub criteria: StocktakeLineCriteria,
+}
+
+#[derive(Default, Debug, PartialEq, Clone)]
+pub struct StocktakeLineCriteria {
+    /// All stock in this location
+    pub location_id: Option<String>,
+    /// All stock of items on this master list, and items on the list without stock
+    pub master_list_id: Option<String>,
+    /// Stock expiring before this date
+    pub expires_before: Option<NaiveDate>,
+    /// Items visible in the store (on one of its master lists) that have no stock
+    pub include_zero_stock_items: bool,
+    /// All stock of this many randomly selected items that are in stock
+    pub random_item_sample_size: Option<u32>,
+    /// All stock of these items, and these items if they have no stock
+    pub item_ids: Option<Vec<String>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -27,6 +58,8 @@ pub enum InsertStocktakeError {
     InternalError(String),
     StocktakeAlreadyExists,
     InvalidStore,
+    InvalidLocation,
+    MasterListNotFoundForThisStore,
 }
 
 fn check_stocktake_does_not_exist(
@@ -49,6 +82,22 @@ fn validate(
     if !check_store_exists(connection, store_id)? {
         return Err(InsertStocktakeError::InvalidStore);
     }
+
+    let StocktakeLineCriteria {
+        location_id,
+        master_list_id,
+        ..
+    } = &stocktake.criteria;
+    if let Some(location_id) = location_id {
+        match LocationRowRepository::new(connection).find_one_by_id(location_id)? {
+            Some(location) if location.store_id == store_id => {}
+            _ => return Err(InsertStocktakeError::InvalidLocation),
+        }
+    }
+    if let Some(master_list_id) = master_list_id {
+        check_master_list_for_store(connection, store_id, master_list_id)?
+            .ok_or(InsertStocktakeError::MasterListNotFoundForThisStore)?;
+    }
     Ok(())
 }
 
@@ -62,11 +111,16 @@ fn generate(
         description,
         stocktake_date,
         is_locked,
+        is_blind_count,
+        recount_tolerance,
+        variance_approval_threshold,
+        criteria,
     }: InsertStocktake,
-) -> Result<StocktakeRow, RepositoryError> {
+) -> Result<(StocktakeRow, Vec<StocktakeLineRow>), RepositoryError> {
     let stocktake_number = next_number(connection, &NumberRowType::Stocktake, store_id)?;
+    let lines = generate_lines(connection, store_id, &id, criteria)?;
 
-    Ok(StocktakeRow {
+    let stocktake = StocktakeRow {
         id,
         stocktake_number,
         comment,
@@ -77,10 +131,213 @@ fn generate(
         user_id: user_id.to_string(),
         store_id: store_id.to_string(),
         is_locked: is_locked.unwrap_or(false),
+        is_blind_count: is_blind_count.unwrap_or(false),
+        recount_tolerance: recount_tolerance.map(u32_to_i32).unwrap_or(0),
+        variance_approval_threshold,
         // Default
         finalised_datetime: None,
         inventory_adjustment_id: None,
-    })
+        approved_user_id: None,
+        approved_datetime: None,
+    };
+
+    Ok((stocktake, lines))
+}
+
+fn generate_lines(
+    connection: &StorageConnection,
+    store_id: &str,
+    stocktake_id: &str,
+    StocktakeLineCriteria {
+        location_id,
+        master_list_id,
+        expires_before,
+        include_zero_stock_items,
+        random_item_sample_size,
+        item_ids,
+    }: StocktakeLineCriteria,
+) -> Result<Vec<StocktakeLineRow>, RepositoryError> {
+    let no_criteria = location_id.is_none()
+        && master_list_id.is_none()
+        && expires_before.is_none()
+        && !include_zero_stock_items
+        && random_item_sample_size.is_none()
+        && item_ids.is_none();
+    if no_criteria {
+        return Ok(vec![]);
+    }
+
+    // Stock lines that are in stock, sorted for a predictable line order
+    let mut store_stock: Vec<StockLineRow> = StockLineRepository::new(connection)
+        .query_by_filter(StockLineFilter::new().store_id(EqualFilter::equal_to(store_id)))?
+        .into_iter()
+        .map(|stock_line| stock_line.stock_line_row)
+        .filter(|stock_line| stock_line.total_number_of_packs > 0)
+        .collect();
+    store_stock.sort_by(|a, b| a.item_id.cmp(&b.item_id).then(a.id.cmp(&b.id)));
+    let items_in_stock: HashSet<String> = store_stock
+        .iter()
+        .map(|stock_line| stock_line.item_id.clone())
+        .collect();
+
+    let mut stock_line_ids: HashSet<String> = HashSet::new();
+    let mut items_without_stock: Vec<String> = Vec::new();
+
+    if let Some(location_id) = location_id {
+        stock_line_ids.extend(
+            store_stock
+                .iter()
+                .filter(|stock_line| stock_line.location_id.as_ref() == Some(&location_id))
+                .map(|stock_line| stock_line.id.clone()),
+        );
+    }
+
+    if let Some(expires_before) = expires_before {
+        stock_line_ids.extend(
+            store_stock
+                .iter()
+                .filter(|stock_line| {
+                    stock_line
+                        .expiry_date
+                        .map_or(false, |expiry_date| expiry_date < expires_before)
+                })
+                .map(|stock_line| stock_line.id.clone()),
+        );
+    }
+
+    if let Some(master_list_id) = master_list_id {
+        let item_ids = master_list_item_ids(connection, vec![master_list_id])?;
+        stock_line_ids.extend(
+            store_stock
+                .iter()
+                .filter(|stock_line| item_ids.contains(&stock_line.item_id))
+                .map(|stock_line| stock_line.id.clone()),
+        );
+        items_without_stock.extend(
+            item_ids
+                .into_iter()
+                .filter(|item_id| !items_in_stock.contains(item_id)),
+        );
+    }
+
+    if include_zero_stock_items {
+        let store_master_list_ids = MasterListRepository::new(connection)
+            .query_by_filter(
+                MasterListFilter::new().exists_for_store_id(EqualFilter::equal_to(store_id)),
+            )?
+            .into_iter()
+            .map(|master_list| master_list.id)
+            .collect();
+        items_without_stock.extend(
+            master_list_item_ids(connection, store_master_list_ids)?
+                .into_iter()
+                .filter(|item_id| !items_in_stock.contains(item_id)),
+        );
+    }
+
+    if let Some(sample_size) = random_item_sample_size {
+        let mut item_ids: Vec<&String> = items_in_stock.iter().collect();
+        item_ids.sort();
+        let sampled_item_ids: HashSet<&String> = item_ids
+            .choose_multiple(&mut thread_rng(), sample_size as usize)
+            .cloned()
+            .collect();
+        stock_line_ids.extend(
+            store_stock
+                .iter()
+                .filter(|stock_line| sampled_item_ids.contains(&stock_line.item_id))
+                .map(|stock_line| stock_line.id.clone()),
+        );
+    }
+
+    if let Some(item_ids) = item_ids {
+        stock_line_ids.extend(
+            store_stock
+                .iter()
+                .filter(|stock_line| item_ids.contains(&stock_line.item_id))
+                .map(|stock_line| stock_line.id.clone()),
+        );
+        items_without_stock.extend(
+            item_ids
+                .into_iter()
+                .filter(|item_id| !items_in_stock.contains(item_id)),
+        );
+    }
+
+    let mut lines: Vec<StocktakeLineRow> = store_stock
+        .into_iter()
+        .filter(|stock_line| stock_line_ids.contains(&stock_line.id))
+        .map(|stock_line| generate_stock_line_line(stocktake_id, stock_line))
+        .collect();
+
+    items_without_stock.sort();
+    items_without_stock.dedup();
+    lines.extend(
+        items_without_stock
+            .into_iter()
+            .map(|item_id| StocktakeLineRow {
+                id: uuid(),
+                stocktake_id: stocktake_id.to_string(),
+                stock_line_id: None,
+                location_id: None,
+                comment: None,
+                snapshot_number_of_packs: 0,
+                counted_number_of_packs: None,
+                item_id,
+                batch: None,
+                expiry_date: None,
+                pack_size: None,
+                cost_price_per_pack: None,
+                sell_price_per_pack: None,
+                note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
+                needs_recount: false,
+            }),
+    );
+
+    Ok(lines)
+}
+
+fn master_list_item_ids(
+    connection: &StorageConnection,
+    master_list_ids: Vec<String>,
+) -> Result<Vec<String>, RepositoryError> {
+    let mut item_ids: Vec<String> = MasterListLineRepository::new(connection)
+        .query_by_filter(
+            MasterListLineFilter::new().master_list_id(EqualFilter::equal_any(master_list_ids)),
+        )?
+        .into_iter()
+        .map(|master_list_line| master_list_line.item_id)
+        .collect();
+    item_ids.sort();
+    item_ids.dedup();
+    Ok(item_ids)
+}
+
+/// Line for existing stock, snapshot is taken when the stocktake is created
+fn generate_stock_line_line(stocktake_id: &str, stock_line: StockLineRow) -> StocktakeLineRow {
+    StocktakeLineRow {
+        id: uuid(),
+        stocktake_id: stocktake_id.to_string(),
+        stock_line_id: Some(stock_line.id),
+        location_id: stock_line.location_id,
+        comment: None,
+        snapshot_number_of_packs: stock_line.total_number_of_packs,
+        counted_number_of_packs: None,
+        item_id: stock_line.item_id,
+        batch: stock_line.batch,
+        expiry_date: stock_line.expiry_date,
+        pack_size: Some(stock_line.pack_size),
+        cost_price_per_pack: Some(stock_line.cost_price_per_pack),
+        sell_price_per_pack: Some(stock_line.sell_price_per_pack),
+        note: stock_line.note,
+        donor_id: stock_line.donor_id,
+        manufacturer_id: stock_line.manufacturer_id,
+        vvm_status_id: stock_line.vvm_status_id,
+        needs_recount: false,
+    }
 }
 
 pub fn insert_stocktake(
@@ -93,8 +350,12 @@ pub fn insert_stocktake(
         .connection
         .transaction_sync(|connection| {
             validate(connection, store_id, &input)?;
-            let new_stocktake = generate(connection, store_id, user_id, input)?;
+            let (new_stocktake, new_lines) = generate(connection, store_id, user_id, input)?;
             StocktakeRowRepository::new(&connection).upsert_one(&new_stocktake)?;
+            let line_repo = StocktakeLineRowRepository::new(connection);
+            for line in new_lines {
+                line_repo.upsert_one(&line)?;
+            }
 
             let stocktake = get_stocktake(ctx, new_stocktake.id)?;
             stocktake.ok_or(InsertStocktakeError::InternalError(
@@ -128,15 +389,20 @@ impl From<RepositoryError> for InsertStocktakeError {
 mod test {
     use chrono::{NaiveDate, Utc};
     use repository::{
-        mock::{mock_stocktake_a, mock_store_a, mock_user_account_a, MockDataInserts},
-        test_db::setup_all,
-        StocktakeRow, StocktakeRowRepository, StocktakeStatus,
+        mock::{
+            common::FullMockMasterList, mock_item_a, mock_name_store_a, mock_stocktake_a,
+            mock_store_a, mock_user_account_a, MockData, MockDataInserts,
+        },
+        test_db::{setup_all, setup_all_with_data},
+        EqualFilter, ItemRow, LocationRow, MasterListLineRow, MasterListNameJoinRow, MasterListRow,
+        StockLineRow, StocktakeLineFilter, StocktakeLineRepository, StocktakeRow,
+        StocktakeRowRepository, StocktakeStatus,
     };
     use util::{inline_edit, inline_init};
 
     use crate::{
         service_provider::ServiceProvider,
-        stocktake::insert::{InsertStocktake, InsertStocktakeError},
+        stocktake::insert::{InsertStocktake, InsertStocktakeError, StocktakeLineCriteria},
     };
 
     #[actix_rt::test]
@@ -189,6 +455,10 @@ mod test {
                     description: Some("description".to_string()),
                     stocktake_date: Some(NaiveDate::from_ymd(2020, 01, 02)),
                     is_locked: Some(true),
+                    is_blind_count: Some(true),
+                    recount_tolerance: Some(2),
+                    variance_approval_threshold: Some(100.0),
+                    criteria: StocktakeLineCriteria::default(),
                 },
             )
             .unwrap();
@@ -209,6 +479,9 @@ mod test {
                 i.description = Some("description".to_string());
                 i.stocktake_date = Some(NaiveDate::from_ymd(2020, 01, 02));
                 i.is_locked = true;
+                i.is_blind_count = true;
+                i.recount_tolerance = 2;
+                i.variance_approval_threshold = Some(100.0);
                 i.status = StocktakeStatus::New;
                 i.store_id = store_a.id;
                 i
@@ -219,4 +492,221 @@ mod test {
             new_row.created_datetime > before_insert && new_row.created_datetime < after_insert
         );
     }
+
+    #[actix_rt::test]
+    async fn insert_stocktake_with_criteria() {
+        fn location() -> LocationRow {
+            inline_init(|r: &mut LocationRow| {
+                r.id = "criteria_location".to_string();
+                r.store_id = mock_store_a().id;
+            })
+        }
+        fn item() -> ItemRow {
+            inline_init(|r: &mut ItemRow| r.id = "criteria_item".to_string())
+        }
+        fn item_no_stock() -> ItemRow {
+            inline_init(|r: &mut ItemRow| r.id = "criteria_item_no_stock".to_string())
+        }
+        fn stock_line(id: &str, item_id: &str, total_number_of_packs: i32) -> StockLineRow {
+            inline_init(|r: &mut StockLineRow| {
+                r.id = id.to_string();
+                r.item_id = item_id.to_string();
+                r.store_id = mock_store_a().id;
+                r.pack_size = 1;
+                r.total_number_of_packs = total_number_of_packs;
+                r.available_number_of_packs = total_number_of_packs;
+            })
+        }
+        fn in_location() -> StockLineRow {
+            inline_edit(
+                &stock_line("criteria_in_location", "criteria_item", 5),
+                |mut r| {
+                    r.location_id = Some(location().id);
+                    r
+                },
+            )
+        }
+        fn expiring() -> StockLineRow {
+            inline_edit(
+                &stock_line("criteria_expiring", &mock_item_a().id, 3),
+                |mut r| {
+                    r.expiry_date = Some(NaiveDate::from_ymd(2019, 12, 31));
+                    r
+                },
+            )
+        }
+        fn expiring_no_stock() -> StockLineRow {
+            inline_edit(
+                &stock_line("criteria_expiring_no_stock", "criteria_item", 0),
+                |mut r| {
+                    r.expiry_date = Some(NaiveDate::from_ymd(2019, 12, 31));
+                    r.location_id = Some(location().id);
+                    r
+                },
+            )
+        }
+        fn master_list() -> FullMockMasterList {
+            let id = "criteria_master_list".to_string();
+            FullMockMasterList {
+                master_list: MasterListRow {
+                    id: id.clone(),
+                    name: id.clone(),
+                    code: id.clone(),
+                    description: id.clone(),
+                },
+                joins: vec![MasterListNameJoinRow {
+                    id: id.clone(),
+                    master_list_id: id.clone(),
+                    name_id: mock_name_store_a().id,
+                }],
+                lines: vec![
+                    MasterListLineRow {
+                        id: "criteria_master_list_line1".to_string(),
+                        item_id: item().id,
+                        master_list_id: id.clone(),
+                    },
+                    MasterListLineRow {
+                        id: "criteria_master_list_line2".to_string(),
+                        item_id: item_no_stock().id,
+                        master_list_id: id.clone(),
+                    },
+                ],
+            }
+        }
+
+        let (_, connection, connection_manager, _) = setup_all_with_data(
+            "insert_stocktake_with_criteria",
+            MockDataInserts::all(),
+            inline_init(|r: &mut MockData| {
+                r.items = vec![item(), item_no_stock()];
+                r.locations = vec![location()];
+                r.stock_lines = vec![in_location(), expiring(), expiring_no_stock()];
+                r.full_master_lists = vec![master_list()];
+            }),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stocktake_service;
+        let insert = |id: &str, criteria: StocktakeLineCriteria| {
+            service.insert_stocktake(
+                &context,
+                &mock_store_a().id,
+                &mock_user_account_a().id,
+                inline_init(|i: &mut InsertStocktake| {
+                    i.id = id.to_string();
+                    i.criteria = criteria;
+                }),
+            )
+        };
+        let lines = |stocktake_id: &str| {
+            let mut lines = StocktakeLineRepository::new(&connection)
+                .query_by_filter(
+                    StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(stocktake_id)),
+                )
+                .unwrap();
+            lines.sort_by(|a, b| a.line.item_id.cmp(&b.line.item_id));
+            lines
+        };
+
+        // error: InvalidLocation
+        assert_eq!(
+            insert(
+                "criteria_stocktake",
+                inline_init(
+                    |c: &mut StocktakeLineCriteria| c.location_id = Some("invalid".to_string())
+                ),
+            ),
+            Err(InsertStocktakeError::InvalidLocation)
+        );
+
+        // error: MasterListNotFoundForThisStore
+        assert_eq!(
+            insert(
+                "criteria_stocktake",
+                inline_init(
+                    |c: &mut StocktakeLineCriteria| c.master_list_id = Some("invalid".to_string())
+                ),
+            ),
+            Err(InsertStocktakeError::MasterListNotFoundForThisStore)
+        );
+
+        // location, stock lines without stock are not added
+        insert(
+            "location_stocktake",
+            inline_init(|c: &mut StocktakeLineCriteria| c.location_id = Some(location().id)),
+        )
+        .unwrap();
+        let result = lines("location_stocktake");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line.stock_line_id, Some(in_location().id));
+        assert_eq!(result[0].line.snapshot_number_of_packs, 5);
+        assert_eq!(result[0].line.location_id, Some(location().id));
+
+        // expiring before
+        insert(
+            "expiry_stocktake",
+            inline_init(|c: &mut StocktakeLineCriteria| {
+                c.expires_before = Some(NaiveDate::from_ymd(2020, 01, 01))
+            }),
+        )
+        .unwrap();
+        let result = lines("expiry_stocktake");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].line.stock_line_id, Some(expiring().id));
+        assert_eq!(result[0].line.snapshot_number_of_packs, 3);
+
+        // master list, stock of listed items and listed items without stock
+        insert(
+            "master_list_stocktake",
+            inline_init(|c: &mut StocktakeLineCriteria| {
+                c.master_list_id = Some(master_list().master_list.id)
+            }),
+        )
+        .unwrap();
+        let result = lines("master_list_stocktake");
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].line.stock_line_id, Some(in_location().id));
+        assert_eq!(result[1].line.item_id, item_no_stock().id);
+        assert_eq!(result[1].line.stock_line_id, None);
+        assert_eq!(result[1].line.snapshot_number_of_packs, 0);
+
+        // zero stock visible items
+        insert(
+            "zero_stock_stocktake",
+            inline_init(|c: &mut StocktakeLineCriteria| c.include_zero_stock_items = true),
+        )
+        .unwrap();
+        let result = lines("zero_stock_stocktake");
+        assert!(result.iter().any(|l| l.line.item_id == item_no_stock().id));
+        assert!(result
+            .iter()
+            .all(|l| l.line.stock_line_id.is_none() && l.line.item_id != item().id));
+
+        // random sample, all stock of the sampled item
+        insert(
+            "sample_stocktake",
+            inline_init(|c: &mut StocktakeLineCriteria| c.random_item_sample_size = Some(1)),
+        )
+        .unwrap();
+        let result = lines("sample_stocktake");
+        assert!(!result.is_empty());
+        assert!(result
+            .iter()
+            .all(|l| l.line.item_id == result[0].line.item_id
+                && l.line.stock_line_id.is_some()
+                && l.line.snapshot_number_of_packs > 0));
+
+        // combined criteria don't duplicate lines
+        insert(
+            "combined_stocktake",
+            inline_init(|c: &mut StocktakeLineCriteria| {
+                c.location_id = Some(location().id);
+                c.master_list_id = Some(master_list().master_list.id);
+            }),
+        )
+        .unwrap();
+        assert_eq!(lines("combined_stocktake").len(), 2);
+    }
 }
@@ -18,6 +18,15 @@ pub use self::update::*;
 mod batch;
 pub use self::batch::*;
 
+mod import_counts;
+pub use self::import_counts::*;
+
+mod variance;
+pub use self::variance::*;
+
+mod approve;
+pub use self::approve::*;
+
 pub trait StocktakeServiceTrait: Sync + Send {
     fn get_stocktakes(
         &self,
@@ -72,6 +81,25 @@ pub trait StocktakeServiceTrait: Sync + Send {
         update_stocktake(ctx, store_id, user_id, input)
     }
 
+    fn get_stocktake_variance(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        stocktake_id: &str,
+    ) -> Result<StocktakeVariance, GetStocktakeVarianceError> {
+        get_stocktake_variance(ctx, store_id, stocktake_id)
+    }
+
+    fn approve_stocktake(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        user_id: &str,
+        stocktake_id: &str,
+    ) -> Result<Stocktake, ApproveStocktakeError> {
+        approve_stocktake(ctx, store_id, user_id, stocktake_id)
+    }
+
     fn batch_stocktake(
         &self,
         ctx: &ServiceContext,
@@ -81,6 +109,17 @@ pub trait StocktakeServiceTrait: Sync + Send {
     ) -> Result<BatchStocktakeResult, RepositoryError> {
         batch_stocktake(ctx, store_id, user_id, input)
     }
+
+    /// Imports counts from a CSV file into the stocktake, see [import_stocktake_counts]
+    fn import_stocktake_counts(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        user_id: &str,
+        input: ImportStocktakeCounts,
+    ) -> Result<ImportStocktakeCountsResult, ImportStocktakeCountsError> {
+        import_stocktake_counts(ctx, store_id, user_id, input)
+    }
 }
 
 pub struct StocktakeService {}
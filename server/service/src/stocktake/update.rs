@@ -3,18 +3,23 @@ use repository::{
     EqualFilter, InvoiceLineRow, InvoiceLineRowRepository, InvoiceLineRowType, InvoiceRow,
     InvoiceRowRepository, InvoiceRowStatus, InvoiceRowType, ItemRowRepository, LogRow, LogType,
     NameRowRepository, NumberRowType, RepositoryError, StockLineRow, StockLineRowRepository,
-    Stocktake, StocktakeLine, StocktakeLineFilter, StocktakeLineRepository, StocktakeLineRow,
-    StocktakeLineRowRepository, StocktakeRow, StocktakeRowRepository, StocktakeStatus,
-    StorageConnection,
+    Stocktake, StocktakeLine, StocktakeLineCountRow, StocktakeLineCountRowRepository,
+    StocktakeLineFilter, StocktakeLineRepository, StocktakeLineRow, StocktakeLineRowRepository,
+    StocktakeRow, StocktakeRowRepository, StocktakeStatus, StorageConnection, VvmStatusLogRow,
+    VvmStatusLogRowRepository,
 };
-use util::{constants::INVENTORY_ADJUSTMENT_NAME_CODE, inline_edit, uuid::uuid};
+use util::{constants::INVENTORY_ADJUSTMENT_NAME_CODE, date_now, inline_edit, uuid::uuid};
 
 use crate::{
     log::log_entry, number::next_number, service_provider::ServiceContext,
-    stocktake::query::get_stocktake, validate::check_store_id_matches,
+    stock_line_reservation::consume::trim_stock_line_reservations, stocktake::query::get_stocktake,
+    stocktake_line::reconcile_counts, u32_to_i32, validate::check_store_id_matches,
 };
 
-use super::validate::{check_stocktake_exist, check_stocktake_not_finalised};
+use super::{
+    generate_stocktake_variance,
+    validate::{check_stocktake_exist, check_stocktake_not_finalised},
+};
 
 #[derive(Default, Debug, Clone)]
 pub struct UpdateStocktake {
@@ -24,6 +29,10 @@ pub struct UpdateStocktake {
     pub status: Option<StocktakeStatus>,
     pub stocktake_date: Option<NaiveDate>,
     pub is_locked: Option<bool>,
+    pub is_blind_count: Option<bool>,
+    pub recount_tolerance: Option<u32>,
+    /// Variance cost value above which the stocktake has to be approved before finalising
+    pub variance_approval_threshold: Option<f64>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -38,6 +47,13 @@ pub enum UpdateStocktakeError {
     NoLines,
     /// Holds list of affected stock lines
     SnapshotCountCurrentCountMismatch(Vec<StocktakeLine>),
+    /// Holds list of lines with independent counts that haven't been reconciled
+    CountsNotReconciled(Vec<StocktakeLine>),
+    /// Variance exceeds the approval threshold and the stocktake hasn't been approved
+    VarianceNotApproved,
+    /// The approval threshold can only be changed while the stocktake is new, i.e. not once it's
+    /// in review or in the update that finalises it
+    CannotChangeApprovalThreshold,
 }
 
 fn check_snapshot_matches_current_count(
@@ -59,6 +75,18 @@ fn check_snapshot_matches_current_count(
     None
 }
 
+fn check_counts_reconciled(stocktake_lines: &[StocktakeLine]) -> Option<Vec<StocktakeLine>> {
+    let unreconciled: Vec<StocktakeLine> = stocktake_lines
+        .iter()
+        .filter(|line| line.line.needs_recount)
+        .cloned()
+        .collect();
+    if !unreconciled.is_empty() {
+        return Some(unreconciled);
+    }
+    None
+}
+
 fn load_stocktake_lines(
     connection: &StorageConnection,
     stocktake_id: &str,
@@ -88,23 +116,108 @@ fn validate(
     if !check_store_id_matches(store_id, &existing.store_id) {
         return Err(UpdateStocktakeError::InvalidStore);
     }
-    let stocktake_lines = load_stocktake_lines(connection, &input.id)?;
+    let mut stocktake_lines = load_stocktake_lines(connection, &input.id)?;
 
-    if let Some(StocktakeStatus::Finalised) = input.status {
+    if check_recount_tolerance_changed(input, &existing) {
+        // reconciling changes the counted lines, which are locked for the review
+        if existing.status != StocktakeStatus::New && input.status != Some(StocktakeStatus::New) {
+            return Err(UpdateStocktakeError::StocktakeIsLocked);
+        }
+        let recount_tolerance = input.recount_tolerance.map(u32_to_i32).unwrap_or_default();
+        stocktake_lines =
+            reconcile_stocktake_lines(connection, recount_tolerance, stocktake_lines)?;
+    }
+
+    if !check_approval_threshold_unchanged(input, &existing)
+        && !check_approval_threshold_editable(input, &existing)
+    {
+        return Err(UpdateStocktakeError::CannotChangeApprovalThreshold);
+    }
+
+    if let Some(StocktakeStatus::Review) | Some(StocktakeStatus::Finalised) = input.status {
         if stocktake_lines.len() == 0 {
             return Err(UpdateStocktakeError::NoLines);
         }
 
+        if let Some(unreconciled) = check_counts_reconciled(&stocktake_lines) {
+            return Err(UpdateStocktakeError::CountsNotReconciled(unreconciled));
+        }
+    }
+
+    if let Some(StocktakeStatus::Finalised) = input.status {
         if let Some(mismatches) = check_snapshot_matches_current_count(&stocktake_lines) {
             return Err(UpdateStocktakeError::SnapshotCountCurrentCountMismatch(
                 mismatches,
             ));
         }
+
+        if !check_variance_approved(&existing, &stocktake_lines) {
+            return Err(UpdateStocktakeError::VarianceNotApproved);
+        }
     }
 
     Ok((existing, stocktake_lines))
 }
 
+fn check_recount_tolerance_changed(input: &UpdateStocktake, existing: &StocktakeRow) -> bool {
+    match input.recount_tolerance {
+        Some(recount_tolerance) => u32_to_i32(recount_tolerance) != existing.recount_tolerance,
+        None => false,
+    }
+}
+
+/// Reconciles the lines with their counts using the new recount tolerance, e.g. lines flagged for
+/// a recount are reconciled once the tolerance is raised
+fn reconcile_stocktake_lines(
+    connection: &StorageConnection,
+    recount_tolerance: i32,
+    stocktake_lines: Vec<StocktakeLine>,
+) -> Result<Vec<StocktakeLine>, RepositoryError> {
+    let line_ids: Vec<String> = stocktake_lines
+        .iter()
+        .map(|line| line.line.id.clone())
+        .collect();
+    let counts = StocktakeLineCountRowRepository::new(connection)
+        .find_many_by_stocktake_line_ids(&line_ids)?;
+
+    Ok(stocktake_lines
+        .into_iter()
+        .map(|line| {
+            let line_counts: Vec<StocktakeLineCountRow> = counts
+                .iter()
+                .filter(|count| count.stocktake_line_id == line.line.id)
+                .cloned()
+                .collect();
+            StocktakeLine {
+                line: reconcile_counts(recount_tolerance, line.line, &line_counts),
+                ..line
+            }
+        })
+        .collect())
+}
+
+fn check_approval_threshold_unchanged(input: &UpdateStocktake, existing: &StocktakeRow) -> bool {
+    match input.variance_approval_threshold {
+        Some(threshold) => existing.variance_approval_threshold == Some(threshold),
+        None => true,
+    }
+}
+
+/// The threshold decides whether the reviewed variance needs an approval, it can't be changed
+/// while the stocktake is in review (unless it's reopened in the same update) or when finalising
+fn check_approval_threshold_editable(input: &UpdateStocktake, existing: &StocktakeRow) -> bool {
+    match input.status {
+        Some(StocktakeStatus::New) => true,
+        Some(StocktakeStatus::Finalised) => false,
+        _ => existing.status == StocktakeStatus::New,
+    }
+}
+
+fn check_variance_approved(existing: &StocktakeRow, stocktake_lines: &[StocktakeLine]) -> bool {
+    let variance = generate_stocktake_variance(existing, stocktake_lines.to_vec());
+    !variance.requires_approval || variance.is_approved
+}
+
 pub fn check_stocktake_is_not_locked(input: &UpdateStocktake, existing: &StocktakeRow) -> bool {
     match &input.is_locked {
         Some(false) => true,
@@ -124,6 +237,9 @@ struct StocktakeGenerateJob {
 
     // list of stock_line upserts
     stock_lines: Vec<StockLineRow>,
+
+    // VVM status changes recorded during the count
+    vvm_status_logs: Vec<VvmStatusLogRow>,
 }
 
 /// Contains entities to be updated when a stock line is update/created
@@ -160,6 +276,21 @@ fn generate_stock_line_update(
         expiry_date: stock_line.expiry_date,
         on_hold: stock_line.on_hold,
         note: stock_line.note.clone(),
+        donor_id: stocktake_line
+            .line
+            .donor_id
+            .clone()
+            .or(stock_line.donor_id.clone()),
+        manufacturer_id: stocktake_line
+            .line
+            .manufacturer_id
+            .clone()
+            .or(stock_line.manufacturer_id.clone()),
+        vvm_status_id: stocktake_line
+            .line
+            .vvm_status_id
+            .clone()
+            .or(stock_line.vvm_status_id.clone()),
     };
 
     let item = match ItemRowRepository::new(connection).find_one_by_id(&stock_line.item_id)? {
@@ -198,6 +329,9 @@ fn generate_stock_line_update(
             tax: None,
             number_of_packs: quantiy_change,
             note: stock_line.note.clone(),
+            donor_id: updated_line.donor_id.clone(),
+            manufacturer_id: updated_line.manufacturer_id.clone(),
+            vvm_status_id: updated_line.vvm_status_id.clone(),
         })
     } else {
         None
@@ -244,6 +378,9 @@ fn generate_new_stock_line(
         expiry_date: row.expiry_date,
         on_hold: false,
         note: row.note.clone(),
+        donor_id: row.donor_id.clone(),
+        manufacturer_id: row.manufacturer_id.clone(),
+        vvm_status_id: row.vvm_status_id.clone(),
     };
 
     let item = match ItemRowRepository::new(connection).find_one_by_id(&item_id)? {
@@ -275,6 +412,9 @@ fn generate_new_stock_line(
             tax: None,
             number_of_packs: counted_number_of_packs,
             note: row.note,
+            donor_id: row.donor_id,
+            manufacturer_id: row.manufacturer_id,
+            vvm_status_id: row.vvm_status_id,
         })
     } else {
         None
@@ -297,6 +437,9 @@ fn generate(
         status: input_status,
         is_locked: input_is_locked,
         stocktake_date: input_stocktake_date,
+        is_blind_count: input_is_blind_count,
+        recount_tolerance: input_recount_tolerance,
+        variance_approval_threshold: input_variance_approval_threshold,
     }: UpdateStocktake,
     existing: StocktakeRow,
     stocktake_lines: Vec<StocktakeLine>,
@@ -310,6 +453,21 @@ fn generate(
             u.comment = input_comment.or(u.comment);
             u.is_locked = input_is_locked.unwrap_or(false);
             u.stocktake_date = input_stocktake_date.or(u.stocktake_date);
+            u.is_blind_count = input_is_blind_count.unwrap_or(u.is_blind_count);
+            u.recount_tolerance = input_recount_tolerance
+                .map(u32_to_i32)
+                .unwrap_or(u.recount_tolerance);
+            let threshold_changed = input_variance_approval_threshold.map_or(false, |threshold| {
+                u.variance_approval_threshold != Some(threshold)
+            });
+            u.variance_approval_threshold =
+                input_variance_approval_threshold.or(u.variance_approval_threshold);
+            // approval is for the reviewed counts and threshold, it's revoked when the stocktake is
+            // reopened or the threshold changes
+            if u.status == StocktakeStatus::New || threshold_changed {
+                u.approved_user_id = None;
+                u.approved_datetime = None;
+            }
             u
         });
         return Ok(StocktakeGenerateJob {
@@ -318,6 +476,7 @@ fn generate(
             inventory_adjustment: None,
             inventory_adjustment_lines: vec![],
             stock_lines: vec![],
+            vvm_status_logs: vec![],
         });
     }
 
@@ -325,8 +484,16 @@ fn generate(
     let mut inventory_adjustment_lines: Vec<InvoiceLineRow> = Vec::new();
     let mut stock_lines: Vec<StockLineRow> = Vec::new();
     let mut stocktake_line_updates: Vec<StocktakeLineRow> = Vec::new();
+    let mut vvm_status_logs: Vec<VvmStatusLogRow> = Vec::new();
     let shipment_id = uuid();
+    let now = Utc::now().naive_utc();
     for stocktake_line in stocktake_lines {
+        let stocktake_line_id = stocktake_line.line.id.clone();
+        let counted_vvm_status_id = stocktake_line.line.vvm_status_id.clone();
+        let previous_vvm_status_id = stocktake_line
+            .stock_line
+            .as_ref()
+            .and_then(|stock_line| stock_line.vvm_status_id.clone());
         let StockLineJob {
             stock_line,
             invoice_line,
@@ -338,6 +505,19 @@ fn generate(
             // create new stock line
             generate_new_stock_line(connection, store_id, &shipment_id, stocktake_line)?
         };
+        if let Some(status_id) = counted_vvm_status_id {
+            if Some(&status_id) != previous_vvm_status_id.as_ref() {
+                vvm_status_logs.push(VvmStatusLogRow {
+                    id: uuid(),
+                    status_id,
+                    stock_line_id: stock_line.id.clone(),
+                    invoice_line_id: None,
+                    stocktake_line_id: Some(stocktake_line_id),
+                    comment: None,
+                    created_datetime: now,
+                });
+            }
+        }
         stock_lines.push(stock_line);
         if let Some(shipment_line) = invoice_line {
             inventory_adjustment_lines.push(shipment_line);
@@ -355,7 +535,6 @@ fn generate(
         ))?;
 
     // create a shipment even if there are no shipment lines
-    let now = Utc::now().naive_utc();
     let shipment = InvoiceRow {
         id: shipment_id,
         user_id: Some(user_id.to_string()),
@@ -385,6 +564,8 @@ fn generate(
         u.description = input_description.or(u.description);
         u.status = input_status.unwrap_or(u.status).clone();
         u.comment = input_comment.or(u.comment);
+        u.variance_approval_threshold =
+            input_variance_approval_threshold.or(u.variance_approval_threshold);
         u.finalised_datetime = Some(now);
         u.inventory_adjustment_id = Some(shipment.id.clone());
         u
@@ -396,6 +577,7 @@ fn generate(
         inventory_adjustment: Some(shipment),
         inventory_adjustment_lines,
         stock_lines,
+        vvm_status_logs,
     })
 }
 
@@ -410,6 +592,16 @@ pub fn update_stocktake(
         .transaction_sync(|connection| {
             let stocktake_id = input.id.clone();
             let (existing, stocktake_lines) = validate(connection, store_id, &input)?;
+            // lines reconciled with a changed recount tolerance
+            let reconciled_lines: Vec<StocktakeLineRow> =
+                if check_recount_tolerance_changed(&input, &existing) {
+                    stocktake_lines
+                        .iter()
+                        .map(|line| line.line.clone())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
             let result = generate(
                 connection,
                 user_id,
@@ -424,12 +616,22 @@ pub fn update_stocktake(
             let stock_line_repo = StockLineRowRepository::new(connection);
             for stock_line in result.stock_lines {
                 stock_line_repo.upsert_one(&stock_line)?;
+                // reservations can't hold stock that was found missing
+                trim_stock_line_reservations(connection, &stock_line, date_now())?;
             }
             // write updated stocktake lines
             let stocktake_line_repo = StocktakeLineRowRepository::new(connection);
+            for stocktake_line in reconciled_lines {
+                stocktake_line_repo.upsert_one(&stocktake_line)?;
+            }
             for stocktake_line in result.stocktake_lines {
                 stocktake_line_repo.upsert_one(&stocktake_line)?;
             }
+            // write VVM status changes
+            let vvm_status_log_repo = VvmStatusLogRowRepository::new(connection);
+            for vvm_status_log in result.vvm_status_logs {
+                vvm_status_log_repo.insert_one(&vvm_status_log)?;
+            }
             // write inventory adjustment
             if let Some(inventory_adjustment) = result.inventory_adjustment {
                 let shipment_repo = InvoiceRowRepository::new(connection);
@@ -442,6 +644,7 @@ pub fn update_stocktake(
             StocktakeRowRepository::new(connection).upsert_one(&result.stocktake)?;
 
             if existing.status != result.stocktake.status
+                && result.stocktake.status == StocktakeStatus::Finalised
             {
                 log_entry(
                     &ctx.connection,
@@ -481,9 +684,10 @@ mod test {
             mock_locked_stocktake, mock_stock_line_a, mock_stocktake_a,
             mock_stocktake_finalised_without_lines, mock_stocktake_full_edit,
             mock_stocktake_line_a, mock_stocktake_line_new_stock_line,
+            mock_stocktake_line_no_count_change, mock_stocktake_line_stock_surplus,
             mock_stocktake_new_stock_line, mock_stocktake_no_count_change, mock_stocktake_no_lines,
             mock_stocktake_stock_deficit, mock_stocktake_stock_surplus, mock_store_a,
-            MockDataInserts,
+            mock_user_account_a, mock_user_account_b, MockDataInserts,
         },
         test_db::setup_all,
         InvoiceLineRowRepository, InvoiceLineRowType, StockLineRowRepository, StocktakeLine,
@@ -493,7 +697,11 @@ mod test {
 
     use crate::{
         service_provider::ServiceProvider,
-        stocktake::update::{UpdateStocktake, UpdateStocktakeError},
+        stocktake::{
+            update::{UpdateStocktake, UpdateStocktakeError},
+            ApproveStocktakeError,
+        },
+        stocktake_line::{InsertStocktakeLineCount, UpdateStocktakeLine, UpdateStocktakeLineError},
     };
 
     #[actix_rt::test]
@@ -611,6 +819,36 @@ mod test {
             .unwrap_err();
         assert_eq!(error, UpdateStocktakeError::NoLines);
 
+        // error: CountsNotReconciled
+        let store_a = mock_store_a();
+        let stocktake = mock_stocktake_no_count_change();
+        let stocktake_line = inline_edit(&mock_stocktake_line_no_count_change(), |mut r| {
+            r.needs_recount = true;
+            r
+        });
+        StocktakeLineRowRepository::new(&context.connection)
+            .upsert_one(&stocktake_line)
+            .unwrap();
+        let error = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::Finalised);
+                }),
+            )
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            UpdateStocktakeError::CountsNotReconciled(lines)
+                if lines.len() == 1 && lines[0].line.id == stocktake_line.id
+        ));
+        StocktakeLineRowRepository::new(&context.connection)
+            .upsert_one(&mock_stocktake_line_no_count_change())
+            .unwrap();
+
         // success surplus should result in StockIn shipment line
         let store_a = mock_store_a();
         let stocktake = mock_stocktake_stock_surplus();
@@ -759,6 +997,9 @@ mod test {
                     status: Some(StocktakeStatus::New),
                     stocktake_date: Some(NaiveDate::from_ymd(2019, 03, 20)),
                     is_locked: Some(false),
+                    is_blind_count: Some(true),
+                    recount_tolerance: Some(1),
+                    variance_approval_threshold: Some(10.0),
                 },
             )
             .unwrap();
@@ -770,6 +1011,9 @@ mod test {
                 i.description = Some("description_1".to_string());
                 i.stocktake_date = Some(NaiveDate::from_ymd(2019, 03, 20));
                 i.is_locked = false;
+                i.is_blind_count = true;
+                i.recount_tolerance = 1;
+                i.variance_approval_threshold = Some(10.0);
                 i
             }),
         );
@@ -817,4 +1061,284 @@ mod test {
             .unwrap();
         assert_eq!(updated_stocktake_line.stock_line_id, Some(stock_line.id));
     }
+
+    #[actix_rt::test]
+    async fn update_stocktake_review() {
+        let (_, _, connection_manager, _) =
+            setup_all("update_stocktake_review", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stocktake_service;
+        let store_a = mock_store_a();
+        let stocktake = mock_stocktake_stock_surplus();
+        let stocktake_line = mock_stocktake_line_stock_surplus();
+
+        // surplus of 10 packs, variance value of 20
+        service_provider
+            .stocktake_line_service
+            .update_stocktake_line(
+                &context,
+                &store_a.id,
+                inline_init(|i: &mut UpdateStocktakeLine| {
+                    i.id = stocktake_line.id.clone();
+                    i.cost_price_per_pack = Some(2.0);
+                }),
+            )
+            .unwrap();
+
+        service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.variance_approval_threshold = Some(15.0);
+                }),
+            )
+            .unwrap();
+
+        // error: CannotChangeApprovalThreshold, raising the threshold would skip the approval
+        let error = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::Finalised);
+                    i.variance_approval_threshold = Some(25.0);
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(error, UpdateStocktakeError::CannotChangeApprovalThreshold);
+
+        // error: VarianceNotApproved
+        let error = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::Finalised);
+                    i.variance_approval_threshold = Some(15.0);
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(error, UpdateStocktakeError::VarianceNotApproved);
+
+        // error: StocktakeNotInReview
+        let error = service
+            .approve_stocktake(&context, &store_a.id, "approver", &stocktake.id)
+            .unwrap_err();
+        assert_eq!(error, ApproveStocktakeError::StocktakeNotInReview);
+
+        // success: move to review
+        let result = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::Review);
+                }),
+            )
+            .unwrap();
+        assert_eq!(result.status, StocktakeStatus::Review);
+        let variance = service
+            .get_stocktake_variance(&context, &store_a.id, &stocktake.id)
+            .unwrap();
+        assert_eq!(variance.total_difference_value, 20.0);
+        assert_eq!(variance.requires_approval, true);
+        assert_eq!(variance.is_approved, false);
+
+        // error: CannotChangeApprovalThreshold, raising the threshold in review and finalising in
+        // a second update would skip the approval as well
+        let error = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.variance_approval_threshold = Some(25.0);
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(error, UpdateStocktakeError::CannotChangeApprovalThreshold);
+        let error = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::Finalised);
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(error, UpdateStocktakeError::VarianceNotApproved);
+
+        // error: lines can't be edited in review
+        let error = service_provider
+            .stocktake_line_service
+            .update_stocktake_line(
+                &context,
+                &store_a.id,
+                inline_init(|i: &mut UpdateStocktakeLine| {
+                    i.id = stocktake_line.id.clone();
+                    i.counted_number_of_packs = Some(0);
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(error, UpdateStocktakeLineError::StocktakeIsLocked);
+
+        // success: approve, reopening revokes the approval
+        let result = service
+            .approve_stocktake(&context, &store_a.id, "approver", &stocktake.id)
+            .unwrap();
+        assert_eq!(result.approved_user_id, Some("approver".to_string()));
+        assert!(result.approved_datetime.is_some());
+
+        let result = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::New);
+                }),
+            )
+            .unwrap();
+        assert_eq!(result.approved_user_id, None);
+        assert_eq!(result.approved_datetime, None);
+
+        // success: finalise approved stocktake
+        service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::Review);
+                }),
+            )
+            .unwrap();
+        service
+            .approve_stocktake(&context, &store_a.id, "approver", &stocktake.id)
+            .unwrap();
+        let result = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::Finalised);
+                }),
+            )
+            .unwrap();
+        assert_eq!(result.status, StocktakeStatus::Finalised);
+        assert_eq!(result.approved_user_id, Some("approver".to_string()));
+    }
+
+    #[actix_rt::test]
+    async fn update_stocktake_recount_tolerance() {
+        let (_, connection, connection_manager, _) =
+            setup_all("update_stocktake_recount_tolerance", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stocktake_service;
+        let store_a = mock_store_a();
+        let stocktake = mock_stocktake_a();
+        let stocktake_line = mock_stocktake_line_a();
+
+        // disagreeing counts flag the line for a recount
+        for (user_id, counted_number_of_packs) in [
+            (mock_user_account_a().id, 10),
+            (mock_user_account_b().id, 12),
+        ] {
+            service_provider
+                .stocktake_line_service
+                .insert_stocktake_line_count(
+                    &context,
+                    &store_a.id,
+                    &user_id,
+                    InsertStocktakeLineCount {
+                        stocktake_line_id: stocktake_line.id.clone(),
+                        counted_number_of_packs,
+                    },
+                )
+                .unwrap();
+        }
+
+        // success: raising the tolerance reconciles the line with the latest count
+        service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.recount_tolerance = Some(2);
+                }),
+            )
+            .unwrap();
+        let updated_line = StocktakeLineRowRepository::new(&connection)
+            .find_one_by_id(&stocktake_line.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated_line.counted_number_of_packs, Some(12));
+        assert_eq!(updated_line.needs_recount, false);
+
+        // success: lowering the tolerance flags the line again
+        service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.recount_tolerance = Some(1);
+                }),
+            )
+            .unwrap();
+        let updated_line = StocktakeLineRowRepository::new(&connection)
+            .find_one_by_id(&stocktake_line.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated_line.needs_recount, true);
+
+        // error: StocktakeIsLocked, the counted lines can't change in review
+        service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.status = Some(StocktakeStatus::Review);
+                    i.recount_tolerance = Some(2);
+                }),
+            )
+            .unwrap();
+        let error = service
+            .update_stocktake(
+                &context,
+                &store_a.id,
+                "n/a",
+                inline_init(|i: &mut UpdateStocktake| {
+                    i.id = stocktake.id.clone();
+                    i.recount_tolerance = Some(5);
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(error, UpdateStocktakeError::StocktakeIsLocked);
+    }
 }
@@ -15,6 +15,11 @@ pub fn check_stocktake_not_finalised(status: &StocktakeStatus) -> bool {
     *status != StocktakeStatus::Finalised
 }
 
+/// Lines of a stocktake under variance review can't be changed
+pub fn check_stocktake_not_in_review(status: &StocktakeStatus) -> bool {
+    *status != StocktakeStatus::Review
+}
+
 pub fn check_no_stocktake_lines_exist(
     connection: &StorageConnection,
     stocktake_line_id: &str,
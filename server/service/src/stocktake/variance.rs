@@ -0,0 +1,261 @@
+use repository::{
+    EqualFilter, RepositoryError, StocktakeLine, StocktakeLineFilter, StocktakeLineRepository,
+    StocktakeRow, StocktakeStatus, StorageConnection,
+};
+
+use crate::{service_provider::ServiceContext, validate::check_store_id_matches};
+
+use super::validate::check_stocktake_exist;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StocktakeLineVariance {
+    pub line: StocktakeLine,
+    /// Counted minus snapshot number of packs (zero for uncounted lines)
+    pub difference_number_of_packs: i32,
+    /// Difference in packs multiplied by the cost price per pack
+    pub difference_value: f64,
+    /// Absolute difference value is above the stocktake's approval threshold
+    pub exceeds_threshold: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct StocktakeVariance {
+    pub stocktake_id: String,
+    pub lines: Vec<StocktakeLineVariance>,
+    /// Sum of line difference values, surpluses and deficits cancel each other out
+    pub total_difference_value: f64,
+    /// Sum of absolute line difference values
+    pub total_absolute_difference_value: f64,
+    /// A line or the total absolute difference value is above the approval threshold
+    pub requires_approval: bool,
+    pub is_approved: bool,
+    /// Blind count that is still being counted, the differences reveal the snapshot
+    pub is_blind_count: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GetStocktakeVarianceError {
+    DatabaseError(RepositoryError),
+    InvalidStore,
+    StocktakeDoesNotExist,
+}
+
+fn exceeds_threshold(threshold: Option<f64>, value: f64) -> bool {
+    match threshold {
+        Some(threshold) => value.abs() > threshold,
+        None => false,
+    }
+}
+
+fn line_cost_price_per_pack(line: &StocktakeLine) -> f64 {
+    line.line
+        .cost_price_per_pack
+        .or(line
+            .stock_line
+            .as_ref()
+            .map(|stock_line| stock_line.cost_price_per_pack))
+        .unwrap_or(0.0)
+}
+
+/// The snapshot of a blind count is hidden while counting and revealed for the review
+pub fn is_snapshot_hidden(stocktake: &StocktakeRow) -> bool {
+    stocktake.is_blind_count && stocktake.status == StocktakeStatus::New
+}
+
+pub fn generate_stocktake_variance(
+    stocktake: &StocktakeRow,
+    stocktake_lines: Vec<StocktakeLine>,
+) -> StocktakeVariance {
+    let threshold = stocktake.variance_approval_threshold;
+    let lines: Vec<StocktakeLineVariance> = stocktake_lines
+        .into_iter()
+        .map(|line| {
+            let difference_number_of_packs = line
+                .line
+                .counted_number_of_packs
+                .map(|counted| counted - line.line.snapshot_number_of_packs)
+                .unwrap_or(0);
+            let difference_value =
+                difference_number_of_packs as f64 * line_cost_price_per_pack(&line);
+            StocktakeLineVariance {
+                line,
+                difference_number_of_packs,
+                difference_value,
+                exceeds_threshold: exceeds_threshold(threshold, difference_value),
+            }
+        })
+        .collect();
+
+    let total_difference_value = lines.iter().map(|line| line.difference_value).sum();
+    let total_absolute_difference_value: f64 =
+        lines.iter().map(|line| line.difference_value.abs()).sum();
+    let requires_approval = lines.iter().any(|line| line.exceeds_threshold)
+        || exceeds_threshold(threshold, total_absolute_difference_value);
+
+    StocktakeVariance {
+        stocktake_id: stocktake.id.clone(),
+        lines,
+        total_difference_value,
+        total_absolute_difference_value,
+        requires_approval,
+        is_approved: stocktake.approved_datetime.is_some(),
+        is_blind_count: is_snapshot_hidden(stocktake),
+    }
+}
+
+pub fn load_stocktake_variance(
+    connection: &StorageConnection,
+    stocktake: &StocktakeRow,
+) -> Result<StocktakeVariance, RepositoryError> {
+    let lines = StocktakeLineRepository::new(connection).query_by_filter(
+        StocktakeLineFilter::new().stocktake_id(EqualFilter::equal_to(&stocktake.id)),
+    )?;
+    Ok(generate_stocktake_variance(stocktake, lines))
+}
+
+pub fn get_stocktake_variance(
+    ctx: &ServiceContext,
+    store_id: &str,
+    stocktake_id: &str,
+) -> Result<StocktakeVariance, GetStocktakeVarianceError> {
+    let stocktake = check_stocktake_exist(&ctx.connection, stocktake_id)?
+        .ok_or(GetStocktakeVarianceError::StocktakeDoesNotExist)?;
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(GetStocktakeVarianceError::InvalidStore);
+    }
+    Ok(load_stocktake_variance(&ctx.connection, &stocktake)?)
+}
+
+impl From<RepositoryError> for GetStocktakeVarianceError {
+    fn from(error: RepositoryError) -> Self {
+        GetStocktakeVarianceError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, StockLineRow, StocktakeLine, StocktakeLineRow,
+        StocktakeRow, StocktakeStatus,
+    };
+    use util::inline_init;
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::{generate_stocktake_variance, GetStocktakeVarianceError};
+
+    fn line(
+        id: &str,
+        snapshot: i32,
+        counted: Option<i32>,
+        cost_price_per_pack: Option<f64>,
+        stock_line_cost_price: Option<f64>,
+    ) -> StocktakeLine {
+        StocktakeLine {
+            line: inline_init(|r: &mut StocktakeLineRow| {
+                r.id = id.to_string();
+                r.snapshot_number_of_packs = snapshot;
+                r.counted_number_of_packs = counted;
+                r.cost_price_per_pack = cost_price_per_pack;
+            }),
+            stock_line: stock_line_cost_price.map(|cost_price_per_pack| {
+                inline_init(|r: &mut StockLineRow| r.cost_price_per_pack = cost_price_per_pack)
+            }),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn stocktake_variance() {
+        let stocktake = inline_init(|r: &mut StocktakeRow| {
+            r.id = "stocktake".to_string();
+            r.variance_approval_threshold = Some(50.0);
+        });
+        let lines = vec![
+            // surplus of 2 packs at the stock line cost price
+            line("surplus", 10, Some(12), None, Some(10.0)),
+            // deficit of 4 packs, line cost price takes precedence
+            line("deficit", 10, Some(6), Some(5.0), Some(10.0)),
+            // not counted
+            line("uncounted", 10, None, None, Some(10.0)),
+        ];
+
+        let variance = generate_stocktake_variance(&stocktake, lines.clone());
+        let differences: Vec<(i32, f64)> = variance
+            .lines
+            .iter()
+            .map(|line| (line.difference_number_of_packs, line.difference_value))
+            .collect();
+        assert_eq!(differences, vec![(2, 20.0), (-4, -20.0), (0, 0.0)]);
+        assert_eq!(variance.total_difference_value, 0.0);
+        assert_eq!(variance.total_absolute_difference_value, 40.0);
+        assert_eq!(variance.requires_approval, false);
+        assert_eq!(variance.is_approved, false);
+
+        // total absolute value exceeds threshold
+        let stocktake = inline_init(|r: &mut StocktakeRow| {
+            r.variance_approval_threshold = Some(30.0);
+        });
+        let variance = generate_stocktake_variance(&stocktake, lines.clone());
+        assert_eq!(
+            variance.lines.iter().any(|line| line.exceeds_threshold),
+            false
+        );
+        assert_eq!(variance.requires_approval, true);
+
+        // single line exceeds threshold
+        let stocktake = inline_init(|r: &mut StocktakeRow| {
+            r.variance_approval_threshold = Some(15.0);
+        });
+        let variance = generate_stocktake_variance(&stocktake, lines.clone());
+        assert_eq!(
+            variance
+                .lines
+                .iter()
+                .map(|line| line.exceeds_threshold)
+                .collect::<Vec<bool>>(),
+            vec![true, true, false]
+        );
+        assert_eq!(variance.requires_approval, true);
+
+        // no threshold
+        let variance = generate_stocktake_variance(&StocktakeRow::default(), lines.clone());
+        assert_eq!(variance.requires_approval, false);
+        assert_eq!(variance.is_blind_count, false);
+
+        // blind count hides the differences until the review
+        let stocktake = inline_init(|r: &mut StocktakeRow| r.is_blind_count = true);
+        let variance = generate_stocktake_variance(&stocktake, lines.clone());
+        assert_eq!(variance.is_blind_count, true);
+        let stocktake = inline_init(|r: &mut StocktakeRow| {
+            r.is_blind_count = true;
+            r.status = StocktakeStatus::Review;
+        });
+        let variance = generate_stocktake_variance(&stocktake, lines.clone());
+        assert_eq!(variance.is_blind_count, false);
+        let stocktake = inline_init(|r: &mut StocktakeRow| {
+            r.is_blind_count = true;
+            r.status = StocktakeStatus::Finalised;
+        });
+        let variance = generate_stocktake_variance(&stocktake, lines);
+        assert_eq!(variance.is_blind_count, false);
+    }
+
+    #[actix_rt::test]
+    async fn get_stocktake_variance_errors() {
+        let (_, _, connection_manager, _) =
+            setup_all("get_stocktake_variance_errors", MockDataInserts::all()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stocktake_service;
+
+        assert_eq!(
+            service.get_stocktake_variance(&context, "store_a", "invalid"),
+            Err(GetStocktakeVarianceError::StocktakeDoesNotExist)
+        );
+        assert_eq!(
+            service.get_stocktake_variance(&context, "invalid", "stocktake_a"),
+            Err(GetStocktakeVarianceError::InvalidStore)
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use chrono::Utc;
+use repository::{
+    RepositoryError, StocktakeLine, StocktakeLineCountRow, StocktakeLineCountRowRepository,
+    StocktakeLineRow, StocktakeLineRowRepository, StocktakeRow, StorageConnection,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::ServiceContext,
+    stocktake::validate::{
+        check_stocktake_exist, check_stocktake_not_finalised, check_stocktake_not_in_review,
+    },
+    stocktake_line::{query::get_stocktake_line, validate::check_stocktake_line_exist},
+    u32_to_i32,
+    validate::check_store_id_matches,
+};
+
+#[derive(Default, Debug, Clone)]
+pub struct InsertStocktakeLineCount {
+    pub stocktake_line_id: String,
+    pub counted_number_of_packs: u32,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InsertStocktakeLineCountError {
+    DatabaseError(RepositoryError),
+    InternalError(String),
+    InvalidStore,
+    StocktakeLineDoesNotExist,
+    CannotEditFinalised,
+    StocktakeIsLocked,
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &InsertStocktakeLineCount,
+) -> Result<(StocktakeRow, StocktakeLineRow), InsertStocktakeLineCountError> {
+    let stocktake_line = match check_stocktake_line_exist(connection, &input.stocktake_line_id)? {
+        Some(stocktake_line) => stocktake_line,
+        None => return Err(InsertStocktakeLineCountError::StocktakeLineDoesNotExist),
+    };
+    let stocktake = match check_stocktake_exist(connection, &stocktake_line.stocktake_id)? {
+        Some(stocktake) => stocktake,
+        None => {
+            return Err(InsertStocktakeLineCountError::InternalError(
+                "Orphan stocktake line!".to_string(),
+            ))
+        }
+    };
+    if !check_stocktake_not_finalised(&stocktake.status) {
+        return Err(InsertStocktakeLineCountError::CannotEditFinalised);
+    }
+
+    if stocktake.is_locked || !check_stocktake_not_in_review(&stocktake.status) {
+        return Err(InsertStocktakeLineCountError::StocktakeIsLocked);
+    }
+
+    if !check_store_id_matches(store_id, &stocktake.store_id) {
+        return Err(InsertStocktakeLineCountError::InvalidStore);
+    }
+
+    Ok((stocktake, stocktake_line))
+}
+
+/// Records the user's count (replacing a previous count by the same user) and reconciles the
+/// line: if all counts are within the stocktake's recount tolerance the line takes the new count,
+/// otherwise the line is flagged for a recount.
+fn generate(
+    user_id: &str,
+    stocktake: StocktakeRow,
+    existing_line: StocktakeLineRow,
+    existing_counts: Vec<StocktakeLineCountRow>,
+    InsertStocktakeLineCount {
+        stocktake_line_id,
+        counted_number_of_packs,
+    }: InsertStocktakeLineCount,
+) -> (StocktakeLineCountRow, StocktakeLineRow) {
+    let counted_number_of_packs = u32_to_i32(counted_number_of_packs);
+    let id = existing_counts
+        .iter()
+        .find(|count| count.user_id == user_id)
+        .map(|count| count.id.clone())
+        .unwrap_or_else(uuid);
+
+    let count = StocktakeLineCountRow {
+        id,
+        stocktake_line_id,
+        user_id: user_id.to_string(),
+        counted_number_of_packs,
+        counted_datetime: Utc::now().naive_utc(),
+    };
+
+    let counts: Vec<StocktakeLineCountRow> = existing_counts
+        .into_iter()
+        .filter(|existing| existing.user_id != user_id)
+        .chain(std::iter::once(count.clone()))
+        .collect();
+    let line = reconcile_counts(stocktake.recount_tolerance, existing_line, &counts);
+
+    (count, line)
+}
+
+/// Reconciles the line with its counts: if all counts are within the recount tolerance the line
+/// takes the latest count, otherwise it keeps its counted number of packs and is flagged for a
+/// recount. Lines without counts are returned unchanged.
+pub(crate) fn reconcile_counts(
+    recount_tolerance: i32,
+    line: StocktakeLineRow,
+    counts: &[StocktakeLineCountRow],
+) -> StocktakeLineRow {
+    let latest = match counts.iter().max_by_key(|count| count.counted_datetime) {
+        Some(latest) => latest,
+        None => return line,
+    };
+    let (min, max) = counts
+        .iter()
+        .fold((i32::MAX, i32::MIN), |(min, max), count| {
+            (
+                min.min(count.counted_number_of_packs),
+                max.max(count.counted_number_of_packs),
+            )
+        });
+    let needs_recount = max - min > recount_tolerance;
+
+    StocktakeLineRow {
+        counted_number_of_packs: if needs_recount {
+            line.counted_number_of_packs
+        } else {
+            Some(latest.counted_number_of_packs)
+        },
+        needs_recount,
+        ..line
+    }
+}
+
+pub fn insert_stocktake_line_count(
+    ctx: &ServiceContext,
+    store_id: &str,
+    user_id: &str,
+    input: InsertStocktakeLineCount,
+) -> Result<StocktakeLine, InsertStocktakeLineCountError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let (stocktake, existing_line) = validate(connection, store_id, &input)?;
+            let count_repo = StocktakeLineCountRowRepository::new(connection);
+            let existing_counts =
+                count_repo.find_many_by_stocktake_line_ids(&[existing_line.id.clone()])?;
+            let (new_count, new_line) =
+                generate(user_id, stocktake, existing_line, existing_counts, input);
+            count_repo.upsert_one(&new_count)?;
+            StocktakeLineRowRepository::new(connection).upsert_one(&new_line)?;
+
+            let line = get_stocktake_line(ctx, new_line.id)?;
+            line.ok_or(InsertStocktakeLineCountError::InternalError(
+                "Failed to read the just counted stocktake line!".to_string(),
+            ))
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for InsertStocktakeLineCountError {
+    fn from(error: RepositoryError) -> Self {
+        InsertStocktakeLineCountError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod stocktake_line_count_test {
+    use repository::{
+        mock::{
+            mock_stocktake_a, mock_stocktake_line_a, mock_stocktake_line_finalised, mock_store_a,
+            mock_user_account_a, mock_user_account_b, MockDataInserts,
+        },
+        test_db::setup_all,
+        StocktakeLineCountRowRepository, StocktakeRowRepository,
+    };
+    use util::inline_edit;
+
+    use crate::{
+        service_provider::ServiceProvider,
+        stocktake_line::count::{InsertStocktakeLineCount, InsertStocktakeLineCountError},
+    };
+
+    #[actix_rt::test]
+    async fn insert_stocktake_line_count() {
+        let (_, connection, connection_manager, _) =
+            setup_all("insert_stocktake_line_count", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.stocktake_line_service;
+        let store_a = mock_store_a();
+        let user_a = mock_user_account_a();
+        let user_b = mock_user_account_b();
+        let count = |user_id: &str, stocktake_line_id: &str, counted_number_of_packs: u32| {
+            service.insert_stocktake_line_count(
+                &context,
+                &store_a.id,
+                user_id,
+                InsertStocktakeLineCount {
+                    stocktake_line_id: stocktake_line_id.to_string(),
+                    counted_number_of_packs,
+                },
+            )
+        };
+
+        // error: StocktakeLineDoesNotExist
+        assert_eq!(
+            count(&user_a.id, "invalid", 1),
+            Err(InsertStocktakeLineCountError::StocktakeLineDoesNotExist)
+        );
+
+        // error: CannotEditFinalised
+        assert_eq!(
+            count(&user_a.id, &mock_stocktake_line_finalised().id, 1),
+            Err(InsertStocktakeLineCountError::CannotEditFinalised)
+        );
+
+        // success: first count is taken as the counted number of packs
+        let line_id = mock_stocktake_line_a().id;
+        let result = count(&user_a.id, &line_id, 10).unwrap();
+        assert_eq!(result.line.counted_number_of_packs, Some(10));
+        assert_eq!(result.line.needs_recount, false);
+
+        // success: disagreeing count flags the line for a recount
+        let result = count(&user_b.id, &line_id, 12).unwrap();
+        assert_eq!(result.line.counted_number_of_packs, Some(10));
+        assert_eq!(result.line.needs_recount, true);
+
+        // success: recount by the same user replaces their count and reconciles the line
+        let result = count(&user_b.id, &line_id, 10).unwrap();
+        assert_eq!(result.line.counted_number_of_packs, Some(10));
+        assert_eq!(result.line.needs_recount, false);
+        let counts = StocktakeLineCountRowRepository::new(&connection)
+            .find_many_by_stocktake_line_ids(&[line_id.clone()])
+            .unwrap();
+        assert_eq!(counts.len(), 2);
+
+        // success: counts within the recount tolerance are reconciled
+        StocktakeRowRepository::new(&connection)
+            .upsert_one(&inline_edit(&mock_stocktake_a(), |mut r| {
+                r.recount_tolerance = 2;
+                r
+            }))
+            .unwrap();
+        let result = count(&user_a.id, &line_id, 12).unwrap();
+        assert_eq!(result.line.counted_number_of_packs, Some(12));
+        assert_eq!(result.line.needs_recount, false);
+    }
+}
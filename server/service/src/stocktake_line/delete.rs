@@ -1,10 +1,13 @@
 use repository::{
-    RepositoryError, StocktakeLineRowRepository, StorageConnection, TransactionError,
+    RepositoryError, StocktakeLineCountRowRepository, StocktakeLineRowRepository,
+    StorageConnection, TransactionError, VvmStatusLogRowRepository,
 };
 
 use crate::{
     service_provider::ServiceContext,
-    stocktake::validate::{check_stocktake_exist, check_stocktake_not_finalised},
+    stocktake::validate::{
+        check_stocktake_exist, check_stocktake_not_finalised, check_stocktake_not_in_review,
+    },
     stocktake_line::validate::check_stocktake_line_exist,
     validate::check_store_id_matches,
 };
@@ -38,7 +41,7 @@ fn validate(
         }
     };
 
-    if stocktake.is_locked {
+    if stocktake.is_locked || !check_stocktake_not_in_review(&stocktake.status) {
         return Err(DeleteStocktakeLineError::StocktakeIsLocked);
     }
 
@@ -60,6 +63,10 @@ pub fn delete_stocktake_line(
     ctx.connection
         .transaction_sync(|connection| {
             validate(connection, store_id, &stocktake_line_id)?;
+            StocktakeLineCountRowRepository::new(&connection)
+                .delete_by_stocktake_line_id(&stocktake_line_id)?;
+            VvmStatusLogRowRepository::new(&connection)
+                .unlink_stocktake_line(&stocktake_line_id)?;
             StocktakeLineRowRepository::new(&connection).delete(&stocktake_line_id)?;
             Ok(())
         })
@@ -8,13 +8,18 @@ use repository::{
 
 use crate::{
     service_provider::ServiceContext,
-    stocktake::validate::{check_stocktake_exist, check_stocktake_not_finalised},
+    stocktake::validate::{
+        check_stocktake_exist, check_stocktake_not_finalised, check_stocktake_not_in_review,
+    },
     stocktake_line::{
         query::get_stocktake_line,
         validate::{check_item_exists, check_location_exists},
     },
     u32_to_i32,
-    validate::check_store_id_matches,
+    validate::{
+        check_donor_exists, check_manufacturer_exists, check_store_id_matches,
+        check_vvm_status_exists,
+    },
 };
 
 #[derive(Default, Debug, Clone)]
@@ -33,6 +38,9 @@ pub struct InsertStocktakeLine {
     pub cost_price_per_pack: Option<f64>,
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -45,6 +53,9 @@ pub enum InsertStocktakeLineError {
     StockLineDoesNotExist,
     StockLineAlreadyExistsInStocktake,
     LocationDoesNotExist,
+    DonorDoesNotExist,
+    ManufacturerDoesNotExist,
+    VvmStatusDoesNotExist,
     CannotEditFinalised,
     /// Either stock line xor item must be set (not both)
     StockLineXOrItem,
@@ -131,7 +142,7 @@ fn validate(
         return Err(InsertStocktakeLineError::StocktakeLineAlreadyExists);
     }
 
-    if stocktake.is_locked {
+    if stocktake.is_locked || !check_stocktake_not_in_review(&stocktake.status) {
         return Err(InsertStocktakeLineError::StocktakeIsLocked);
     }
 
@@ -164,6 +175,24 @@ fn validate(
         }
     }
 
+    if let Some(donor_id) = &input.donor_id {
+        if !check_donor_exists(connection, donor_id)? {
+            return Err(InsertStocktakeLineError::DonorDoesNotExist);
+        }
+    }
+
+    if let Some(manufacturer_id) = &input.manufacturer_id {
+        if !check_manufacturer_exists(connection, manufacturer_id)? {
+            return Err(InsertStocktakeLineError::ManufacturerDoesNotExist);
+        }
+    }
+
+    if let Some(vvm_status_id) = &input.vvm_status_id {
+        if !check_vvm_status_exists(connection, vvm_status_id)? {
+            return Err(InsertStocktakeLineError::VvmStatusDoesNotExist);
+        }
+    }
+
     Ok((stock_line, item_id))
 }
 
@@ -184,13 +213,22 @@ fn generate(
         cost_price_per_pack,
         sell_price_per_pack,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
     }: InsertStocktakeLine,
 ) -> StocktakeLineRow {
-    let snapshot_number_of_packs = if let Some(stock_line) = stock_line {
-        stock_line.stock_line_row.total_number_of_packs
-    } else {
-        0
-    };
+    let (snapshot_number_of_packs, donor_id, manufacturer_id, vvm_status_id) =
+        if let Some(stock_line) = stock_line {
+            (
+                stock_line.stock_line_row.total_number_of_packs,
+                donor_id.or(stock_line.stock_line_row.donor_id),
+                manufacturer_id.or(stock_line.stock_line_row.manufacturer_id),
+                vvm_status_id.or(stock_line.stock_line_row.vvm_status_id),
+            )
+        } else {
+            (0, donor_id, manufacturer_id, vvm_status_id)
+        };
     StocktakeLineRow {
         id,
         stocktake_id,
@@ -206,6 +244,10 @@ fn generate(
         cost_price_per_pack,
         sell_price_per_pack,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
+        needs_recount: false,
     }
 }
 
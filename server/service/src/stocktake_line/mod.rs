@@ -6,6 +6,10 @@ use crate::{service_provider::ServiceContext, ListResult};
 pub mod query;
 pub mod validate;
 
+mod count;
+pub(crate) use self::count::reconcile_counts;
+pub use self::count::*;
+
 mod delete;
 pub use self::delete::*;
 
@@ -55,6 +59,16 @@ pub trait StocktakeLineServiceTrait: Sync + Send {
         update_stocktake_line(ctx, store_id, input)
     }
 
+    fn insert_stocktake_line_count(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        user_id: &str,
+        input: InsertStocktakeLineCount,
+    ) -> Result<StocktakeLine, InsertStocktakeLineCountError> {
+        insert_stocktake_line_count(ctx, store_id, user_id, input)
+    }
+
     fn delete_stocktake_line(
         &self,
         ctx: &ServiceContext,
@@ -5,13 +5,18 @@ use repository::{
 
 use crate::{
     service_provider::ServiceContext,
-    stocktake::validate::{check_stocktake_exist, check_stocktake_not_finalised},
+    stocktake::validate::{
+        check_stocktake_exist, check_stocktake_not_finalised, check_stocktake_not_in_review,
+    },
     stocktake_line::{
         query::get_stocktake_line,
         validate::{check_location_exists, check_stocktake_line_exist},
     },
     u32_to_i32,
-    validate::check_store_id_matches,
+    validate::{
+        check_donor_exists, check_manufacturer_exists, check_store_id_matches,
+        check_vvm_status_exists,
+    },
 };
 
 #[derive(Default, Debug, Clone)]
@@ -28,6 +33,9 @@ pub struct UpdateStocktakeLine {
     pub cost_price_per_pack: Option<f64>,
     pub sell_price_per_pack: Option<f64>,
     pub note: Option<String>,
+    pub donor_id: Option<String>,
+    pub manufacturer_id: Option<String>,
+    pub vvm_status_id: Option<String>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -37,6 +45,9 @@ pub enum UpdateStocktakeLineError {
     InvalidStore,
     StocktakeLineDoesNotExist,
     LocationDoesNotExist,
+    DonorDoesNotExist,
+    ManufacturerDoesNotExist,
+    VvmStatusDoesNotExist,
     CannotEditFinalised,
     StocktakeIsLocked,
 }
@@ -62,7 +73,7 @@ fn validate(
         return Err(UpdateStocktakeLineError::CannotEditFinalised);
     }
 
-    if stocktake.is_locked {
+    if stocktake.is_locked || !check_stocktake_not_in_review(&stocktake.status) {
         return Err(UpdateStocktakeLineError::StocktakeIsLocked);
     }
 
@@ -76,6 +87,24 @@ fn validate(
         }
     }
 
+    if let Some(donor_id) = &input.donor_id {
+        if !check_donor_exists(connection, donor_id)? {
+            return Err(UpdateStocktakeLineError::DonorDoesNotExist);
+        }
+    }
+
+    if let Some(manufacturer_id) = &input.manufacturer_id {
+        if !check_manufacturer_exists(connection, manufacturer_id)? {
+            return Err(UpdateStocktakeLineError::ManufacturerDoesNotExist);
+        }
+    }
+
+    if let Some(vvm_status_id) = &input.vvm_status_id {
+        if !check_vvm_status_exists(connection, vvm_status_id)? {
+            return Err(UpdateStocktakeLineError::VvmStatusDoesNotExist);
+        }
+    }
+
     Ok(stocktake_line)
 }
 
@@ -93,8 +122,13 @@ fn generate(
         cost_price_per_pack,
         sell_price_per_pack,
         note,
+        donor_id,
+        manufacturer_id,
+        vvm_status_id,
     }: UpdateStocktakeLine,
 ) -> Result<StocktakeLineRow, UpdateStocktakeLineError> {
+    // Setting the counted number of packs directly reconciles disagreeing counts
+    let needs_recount = counted_number_of_packs.is_none() && existing.needs_recount;
     Ok(StocktakeLineRow {
         id: existing.id,
         stocktake_id: existing.stocktake_id,
@@ -116,6 +150,10 @@ fn generate(
         cost_price_per_pack: cost_price_per_pack.or(existing.cost_price_per_pack),
         sell_price_per_pack: sell_price_per_pack.or(existing.sell_price_per_pack),
         note: note.or(existing.note),
+        donor_id: donor_id.or(existing.donor_id),
+        manufacturer_id: manufacturer_id.or(existing.manufacturer_id),
+        vvm_status_id: vvm_status_id.or(existing.vvm_status_id),
+        needs_recount,
     })
 }
 
@@ -312,6 +350,10 @@ mod stocktake_line_test {
                 expiry_date: None,
                 pack_size: None,
                 note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
+                needs_recount: false,
             }
         );
     }
@@ -0,0 +1,33 @@
+use self::{
+    query::get_store_preference,
+    update::{update_store_preference, UpdateStorePreference, UpdateStorePreferenceError},
+};
+
+use crate::service_provider::ServiceContext;
+use repository::{RepositoryError, StorePreferenceRow};
+
+pub mod query;
+pub mod update;
+
+pub trait StorePreferenceServiceTrait: Sync + Send {
+    /// Preferences of the store, defaults if they haven't been set
+    fn get_store_preference(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+    ) -> Result<StorePreferenceRow, RepositoryError> {
+        get_store_preference(&ctx.connection, store_id)
+    }
+
+    fn update_store_preference(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        input: UpdateStorePreference,
+    ) -> Result<StorePreferenceRow, UpdateStorePreferenceError> {
+        update_store_preference(ctx, store_id, input)
+    }
+}
+
+pub struct StorePreferenceService {}
+impl StorePreferenceServiceTrait for StorePreferenceService {}
@@ -0,0 +1,16 @@
+use repository::{
+    RepositoryError, StorageConnection, StorePreferenceRow, StorePreferenceRowRepository,
+};
+
+pub fn get_store_preference(
+    connection: &StorageConnection,
+    store_id: &str,
+) -> Result<StorePreferenceRow, RepositoryError> {
+    let preference = StorePreferenceRowRepository::new(connection)
+        .find_one_by_id(store_id)?
+        .unwrap_or_else(|| StorePreferenceRow {
+            id: store_id.to_string(),
+            ..Default::default()
+        });
+    Ok(preference)
+}
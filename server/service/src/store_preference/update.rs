@@ -0,0 +1,163 @@
+use repository::{
+    AllocationStrategy, LocationRowRepository, RepositoryError, StorageConnection,
+    StorePreferenceRow, StorePreferenceRowRepository,
+};
+
+use crate::{service_provider::ServiceContext, validate::check_store_exists};
+
+/// Replaces all preferences of the store
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct UpdateStorePreference {
+    pub allocation_strategy: AllocationStrategy,
+    pub pick_face_location_id: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateStorePreferenceError {
+    DatabaseError(RepositoryError),
+    InvalidStore,
+    LocationDoesNotExist,
+    LocationDoesNotBelongToCurrentStore,
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpdateStorePreference,
+) -> Result<(), UpdateStorePreferenceError> {
+    if !check_store_exists(connection, store_id)? {
+        return Err(UpdateStorePreferenceError::InvalidStore);
+    }
+    if let Some(location_id) = &input.pick_face_location_id {
+        let location = LocationRowRepository::new(connection)
+            .find_one_by_id(location_id)?
+            .ok_or(UpdateStorePreferenceError::LocationDoesNotExist)?;
+        if location.store_id != store_id {
+            return Err(UpdateStorePreferenceError::LocationDoesNotBelongToCurrentStore);
+        }
+    }
+    Ok(())
+}
+
+fn generate(
+    store_id: &str,
+    UpdateStorePreference {
+        allocation_strategy,
+        pick_face_location_id,
+    }: UpdateStorePreference,
+) -> StorePreferenceRow {
+    StorePreferenceRow {
+        id: store_id.to_string(),
+        allocation_strategy,
+        pick_face_location_id,
+    }
+}
+
+pub fn update_store_preference(
+    ctx: &ServiceContext,
+    store_id: &str,
+    input: UpdateStorePreference,
+) -> Result<StorePreferenceRow, UpdateStorePreferenceError> {
+    let result = ctx
+        .connection
+        .transaction_sync(|connection| {
+            validate(connection, store_id, &input)?;
+            let preference = generate(store_id, input);
+            StorePreferenceRowRepository::new(connection).upsert_one(&preference)?;
+            Ok(preference)
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(result)
+}
+
+impl From<RepositoryError> for UpdateStorePreferenceError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateStorePreferenceError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_location_1, mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        AllocationStrategy, StorePreferenceRow,
+    };
+
+    use crate::service_provider::ServiceProvider;
+
+    use super::{UpdateStorePreference, UpdateStorePreferenceError};
+
+    #[actix_rt::test]
+    async fn update_store_preference() {
+        let (_, _, connection_manager, _) =
+            setup_all("update_store_preference", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.store_preference_service;
+        let store_id = mock_store_a().id;
+
+        // defaults
+        assert_eq!(
+            service.get_store_preference(&context, &store_id),
+            Ok(StorePreferenceRow {
+                id: store_id.clone(),
+                allocation_strategy: AllocationStrategy::ExpiryFirst,
+                pick_face_location_id: None,
+            })
+        );
+
+        // InvalidStore
+        assert_eq!(
+            service.update_store_preference(&context, "invalid", UpdateStorePreference::default()),
+            Err(UpdateStorePreferenceError::InvalidStore)
+        );
+        // LocationDoesNotExist
+        assert_eq!(
+            service.update_store_preference(
+                &context,
+                &store_id,
+                UpdateStorePreference {
+                    allocation_strategy: AllocationStrategy::PreferredLocation,
+                    pick_face_location_id: Some("invalid".to_string()),
+                }
+            ),
+            Err(UpdateStorePreferenceError::LocationDoesNotExist)
+        );
+        // LocationDoesNotBelongToCurrentStore
+        assert_eq!(
+            service.update_store_preference(
+                &context,
+                &store_id,
+                UpdateStorePreference {
+                    allocation_strategy: AllocationStrategy::PreferredLocation,
+                    pick_face_location_id: Some("location_in_another_store".to_string()),
+                }
+            ),
+            Err(UpdateStorePreferenceError::LocationDoesNotBelongToCurrentStore)
+        );
+
+        // Success
+        let expected = StorePreferenceRow {
+            id: store_id.clone(),
+            allocation_strategy: AllocationStrategy::PreferredLocation,
+            pick_face_location_id: Some(mock_location_1().id),
+        };
+        assert_eq!(
+            service.update_store_preference(
+                &context,
+                &store_id,
+                UpdateStorePreference {
+                    allocation_strategy: AllocationStrategy::PreferredLocation,
+                    pick_face_location_id: Some(mock_location_1().id),
+                }
+            ),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            service.get_store_preference(&context, &store_id),
+            Ok(expected)
+        );
+    }
+}
@@ -1,11 +1,19 @@
-use crate::sync::Synchroniser;
+use crate::sync::{settings::SyncQuietHours, Synchroniser};
 
-use log::{error, info};
+use chrono::Local;
+use log::{error, info, warn};
 use tokio::{
     sync::mpsc::{self, error as mpsc_error, Receiver as MpscReceiver, Sender as MpscSender},
     time::Duration,
 };
 
+/// Delay before the first retry of a failed sync, doubled for every consecutive failure
+const SYNC_RETRY_MIN_DELAY_SEC: u64 = 15;
+/// Upper limit for the delay between retries of a failing sync
+const SYNC_RETRY_MAX_DELAY_SEC: u64 = 60 * 60;
+/// How often to check if an unreachable central server is back online
+const CENTRAL_SERVER_ONLINE_CHECK_INTERVAL_SEC: u64 = 30;
+
 pub fn get_sync_actors() -> (SyncSenderActor, SyncReceiverActor) {
     // We use a single-element channel so that we can only have one sync pending at a time.
     // We consume this at the *start* of sync, so we could schedule a sync while syncing.
@@ -19,6 +27,7 @@ pub fn get_sync_actors() -> (SyncSenderActor, SyncReceiverActor) {
     (sync_sender, sync_receiver)
 }
 
+/// Triggers a sync outside of the schedule, e.g. a manual sync from the UI
 #[derive(Clone)]
 pub struct SyncSenderActor {
     sender: MpscSender<()>,
@@ -37,35 +46,192 @@ impl SyncSenderActor {
             Ok(_) => {}
         };
     }
+}
 
-    pub async fn schedule_send(&mut self, interval_duration: Duration) {
-        loop {
-            // This implementation is purely tick-based, not taking into account how long sync
-            // takes, whether manual sync has been triggered and so the schedule should be
-            // adjusted, whether it failed and should be tried again sooner, &c. If you want to
-            // take any of these into account, create another channel from sync scheduler.
-            tokio::time::sleep(interval_duration).await;
-            self.send();
+/// State used to work out when the next scheduled sync should happen
+struct SyncSchedule {
+    interval: Duration,
+    quiet_hours: Option<SyncQuietHours>,
+    consecutive_failures: u32,
+    is_offline: bool,
+}
+
+impl SyncSchedule {
+    fn new(interval: Duration, quiet_hours: Option<SyncQuietHours>) -> Self {
+        SyncSchedule {
+            interval,
+            quiet_hours,
+            consecutive_failures: 0,
+            is_offline: false,
+        }
+    }
+
+    /// Delay until the next scheduled sync (or online check while the central server is
+    /// unreachable), not taking quiet hours into account
+    fn next_delay(&self) -> Duration {
+        if self.is_offline {
+            return Duration::from_secs(CENTRAL_SERVER_ONLINE_CHECK_INTERVAL_SEC);
+        }
+        if self.consecutive_failures == 0 {
+            return self.interval;
+        }
+        let exponent = (self.consecutive_failures - 1).min(16);
+        let delay_sec = (SYNC_RETRY_MIN_DELAY_SEC << exponent).min(SYNC_RETRY_MAX_DELAY_SEC);
+        Duration::from_secs(delay_sec)
+    }
+
+    /// Remaining quiet hours at `now`, scheduled syncs are postponed until then
+    fn quiet_hours_remaining(&self, now: &chrono::NaiveTime) -> Option<Duration> {
+        self.quiet_hours
+            .as_ref()
+            .and_then(|quiet_hours| quiet_hours.remaining(now))
+            .and_then(|remaining| remaining.to_std().ok())
+    }
+
+    fn is_offline(&self) -> bool {
+        self.is_offline
+    }
+
+    fn sync_finished(&mut self, result: &anyhow::Result<()>) {
+        match result {
+            Ok(_) => {
+                self.consecutive_failures = 0;
+                self.is_offline = false;
+            }
+            // Connection failures pause sync until the central server is back, there is no point
+            // in counting them towards the backoff
+            Err(error) if is_connection_error(error) => {
+                self.consecutive_failures = 0;
+                self.is_offline = true;
+            }
+            Err(_) => {
+                self.consecutive_failures += 1;
+                self.is_offline = false;
+            }
         }
     }
 }
+
+/// Returns true if the error was caused by the central server being unreachable
+fn is_connection_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .map(|error| error.is_connect() || error.is_timeout())
+            .unwrap_or(false)
+    })
+}
+
 pub struct SyncReceiverActor {
     receiver: MpscReceiver<()>,
 }
 
 #[allow(unused_assignments)]
 impl SyncReceiverActor {
-    // Listen for incoming sync messages.
-    pub async fn listen(&mut self, synchroniser: &Synchroniser) {
-        while let Some(()) = self.receiver.recv().await {
+    /// Runs scheduled syncs and syncs triggered through the [SyncSenderActor].
+    ///
+    /// Failed syncs are retried with an exponential backoff. While the central server is
+    /// unreachable scheduled syncs are paused and resumed as soon as it is back online. Scheduled
+    /// syncs are skipped during quiet hours, triggered syncs always run. The schedule restarts
+    /// after every sync.
+    pub async fn listen(
+        &mut self,
+        synchroniser: &Synchroniser,
+        interval: Duration,
+        quiet_hours: Option<SyncQuietHours>,
+    ) {
+        let mut schedule = SyncSchedule::new(interval, quiet_hours);
+        let mut delay = schedule.next_delay();
+        loop {
+            let is_triggered = tokio::select! {
+                Some(()) = self.receiver.recv() => true,
+                _ = tokio::time::sleep(delay) => false,
+            };
+
+            if !is_triggered {
+                if let Some(remaining) = schedule.quiet_hours_remaining(&Local::now().time()) {
+                    info!("Sync quiet hours, postponing sync by {:?}", remaining);
+                    delay = remaining;
+                    continue;
+                }
+
+                if schedule.is_offline() && !synchroniser.is_central_server_reachable().await {
+                    delay = schedule.next_delay();
+                    continue;
+                }
+            }
+
             info!("Starting sync...");
-            if let Err(error) = synchroniser.sync().await {
-                error!("Sync encountered an error!");
-                error!("{:?}", error);
-            } else {
-                info!("Finished sync!");
+            let result = synchroniser.sync().await;
+            match &result {
+                Ok(_) => info!("Finished sync!"),
+                Err(error) => {
+                    error!("Sync encountered an error!");
+                    error!("{:?}", error);
+                }
             }
+            schedule.sync_finished(&result);
+            if schedule.is_offline() {
+                warn!("Central server is unreachable, pausing sync until it is back online");
+            }
+            delay = schedule.next_delay();
         }
-        unreachable!("Sync receiver has stopped listening as channel has closed");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveTime;
+    use tokio::time::Duration;
+
+    use crate::sync::settings::SyncQuietHours;
+
+    use super::SyncSchedule;
+
+    #[test]
+    fn sync_schedule() {
+        let mut schedule = SyncSchedule::new(
+            Duration::from_secs(300),
+            Some(SyncQuietHours {
+                start: NaiveTime::from_hms(22, 0, 0),
+                end: NaiveTime::from_hms(6, 0, 0),
+            }),
+        );
+        assert_eq!(schedule.next_delay(), Duration::from_secs(300));
+
+        // backoff
+        schedule.sync_finished(&Err(anyhow::anyhow!("error")));
+        assert_eq!(schedule.next_delay(), Duration::from_secs(15));
+        schedule.sync_finished(&Err(anyhow::anyhow!("error")));
+        assert_eq!(schedule.next_delay(), Duration::from_secs(30));
+        schedule.sync_finished(&Err(anyhow::anyhow!("error")));
+        assert_eq!(schedule.next_delay(), Duration::from_secs(60));
+        for _ in 0..20 {
+            schedule.sync_finished(&Err(anyhow::anyhow!("error")));
+        }
+        assert_eq!(schedule.next_delay(), Duration::from_secs(60 * 60));
+        assert!(!schedule.is_offline());
+
+        // back to the interval after a successful sync
+        schedule.sync_finished(&Ok(()));
+        assert_eq!(schedule.next_delay(), Duration::from_secs(300));
+
+        // quiet hours spanning midnight
+        assert_eq!(
+            schedule.quiet_hours_remaining(&NaiveTime::from_hms(23, 0, 0)),
+            Some(Duration::from_secs(7 * 60 * 60))
+        );
+        assert_eq!(
+            schedule.quiet_hours_remaining(&NaiveTime::from_hms(5, 30, 0)),
+            Some(Duration::from_secs(30 * 60))
+        );
+        assert_eq!(
+            schedule.quiet_hours_remaining(&NaiveTime::from_hms(6, 0, 0)),
+            None
+        );
+        assert_eq!(
+            schedule.quiet_hours_remaining(&NaiveTime::from_hms(12, 0, 0)),
+            None
+        );
     }
 }
@@ -1,11 +1,14 @@
 use crate::{
     apis::sync_api_v5::{CentralSyncBatchV5, CentralSyncRecordV5, SyncApiV5, SyncConnectionError},
-    sync::translation_central::{import_sync_records, TRANSLATION_RECORDS},
+    sync::{
+        sync_logger::SyncLogger,
+        translation_central::{import_sync_records, TRANSLATION_RECORDS},
+    },
 };
 use log::info;
 use repository::{
     CentralSyncBufferRepository, CentralSyncBufferRow, KeyValueStoreRepository, KeyValueType,
-    RepositoryError, StorageConnection, TransactionError,
+    RepositoryError, StorageConnection, SyncLogStage, TransactionError,
 };
 use thiserror::Error;
 
@@ -27,6 +30,8 @@ pub enum CentralSyncError {
     RemoveCentralSyncBufferRecordsError { source: RepositoryError },
     #[error("Failed to connect to DB - {source:?}")]
     DBConnectionError { source: RepositoryError },
+    #[error("Failed to update sync log - {source:?}")]
+    UpdateSyncLogError { source: RepositoryError },
 }
 
 impl CentralSyncError {
@@ -42,6 +47,7 @@ impl CentralDataSynchroniser {
     async fn pull_central_records(
         &self,
         connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
     ) -> Result<(), CentralSyncError> {
         let central_sync_cursor = CentralSyncPullCursor::new(&connection);
         let mut cursor: u32 = central_sync_cursor.get_cursor().unwrap_or_else(|_| {
@@ -69,9 +75,10 @@ impl CentralDataSynchroniser {
                 break;
             }
 
+            let number_of_records = central_sync_records.len() as u32;
             info!(
                 "Inserting {} central sync records into central sync buffer",
-                central_sync_records.len()
+                number_of_records
             );
 
             for central_sync_record in central_sync_records {
@@ -86,6 +93,12 @@ impl CentralDataSynchroniser {
             cursor = central_sync_cursor
                 .get_cursor()
                 .map_err(|source| CentralSyncError::GetCentralSyncCursorRecordError { source })?;
+            logger
+                .pulled_central(
+                    number_of_records,
+                    sync_batch.max_cursor.saturating_sub(cursor + 1),
+                )
+                .map_err(|source| CentralSyncError::UpdateSyncLogError { source })?;
 
             if cursor >= sync_batch.max_cursor - 1 {
                 info!("All central sync records pulled successfully");
@@ -114,9 +127,41 @@ impl CentralDataSynchroniser {
         Ok(result?)
     }
 
-    pub async fn integrate_central_records(
+    /// Id of the last central record that has been pulled
+    pub fn pull_cursor(connection: &StorageConnection) -> Result<u32, RepositoryError> {
+        CentralSyncPullCursor::new(connection).get_cursor()
+    }
+
+    /// Inserts records that are newer than the pull cursor, e.g. from an offline sync file that
+    /// has been partially imported before. Returns the number of inserted records.
+    pub fn insert_new_records(
         connection: &StorageConnection,
-    ) -> Result<(), CentralSyncError> {
+        central_sync_buffer_rows: &Vec<CentralSyncBufferRow>,
+    ) -> Result<u32, RepositoryError> {
+        let result: Result<u32, TransactionError<RepositoryError>> =
+            connection.transaction_sync(|con| {
+                let cursor_repo = CentralSyncPullCursor::new(con);
+                let cursor = cursor_repo.get_cursor()?;
+                let new_rows: Vec<CentralSyncBufferRow> = central_sync_buffer_rows
+                    .iter()
+                    .filter(|row| cursor == 0 || row.id as u32 > cursor)
+                    .cloned()
+                    .collect();
+                let max_id = match new_rows.iter().map(|row| row.id).max() {
+                    Some(max_id) => max_id,
+                    None => return Ok(0),
+                };
+                CentralSyncBufferRepository::new(con).insert_many(&new_rows)?;
+                cursor_repo.update_cursor(max_id as u32)?;
+                Ok(new_rows.len() as u32)
+            });
+        Ok(result?)
+    }
+
+    /// Returns the number of integrated records
+    pub fn integrate_central_records(
+        connection: &StorageConnection,
+    ) -> Result<u32, CentralSyncError> {
         let central_sync_buffer_repository = CentralSyncBufferRepository::new(&connection);
 
         let mut records: Vec<CentralSyncBufferRow> = Vec::new();
@@ -128,7 +173,6 @@ impl CentralDataSynchroniser {
 
             let mut buffer_rows = central_sync_buffer_repository
                 .get_sync_entries(table_name)
-                .await
                 .map_err(|source| CentralSyncError::GetCentralSyncBufferRecordsError { source })?;
 
             info!(
@@ -142,30 +186,38 @@ impl CentralDataSynchroniser {
 
         info!("Importing {} central sync buffer records...", records.len());
         import_sync_records(connection, &records)
-            .await
             .map_err(|source| CentralSyncError::ImportCentralSyncRecordsError { source })?;
         info!("Successfully Imported central sync buffer records",);
 
         info!("Clearing central sync buffer");
         central_sync_buffer_repository
             .remove_all()
-            .await
             .map_err(|source| CentralSyncError::RemoveCentralSyncBufferRecordsError { source })?;
         info!("Successfully cleared central sync buffer");
 
-        Ok(())
+        Ok(records.len() as u32)
     }
 
     pub async fn pull_and_integrate_records(
         &self,
         connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
     ) -> Result<(), CentralSyncError> {
         info!("Syncing central records...");
-        self.pull_central_records(connection).await?;
+        logger
+            .stage(SyncLogStage::CentralPull)
+            .map_err(|source| CentralSyncError::UpdateSyncLogError { source })?;
+        self.pull_central_records(connection, logger).await?;
         info!("Successfully synced central records");
 
         info!("Integrating central records...");
-        CentralDataSynchroniser::integrate_central_records(connection).await?;
+        logger
+            .stage(SyncLogStage::Integrate)
+            .map_err(|source| CentralSyncError::UpdateSyncLogError { source })?;
+        let number_of_records = CentralDataSynchroniser::integrate_central_records(connection)?;
+        logger
+            .integrated(number_of_records)
+            .map_err(|source| CentralSyncError::UpdateSyncLogError { source })?;
         info!("Successfully integrated central records");
 
         Ok(())
@@ -256,7 +308,6 @@ mod tests {
             .expect("Failed to insert central sync records into sync buffer");
 
         CentralDataSynchroniser::integrate_central_records(&connection)
-            .await
             .expect("Failed to integrate central records");
 
         check_records_against_database(&connection, test_records).await;
@@ -0,0 +1,1141 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use log::info;
+use repository::{
+    CentralSyncBufferRepository, CentralSyncBufferRow, ChangelogRow, ChangelogRowRepository,
+    ChangelogTableName, InvoiceLineRowRepository, InvoiceRowRepository, LocationRowRepository,
+    NumberRowRepository, RemoteSyncBufferAction, RemoteSyncBufferRepository, RemoteSyncBufferRow,
+    RepositoryError, RequisitionLineRowRepository, RequisitionRowRepository,
+    StockLineRowRepository, StocktakeLineRowRepository, StocktakeRowRepository, StorageConnection,
+    StoreRowRepository, SyncSiteChangelogRow, SyncSiteChangelogRowRepository, SyncSiteRow,
+    SyncSiteRowRepository,
+};
+use thiserror::Error;
+use util::hash::{constant_time_eq, sha256};
+
+use crate::apis::{
+    sync_api_v3::{RemotePostRecordV3, SyncTypeV3},
+    sync_api_v5::{
+        CentralSyncBatchV5, CentralSyncRecordV5, RemoteSyncActionV5, RemoteSyncBatchV5,
+        RemoteSyncRecordV5, StoreIntegrityV5,
+    },
+};
+
+use super::{
+    offline_sync::{
+        read_offline_sync_file, write_offline_sync_file, OfflineSyncDataV5, OfflineSyncError,
+    },
+    remote_data_synchroniser::RemoteDataSynchroniser,
+    settings::CentralServerSettings,
+    sync_integrity::{
+        integrity_range_records, integrity_table, store_integrity, SyncIntegrityError,
+    },
+    translation_central::{import_sync_records, TRANSLATION_RECORDS},
+    translation_remote::{
+        parse_number_name,
+        push::{translate_changelog_for_remote_site, PushRecord},
+        table_name_from_central, TRANSLATION_RECORD_NUMBER,
+    },
+    SyncTranslationError,
+};
+
+/// Number of changelogs that are translated at a time when collecting the queue of a site
+const QUEUE_SCAN_BATCH_SIZE: u32 = 1000;
+/// Max number of queued and of central records in an offline sync file for a site, the remaining
+/// records follow with the next file
+const OFFLINE_SYNC_FILE_LIMIT: u32 = 100000;
+
+#[derive(Error, Debug)]
+pub enum CentralServerError {
+    #[error("Invalid site credentials")]
+    Unauthorised,
+    #[error("Site has been initialised on a different device")]
+    HardwareIdMismatch,
+    #[error("Site hasn't been initialised")]
+    SiteNotInitialised,
+    #[error("Records must be pushed from site {site_id} to site {central_site_id}")]
+    InvalidPushSites { site_id: i32, central_site_id: u32 },
+    #[error("Invalid sync request: {0}")]
+    InvalidRequest(String),
+    #[error("Failed to translate changelog")]
+    TranslationError(#[from] SyncTranslationError),
+    #[error("Failed to integrate sync records")]
+    IntegrationError(#[source] anyhow::Error),
+    #[error("Failed to calculate sync integrity")]
+    IntegrityError(#[from] SyncIntegrityError),
+    #[error(transparent)]
+    OfflineSyncError(#[from] OfflineSyncError),
+    #[error("Database error")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+/// Adds a remote site or updates the credentials of an existing site
+pub fn upsert_sync_site(
+    connection: &StorageConnection,
+    site_id: u32,
+    username: &str,
+    password: &str,
+) -> Result<SyncSiteRow, RepositoryError> {
+    let repo = SyncSiteRowRepository::new(connection);
+    let site = match repo.find_one_by_id(site_id as i32)? {
+        Some(site) => SyncSiteRow {
+            name: username.to_string(),
+            password_sha256: sha256(password),
+            ..site
+        },
+        None => SyncSiteRow {
+            id: site_id as i32,
+            name: username.to_string(),
+            password_sha256: sha256(password),
+            ..Default::default()
+        },
+    };
+    repo.upsert_one(&site)?;
+    Ok(site)
+}
+
+/// Checks the credentials of a site. Once initialised a site can only sync from the same device.
+pub fn authenticate_site(
+    connection: &StorageConnection,
+    site_id: u32,
+    password_sha256: &str,
+    hardware_id: &str,
+) -> Result<SyncSiteRow, CentralServerError> {
+    let site = SyncSiteRowRepository::new(connection)
+        .find_one_by_id(site_id as i32)?
+        .ok_or(CentralServerError::Unauthorised)?;
+    if !constant_time_eq(&site.password_sha256, password_sha256) {
+        return Err(CentralServerError::Unauthorised);
+    }
+    match &site.hardware_id {
+        Some(site_hardware_id) if site_hardware_id != hardware_id => {
+            Err(CentralServerError::HardwareIdMismatch)
+        }
+        _ => Ok(site),
+    }
+}
+
+/// Registers the device of the site and queues all remote records of the site again
+pub fn initialise_site(
+    connection: &StorageConnection,
+    site: SyncSiteRow,
+    hardware_id: &str,
+) -> Result<RemoteSyncBatchV5, CentralServerError> {
+    info!("Central server: Initialise site {}", site.id);
+    SyncSiteRowRepository::new(connection).upsert_one(&SyncSiteRow {
+        hardware_id: Some(hardware_id.to_string()),
+        queue_cursor: 0,
+        initialised_datetime: Some(Utc::now().naive_utc()),
+        // The offline sync state of the site starts over as well
+        offline_import_sequence: 0,
+        offline_central_cursor: 0,
+        ..site
+    })?;
+    let queue_length = ChangelogRowRepository::new(connection).count(0)?;
+    Ok(RemoteSyncBatchV5 {
+        queue_length: queue_length as u32,
+        data: None,
+    })
+}
+
+/// Stores and names of the stores of a site
+struct SiteStores {
+    store_ids: HashSet<String>,
+    name_ids: HashSet<String>,
+}
+
+impl SiteStores {
+    fn load(connection: &StorageConnection, site_id: i32) -> Result<Self, RepositoryError> {
+        let stores: Vec<_> = StoreRowRepository::new(connection)
+            .all()?
+            .into_iter()
+            .filter(|store| store.site_id == site_id)
+            .collect();
+        Ok(SiteStores {
+            store_ids: stores.iter().map(|store| store.id.clone()).collect(),
+            name_ids: stores.into_iter().map(|store| store.name_id).collect(),
+        })
+    }
+}
+
+/// Store of an existing row and, for transfers, the name of the other party
+fn row_owner(
+    connection: &StorageConnection,
+    table_name: &ChangelogTableName,
+    row_id: &str,
+) -> Result<Option<(String, Option<String>)>, RepositoryError> {
+    let invoice_owner = |invoice_id: &str| -> Result<_, RepositoryError> {
+        Ok(InvoiceRowRepository::new(connection)
+            .find_one_by_id_option(invoice_id)?
+            .map(|invoice| (invoice.store_id, Some(invoice.name_id))))
+    };
+    let requisition_owner = |requisition_id: &str| -> Result<_, RepositoryError> {
+        Ok(RequisitionRowRepository::new(connection)
+            .find_one_by_id(requisition_id)?
+            .map(|requisition| (requisition.store_id, Some(requisition.name_id))))
+    };
+
+    let stocktake_owner = |stocktake_id: &str| -> Result<_, RepositoryError> {
+        Ok(StocktakeRowRepository::new(connection)
+            .find_one_by_id(stocktake_id)?
+            .map(|stocktake| (stocktake.store_id, None)))
+    };
+
+    let owner = match table_name {
+        ChangelogTableName::Invoice => invoice_owner(row_id)?,
+        ChangelogTableName::InvoiceLine => {
+            match InvoiceLineRowRepository::new(connection).find_one_by_id_option(row_id)? {
+                Some(line) => invoice_owner(&line.invoice_id)?,
+                None => None,
+            }
+        }
+        ChangelogTableName::Requisition => requisition_owner(row_id)?,
+        ChangelogTableName::RequisitionLine => {
+            match RequisitionLineRowRepository::new(connection).find_one_by_id(row_id)? {
+                Some(line) => requisition_owner(&line.requisition_id)?,
+                None => None,
+            }
+        }
+        ChangelogTableName::Stocktake => stocktake_owner(row_id)?,
+        ChangelogTableName::StocktakeLine => {
+            match StocktakeLineRowRepository::new(connection).find_one_by_id(row_id)? {
+                Some(line) => stocktake_owner(&line.stocktake_id)?,
+                None => None,
+            }
+        }
+        ChangelogTableName::Location => LocationRowRepository::new(connection)
+            .find_one_by_id(row_id)?
+            .map(|location| (location.store_id, None)),
+        ChangelogTableName::Number => NumberRowRepository::new(connection)
+            .find_one_by_id(row_id)?
+            .map(|number| (number.store_id, None)),
+        ChangelogTableName::StockLine => StockLineRowRepository::new(connection)
+            .find_many_by_ids(&[row_id.to_string()])?
+            .pop()
+            .map(|stock_line| (stock_line.store_id, None)),
+        _ => None,
+    };
+    Ok(owner)
+}
+
+/// Records are routed to the site of their store, and transfers also to the site of the other
+/// party. Records without a store, e.g. names, and deletions go to all sites.
+fn is_queued_for_site(
+    connection: &StorageConnection,
+    changelog: &ChangelogRow,
+    record: &PushRecord,
+    site_stores: &SiteStores,
+) -> Result<bool, RepositoryError> {
+    let record = match record {
+        // The row doesn't exist anymore, i.e. the site of the record can't be determined
+        PushRecord::Delete(_) => return Ok(true),
+        PushRecord::Upsert(record) => record,
+    };
+    let (store_id, other_party_name_id) =
+        match row_owner(connection, &changelog.table_name, &changelog.row_id)? {
+            Some((store_id, name_id)) => (Some(store_id), name_id),
+            None => (record.store_id.clone(), None),
+        };
+    let store_id = match store_id {
+        Some(store_id) => store_id,
+        None => return Ok(true),
+    };
+    Ok(site_stores.store_ids.contains(&store_id)
+        || other_party_name_id
+            .map(|name_id| site_stores.name_ids.contains(&name_id))
+            .unwrap_or(false))
+}
+
+/// Returns the next records of the remote queue of a site. The queue contains the latest version
+/// of all rows that changed since the last acknowledged record, apart from changes that have been
+/// pushed by the site itself.
+///
+/// The returned queue length is an upper bound, it only becomes exact once the end of the queue
+/// is reached.
+pub fn queued_records(
+    connection: &StorageConnection,
+    site: &SyncSiteRow,
+    limit: u32,
+) -> Result<RemoteSyncBatchV5, CentralServerError> {
+    if site.hardware_id.is_none() {
+        return Err(CentralServerError::SiteNotInitialised);
+    }
+    let site_stores = SiteStores::load(connection, site.id)?;
+    let changelog_repo = ChangelogRowRepository::new(connection);
+    let site_changelog_repo = SyncSiteChangelogRowRepository::new(connection);
+
+    let mut records: Vec<RemoteSyncRecordV5> = Vec::new();
+    let mut cursor = site.queue_cursor;
+    // Changelogs before the first queued record don't need to be acknowledged by the site
+    let mut unqueued_cursor = site.queue_cursor;
+    let mut is_limit_reached = false;
+    'scan: loop {
+        let mut changelogs = changelog_repo.changelogs(cursor as u64, QUEUE_SCAN_BATCH_SIZE)?;
+        if changelogs.is_empty() {
+            break;
+        }
+        changelogs.sort_by_key(|changelog| changelog.id);
+        let changelog_ids: Vec<i64> = changelogs.iter().map(|changelog| changelog.id).collect();
+        let pushed_by_site: HashSet<i64> = site_changelog_repo
+            .find_site_changelog_ids(site.id, &changelog_ids)?
+            .into_iter()
+            .collect();
+
+        for changelog in changelogs {
+            cursor = changelog.id + 1;
+            let mut site_records = Vec::new();
+            if !pushed_by_site.contains(&changelog.id) {
+                let mut push_records = Vec::new();
+                translate_changelog_for_remote_site(connection, &changelog, &mut push_records)?;
+                for record in push_records {
+                    if is_queued_for_site(connection, &changelog, &record, &site_stores)? {
+                        site_records.push(RemoteSyncRecordV5::from(record));
+                    }
+                }
+            }
+
+            if site_records.is_empty() {
+                if records.is_empty() {
+                    unqueued_cursor = cursor;
+                }
+                continue;
+            }
+            records.append(&mut site_records);
+            if records.len() as u32 >= limit {
+                is_limit_reached = true;
+                break 'scan;
+            }
+        }
+    }
+
+    if unqueued_cursor != site.queue_cursor {
+        SyncSiteRowRepository::new(connection).upsert_one(&SyncSiteRow {
+            queue_cursor: unqueued_cursor,
+            ..site.clone()
+        })?;
+    }
+    let remaining = if is_limit_reached {
+        changelog_repo.count(cursor as u64)? as u32
+    } else {
+        0
+    };
+    Ok(RemoteSyncBatchV5 {
+        queue_length: records.len() as u32 + remaining,
+        data: if records.is_empty() {
+            None
+        } else {
+            Some(records)
+        },
+    })
+}
+
+/// Removes the acknowledged records from the queue of the site
+pub fn acknowledge_records(
+    connection: &StorageConnection,
+    site: &SyncSiteRow,
+    sync_ids: &[String],
+) -> Result<(), CentralServerError> {
+    if site.hardware_id.is_none() {
+        return Err(CentralServerError::SiteNotInitialised);
+    }
+    let mut last_changelog_id = None;
+    for sync_id in sync_ids {
+        let changelog_id: i64 = sync_id.parse().map_err(|_| {
+            CentralServerError::InvalidRequest(format!("Unknown sync id {}", sync_id))
+        })?;
+        last_changelog_id = last_changelog_id.max(Some(changelog_id));
+    }
+
+    match last_changelog_id {
+        Some(last_changelog_id) if last_changelog_id >= site.queue_cursor => {
+            SyncSiteRowRepository::new(connection).upsert_one(&SyncSiteRow {
+                queue_cursor: last_changelog_id + 1,
+                ..site.clone()
+            })?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Returns central records after the `cursor`
+pub fn central_records(
+    connection: &StorageConnection,
+    cursor: u32,
+    limit: u32,
+) -> Result<CentralSyncBatchV5, CentralServerError> {
+    let repo = CentralSyncBufferRepository::new(connection);
+    let max_cursor = repo.max_id()?.unwrap_or(0) as u32 + 1;
+    let records = repo
+        .find_after(cursor as i32, limit)?
+        .into_iter()
+        .map(|row| {
+            Ok(CentralSyncRecordV5 {
+                id: row.id,
+                table_name: row.table_name,
+                record_id: row.record_id,
+                data: serde_json::from_str(&row.data).map_err(|err| {
+                    CentralServerError::InvalidRequest(format!(
+                        "Invalid central record {}: {}",
+                        row.id, err
+                    ))
+                })?,
+            })
+        })
+        .collect::<Result<Vec<_>, CentralServerError>>()?;
+
+    Ok(CentralSyncBatchV5 {
+        max_cursor,
+        data: if records.is_empty() {
+            None
+        } else {
+            Some(records)
+        },
+    })
+}
+
+/// Appends records to the central records served to remote sites and integrates them locally.
+/// Returns the number of appended records.
+pub fn append_central_records(
+    connection: &StorageConnection,
+    mut records: Vec<CentralSyncBufferRow>,
+) -> Result<u32, CentralServerError> {
+    let repo = CentralSyncBufferRepository::new(connection);
+    let max_id = repo.max_id()?.unwrap_or(0);
+    records.sort_by_key(|record| record.id);
+    for (index, record) in records.iter_mut().enumerate() {
+        record.id = max_id + 1 + index as i32;
+    }
+    repo.insert_many(&records)?;
+
+    // Integrate in the same order as a regular sync
+    let table_order = |record: &CentralSyncBufferRow| {
+        TRANSLATION_RECORDS
+            .iter()
+            .position(|table| *table == record.table_name)
+            .unwrap_or(TRANSLATION_RECORDS.len())
+    };
+    records.sort_by_key(table_order);
+    import_sync_records(connection, &records)
+        .map_err(|error| CentralServerError::IntegrationError(error.into()))?;
+
+    Ok(records.len() as u32)
+}
+
+fn validate_site_store(
+    connection: &StorageConnection,
+    site: &SyncSiteRow,
+    store_id: &str,
+) -> Result<(), CentralServerError> {
+    if site.hardware_id.is_none() {
+        return Err(CentralServerError::SiteNotInitialised);
+    }
+    if !SiteStores::load(connection, site.id)?
+        .store_ids
+        .contains(store_id)
+    {
+        return Err(CentralServerError::InvalidRequest(format!(
+            "Store {} doesn't belong to site {}",
+            store_id, site.id
+        )));
+    }
+    Ok(())
+}
+
+/// Integrity summary of the remote data of a store of the site, see
+/// [super::sync_integrity::store_integrity]
+pub fn site_store_integrity(
+    connection: &StorageConnection,
+    site: &SyncSiteRow,
+    store_id: &str,
+) -> Result<StoreIntegrityV5, CentralServerError> {
+    validate_site_store(connection, site, store_id)?;
+    Ok(store_integrity(connection, store_id)?)
+}
+
+/// Records of a store of the site in an integrity range, used by the site to re-pull records that
+/// don't match the central server
+pub fn site_integrity_records(
+    connection: &StorageConnection,
+    site: &SyncSiteRow,
+    store_id: &str,
+    table_name: &str,
+    range: &str,
+) -> Result<RemoteSyncBatchV5, CentralServerError> {
+    validate_site_store(connection, site, store_id)?;
+    let table = integrity_table(table_name).ok_or_else(|| {
+        CentralServerError::InvalidRequest(format!("Unknown integrity table {}", table_name))
+    })?;
+    let records = integrity_range_records(connection, table, store_id, range)?;
+    Ok(RemoteSyncBatchV5 {
+        queue_length: records.len() as u32,
+        data: if records.is_empty() {
+            None
+        } else {
+            Some(records)
+        },
+    })
+}
+
+/// Stores a pushed record belongs to: the store of the record, the store of the parent of a line
+/// and the store of the existing row, i.e. a site can't take over rows of other sites either.
+/// Parents can be pushed together with their lines, `pushed_stores` are the stores of the pushed
+/// records by record id.
+fn pushed_record_stores(
+    connection: &StorageConnection,
+    record: &RemotePostRecordV3,
+    pushed_stores: &HashMap<&str, &str>,
+) -> Result<Vec<String>, RepositoryError> {
+    let data_field = |field: &str| {
+        record
+            .data
+            .as_ref()
+            .and_then(|data| data.get(field))
+            .and_then(|value| value.as_str())
+    };
+    let mut store_ids: Vec<String> = record.store_id.iter().cloned().collect();
+    store_ids.extend(data_field("store_ID").map(str::to_string));
+    // Number records only name their store, e.g. `customer_invoice_number_for_store_<store_id>`
+    if record.record_type == TRANSLATION_RECORD_NUMBER {
+        if let Some((_, store_id)) =
+            data_field("name").and_then(|name| parse_number_name(name.to_string()))
+        {
+            store_ids.push(store_id);
+        }
+    }
+
+    let table_name = match table_name_from_central(&record.record_type) {
+        Some(table_name) => table_name,
+        None => return Ok(store_ids),
+    };
+    let parent = match table_name {
+        ChangelogTableName::InvoiceLine => {
+            data_field("transaction_ID").map(|invoice_id| (ChangelogTableName::Invoice, invoice_id))
+        }
+        ChangelogTableName::RequisitionLine => data_field("requisition_ID")
+            .map(|requisition_id| (ChangelogTableName::Requisition, requisition_id)),
+        ChangelogTableName::StocktakeLine => data_field("stock_take_ID")
+            .map(|stocktake_id| (ChangelogTableName::Stocktake, stocktake_id)),
+        _ => None,
+    };
+    if let Some((parent_table_name, parent_id)) = parent {
+        match pushed_stores.get(parent_id) {
+            Some(store_id) => store_ids.push(store_id.to_string()),
+            None => store_ids.extend(
+                row_owner(connection, &parent_table_name, parent_id)?.map(|(store_id, _)| store_id),
+            ),
+        }
+    }
+    store_ids.extend(
+        row_owner(connection, &table_name, &record.record_id)?.map(|(store_id, _)| store_id),
+    );
+    Ok(store_ids)
+}
+
+/// Integrates records pushed by a site. The resulting changelogs are queued for other sites, e.g.
+/// the receiving site of a transfer, but not for the pushing site.
+pub fn receive_pushed_records(
+    connection: &StorageConnection,
+    settings: &CentralServerSettings,
+    site: &SyncSiteRow,
+    from_site: u32,
+    to_site: u32,
+    records: Vec<RemotePostRecordV3>,
+) -> Result<u32, CentralServerError> {
+    if site.hardware_id.is_none() {
+        return Err(CentralServerError::SiteNotInitialised);
+    }
+    if from_site != site.id as u32 || to_site != settings.site_id {
+        return Err(CentralServerError::InvalidPushSites {
+            site_id: site.id,
+            central_site_id: settings.site_id,
+        });
+    }
+
+    let site_stores = SiteStores::load(connection, site.id)?;
+    let pushed_stores: HashMap<&str, &str> = records
+        .iter()
+        .filter_map(|record| {
+            let store_id = record.store_id.as_deref().or_else(|| {
+                record
+                    .data
+                    .as_ref()
+                    .and_then(|data| data.get("store_ID"))
+                    .and_then(|value| value.as_str())
+            })?;
+            Some((record.record_id.as_str(), store_id))
+        })
+        .collect();
+    for record in &records {
+        for store_id in pushed_record_stores(connection, record, &pushed_stores)? {
+            if !site_stores.store_ids.contains(&store_id) {
+                return Err(CentralServerError::InvalidRequest(format!(
+                    "Record {} of store {} can't be pushed by site {}",
+                    record.record_id, store_id, site.id
+                )));
+            }
+        }
+    }
+
+    let rows = records
+        .into_iter()
+        .map(|record| {
+            Ok(RemoteSyncBufferRow {
+                // Sync ids are only unique per site
+                id: format!("{}_{}", site.id, record.sync_id),
+                table_name: record.record_type,
+                record_id: record.record_id,
+                action: match record.sync_type {
+                    SyncTypeV3::Insert => RemoteSyncBufferAction::Create,
+                    SyncTypeV3::Update => RemoteSyncBufferAction::Update,
+                    SyncTypeV3::Delete => RemoteSyncBufferAction::Delete,
+                    SyncTypeV3::Merge => RemoteSyncBufferAction::Merge,
+                },
+                data: serde_json::to_string(&record.data)
+                    .map_err(|err| CentralServerError::InvalidRequest(format!("{}", err)))?,
+            })
+        })
+        .collect::<Result<Vec<_>, CentralServerError>>()?;
+    info!(
+        "Central server: Integrate {} records pushed by site {}",
+        rows.len(),
+        site.id
+    );
+
+    // Other pushes don't see the buffer rows of this push until it has been committed
+    connection
+        .transaction_sync(|con| -> Result<(), CentralServerError> {
+            let first_changelog_id = ChangelogRowRepository::new(con)
+                .latest_changelog()?
+                .map(|changelog| changelog.id + 1)
+                .unwrap_or(0);
+            RemoteSyncBufferRepository::new(con).upsert_many(&rows)?;
+            RemoteDataSynchroniser::do_integrate_records(con, None)
+                .map_err(CentralServerError::IntegrationError)?;
+
+            let record_ids: Vec<String> = rows.iter().map(|row| row.record_id.clone()).collect();
+            let site_changelog_repo = SyncSiteChangelogRowRepository::new(con);
+            for changelog in ChangelogRowRepository::new(con)
+                .changelogs_for_rows(first_changelog_id as u64, &record_ids)?
+            {
+                site_changelog_repo.upsert_one(&SyncSiteChangelogRow {
+                    changelog_id: changelog.id,
+                    site_id: site.id,
+                })?;
+            }
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+
+    Ok(rows.len() as u32)
+}
+
+/// Creates an offline sync file for a site without connectivity, containing the queued records of
+/// the site and the central records after the central cursor of the last file imported from the
+/// site.
+///
+/// Records are only removed from the queue once the site acknowledges them with an imported file,
+/// i.e. a lost file is replaced by the next one.
+pub fn export_site_offline_sync_file(
+    connection: &StorageConnection,
+    site: &SyncSiteRow,
+) -> Result<Vec<u8>, CentralServerError> {
+    let remote = queued_records(connection, site, OFFLINE_SYNC_FILE_LIMIT)?;
+    let central = central_records(
+        connection,
+        site.offline_central_cursor as u32,
+        OFFLINE_SYNC_FILE_LIMIT,
+    )?;
+    // queued_records might have moved the queue cursor
+    let site = SyncSiteRowRepository::new(connection)
+        .find_one_by_id(site.id)?
+        .unwrap_or_else(|| site.clone());
+    let sequence = site.offline_export_sequence + 1;
+    SyncSiteRowRepository::new(connection).upsert_one(&SyncSiteRow {
+        offline_export_sequence: sequence,
+        ..site.clone()
+    })?;
+    info!(
+        "Central server: Export offline sync file {} for site {}",
+        sequence, site.id
+    );
+
+    let data = OfflineSyncDataV5 {
+        sequence: sequence as u64,
+        central: Some(central),
+        remote: Some(remote),
+        acknowledged: None,
+        central_cursor: None,
+    };
+    write_offline_sync_file(site.id as u32, &site.password_sha256, &data)
+        .map_err(|error| OfflineSyncError::FileError(error).into())
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SiteOfflineSyncImport {
+    pub pushed_records: u32,
+    pub acknowledged_records: u32,
+}
+
+/// Imports an offline sync file created by a site, the pushed records are integrated as if they
+/// were pushed online and the acknowledged records are removed from the queue of the site.
+///
+/// Files have to be imported in the order they have been created by the site.
+pub fn import_site_offline_sync_file(
+    connection: &StorageConnection,
+    settings: &CentralServerSettings,
+    site: &SyncSiteRow,
+    file: &[u8],
+) -> Result<SiteOfflineSyncImport, CentralServerError> {
+    let data = read_offline_sync_file(site.id as u32, &site.password_sha256, file)?;
+    let sequence = data.sequence;
+    let central_cursor = data.central_cursor;
+    if sequence <= site.offline_import_sequence as u64 {
+        return Err(OfflineSyncError::AlreadyImported {
+            sequence,
+            last_imported_sequence: site.offline_import_sequence as u64,
+        }
+        .into());
+    }
+
+    let records: Vec<RemotePostRecordV3> = data
+        .remote
+        .and_then(|batch| batch.data)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|record| RemotePostRecordV3 {
+            sync_id: record.sync_id,
+            record_type: record.table,
+            record_id: record.record_id,
+            sync_type: match record.action {
+                RemoteSyncActionV5::Create => SyncTypeV3::Insert,
+                RemoteSyncActionV5::Update => SyncTypeV3::Update,
+                RemoteSyncActionV5::Delete => SyncTypeV3::Delete,
+                RemoteSyncActionV5::Merge => SyncTypeV3::Merge,
+            },
+            // The stores of the records are taken from the record data
+            store_id: None,
+            data: record.data,
+        })
+        .collect();
+    let sync_ids = data
+        .acknowledged
+        .map(|acknowledged| acknowledged.sync_ids)
+        .unwrap_or_default();
+
+    let result = connection
+        .transaction_sync(|con| -> Result<_, CentralServerError> {
+            let pushed_records = receive_pushed_records(
+                con,
+                settings,
+                site,
+                site.id as u32,
+                settings.site_id,
+                records,
+            )?;
+            acknowledge_records(con, site, &sync_ids)?;
+
+            let repo = SyncSiteRowRepository::new(con);
+            let site = repo
+                .find_one_by_id(site.id)?
+                .ok_or(CentralServerError::SiteNotInitialised)?;
+            repo.upsert_one(&SyncSiteRow {
+                offline_import_sequence: sequence as i64,
+                offline_central_cursor: central_cursor
+                    .map(i64::from)
+                    .unwrap_or(site.offline_central_cursor),
+                ..site
+            })?;
+            Ok(SiteOfflineSyncImport {
+                pushed_records,
+                acknowledged_records: sync_ids.len() as u32,
+            })
+        })
+        .map_err(|error| error.to_inner_error())?;
+    info!(
+        "Central server: Imported offline sync file {} of site {}",
+        sequence, site.id
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_outbound_shipment_a, mock_store_a, mock_store_b, MockDataInserts},
+        test_db::setup_all,
+        CentralSyncBufferRow, LocationRow, LocationRowRepository, NumberRow, NumberRowType,
+        StoreRow, StoreRowRepository,
+    };
+    use serde_json::json;
+    use util::{hash::sha256, inline_init};
+
+    use crate::{
+        apis::sync_api_v3::{RemotePostRecordV3, SyncTypeV3},
+        service_provider::ServiceProvider,
+        sync::{
+            offline_sync::{
+                confirm_offline_sync_export, export_offline_sync_file, import_offline_sync_file,
+            },
+            settings::{CentralServerSettings, SyncSettings},
+        },
+    };
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn central_server() {
+        let (_, connection, _, _) = setup_all("central_server", MockDataInserts::all()).await;
+        let settings = CentralServerSettings { site_id: 1 };
+
+        let store_repo = StoreRowRepository::new(&connection);
+        store_repo
+            .upsert_one(&StoreRow {
+                site_id: 2,
+                ..mock_store_a()
+            })
+            .unwrap();
+        store_repo
+            .upsert_one(&StoreRow {
+                site_id: 3,
+                ..mock_store_b()
+            })
+            .unwrap();
+        upsert_sync_site(&connection, 2, "site_a", "pass_a").unwrap();
+        upsert_sync_site(&connection, 3, "site_b", "pass_b").unwrap();
+
+        // Authentication
+        assert!(matches!(
+            authenticate_site(&connection, 2, &sha256("pass_b"), "hardware_a"),
+            Err(CentralServerError::Unauthorised)
+        ));
+        assert!(matches!(
+            authenticate_site(&connection, 4, &sha256("pass_a"), "hardware_a"),
+            Err(CentralServerError::Unauthorised)
+        ));
+        let site_a = authenticate_site(&connection, 2, &sha256("pass_a"), "hardware_a").unwrap();
+        assert!(matches!(
+            queued_records(&connection, &site_a, 100),
+            Err(CentralServerError::SiteNotInitialised)
+        ));
+        initialise_site(&connection, site_a, "hardware_a").unwrap();
+        assert!(matches!(
+            authenticate_site(&connection, 2, &sha256("pass_a"), "hardware_b"),
+            Err(CentralServerError::HardwareIdMismatch)
+        ));
+        let site_b = authenticate_site(&connection, 3, &sha256("pass_b"), "hardware_b").unwrap();
+        initialise_site(&connection, site_b, "hardware_b").unwrap();
+
+        // Drain the initial queues
+        let drain = |site_id: u32, password: &str, hardware_id: &str| {
+            let site =
+                authenticate_site(&connection, site_id, &sha256(password), hardware_id).unwrap();
+            let batch = queued_records(&connection, &site, 100000).unwrap();
+            let records = batch.data.unwrap_or_default();
+            let sync_ids: Vec<String> = records.iter().map(|r| r.sync_id.clone()).collect();
+            acknowledge_records(&connection, &site, &sync_ids).unwrap();
+            records
+        };
+        drain(2, "pass_a", "hardware_a");
+        drain(3, "pass_b", "hardware_b");
+        assert!(drain(2, "pass_a", "hardware_a").is_empty());
+
+        // Location pushed by site a is routed to neither site
+        let site_a = authenticate_site(&connection, 2, &sha256("pass_a"), "hardware_a").unwrap();
+        let pushed_location = RemotePostRecordV3 {
+            sync_id: "1".to_string(),
+            record_type: "Location".to_string(),
+            record_id: "pushed_location".to_string(),
+            sync_type: SyncTypeV3::Update,
+            store_id: Some(mock_store_a().id),
+            data: Some(json!({
+                "ID": "pushed_location",
+                "Description": "pushed",
+                "code": "pushed",
+                "hold": false,
+                "store_ID": mock_store_a().id
+            })),
+        };
+        assert!(matches!(
+            receive_pushed_records(&connection, &settings, &site_a, 3, 1, vec![]),
+            Err(CentralServerError::InvalidPushSites { .. })
+        ));
+        receive_pushed_records(&connection, &settings, &site_a, 2, 1, vec![pushed_location])
+            .unwrap();
+
+        // Records of stores of other sites are rejected
+        let location_record = |id: &str, store_id: &str| RemotePostRecordV3 {
+            sync_id: "2".to_string(),
+            record_type: "Location".to_string(),
+            record_id: id.to_string(),
+            sync_type: SyncTypeV3::Update,
+            store_id: Some(store_id.to_string()),
+            data: Some(json!({
+                "ID": id,
+                "Description": "pushed",
+                "code": "pushed",
+                "hold": false,
+                "store_ID": store_id
+            })),
+        };
+        let is_rejected = |record: RemotePostRecordV3| {
+            matches!(
+                receive_pushed_records(&connection, &settings, &site_a, 2, 1, vec![record]),
+                Err(CentralServerError::InvalidRequest(_))
+            )
+        };
+        assert!(is_rejected(location_record(
+            "other_location",
+            &mock_store_b().id
+        )));
+        // Existing location of store b
+        assert!(is_rejected(location_record(
+            "location_in_another_store",
+            &mock_store_a().id
+        )));
+        // Line of an invoice of store b
+        assert!(is_rejected(RemotePostRecordV3 {
+            sync_id: "3".to_string(),
+            record_type: "trans_line".to_string(),
+            record_id: "pushed_line".to_string(),
+            sync_type: SyncTypeV3::Update,
+            store_id: None,
+            data: Some(json!({
+                "ID": "pushed_line",
+                "transaction_ID": mock_outbound_shipment_a().id
+            })),
+        }));
+        // Numbers name their store
+        let number_record = |id: &str, store_id: &str| RemotePostRecordV3 {
+            sync_id: "4".to_string(),
+            record_type: "number".to_string(),
+            record_id: id.to_string(),
+            sync_type: SyncTypeV3::Update,
+            store_id: Some(mock_store_a().id),
+            data: Some(json!({
+                "ID": id,
+                "name": format!("customer_invoice_number_for_store_{}", store_id),
+                "value": 100
+            })),
+        };
+        assert!(is_rejected(number_record(
+            "pushed_number",
+            &mock_store_b().id
+        )));
+        NumberRowRepository::new(&connection)
+            .upsert_one(&NumberRow {
+                id: "number_store_b".to_string(),
+                value: 1,
+                store_id: mock_store_b().id,
+                r#type: NumberRowType::OutboundShipment,
+            })
+            .unwrap();
+        // Existing number of store b
+        assert!(is_rejected(number_record(
+            "number_store_b",
+            &mock_store_a().id
+        )));
+        assert_eq!(
+            NumberRowRepository::new(&connection)
+                .find_one_by_id("number_store_b")
+                .unwrap()
+                .unwrap()
+                .value,
+            1
+        );
+        assert!(LocationRowRepository::new(&connection)
+            .find_one_by_id("other_location")
+            .unwrap()
+            .is_none());
+        assert!(LocationRowRepository::new(&connection)
+            .find_one_by_id("pushed_location")
+            .unwrap()
+            .is_some());
+        assert!(drain(2, "pass_a", "hardware_a").is_empty());
+        assert!(drain(3, "pass_b", "hardware_b").is_empty());
+
+        // Change on the central server is routed to the site of the store
+        LocationRowRepository::new(&connection)
+            .upsert_one(&LocationRow {
+                id: "pushed_location".to_string(),
+                name: "central".to_string(),
+                code: "pushed".to_string(),
+                on_hold: false,
+                store_id: mock_store_a().id,
+            })
+            .unwrap();
+        let records = drain(2, "pass_a", "hardware_a");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].record_id, "pushed_location");
+        assert_eq!(records[0].data.as_ref().unwrap()["Description"], "central");
+        assert!(drain(3, "pass_b", "hardware_b").is_empty());
+
+        // Re-initialisation queues the location again
+        let site_a = authenticate_site(&connection, 2, &sha256("pass_a"), "hardware_a").unwrap();
+        initialise_site(&connection, site_a, "hardware_a").unwrap();
+        assert!(drain(2, "pass_a", "hardware_a")
+            .iter()
+            .any(|record| record.record_id == "pushed_location"));
+
+        // Central records
+        append_central_records(
+            &connection,
+            vec![CentralSyncBufferRow {
+                id: 10,
+                table_name: "unit".to_string(),
+                record_id: "central_unit".to_string(),
+                data:
+                    r#"{"ID": "central_unit", "units": "units", "comment": "", "order_number": 0}"#
+                        .to_string(),
+            }],
+        )
+        .unwrap();
+        let batch = central_records(&connection, 0, 10).unwrap();
+        assert_eq!(batch.max_cursor, 2);
+        let records = batch.data.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, 1);
+        assert_eq!(records[0].record_id, "central_unit");
+        assert!(central_records(&connection, 1, 10).unwrap().data.is_none());
+
+        // Integrity of the stores of the site
+        let site_a = authenticate_site(&connection, 2, &sha256("pass_a"), "hardware_a").unwrap();
+        let integrity = site_store_integrity(&connection, &site_a, &mock_store_a().id).unwrap();
+        assert!(integrity
+            .tables
+            .iter()
+            .any(|table| table.table_name == "Location" && table.count > 0));
+        assert!(matches!(
+            site_store_integrity(&connection, &site_a, &mock_store_b().id),
+            Err(CentralServerError::InvalidRequest(_))
+        ));
+        let batch =
+            site_integrity_records(&connection, &site_a, &mock_store_a().id, "Location", "p")
+                .unwrap();
+        assert!(batch
+            .data
+            .unwrap()
+            .iter()
+            .any(|record| record.record_id == "pushed_location"));
+    }
+
+    #[actix_rt::test]
+    async fn central_server_offline_sync() {
+        let (_, central, _, _) =
+            setup_all("central_server_offline_sync", MockDataInserts::all()).await;
+        let (_, remote, remote_connection_manager, _) =
+            setup_all("central_server_offline_sync_remote", MockDataInserts::all()).await;
+        let remote_service_provider = ServiceProvider::new(remote_connection_manager, "app_data");
+        let central_settings = CentralServerSettings { site_id: 1 };
+        let remote_settings = inline_init(|r: &mut SyncSettings| {
+            r.site_id = 2;
+            r.central_server_site_id = 1;
+            r.password_sha256 = sha256("pass_a");
+        });
+
+        StoreRowRepository::new(&central)
+            .upsert_one(&StoreRow {
+                site_id: 2,
+                ..mock_store_a()
+            })
+            .unwrap();
+        let site = upsert_sync_site(&central, 2, "site_a", "pass_a").unwrap();
+        initialise_site(&central, site, "offline").unwrap();
+        let site = || {
+            SyncSiteRowRepository::new(&central)
+                .find_one_by_id(2)
+                .unwrap()
+                .unwrap()
+        };
+
+        // central -> remote: queue of the site and central records
+        let file = export_site_offline_sync_file(&central, &site()).unwrap();
+        assert_eq!(site().offline_export_sequence, 1);
+        let result =
+            import_offline_sync_file(&remote_service_provider, &remote_settings, &file).unwrap();
+        assert!(result.remote_records > 0);
+
+        // remote -> central: local change and acknowledgements
+        let location = LocationRow {
+            id: "offline_location".to_string(),
+            name: "offline".to_string(),
+            code: "offline".to_string(),
+            on_hold: false,
+            store_id: mock_store_a().id,
+        };
+        LocationRowRepository::new(&remote)
+            .upsert_one(&location)
+            .unwrap();
+        let export = export_offline_sync_file(&remote, &remote_settings).unwrap();
+        confirm_offline_sync_export(&remote, &export).unwrap();
+
+        // Files are signed with the password of the site
+        upsert_sync_site(&central, 2, "site_a", "other_pass").unwrap();
+        assert!(matches!(
+            import_site_offline_sync_file(&central, &central_settings, &site(), &export.file),
+            Err(CentralServerError::OfflineSyncError(
+                OfflineSyncError::InvalidSignature
+            ))
+        ));
+        upsert_sync_site(&central, 2, "site_a", "pass_a").unwrap();
+
+        let result =
+            import_site_offline_sync_file(&central, &central_settings, &site(), &export.file)
+                .unwrap();
+        assert_eq!(
+            result,
+            SiteOfflineSyncImport {
+                pushed_records: 1,
+                acknowledged_records: export.acknowledged_records,
+            }
+        );
+        assert_eq!(
+            LocationRowRepository::new(&central)
+                .find_one_by_id(&location.id)
+                .unwrap(),
+            Some(location.clone())
+        );
+        // Acknowledged records are removed from the queue, the pushed location isn't queued
+        assert!(queued_records(&central, &site(), 100000)
+            .unwrap()
+            .data
+            .is_none());
+        assert_eq!(site().offline_import_sequence, 1);
+
+        // The same file can't be imported again
+        assert!(matches!(
+            import_site_offline_sync_file(&central, &central_settings, &site(), &export.file),
+            Err(CentralServerError::OfflineSyncError(
+                OfflineSyncError::AlreadyImported { .. }
+            ))
+        ));
+
+        // New central records reach the site, and aren't exported again once integrated
+        append_central_records(
+            &central,
+            vec![CentralSyncBufferRow {
+                id: 1,
+                table_name: "unit".to_string(),
+                record_id: "central_unit".to_string(),
+                data:
+                    r#"{"ID": "central_unit", "units": "units", "comment": "", "order_number": 0}"#
+                        .to_string(),
+            }],
+        )
+        .unwrap();
+        let file = export_site_offline_sync_file(&central, &site()).unwrap();
+        let result =
+            import_offline_sync_file(&remote_service_provider, &remote_settings, &file).unwrap();
+        assert_eq!(result.central_records, 1);
+
+        let export = export_offline_sync_file(&remote, &remote_settings).unwrap();
+        confirm_offline_sync_export(&remote, &export).unwrap();
+        import_site_offline_sync_file(&central, &central_settings, &site(), &export.file).unwrap();
+        assert_eq!(site().offline_central_cursor, 1);
+        let file = export_site_offline_sync_file(&central, &site()).unwrap();
+        let result =
+            import_offline_sync_file(&remote_service_provider, &remote_settings, &file).unwrap();
+        assert_eq!(result.central_records, 0);
+    }
+}
@@ -0,0 +1,272 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use log::{error, info};
+use repository::{
+    ChangelogCompactionLogRow, ChangelogCompactionLogRowRepository, ChangelogRowRepository,
+    RepositoryError, SyncLogRowRepository, SyncSiteChangelogRowRepository,
+};
+use util::uuid::uuid;
+
+use crate::{
+    service_provider::{ServiceContext, ServiceProvider},
+    settings::ChangelogSettings,
+};
+
+use super::remote_data_synchroniser::RemoteSyncState;
+
+pub trait ChangelogCompactionServiceTrait: Sync + Send {
+    /// See [compact_changelog]
+    fn compact_changelog(
+        &self,
+        ctx: &ServiceContext,
+        settings: &ChangelogSettings,
+        is_push_cursor_used: bool,
+    ) -> Result<ChangelogCompactionLogRow, RepositoryError> {
+        compact_changelog(ctx, settings, is_push_cursor_used)
+    }
+
+    fn compaction_logs(
+        &self,
+        ctx: &ServiceContext,
+        limit: u32,
+    ) -> Result<Vec<ChangelogCompactionLogRow>, RepositoryError> {
+        ChangelogCompactionLogRowRepository::new(&ctx.connection).find_latest(limit)
+    }
+}
+
+pub struct ChangelogCompactionService {}
+impl ChangelogCompactionServiceTrait for ChangelogCompactionService {}
+
+/// Removes changelogs that have been superseded by a newer changelog of the same row. Every run is
+/// logged in the changelog compaction log.
+///
+/// Only changelogs before the push cursor are removed, i.e. changelogs that have already been
+/// pushed. A server that doesn't push its changes, e.g. a central server, sets
+/// `is_push_cursor_used` to false; its sync queues only read the latest changelog of a row anyway.
+/// The `retained_changelogs` most recent changelogs are never removed.
+///
+/// The sync log is trimmed to the `retained_sync_logs` most recent sync runs as well.
+pub fn compact_changelog(
+    ctx: &ServiceContext,
+    settings: &ChangelogSettings,
+    is_push_cursor_used: bool,
+) -> Result<ChangelogCompactionLogRow, RepositoryError> {
+    ctx.connection
+        .transaction_sync(|connection| {
+            let changelog_repo = ChangelogRowRepository::new(connection);
+            let end = changelog_repo
+                .latest_changelog()?
+                .map(|changelog| changelog.id as u64 + 1)
+                .unwrap_or(0);
+            let mut cursor = end.saturating_sub(settings.retained_changelogs);
+            if is_push_cursor_used {
+                let push_cursor = RemoteSyncState::new(connection).get_push_cursor()?;
+                cursor = cursor.min(push_cursor as u64);
+            }
+
+            let removed_changelogs = changelog_repo.delete_superseded(cursor)?;
+            if removed_changelogs > 0 {
+                SyncSiteChangelogRowRepository::new(connection).delete_removed_changelogs()?;
+            }
+
+            SyncLogRowRepository::new(connection)
+                .delete_all_but_latest(settings.retained_sync_logs)?;
+
+            let log = ChangelogCompactionLogRow {
+                id: uuid(),
+                datetime: Utc::now().naive_utc(),
+                cursor: cursor as i64,
+                removed_changelogs: removed_changelogs as i64,
+            };
+            ChangelogCompactionLogRowRepository::new(connection).insert_one(&log)?;
+            Ok(log)
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Compacts the changelog at the interval from the settings
+pub async fn run_changelog_compaction_scheduler(
+    service_provider: Arc<ServiceProvider>,
+    settings: ChangelogSettings,
+    is_push_cursor_used: bool,
+) {
+    // tokio::time::interval panics on a zero period
+    let interval_sec = settings.compaction_interval_sec.max(1);
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_sec));
+    loop {
+        interval.tick().await;
+        let ctx = match service_provider.context() {
+            Ok(ctx) => ctx,
+            Err(error) => {
+                error!("Changelog compaction failed to connect: {:?}", error);
+                continue;
+            }
+        };
+        match service_provider
+            .changelog_compaction_service
+            .compact_changelog(&ctx, &settings, is_push_cursor_used)
+        {
+            Ok(log) => info!(
+                "Changelog compaction removed {} changelogs before {}",
+                log.removed_changelogs, log.cursor
+            ),
+            Err(error) => error!("Failed to compact changelog: {:?}", error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        ChangelogRowRepository, LocationRow, LocationRowRepository, SyncLogRow,
+        SyncLogRowRepository,
+    };
+
+    use crate::{
+        service_provider::ServiceProvider, settings::ChangelogSettings,
+        sync::remote_data_synchroniser::RemoteSyncState,
+    };
+
+    fn location(id: &str, code: &str) -> LocationRow {
+        LocationRow {
+            id: id.to_string(),
+            name: id.to_string(),
+            code: code.to_string(),
+            on_hold: false,
+            store_id: mock_store_a().id,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn compact_changelog() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "compact_changelog",
+            MockDataInserts::none().names().stores(),
+        )
+        .await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.changelog_compaction_service;
+        let changelog_repo = ChangelogRowRepository::new(&connection);
+        let location_repo = LocationRowRepository::new(&connection);
+        let settings = ChangelogSettings {
+            retained_changelogs: 0,
+            ..Default::default()
+        };
+
+        let start = changelog_repo
+            .latest_changelog()
+            .unwrap()
+            .map(|changelog| changelog.id + 1)
+            .unwrap_or(0);
+        location_repo.upsert_one(&location("a", "1")).unwrap();
+        location_repo.upsert_one(&location("b", "1")).unwrap();
+        location_repo.upsert_one(&location("a", "2")).unwrap();
+        // Pushed up to here
+        let push_cursor = start + 3;
+        location_repo.upsert_one(&location("b", "2")).unwrap();
+        location_repo.upsert_one(&location("b", "3")).unwrap();
+        RemoteSyncState::new(&connection)
+            .update_push_cursor(push_cursor as u32)
+            .unwrap();
+
+        // Superseded changelogs that haven't been pushed yet are kept
+        let log = service
+            .compact_changelog(&context, &settings, true)
+            .unwrap();
+        assert_eq!(log.cursor, push_cursor);
+        assert_eq!(log.removed_changelogs, 2);
+        let remaining: Vec<(String, i64)> = changelog_repo
+            .changelogs_for_rows(start as u64, &["a".to_string(), "b".to_string()])
+            .unwrap()
+            .into_iter()
+            .map(|changelog| (changelog.row_id, changelog.id - start))
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![
+                ("a".to_string(), 2),
+                ("b".to_string(), 3),
+                ("b".to_string(), 4)
+            ]
+        );
+        // The deduped changelog is unchanged
+        assert_eq!(changelog_repo.count(start as u64).unwrap(), 2);
+
+        // Retained changelogs aren't removed
+        let log = service
+            .compact_changelog(
+                &context,
+                &ChangelogSettings {
+                    retained_changelogs: 2,
+                    ..Default::default()
+                },
+                false,
+            )
+            .unwrap();
+        assert_eq!(log.cursor, start + 3);
+        assert_eq!(log.removed_changelogs, 0);
+
+        // Without push cursor all superseded changelogs are removed
+        let log = service
+            .compact_changelog(&context, &settings, false)
+            .unwrap();
+        assert_eq!(log.cursor, start + 5);
+        assert_eq!(log.removed_changelogs, 1);
+
+        let logs = service.compaction_logs(&context, 10).unwrap();
+        assert_eq!(logs.len(), 3);
+    }
+
+    #[actix_rt::test]
+    async fn compact_changelog_sync_logs() {
+        let (_, connection, connection_manager, _) =
+            setup_all("compact_changelog_sync_logs", MockDataInserts::none()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let sync_log_repo = SyncLogRowRepository::new(&connection);
+        let sync_log = |id: &str, day: u32, error: Option<&str>| {
+            let started_datetime = NaiveDate::from_ymd(2022, 7, day).and_hms(10, 0, 0);
+            sync_log_repo
+                .upsert_one(&SyncLogRow {
+                    id: id.to_string(),
+                    started_datetime,
+                    finished_datetime: Some(started_datetime),
+                    error: error.map(str::to_string),
+                    ..Default::default()
+                })
+                .unwrap();
+        };
+        sync_log("successful", 1, None);
+        sync_log("failed_1", 2, Some("error"));
+        sync_log("failed_2", 3, Some("error"));
+        sync_log("failed_3", 4, Some("error"));
+
+        service_provider
+            .changelog_compaction_service
+            .compact_changelog(
+                &context,
+                &ChangelogSettings {
+                    retained_sync_logs: 2,
+                    ..Default::default()
+                },
+                false,
+            )
+            .unwrap();
+
+        // Latest successful sync is kept for the sync status
+        let remaining: Vec<String> = sync_log_repo
+            .find_many_latest_first(10)
+            .unwrap()
+            .into_iter()
+            .map(|row| row.id)
+            .collect();
+        assert_eq!(remaining, vec!["failed_3", "failed_2", "successful"]);
+    }
+}
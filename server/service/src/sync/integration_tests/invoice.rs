@@ -82,6 +82,9 @@ impl SyncRecordTester<Vec<FullInvoice>> for InvoiceRecordTester {
             tax: Some(10.0),
             number_of_packs: 10,
             note: None,
+            donor_id: None,
+            manufacturer_id: None,
+            vvm_status_id: None,
         };
         let invoice_row_id_1 = uuid();
         let rows = vec![
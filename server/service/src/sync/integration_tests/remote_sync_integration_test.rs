@@ -36,11 +36,17 @@ mod remote_sync_integration_tests {
             requisition::RequisitionRecordTester, stock_line::StockLineRecordTester,
             stocktake::StocktakeRecordTester,
         },
+        sync_logger::SyncLogger,
         Synchroniser,
     };
 
     use super::SyncRecordTester;
 
+    #[allow(dead_code)]
+    fn logger(connection: &StorageConnection) -> SyncLogger {
+        SyncLogger::start(connection, false).unwrap()
+    }
+
     #[allow(dead_code)]
     async fn init_db(
         sync_settings: &SyncSettings,
@@ -57,7 +63,7 @@ mod remote_sync_integration_tests {
         let synchroniser = Synchroniser::new(sync_settings.clone(), service_provider).unwrap();
         synchroniser
             .central_data
-            .pull_and_integrate_records(&connection)
+            .pull_and_integrate_records(&connection, &mut logger(&connection))
             .await
             .unwrap();
 
@@ -74,7 +80,7 @@ mod remote_sync_integration_tests {
         let (connection, synchroniser) = init_db(sync_settings, "step0").await;
         synchroniser
             .remote_data
-            .initial_pull(&connection)
+            .initial_pull(&connection, &mut logger(&connection))
             .await
             .unwrap();
         let store_id = StoreRepository::new(&connection)
@@ -90,7 +96,7 @@ mod remote_sync_integration_tests {
         let data = tester.insert(&connection, &store_id);
         synchroniser
             .remote_data
-            .push_changes(&connection)
+            .push_changes(&connection, &mut logger(&connection))
             .await
             .unwrap();
 
@@ -98,7 +104,7 @@ mod remote_sync_integration_tests {
         let (connection, synchroniser) = init_db(sync_settings, "step1").await;
         synchroniser
             .remote_data
-            .initial_pull(&connection)
+            .initial_pull(&connection, &mut logger(&connection))
             .await
             .unwrap();
         // validate we pulled the same data we inserted
@@ -108,14 +114,14 @@ mod remote_sync_integration_tests {
         let data = tester.mutate(&connection, &data);
         synchroniser
             .remote_data
-            .push_changes(&connection)
+            .push_changes(&connection, &mut logger(&connection))
             .await
             .unwrap();
         // reset local DB and pull changes
         let (connection, synchroniser) = init_db(sync_settings, "step2").await;
         synchroniser
             .remote_data
-            .initial_pull(&connection)
+            .initial_pull(&connection, &mut logger(&connection))
             .await
             .unwrap();
         // validate we pulled the same data we inserted
@@ -140,6 +146,8 @@ mod remote_sync_integration_tests {
             interval_sec: 60 * 60,
             central_server_site_id: 1,
             site_id: 7,
+            quiet_hours: None,
+            conflict_policies: Default::default(),
         };
 
         println!("number...");
@@ -40,6 +40,9 @@ impl SyncRecordTester<Vec<StockLineRow>> for StockLineRecordTester {
             expiry_date: Some(NaiveDate::from_ymd(2021, 03, 21)),
             on_hold: true,
             note: Some("some remote sync test note".to_string()),
+            donor_id: None,
+            manufacturer_id: None,
+            vvm_status_id: None,
         }];
         let repo = StockLineRowRepository::new(connection);
         for row in &rows {
@@ -49,6 +49,11 @@ impl SyncRecordTester<Vec<FullStocktake>> for StocktakeRecordTester {
                 finalised_datetime: None,
                 inventory_adjustment_id: None,
                 is_locked: true,
+                is_blind_count: false,
+                recount_tolerance: 0,
+                variance_approval_threshold: None,
+                approved_user_id: None,
+                approved_datetime: None,
             },
             lines: vec![StocktakeLineRow {
                 id: uuid(),
@@ -65,6 +70,10 @@ impl SyncRecordTester<Vec<FullStocktake>> for StocktakeRecordTester {
                 cost_price_per_pack: Some(0.0),
                 sell_price_per_pack: Some(0.0),
                 note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
+                needs_recount: false,
             }],
         }];
         let repo = StocktakeRowRepository::new(connection);
@@ -1,8 +1,17 @@
 mod actor;
 pub mod central_data_synchroniser;
+pub mod central_server;
+pub mod changelog_compaction;
+pub mod offline_sync;
 pub mod remote_data_synchroniser;
 pub mod settings;
+pub mod site_reinitialisation;
+pub mod sync_conflict;
+pub mod sync_integrity;
+mod sync_logger;
+pub mod sync_quarantine;
 mod sync_serde;
+pub mod sync_status;
 mod synchroniser;
 mod translation_central;
 mod translation_remote;
@@ -0,0 +1,461 @@
+use std::io::{Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use log::info;
+use repository::{
+    ChangelogRowRepository, RemoteSyncBufferRepository, RepositoryError, StorageConnection,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use util::hash::{constant_time_eq, hmac_sha256};
+
+use crate::{
+    apis::sync_api_v5::{
+        CentralSyncBatchV5, RemoteSyncAckV5, RemoteSyncBatchV5, RemoteSyncRecordV5,
+    },
+    service_provider::ServiceProvider,
+};
+
+use super::{
+    central_data_synchroniser::{
+        central_sync_batch_records_to_buffer_rows, CentralDataSynchroniser,
+    },
+    remote_data_synchroniser::{
+        finish_initial_pull, remote_sync_batch_records_to_buffer_rows, RemoteDataSynchroniser,
+        RemoteSyncState,
+    },
+    settings::SyncSettings,
+    sync_conflict::SyncConflictDetector,
+    translation_remote::push::translate_changelog,
+    SyncTranslationError,
+};
+
+pub const OFFLINE_SYNC_FILE_EXTENSION: &str = "omsync";
+
+/// Content of an offline sync file (gzip compressed JSON)
+#[derive(Debug, Serialize, Deserialize)]
+struct OfflineSyncFileV5 {
+    /// Remote site the file has been created for or by
+    #[serde(rename = "siteId")]
+    site_id: u32,
+    /// JSON of [OfflineSyncDataV5], kept as a string so the signature can be verified
+    data: String,
+    /// Hex encoded HMAC-SHA256 of `data`, keyed with the sync password hash of the site
+    signature: String,
+}
+
+/// Sync records for sites without connectivity, using the same formats as the online sync
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct OfflineSyncDataV5 {
+    /// Increases with every file created for (or by) the site, files with a sequence at or below
+    /// the last imported one are rejected so an old file can't be imported again
+    pub sequence: u64,
+    /// Central records for the site (central -> remote)
+    pub central: Option<CentralSyncBatchV5>,
+    /// Queued records for the site (central -> remote) or pushed records (remote -> central)
+    pub remote: Option<RemoteSyncBatchV5>,
+    /// Queued records that have been integrated by the site (remote -> central)
+    pub acknowledged: Option<RemoteSyncAckV5>,
+    /// Last central record integrated by the site, the next file for the site contains the
+    /// central records after it (remote -> central)
+    #[serde(rename = "centralCursor")]
+    pub central_cursor: Option<u32>,
+}
+
+#[derive(Error, Debug)]
+pub enum OfflineSyncError {
+    #[error("A sync is currently in progress")]
+    SyncInProgress,
+    #[error("Invalid offline sync file: {0}")]
+    InvalidFile(String),
+    #[error("Offline sync file is for site {file_site_id}, this is site {site_id}")]
+    WrongSite { file_site_id: u32, site_id: u32 },
+    #[error("Offline sync file signature doesn't match, the file has been modified or was created with a different sync password")]
+    InvalidSignature,
+    #[error("Offline sync file {sequence} has already been imported or is older than the last imported file {last_imported_sequence}")]
+    AlreadyImported {
+        sequence: u64,
+        last_imported_sequence: u64,
+    },
+    #[error("Failed to write offline sync file")]
+    FileError(#[from] std::io::Error),
+    #[error("Failed to translate local changes")]
+    TranslationError(#[from] SyncTranslationError),
+    #[error("Failed to integrate offline sync records")]
+    IntegrationError(#[source] anyhow::Error),
+    #[error("Database error")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+pub fn write_offline_sync_file(
+    site_id: u32,
+    password_sha256: &str,
+    data: &OfflineSyncDataV5,
+) -> Result<Vec<u8>, std::io::Error> {
+    let data = serde_json::to_string(data)?;
+    let file = OfflineSyncFileV5 {
+        site_id,
+        signature: hmac_sha256(password_sha256, &data),
+        data,
+    };
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(serde_json::to_string(&file)?.as_bytes())?;
+    encoder.finish()
+}
+
+pub fn read_offline_sync_file(
+    site_id: u32,
+    password_sha256: &str,
+    bytes: &[u8],
+) -> Result<OfflineSyncDataV5, OfflineSyncError> {
+    let mut content = String::new();
+    GzDecoder::new(bytes)
+        .read_to_string(&mut content)
+        .map_err(|err| OfflineSyncError::InvalidFile(format!("{}", err)))?;
+    let file: OfflineSyncFileV5 = serde_json::from_str(&content)
+        .map_err(|err| OfflineSyncError::InvalidFile(format!("{}", err)))?;
+
+    if file.site_id != site_id {
+        return Err(OfflineSyncError::WrongSite {
+            file_site_id: file.site_id,
+            site_id,
+        });
+    }
+    if !constant_time_eq(&hmac_sha256(password_sha256, &file.data), &file.signature) {
+        return Err(OfflineSyncError::InvalidSignature);
+    }
+
+    serde_json::from_str(&file.data)
+        .map_err(|err| OfflineSyncError::InvalidFile(format!("{}", err)))
+}
+
+#[derive(Debug)]
+pub struct OfflineSyncExport {
+    pub file: Vec<u8>,
+    pub pushed_records: u32,
+    pub acknowledged_records: u32,
+    sequence: u64,
+    /// Push cursor after the exported changelogs, None if there were no changelogs
+    push_cursor: Option<u32>,
+    acknowledged_sync_ids: Vec<String>,
+}
+
+/// Exports local changes that haven't been pushed yet, together with the acknowledgements of
+/// previously imported remote records.
+///
+/// The sync state isn't changed, the file takes the place of an online push once it has been
+/// stored and [confirm_offline_sync_export] is called. Until then every export contains the same
+/// changes (and any newer ones), which the central server can integrate more than once.
+pub fn export_offline_sync_file(
+    connection: &StorageConnection,
+    settings: &SyncSettings,
+) -> Result<OfflineSyncExport, OfflineSyncError> {
+    let state = RemoteSyncState::new(connection);
+    let cursor = state.get_push_cursor()?;
+    let changelogs =
+        ChangelogRowRepository::new(connection).outstanding_changelogs(cursor as u64)?;
+    info!("Offline sync: Translate {} changelogs...", changelogs.len());
+    let mut push_records = Vec::new();
+    for changelog in &changelogs {
+        translate_changelog(connection, changelog, &mut push_records)?;
+    }
+    let records: Vec<RemoteSyncRecordV5> = push_records
+        .into_iter()
+        .map(RemoteSyncRecordV5::from)
+        .collect();
+    let sync_ids = state.get_pending_acknowledgements()?;
+    let sequence = state.get_offline_export_sequence()?.unwrap_or(0) + 1;
+
+    let pushed_records = records.len() as u32;
+    let acknowledged_records = sync_ids.len() as u32;
+    let data = OfflineSyncDataV5 {
+        sequence,
+        central: None,
+        remote: Some(RemoteSyncBatchV5 {
+            queue_length: pushed_records,
+            data: Some(records),
+        }),
+        acknowledged: Some(RemoteSyncAckV5 {
+            sync_ids: sync_ids.clone(),
+        }),
+        central_cursor: Some(CentralDataSynchroniser::pull_cursor(connection)?),
+    };
+    let file = write_offline_sync_file(settings.site_id, &settings.password_sha256, &data)?;
+    info!(
+        "Offline sync: Exported {} records and {} acknowledgements",
+        pushed_records, acknowledged_records
+    );
+
+    Ok(OfflineSyncExport {
+        file,
+        pushed_records,
+        acknowledged_records,
+        sequence,
+        push_cursor: changelogs
+            .iter()
+            .map(|changelog| changelog.id as u32 + 1)
+            .max(),
+        acknowledged_sync_ids: sync_ids,
+    })
+}
+
+/// Marks the changes and acknowledgements of the export as pushed, to be called once the exported
+/// file has been stored
+pub fn confirm_offline_sync_export(
+    connection: &StorageConnection,
+    export: &OfflineSyncExport,
+) -> Result<(), OfflineSyncError> {
+    connection
+        .transaction_sync(|con| {
+            let state = RemoteSyncState::new(con);
+            if let Some(push_cursor) = export.push_cursor {
+                if push_cursor > state.get_push_cursor()? {
+                    state.update_push_cursor(push_cursor)?;
+                }
+            }
+            // Records imported since the export are acknowledged with the next file
+            let pending_sync_ids: Vec<String> = state
+                .get_pending_acknowledgements()?
+                .into_iter()
+                .filter(|sync_id| !export.acknowledged_sync_ids.contains(sync_id))
+                .collect();
+            state.set_pending_acknowledgements(&pending_sync_ids)?;
+            if state.get_offline_export_sequence()?.unwrap_or(0) < export.sequence {
+                state.set_offline_export_sequence(export.sequence)?;
+            }
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+pub struct OfflineSyncImport {
+    pub central_records: u32,
+    pub remote_records: u32,
+}
+
+/// Imports central and remote records from an offline sync file created for this site. Imported
+/// remote records are acknowledged with the next exported file.
+///
+/// The first import is treated as the initial pull of the remote data. Files have to be imported
+/// in the order they have been created, a file that is older than the last imported one is
+/// rejected.
+///
+/// Takes a ServiceProvider instead of a ServiceContext to hold the sync lock while importing, i.e.
+/// the import fails if an online sync is running.
+pub fn import_offline_sync_file(
+    service_provider: &ServiceProvider,
+    settings: &SyncSettings,
+    file: &[u8],
+) -> Result<OfflineSyncImport, OfflineSyncError> {
+    let _sync_lock = service_provider
+        .sync_lock
+        .try_lock()
+        .map_err(|_| OfflineSyncError::SyncInProgress)?;
+    let ctx = service_provider.context()?;
+    let connection = &ctx.connection;
+    let data = read_offline_sync_file(settings.site_id, &settings.password_sha256, file)?;
+    let state = RemoteSyncState::new(connection);
+    if let Some(last_imported_sequence) = state.get_offline_import_sequence()? {
+        if data.sequence <= last_imported_sequence {
+            return Err(OfflineSyncError::AlreadyImported {
+                sequence: data.sequence,
+                last_imported_sequence,
+            });
+        }
+    }
+
+    let mut central_rows =
+        central_sync_batch_records_to_buffer_rows(data.central.and_then(|batch| batch.data))
+            .map_err(|err| OfflineSyncError::InvalidFile(format!("{}", err)))?;
+    central_rows.sort_by_key(|row| row.id);
+    let central_records = CentralDataSynchroniser::insert_new_records(connection, &central_rows)?;
+
+    let is_initial_pull = !state.initial_remote_data_synced()?;
+    // Snapshot unpushed local changes before central data is integrated, see Synchroniser::sync
+    let conflict_detector = if is_initial_pull {
+        None
+    } else {
+        Some(SyncConflictDetector::load(connection, settings)?)
+    };
+
+    info!(
+        "Offline sync: Integrate {} central records",
+        central_records
+    );
+    CentralDataSynchroniser::integrate_central_records(connection)
+        .map_err(|error| OfflineSyncError::IntegrationError(error.into()))?;
+
+    let remote_records = data.remote.and_then(|batch| batch.data).unwrap_or_default();
+    let mut sync_ids: Vec<String> = remote_records
+        .iter()
+        .map(|record| record.sync_id.clone())
+        .collect();
+    let remote_rows = remote_sync_batch_records_to_buffer_rows(remote_records)
+        .map_err(|err| OfflineSyncError::InvalidFile(format!("{}", err)))?;
+    RemoteSyncBufferRepository::new(connection).upsert_many(&remote_rows)?;
+    info!(
+        "Offline sync: Integrate {} remote records",
+        remote_rows.len()
+    );
+    RemoteDataSynchroniser::do_integrate_records(connection, conflict_detector.as_ref())
+        .map_err(OfflineSyncError::IntegrationError)?;
+
+    if is_initial_pull {
+        finish_initial_pull(connection, settings.site_id)?;
+    }
+    let mut pending_sync_ids = state.get_pending_acknowledgements()?;
+    pending_sync_ids.append(&mut sync_ids);
+    state.set_pending_acknowledgements(&pending_sync_ids)?;
+    state.set_offline_import_sequence(data.sequence)?;
+
+    Ok(OfflineSyncImport {
+        central_records,
+        remote_records: remote_rows.len() as u32,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, ChangelogRowRepository, LocationRowRepository,
+    };
+    use util::inline_init;
+
+    use crate::{
+        apis::sync_api_v5::{RemoteSyncActionV5, RemoteSyncBatchV5, RemoteSyncRecordV5},
+        service_provider::ServiceProvider,
+        sync::{
+            remote_data_synchroniser::RemoteSyncState, settings::SyncSettings,
+            translation_remote::test_data::location::get_test_location_records,
+        },
+    };
+
+    use super::{
+        confirm_offline_sync_export, export_offline_sync_file, import_offline_sync_file,
+        read_offline_sync_file, write_offline_sync_file, OfflineSyncDataV5, OfflineSyncError,
+        OfflineSyncImport,
+    };
+
+    #[actix_rt::test]
+    async fn offline_sync() {
+        let (_, connection, connection_manager, _) =
+            setup_all("offline_sync", MockDataInserts::all()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+
+        let settings = inline_init(|r: &mut SyncSettings| {
+            r.site_id = 2;
+            r.password_sha256 = "password hash".to_string();
+        });
+
+        // Signature and site are checked
+        let file = write_offline_sync_file(2, "other password hash", &Default::default()).unwrap();
+        assert!(matches!(
+            read_offline_sync_file(2, &settings.password_sha256, &file),
+            Err(OfflineSyncError::InvalidSignature)
+        ));
+        let file =
+            write_offline_sync_file(3, &settings.password_sha256, &Default::default()).unwrap();
+        assert!(matches!(
+            read_offline_sync_file(2, &settings.password_sha256, &file),
+            Err(OfflineSyncError::WrongSite { .. })
+        ));
+
+        // Initial import
+        let location = get_test_location_records().pop().unwrap();
+        let buffer_row = location.remote_sync_buffer_row;
+        let data = OfflineSyncDataV5 {
+            sequence: 1,
+            central: None,
+            remote: Some(RemoteSyncBatchV5 {
+                queue_length: 1,
+                data: Some(vec![RemoteSyncRecordV5 {
+                    sync_id: buffer_row.id.clone(),
+                    table: buffer_row.table_name.clone(),
+                    record_id: buffer_row.record_id.clone(),
+                    action: RemoteSyncActionV5::Update,
+                    data: Some(serde_json::from_str(&buffer_row.data).unwrap()),
+                }]),
+            }),
+            acknowledged: None,
+            central_cursor: None,
+        };
+        let file = write_offline_sync_file(2, &settings.password_sha256, &data).unwrap();
+        {
+            // Not while a sync is running
+            let _sync_lock = service_provider.sync_lock.try_lock().unwrap();
+            assert!(matches!(
+                import_offline_sync_file(&service_provider, &settings, &file),
+                Err(OfflineSyncError::SyncInProgress)
+            ));
+        }
+        let result = import_offline_sync_file(&service_provider, &settings, &file).unwrap();
+        assert_eq!(
+            result,
+            OfflineSyncImport {
+                central_records: 0,
+                remote_records: 1
+            }
+        );
+        let location_repo = LocationRowRepository::new(&connection);
+        let mut location_row = location_repo
+            .find_one_by_id(&buffer_row.record_id)
+            .unwrap()
+            .unwrap();
+        let state = RemoteSyncState::new(&connection);
+        assert!(state.initial_remote_data_synced().unwrap());
+        assert_eq!(
+            state.get_pending_acknowledgements().unwrap(),
+            vec![buffer_row.id.clone()]
+        );
+
+        // The same (or an older) file can't be imported again
+        assert!(matches!(
+            import_offline_sync_file(&service_provider, &settings, &file),
+            Err(OfflineSyncError::AlreadyImported {
+                sequence: 1,
+                last_imported_sequence: 1
+            })
+        ));
+
+        // Export local change and acknowledgement
+        location_row.name = "local name".to_string();
+        location_repo.upsert_one(&location_row).unwrap();
+
+        let export = export_offline_sync_file(&connection, &settings).unwrap();
+        assert_eq!(export.pushed_records, 1);
+        assert_eq!(export.acknowledged_records, 1);
+        let exported = read_offline_sync_file(2, &settings.password_sha256, &export.file).unwrap();
+        assert_eq!(exported.sequence, 1);
+        let pushed = exported.remote.unwrap().data.unwrap();
+        assert_eq!(pushed[0].record_id, buffer_row.record_id);
+        assert_eq!(
+            pushed[0].data.as_ref().unwrap()["Description"],
+            "local name"
+        );
+        assert_eq!(exported.acknowledged.unwrap().sync_ids, vec![buffer_row.id]);
+
+        // Nothing is marked as pushed until the export is confirmed, e.g. the file failed to save
+        let export = export_offline_sync_file(&connection, &settings).unwrap();
+        assert_eq!(export.pushed_records, 1);
+        assert_eq!(export.acknowledged_records, 1);
+        confirm_offline_sync_export(&connection, &export).unwrap();
+
+        // Cursor and acknowledgements advanced
+        let latest_changelog = ChangelogRowRepository::new(&connection)
+            .latest_changelog()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            state.get_push_cursor().unwrap(),
+            latest_changelog.id as u32 + 1
+        );
+        let export = export_offline_sync_file(&connection, &settings).unwrap();
+        assert_eq!(export.pushed_records, 0);
+        assert_eq!(export.acknowledged_records, 0);
+        let exported = read_offline_sync_file(2, &settings.password_sha256, &export.file).unwrap();
+        assert_eq!(exported.sequence, 2);
+    }
+}
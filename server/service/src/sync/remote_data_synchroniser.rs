@@ -2,7 +2,7 @@ use log::info;
 use repository::{
     ChangelogRow, ChangelogRowRepository, KeyValueStoreRepository, KeyValueType,
     RemoteSyncBufferAction, RemoteSyncBufferRepository, RemoteSyncBufferRow, RepositoryError,
-    StorageConnection,
+    StorageConnection, SyncLogStage,
 };
 use thiserror::Error;
 
@@ -11,10 +11,14 @@ use crate::{
         sync_api_v3::{RemotePostRecordV3, SyncApiV3, SyncTypeV3},
         sync_api_v5::{RemoteSyncActionV5, RemoteSyncRecordV5, SyncApiV5},
     },
-    sync::translation_remote::{
-        pull::import_sync_pull_records,
-        push::{translate_changelog, PushRecord},
-        REMOTE_TRANSLATION_RECORDS,
+    sync::{
+        sync_conflict::SyncConflictDetector,
+        sync_logger::SyncLogger,
+        translation_remote::{
+            pull::import_sync_pull_records,
+            push::{translate_changelog, PushRecord},
+            REMOTE_TRANSLATION_RECORDS,
+        },
     },
 };
 
@@ -47,6 +51,7 @@ impl RemoteDataSynchroniser {
     pub async fn initial_pull(
         &self,
         connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
     ) -> Result<(), RemoteSyncError> {
         let state = RemoteSyncState::new(connection);
         if state.initial_remote_data_synced()? {
@@ -66,26 +71,24 @@ impl RemoteDataSynchroniser {
             info!("Initialised remote sync records");
         }
 
-        self.pull(connection).await?;
-        RemoteDataSynchroniser::integrate_records(connection).await?;
-
-        // Update push cursor after initial sync, i.e. set it to the end of the just received data
-        // so we only push new data to the central server
-        let cursor = ChangelogRowRepository::new(connection)
-            .latest_changelog()?
-            .map(|row| row.id)
-            .unwrap_or(0) as u32;
-        state.update_push_cursor(cursor + 1)?;
+        self.pull(connection, logger).await?;
+        // There are no local changes before the initial pull, changelogs are only from integrating
+        // remote records, e.g. of a previously aborted initial pull
+        RemoteDataSynchroniser::integrate_records(connection, logger, None).await?;
 
-        state.set_site_id(self.site_id as i32)?;
-        state.set_initial_remote_data_synced()?;
+        finish_initial_pull(connection, self.site_id)?;
         Ok(())
     }
 
     /// Pull all records from the central server
-    pub async fn pull(&self, connection: &StorageConnection) -> Result<(), RemoteSyncError> {
+    pub async fn pull(
+        &self,
+        connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
+    ) -> Result<(), RemoteSyncError> {
         info!("Pull remote records...");
-        self.pull_records(connection)
+        logger.stage(SyncLogStage::RemotePull)?;
+        self.pull_records(connection, logger)
             .await
             .map_err(|error| RemoteSyncError {
                 msg: "Failed to pull remote records",
@@ -96,22 +99,34 @@ impl RemoteDataSynchroniser {
         Ok(())
     }
 
-    /// Integrate previously pulled records
-    pub async fn integrate_records(connection: &StorageConnection) -> Result<(), RemoteSyncError> {
+    /// Integrate previously pulled records, conflicts with local changes are resolved if a
+    /// `conflict_detector` is provided
+    pub async fn integrate_records(
+        connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
+        conflict_detector: Option<&SyncConflictDetector>,
+    ) -> Result<(), RemoteSyncError> {
         info!("Integrate remote records...");
-        RemoteDataSynchroniser::do_integrate_records(connection).map_err(|error| {
-            RemoteSyncError {
-                msg: "Failed to integrate remote records",
-                source: error,
-            }
-        })?;
+        logger.stage(SyncLogStage::Integrate)?;
+        let number_of_records =
+            RemoteDataSynchroniser::do_integrate_records(connection, conflict_detector).map_err(
+                |error| RemoteSyncError {
+                    msg: "Failed to integrate remote records",
+                    source: error,
+                },
+            )?;
+        logger.integrated(number_of_records)?;
         info!("Successfully integrate remote records");
 
         Ok(())
     }
 
     /// Pulls all records and stores them in the RemoteSyncBufferRepository
-    async fn pull_records(&self, connection: &StorageConnection) -> anyhow::Result<()> {
+    async fn pull_records(
+        &self,
+        connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
+    ) -> anyhow::Result<()> {
         // Arbitrary batch size TODO: should come from settings
         const BATCH_SIZE: u32 = 500;
 
@@ -128,6 +143,7 @@ impl RemoteDataSynchroniser {
                 "Pulled {} remote sync records ({} remaining)",
                 number_of_pulled_records, remaining
             );
+            logger.pulled_remote(number_of_pulled_records, remaining)?;
 
             if let Some(data) = sync_batch.data {
                 let sync_ids: Vec<String> =
@@ -149,7 +165,11 @@ impl RemoteDataSynchroniser {
         Ok(())
     }
 
-    pub fn do_integrate_records(connection: &StorageConnection) -> anyhow::Result<()> {
+    /// Returns the number of integrated records
+    pub fn do_integrate_records(
+        connection: &StorageConnection,
+        conflict_detector: Option<&SyncConflictDetector>,
+    ) -> anyhow::Result<u32> {
         let remote_sync_buffer_repository = RemoteSyncBufferRepository::new(&connection);
 
         let mut records: Vec<RemoteSyncBufferRow> = Vec::new();
@@ -167,6 +187,10 @@ impl RemoteDataSynchroniser {
             records.append(&mut buffer_rows);
         }
 
+        if let Some(conflict_detector) = conflict_detector {
+            records = conflict_detector.resolve(connection, records)?;
+        }
+
         info!("Importing {} remote sync buffer records...", records.len());
         import_sync_pull_records(connection, &records)?;
         info!("Successfully Imported remote sync buffer records",);
@@ -175,13 +199,18 @@ impl RemoteDataSynchroniser {
         remote_sync_buffer_repository.remove_all()?;
         info!("Successfully cleared remote sync buffer");
 
-        Ok(())
+        Ok(records.len() as u32)
     }
 
     // push
 
-    pub async fn push_changes(&self, connection: &StorageConnection) -> Result<(), anyhow::Error> {
+    pub async fn push_changes(
+        &self,
+        connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
+    ) -> Result<(), anyhow::Error> {
         let changelog = ChangelogRowRepository::new(connection);
+        logger.stage(SyncLogStage::Push)?;
 
         const BATCH_SIZE: u32 = 1000;
         let state = RemoteSyncState::new(connection);
@@ -204,6 +233,7 @@ impl RemoteDataSynchroniser {
                 .await?;
 
             state.update_push_cursor(last_changelog_id as u32 + 1)?;
+            logger.pushed(records.len() as u32)?;
             info!(
                 "Remote push: {} records pushed to central server",
                 records.len()
@@ -214,6 +244,24 @@ impl RemoteDataSynchroniser {
     }
 }
 
+/// Marks the initial remote data as synced, once the initial remote data has been integrated
+pub fn finish_initial_pull(
+    connection: &StorageConnection,
+    site_id: u32,
+) -> Result<(), RepositoryError> {
+    let state = RemoteSyncState::new(connection);
+    // Update push cursor after initial sync, i.e. set it to the end of the just received data
+    // so we only push new data to the central server
+    let cursor = ChangelogRowRepository::new(connection)
+        .latest_changelog()?
+        .map(|row| row.id)
+        .unwrap_or(0) as u32;
+    state.update_push_cursor(cursor + 1)?;
+
+    state.set_site_id(site_id as i32)?;
+    state.set_initial_remote_data_synced()
+}
+
 pub fn translate_changelogs_to_push_records(
     connection: &StorageConnection,
     changelogs: Vec<ChangelogRow>,
@@ -331,6 +379,53 @@ impl<'a> RemoteSyncState<'a> {
         self.key_value_store
             .set_i32(KeyValueType::RemoteSyncPushCursor, Some(cursor as i32))
     }
+
+    pub fn get_pending_acknowledgements(&self) -> Result<Vec<String>, RepositoryError> {
+        let value = self
+            .key_value_store
+            .get_string(KeyValueType::RemoteSyncPendingAcknowledgements)?;
+        Ok(value
+            .and_then(|sync_ids| serde_json::from_str(&sync_ids).ok())
+            .unwrap_or_default())
+    }
+
+    pub fn set_pending_acknowledgements(&self, sync_ids: &[String]) -> Result<(), RepositoryError> {
+        let value = if sync_ids.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(sync_ids).unwrap())
+        };
+        self.key_value_store
+            .set_string(KeyValueType::RemoteSyncPendingAcknowledgements, value)
+    }
+
+    pub fn get_offline_import_sequence(&self) -> Result<Option<u64>, RepositoryError> {
+        let value = self
+            .key_value_store
+            .get_i64(KeyValueType::RemoteSyncOfflineImportSequence)?;
+        Ok(value.map(|sequence| sequence as u64))
+    }
+
+    pub fn set_offline_import_sequence(&self, sequence: u64) -> Result<(), RepositoryError> {
+        self.key_value_store.set_i64(
+            KeyValueType::RemoteSyncOfflineImportSequence,
+            Some(sequence as i64),
+        )
+    }
+
+    pub fn get_offline_export_sequence(&self) -> Result<Option<u64>, RepositoryError> {
+        let value = self
+            .key_value_store
+            .get_i64(KeyValueType::RemoteSyncOfflineExportSequence)?;
+        Ok(value.map(|sequence| sequence as u64))
+    }
+
+    pub fn set_offline_export_sequence(&self, sequence: u64) -> Result<(), RepositoryError> {
+        self.key_value_store.set_i64(
+            KeyValueType::RemoteSyncOfflineExportSequence,
+            Some(sequence as i64),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -362,7 +457,7 @@ mod tests {
             .upsert_many(&buffer_rows)
             .expect("Failed to insert remote sync records into sync buffer");
 
-        RemoteDataSynchroniser::do_integrate_records(&connection)
+        RemoteDataSynchroniser::do_integrate_records(&connection, None)
             .expect("Failed to integrate remote records");
 
         check_records_against_database(&connection, test_records);
@@ -1,3 +1,9 @@
+use std::collections::HashMap;
+
+use chrono::NaiveTime;
+
+use super::translation_remote::REMOTE_TRANSLATION_RECORDS;
+
 #[derive(serde::Deserialize, Clone, Debug, PartialEq, Default)]
 pub struct SyncSettings {
     pub url: String,
@@ -7,4 +13,76 @@ pub struct SyncSettings {
     pub interval_sec: u64,
     pub central_server_site_id: u32,
     pub site_id: u32,
+    /// Scheduled sync is paused during quiet hours, e.g. for sites on metered connections
+    #[serde(default)]
+    pub quiet_hours: Option<SyncQuietHours>,
+    /// Conflict resolution policy by central table name, e.g. `transact`, defaults to
+    /// [SyncConflictPolicy::RemoteWins] for tables not listed
+    #[serde(default)]
+    pub conflict_policies: HashMap<String, SyncConflictPolicy>,
+}
+
+impl SyncSettings {
+    /// Tables in the conflict policies that aren't synced as remote data
+    pub fn invalid_conflict_policy_tables(&self) -> Vec<&str> {
+        self.conflict_policies
+            .keys()
+            .filter(|table_name| !REMOTE_TRANSLATION_RECORDS.contains(&table_name.as_str()))
+            .map(|table_name| table_name.as_str())
+            .collect()
+    }
+}
+
+/// Settings for running this server as central server of remote sites
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct CentralServerSettings {
+    /// Site id of the central server, remote sites push their records to this site
+    pub site_id: u32,
+}
+
+/// How to resolve a remote record that arrives while the same row has local changes that haven't
+/// been pushed yet
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncConflictPolicy {
+    /// Integrate the remote record, overwriting the local changes
+    RemoteWins,
+    /// Drop the remote record and keep the local changes, they are pushed with the next sync
+    LocalWins,
+}
+
+impl Default for SyncConflictPolicy {
+    fn default() -> Self {
+        SyncConflictPolicy::RemoteWins
+    }
+}
+
+/// Local time range, `end` may be before `start` for quiet hours spanning midnight
+#[derive(serde::Deserialize, Clone, Debug, PartialEq)]
+pub struct SyncQuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl SyncQuietHours {
+    pub fn contains(&self, time: &NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= *time && *time < self.end
+        } else {
+            self.start <= *time || *time < self.end
+        }
+    }
+
+    /// Time left until the quiet hours end, None if `time` is outside of the quiet hours
+    pub fn remaining(&self, time: &NaiveTime) -> Option<chrono::Duration> {
+        if !self.contains(time) {
+            return None;
+        }
+        let remaining = self.end.signed_duration_since(*time);
+        Some(if remaining < chrono::Duration::zero() {
+            remaining + chrono::Duration::days(1)
+        } else {
+            remaining
+        })
+    }
 }
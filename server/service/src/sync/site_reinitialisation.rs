@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use log::info;
+use repository::{
+    database_settings::DatabaseSettings, CentralSyncBufferRepository, KeyValueStoreRepository,
+    KeyValueType, RemoteDataRepository, RepositoryError,
+};
+use thiserror::Error;
+
+use crate::{
+    service_provider::{ServiceContext, ServiceProvider},
+    settings_service::{validate, UpdateSettingsError},
+};
+
+use super::{
+    remote_data_synchroniser::RemoteSyncState, settings::SyncSettings,
+    sync_integrity::has_unpushed_changes, Synchroniser,
+};
+
+#[derive(Error, Debug)]
+pub enum SiteReinitialisationError {
+    #[error("Invalid sync settings: {0}")]
+    InvalidSettings(String),
+    #[error("A sync is currently in progress")]
+    SyncInProgress,
+    #[error("Local changes haven't been pushed to the central server and aren't discarded")]
+    UnpushedChanges,
+    #[error("Database backups aren't supported for this database, the backup must be skipped")]
+    BackupNotSupported,
+    #[error("Failed to connect to the central server with the new sync settings")]
+    ConnectionError(#[source] anyhow::Error),
+    #[error("Failed to back up the database")]
+    BackupError(#[source] RepositoryError),
+    #[error("Failed to pull the data of the site, re-initialisation needs to be retried")]
+    InitialPullError(#[source] anyhow::Error),
+    #[error("Database error")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+impl From<UpdateSettingsError> for SiteReinitialisationError {
+    fn from(error: UpdateSettingsError) -> Self {
+        match error {
+            UpdateSettingsError::RepositoryError(error) => {
+                SiteReinitialisationError::DatabaseError(error)
+            }
+            UpdateSettingsError::InvalidSettings(msg) => {
+                SiteReinitialisationError::InvalidSettings(msg)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ReinitialiseSiteOptions {
+    /// Local changes that haven't been pushed to the central server are lost, without this the
+    /// re-initialisation fails if there are any
+    pub discard_unpushed_changes: bool,
+    /// Skip the database backup, e.g. for postgres databases that are backed up with pg_dump
+    pub skip_backup: bool,
+}
+
+#[derive(Debug)]
+pub struct SiteReinitialisation {
+    /// Copy of the database before the re-initialisation, if it hasn't been skipped
+    pub backup_file: Option<String>,
+}
+
+/// Backups are written next to the database file, e.g.
+/// `omsupply-database.sqlite.20220801T101500.backup`
+fn backup_file_path(database: &DatabaseSettings) -> String {
+    format!(
+        "{}.{}.backup",
+        database.database_name,
+        Utc::now().naive_utc().format("%Y%m%dT%H%M%S")
+    )
+}
+
+/// Deletes the remote data and resets the sync state, i.e. the next initial pull fetches the
+/// remote data of the site from the central server, and stores the new sync settings
+fn reset_site_data(
+    ctx: &ServiceContext,
+    service_provider: &ServiceProvider,
+    settings: &SyncSettings,
+    discard_unpushed_changes: bool,
+) -> Result<(), SiteReinitialisationError> {
+    ctx.connection
+        .transaction_sync(|con| -> Result<(), SiteReinitialisationError> {
+            if !discard_unpushed_changes && has_unpushed_changes(con)? {
+                return Err(SiteReinitialisationError::UnpushedChanges);
+            }
+            RemoteDataRepository::new(con).delete_all()?;
+            CentralSyncBufferRepository::new(con).remove_all()?;
+
+            let key_value_store = KeyValueStoreRepository::new(con);
+            // Central data is pulled again as well, it might differ for the new site
+            key_value_store.set_i32(KeyValueType::CentralSyncPullCursor, None)?;
+            key_value_store.set_bool(KeyValueType::RemoteSyncInitilisationStarted, Some(false))?;
+            key_value_store.set_bool(KeyValueType::RemoteSyncInitilisationFinished, Some(false))?;
+            RemoteSyncState::new(con).set_pending_acknowledgements(&[])?;
+            // Offline sync files of the new site are numbered from the start
+            key_value_store.set_i64(KeyValueType::RemoteSyncOfflineImportSequence, None)?;
+            key_value_store.set_i64(KeyValueType::RemoteSyncOfflineExportSequence, None)?;
+
+            service_provider
+                .settings
+                .update_sync_settings(ctx, settings)?;
+            Ok(())
+        })
+        .map_err(|error| error.to_inner_error())
+}
+
+/// Re-initialises the site with new sync settings, e.g. to move the server to a different site or
+/// to recover a broken site:
+/// 1) The new settings are validated against the central server
+/// 2) The database is backed up, see [SiteReinitialisation::backup_file]
+/// 3) The remote data is deleted and the new settings are stored
+/// 4) The remote data of the site is pulled from the central server
+///
+/// Fails if a sync is running or, unless discarded explicitly, there are unpushed local changes.
+/// Sync is paused while the site is re-initialised. The initial pull is logged in the sync log,
+/// i.e. its progress is reported like the progress of any other sync. If the initial pull fails
+/// sync stays paused (the previous settings are still used by the running sync) and the
+/// re-initialisation needs to be retried. The server needs to be restarted afterwards to sync with
+/// the new settings.
+///
+/// Note, this takes a ServiceProvider instead of a ServiceContext since the ServiceContext can't
+/// be used across the async calls to the central server, see `LoginService::login`.
+pub async fn reinitialise_site(
+    service_provider: Arc<ServiceProvider>,
+    database: &DatabaseSettings,
+    settings: SyncSettings,
+    options: ReinitialiseSiteOptions,
+) -> Result<SiteReinitialisation, SiteReinitialisationError> {
+    validate(&settings)?;
+    // Held until the end, scheduled syncs with the previous settings are skipped meanwhile
+    let _sync_lock = service_provider
+        .sync_lock
+        .try_lock()
+        .map_err(|_| SiteReinitialisationError::SyncInProgress)?;
+    {
+        let ctx = service_provider.context()?;
+        if !options.skip_backup && !ctx.connection.is_backup_supported() {
+            return Err(SiteReinitialisationError::BackupNotSupported);
+        }
+        if !options.discard_unpushed_changes && has_unpushed_changes(&ctx.connection)? {
+            return Err(SiteReinitialisationError::UnpushedChanges);
+        }
+    }
+
+    let synchroniser = Synchroniser::new(settings.clone(), service_provider.clone())
+        .map_err(SiteReinitialisationError::ConnectionError)?;
+    synchroniser
+        .central_data
+        .sync_api_v5
+        .get_central_records(0, 1)
+        .await
+        .map_err(SiteReinitialisationError::ConnectionError)?;
+
+    let (backup_file, was_sync_disabled) = {
+        let ctx = service_provider.context()?;
+        let backup_file = if options.skip_backup {
+            info!("Site re-initialisation: database backup skipped");
+            None
+        } else {
+            let backup_file = backup_file_path(database);
+            ctx.connection
+                .backup_to_file(&backup_file)
+                .map_err(SiteReinitialisationError::BackupError)?;
+            info!(
+                "Site re-initialisation: database backed up to {}",
+                backup_file
+            );
+            Some(backup_file)
+        };
+
+        let was_sync_disabled = service_provider.settings.is_sync_disabled(&ctx)?;
+        service_provider.settings.disable_sync(&ctx)?;
+        if let Err(error) = reset_site_data(
+            &ctx,
+            &service_provider,
+            &settings,
+            options.discard_unpushed_changes,
+        ) {
+            if !was_sync_disabled {
+                service_provider.settings.enable_sync(&ctx)?;
+            }
+            return Err(error);
+        }
+        (backup_file, was_sync_disabled)
+    };
+
+    synchroniser
+        .force_initial_pull()
+        .await
+        .map_err(SiteReinitialisationError::InitialPullError)?;
+    info!("Site re-initialisation: finished initial pull");
+
+    if !was_sync_disabled {
+        let ctx = service_provider.context()?;
+        service_provider.settings.enable_sync(&ctx)?;
+    }
+    Ok(SiteReinitialisation { backup_file })
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use repository::{
+        mock::{mock_outbound_shipment_a, MockDataInserts},
+        test_db::{get_test_db_settings, setup_all},
+        InvoiceRowRepository,
+    };
+    use util::inline_init;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn reinitialise_site_validation() {
+        let (_, connection, connection_manager, _) =
+            setup_all("reinitialise_site_validation", MockDataInserts::all()).await;
+        let service_provider = Arc::new(ServiceProvider::new(connection_manager, "app_data"));
+        let database = get_test_db_settings("reinitialise_site_validation");
+
+        let settings = inline_init(|r: &mut SyncSettings| {
+            r.url = "http://0.0.0.0:0".to_string();
+            r.username = "site".to_string();
+        });
+        let discard = ReinitialiseSiteOptions {
+            discard_unpushed_changes: true,
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            reinitialise_site(
+                service_provider.clone(),
+                &database,
+                inline_init(|r: &mut SyncSettings| r.url = "http://0.0.0.0:0".to_string()),
+                discard.clone(),
+            )
+            .await,
+            Err(SiteReinitialisationError::InvalidSettings(_))
+        ));
+
+        // Mock data hasn't been pushed
+        assert!(matches!(
+            reinitialise_site(
+                service_provider.clone(),
+                &database,
+                settings.clone(),
+                ReinitialiseSiteOptions::default(),
+            )
+            .await,
+            Err(SiteReinitialisationError::UnpushedChanges)
+        ));
+
+        // Running sync
+        {
+            let _sync_lock = service_provider.sync_lock.try_lock().unwrap();
+            assert!(matches!(
+                reinitialise_site(
+                    service_provider.clone(),
+                    &database,
+                    settings.clone(),
+                    discard.clone(),
+                )
+                .await,
+                Err(SiteReinitialisationError::SyncInProgress)
+            ));
+        }
+
+        // Central server can't be reached, nothing is changed
+        assert!(matches!(
+            reinitialise_site(service_provider.clone(), &database, settings, discard).await,
+            Err(SiteReinitialisationError::ConnectionError(_))
+        ));
+        assert!(InvoiceRowRepository::new(&connection)
+            .find_one_by_id_option(&mock_outbound_shipment_a().id)
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            service_provider
+                .settings
+                .sync_settings(&service_provider.context().unwrap())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[actix_rt::test]
+    async fn reinitialise_site_reset_data() {
+        let (_, connection, connection_manager, _) =
+            setup_all("reinitialise_site_reset_data", MockDataInserts::all()).await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let ctx = service_provider.context().unwrap();
+        let state = RemoteSyncState::new(&connection);
+        state.set_sync_queue_initalised().unwrap();
+        state.set_initial_remote_data_synced().unwrap();
+
+        let settings = inline_init(|r: &mut SyncSettings| {
+            r.url = "http://localhost:2048".to_string();
+            r.username = "new_site".to_string();
+            r.site_id = 3;
+        });
+        assert!(matches!(
+            reset_site_data(&ctx, &service_provider, &settings, false),
+            Err(SiteReinitialisationError::UnpushedChanges)
+        ));
+        assert!(InvoiceRowRepository::new(&connection)
+            .find_one_by_id_option(&mock_outbound_shipment_a().id)
+            .unwrap()
+            .is_some());
+
+        reset_site_data(&ctx, &service_provider, &settings, true).unwrap();
+
+        assert_eq!(
+            InvoiceRowRepository::new(&connection)
+                .find_one_by_id_option(&mock_outbound_shipment_a().id)
+                .unwrap(),
+            None
+        );
+        assert!(!state.sync_queue_initalised().unwrap());
+        assert!(!state.initial_remote_data_synced().unwrap());
+        assert_eq!(
+            service_provider.settings.sync_settings(&ctx).unwrap(),
+            Some(settings)
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use log::warn;
+use repository::{
+    ChangelogRow, ChangelogRowRepository, RemoteSyncBufferRow, RepositoryError, StorageConnection,
+    SyncConflictResolution, SyncConflictRow, SyncConflictRowRepository,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+use super::{
+    remote_data_synchroniser::RemoteSyncState,
+    settings::{SyncConflictPolicy, SyncSettings},
+    translation_remote::{
+        push::{translate_changelog, PushRecord},
+        table_name_to_central,
+    },
+};
+
+/// Detects remote records that arrive while the same row has local changes that haven't been
+/// pushed yet.
+///
+/// The latest changelog entry of a row is used as the row version, a row has unpushed changes if
+/// its version is at or after the push cursor when the sync starts. Local changes are pushed before
+/// remote records are pulled, so the remote records were queued before the central server received
+/// the local changes.
+pub struct SyncConflictDetector {
+    conflict_policies: HashMap<String, SyncConflictPolicy>,
+    /// Latest unpushed changelog by central table name and record id
+    local_changes: HashMap<(String, String), ChangelogRow>,
+}
+
+impl SyncConflictDetector {
+    /// Takes a snapshot of the unpushed local changes. This needs to happen before pushing, which
+    /// moves the push cursor past the local changes, and before integrating pulled data, since
+    /// integrated rows are added to the changelog as well.
+    pub fn load(
+        connection: &StorageConnection,
+        settings: &SyncSettings,
+    ) -> Result<Self, RepositoryError> {
+        let push_cursor = RemoteSyncState::new(connection).get_push_cursor()?;
+        let local_changes = ChangelogRowRepository::new(connection)
+            .outstanding_changelogs(push_cursor as u64)?
+            .into_iter()
+            .map(|changelog| {
+                let key = (
+                    table_name_to_central(&changelog.table_name).to_string(),
+                    changelog.row_id.clone(),
+                );
+                (key, changelog)
+            })
+            .collect();
+        Ok(SyncConflictDetector {
+            conflict_policies: settings.conflict_policies.clone(),
+            local_changes,
+        })
+    }
+
+    /// Records conflicting remote records and returns the records that should be integrated
+    pub fn resolve(
+        &self,
+        connection: &StorageConnection,
+        records: Vec<RemoteSyncBufferRow>,
+    ) -> anyhow::Result<Vec<RemoteSyncBufferRow>> {
+        let repo = SyncConflictRowRepository::new(connection);
+        let mut result = Vec::new();
+        for record in records {
+            let key = (record.table_name.clone(), record.record_id.clone());
+            let changelog = match self.local_changes.get(&key) {
+                Some(changelog) => changelog,
+                None => {
+                    result.push(record);
+                    continue;
+                }
+            };
+            // Only a conflict if the local change is pushed, e.g. name_store_join rows are never
+            // pushed
+            let local_data = match local_push_data(connection, changelog)? {
+                Some(local_data) => local_data,
+                None => {
+                    result.push(record);
+                    continue;
+                }
+            };
+
+            let policy = self
+                .conflict_policies
+                .get(&record.table_name)
+                .cloned()
+                .unwrap_or_default();
+            warn!(
+                "Sync conflict ({:?}) for {} record {}",
+                policy, record.table_name, record.record_id
+            );
+            repo.upsert_one(&SyncConflictRow {
+                id: uuid(),
+                table_name: record.table_name.clone(),
+                record_id: record.record_id.clone(),
+                resolution: match policy {
+                    SyncConflictPolicy::RemoteWins => SyncConflictResolution::RemoteWins,
+                    SyncConflictPolicy::LocalWins => SyncConflictResolution::LocalWins,
+                },
+                local_data,
+                remote_action: record.action.clone(),
+                remote_data: record.data.clone(),
+                detected_datetime: Utc::now().naive_utc(),
+            })?;
+
+            match policy {
+                SyncConflictPolicy::RemoteWins => result.push(record),
+                // The local change was pushed after the remote change was queued, so the central
+                // server has the local record as well
+                SyncConflictPolicy::LocalWins => {}
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Returns None if the local change isn't pushed, Some(None) for a local deletion and
+/// Some(Some(data)) for the record data as it will be pushed
+fn local_push_data(
+    connection: &StorageConnection,
+    changelog: &ChangelogRow,
+) -> anyhow::Result<Option<Option<String>>> {
+    let mut push_records = Vec::new();
+    translate_changelog(connection, changelog, &mut push_records)?;
+    let local_data = push_records
+        .into_iter()
+        .find_map(|push_record| match push_record {
+            PushRecord::Upsert(record) if record.record_id == changelog.row_id => {
+                Some(Some(record.data.to_string()))
+            }
+            PushRecord::Upsert(_) => None,
+            PushRecord::Delete(_) => Some(None),
+        });
+    Ok(local_data)
+}
+
+pub trait SyncConflictServiceTrait: Sync + Send {
+    /// Most recently detected conflicts first
+    fn get_sync_conflicts(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<SyncConflictRow>, RepositoryError> {
+        SyncConflictRowRepository::new(&ctx.connection).find_all()
+    }
+}
+
+pub struct SyncConflictService {}
+impl SyncConflictServiceTrait for SyncConflictService {}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, ChangelogRowRepository, LocationRowRepository,
+        RemoteSyncBufferRepository, SyncConflictResolution,
+    };
+    use util::inline_init;
+
+    use crate::{
+        service_provider::ServiceProvider,
+        sync::{
+            remote_data_synchroniser::{RemoteDataSynchroniser, RemoteSyncState},
+            settings::{SyncConflictPolicy, SyncSettings},
+            translation_remote::{
+                test_data::{extract_sync_buffer_rows, location::get_test_location_records},
+                TRANSLATION_RECORD_LOCATION,
+            },
+        },
+    };
+
+    use super::SyncConflictDetector;
+
+    #[actix_rt::test]
+    async fn sync_conflict() {
+        let (_, connection, connection_manager, _) =
+            setup_all("sync_conflict", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = &service_provider.sync_conflict_service;
+
+        let buffer_rows = extract_sync_buffer_rows(&get_test_location_records());
+        let remote_location = &buffer_rows[0];
+        let integrate = |settings: &SyncSettings| {
+            let detector = SyncConflictDetector::load(&connection, settings).unwrap();
+            RemoteSyncBufferRepository::new(&connection)
+                .upsert_many(&buffer_rows)
+                .unwrap();
+            RemoteDataSynchroniser::do_integrate_records(&connection, Some(&detector)).unwrap();
+        };
+        let location_repo = LocationRowRepository::new(&connection);
+        let push_all = || {
+            let cursor = ChangelogRowRepository::new(&connection)
+                .latest_changelog()
+                .unwrap()
+                .map(|row| row.id)
+                .unwrap_or(0) as u32;
+            RemoteSyncState::new(&connection)
+                .update_push_cursor(cursor + 1)
+                .unwrap();
+        };
+
+        // No local changes
+        integrate(&SyncSettings::default());
+        assert_eq!(service.get_sync_conflicts(&context).unwrap(), vec![]);
+        push_all();
+
+        // Local change, local wins
+        let mut local_location = location_repo
+            .find_one_by_id(&remote_location.record_id)
+            .unwrap()
+            .unwrap();
+        local_location.name = "local name".to_string();
+        location_repo.upsert_one(&local_location).unwrap();
+
+        integrate(&inline_init(|r: &mut SyncSettings| {
+            r.conflict_policies.insert(
+                TRANSLATION_RECORD_LOCATION.to_string(),
+                SyncConflictPolicy::LocalWins,
+            );
+        }));
+        let conflicts = service.get_sync_conflicts(&context).unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].resolution, SyncConflictResolution::LocalWins);
+        assert_eq!(conflicts[0].table_name, TRANSLATION_RECORD_LOCATION);
+        assert_eq!(conflicts[0].record_id, remote_location.record_id);
+        assert_eq!(conflicts[0].remote_data, remote_location.data);
+        assert!(conflicts[0]
+            .local_data
+            .as_ref()
+            .unwrap()
+            .contains("local name"));
+        assert_eq!(
+            location_repo
+                .find_one_by_id(&remote_location.record_id)
+                .unwrap()
+                .unwrap()
+                .name,
+            "local name"
+        );
+
+        // Local change still not pushed, remote wins by default
+        integrate(&SyncSettings::default());
+        let conflicts = service.get_sync_conflicts(&context).unwrap();
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts
+            .iter()
+            .any(|conflict| conflict.resolution == SyncConflictResolution::RemoteWins));
+        assert_eq!(
+            location_repo
+                .find_one_by_id(&remote_location.record_id)
+                .unwrap()
+                .unwrap()
+                .name,
+            "NameRed.02"
+        );
+
+        // Changes that have been pushed aren't conflicts
+        push_all();
+        integrate(&SyncSettings::default());
+        assert_eq!(service.get_sync_conflicts(&context).unwrap().len(), 2);
+    }
+}
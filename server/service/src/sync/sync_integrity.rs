@@ -0,0 +1,476 @@
+use std::collections::BTreeMap;
+
+use log::info;
+use repository::{
+    ChangelogAction, ChangelogRow, ChangelogRowRepository, ChangelogTableName,
+    RemoteSyncBufferRepository, RepositoryError, StorageConnection, StoreRemoteDataRepository,
+    StoreRowRepository, STORE_REMOTE_DATA_TABLES,
+};
+use reqwest::{Client, Url};
+use thiserror::Error;
+use util::hash::sha256;
+
+use crate::{
+    apis::{
+        sync_api_credentials::SyncCredentials,
+        sync_api_v5::{
+            IntegrityRangeV5, RemoteSyncActionV5, RemoteSyncRecordV5, StoreIntegrityV5, SyncApiV5,
+            TableIntegrityV5,
+        },
+    },
+    service_provider::ServiceProvider,
+};
+
+use super::{
+    remote_data_synchroniser::{
+        remote_sync_batch_records_to_buffer_rows, RemoteDataSynchroniser, RemoteSyncState,
+    },
+    settings::SyncSettings,
+    translation_remote::{
+        push::{translate_changelog, PushRecord},
+        table_name_to_central,
+    },
+    SyncTranslationError,
+};
+
+#[derive(Error, Debug)]
+pub enum SyncIntegrityError {
+    #[error("Store {0} doesn't belong to this site")]
+    StoreNotOnSite(String),
+    #[error("Local changes need to be pushed before records can be re-pulled")]
+    UnpushedChanges,
+    #[error("A sync is currently in progress")]
+    SyncInProgress,
+    #[error("Failed to connect to the central server")]
+    ConnectionError(#[source] anyhow::Error),
+    #[error("Failed to translate record")]
+    TranslationError(#[from] SyncTranslationError),
+    #[error("Failed to integrate re-pulled records")]
+    IntegrationError(#[source] anyhow::Error),
+    #[error("Database error")]
+    DatabaseError(#[from] RepositoryError),
+}
+
+/// A range of a table that differs between the site and the central server
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegrityMismatch {
+    /// Central table name, e.g. `transact`
+    pub table_name: String,
+    pub range: String,
+    pub local_count: u32,
+    pub central_count: u32,
+}
+
+#[derive(Debug)]
+pub struct SyncIntegrityReport {
+    pub local: StoreIntegrityV5,
+    /// None if the central server doesn't provide integrity summaries
+    pub central: Option<StoreIntegrityV5>,
+    pub mismatches: Vec<IntegrityMismatch>,
+}
+
+#[derive(Debug)]
+pub struct SyncIntegrityRepair {
+    pub repulled_records: u32,
+    /// Report after the re-pull. Records that only exist locally aren't removed by a re-pull, i.e.
+    /// their ranges still mismatch.
+    pub report: SyncIntegrityReport,
+}
+
+/// Records are grouped into ranges by the first character of their id. Record ids are random,
+/// i.e. the ranges are of similar size and don't change when records are added.
+fn range_of(record_id: &str) -> String {
+    record_id.chars().take(1).collect()
+}
+
+/// Record data as pushed to the central server, None if the row isn't pushed
+fn push_data(
+    connection: &StorageConnection,
+    table: &ChangelogTableName,
+    row_id: &str,
+) -> Result<Option<serde_json::Value>, SyncTranslationError> {
+    let changelog = ChangelogRow {
+        id: 0,
+        table_name: table.clone(),
+        row_id: row_id.to_string(),
+        row_action: ChangelogAction::Upsert,
+    };
+    let mut push_records = Vec::new();
+    translate_changelog(connection, &changelog, &mut push_records)?;
+    let data = push_records
+        .into_iter()
+        .find_map(|push_record| match push_record {
+            PushRecord::Upsert(record) if record.record_id == row_id => Some(record.data),
+            _ => None,
+        });
+    Ok(data)
+}
+
+/// Translated records of a store table, grouped by range and ordered by id
+fn table_records(
+    connection: &StorageConnection,
+    table: &ChangelogTableName,
+    store_id: &str,
+    range: Option<&str>,
+) -> Result<BTreeMap<String, BTreeMap<String, serde_json::Value>>, SyncIntegrityError> {
+    let row_ids = StoreRemoteDataRepository::new(connection)
+        .row_ids(table, store_id)?
+        .unwrap_or_default();
+
+    let mut ranges: BTreeMap<String, BTreeMap<String, serde_json::Value>> = BTreeMap::new();
+    for row_id in row_ids {
+        let row_range = range_of(&row_id);
+        if range.map(|range| range != row_range).unwrap_or(false) {
+            continue;
+        }
+        if let Some(data) = push_data(connection, table, &row_id)? {
+            ranges.entry(row_range).or_default().insert(row_id, data);
+        }
+    }
+    Ok(ranges)
+}
+
+/// Row counts and content hashes of the remote data of a store. The content hash is calculated
+/// from the records as they are pushed, i.e. the site and the central server calculate the same
+/// hashes for the same data.
+pub fn store_integrity(
+    connection: &StorageConnection,
+    store_id: &str,
+) -> Result<StoreIntegrityV5, SyncIntegrityError> {
+    let mut tables = Vec::new();
+    for table in STORE_REMOTE_DATA_TABLES {
+        let ranges: Vec<IntegrityRangeV5> = table_records(connection, table, store_id, None)?
+            .into_iter()
+            .map(|(range, records)| {
+                let content: String = records
+                    .iter()
+                    .map(|(record_id, data)| format!("{}\n{}\n", record_id, data))
+                    .collect();
+                IntegrityRangeV5 {
+                    range,
+                    count: records.len() as u32,
+                    hash: sha256(&content),
+                }
+            })
+            .collect();
+        let range_hashes: String = ranges.iter().map(|range| range.hash.as_str()).collect();
+        tables.push(TableIntegrityV5 {
+            table_name: table_name_to_central(table).to_string(),
+            count: ranges.iter().map(|range| range.count).sum(),
+            hash: sha256(&range_hashes),
+            ranges,
+        });
+    }
+
+    Ok(StoreIntegrityV5 {
+        store_id: store_id.to_string(),
+        tables,
+    })
+}
+
+/// Returns the ranges that differ between the local and the central summary
+pub fn compare_store_integrity(
+    local: &StoreIntegrityV5,
+    central: &StoreIntegrityV5,
+) -> Vec<IntegrityMismatch> {
+    let mut mismatches = Vec::new();
+    for local_table in &local.tables {
+        let central_ranges: Vec<IntegrityRangeV5> = central
+            .tables
+            .iter()
+            .find(|table| table.table_name == local_table.table_name)
+            .map(|table| table.ranges.clone())
+            .unwrap_or_default();
+        if central_ranges == local_table.ranges {
+            continue;
+        }
+
+        let mut ranges: BTreeMap<&str, (Option<&IntegrityRangeV5>, Option<&IntegrityRangeV5>)> =
+            BTreeMap::new();
+        for range in &local_table.ranges {
+            ranges.entry(range.range.as_str()).or_default().0 = Some(range);
+        }
+        for range in &central_ranges {
+            ranges.entry(range.range.as_str()).or_default().1 = Some(range);
+        }
+        for (range, (local_range, central_range)) in ranges {
+            if local_range.map(|range| &range.hash) == central_range.map(|range| &range.hash) {
+                continue;
+            }
+            mismatches.push(IntegrityMismatch {
+                table_name: local_table.table_name.clone(),
+                range: range.to_string(),
+                local_count: local_range.map(|range| range.count).unwrap_or(0),
+                central_count: central_range.map(|range| range.count).unwrap_or(0),
+            });
+        }
+    }
+    mismatches
+}
+
+/// Looks up a store remote data table by its central table name
+pub fn integrity_table(table_name: &str) -> Option<&'static ChangelogTableName> {
+    STORE_REMOTE_DATA_TABLES
+        .iter()
+        .find(|table| table_name_to_central(table) == table_name)
+}
+
+/// All records of a store table in an integrity range, in the format of the remote sync queue
+pub fn integrity_range_records(
+    connection: &StorageConnection,
+    table: &ChangelogTableName,
+    store_id: &str,
+    range: &str,
+) -> Result<Vec<RemoteSyncRecordV5>, SyncIntegrityError> {
+    let table_name = table_name_to_central(table);
+    let records = table_records(connection, table, store_id, Some(range))?
+        .into_iter()
+        .flat_map(|(_, records)| records)
+        .map(|(record_id, data)| RemoteSyncRecordV5 {
+            sync_id: format!("{}_{}", table_name, record_id),
+            table: table_name.to_string(),
+            record_id,
+            action: RemoteSyncActionV5::Update,
+            data: Some(data),
+        })
+        .collect();
+    Ok(records)
+}
+
+fn validate_store(
+    connection: &StorageConnection,
+    settings: &SyncSettings,
+    store_id: &str,
+) -> Result<(), SyncIntegrityError> {
+    match StoreRowRepository::new(connection).find_one_by_id(store_id)? {
+        Some(store) if store.site_id == settings.site_id as i32 => Ok(()),
+        _ => Err(SyncIntegrityError::StoreNotOnSite(store_id.to_string())),
+    }
+}
+
+fn sync_api(
+    service_provider: &ServiceProvider,
+    settings: &SyncSettings,
+) -> Result<SyncApiV5, SyncIntegrityError> {
+    let url = Url::parse(&settings.url)
+        .map_err(|error| SyncIntegrityError::ConnectionError(error.into()))?;
+    let hardware_id = service_provider
+        .app_data_service
+        .get_hardware_id()
+        .map_err(|error| SyncIntegrityError::ConnectionError(error.into()))?;
+    let credentials = SyncCredentials {
+        username: settings.username.clone(),
+        password_sha256: settings.password_sha256.clone(),
+    };
+    Ok(SyncApiV5::new(
+        url,
+        credentials,
+        Client::new(),
+        &hardware_id,
+        settings.site_id,
+    ))
+}
+
+/// Compares the remote data of a store with the central server.
+///
+/// Note, this takes a ServiceProvider instead of a ServiceContext since the ServiceContext can't
+/// be used across the async calls to the central server, see `LoginService::login`.
+pub async fn verify_store_integrity(
+    service_provider: &ServiceProvider,
+    settings: &SyncSettings,
+    store_id: &str,
+) -> Result<SyncIntegrityReport, SyncIntegrityError> {
+    let local = {
+        let ctx = service_provider.context()?;
+        validate_store(&ctx.connection, settings, store_id)?;
+        store_integrity(&ctx.connection, store_id)?
+    };
+
+    let central = sync_api(service_provider, settings)?
+        .get_store_integrity(store_id)
+        .await
+        .map_err(SyncIntegrityError::ConnectionError)?;
+    let mismatches = central
+        .as_ref()
+        .map(|central| compare_store_integrity(&local, central))
+        .unwrap_or_default();
+    info!(
+        "Sync integrity: {} mismatching ranges for store {}",
+        mismatches.len(),
+        store_id
+    );
+
+    Ok(SyncIntegrityReport {
+        local,
+        central,
+        mismatches,
+    })
+}
+
+/// Re-pulls the records of all mismatching ranges of a store from the central server.
+///
+/// Re-pulled records overwrite local rows, i.e. all local changes need to be pushed first. The
+/// sync lock is held for the whole repair, a sync can't push or pull records meanwhile.
+pub async fn repair_store_integrity(
+    service_provider: &ServiceProvider,
+    settings: &SyncSettings,
+    store_id: &str,
+) -> Result<SyncIntegrityRepair, SyncIntegrityError> {
+    let _sync_lock = service_provider
+        .sync_lock
+        .try_lock()
+        .map_err(|_| SyncIntegrityError::SyncInProgress)?;
+    let report = verify_store_integrity(service_provider, settings, store_id).await?;
+    if report.mismatches.is_empty() {
+        return Ok(SyncIntegrityRepair {
+            repulled_records: 0,
+            report,
+        });
+    }
+    {
+        let ctx = service_provider.context()?;
+        check_no_unpushed_changes(&ctx.connection)?;
+    }
+
+    let sync_api = sync_api(service_provider, settings)?;
+    let mut records = Vec::new();
+    for mismatch in &report.mismatches {
+        let batch = sync_api
+            .get_integrity_records(store_id, &mismatch.table_name, &mismatch.range)
+            .await
+            .map_err(SyncIntegrityError::ConnectionError)?;
+        records.append(&mut batch.data.unwrap_or_default());
+    }
+    let repulled_records = records.len() as u32;
+    let rows = remote_sync_batch_records_to_buffer_rows(records)
+        .map_err(|error| SyncIntegrityError::IntegrationError(error.into()))?;
+
+    {
+        let ctx = service_provider.context()?;
+        info!(
+            "Sync integrity: Integrate {} re-pulled records for store {}",
+            repulled_records, store_id
+        );
+        ctx.connection
+            .transaction_sync(|con| -> Result<(), SyncIntegrityError> {
+                check_no_unpushed_changes(con)?;
+                RemoteSyncBufferRepository::new(con).upsert_many(&rows)?;
+                RemoteDataSynchroniser::do_integrate_records(con, None)
+                    .map_err(SyncIntegrityError::IntegrationError)?;
+                // The re-pulled records are already on the central server
+                let cursor = ChangelogRowRepository::new(con)
+                    .latest_changelog()?
+                    .map(|changelog| changelog.id + 1)
+                    .unwrap_or(0);
+                RemoteSyncState::new(con).update_push_cursor(cursor as u32)?;
+                Ok(())
+            })
+            .map_err(|error| error.to_inner_error())?;
+    }
+
+    let report = verify_store_integrity(service_provider, settings, store_id).await?;
+    Ok(SyncIntegrityRepair {
+        repulled_records,
+        report,
+    })
+}
+
+/// True if there are local changes that haven't been pushed to the central server yet
+pub(crate) fn has_unpushed_changes(
+    connection: &StorageConnection,
+) -> Result<bool, RepositoryError> {
+    let push_cursor = RemoteSyncState::new(connection).get_push_cursor()?;
+    Ok(ChangelogRowRepository::new(connection).count(push_cursor as u64)? > 0)
+}
+
+fn check_no_unpushed_changes(connection: &StorageConnection) -> Result<(), SyncIntegrityError> {
+    if has_unpushed_changes(connection)? {
+        return Err(SyncIntegrityError::UnpushedChanges);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_store_a, MockDataInserts},
+        test_db::setup_all,
+        ChangelogTableName, LocationRow, LocationRowRepository,
+    };
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn store_integrity_mismatches() {
+        let (_, connection, _, _) =
+            setup_all("store_integrity_mismatches", MockDataInserts::all()).await;
+        let store_id = mock_store_a().id;
+
+        let central = store_integrity(&connection, &store_id).unwrap();
+        assert!(compare_store_integrity(&central, &central).is_empty());
+        let location_table = central
+            .tables
+            .iter()
+            .find(|table| table.table_name == "Location")
+            .unwrap();
+        let ranges: u32 = location_table.ranges.iter().map(|range| range.count).sum();
+        assert_eq!(location_table.count, ranges);
+
+        // Changed and added locations
+        let location_repo = LocationRowRepository::new(&connection);
+        location_repo
+            .upsert_one(&LocationRow {
+                id: "integrity_location".to_string(),
+                name: "integrity".to_string(),
+                code: "integrity".to_string(),
+                on_hold: false,
+                store_id: store_id.clone(),
+            })
+            .unwrap();
+        let local = store_integrity(&connection, &store_id).unwrap();
+        assert_eq!(
+            compare_store_integrity(&local, &central),
+            vec![IntegrityMismatch {
+                table_name: "Location".to_string(),
+                range: "i".to_string(),
+                local_count: 1,
+                central_count: 0,
+            }]
+        );
+
+        let records = integrity_range_records(
+            &connection,
+            integrity_table("Location").unwrap(),
+            &store_id,
+            "i",
+        )
+        .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].record_id, "integrity_location");
+        assert_eq!(
+            records[0].data.as_ref().unwrap()["Description"],
+            "integrity"
+        );
+        assert_eq!(
+            integrity_table("transact"),
+            Some(&ChangelogTableName::Invoice)
+        );
+        assert_eq!(integrity_table("name"), None);
+    }
+
+    #[actix_rt::test]
+    async fn repair_store_integrity_sync_in_progress() {
+        let (_, _, connection_manager, _) = setup_all(
+            "repair_store_integrity_sync_in_progress",
+            MockDataInserts::none(),
+        )
+        .await;
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+
+        let _sync_lock = service_provider.sync_lock.try_lock().unwrap();
+        assert!(matches!(
+            repair_store_integrity(&service_provider, &SyncSettings::default(), "store").await,
+            Err(SyncIntegrityError::SyncInProgress)
+        ));
+    }
+}
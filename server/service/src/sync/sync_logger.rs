@@ -0,0 +1,74 @@
+use chrono::Utc;
+use repository::{
+    RepositoryError, StorageConnection, SyncLogRow, SyncLogRowRepository, SyncLogStage,
+};
+use util::uuid::uuid;
+
+/// Records the progress of a sync run in the sync log, so it can be shown to users
+pub struct SyncLogger<'a> {
+    sync_log_repo: SyncLogRowRepository<'a>,
+    row: SyncLogRow,
+}
+
+impl<'a> SyncLogger<'a> {
+    pub fn start(
+        connection: &'a StorageConnection,
+        is_initial_pull: bool,
+    ) -> Result<Self, RepositoryError> {
+        let logger = SyncLogger {
+            sync_log_repo: SyncLogRowRepository::new(connection),
+            row: SyncLogRow {
+                id: uuid(),
+                is_initial_pull,
+                started_datetime: Utc::now().naive_utc(),
+                ..Default::default()
+            },
+        };
+        logger.sync_log_repo.upsert_one(&logger.row)?;
+        Ok(logger)
+    }
+
+    pub fn stage(&mut self, stage: SyncLogStage) -> Result<(), RepositoryError> {
+        self.row.stage = Some(stage);
+        self.row.pull_remaining_records = None;
+        self.sync_log_repo.upsert_one(&self.row)
+    }
+
+    pub fn pushed(&mut self, number_of_records: u32) -> Result<(), RepositoryError> {
+        self.row.pushed_records += number_of_records as i32;
+        self.sync_log_repo.upsert_one(&self.row)
+    }
+
+    pub fn pulled_remote(
+        &mut self,
+        number_of_records: u32,
+        remaining: u32,
+    ) -> Result<(), RepositoryError> {
+        self.row.pulled_remote_records += number_of_records as i32;
+        self.row.pull_remaining_records = Some(remaining as i32);
+        self.sync_log_repo.upsert_one(&self.row)
+    }
+
+    pub fn pulled_central(
+        &mut self,
+        number_of_records: u32,
+        remaining: u32,
+    ) -> Result<(), RepositoryError> {
+        self.row.pulled_central_records += number_of_records as i32;
+        self.row.pull_remaining_records = Some(remaining as i32);
+        self.sync_log_repo.upsert_one(&self.row)
+    }
+
+    pub fn integrated(&mut self, number_of_records: u32) -> Result<(), RepositoryError> {
+        self.row.integrated_records += number_of_records as i32;
+        self.sync_log_repo.upsert_one(&self.row)
+    }
+
+    /// Marks the sync run as finished, the stage is kept to show where a failed sync stopped
+    pub fn finish<T>(mut self, result: &anyhow::Result<T>) -> Result<(), RepositoryError> {
+        self.row.finished_datetime = Some(Utc::now().naive_utc());
+        self.row.pull_remaining_records = None;
+        self.row.error = result.as_ref().err().map(|error| format!("{:#}", error));
+        self.sync_log_repo.upsert_one(&self.row)
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use repository::{
+    CentralSyncBufferRow, RemoteSyncBufferAction, RemoteSyncBufferRow, RepositoryError,
+    StorageConnection, SyncQuarantineRow, SyncQuarantineRowRepository, SyncQuarantineSource,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::{ServiceContext, ServiceProvider};
+
+use super::{
+    translation_central::{import_sync_records, TRANSLATION_RECORDS},
+    translation_remote::{pull::import_sync_pull_records, REMOTE_TRANSLATION_RECORDS},
+    SyncImportError,
+};
+
+/// Sync record as it was received from the central server
+pub(crate) struct SyncQuarantineRecord<'a> {
+    pub table_name: &'a str,
+    pub record_id: &'a str,
+    pub action: Option<RemoteSyncBufferAction>,
+    pub data: &'a str,
+}
+
+impl<'a> From<&'a CentralSyncBufferRow> for SyncQuarantineRecord<'a> {
+    fn from(row: &'a CentralSyncBufferRow) -> Self {
+        SyncQuarantineRecord {
+            table_name: &row.table_name,
+            record_id: &row.record_id,
+            action: None,
+            data: &row.data,
+        }
+    }
+}
+
+impl<'a> From<&'a RemoteSyncBufferRow> for SyncQuarantineRecord<'a> {
+    fn from(row: &'a RemoteSyncBufferRow) -> Self {
+        SyncQuarantineRecord {
+            table_name: &row.table_name,
+            record_id: &row.record_id,
+            action: Some(row.action.clone()),
+            data: &row.data,
+        }
+    }
+}
+
+/// Keeps records that failed to translate or integrate in the sync quarantine, and removes them
+/// again once a later attempt succeeds
+pub(crate) struct SyncQuarantine {
+    source: SyncQuarantineSource,
+    /// Quarantined rows by table name and record id
+    rows: HashMap<(String, String), SyncQuarantineRow>,
+}
+
+impl SyncQuarantine {
+    pub(crate) fn load(
+        connection: &StorageConnection,
+        source: SyncQuarantineSource,
+    ) -> Result<Self, RepositoryError> {
+        let rows = SyncQuarantineRowRepository::new(connection)
+            .find_many_by_source(source)?
+            .into_iter()
+            .map(|row| ((row.table_name.clone(), row.record_id.clone()), row))
+            .collect();
+        Ok(SyncQuarantine { source, rows })
+    }
+
+    pub(crate) fn record_failure(
+        &mut self,
+        connection: &StorageConnection,
+        record: SyncQuarantineRecord,
+        error: String,
+    ) -> Result<(), RepositoryError> {
+        let now = Utc::now().naive_utc();
+        let key = (record.table_name.to_string(), record.record_id.to_string());
+        let row = match self.rows.remove(&key) {
+            Some(row) => SyncQuarantineRow {
+                action: record.action,
+                data: record.data.to_string(),
+                error,
+                attempt_count: row.attempt_count + 1,
+                last_failed_datetime: now,
+                ..row
+            },
+            None => SyncQuarantineRow {
+                id: uuid(),
+                source: self.source,
+                table_name: record.table_name.to_string(),
+                record_id: record.record_id.to_string(),
+                action: record.action,
+                data: record.data.to_string(),
+                error,
+                attempt_count: 1,
+                first_failed_datetime: now,
+                last_failed_datetime: now,
+            },
+        };
+        SyncQuarantineRowRepository::new(connection).upsert_one(&row)?;
+        self.rows.insert(key, row);
+        Ok(())
+    }
+
+    pub(crate) fn record_success(
+        &mut self,
+        connection: &StorageConnection,
+        record: SyncQuarantineRecord,
+    ) -> Result<(), RepositoryError> {
+        let key = (record.table_name.to_string(), record.record_id.to_string());
+        if let Some(row) = self.rows.remove(&key) {
+            SyncQuarantineRowRepository::new(connection).delete(&row.id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum RetrySyncQuarantineError {
+    /// Records can't be retried while a sync is integrating records
+    SyncInProgress,
+    DatabaseError(RepositoryError),
+    ImportError(SyncImportError),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RetrySyncQuarantineResult {
+    pub integrated_count: u32,
+    /// Retried records that failed again
+    pub still_quarantined: Vec<SyncQuarantineRow>,
+}
+
+pub trait SyncQuarantineServiceTrait: Sync + Send {
+    /// Most recently failed records first
+    fn get_sync_quarantine(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Vec<SyncQuarantineRow>, RepositoryError> {
+        SyncQuarantineRowRepository::new(&ctx.connection).find_all()
+    }
+
+    /// Retries integration of the quarantined records with the given ids, or of all quarantined
+    /// records if no ids are given. Records are removed from the quarantine once integrated.
+    ///
+    /// Takes a ServiceProvider instead of a ServiceContext to hold the sync lock while retrying.
+    fn retry_sync_quarantine(
+        &self,
+        service_provider: &ServiceProvider,
+        ids: Option<Vec<String>>,
+    ) -> Result<RetrySyncQuarantineResult, RetrySyncQuarantineError> {
+        let _sync_lock = service_provider
+            .sync_lock
+            .try_lock()
+            .map_err(|_| RetrySyncQuarantineError::SyncInProgress)?;
+        retry_sync_quarantine(&service_provider.context()?, ids)
+    }
+}
+
+pub struct SyncQuarantineService {}
+impl SyncQuarantineServiceTrait for SyncQuarantineService {}
+
+fn retry_sync_quarantine(
+    ctx: &ServiceContext,
+    ids: Option<Vec<String>>,
+) -> Result<RetrySyncQuarantineResult, RetrySyncQuarantineError> {
+    let repo = SyncQuarantineRowRepository::new(&ctx.connection);
+    let rows = match &ids {
+        Some(ids) => repo.find_many_by_id(ids)?,
+        None => repo.find_all()?,
+    };
+
+    let (mut central_rows, mut remote_rows): (Vec<_>, Vec<_>) = rows
+        .iter()
+        .partition(|row| row.source == SyncQuarantineSource::Central);
+    // Integrate in the same order as a regular sync, so records that depend on each other can be
+    // integrated in one go
+    let table_order = |tables: &[&str], row: &SyncQuarantineRow| {
+        tables
+            .iter()
+            .position(|table| *table == row.table_name)
+            .unwrap_or(tables.len())
+    };
+    central_rows.sort_by_key(|row| table_order(TRANSLATION_RECORDS, *row));
+    remote_rows.sort_by_key(|row| table_order(REMOTE_TRANSLATION_RECORDS, *row));
+
+    let central_records = central_rows
+        .into_iter()
+        .map(|row| CentralSyncBufferRow {
+            // Only used to order records in the central sync buffer
+            id: 0,
+            table_name: row.table_name.clone(),
+            record_id: row.record_id.clone(),
+            data: row.data.clone(),
+        })
+        .collect();
+    import_sync_records(&ctx.connection, &central_records)?;
+
+    let remote_records = remote_rows
+        .into_iter()
+        .filter_map(|row| {
+            Some(RemoteSyncBufferRow {
+                id: row.id.clone(),
+                table_name: row.table_name.clone(),
+                record_id: row.record_id.clone(),
+                action: row.action.clone()?,
+                data: row.data.clone(),
+            })
+        })
+        .collect();
+    import_sync_pull_records(&ctx.connection, &remote_records)?;
+
+    let retried_ids: Vec<String> = rows.into_iter().map(|row| row.id).collect();
+    let still_quarantined = repo.find_many_by_id(&retried_ids)?;
+    Ok(RetrySyncQuarantineResult {
+        integrated_count: (retried_ids.len() - still_quarantined.len()) as u32,
+        still_quarantined,
+    })
+}
+
+impl From<RepositoryError> for RetrySyncQuarantineError {
+    fn from(error: RepositoryError) -> Self {
+        RetrySyncQuarantineError::DatabaseError(error)
+    }
+}
+
+impl From<SyncImportError> for RetrySyncQuarantineError {
+    fn from(error: SyncImportError) -> Self {
+        RetrySyncQuarantineError::ImportError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, StoreRowRepository, SyncQuarantineSource,
+    };
+
+    use crate::{
+        service_provider::ServiceProvider,
+        sync::translation_central::{
+            import_sync_records,
+            test_data::{
+                extract_sync_buffer_rows, name::get_test_name_records,
+                store::get_test_store_records,
+            },
+        },
+    };
+
+    use super::{RetrySyncQuarantineError, RetrySyncQuarantineResult};
+
+    #[actix_rt::test]
+    async fn sync_quarantine() {
+        let (_, connection, connection_manager, _) =
+            setup_all("sync_quarantine", MockDataInserts::none()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = &service_provider.sync_quarantine_service;
+
+        // Stores reference names that haven't been pulled yet
+        let store_records = extract_sync_buffer_rows(&get_test_store_records());
+        import_sync_records(&connection, &store_records).unwrap();
+
+        let quarantined = service.get_sync_quarantine(&context).unwrap();
+        assert!(!quarantined.is_empty());
+        assert!(quarantined
+            .iter()
+            .all(|row| row.source == SyncQuarantineSource::Central
+                && row.table_name == "store"
+                && row.attempt_count == 1));
+
+        // Names are still missing
+        let result = service
+            .retry_sync_quarantine(&service_provider, None)
+            .unwrap();
+        assert_eq!(result.integrated_count, 0);
+        assert_eq!(result.still_quarantined.len(), quarantined.len());
+        assert!(result
+            .still_quarantined
+            .iter()
+            .all(|row| row.attempt_count == 2));
+
+        // Names have arrived
+        import_sync_records(
+            &connection,
+            &extract_sync_buffer_rows(&get_test_name_records()),
+        )
+        .unwrap();
+        let result = service
+            .retry_sync_quarantine(
+                &service_provider,
+                Some(quarantined.iter().map(|row| row.id.clone()).collect()),
+            )
+            .unwrap();
+        assert_eq!(
+            result,
+            RetrySyncQuarantineResult {
+                integrated_count: quarantined.len() as u32,
+                still_quarantined: vec![],
+            }
+        );
+        assert_eq!(service.get_sync_quarantine(&context).unwrap(), vec![]);
+        assert!(StoreRowRepository::new(&connection)
+            .find_one_by_id(&quarantined[0].record_id)
+            .unwrap()
+            .is_some());
+
+        // Not while a sync is running
+        let _sync_lock = service_provider.sync_lock.try_lock().unwrap();
+        assert!(matches!(
+            service.retry_sync_quarantine(&service_provider, None),
+            Err(RetrySyncQuarantineError::SyncInProgress)
+        ));
+    }
+}
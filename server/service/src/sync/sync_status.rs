@@ -0,0 +1,104 @@
+use repository::{RepositoryError, SyncLogRow, SyncLogRowRepository};
+
+use crate::service_provider::ServiceContext;
+
+/// Maximum number of sync runs returned in the sync history
+pub const MAX_SYNC_HISTORY: u32 = 100;
+
+pub trait SyncStatusServiceTrait: Sync + Send {
+    /// Latest sync run, this is the sync in progress if there is one
+    fn get_latest_sync_status(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Option<SyncLogRow>, RepositoryError> {
+        SyncLogRowRepository::new(&ctx.connection).find_latest()
+    }
+
+    /// Latest sync run that finished without an error
+    fn get_latest_successful_sync_status(
+        &self,
+        ctx: &ServiceContext,
+    ) -> Result<Option<SyncLogRow>, RepositoryError> {
+        SyncLogRowRepository::new(&ctx.connection).find_latest_successful()
+    }
+
+    /// Most recent sync runs first, limited to [MAX_SYNC_HISTORY]
+    fn get_sync_history(
+        &self,
+        ctx: &ServiceContext,
+        limit: Option<u32>,
+    ) -> Result<Vec<SyncLogRow>, RepositoryError> {
+        let limit = limit.unwrap_or(MAX_SYNC_HISTORY).min(MAX_SYNC_HISTORY);
+        SyncLogRowRepository::new(&ctx.connection).find_many_latest_first(limit)
+    }
+}
+
+pub struct SyncStatusService {}
+impl SyncStatusServiceTrait for SyncStatusService {}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use repository::{mock::MockDataInserts, test_db::setup_all, SyncLogStage};
+    use util::inline_init;
+
+    use crate::{
+        service_provider::ServiceProvider,
+        sync::{settings::SyncSettings, sync_logger::SyncLogger, Synchroniser},
+    };
+
+    #[actix_rt::test]
+    async fn sync_status() {
+        let (_, connection, connection_manager, _) =
+            setup_all("sync_status", MockDataInserts::none()).await;
+
+        let service_provider = Arc::new(ServiceProvider::new(connection_manager, "app_data"));
+        let context = service_provider.context().unwrap();
+        let service = &service_provider.sync_status_service;
+
+        assert_eq!(service.get_latest_sync_status(&context).unwrap(), None);
+
+        // Successful sync
+        let mut logger = SyncLogger::start(&connection, false).unwrap();
+        logger.stage(SyncLogStage::Push).unwrap();
+        logger.pushed(3).unwrap();
+        logger.stage(SyncLogStage::RemotePull).unwrap();
+        logger.pulled_remote(5, 2).unwrap();
+        logger.pulled_remote(2, 0).unwrap();
+        logger.stage(SyncLogStage::Integrate).unwrap();
+        logger.integrated(7).unwrap();
+        logger.finish(&Ok(())).unwrap();
+
+        let successful = service.get_latest_sync_status(&context).unwrap().unwrap();
+        assert!(successful.finished_datetime.is_some());
+        assert_eq!(successful.stage, Some(SyncLogStage::Integrate));
+        assert_eq!(successful.pushed_records, 3);
+        assert_eq!(successful.pulled_remote_records, 7);
+        assert_eq!(successful.integrated_records, 7);
+        assert_eq!(successful.error, None);
+
+        // Failed sync, central server can't be reached
+        let synchroniser = Synchroniser::new(
+            inline_init(|r: &mut SyncSettings| r.url = "http://0.0.0.0:0".to_string()),
+            service_provider.clone(),
+        )
+        .unwrap();
+        assert!(synchroniser.sync().await.is_err());
+
+        let failed = service.get_latest_sync_status(&context).unwrap().unwrap();
+        assert_ne!(failed.id, successful.id);
+        assert!(failed.finished_datetime.is_some());
+        assert!(failed.stage.is_some());
+        assert!(failed.error.is_some());
+
+        assert_eq!(
+            service.get_latest_successful_sync_status(&context).unwrap(),
+            Some(successful.clone())
+        );
+        assert_eq!(
+            service.get_sync_history(&context, None).unwrap(),
+            vec![failed, successful]
+        );
+    }
+}
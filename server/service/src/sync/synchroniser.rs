@@ -2,20 +2,26 @@ use crate::{
     apis::{sync_api_credentials::SyncCredentials, sync_api_v3::SyncApiV3, sync_api_v5::SyncApiV5},
     service_provider::ServiceProvider,
 };
-use log::warn;
+use log::{error, warn};
+use repository::StorageConnection;
 use reqwest::{Client, Url};
 use std::{sync::Arc, time::Duration};
 
 use super::{
     central_data_synchroniser::{CentralDataSynchroniser, CentralSyncError},
-    get_sync_actors,
-    remote_data_synchroniser::RemoteDataSynchroniser,
+    remote_data_synchroniser::{RemoteDataSynchroniser, RemoteSyncError, RemoteSyncState},
     settings::SyncSettings,
-    SyncReceiverActor, SyncSenderActor,
+    sync_conflict::SyncConflictDetector,
+    sync_logger::SyncLogger,
+    SyncReceiverActor,
 };
 
+/// How long to wait for a response when checking if the central server is reachable
+const CENTRAL_SERVER_ONLINE_CHECK_TIMEOUT_SEC: u64 = 10;
+
 pub struct Synchroniser {
     settings: SyncSettings,
+    client: Client,
     service_provider: Arc<ServiceProvider>,
     pub(crate) central_data: CentralDataSynchroniser,
     pub(crate) remote_data: RemoteDataSynchroniser,
@@ -65,9 +71,17 @@ impl Synchroniser {
             credentials.clone(),
             client.clone(),
             &hardware_id,
+            settings.site_id,
         );
-        let sync_api_v3 = SyncApiV3::new(url, credentials, client, &hardware_id)?;
+        let sync_api_v3 = SyncApiV3::new(
+            url,
+            credentials,
+            client.clone(),
+            &hardware_id,
+            settings.site_id,
+        )?;
         Ok(Synchroniser {
+            client,
             remote_data: RemoteDataSynchroniser {
                 sync_api_v5: sync_api_v5.clone(),
                 sync_api_v3,
@@ -81,6 +95,7 @@ impl Synchroniser {
     }
 
     pub async fn initial_pull(&self) -> anyhow::Result<()> {
+        let _sync_lock = self.service_provider.sync_lock.lock().await;
         let ctx = self
             .service_provider
             .context()
@@ -95,18 +110,58 @@ impl Synchroniser {
             return Ok(());
         }
 
+        self.force_initial_pull().await
+    }
+
+    /// Initial pull that also runs while sync is disabled, e.g. while a site is re-initialised and
+    /// scheduled syncs with the previous settings must not run. The caller must hold the
+    /// `sync_lock` of the service provider.
+    pub(crate) async fn force_initial_pull(&self) -> anyhow::Result<()> {
+        let ctx = self
+            .service_provider
+            .context()
+            .map_err(CentralSyncError::from_database_error)?;
+
+        // Central data is pulled on every start up, only log it as an initial pull while the
+        // remote data hasn't been pulled yet
+        let is_initial_pull = !RemoteSyncState::new(&ctx.connection)
+            .initial_remote_data_synced()
+            .map_err(CentralSyncError::from_database_error)?;
+        let mut logger = SyncLogger::start(&ctx.connection, is_initial_pull)
+            .map_err(CentralSyncError::from_database_error)?;
+        let result = self.do_initial_pull(&ctx.connection, &mut logger).await;
+        // Failing to log the end of the sync mustn't hide the sync result
+        if let Err(error) = logger.finish(&result) {
+            error!("Failed to log the end of the sync: {:?}", error);
+        }
+        result
+    }
+
+    async fn do_initial_pull(
+        &self,
+        connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
+    ) -> anyhow::Result<()> {
         // first pull data from the central server
         self.central_data
-            .pull_and_integrate_records(&ctx.connection)
+            .pull_and_integrate_records(connection, logger)
             .await?;
 
-        self.remote_data.initial_pull(&ctx.connection).await?;
+        self.remote_data.initial_pull(connection, logger).await?;
 
         Ok(())
     }
 
-    /// Sync must not be called concurrently (e.g. sync cursors are fetched/updated without DB tx)
+    /// Sync must not be called concurrently (e.g. sync cursors are fetched/updated without DB tx),
+    /// the sync is skipped while another sync or a site re-initialisation is running
     pub async fn sync(&self) -> anyhow::Result<()> {
+        let _sync_lock = match self.service_provider.sync_lock.try_lock() {
+            Ok(sync_lock) => sync_lock,
+            Err(_) => {
+                warn!("Another sync is running, skipping");
+                return Ok(());
+            }
+        };
         let ctx = self
             .service_provider
             .context()
@@ -121,42 +176,87 @@ impl Synchroniser {
             return Ok(());
         }
 
+        let mut logger = SyncLogger::start(&ctx.connection, false)
+            .map_err(CentralSyncError::from_database_error)?;
+        let result = self.do_sync(&ctx.connection, &mut logger).await;
+        // Failing to log the end of the sync mustn't hide the sync result
+        if let Err(error) = logger.finish(&result) {
+            error!("Failed to log the end of the sync: {:?}", error);
+        }
+        result
+    }
+
+    async fn do_sync(
+        &self,
+        connection: &StorageConnection,
+        logger: &mut SyncLogger<'_>,
+    ) -> anyhow::Result<()> {
+        // Snapshot unpushed local changes before they are pushed (which moves the push cursor past
+        // them) and before pulled data is integrated, e.g. integrated names are added to the
+        // changelog as well
+        let conflict_detector = SyncConflictDetector::load(connection, &self.settings)
+            .map_err(RemoteSyncError::from)?;
         // First push before pulling. This avoids problems with the existing central server
         // implementation...
-        self.remote_data.push_changes(&ctx.connection).await?;
-        self.remote_data.pull(&ctx.connection).await?;
+        self.remote_data.push_changes(connection, logger).await?;
+        self.remote_data.pull(connection, logger).await?;
 
         // Check if there is new data on the central server. Do this after pulling the remote data
         // in case the just pulled remote data requires the new central data.
         self.central_data
-            .pull_and_integrate_records(&ctx.connection)
+            .pull_and_integrate_records(connection, logger)
             .await?;
 
-        RemoteDataSynchroniser::integrate_records(&ctx.connection).await?;
+        RemoteDataSynchroniser::integrate_records(connection, logger, Some(&conflict_detector))
+            .await?;
         Ok(())
     }
 
-    /// Runs the continues sync process (not suppose to return)
-    pub async fn run(&mut self) {
-        let (mut sync_sender, mut sync_receiver): (SyncSenderActor, SyncReceiverActor) =
-            get_sync_actors();
-
-        tokio::select! {
-            () = async {
-              sync_sender.schedule_send(Duration::from_secs(self.settings.interval_sec)).await;
-            } => unreachable!("Sync receiver unexpectedly died!?"),
-            () = async {
-                sync_receiver.listen(self).await;
-            } => unreachable!("Sync scheduler unexpectedly died!?"),
-        };
+    /// Returns true if the central server responds, regardless of the response status
+    pub async fn is_central_server_reachable(&self) -> bool {
+        self.client
+            .get(&self.settings.url)
+            .timeout(Duration::from_secs(CENTRAL_SERVER_ONLINE_CHECK_TIMEOUT_SEC))
+            .send()
+            .await
+            .is_ok()
+    }
+
+    /// Runs the continues sync process (not suppose to return), a sync can be triggered through
+    /// the [SyncSenderActor](super::SyncSenderActor) matching the `sync_receiver`
+    pub async fn run(&self, mut sync_receiver: SyncReceiverActor) {
+        sync_receiver
+            .listen(
+                self,
+                Duration::from_secs(self.settings.interval_sec),
+                self.settings.quiet_hours.clone(),
+            )
+            .await;
+        unreachable!("Sync receiver unexpectedly died!?")
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use repository::{mock::MockDataInserts, test_db::setup_all};
+    use httpmock::{
+        Method::{GET, POST},
+        MockServer,
+    };
+    use repository::{
+        mock::MockDataInserts, test_db::setup_all, ChangelogRowRepository, LocationRowRepository,
+        RemoteSyncBufferRepository,
+    };
     use util::inline_init;
 
+    use crate::{
+        apis::sync_api_v5::{
+            CentralSyncBatchV5, RemoteSyncActionV5, RemoteSyncBatchV5, RemoteSyncRecordV5,
+        },
+        sync::translation_remote::test_data::{
+            extract_sync_buffer_rows, location::get_test_location_records,
+        },
+    };
+
     use super::*;
 
     #[actix_rt::test]
@@ -196,4 +296,92 @@ mod tests {
             "sync should succeeded with early return"
         );
     }
+
+    #[actix_rt::test]
+    async fn test_sync_detects_conflicts_with_pushed_changes() {
+        let (_, connection, connection_manager, _) = setup_all(
+            "test_sync_detects_conflicts_with_pushed_changes",
+            MockDataInserts::all(),
+        )
+        .await;
+
+        let service_provider =
+            Arc::new(ServiceProvider::new(connection_manager.clone(), "app_data"));
+        let ctx = service_provider.context().unwrap();
+
+        // Remote location that has been integrated and pushed before
+        let remote_location = extract_sync_buffer_rows(&get_test_location_records()).remove(0);
+        RemoteSyncBufferRepository::new(&connection)
+            .upsert_many(&vec![remote_location.clone()])
+            .unwrap();
+        RemoteDataSynchroniser::do_integrate_records(&connection, None).unwrap();
+        let push_cursor = ChangelogRowRepository::new(&connection)
+            .latest_changelog()
+            .unwrap()
+            .map(|row| row.id + 1)
+            .unwrap_or(0);
+        RemoteSyncState::new(&connection)
+            .update_push_cursor(push_cursor as u32)
+            .unwrap();
+
+        // Local change, pushed by the sync that pulls the remote change
+        let location_repo = LocationRowRepository::new(&connection);
+        let mut local_location = location_repo
+            .find_one_by_id(&remote_location.record_id)
+            .unwrap()
+            .unwrap();
+        local_location.name = "local name".to_string();
+        location_repo.upsert_one(&local_location).unwrap();
+
+        let mock_server = MockServer::start();
+        let push_mock = mock_server.mock(|when, then| {
+            when.method(POST).path("/sync/v3/queued_records");
+            then.status(200).body("{}");
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/sync/v5/queued_records");
+            then.status(200).body(
+                serde_json::to_string(&RemoteSyncBatchV5 {
+                    queue_length: 1,
+                    data: Some(vec![RemoteSyncRecordV5 {
+                        sync_id: "sync_id".to_string(),
+                        table: remote_location.table_name.clone(),
+                        record_id: remote_location.record_id.clone(),
+                        action: RemoteSyncActionV5::Update,
+                        data: serde_json::from_str(&remote_location.data).unwrap(),
+                    }]),
+                })
+                .unwrap(),
+            );
+        });
+        mock_server.mock(|when, then| {
+            when.method(POST).path("/sync/v5/acknowledged_records");
+            then.status(200);
+        });
+        mock_server.mock(|when, then| {
+            when.method(GET).path("/sync/v5/central_records");
+            then.status(200).body(
+                serde_json::to_string(&CentralSyncBatchV5 {
+                    max_cursor: 0,
+                    data: None,
+                })
+                .unwrap(),
+            );
+        });
+
+        let s = Synchroniser::new(
+            inline_init(|r: &mut SyncSettings| r.url = mock_server.base_url()),
+            service_provider.clone(),
+        )
+        .unwrap();
+        s.sync().await.unwrap();
+        push_mock.assert();
+
+        let conflicts = service_provider
+            .sync_conflict_service
+            .get_sync_conflicts(&ctx)
+            .unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].record_id, remote_location.record_id);
+    }
 }
@@ -22,6 +22,28 @@ pub struct LegacyItemRow {
     unit_ID: String,
     type_of: LegacyItemType,
     default_pack_size: i32,
+    #[serde(default)]
+    doses: i32,
+    #[serde(default)]
+    is_vaccine: bool,
+    #[serde(default)]
+    category_ID: String,
+    #[serde(default)]
+    department_ID: String,
+    #[serde(default)]
+    atc_category: String,
+    #[serde(default)]
+    strength: String,
+    #[serde(default)]
+    outer_pack_size: i32,
+    #[serde(default)]
+    volume_per_pack: f64,
+    #[serde(default)]
+    weight: f64,
+    #[serde(default)]
+    essential_drug_list: bool,
+    #[serde(default)]
+    is_serial_tracked: bool,
 }
 
 fn to_item_type(type_of: LegacyItemType) -> ItemRowType {
@@ -52,12 +74,29 @@ impl CentralPushTranslation for ItemTranslation {
             unit_id: None,
             default_pack_size: data.default_pack_size,
             r#type: to_item_type(data.type_of),
+            doses: data.doses,
+            is_vaccine: data.is_vaccine,
+            category_id: None,
+            department_id: None,
+            atc_category: data.atc_category,
+            strength: data.strength,
+            outer_pack_size: data.outer_pack_size,
+            volume_per_pack: data.volume_per_pack,
+            weight: data.weight,
+            essential_drug_list: data.essential_drug_list,
+            is_serial_tracked: data.is_serial_tracked,
             legacy_record: sync_record.data.clone(),
         };
 
         if data.unit_ID != "" {
             result.unit_id = Some(data.unit_ID);
         }
+        if data.category_ID != "" {
+            result.category_id = Some(data.category_ID);
+        }
+        if data.department_ID != "" {
+            result.department_id = Some(data.department_ID);
+        }
 
         Ok(Some(IntegrationUpsertRecord::Item(result)))
     }
@@ -0,0 +1,70 @@
+use crate::sync::translation_central::TRANSLATION_RECORD_ITEM_CATEGORY;
+use repository::{CentralSyncBufferRow, ItemCategoryRow};
+
+use serde::Deserialize;
+
+use super::{CentralPushTranslation, IntegrationUpsertRecord};
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+pub struct LegacyItemCategoryRow {
+    ID: String,
+    Description: String,
+    #[serde(default)]
+    parent_ID: String,
+}
+
+pub struct ItemCategoryTranslation {}
+impl CentralPushTranslation for ItemCategoryTranslation {
+    fn try_translate(
+        &self,
+        sync_record: &CentralSyncBufferRow,
+    ) -> Result<Option<IntegrationUpsertRecord>, anyhow::Error> {
+        let table_name = TRANSLATION_RECORD_ITEM_CATEGORY;
+        if sync_record.table_name != table_name {
+            return Ok(None);
+        }
+
+        let data = serde_json::from_str::<LegacyItemCategoryRow>(&sync_record.data)?;
+        let result = ItemCategoryRow {
+            id: data.ID,
+            name: data.Description,
+            parent_id: if data.parent_ID.is_empty() {
+                None
+            } else {
+                Some(data.parent_ID)
+            },
+        };
+
+        Ok(Some(IntegrationUpsertRecord::ItemCategory(result)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CentralPushTranslation;
+    use crate::sync::translation_central::{
+        item_category::ItemCategoryTranslation,
+        test_data::{item_category::get_test_item_category_records, TestSyncDataRecord},
+        IntegrationUpsertRecord,
+    };
+
+    #[test]
+    fn test_item_category_translation() {
+        for record in get_test_item_category_records() {
+            match record.translated_record {
+                TestSyncDataRecord::ItemCategory(translated_record) => {
+                    assert_eq!(
+                        ItemCategoryTranslation {}
+                            .try_translate(&record.central_sync_buffer_row)
+                            .unwrap(),
+                        translated_record.map(|r| (IntegrationUpsertRecord::ItemCategory(r))),
+                        "{}",
+                        record.identifier
+                    )
+                }
+                _ => panic!("Testing wrong record type {:#?}", record.translated_record),
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::sync::translation_central::TRANSLATION_RECORD_ITEM_DEPARTMENT;
+use repository::{CentralSyncBufferRow, ItemDepartmentRow};
+
+use serde::Deserialize;
+
+use super::{CentralPushTranslation, IntegrationUpsertRecord};
+
+#[allow(non_snake_case)]
+#[derive(Deserialize)]
+pub struct LegacyItemDepartmentRow {
+    ID: String,
+    department: String,
+}
+
+pub struct ItemDepartmentTranslation {}
+impl CentralPushTranslation for ItemDepartmentTranslation {
+    fn try_translate(
+        &self,
+        sync_record: &CentralSyncBufferRow,
+    ) -> Result<Option<IntegrationUpsertRecord>, anyhow::Error> {
+        let table_name = TRANSLATION_RECORD_ITEM_DEPARTMENT;
+        if sync_record.table_name != table_name {
+            return Ok(None);
+        }
+
+        let data = serde_json::from_str::<LegacyItemDepartmentRow>(&sync_record.data)?;
+        let result = ItemDepartmentRow {
+            id: data.ID,
+            name: data.department,
+        };
+
+        Ok(Some(IntegrationUpsertRecord::ItemDepartment(result)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CentralPushTranslation;
+    use crate::sync::translation_central::{
+        item_department::ItemDepartmentTranslation,
+        test_data::{item_department::get_test_item_department_records, TestSyncDataRecord},
+        IntegrationUpsertRecord,
+    };
+
+    #[test]
+    fn test_item_department_translation() {
+        for record in get_test_item_department_records() {
+            match record.translated_record {
+                TestSyncDataRecord::ItemDepartment(translated_record) => {
+                    assert_eq!(
+                        ItemDepartmentTranslation {}
+                            .try_translate(&record.central_sync_buffer_row)
+                            .unwrap(),
+                        translated_record.map(|r| (IntegrationUpsertRecord::ItemDepartment(r))),
+                        "{}",
+                        record.identifier
+                    )
+                }
+                _ => panic!("Testing wrong record type {:#?}", record.translated_record),
+            }
+        }
+    }
+}
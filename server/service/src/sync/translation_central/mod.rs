@@ -1,4 +1,6 @@
 mod item;
+mod item_category;
+mod item_department;
 mod list_master;
 mod list_master_line;
 mod list_master_name_join;
@@ -9,15 +11,17 @@ pub mod test_data;
 mod unit;
 
 use crate::sync::translation_central::{
-    item::ItemTranslation, list_master_line::MasterListLineTranslation,
+    item::ItemTranslation, item_category::ItemCategoryTranslation,
+    item_department::ItemDepartmentTranslation, list_master_line::MasterListLineTranslation,
     list_master_name_join::MasterListNameJoinTranslation, report::ReportTranslation,
 };
 use repository::{
-    CentralSyncBufferRow, ItemRow, ItemRowRepository, MasterListLineRow,
+    CentralSyncBufferRow, ItemCategoryRow, ItemCategoryRowRepository, ItemDepartmentRow,
+    ItemDepartmentRowRepository, ItemRow, ItemRowRepository, MasterListLineRow,
     MasterListLineRowRepository, MasterListNameJoinRepository, MasterListNameJoinRow,
     MasterListRow, MasterListRowRepository, NameRow, NameRowRepository, ReportRow,
     ReportRowRepository, RepositoryError, StorageConnection, StoreRow, StoreRowRepository,
-    TransactionError, UnitRow, UnitRowRepository,
+    SyncQuarantineSource, TransactionError, UnitRow, UnitRowRepository,
 };
 
 use log::{info, warn};
@@ -30,12 +34,14 @@ use self::{
 // Also used for remote data sync
 pub use name::{translate_name, LegacyNameRow, LegacyNameType};
 
-use super::{SyncImportError, SyncTranslationError};
+use super::{sync_quarantine::SyncQuarantine, SyncImportError, SyncTranslationError};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub enum IntegrationUpsertRecord {
     Unit(UnitRow),
     Name(NameRow),
+    ItemCategory(ItemCategoryRow),
+    ItemDepartment(ItemDepartmentRow),
     Item(ItemRow),
     Store(StoreRow),
     MasterList(MasterListRow),
@@ -44,11 +50,6 @@ pub enum IntegrationUpsertRecord {
     Report(ReportRow),
 }
 
-#[derive(Debug)]
-struct IntegrationRecord {
-    pub upserts: Vec<IntegrationUpsertRecord>,
-}
-
 pub trait CentralPushTranslation {
     fn try_translate(
         &self,
@@ -57,14 +58,15 @@ pub trait CentralPushTranslation {
 }
 
 /// Translates sync records into the local DB schema.
-/// Translated records are added to integration_records.
+/// Returns None if the record is not used by the remote server.
 fn do_translation(
     sync_record: &CentralSyncBufferRow,
-    records: &mut IntegrationRecord,
-) -> Result<(), SyncTranslationError> {
+) -> Result<Option<IntegrationUpsertRecord>, SyncTranslationError> {
     let translations: Vec<Box<dyn CentralPushTranslation>> = vec![
         Box::new(NameTranslation {}),
         Box::new(UnitTranslation {}),
+        Box::new(ItemCategoryTranslation {}),
+        Box::new(ItemDepartmentTranslation {}),
         Box::new(ItemTranslation {}),
         Box::new(StoreTranslation {}),
         Box::new(MasterListTranslation {}),
@@ -83,8 +85,7 @@ fn do_translation(
                 })?;
 
         if let Some(translated_record) = result {
-            records.upserts.push(translated_record);
-            return Ok(());
+            return Ok(Some(translated_record));
         }
     }
     log::info!(
@@ -92,11 +93,13 @@ fn do_translation(
         sync_record.table_name,
         sync_record.record_id
     );
-    Ok(())
+    Ok(None)
 }
 
 pub const TRANSLATION_RECORD_NAME: &str = "name";
 pub const TRANSLATION_RECORD_UNIT: &str = "unit";
+pub const TRANSLATION_RECORD_ITEM_CATEGORY: &str = "item_category";
+pub const TRANSLATION_RECORD_ITEM_DEPARTMENT: &str = "item_department";
 pub const TRANSLATION_RECORD_ITEM: &str = "item";
 pub const TRANSLATION_RECORD_STORE: &str = "store";
 pub const TRANSLATION_RECORD_LIST_MASTER: &str = "list_master";
@@ -109,6 +112,8 @@ pub const TRANSLATION_RECORD_REPORT: &str = "report";
 pub const TRANSLATION_RECORDS: &[&str] = &[
     TRANSLATION_RECORD_NAME,
     TRANSLATION_RECORD_UNIT,
+    TRANSLATION_RECORD_ITEM_CATEGORY,
+    TRANSLATION_RECORD_ITEM_DEPARTMENT,
     TRANSLATION_RECORD_ITEM,
     TRANSLATION_RECORD_STORE,
     TRANSLATION_RECORD_LIST_MASTER,
@@ -119,27 +124,37 @@ pub const TRANSLATION_RECORDS: &[&str] = &[
 
 /// Imports sync records and writes them to the DB
 /// If needed data records are translated to the local DB schema.
-pub async fn import_sync_records(
+/// Records that fail to translate or integrate are put into the sync quarantine.
+pub fn import_sync_records(
     connection: &StorageConnection,
     records: &Vec<CentralSyncBufferRow>,
 ) -> Result<(), SyncImportError> {
-    let mut integration_records = IntegrationRecord {
-        upserts: Vec::new(),
-    };
+    let mut quarantine = SyncQuarantine::load(connection, SyncQuarantineSource::Central)
+        .map_err(|error| SyncImportError::as_integration_error(error, "Load sync quarantine"))?;
+    let mut integration_records = Vec::new();
 
     info!(
         "Translating {} central sync buffer records...",
         records.len()
     );
     for record in records {
-        if let Err(error) = do_translation(&record, &mut integration_records) {
-            warn!("Failed to translate ({}): {:?}", error, record)
+        match do_translation(&record) {
+            Ok(Some(upsert)) => integration_records.push((record, upsert)),
+            Ok(None) => {}
+            Err(error) => {
+                warn!("Failed to translate ({}): {:?}", error, record);
+                quarantine
+                    .record_failure(connection, record.into(), format!("{:#}", error.source))
+                    .map_err(|error| {
+                        SyncImportError::as_integration_error(error, "Quarantine record")
+                    })?;
+            }
         }
     }
     info!("Succesfully translated central sync buffer records");
 
     info!("Storing integration records...");
-    store_integration_records(connection, &integration_records).await?;
+    store_integration_records(connection, &integration_records, &mut quarantine)?;
     info!("Successfully stored integration records");
 
     Ok(())
@@ -152,6 +167,12 @@ fn integrate_record(
     match &record {
         IntegrationUpsertRecord::Name(record) => NameRowRepository::new(con).upsert_one(record),
         IntegrationUpsertRecord::Unit(record) => UnitRowRepository::new(con).upsert_one(record),
+        IntegrationUpsertRecord::ItemCategory(record) => {
+            ItemCategoryRowRepository::new(con).upsert_one(record)
+        }
+        IntegrationUpsertRecord::ItemDepartment(record) => {
+            ItemDepartmentRowRepository::new(con).upsert_one(record)
+        }
         IntegrationUpsertRecord::Item(record) => ItemRowRepository::new(con).upsert_one(record),
         IntegrationUpsertRecord::Store(record) => StoreRowRepository::new(con).upsert_one(record),
         IntegrationUpsertRecord::MasterList(record) => {
@@ -167,28 +188,34 @@ fn integrate_record(
     }
 }
 
-async fn store_integration_records(
+fn store_integration_records(
     connection: &StorageConnection,
-    integration_records: &IntegrationRecord,
+    integration_records: &Vec<(&CentralSyncBufferRow, IntegrationUpsertRecord)>,
+    quarantine: &mut SyncQuarantine,
 ) -> Result<(), SyncImportError> {
     connection
         .transaction_sync(|con| {
-            for record in &integration_records.upserts {
+            for (sync_record, record) in integration_records {
                 // Integrate every record in a sub transaction. This is mainly for Postgres where the
                 // whole transaction fails when there is a DB error (not a problem in sqlite).
                 let sub_result =
                     con.transaction_sync_etc(|sub_tx| integrate_record(record, sub_tx), false);
                 match sub_result {
-                    Ok(_) => Ok(()),
+                    Ok(_) => quarantine.record_success(con, (*sync_record).into()),
                     Err(TransactionError::Inner(err @ RepositoryError::ForeignKeyViolation(_))) => {
                         warn!("Failed to import ({}): {:?}", err, record);
-                        Ok(())
+                        quarantine.record_failure(con, (*sync_record).into(), format!("{}", err))
                     }
-                    Err(err) => Err(SyncImportError::as_integration_error(
-                        RepositoryError::from(err),
-                        "",
-                    )),
-                }?;
+                    Err(err) => {
+                        return Err(SyncImportError::as_integration_error(
+                            RepositoryError::from(err),
+                            "",
+                        ))
+                    }
+                }
+                .map_err(|error| {
+                    SyncImportError::as_integration_error(error, "Update sync quarantine")
+                })?;
             }
             Ok(())
         })
@@ -211,6 +238,8 @@ mod tests {
     use super::test_data::{
         check_records_against_database, extract_sync_buffer_rows,
         item::{get_test_item_records, get_test_item_upsert_records},
+        item_category::get_test_item_category_records,
+        item_department::get_test_item_department_records,
         master_list::{get_test_master_list_records, get_test_master_list_upsert_records},
         master_list_line::get_test_master_list_line_records,
         master_list_name_join::get_test_master_list_name_join_records,
@@ -230,15 +259,15 @@ mod tests {
         records.append(&mut get_test_name_records());
         records.append(&mut get_test_store_records());
         records.append(&mut get_test_unit_records());
+        records.append(&mut get_test_item_category_records());
+        records.append(&mut get_test_item_department_records());
         records.append(&mut get_test_item_records());
         records.append(&mut get_test_master_list_records());
         records.append(&mut get_test_master_list_line_records());
         records.append(&mut get_test_master_list_name_join_records());
         records.append(&mut get_test_report_records());
 
-        import_sync_records(&connection, &extract_sync_buffer_rows(&records))
-            .await
-            .unwrap();
+        import_sync_records(&connection, &extract_sync_buffer_rows(&records)).unwrap();
 
         // Asserts inside this method, to avoid repetition
         check_records_against_database(&connection, records).await;
@@ -265,9 +294,7 @@ mod tests {
         records.append(&mut init_records.iter().cloned().collect());
         records.append(&mut upsert_records.iter().cloned().collect());
 
-        import_sync_records(&connection, &extract_sync_buffer_rows(&records))
-            .await
-            .unwrap();
+        import_sync_records(&connection, &extract_sync_buffer_rows(&records)).unwrap();
 
         // Asserts inside this method, to avoid repetition
         check_records_against_database(&connection, upsert_records).await;
@@ -89,7 +89,7 @@ const ITEM_1_UPSERT: (&'static str, &'static str) = (
     "manufacture_method": "",
     "default_pack_size": 1,
     "dose_picture": "[object Picture]",
-    "atc_category": "",
+    "atc_category": "J07BC01",
     "medication_purpose": "",
     "instructions": "",
     "user_field_7": false,
@@ -104,10 +104,11 @@ const ITEM_1_UPSERT: (&'static str, &'static str) = (
     "spare_ignore_for_orders": false,
     "sms_pack_size": 0,
     "expiry_date_mandatory": false,
-    "volume_per_pack": 0,
-    "department_ID": "",
-    "weight": 0,
-    "essential_drug_list": false,
+    "volume_per_pack": 0.5,
+    "department_ID": "4A1E8D4F6C1B4A6C9E2D7B3F5A8C9D01",
+    "weight": 1.5,
+    "essential_drug_list": true,
+    "is_serial_tracked": true,
     "catalogue_code": "",
     "indic_price": 0,
     "user_field_1": "",
@@ -121,7 +122,7 @@ const ITEM_1_UPSERT: (&'static str, &'static str) = (
     "interaction_group_ID": "",
     "spare_pack_to_one_on_receive": false,
     "cross_ref_item_ID": "",
-    "strength": "",
+    "strength": "10mg",
     "user_field_4": false,
     "user_field_6": "",
     "spare_internal_analysis": 0,
@@ -132,8 +133,8 @@ const ITEM_1_UPSERT: (&'static str, &'static str) = (
     "account_purchases_ID": "330ACC81721C4126BD5DD6769466C5C4",
     "account_income_ID": "EF34ADD07C014AB8914E30CA2E3FEA8D",
     "unit_ID": "A02C91EB6C77400BA783C4CD7C565F29",
-    "outer_pack_size": 0,
-    "category_ID": "",
+    "outer_pack_size": 20,
+    "category_ID": "8E3A7C1D2B4F4E5A9C6D0B1A2F3E4D5C",
     "ABC_category": "",
     "warning_quantity": 0,
     "user_field_5": 0,
@@ -153,8 +154,8 @@ const ITEM_1_UPSERT: (&'static str, &'static str) = (
     "universalcodes_name": "",
     "kit_data": null,
     "custom_data": null,
-    "doses": 0,
-    "is_vaccine": false,
+    "doses": 10,
+    "is_vaccine": true,
     "restricted_location_type_ID": ""
 }"#,
 );
@@ -171,6 +172,17 @@ pub fn get_test_item_records() -> Vec<TestSyncRecord> {
             unit_id: None,
             default_pack_size: 1,
             r#type: ItemRowType::NonStock,
+            doses: 0,
+            is_vaccine: false,
+            category_id: None,
+            department_id: None,
+            atc_category: "".to_owned(),
+            strength: "".to_owned(),
+            outer_pack_size: 0,
+            volume_per_pack: 0.0,
+            weight: 0.0,
+            essential_drug_list: false,
+            is_serial_tracked: false,
             legacy_record: ITEM_1.1.to_owned(),
         })),
         identifier: "Non stock items",
@@ -192,6 +204,17 @@ pub fn get_test_item_upsert_records() -> Vec<TestSyncRecord> {
             unit_id: Some("A02C91EB6C77400BA783C4CD7C565F29".to_owned()),
             default_pack_size: 1,
             r#type: ItemRowType::Stock,
+            doses: 10,
+            is_vaccine: true,
+            category_id: Some("8E3A7C1D2B4F4E5A9C6D0B1A2F3E4D5C".to_owned()),
+            department_id: Some("4A1E8D4F6C1B4A6C9E2D7B3F5A8C9D01".to_owned()),
+            atc_category: "J07BC01".to_owned(),
+            strength: "10mg".to_owned(),
+            outer_pack_size: 20,
+            volume_per_pack: 0.5,
+            weight: 1.5,
+            essential_drug_list: true,
+            is_serial_tracked: true,
             legacy_record: ITEM_1_UPSERT.1.to_owned(),
         })),
         identifier: "Non stock items 2",
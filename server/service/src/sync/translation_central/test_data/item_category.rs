@@ -0,0 +1,59 @@
+use crate::sync::translation_central::{
+    test_data::{TestSyncDataRecord, TestSyncRecord},
+    TRANSLATION_RECORD_ITEM_CATEGORY,
+};
+use repository::{CentralSyncBufferRow, ItemCategoryRow};
+
+const ITEM_CATEGORY_1: (&'static str, &'static str) = (
+    "2C6B1F0E9D8A4B7C8E5F3A1D0B9C8E7F",
+    r#"{
+        "ID": "2C6B1F0E9D8A4B7C8E5F3A1D0B9C8E7F",
+        "Description": "Medicines",
+        "parent_ID": "",
+        "sort_order": 0
+    }"#,
+);
+
+const ITEM_CATEGORY_2: (&'static str, &'static str) = (
+    "8E3A7C1D2B4F4E5A9C6D0B1A2F3E4D5C",
+    r#"{
+        "ID": "8E3A7C1D2B4F4E5A9C6D0B1A2F3E4D5C",
+        "Description": "Vaccines",
+        "parent_ID": "2C6B1F0E9D8A4B7C8E5F3A1D0B9C8E7F",
+        "sort_order": 1
+    }"#,
+);
+
+#[allow(dead_code)]
+pub fn get_test_item_category_records() -> Vec<TestSyncRecord> {
+    vec![
+        TestSyncRecord {
+            translated_record: TestSyncDataRecord::ItemCategory(Some(ItemCategoryRow {
+                id: ITEM_CATEGORY_1.0.to_owned(),
+                name: "Medicines".to_owned(),
+                parent_id: None,
+            })),
+            identifier: "Item category - medicines",
+            central_sync_buffer_row: CentralSyncBufferRow {
+                id: 460,
+                table_name: TRANSLATION_RECORD_ITEM_CATEGORY.to_owned(),
+                record_id: ITEM_CATEGORY_1.0.to_owned(),
+                data: ITEM_CATEGORY_1.1.to_owned(),
+            },
+        },
+        TestSyncRecord {
+            translated_record: TestSyncDataRecord::ItemCategory(Some(ItemCategoryRow {
+                id: ITEM_CATEGORY_2.0.to_owned(),
+                name: "Vaccines".to_owned(),
+                parent_id: Some(ITEM_CATEGORY_1.0.to_owned()),
+            })),
+            identifier: "Item category - vaccines",
+            central_sync_buffer_row: CentralSyncBufferRow {
+                id: 461,
+                table_name: TRANSLATION_RECORD_ITEM_CATEGORY.to_owned(),
+                record_id: ITEM_CATEGORY_2.0.to_owned(),
+                data: ITEM_CATEGORY_2.1.to_owned(),
+            },
+        },
+    ]
+}
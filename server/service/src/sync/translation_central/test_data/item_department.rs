@@ -0,0 +1,30 @@
+use crate::sync::translation_central::{
+    test_data::{TestSyncDataRecord, TestSyncRecord},
+    TRANSLATION_RECORD_ITEM_DEPARTMENT,
+};
+use repository::{CentralSyncBufferRow, ItemDepartmentRow};
+
+const ITEM_DEPARTMENT_1: (&'static str, &'static str) = (
+    "4A1E8D4F6C1B4A6C9E2D7B3F5A8C9D01",
+    r#"{
+        "ID": "4A1E8D4F6C1B4A6C9E2D7B3F5A8C9D01",
+        "department": "Pharmacy"
+    }"#,
+);
+
+#[allow(dead_code)]
+pub fn get_test_item_department_records() -> Vec<TestSyncRecord> {
+    vec![TestSyncRecord {
+        translated_record: TestSyncDataRecord::ItemDepartment(Some(ItemDepartmentRow {
+            id: ITEM_DEPARTMENT_1.0.to_owned(),
+            name: "Pharmacy".to_owned(),
+        })),
+        identifier: "Item department - pharmacy",
+        central_sync_buffer_row: CentralSyncBufferRow {
+            id: 470,
+            table_name: TRANSLATION_RECORD_ITEM_DEPARTMENT.to_owned(),
+            record_id: ITEM_DEPARTMENT_1.0.to_owned(),
+            data: ITEM_DEPARTMENT_1.1.to_owned(),
+        },
+    }]
+}
@@ -1,4 +1,6 @@
 pub mod item;
+pub mod item_category;
+pub mod item_department;
 pub mod master_list;
 pub mod master_list_line;
 pub mod master_list_name_join;
@@ -8,7 +10,8 @@ pub mod store;
 pub mod unit;
 
 use repository::{
-    CentralSyncBufferRow, ItemRow, ItemRowRepository, MasterListLineRow,
+    CentralSyncBufferRow, ItemCategoryRow, ItemCategoryRowRepository, ItemDepartmentRow,
+    ItemDepartmentRowRepository, ItemRow, ItemRowRepository, MasterListLineRow,
     MasterListLineRowRepository, MasterListNameJoinRepository, MasterListNameJoinRow,
     MasterListRow, MasterListRowRepository, NameRow, NameRowRepository, ReportRow,
     ReportRowRepository, RepositoryError, StorageConnection, StoreRow, StoreRowRepository, UnitRow,
@@ -20,6 +23,8 @@ use repository::{
 pub enum TestSyncDataRecord {
     Unit(Option<UnitRow>),
     Item(Option<ItemRow>),
+    ItemCategory(Option<ItemCategoryRow>),
+    ItemDepartment(Option<ItemDepartmentRow>),
     Store(Option<StoreRow>),
     Name(Option<NameRow>),
     MasterList(Option<MasterListRow>),
@@ -94,6 +99,22 @@ pub async fn check_records_against_database(
                     comparison_record
                 )
             }
+            TestSyncDataRecord::ItemCategory(comparison_record) => {
+                assert_eq!(
+                    ItemCategoryRowRepository::new(&connection)
+                        .find_one_by_id(&record.central_sync_buffer_row.record_id)
+                        .unwrap(),
+                    comparison_record
+                )
+            }
+            TestSyncDataRecord::ItemDepartment(comparison_record) => {
+                assert_eq!(
+                    ItemDepartmentRowRepository::new(&connection)
+                        .find_one_by_id(&record.central_sync_buffer_row.record_id)
+                        .unwrap(),
+                    comparison_record
+                )
+            }
             TestSyncDataRecord::MasterList(comparison_record) => {
                 assert_eq!(
                     MasterListRowRepository::new(&connection)
@@ -66,6 +66,12 @@ pub struct LegacyTransLineRow {
     pub total_before_tax: Option<f64>,
     #[serde(rename = "om_total_after_tax")]
     pub total_after_tax: Option<f64>,
+    #[serde(deserialize_with = "empty_str_as_option")]
+    #[serde(default)]
+    pub donor_id: Option<String>,
+    #[serde(deserialize_with = "empty_str_as_option")]
+    #[serde(default)]
+    pub manufacturer_ID: Option<String>,
 }
 
 pub struct InvoiceLineTranslation {}
@@ -132,6 +138,10 @@ impl RemotePullTranslation for InvoiceLineTranslation {
                 r#type: line_type,
                 number_of_packs: data.number_of_packs,
                 note: data.note,
+                donor_id: data.donor_id,
+                manufacturer_id: data.manufacturer_ID,
+                // VVM status is recorded locally and not synced with mSupply
+                vvm_status_id: None,
             }),
         )))
     }
@@ -186,6 +196,9 @@ impl RemotePushUpsertTranslation for InvoiceLineTranslation {
             r#type,
             number_of_packs,
             note,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id: _,
         } = InvoiceLineRowRepository::new(connection).find_one_by_id(&changelog.row_id)?;
 
         let legacy_row = LegacyTransLineRow {
@@ -207,6 +220,8 @@ impl RemotePushUpsertTranslation for InvoiceLineTranslation {
             tax,
             total_before_tax: Some(total_before_tax),
             total_after_tax: Some(total_after_tax),
+            donor_id,
+            manufacturer_ID: manufacturer_id,
         };
 
         Ok(Some(vec![PushUpsertRecord {
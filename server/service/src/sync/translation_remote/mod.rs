@@ -15,6 +15,8 @@ mod stocktake_line;
 pub mod pull;
 pub mod push;
 
+pub(crate) use number::parse_number_name;
+
 #[cfg(test)]
 pub mod test_data;
 
@@ -62,3 +64,21 @@ pub fn table_name_to_central(table: &ChangelogTableName) -> &'static str {
         ChangelogTableName::RequisitionLine => TRANSLATION_RECORD_REQUISITION_LINE,
     }
 }
+
+pub fn table_name_from_central(table: &str) -> Option<ChangelogTableName> {
+    let table = match table {
+        TRANSLATION_RECORD_NUMBER => ChangelogTableName::Number,
+        TRANSLATION_RECORD_LOCATION => ChangelogTableName::Location,
+        TRANSLATION_RECORD_ITEM_LINE => ChangelogTableName::StockLine,
+        TRANSLATION_RECORD_NAME => ChangelogTableName::Name,
+        TRANSLATION_RECORD_NAME_STORE_JOIN => ChangelogTableName::NameStoreJoin,
+        TRANSLATION_RECORD_TRANSACT => ChangelogTableName::Invoice,
+        TRANSLATION_RECORD_TRANS_LINE => ChangelogTableName::InvoiceLine,
+        TRANSLATION_RECORD_STOCKTAKE => ChangelogTableName::Stocktake,
+        TRANSLATION_RECORD_STOCKTAKE_LINE => ChangelogTableName::StocktakeLine,
+        TRANSLATION_RECORD_REQUISITION => ChangelogTableName::Requisition,
+        TRANSLATION_RECORD_REQUISITION_LINE => ChangelogTableName::RequisitionLine,
+        _ => return None,
+    };
+    Some(table)
+}
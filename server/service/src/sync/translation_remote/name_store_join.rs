@@ -1,10 +1,14 @@
 use log::error;
-use repository::{NameRowRepository, NameStoreJoinRow, RemoteSyncBufferRow, StorageConnection};
+use repository::{
+    ChangelogRow, ChangelogTableName, NameRowRepository, NameStoreJoinRepository, NameStoreJoinRow,
+    RemoteSyncBufferRow, StorageConnection,
+};
 
 use serde::{Deserialize, Serialize};
 
 use super::{
     pull::{IntegrationRecord, IntegrationUpsertRecord, RemotePullTranslation},
+    push::{PushUpsertRecord, RemotePushUpsertTranslation},
     TRANSLATION_RECORD_NAME_STORE_JOIN,
 };
 
@@ -57,3 +61,46 @@ impl RemotePullTranslation for NameStoreJoinTranslation {
         )))
     }
 }
+
+/// Only used when running as central server, remote sites don't push name_store_join rows
+impl RemotePushUpsertTranslation for NameStoreJoinTranslation {
+    fn try_translate_push(
+        &self,
+        connection: &StorageConnection,
+        changelog: &ChangelogRow,
+    ) -> Result<Option<Vec<PushUpsertRecord>>, anyhow::Error> {
+        if changelog.table_name != ChangelogTableName::NameStoreJoin {
+            return Ok(None);
+        }
+        let table_name = TRANSLATION_RECORD_NAME_STORE_JOIN;
+
+        let NameStoreJoinRow {
+            id,
+            name_id,
+            store_id,
+            name_is_customer,
+            name_is_supplier,
+        } = NameStoreJoinRepository::new(connection)
+            .find_one_by_id(&changelog.row_id)?
+            .ok_or(anyhow::Error::msg(format!(
+                "Name store join row ({}) not found",
+                changelog.row_id
+            )))?;
+
+        let legacy_row = LegacyNameStoreJoinRow {
+            ID: id.clone(),
+            store_ID: store_id.clone(),
+            name_ID: name_id,
+            name_is_customer: Some(name_is_customer),
+            name_is_supplier: Some(name_is_supplier),
+        };
+
+        Ok(Some(vec![PushUpsertRecord {
+            sync_id: changelog.id,
+            store_id: Some(store_id),
+            table_name,
+            record_id: id,
+            data: serde_json::to_value(&legacy_row)?,
+        }]))
+    }
+}
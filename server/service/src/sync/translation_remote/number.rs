@@ -89,7 +89,7 @@ impl RemotePushUpsertTranslation for NumberTranslation {
     }
 }
 
-fn parse_number_name(value: String) -> Option<(NumberRowType, String)> {
+pub(crate) fn parse_number_name(value: String) -> Option<(NumberRowType, String)> {
     let mut split = value.split("_for_store_");
     let number_type = match split.next()? {
         "stock_take_number" => NumberRowType::Stocktake,
@@ -5,10 +5,11 @@ use repository::{
     NumberRow, NumberRowRepository, RemoteSyncBufferRow, RepositoryError, RequisitionLineRow,
     RequisitionLineRowRepository, RequisitionRow, RequisitionRowRepository, StockLineRow,
     StockLineRowRepository, StocktakeLineRow, StocktakeLineRowRepository, StocktakeRow,
-    StocktakeRowRepository, StorageConnection, TransactionError,
+    StocktakeRowRepository, StorageConnection, SyncQuarantineSource, TransactionError,
 };
 
 use crate::sync::{
+    sync_quarantine::SyncQuarantine,
     translation_remote::{
         invoice::InvoiceTranslation, invoice_line::InvoiceLineTranslation,
         location::LocationTranslation, name::NameTranslation,
@@ -57,35 +58,47 @@ pub trait RemotePullTranslation {
 
 /// Imports sync records and writes them to the DB
 /// If needed data records are translated to the local DB schema.
+/// Records that fail to translate or integrate are put into the sync quarantine.
 pub fn import_sync_pull_records(
     connection: &StorageConnection,
     records: &Vec<RemoteSyncBufferRow>,
 ) -> Result<(), SyncImportError> {
-    let mut integration_records = IntegrationRecord {
-        upserts: Vec::new(),
-    };
+    let mut quarantine = SyncQuarantine::load(connection, SyncQuarantineSource::Remote)
+        .map_err(|error| SyncImportError::as_integration_error(error, "Load sync quarantine"))?;
+    let mut integration_records = Vec::new();
 
     info!(
         "Translating {} remote sync buffer records...",
         records.len()
     );
     for record in records {
-        do_translation(connection, &record, &mut integration_records)?;
+        match do_translation(connection, &record) {
+            Ok(Some(integration_record)) => integration_records.push((record, integration_record)),
+            Ok(None) => {}
+            Err(error) => {
+                warn!("Failed to translate ({}): {:?}", error, record);
+                quarantine
+                    .record_failure(connection, record.into(), format!("{:#}", error.source))
+                    .map_err(|error| {
+                        SyncImportError::as_integration_error(error, "Quarantine record")
+                    })?;
+            }
+        }
     }
     info!("Succesfully translated remote sync buffer records");
 
     info!("Storing integration remote records...");
-    store_integration_records(connection, &integration_records)?;
+    store_integration_records(connection, &integration_records, &mut quarantine)?;
     info!("Successfully stored integration remote records");
 
     Ok(())
 }
 
+/// Returns None if the record is not handled by any translation
 fn do_translation(
     connection: &StorageConnection,
     sync_record: &RemoteSyncBufferRow,
-    records: &mut IntegrationRecord,
-) -> Result<(), SyncTranslationError> {
+) -> Result<Option<IntegrationRecord>, SyncTranslationError> {
     let translations: Vec<Box<dyn RemotePullTranslation>> = vec![
         Box::new(NumberTranslation {}),
         Box::new(LocationTranslation {}),
@@ -99,26 +112,25 @@ fn do_translation(
         Box::new(RequisitionTranslation {}),
         Box::new(RequisitionLineTranslation {}),
     ];
+    let mut translation_error = None;
     for translation in translations {
         match translation.try_translate_pull(connection, sync_record) {
-            Ok(Some(mut result)) => {
-                records.upserts.append(&mut result.upserts);
-                return Ok(());
-            }
-            Err(error) => warn!(
-                "Failed to translate ({}): {:?}",
-                SyncTranslationError {
+            Ok(Some(result)) => return Ok(Some(result)),
+            Err(error) => {
+                translation_error = Some(SyncTranslationError {
                     table_name: sync_record.table_name.clone(),
                     source: error,
                     record: format!("{:?}", sync_record),
-                },
-                sync_record
-            ),
+                })
+            }
             _ => {}
         };
     }
+    if let Some(error) = translation_error {
+        return Err(error);
+    }
     warn!("Unhandled remote pull record: {:?}", sync_record);
-    Ok(())
+    Ok(None)
 }
 
 fn integrate_record(
@@ -160,26 +172,39 @@ fn integrate_record(
 
 fn store_integration_records(
     connection: &StorageConnection,
-    integration_records: &IntegrationRecord,
+    integration_records: &Vec<(&RemoteSyncBufferRow, IntegrationRecord)>,
+    quarantine: &mut SyncQuarantine,
 ) -> Result<(), SyncImportError> {
     connection
         .transaction_sync(|con| {
-            for record in &integration_records.upserts {
+            for (sync_record, integration_record) in integration_records {
                 // Integrate every record in a sub transaction. This is mainly for Postgres where the
                 // whole transaction fails when there is a DB error (not a problem in sqlite).
-                let sub_result =
-                    con.transaction_sync_etc(|sub_tx| integrate_record(record, sub_tx), false);
+                let sub_result = con.transaction_sync_etc(
+                    |sub_tx| {
+                        for record in &integration_record.upserts {
+                            integrate_record(record, sub_tx)?;
+                        }
+                        Ok(())
+                    },
+                    false,
+                );
                 match sub_result {
-                    Ok(_) => Ok(()),
+                    Ok(_) => quarantine.record_success(con, (*sync_record).into()),
                     Err(TransactionError::Inner(err @ RepositoryError::ForeignKeyViolation(_))) => {
-                        warn!("Failed to import ({}): {:?}", err, record);
-                        Ok(())
+                        warn!("Failed to import ({}): {:?}", err, integration_record);
+                        quarantine.record_failure(con, (*sync_record).into(), format!("{}", err))
+                    }
+                    Err(err) => {
+                        return Err(SyncImportError::as_integration_error(
+                            RepositoryError::from(err),
+                            "",
+                        ))
                     }
-                    Err(err) => Err(SyncImportError::as_integration_error(
-                        RepositoryError::from(err),
-                        "",
-                    )),
-                }?;
+                }
+                .map_err(|error| {
+                    SyncImportError::as_integration_error(error, "Update sync quarantine")
+                })?;
             }
             Ok(())
         })
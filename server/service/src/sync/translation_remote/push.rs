@@ -1,15 +1,19 @@
 use log::{info, warn};
-use repository::{ChangelogAction, ChangelogRow, StorageConnection};
+use repository::{ChangelogAction, ChangelogRow, ChangelogTableName, StorageConnection};
 
-use crate::sync::{
-    translation_remote::{
-        invoice::InvoiceTranslation, invoice_line::InvoiceLineTranslation,
-        location::LocationTranslation, name::NameTranslation, number::NumberTranslation,
-        requisition::RequisitionTranslation, requisition_line::RequisitionLineTranslation,
-        stock_line::StockLineTranslation, stocktake::StocktakeTranslation,
-        stocktake_line::StocktakeLineTranslation, table_name_to_central,
+use crate::{
+    apis::sync_api_v5::{RemoteSyncActionV5, RemoteSyncRecordV5},
+    sync::{
+        translation_remote::{
+            invoice::InvoiceTranslation, invoice_line::InvoiceLineTranslation,
+            location::LocationTranslation, name::NameTranslation,
+            name_store_join::NameStoreJoinTranslation, number::NumberTranslation,
+            requisition::RequisitionTranslation, requisition_line::RequisitionLineTranslation,
+            stock_line::StockLineTranslation, stocktake::StocktakeTranslation,
+            stocktake_line::StocktakeLineTranslation, table_name_to_central,
+        },
+        SyncTranslationError,
     },
-    SyncTranslationError,
 };
 
 // Translates push upserts
@@ -43,6 +47,27 @@ pub enum PushRecord {
     Delete(PushDeleteRecord),
 }
 
+impl From<PushRecord> for RemoteSyncRecordV5 {
+    fn from(record: PushRecord) -> Self {
+        match record {
+            PushRecord::Upsert(record) => RemoteSyncRecordV5 {
+                sync_id: format!("{}", record.sync_id),
+                table: record.table_name.to_string(),
+                record_id: record.record_id,
+                action: RemoteSyncActionV5::Update,
+                data: Some(record.data),
+            },
+            PushRecord::Delete(record) => RemoteSyncRecordV5 {
+                sync_id: format!("{}", record.sync_id),
+                table: record.table_name.to_string(),
+                record_id: record.record_id,
+                action: RemoteSyncActionV5::Delete,
+                data: None,
+            },
+        }
+    }
+}
+
 pub fn translate_changelog(
     connection: &StorageConnection,
     changelog: &ChangelogRow,
@@ -103,3 +128,30 @@ pub fn translate_changelog(
     warn!("Unhandled push changlog: {:?}", changelog);
     Ok(())
 }
+
+/// Translates a changelog for the queue of a remote site, when running as central server.
+///
+/// Unlike [translate_changelog] this includes name_store_join rows.
+pub fn translate_changelog_for_remote_site(
+    connection: &StorageConnection,
+    changelog: &ChangelogRow,
+    results: &mut Vec<PushRecord>,
+) -> Result<(), SyncTranslationError> {
+    if changelog.table_name == ChangelogTableName::NameStoreJoin
+        && changelog.row_action == ChangelogAction::Upsert
+    {
+        let translation = NameStoreJoinTranslation {};
+        if let Some(records) = translation
+            .try_translate_push(connection, changelog)
+            .map_err(|err| SyncTranslationError {
+                table_name: table_name_to_central(&changelog.table_name).to_string(),
+                source: err,
+                record: format!("{:?}", changelog),
+            })?
+        {
+            results.extend(records.into_iter().map(PushRecord::Upsert));
+            return Ok(());
+        }
+    }
+    translate_changelog(connection, changelog, results)
+}
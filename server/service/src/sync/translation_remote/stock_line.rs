@@ -33,6 +33,15 @@ pub struct LegacyStockLineRow {
     pub cost_price: f64,
     pub sell_price: f64,
     pub note: Option<String>,
+    #[serde(deserialize_with = "empty_str_as_option")]
+    #[serde(default)]
+    pub donor_id: Option<String>,
+    #[serde(deserialize_with = "empty_str_as_option")]
+    #[serde(default)]
+    pub manufacturer_ID: Option<String>,
+    #[serde(deserialize_with = "empty_str_as_option")]
+    #[serde(default)]
+    pub vvm_status: Option<String>,
 }
 
 pub struct StockLineTranslation {}
@@ -64,6 +73,9 @@ impl RemotePullTranslation for StockLineTranslation {
                 expiry_date: data.expiry_date,
                 on_hold: data.hold,
                 note: data.note,
+                donor_id: data.donor_id,
+                manufacturer_id: data.manufacturer_ID,
+                vvm_status_id: data.vvm_status,
             }),
         )))
     }
@@ -94,6 +106,9 @@ impl RemotePushUpsertTranslation for StockLineTranslation {
             expiry_date,
             on_hold,
             note,
+            donor_id,
+            manufacturer_id,
+            vvm_status_id,
         } = StockLineRowRepository::new(connection).find_one_by_id(&changelog.row_id)?;
 
         let legacy_row = LegacyStockLineRow {
@@ -110,6 +125,9 @@ impl RemotePushUpsertTranslation for StockLineTranslation {
             cost_price: cost_price_per_pack,
             sell_price: sell_price_per_pack,
             note,
+            donor_id,
+            manufacturer_ID: manufacturer_id,
+            vvm_status: vvm_status_id,
         };
 
         Ok(Some(vec![PushUpsertRecord {
@@ -68,6 +68,23 @@ pub struct LegacyStocktakeRow {
     #[serde(default)]
     #[serde(deserialize_with = "empty_date_time_as_option")]
     pub finalised_datetime: Option<NaiveDateTime>,
+
+    /// mSupply has no review status, see [legacy_stocktake_status]
+    #[serde(default)]
+    pub om_status: Option<StocktakeStatus>,
+
+    #[serde(rename = "om_variance_approval_threshold")]
+    #[serde(default)]
+    pub variance_approval_threshold: Option<f64>,
+
+    #[serde(rename = "om_approved_user_id")]
+    #[serde(default)]
+    #[serde(deserialize_with = "empty_str_as_option")]
+    pub approved_user_id: Option<String>,
+
+    #[serde(rename = "om_approved_datetime")]
+    #[serde(default)]
+    pub approved_datetime: Option<NaiveDateTime>,
 }
 
 pub struct StocktakeTranslation {}
@@ -103,15 +120,20 @@ impl RemotePullTranslation for StocktakeTranslation {
                 stocktake_number: data.serial_number,
                 comment: data.comment,
                 description: data.Description,
-                status: stocktake_status(&data.status).ok_or(anyhow::Error::msg(format!(
-                    "Unexpected stocktake status: {:?}",
-                    data.status
-                )))?,
+                status: data.om_status.or(stocktake_status(&data.status)).ok_or(
+                    anyhow::Error::msg(format!("Unexpected stocktake status: {:?}", data.status)),
+                )?,
                 created_datetime,
                 finalised_datetime,
                 inventory_adjustment_id: data.invad_additions_ID,
                 stocktake_date: data.stocktake_date,
                 is_locked: data.is_locked,
+                // Blind counts are configured locally and not synced with mSupply
+                is_blind_count: false,
+                recount_tolerance: 0,
+                variance_approval_threshold: data.variance_approval_threshold,
+                approved_user_id: data.approved_user_id,
+                approved_datetime: data.approved_datetime,
             }),
         )))
     }
@@ -150,6 +172,11 @@ impl RemotePushUpsertTranslation for StocktakeTranslation {
             inventory_adjustment_id,
             is_locked,
             stocktake_date,
+            is_blind_count: _,
+            recount_tolerance: _,
+            variance_approval_threshold,
+            approved_user_id,
+            approved_datetime,
         } = StocktakeRowRepository::new(connection)
             .find_one_by_id(&changelog.row_id)?
             .ok_or(anyhow::Error::msg("Stocktake row not found"))?;
@@ -159,6 +186,7 @@ impl RemotePushUpsertTranslation for StocktakeTranslation {
             user_id,
             store_ID: store_id.clone(),
             status: legacy_stocktake_status(&status),
+            om_status: Some(status),
             Description: description,
             comment,
             is_locked,
@@ -169,6 +197,9 @@ impl RemotePushUpsertTranslation for StocktakeTranslation {
             stock_take_time: created_datetime.time(),
             created_datetime: Some(created_datetime),
             finalised_datetime,
+            variance_approval_threshold,
+            approved_user_id,
+            approved_datetime,
         };
 
         Ok(Some(vec![PushUpsertRecord {
@@ -184,6 +215,8 @@ impl RemotePushUpsertTranslation for StocktakeTranslation {
 fn legacy_stocktake_status(status: &StocktakeStatus) -> LegacyStocktakeStatus {
     match status {
         StocktakeStatus::New => LegacyStocktakeStatus::Sg,
+        // mSupply has no review status, the stocktake is still suggested (not finalised)
+        StocktakeStatus::Review => LegacyStocktakeStatus::Sg,
         StocktakeStatus::Finalised => LegacyStocktakeStatus::Fn,
     }
 }
@@ -41,6 +41,9 @@ pub struct LegacyStocktakeLineRow {
     #[serde(deserialize_with = "empty_str_as_option")]
     #[serde(default)]
     pub note: Option<String>,
+    #[serde(deserialize_with = "empty_str_as_option")]
+    #[serde(default)]
+    pub donor_ID: Option<String>,
 }
 
 pub struct StocktakeLineTranslation {}
@@ -79,6 +82,13 @@ impl RemotePullTranslation for StocktakeLineTranslation {
             cost_price_per_pack: Some(data.cost_price),
             sell_price_per_pack: Some(data.sell_price),
             note: data.note,
+            donor_id: data.donor_ID,
+            // Stock_take_lines in mSupply don't have a manufacturer
+            manufacturer_id: None,
+            // VVM status is recorded locally and not synced with mSupply
+            vvm_status_id: None,
+            // Independent counts are recorded locally and not synced with mSupply
+            needs_recount: false,
         };
         Ok(Some(IntegrationRecord::from_upsert(
             IntegrationUpsertRecord::StocktakeLine(row),
@@ -112,6 +122,10 @@ impl RemotePushUpsertTranslation for StocktakeLineTranslation {
             cost_price_per_pack,
             sell_price_per_pack,
             note,
+            donor_id,
+            manufacturer_id: _,
+            vvm_status_id: _,
+            needs_recount: _,
         } = StocktakeLineRowRepository::new(connection)
             .find_one_by_id(&changelog.row_id)?
             .ok_or(anyhow::Error::msg("Stocktake row not found"))?;
@@ -139,6 +153,7 @@ impl RemotePushUpsertTranslation for StocktakeLineTranslation {
             cost_price: cost_price_per_pack.unwrap_or(0.0),
             sell_price: sell_price_per_pack.unwrap_or(0.0),
             note,
+            donor_ID: donor_id,
         };
 
         Ok(Some(vec![PushUpsertRecord {
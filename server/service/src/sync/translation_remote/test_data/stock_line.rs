@@ -53,7 +53,7 @@ const ITEM_LINE_1: (&'static str, &'static str) = (
       "user_7_ID": "",
       "user_8_ID": "",
       "volume_per_pack": 0,
-      "vvm_status": "",
+      "vvm_status": "VVM_STAGE_1",
       "weight_per_pack": 0
     }"#,
 );
@@ -74,6 +74,9 @@ fn item_line_1_pull_record() -> TestSyncRecord {
                 expiry_date: Some(NaiveDate::from_ymd(2022, 2, 17)),
                 on_hold: false,
                 note: Some("test note".to_string()),
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: Some("VVM_STAGE_1".to_string()),
             }),
         )),
         identifier: "Stock line 1",
@@ -107,7 +110,10 @@ fn item_line_1_push_record() -> TestSyncPushRecord {
             quantity: 694,
             cost_price: 5.0,
             sell_price: 10.0,
-            note: Some("test note".to_string())
+            note: Some("test note".to_string()),
+            donor_id: None,
+            manufacturer_ID: None,
+            vvm_status: Some("VVM_STAGE_1".to_string()),
         }),
     }
 }
@@ -172,6 +178,9 @@ fn item_line_2_pull_record() -> TestSyncRecord {
                 expiry_date: None,
                 on_hold: false,
                 note: Some("".to_string()),
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }),
         )),
         identifier: "Stock line 2",
@@ -205,7 +214,10 @@ fn item_line_2_push_record() -> TestSyncPushRecord {
             quantity: -1001,
             cost_price: 0.0,
             sell_price: 0.0,
-            note: Some("".to_string())
+            note: Some("".to_string()),
+            donor_id: None,
+            manufacturer_ID: None,
+            vvm_status: None,
         }),
     }
 }
@@ -51,6 +51,11 @@ fn stocktake_pull_record() -> TestSyncRecord {
                 finalised_datetime: None,
                 inventory_adjustment_id: Some("inbound_shipment_a".to_string()),
                 is_locked: false,
+                is_blind_count: false,
+                recount_tolerance: 0,
+                variance_approval_threshold: None,
+                approved_user_id: None,
+                approved_datetime: None,
                 stocktake_date: Some(NaiveDate::from_ymd(2021, 07, 30)),
             }),
         )),
@@ -90,6 +95,10 @@ fn stocktake_push_record() -> TestSyncPushRecord {
                     .and_time(NaiveTime::from_num_seconds_from_midnight_opt(47061, 0).unwrap())
             ),
             finalised_datetime: None,
+            om_status: Some(StocktakeStatus::Finalised),
+            variance_approval_threshold: None,
+            approved_user_id: None,
+            approved_datetime: None,
         }),
     }
 }
@@ -114,7 +123,11 @@ const STOCKTAKE_OM_FIELD: (&'static str, &'static str) = (
       "store_ID": "store_a",
       "type": "",
       "om_created_datetime": "2021-07-30T15:15:15",
-      "om_finalised_datetime": "2021-07-31T15:15:15"
+      "om_finalised_datetime": "2021-07-31T15:15:15",
+      "om_status": "FINALISED",
+      "om_variance_approval_threshold": 100.0,
+      "om_approved_user_id": "approver",
+      "om_approved_datetime": "2021-07-31T10:10:10"
     }"#,
 );
 fn stocktake_om_field_pull_record() -> TestSyncRecord {
@@ -132,6 +145,11 @@ fn stocktake_om_field_pull_record() -> TestSyncRecord {
                 finalised_datetime: Some(NaiveDate::from_ymd(2021, 07, 31).and_hms(15, 15, 15)),
                 inventory_adjustment_id: Some("inbound_shipment_a".to_string()),
                 is_locked: false,
+                is_blind_count: false,
+                recount_tolerance: 0,
+                variance_approval_threshold: Some(100.0),
+                approved_user_id: Some("approver".to_string()),
+                approved_datetime: Some(NaiveDate::from_ymd(2021, 07, 31).and_hms(10, 10, 10)),
                 stocktake_date: Some(NaiveDate::from_ymd(2021, 07, 30)),
             }),
         )),
@@ -168,6 +186,10 @@ fn stocktake_om_field_push_record() -> TestSyncPushRecord {
             stocktake_date: Some(NaiveDate::from_ymd(2021, 07, 30)),
             created_datetime: Some(NaiveDate::from_ymd(2021, 07, 30).and_hms(15, 15, 15)),
             finalised_datetime: Some(NaiveDate::from_ymd(2021, 07, 31).and_hms(15, 15, 15)),
+            om_status: Some(StocktakeStatus::Finalised),
+            variance_approval_threshold: Some(100.0),
+            approved_user_id: Some("approver".to_string()),
+            approved_datetime: Some(NaiveDate::from_ymd(2021, 07, 31).and_hms(10, 10, 10)),
         }),
     }
 }
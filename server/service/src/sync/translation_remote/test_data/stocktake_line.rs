@@ -56,6 +56,10 @@ fn stocktake_line_pull_record() -> TestSyncRecord {
                 cost_price_per_pack: Some(12.0),
                 sell_price_per_pack: Some(15.0),
                 note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
+                needs_recount: false,
             }),
         )),
         identifier: "Stocktake 1",
@@ -92,6 +96,7 @@ fn stocktake_line_push_record() -> TestSyncPushRecord {
             cost_price: 12.0,
             sell_price: 15.0,
             note: None,
+            donor_ID: None,
         }),
     }
 }
@@ -140,6 +145,10 @@ fn stocktake_line_om_field_pull_record() -> TestSyncRecord {
                 cost_price_per_pack: Some(12.0),
                 sell_price_per_pack: Some(15.0),
                 note: Some("om note".to_string()),
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
+                needs_recount: false,
             }),
         )),
         identifier: "Stocktake om field",
@@ -176,6 +185,7 @@ fn stocktake_line_om_field_push_record() -> TestSyncPushRecord {
             cost_price: 12.0,
             sell_price: 15.0,
             note: Some("om note".to_string()),
+            donor_ID: None,
         }),
     }
 }
@@ -94,6 +94,9 @@ fn trans_line_1_pull_record() -> TestSyncRecord {
                 r#type: InvoiceLineRowType::StockIn,
                 number_of_packs: 700,
                 note: None,
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }),
         )),
         identifier: "Transact line 1",
@@ -133,6 +136,8 @@ fn trans_line_1_push_record() -> TestSyncPushRecord {
             tax: None,
             total_before_tax: Some(10.0 * 700.0),
             total_after_tax: Some(10.0 * 700.0),
+            donor_id: None,
+            manufacturer_ID: None,
         }),
     }
 }
@@ -216,6 +221,9 @@ fn trans_line_2_pull_record() -> TestSyncRecord {
                 r#type: InvoiceLineRowType::StockOut,
                 number_of_packs: 1000,
                 note: Some("every FOUR to SIX hours when necessary ".to_string()),
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }),
         )),
         identifier: "Transact line (Placeholder)",
@@ -255,6 +263,8 @@ fn trans_line_2_push_record() -> TestSyncPushRecord {
             tax: None,
             total_before_tax: Some(2.0 * 1000.0),
             total_after_tax: Some(2.0 * 1000.0),
+            donor_id: None,
+            manufacturer_ID: None,
         }),
     }
 }
@@ -341,6 +351,9 @@ fn trans_line_om_fields_pull_record() -> TestSyncRecord {
                 r#type: InvoiceLineRowType::StockOut,
                 number_of_packs: 1000,
                 note: Some("every FOUR to SIX hours when necessary ".to_string()),
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }),
         )),
         identifier: "Transact line (om fields))",
@@ -380,6 +393,8 @@ fn trans_line_om_fields_push_record() -> TestSyncPushRecord {
             tax: Some(33.3),
             total_before_tax: Some(105.4),
             total_after_tax: Some(130.5),
+            donor_id: None,
+            manufacturer_ID: None,
         }),
     }
 }
@@ -466,6 +481,9 @@ fn trans_line_om_fields_unset_tax_pull_record() -> TestSyncRecord {
                 r#type: InvoiceLineRowType::StockOut,
                 number_of_packs: 1000,
                 note: Some("every FOUR to SIX hours when necessary ".to_string()),
+                donor_id: None,
+                manufacturer_id: None,
+                vvm_status_id: None,
             }),
         )),
         identifier: "Transact line (om fields))",
@@ -505,6 +523,8 @@ fn trans_line_om_fields_unset_tax_push_record() -> TestSyncPushRecord {
             tax: None,
             total_before_tax: Some(105.4),
             total_after_tax: Some(130.5),
+            donor_id: None,
+            manufacturer_ID: None,
         }),
     }
 }
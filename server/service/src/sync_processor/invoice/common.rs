@@ -169,6 +169,9 @@ pub fn regenerate_linked_invoice_lines(
                  total_after_tax: _,
                  total_before_tax: _,
                  tax: _,
+                 donor_id,
+                 manufacturer_id,
+                 vvm_status_id,
              }| {
                 let cost_price_per_pack = sell_price_per_pack;
                 InvoiceLineRow {
@@ -193,6 +196,9 @@ pub fn regenerate_linked_invoice_lines(
                     },
                     number_of_packs,
                     note,
+                    donor_id,
+                    manufacturer_id,
+                    vvm_status_id,
                     // Default
                     stock_line_id: None,
                     location_id: None,
@@ -1,6 +1,6 @@
 use repository::{
-    EqualFilter, Name, NameFilter, NameRepository, RepositoryError, StorageConnection,
-    StoreRowRepository,
+    EqualFilter, Name, NameFilter, NameRepository, NameRowRepository, RepositoryError,
+    StorageConnection, StoreRowRepository, VvmStatusRowRepository,
 };
 
 pub fn check_store_id_matches(store_id_a: &str, store_id_b: &str) -> bool {
@@ -16,6 +16,46 @@ pub fn check_store_exists(
         .is_some())
 }
 
+pub fn check_name_exists(
+    connection: &StorageConnection,
+    name_id: &str,
+) -> Result<bool, RepositoryError> {
+    Ok(NameRowRepository::new(connection)
+        .find_one_by_id(name_id)?
+        .is_some())
+}
+
+/// Checks that the name exists and is flagged as a donor
+pub fn check_donor_exists(
+    connection: &StorageConnection,
+    donor_id: &str,
+) -> Result<bool, RepositoryError> {
+    Ok(NameRowRepository::new(connection)
+        .find_one_by_id(donor_id)?
+        .map(|name_row| name_row.is_donor)
+        .unwrap_or(false))
+}
+
+/// Checks that the name exists and is flagged as a manufacturer
+pub fn check_manufacturer_exists(
+    connection: &StorageConnection,
+    manufacturer_id: &str,
+) -> Result<bool, RepositoryError> {
+    Ok(NameRowRepository::new(connection)
+        .find_one_by_id(manufacturer_id)?
+        .map(|name_row| name_row.is_manufacturer)
+        .unwrap_or(false))
+}
+
+pub fn check_vvm_status_exists(
+    connection: &StorageConnection,
+    vvm_status_id: &str,
+) -> Result<bool, RepositoryError> {
+    Ok(VvmStatusRowRepository::new(connection)
+        .find_one_by_id(vvm_status_id)?
+        .is_some())
+}
+
 pub enum OtherPartyErrors {
     OtherPartyDoesNotExist,
     OtherPartyNotVisible,
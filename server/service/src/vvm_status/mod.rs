@@ -0,0 +1,41 @@
+use self::{
+    query::{get_vvm_status_logs, get_vvm_statuses},
+    update::{
+        update_stock_line_vvm_status, UpdateStockLineVvmStatus, UpdateStockLineVvmStatusError,
+    },
+};
+
+use crate::{service_provider::ServiceContext, SingleRecordError};
+use repository::{RepositoryError, VvmStatusLogRow, VvmStatusRow};
+
+pub mod query;
+pub mod update;
+
+pub trait VvmStatusServiceTrait: Sync + Send {
+    /// Active VVM statuses, lowest level first
+    fn get_vvm_statuses(&self, ctx: &ServiceContext) -> Result<Vec<VvmStatusRow>, RepositoryError> {
+        get_vvm_statuses(ctx)
+    }
+
+    /// VVM status history of a stock line, oldest first
+    fn get_vvm_status_logs(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        stock_line_id: &str,
+    ) -> Result<Vec<VvmStatusLogRow>, SingleRecordError> {
+        get_vvm_status_logs(ctx, store_id, stock_line_id)
+    }
+
+    fn update_stock_line_vvm_status(
+        &self,
+        ctx: &ServiceContext,
+        store_id: &str,
+        input: UpdateStockLineVvmStatus,
+    ) -> Result<VvmStatusLogRow, UpdateStockLineVvmStatusError> {
+        update_stock_line_vvm_status(ctx, store_id, input)
+    }
+}
+
+pub struct VvmStatusService {}
+impl VvmStatusServiceTrait for VvmStatusService {}
@@ -0,0 +1,37 @@
+use repository::{
+    RepositoryError, StockLineRowRepository, VvmStatusLogRow, VvmStatusLogRowRepository,
+    VvmStatusRow, VvmStatusRowRepository,
+};
+
+use crate::{service_provider::ServiceContext, SingleRecordError};
+
+pub fn get_vvm_statuses(ctx: &ServiceContext) -> Result<Vec<VvmStatusRow>, RepositoryError> {
+    Ok(VvmStatusRowRepository::new(&ctx.connection)
+        .find_all()?
+        .into_iter()
+        .filter(|status| status.is_active)
+        .collect())
+}
+
+pub fn get_vvm_status_logs(
+    ctx: &ServiceContext,
+    store_id: &str,
+    stock_line_id: &str,
+) -> Result<Vec<VvmStatusLogRow>, SingleRecordError> {
+    let stock_line =
+        match StockLineRowRepository::new(&ctx.connection).find_one_by_id(stock_line_id) {
+            Ok(stock_line) => stock_line,
+            Err(RepositoryError::NotFound) => {
+                return Err(SingleRecordError::NotFound(stock_line_id.to_string()))
+            }
+            Err(error) => return Err(error.into()),
+        };
+    if stock_line.store_id != store_id {
+        return Err(SingleRecordError::NotFound(stock_line_id.to_string()));
+    }
+
+    Ok(
+        VvmStatusLogRowRepository::new(&ctx.connection)
+            .find_many_by_stock_line_id(stock_line_id)?,
+    )
+}
@@ -0,0 +1,234 @@
+use chrono::Utc;
+use repository::{
+    InvoiceLineRowRepository, RepositoryError, StockLineRow, StockLineRowRepository,
+    StorageConnection, VvmStatusLogRow, VvmStatusLogRowRepository, VvmStatusRowRepository,
+};
+use util::uuid::uuid;
+
+use crate::service_provider::ServiceContext;
+
+#[derive(Default, Debug, Clone)]
+pub struct UpdateStockLineVvmStatus {
+    pub stock_line_id: String,
+    pub status_id: String,
+    /// Shipment line the status was observed on, e.g. on receipt or at issue
+    pub invoice_line_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateStockLineVvmStatusError {
+    StockLineDoesNotExist,
+    NotThisStoreStockLine,
+    VvmStatusDoesNotExist,
+    InvoiceLineDoesNotExist,
+    InvoiceLineNotForStockLine,
+    DatabaseError(RepositoryError),
+}
+
+type OutError = UpdateStockLineVvmStatusError;
+
+pub fn update_stock_line_vvm_status(
+    ctx: &ServiceContext,
+    store_id: &str,
+    input: UpdateStockLineVvmStatus,
+) -> Result<VvmStatusLogRow, OutError> {
+    let log_row = ctx
+        .connection
+        .transaction_sync(|connection| {
+            let stock_line = validate(connection, store_id, &input)?;
+            let (updated_stock_line, log_row) = generate(stock_line, input);
+
+            StockLineRowRepository::new(connection).upsert_one(&updated_stock_line)?;
+            VvmStatusLogRowRepository::new(connection).insert_one(&log_row)?;
+
+            Ok(log_row) as Result<VvmStatusLogRow, OutError>
+        })
+        .map_err(|error| error.to_inner_error())?;
+    Ok(log_row)
+}
+
+fn validate(
+    connection: &StorageConnection,
+    store_id: &str,
+    input: &UpdateStockLineVvmStatus,
+) -> Result<StockLineRow, OutError> {
+    let stock_line =
+        match StockLineRowRepository::new(connection).find_one_by_id(&input.stock_line_id) {
+            Ok(stock_line) => stock_line,
+            Err(RepositoryError::NotFound) => return Err(OutError::StockLineDoesNotExist),
+            Err(error) => return Err(error.into()),
+        };
+    if stock_line.store_id != store_id {
+        return Err(OutError::NotThisStoreStockLine);
+    }
+
+    match VvmStatusRowRepository::new(connection).find_one_by_id(&input.status_id)? {
+        Some(status) if status.is_active => {}
+        _ => return Err(OutError::VvmStatusDoesNotExist),
+    }
+
+    if let Some(invoice_line_id) = &input.invoice_line_id {
+        let invoice_line =
+            match InvoiceLineRowRepository::new(connection).find_one_by_id(invoice_line_id) {
+                Ok(invoice_line) => invoice_line,
+                Err(RepositoryError::NotFound) => return Err(OutError::InvoiceLineDoesNotExist),
+                Err(error) => return Err(error.into()),
+            };
+        if invoice_line.stock_line_id.as_ref() != Some(&stock_line.id) {
+            return Err(OutError::InvoiceLineNotForStockLine);
+        }
+    }
+
+    Ok(stock_line)
+}
+
+fn generate(
+    mut stock_line: StockLineRow,
+    UpdateStockLineVvmStatus {
+        stock_line_id: _,
+        status_id,
+        invoice_line_id,
+        comment,
+    }: UpdateStockLineVvmStatus,
+) -> (StockLineRow, VvmStatusLogRow) {
+    stock_line.vvm_status_id = Some(status_id.clone());
+
+    let log_row = VvmStatusLogRow {
+        id: uuid(),
+        status_id,
+        stock_line_id: stock_line.id.clone(),
+        invoice_line_id,
+        stocktake_line_id: None,
+        comment,
+        created_datetime: Utc::now().naive_utc(),
+    };
+
+    (stock_line, log_row)
+}
+
+impl From<RepositoryError> for UpdateStockLineVvmStatusError {
+    fn from(error: RepositoryError) -> Self {
+        UpdateStockLineVvmStatusError::DatabaseError(error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use repository::{
+        mock::{mock_stock_line_a, MockDataInserts},
+        test_db::setup_all,
+        StockLineRowRepository,
+    };
+
+    use crate::{
+        service_provider::ServiceProvider,
+        vvm_status::update::{
+            UpdateStockLineVvmStatus, UpdateStockLineVvmStatusError as ServiceError,
+        },
+    };
+
+    #[actix_rt::test]
+    async fn update_stock_line_vvm_status() {
+        let (_, connection, connection_manager, _) =
+            setup_all("update_stock_line_vvm_status", MockDataInserts::all()).await;
+
+        let service_provider = ServiceProvider::new(connection_manager, "app_data");
+        let context = service_provider.context().unwrap();
+        let service = service_provider.vvm_status_service;
+
+        // StockLineDoesNotExist
+        assert_eq!(
+            service.update_stock_line_vvm_status(
+                &context,
+                "store_a",
+                UpdateStockLineVvmStatus {
+                    stock_line_id: "invalid".to_string(),
+                    status_id: "VVM_STAGE_1".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::StockLineDoesNotExist)
+        );
+
+        // NotThisStoreStockLine
+        assert_eq!(
+            service.update_stock_line_vvm_status(
+                &context,
+                "store_b",
+                UpdateStockLineVvmStatus {
+                    stock_line_id: mock_stock_line_a().id,
+                    status_id: "VVM_STAGE_1".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::NotThisStoreStockLine)
+        );
+
+        // VvmStatusDoesNotExist
+        assert_eq!(
+            service.update_stock_line_vvm_status(
+                &context,
+                "store_a",
+                UpdateStockLineVvmStatus {
+                    stock_line_id: mock_stock_line_a().id,
+                    status_id: "invalid".to_string(),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::VvmStatusDoesNotExist)
+        );
+
+        // InvoiceLineDoesNotExist
+        assert_eq!(
+            service.update_stock_line_vvm_status(
+                &context,
+                "store_a",
+                UpdateStockLineVvmStatus {
+                    stock_line_id: mock_stock_line_a().id,
+                    status_id: "VVM_STAGE_1".to_string(),
+                    invoice_line_id: Some("invalid".to_string()),
+                    ..Default::default()
+                },
+            ),
+            Err(ServiceError::InvoiceLineDoesNotExist)
+        );
+
+        // Success
+        service
+            .update_stock_line_vvm_status(
+                &context,
+                "store_a",
+                UpdateStockLineVvmStatus {
+                    stock_line_id: mock_stock_line_a().id,
+                    status_id: "VVM_STAGE_1".to_string(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        let log_row = service
+            .update_stock_line_vvm_status(
+                &context,
+                "store_a",
+                UpdateStockLineVvmStatus {
+                    stock_line_id: mock_stock_line_a().id,
+                    status_id: "VVM_STAGE_2".to_string(),
+                    comment: Some("checked at issue".to_string()),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        let stock_line = StockLineRowRepository::new(&connection)
+            .find_one_by_id(&mock_stock_line_a().id)
+            .unwrap();
+        assert_eq!(stock_line.vvm_status_id, Some("VVM_STAGE_2".to_string()));
+
+        let history = service
+            .get_vvm_status_logs(&context, "store_a", &mock_stock_line_a().id)
+            .unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].status_id, "VVM_STAGE_1");
+        assert_eq!(history[1], log_row);
+    }
+}
@@ -1,9 +1,30 @@
+use hmac::{Hmac, Mac, NewMac};
 use sha2::{Digest, Sha256};
 
 pub fn sha256(plaintext: &str) -> String {
     format!("{:x}", Sha256::digest(plaintext.as_bytes()))
 }
 
+/// Hex encoded HMAC-SHA256 of `message`
+pub fn hmac_sha256(key: &str, message: &str) -> String {
+    // HMAC accepts keys of any size
+    let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).unwrap();
+    mac.update(message.as_bytes());
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+/// Compares the strings in time independent of where they differ, e.g. to check password hashes
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b)
+        .fold(0, |difference, (a, b)| difference | (a ^ b))
+        == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -15,4 +36,21 @@ mod tests {
             "96d62e2abd3e42de5f50330fb8efc4c5599835278077b21e9aa0b33c1df07a1c".to_owned();
         assert_eq!(sha256(plaintext), ciphertext);
     }
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hmac_sha256("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("hash", "hash"));
+        assert!(!constant_time_eq("hash", "hasH"));
+        assert!(!constant_time_eq("hash", "hash_"));
+        assert!(constant_time_eq("", ""));
+    }
 }